@@ -6,11 +6,12 @@ fn main() {
     println!("cargo:rerun-if-changed=assembly/yes/fyes.asm");
     println!("cargo:rerun-if-changed=assembly/yes/build.py");
     println!("cargo:rerun-if-changed=assembly/yes/fyes_arm64.s");
+    println!("cargo:rerun-if-changed=src/stdbuf/libstdbuf.c");
 
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
     let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
 
-    // Assembly build only applies to Linux.
+    // Assembly and the LD_PRELOAD shim only apply to Linux.
     if target_os != "linux" {
         return;
     }
@@ -18,6 +19,8 @@ fn main() {
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
     let out_dir = env::var("OUT_DIR").unwrap();
 
+    build_libstdbuf(&manifest_dir, &out_dir);
+
     match target_arch.as_str() {
         "x86_64" => build_x86_64(&manifest_dir, &out_dir),
         "aarch64" => build_aarch64(&manifest_dir, &out_dir),
@@ -25,6 +28,30 @@ fn main() {
     }
 }
 
+/// Compile the stdbuf LD_PRELOAD shim into OUT_DIR and pass its path to the
+/// crate as FSTDBUF_LIBSTDBUF, where fstdbuf looks for it first.
+fn build_libstdbuf(manifest_dir: &str, out_dir: &str) {
+    // A cross build needs a C compiler for the target; without one, fstdbuf
+    // falls back to an installed shim.
+    let cc = env::var("TARGET_CC").or_else(|_| env::var("CC")).ok();
+    if cc.is_none() && env::var("HOST").ok() != env::var("TARGET").ok() {
+        println!("cargo:warning=libstdbuf.so: no C compiler for the target — not built");
+        return;
+    }
+    let cc = cc.unwrap_or_else(|| "cc".to_string());
+
+    let src = format!("{}/src/stdbuf/libstdbuf.c", manifest_dir);
+    let lib_out = format!("{}/libstdbuf.so", out_dir);
+    let status = Command::new(&cc)
+        .args(["-shared", "-fPIC", "-O2", "-o", &lib_out, &src])
+        .status();
+    if !matches!(status, Ok(s) if s.success()) {
+        println!("cargo:warning=libstdbuf.so: '{}' failed — not built", cc);
+        return;
+    }
+    println!("cargo:rustc-env=FSTDBUF_LIBSTDBUF={}", lib_out);
+}
+
 fn build_x86_64(manifest_dir: &str, out_dir: &str) {
     // Check for required tools.
     let has_nasm = Command::new("nasm")
@@ -398,11 +398,10 @@ fn encode_streaming(data: &[u8], wrap: usize) -> io::Result<()> {
     let batch_input = BATCH_INPUT / 5 * 5;
     // Output per batch: batch_input/5*8 raw + batch_input/5*8/wrap newlines + margin
     let batch_output_raw = batch_input / 5 * 8;
-    let batch_output_wrapped = if wrap > 0 {
-        batch_output_raw + batch_output_raw / wrap + 16
-    } else {
-        batch_output_raw + 16
-    };
+    let batch_output_wrapped = batch_output_raw
+        .checked_div(wrap)
+        .map_or(batch_output_raw, |lines| batch_output_raw + lines)
+        + 16;
     let mut raw_buf = vec![0u8; batch_output_raw + 16];
     let mut wrap_buf = if wrap > 0 {
         vec![0u8; batch_output_wrapped]
@@ -865,18 +865,16 @@ fn encode_streaming_generic(
     let chunk_size = (256 * 1024 / block_size) * block_size;
 
     // Pre-allocate output buffer large enough for one chunk's encoded + wrapped output
-    let max_encoded = match encoding {
+    let max_encoded: usize = match encoding {
         Encoding::Base32 | Encoding::Base32Hex => chunk_size * 8 / 5 + 8,
         Encoding::Base16 => chunk_size * 2,
         Encoding::Base2Msbf | Encoding::Base2Lsbf => chunk_size * 8,
         Encoding::Z85 => chunk_size * 5 / 4 + 5,
         _ => unreachable!(),
     };
-    let max_wrapped = if wrap > 0 {
-        max_encoded + max_encoded / wrap + 2
-    } else {
-        max_encoded
-    };
+    let max_wrapped = max_encoded
+        .checked_div(wrap)
+        .map_or(max_encoded, |lines| max_encoded + lines + 2);
     let mut out_buf: Vec<u8> = Vec::with_capacity(max_wrapped);
     let mut col = 0usize;
 
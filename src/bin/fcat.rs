@@ -1,7 +1,7 @@
 use std::io::{self, BufWriter, Write};
 use std::process;
 
-use coreutils_rs::cat::{self, CatConfig};
+use coreutils_rs::cat::{self, CatConfig, CatState};
 use coreutils_rs::common::{io_error_msg, reset_sigpipe};
 
 struct Cli {
@@ -29,9 +29,7 @@ fn parse_args() -> Cli {
         if bytes.starts_with(b"--") {
             match bytes {
                 b"--show-all" => {
-                    cli.config.show_nonprinting = true;
-                    cli.config.show_ends = true;
-                    cli.config.show_tabs = true;
+                    cli.config.apply_short_option(b'A');
                 }
                 b"--number-nonblank" => {
                     cli.config.number_nonblank = true;
@@ -68,46 +66,10 @@ fn parse_args() -> Cli {
             }
         } else if bytes.len() > 1 && bytes[0] == b'-' {
             for &b in &bytes[1..] {
-                match b {
-                    b'A' => {
-                        cli.config.show_nonprinting = true;
-                        cli.config.show_ends = true;
-                        cli.config.show_tabs = true;
-                    }
-                    b'b' => {
-                        cli.config.number_nonblank = true;
-                    }
-                    b'e' => {
-                        cli.config.show_nonprinting = true;
-                        cli.config.show_ends = true;
-                    }
-                    b'E' => {
-                        cli.config.show_ends = true;
-                    }
-                    b'n' => {
-                        cli.config.number = true;
-                    }
-                    b's' => {
-                        cli.config.squeeze_blank = true;
-                    }
-                    b't' => {
-                        cli.config.show_nonprinting = true;
-                        cli.config.show_tabs = true;
-                    }
-                    b'T' => {
-                        cli.config.show_tabs = true;
-                    }
-                    b'v' => {
-                        cli.config.show_nonprinting = true;
-                    }
-                    b'u' => {
-                        // -u is ignored (POSIX requires it, GNU ignores it)
-                    }
-                    _ => {
-                        eprintln!("cat: invalid option -- '{}'", b as char);
-                        eprintln!("Try 'cat --help' for more information.");
-                        process::exit(1);
-                    }
+                if !cli.config.apply_short_option(b) {
+                    eprintln!("cat: invalid option -- '{}'", b as char);
+                    eprintln!("Try 'cat --help' for more information.");
+                    process::exit(1);
                 }
             }
         } else {
@@ -136,7 +98,7 @@ fn print_help() {
          \x20 -s, --squeeze-blank      suppress repeated empty output lines\n\
          \x20 -t                       equivalent to -vT\n\
          \x20 -T, --show-tabs          display TAB characters as ^I\n\
-         \x20 -u                       (ignored)\n\
+         \x20 -u                       write output as soon as it is read\n\
          \x20 -v, --show-nonprinting   use ^ and M- notation, except for LFD and TAB\n\
          \x20     --help               display this help and exit\n\
          \x20     --version            output version information and exit\n"
@@ -179,16 +141,9 @@ fn main() {
             use std::os::unix::io::FromRawFd;
             let mut raw_out = unsafe { ManuallyDrop::new(std::fs::File::from_raw_fd(1)) };
             let mut had_error = false;
-            let mut pending_cr = false;
+            let mut state = CatState::default();
             for filename in &files {
-                match cat::cat_file(
-                    filename,
-                    &cli.config,
-                    &mut 1u64,
-                    &mut pending_cr,
-                    &mut *raw_out,
-                    tool_name,
-                ) {
+                match cat::cat_file(filename, &cli.config, &mut state, &mut *raw_out, tool_name) {
                     Ok(true) => {}
                     Ok(false) => had_error = true,
                     Err(e) => {
@@ -210,16 +165,9 @@ fn main() {
             let stdout = io::stdout();
             let mut out = stdout.lock();
             let mut had_error = false;
-            let mut pending_cr = false;
+            let mut state = CatState::default();
             for filename in &files {
-                match cat::cat_file(
-                    filename,
-                    &cli.config,
-                    &mut 1u64,
-                    &mut pending_cr,
-                    &mut out,
-                    tool_name,
-                ) {
+                match cat::cat_file(filename, &cli.config, &mut state, &mut out, tool_name) {
                     Ok(true) => {}
                     Ok(false) => had_error = true,
                     Err(e) => {
@@ -238,22 +186,15 @@ fn main() {
         }
     }
 
-    // With options, use BufWriter
+    // With options, use BufWriter; -u passes every write straight through
+    let capacity = if cli.config.unbuffered { 0 } else { 256 * 1024 };
     let stdout = io::stdout();
-    let mut out = BufWriter::with_capacity(256 * 1024, stdout.lock());
+    let mut out = BufWriter::with_capacity(capacity, stdout.lock());
     let mut had_error = false;
-    let mut line_num = 1u64;
-    let mut pending_cr = false;
+    let mut state = CatState::default();
 
     for filename in &files {
-        match cat::cat_file(
-            filename,
-            &cli.config,
-            &mut line_num,
-            &mut pending_cr,
-            &mut out,
-            tool_name,
-        ) {
+        match cat::cat_file(filename, &cli.config, &mut state, &mut out, tool_name) {
             Ok(true) => {}
             Ok(false) => had_error = true,
             Err(e) => {
@@ -268,7 +209,7 @@ fn main() {
     }
 
     // Emit any pending CR that wasn't followed by LF
-    if pending_cr {
+    if state.pending_cr {
         let _ = out.write_all(b"\r");
     }
 
@@ -562,4 +503,166 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(output.stdout, b"from stdin\n");
     }
+
+    #[test]
+    fn test_cat_unbuffered_delivers_before_eof() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::process::Stdio;
+        use std::sync::mpsc;
+        use std::time::Duration;
+        let mut child = cmd()
+            .args(["-u", "-n"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        stdin.write_all(b"first\n").unwrap();
+        stdin.flush().unwrap();
+        // Read the first line while stdin is still open
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            BufReader::new(stdout).read_line(&mut line).unwrap();
+            tx.send(line).unwrap();
+        });
+        let line = rx.recv_timeout(Duration::from_secs(10));
+        drop(stdin);
+        child.wait().unwrap();
+        assert_eq!(line.unwrap(), "     1\tfirst\n");
+    }
+
+    #[test]
+    fn test_cat_unbuffered_squeeze_across_reads() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-u", "-s", "-n"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(b"a\n\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        stdin.write_all(b"\n\nb").unwrap();
+        drop(stdin);
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"     1\ta\n     2\t\n     3\tb");
+    }
+
+    #[test]
+    fn test_cat_show_all_numbered_matches_gnu() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("in");
+        std::fs::write(&path, b"a\tb\r\n\x80\xff\x1b c\r\n\n\nlast\tx\x7f").unwrap();
+        let expected: &[u8] = b"     1\ta^Ib^M$\n     2\tM-^@M-^?^[ c^M$\n     3\t$\n     \
+                                4\t$\n     5\tlast^Ix^?";
+        let output = cmd().arg("-An").arg(&path).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, expected);
+        // -A is -vET, and -e/-t combine to the same thing
+        let output = cmd().args(["-n", "-vET"]).arg(&path).output().unwrap();
+        assert_eq!(output.stdout, expected);
+        let output = cmd().args(["-n", "-e", "-t"]).arg(&path).output().unwrap();
+        assert_eq!(output.stdout, expected);
+    }
+
+    #[test]
+    fn test_cat_number_continues_partial_line_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, "one\ntw").unwrap();
+        std::fs::write(&b, "o\n\n\nthree\n").unwrap();
+        let output = cmd().arg("-n").arg(&a).arg(&b).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "     1\tone\n     2\ttwo\n     3\t\n     4\t\n     5\tthree\n"
+        );
+        let output = cmd().arg("-bs").arg(&a).arg(&b).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "     1\tone\n     2\ttwo\n\n     3\tthree\n"
+        );
+    }
+
+    #[test]
+    fn test_cat_regular_file_to_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        let data: Vec<u8> = (0..3_000_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&a, &data).unwrap();
+        std::fs::write(&b, "tail\n").unwrap();
+        let out_path = dir.path().join("out");
+        let out = std::fs::File::create(&out_path).unwrap();
+        let status = cmd().arg(&a).arg(&b).stdout(out).status().unwrap();
+        assert!(status.success());
+        let mut expected = data.clone();
+        expected.extend_from_slice(b"tail\n");
+        assert_eq!(std::fs::read(&out_path).unwrap(), expected);
+
+        // Appending (O_APPEND stdout) falls back to a slower path but keeps the data.
+        let out = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&out_path)
+            .unwrap();
+        let status = cmd().arg(&b).arg(&a).stdout(out).status().unwrap();
+        assert!(status.success());
+        expected.extend_from_slice(b"tail\n");
+        expected.extend_from_slice(&data);
+        assert_eq!(std::fs::read(&out_path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_cat_number_to_regular_file_skips_fast_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        std::fs::write(&a, "x\ny\n").unwrap();
+        let out_path = dir.path().join("out");
+        let out = std::fs::File::create(&out_path).unwrap();
+        let status = cmd().arg("-n").arg(&a).stdout(out).status().unwrap();
+        assert!(status.success());
+        assert_eq!(
+            std::fs::read_to_string(&out_path).unwrap(),
+            "     1\tx\n     2\ty\n"
+        );
+    }
+
+    #[test]
+    fn test_cat_directory_and_missing_operands() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("d")).unwrap();
+        std::fs::write(dir.path().join("f"), "file\n").unwrap();
+        // Regular-file stdout takes the copy_file_range path for plain cat.
+        let out_path = dir.path().join("out");
+        for (args, expected) in [
+            (&["d", "missing", "f"][..], "file\n"),
+            (&["-A", "d", "-", "missing", "f"], "in$\nfile$\n"),
+        ] {
+            let mut child = cmd()
+                .args(args)
+                .current_dir(dir.path())
+                .stdin(Stdio::piped())
+                .stdout(std::fs::File::create(&out_path).unwrap())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap();
+            // Without a "-" operand cat may exit before reading stdin.
+            let _ = child.stdin.take().unwrap().write_all(b"in\n");
+            let output = child.wait_with_output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "cat: d: Is a directory\ncat: missing: No such file or directory\n"
+            );
+            assert_eq!(std::fs::read_to_string(&out_path).unwrap(), expected);
+        }
+    }
 }
@@ -10,6 +10,8 @@ fn main() {
 //   or:  chmod [OPTION]... OCTAL-MODE FILE...
 //   or:  chmod [OPTION]... --reference=RFILE FILE...
 
+#[cfg(unix)]
+use coreutils_rs::chown::SymlinkFollow;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 #[cfg(unix)]
@@ -65,6 +67,9 @@ fn main() {
             "--no-preserve-root" => config.preserve_root = false,
             "--preserve-root" => config.preserve_root = true,
             "-R" | "--recursive" => config.recursive = true,
+            "-H" => config.symlink_follow = SymlinkFollow::CommandLine,
+            "-L" => config.symlink_follow = SymlinkFollow::Always,
+            "-P" => config.symlink_follow = SymlinkFollow::Never,
             s if s.starts_with("--reference=") => {
                 reference = Some(s["--reference=".len()..].to_string());
             }
@@ -81,7 +86,9 @@ fn main() {
                 // Could be combined short flags like -Rvc, OR a symbolic mode like -rwx
                 // Try to parse as flags first
                 let chars: Vec<char> = s[1..].chars().collect();
-                let all_flags = chars.iter().all(|c| matches!(c, 'c' | 'f' | 'v' | 'R'));
+                let all_flags = chars
+                    .iter()
+                    .all(|c| matches!(c, 'c' | 'f' | 'v' | 'R' | 'H' | 'L' | 'P'));
                 if all_flags {
                     for ch in &chars {
                         match ch {
@@ -89,6 +96,9 @@ fn main() {
                             'f' => config.quiet = true,
                             'v' => config.verbose = true,
                             'R' => config.recursive = true,
+                            'H' => config.symlink_follow = SymlinkFollow::CommandLine,
+                            'L' => config.symlink_follow = SymlinkFollow::Always,
+                            'P' => config.symlink_follow = SymlinkFollow::Never,
                             _ => unreachable!(),
                         }
                     }
@@ -176,12 +186,15 @@ fn main() {
                 continue;
             }
 
-            if let Err(e) = coreutils_rs::chmod::chmod_recursive(path, &effective_mode_str, &config)
-            {
-                if !config.quiet {
-                    eprintln!("{}: {}", TOOL_NAME, e);
+            match coreutils_rs::chmod::chmod_recursive(path, &effective_mode_str, &config) {
+                Ok(true) => {}
+                Ok(false) => exit_code = 1,
+                Err(e) => {
+                    if !config.quiet {
+                        eprintln!("{}: {}", TOOL_NAME, e);
+                    }
+                    exit_code = 1;
                 }
-                exit_code = 1;
             }
         } else {
             // Get current mode
@@ -196,6 +209,7 @@ fn main() {
                             coreutils_rs::common::io_error_msg(&e)
                         );
                     }
+                    coreutils_rs::chmod::report_not_accessed(path, &config);
                     exit_code = 1;
                     continue;
                 }
@@ -210,16 +224,9 @@ fn main() {
                         // Symlink target exists - use target metadata instead
                         // (fall through to normal processing)
                     }
-                    Err(e) => {
+                    Err(_) => {
                         // Dangling symlink - error like GNU chmod
-                        if !config.quiet {
-                            eprintln!(
-                                "{}: cannot operate on dangling symlink '{}': {}",
-                                TOOL_NAME,
-                                file,
-                                coreutils_rs::common::io_error_msg(&e)
-                            );
-                        }
+                        coreutils_rs::chmod::report_dangling_symlink(path, &config);
                         exit_code = 1;
                         continue;
                     }
@@ -306,6 +313,13 @@ fn print_help() {
     println!("      --preserve-root    fail to operate recursively on '/'");
     println!("      --reference=RFILE  use RFILE's mode instead of MODE values");
     println!("  -R, --recursive        change files and directories recursively");
+    println!();
+    println!("The following options modify how a hierarchy is traversed when -R is specified:");
+    println!("  -H                     if a command line argument is a symbolic link to a");
+    println!("                         directory, traverse it (default)");
+    println!("  -L                     traverse every symbolic link to a directory encountered");
+    println!("  -P                     do not traverse any symbolic links");
+    println!();
     println!("      --help     display this help and exit");
     println!("      --version  output version information and exit");
     println!();
@@ -491,6 +505,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_quiet_keeps_verbose_reports() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("existing");
+        std::fs::write(&file, "x").unwrap();
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let missing = dir.path().join("missing");
+
+        let output = cmd()
+            .args(["-f", "-v", "755"])
+            .arg(&file)
+            .arg(&missing)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            stdout,
+            format!(
+                "mode of '{}' changed from 0644 (rw-r--r--) to 0755 (rwxr-xr-x)\n\
+                 '{}' could not be accessed\n",
+                file.display(),
+                missing.display()
+            )
+        );
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o755);
+
+        // -c reports only actual changes, so the missing file is not mentioned.
+        let output = cmd()
+            .args(["-f", "-c", "700"])
+            .arg(&file)
+            .arg(&missing)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!(
+                "mode of '{}' changed from 0755 (rwxr-xr-x) to 0700 (rwx------)\n",
+                file.display()
+            )
+        );
+    }
+
     #[test]
     fn test_preserve_root() {
         let output = cmd()
@@ -719,4 +781,106 @@ mod tests {
             0o600
         );
     }
+
+    #[test]
+    fn test_recursive_symlink_traversal() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        let setup = || {
+            let real = dir.path().join("real");
+            let top = dir.path().join("top");
+            let _ = std::fs::remove_dir_all(&real);
+            let _ = std::fs::remove_dir_all(&top);
+            std::fs::create_dir_all(&real).unwrap();
+            std::fs::create_dir_all(&top).unwrap();
+            std::fs::write(real.join("f"), "").unwrap();
+            std::fs::set_permissions(real.join("f"), std::fs::Permissions::from_mode(0o644))
+                .unwrap();
+            std::os::unix::fs::symlink(&real, top.join("link")).unwrap();
+            (real, top)
+        };
+
+        // The default, -P and -H leave the nested symlink alone; -L follows it.
+        for (flags, expected) in [
+            (&["-R"][..], 0o644),
+            (&["-R", "-P"], 0o644),
+            (&["-R", "-H"], 0o644),
+            (&["-R", "-L"], 0o700),
+        ] {
+            let (real, top) = setup();
+            let output = cmd()
+                .args(flags)
+                .args(["700", top.to_str().unwrap()])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{flags:?}");
+            assert_eq!(mode(&real.join("f")), expected, "{flags:?}");
+        }
+
+        // A symlink named on the command line is followed, as GNU does,
+        // unless -P is given.
+        let arg = dir.path().join("arglink");
+        for (flags, expected) in [(&["-R"][..], 0o700), (&["-RH"], 0o700), (&["-RP"], 0o644)] {
+            let (real, _) = setup();
+            let _ = std::fs::remove_file(&arg);
+            std::os::unix::fs::symlink(&real, &arg).unwrap();
+            let output = cmd()
+                .args(flags)
+                .args(["700", arg.to_str().unwrap()])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{flags:?}");
+            assert_eq!(mode(&real.join("f")), expected, "{flags:?}");
+        }
+    }
+
+    #[test]
+    fn test_recursive_follow_all_symlink_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("a").join("b");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::os::unix::fs::symlink("..", sub.join("up")).unwrap();
+
+        let output = cmd()
+            .args([
+                "-R",
+                "-L",
+                "-v",
+                "755",
+                dir.path().join("a").to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // Each directory is reported once; the loop back up is not re-entered.
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.lines().count(), 3, "{stdout}");
+    }
+
+    #[test]
+    fn test_dangling_symlink_operand() {
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink("missing", dir.path().join("dang")).unwrap();
+        // Reported the same way, and only once, with or without -R.
+        for args in [&["600", "dang"][..], &["-R", "600", "dang"]] {
+            let output = cmd().args(args).current_dir(dir.path()).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "chmod: cannot operate on dangling symlink 'dang'\n"
+            );
+        }
+        // A missing operand under -R has no OS error suffix or summary line.
+        let output = cmd()
+            .args(["-R", "600", "missing"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "chmod: cannot access 'missing': No such file or directory\n"
+        );
+    }
 }
@@ -353,4 +353,63 @@ mod tests {
         let lines: Vec<&str> = stdout.lines().collect();
         assert_eq!(lines, vec!["b", "c"]);
     }
+
+    #[test]
+    fn test_comm_check_order_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a\nc\nb\n").unwrap();
+        std::fs::write(&f2, "b\nd\n").unwrap();
+        let run = |opt: Option<&str>| {
+            let mut c = cmd();
+            c.args(opt);
+            c.args([f1.to_str().unwrap(), f2.to_str().unwrap()])
+                .output()
+                .unwrap()
+        };
+
+        // Default: warn once per file, keep going, summarize, exit 1.
+        let output = run(None);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a\n\tb\nc\nb\n\td\n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "comm: file 1 is not in sorted order\ncomm: input is not in sorted order\n"
+        );
+
+        // --check-order: stop at the first out-of-order line.
+        let output = run(Some("--check-order"));
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a\n\tb\nc\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "comm: file 1 is not in sorted order\n"
+        );
+
+        let output = run(Some("--nocheck-order"));
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_comm_default_check_waits_for_unpairable_line() {
+        // GNU only checks by default once a line unique to one file is seen,
+        // so identical unsorted inputs pass; --check-order still catches them.
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("a.txt");
+        std::fs::write(&f, "a\nc\nb\n").unwrap();
+        let path = f.to_str().unwrap();
+
+        let output = cmd().args([path, path]).output().unwrap();
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty());
+
+        let output = cmd().args(["--check-order", path, path]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "\t\ta\n\t\tc\n");
+    }
 }
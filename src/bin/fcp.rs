@@ -11,8 +11,8 @@ use std::process;
 use coreutils_rs::common::reset_sigpipe;
 #[cfg(unix)]
 use coreutils_rs::cp::{
-    CpConfig, DerefMode, apply_no_preserve, apply_preserve, parse_backup_mode, parse_reflink_mode,
-    parse_sparse_mode,
+    BackupMode, CpConfig, DerefMode, apply_no_preserve, apply_preserve, backup_suffix,
+    parse_backup_mode, parse_reflink_mode, parse_sparse_mode,
 };
 
 #[cfg(unix)]
@@ -60,6 +60,15 @@ Copy SOURCE to DEST, or multiple SOURCE(s) to DIRECTORY.
       --attributes-only      don't copy the file data, just the attributes
       --help                 display this help and exit
       --version              output version information and exit
+
+The backup suffix is '~', unless set with --suffix or SIMPLE_BACKUP_SUFFIX.
+The version control method may be selected via the --backup option or through
+the VERSION_CONTROL environment variable.  Here are the values:
+
+  none, off       never make backups (even if --backup is given)
+  numbered, t     make numbered backups
+  existing, nil   numbered if numbered backups exist, simple otherwise
+  simple, never   always make simple backups
 "
     );
 }
@@ -71,6 +80,11 @@ fn main() {
     let mut config = CpConfig::default();
     let mut positional: Vec<String> = Vec::new();
     let mut saw_dashdash = false;
+    // As in GNU, -b/--backup/-S only request backups; the CONTROL value
+    // (or VERSION_CONTROL) is resolved once all options are read.
+    let mut make_backups = false;
+    let mut backup_control: Option<String> = None;
+    let mut suffix: Option<String> = None;
 
     let args: Vec<String> = std::env::args().skip(1).collect();
     let mut i = 0;
@@ -96,13 +110,10 @@ fn main() {
                 let (key, val) = arg.split_at(eq_pos);
                 let val = &val[1..]; // skip '='
                 match key {
-                    "--backup" => match parse_backup_mode(val) {
-                        Ok(m) => config.backup = Some(m),
-                        Err(e) => {
-                            eprintln!("cp: {}", e);
-                            process::exit(1);
-                        }
-                    },
+                    "--backup" => {
+                        make_backups = true;
+                        backup_control = Some(val.to_string());
+                    }
                     "--preserve" => apply_preserve(val, &mut config),
                     "--no-preserve" => apply_no_preserve(val, &mut config),
                     "--reflink" => match parse_reflink_mode(val) {
@@ -119,7 +130,10 @@ fn main() {
                             process::exit(1);
                         }
                     },
-                    "--suffix" => config.suffix = val.to_string(),
+                    "--suffix" => {
+                        make_backups = true;
+                        suffix = Some(val.to_string());
+                    }
                     "--target-directory" => config.target_directory = Some(val.to_string()),
                     _ => {
                         eprintln!("cp: unrecognized option '{}'", arg);
@@ -147,8 +161,16 @@ fn main() {
                     config.preserve_ownership = true;
                     config.preserve_timestamps = true;
                 }
-                "--backup" => {
-                    config.backup = Some(coreutils_rs::cp::BackupMode::Existing);
+                "--backup" => make_backups = true,
+                "--suffix" => {
+                    i += 1;
+                    if i >= args.len() {
+                        eprintln!("cp: option '--suffix' requires an argument");
+                        eprintln!("Try 'cp --help' for more information.");
+                        process::exit(1);
+                    }
+                    make_backups = true;
+                    suffix = Some(args[i].clone());
                 }
                 "--force" => config.force = true,
                 "--interactive" => config.interactive = true,
@@ -193,9 +215,7 @@ fn main() {
                         config.preserve_ownership = true;
                         config.preserve_timestamps = true;
                     }
-                    b'b' => {
-                        config.backup = Some(coreutils_rs::cp::BackupMode::Existing);
-                    }
+                    b'b' => make_backups = true,
                     b'd' => {
                         config.dereference = DerefMode::Never;
                         // --preserve=links is acknowledged but links preservation
@@ -219,15 +239,16 @@ fn main() {
                         // -S SUFFIX: value is either rest of this arg or next arg.
                         let rest = &arg[(j + 1)..];
                         if !rest.is_empty() {
-                            config.suffix = rest.to_string();
+                            suffix = Some(rest.to_string());
                         } else {
                             i += 1;
                             if i >= args.len() {
                                 eprintln!("cp: option requires an argument -- 'S'");
                                 process::exit(1);
                             }
-                            config.suffix = args[i].clone();
+                            suffix = Some(args[i].clone());
                         }
+                        make_backups = true;
                         j = bytes.len(); // consumed rest
                         continue;
                     }
@@ -268,6 +289,33 @@ fn main() {
         i += 1;
     }
 
+    if make_backups {
+        let env_control = std::env::var("VERSION_CONTROL").ok();
+        // An empty CONTROL or VERSION_CONTROL means the default, as in GNU.
+        let control = backup_control.as_deref().filter(|c| !c.is_empty());
+        let env_control = env_control.as_deref().filter(|c| !c.is_empty());
+        let parsed = match (control, env_control) {
+            (Some(control), _) => parse_backup_mode(control, "backup type"),
+            (None, Some(control)) => parse_backup_mode(control, "$VERSION_CONTROL"),
+            (None, None) => Ok(BackupMode::Existing),
+        };
+        match parsed {
+            Ok(mode) => config.backup = Some(mode),
+            Err(e) => {
+                eprintln!("cp: {}", e);
+                eprintln!("Try 'cp --help' for more information.");
+                process::exit(1);
+            }
+        }
+        config.suffix = backup_suffix(suffix.as_deref());
+    }
+
+    if make_backups && config.no_clobber {
+        eprintln!("cp: options --backup and --no-clobber are mutually exclusive");
+        eprintln!("Try 'cp --help' for more information.");
+        process::exit(1);
+    }
+
     if positional.is_empty() {
         eprintln!("cp: missing file operand");
         eprintln!("Try 'cp --help' for more information.");
@@ -518,4 +566,145 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(std::fs::metadata(&dst).unwrap().len(), 2 * 1024 * 1024);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cp_backup_controls() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dst = dir.path().join("dst");
+        let (src_s, dst_s) = (src.to_str().unwrap(), dst.to_str().unwrap());
+        std::fs::write(&src, "new\n").unwrap();
+        let backup = |suffix: &str| dir.path().join(format!("dst{}", suffix));
+
+        // -S alone requests a simple backup.
+        std::fs::write(&dst, "v1\n").unwrap();
+        let output = cmd().args(["-S", ".bak", src_s, dst_s]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(backup(".bak")).unwrap(), "v1\n");
+
+        // Numbered backups continue after the highest existing number.
+        std::fs::write(&dst, "v2\n").unwrap();
+        std::fs::write(backup(".~4~"), "old\n").unwrap();
+        let output = cmd()
+            .args(["-b", src_s, dst_s])
+            .env("VERSION_CONTROL", "numbered")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(backup(".~5~")).unwrap(), "v2\n");
+        assert!(!backup(".~1~").exists());
+
+        // Abbreviated CONTROL; "existing" goes numbered once one exists.
+        let output = cmd()
+            .args(["--backup=ex", src_s, dst_s])
+            .env("VERSION_CONTROL", "none")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(backup(".~6~")).unwrap(), "new\n");
+
+        // VERSION_CONTROL=none turns -b off.
+        let output = cmd()
+            .args(["-b", src_s, dst_s])
+            .env("VERSION_CONTROL", "none")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(!backup(".~7~").exists() && !backup("~").exists());
+
+        let output = cmd().args(["--backup=n", src_s, dst_s]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("cp: ambiguous argument 'n' for 'backup type'\n"));
+        let output = cmd()
+            .args(["-b", src_s, dst_s])
+            .env("VERSION_CONTROL", "bogus")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("cp: invalid argument 'bogus' for '$VERSION_CONTROL'\n"));
+
+        let output = cmd().args(["-n", "-b", src_s, dst_s]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "cp: options --backup and --no-clobber are mutually exclusive\n\
+             Try 'cp --help' for more information.\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cp_backup_with_force_and_same_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dst = dir.path().join("dst");
+        let (src_s, dst_s) = (src.to_str().unwrap(), dst.to_str().unwrap());
+        std::fs::write(&src, "new\n").unwrap();
+        std::fs::write(&dst, "old\n").unwrap();
+        std::fs::set_permissions(&dst, std::fs::Permissions::from_mode(0o444)).unwrap();
+
+        // -f must not remove the destination before it is backed up.
+        let output = cmd().args(["-bf", src_s, dst_s]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "new\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("dst~")).unwrap(),
+            "old\n"
+        );
+
+        // Copying a file onto itself is an error unless -f is also given.
+        let output = cmd().args(["-b", src_s, src_s]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("are the same file"));
+        assert!(!dir.path().join("src~").exists());
+
+        let output = cmd().args(["-bf", src_s, src_s]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(&src).unwrap(), "new\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src~")).unwrap(),
+            "new\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cp_verbose_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("s"), "new\n").unwrap();
+
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["-v", "-b", "s", "d"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "'s' -> 'd'\n");
+
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["-v", "-b", "s", "d"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "'s' -> 'd' (backup: 'd~')\n"
+        );
+
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["-v", "--backup=numbered", "s", "d"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "'s' -> 'd' (backup: 'd.~1~')\n"
+        );
+    }
 }
@@ -34,10 +34,7 @@ fn parse_args() -> Cli {
             } else if let Some(val) = s.strip_prefix("--suffix-format=") {
                 cli.config.suffix_format = val.to_string();
             } else if let Some(val) = s.strip_prefix("--digits=") {
-                cli.config.digits = val.parse().unwrap_or_else(|_| {
-                    eprintln!("csplit: invalid number of digits: '{}'", val);
-                    process::exit(1);
-                });
+                cli.config.digits = parse_digits(val);
             } else {
                 match bytes {
                     b"--keep-files" => cli.config.keep_files = true,
@@ -62,13 +59,7 @@ fn parse_args() -> Cli {
                             eprintln!("csplit: option '--digits' requires an argument");
                             process::exit(1);
                         });
-                        cli.config.digits = val.to_string_lossy().parse().unwrap_or_else(|_| {
-                            eprintln!(
-                                "csplit: invalid number of digits: '{}'",
-                                val.to_string_lossy()
-                            );
-                            process::exit(1);
-                        });
+                        cli.config.digits = parse_digits(&val.to_string_lossy());
                     }
                     b"--help" => {
                         print_help();
@@ -132,10 +123,7 @@ fn parse_args() -> Cli {
                         } else {
                             rest
                         };
-                        cli.config.digits = val_str.parse().unwrap_or_else(|_| {
-                            eprintln!("csplit: invalid number of digits: '{}'", val_str);
-                            process::exit(1);
-                        });
+                        cli.config.digits = parse_digits(&val_str);
                         break;
                     }
                     'k' => cli.config.keep_files = true,
@@ -171,6 +159,13 @@ fn parse_args() -> Cli {
     cli
 }
 
+fn parse_digits(s: &str) -> usize {
+    csplit::parse_digits(s).unwrap_or_else(|e| {
+        eprintln!("csplit: {}", e);
+        process::exit(1);
+    })
+}
+
 fn print_help() {
     print!(
         "Usage: csplit [OPTION]... FILE PATTERN...\n\
@@ -199,11 +194,42 @@ fn main() {
 
     let cli = parse_args();
 
+    if !cli.config.suffix_format.is_empty()
+        && let Err(e) = csplit::validate_suffix_format(&cli.config.suffix_format)
+    {
+        eprintln!("csplit: {}", e);
+        process::exit(1);
+    }
+
     // Parse pattern strings
     let mut patterns: Vec<Pattern> = Vec::new();
+    let mut last_line_number = 0;
     for pat_str in &cli.patterns {
+        // A repeat count only means something after another pattern.
+        if patterns.is_empty() && pat_str.starts_with('{') {
+            eprintln!("csplit: '{}': invalid pattern", pat_str);
+            process::exit(1);
+        }
         match csplit::parse_pattern(pat_str) {
-            Ok(p) => patterns.push(p),
+            Ok(p) => {
+                if let Pattern::LineNumber(n) = p {
+                    if n < last_line_number {
+                        eprintln!(
+                            "csplit: line number '{}' is smaller than preceding line number, {}",
+                            n, last_line_number
+                        );
+                        process::exit(1);
+                    }
+                    if n == last_line_number {
+                        eprintln!(
+                            "csplit: warning: line number '{}' is the same as preceding line number",
+                            n
+                        );
+                    }
+                    last_line_number = n;
+                }
+                patterns.push(p);
+            }
             Err(e) => {
                 eprintln!("csplit: {}", e);
                 process::exit(1);
@@ -211,16 +237,11 @@ fn main() {
         }
     }
 
-    match csplit::csplit_from_path(&cli.file, &patterns, &cli.config) {
-        Ok(sizes) => {
-            if !cli.config.quiet {
-                csplit::print_sizes(&sizes);
-            }
-        }
-        Err(e) => {
-            eprintln!("csplit: {}", e);
-            process::exit(1);
-        }
+    let stdout = std::io::stdout();
+    if let Err(e) = csplit::csplit_from_path(&cli.file, &patterns, &cli.config, &mut stdout.lock())
+    {
+        eprintln!("csplit: {}", e);
+        process::exit(1);
     }
 }
 
@@ -359,4 +380,159 @@ mod tests {
         let content = std::fs::read_to_string(dir.path().join("xx00")).unwrap();
         assert!(content.is_empty());
     }
+
+    fn seq_input(dir: &std::path::Path, n: usize) -> String {
+        let input = dir.join("input.txt");
+        let text: String = (1..=n).map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&input, text).unwrap();
+        input.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_csplit_no_match_removes_or_keeps_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = seq_input(dir.path(), 10);
+        let output = cmd()
+            .args([&input, "/3/", "/99/"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        // Sizes of the files written before the failure are still reported.
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "4\n17\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "csplit: '/99/': match not found\n"
+        );
+        assert!(!dir.path().join("xx00").exists());
+        assert!(!dir.path().join("xx01").exists());
+
+        let output = cmd()
+            .args(["-k", &input, "/3/", "/99/"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "4\n17\n");
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("xx00"), "1\n2\n");
+        // The rest of the input lands in the file that was being filled.
+        assert_eq!(read("xx01"), "3\n4\n5\n6\n7\n8\n9\n10\n");
+    }
+
+    #[test]
+    fn test_csplit_keep_files_repeat() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = seq_input(dir.path(), 10);
+
+        // {*} running out of matches is a normal end, not an error.
+        let output = cmd()
+            .args(["-k", &input, "/[05]/", "{*}"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "8\n10\n3\n");
+
+        // A counted repeat that overruns the input fails on that repetition.
+        let output = cmd()
+            .args(["-k", &input, "4", "{5}"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "6\n8\n7\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "csplit: '4': line number out of range on repetition 2\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("xx02")).unwrap(),
+            "8\n9\n10\n"
+        );
+    }
+
+    #[test]
+    fn test_csplit_keep_files_custom_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = seq_input(dir.path(), 20);
+        let output = cmd()
+            .args([
+                "-k", "-s", "-f", "part-", "-b", "%#x.txt", &input, "/5/", "/99/",
+            ])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("part-0.txt")).unwrap(),
+            "1\n2\n3\n4\n"
+        );
+        assert!(dir.path().join("part-0x1.txt").exists());
+    }
+
+    #[test]
+    fn test_csplit_suffix_format_and_digits() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = seq_input(dir.path(), 6);
+        let output = cmd()
+            .args(["-s", "-f", "part", "-b", "%03d.txt", "-n", "5", &input, "3"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // -b brings its own width, so -n has no effect.
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("part000.txt")).unwrap(),
+            "1\n2\n"
+        );
+        assert!(dir.path().join("part001.txt").exists());
+
+        let output = cmd()
+            .args(["-s", "-n", "4", &input, "3"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(dir.path().join("xx0000").exists());
+        assert!(dir.path().join("xx0001").exists());
+
+        for (args, err) in [
+            (
+                ["-b", "part"],
+                "csplit: missing % conversion specification in suffix\n",
+            ),
+            (
+                ["-b", "%s"],
+                "csplit: invalid conversion specifier in suffix: s\n",
+            ),
+            (
+                ["-b", "%d%d"],
+                "csplit: too many % conversion specifications in suffix\n",
+            ),
+            (
+                ["-b", "part%"],
+                "csplit: missing conversion specifier in suffix\n",
+            ),
+            (
+                ["-b", "%05"],
+                "csplit: missing conversion specifier in suffix\n",
+            ),
+            (
+                ["-b", "%d%"],
+                "csplit: too many % conversion specifications in suffix\n",
+            ),
+            (["-n", "x"], "csplit: invalid number: 'x'\n"),
+        ] {
+            let output = cmd()
+                .args(args)
+                .args([&input, "3"])
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(String::from_utf8_lossy(&output.stderr), err);
+        }
+    }
 }
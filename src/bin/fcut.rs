@@ -128,11 +128,12 @@ struct Cli {
     only_delimited: bool,
     output_delimiter: Option<String>,
     zero_terminated: bool,
+    whitespace: bool,
     files: Vec<String>,
 }
 
 /// Hand-rolled argument parser — eliminates clap's ~100-200µs initialization.
-/// cut's args: -b, -c, -f (with LIST), -d (with DELIM), -s, -z, -n, --complement,
+/// cut's args: -b, -c, -f (with LIST), -d (with DELIM), -s, -w, -z, -n, --complement,
 /// --output-delimiter, and positional files.
 fn parse_args() -> Cli {
     let mut cli = Cli {
@@ -144,6 +145,7 @@ fn parse_args() -> Cli {
         only_delimited: false,
         output_delimiter: None,
         zero_terminated: false,
+        whitespace: false,
         files: Vec::new(),
     };
 
@@ -216,6 +218,7 @@ fn parse_args() -> Cli {
                     b"--complement" => cli.complement = true,
                     b"--only-delimited" => cli.only_delimited = true,
                     b"--zero-terminated" => cli.zero_terminated = true,
+                    b"--whitespace" => cli.whitespace = true,
                     b"--help" => {
                         print!(
                             "Usage: cut OPTION... [FILE]...\n\
@@ -234,6 +237,8 @@ fn parse_args() -> Cli {
                             \x20 -s, --only-delimited     do not print lines not containing delimiters\n\
                             \x20     --output-delimiter=STRING  use STRING as the output delimiter\n\
                             \x20                           the default is to use the input delimiter\n\
+                            \x20 -w, --whitespace         use runs of spaces and tabs as the field delimiter;\n\
+                            \x20                           leading and trailing blanks are ignored\n\
                             \x20 -z, --zero-terminated    line delimiter is NUL, not newline\n\
                             \x20     --help               display this help and exit\n\
                             \x20     --version            output version information and exit\n"
@@ -258,6 +263,7 @@ fn parse_args() -> Cli {
                 match bytes[i] {
                     b'n' => {} // ignored (POSIX compat)
                     b's' => cli.only_delimited = true,
+                    b'w' => cli.whitespace = true,
                     b'z' => cli.zero_terminated = true,
                     b'b' | b'c' | b'd' | b'f' => {
                         // These take a value: rest of arg or next arg
@@ -406,8 +412,14 @@ fn main() {
         process::exit(1);
     }
 
+    if cli.whitespace && cli.delimiter.is_some() {
+        eprintln!("cut: options '-d' and '-w' are mutually exclusive");
+        eprintln!("Try 'cut --help' for more information.");
+        process::exit(1);
+    }
+
     // GNU cut: -d (--delimiter) is only valid with -f
-    if cli.delimiter.is_some() && mode != CutMode::Fields {
+    if (cli.delimiter.is_some() || cli.whitespace) && mode != CutMode::Fields {
         eprintln!("cut: an input delimiter may be specified only when operating on fields");
         eprintln!("Try 'cut --help' for more information.");
         process::exit(1);
@@ -418,7 +430,7 @@ fn main() {
     let has_output_delim = cli.output_delimiter.is_some();
     let no_merge_adjacent = has_output_delim && mode != CutMode::Fields;
 
-    let ranges = match cut::parse_ranges(spec, no_merge_adjacent) {
+    let ranges = match cut::parse_ranges(spec, mode, no_merge_adjacent) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("cut: {}", e);
@@ -427,18 +439,19 @@ fn main() {
         }
     };
 
+    // As in GNU cut, an empty -d argument (what `-d $'\0'` passes) means NUL.
     let delim = if let Some(ref d) = cli.delimiter {
-        if d.len() != 1 {
+        if d.len() > 1 {
             eprintln!("cut: the delimiter must be a single character");
             eprintln!("Try 'cut --help' for more information.");
             process::exit(1);
         }
-        d.as_bytes()[0]
+        d.as_bytes().first().copied().unwrap_or(b'\0')
     } else {
         b'\t'
     };
 
-    // Default output delimiter: field delimiter for -f, empty for -b/-c
+    // Default output delimiter: field delimiter for -f (TAB for -w), empty for -b/-c
     // GNU cut only uses a delimiter between fields, not between byte/char ranges
     let output_delim = if let Some(ref od) = cli.output_delimiter {
         od.as_bytes().to_vec()
@@ -480,6 +493,7 @@ fn main() {
         output_delim: &output_delim,
         suppress_no_delim: cli.only_delimited,
         line_delim,
+        whitespace: cli.whitespace,
     };
 
     // Try to mmap stdin for zero-copy (only used if stdin is a regular file)
@@ -894,4 +908,173 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "second");
     }
+
+    fn cut_fields(list: &str, input: &[u8]) -> std::process::Output {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-d:", "-f", list])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_cut_fields_increasing_order() {
+        let output = cut_fields("5,1,3", b"a:b:c:d:e\n");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"a:c:e\n");
+        let output = cut_fields("1-3,2-4", b"a:b:c:d:e\n");
+        assert_eq!(output.stdout, b"a:b:c:d\n");
+        let output = cut_fields("4 2", b"a:b:c:d:e\n");
+        assert_eq!(output.stdout, b"b:d\n");
+    }
+
+    #[test]
+    fn test_cut_fields_open_ranges() {
+        let output = cut_fields("2-", b"a:b:c:d:e\n");
+        assert_eq!(output.stdout, b"b:c:d:e\n");
+        let output = cut_fields("-3", b"a:b:c:d:e\n");
+        assert_eq!(output.stdout, b"a:b:c\n");
+    }
+
+    #[test]
+    fn test_cut_list_errors() {
+        for (list, msg) in [
+            ("3-1", "invalid decreasing range"),
+            ("", "fields are numbered from 1"),
+            ("1,,3", "fields are numbered from 1"),
+            ("1-3-5", "invalid field range"),
+            ("+1", "invalid field value '+1'"),
+            (
+                "99999999999999999999",
+                "field number '99999999999999999999' is too large",
+            ),
+        ] {
+            let output = cut_fields(list, b"");
+            assert_eq!(output.status.code(), Some(1), "{}", list);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.starts_with(&format!("cut: {}\n", msg)), "{}", stderr);
+        }
+        let output = cmd().args(["-b", "0"]).output().unwrap();
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("cut: byte/character positions are numbered from 1\n")
+        );
+    }
+
+    fn cut_whitespace(args: &[&str], input: &[u8]) -> std::process::Output {
+        let mut child = cmd()
+            .arg("-w")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_cut_whitespace_fields() {
+        let input = b"  alpha \t beta   gamma\t\nsolo\n\tone  two\n";
+        let output = cut_whitespace(&["-f2"], input);
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"beta\nsolo\ntwo\n");
+        let output = cut_whitespace(&["-f1,3"], input);
+        assert_eq!(output.stdout, b"alpha\tgamma\nsolo\none\n");
+        let output = cut_whitespace(&["-s", "--output-delimiter=,", "-f-2"], input);
+        assert_eq!(output.stdout, b"alpha,beta\none,two\n");
+        let output = cut_whitespace(&["--complement", "-f1"], input);
+        assert_eq!(output.stdout, b"beta\tgamma\nsolo\ntwo\n");
+    }
+
+    #[test]
+    fn test_cut_whitespace_errors() {
+        let output = cut_whitespace(&["-d:", "-f1"], b"");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("cut: options '-d' and '-w' are mutually exclusive\n")
+        );
+        let output = cut_whitespace(&["-b1"], b"");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with(
+            "cut: an input delimiter may be specified only when operating on fields\n"
+        ));
+    }
+
+    #[test]
+    fn test_cut_fields_from_file_with_long_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.csv");
+        let long = "x".repeat(1000);
+        let input =
+            format!("a,b,c,d\nno delimiter\n{long},{long},{long}\nshort,row\nlast,{long},end");
+        std::fs::write(&file, &input).unwrap();
+        let run = |args: &[&str]| {
+            let output = cmd().args(args).arg(&file).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        assert_eq!(
+            run(&["-d,", "-f3"]),
+            format!("c\nno delimiter\n{long}\n\nend\n")
+        );
+        assert_eq!(run(&["-d,", "-s", "-f3"]), format!("c\n{long}\n\nend\n"));
+        assert_eq!(
+            run(&["-d,", "-f2-3", "--output-delimiter= | "]),
+            format!("b | c\nno delimiter\n{long} | {long}\nrow\n{long} | end\n")
+        );
+        assert_eq!(
+            run(&["-d,", "-s", "-f1,3-", "--output-delimiter=;"]),
+            format!("a;c;d\n{long};{long}\nshort\nlast;end\n")
+        );
+    }
+
+    #[test]
+    fn test_cut_nul_delimiter() {
+        let cut = |args: &[&str], input: &[u8]| -> Vec<u8> {
+            let mut child = cmd()
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child.stdin.take().unwrap().write_all(input).unwrap();
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            output.stdout
+        };
+        // An empty -d is NUL; records stay newline-terminated.
+        let input = b"a\0b\0c\nplain\ne\0f";
+        assert_eq!(cut(&["-d", "", "-f2"], input), b"b\nplain\nf\n");
+        assert_eq!(cut(&["-d", "", "-f2", "-s"], input), b"b\nf\n");
+        assert_eq!(cut(&["-d", "", "-f1,3"], input), b"a\0c\nplain\ne\n");
+        assert_eq!(
+            cut(&["-d", "", "-f1,3", "--output-delimiter=:"], input),
+            b"a:c\nplain\ne\n"
+        );
+
+        // With -z the delimiter also ends records, so the input is one
+        // record and only a final NUL terminates it.
+        assert_eq!(
+            cut(&["-z", "-d", "", "-f1,3"], b"a\0b\0c\nd\0"),
+            b"a\0c\nd\0"
+        );
+        assert_eq!(cut(&["-z", "-d", "", "-f2-"], b"a\0b\0c"), b"b\0c\0");
+        assert_eq!(cut(&["-z", "-d", "", "-f2"], b"a\0"), b"\0");
+        assert_eq!(cut(&["-z", "-d", "", "-f2", "-s"], b"a\0"), b"");
+        assert_eq!(cut(&["-d", "\n", "-f2,3"], b"a\nb\nc\nd\n"), b"b\nc\n");
+
+        // A last line without a terminator still gets one.
+        assert_eq!(cut(&["-f2-3"], b"a\tb"), b"b\n");
+        assert_eq!(cut(&["-f3-4"], b"x\ty\na\tb"), b"\n\n");
+        assert_eq!(cut(&["-f2-"], b"a"), b"a\n");
+    }
 }
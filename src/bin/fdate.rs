@@ -309,18 +309,14 @@ fn main() {
     let mut out = stdout.lock();
     let mut had_error = false;
 
-    // Handle --set (we parse but don't actually set the clock; that requires root)
-    if let Some(ref set_str) = config.set_string {
-        match date::parse_date_string(set_str, config.utc) {
-            Ok(_time) => {
-                eprintln!("date: cannot set date: Operation not permitted");
-                process::exit(1);
-            }
-            Err(e) => {
-                eprintln!("date: {}", e);
-                process::exit(1);
-            }
-        }
+    if config.set_string.is_some()
+        && (config.date_string.is_some()
+            || config.date_file.is_some()
+            || config.reference_file.is_some())
+    {
+        eprintln!("date: the options to print and set the time may not be used together");
+        eprintln!("Try 'date --help' for more information.");
+        process::exit(1);
     }
 
     // Handle --file: read dates from file
@@ -374,7 +370,23 @@ fn main() {
     }
 
     // Determine the time to display
-    let time = if let Some(ref date_str) = config.date_string {
+    let time = if let Some(ref set_str) = config.set_string {
+        // Relative dates ("+1 hour") are resolved against the current time,
+        // and -u makes a zone-less STRING UTC. The new time is printed even
+        // when setting the clock fails, as GNU date does.
+        let t = match date::parse_date_string(set_str, config.utc) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("date: {}", e);
+                process::exit(1);
+            }
+        };
+        if let Err(e) = date::set_system_time(&t) {
+            eprintln!("date: cannot set date: {}", io_error_msg(&e));
+            had_error = true;
+        }
+        t
+    } else if let Some(ref date_str) = config.date_string {
         match date::parse_date_string(date_str, config.utc) {
             Ok(t) => t,
             Err(e) => {
@@ -409,6 +421,10 @@ fn main() {
             process::exit(1);
         }
     }
+
+    if had_error {
+        process::exit(1);
+    }
 }
 
 #[cfg(test)]
@@ -555,4 +571,118 @@ mod tests {
         // Should contain date-time with timezone offset
         assert!(stdout.contains("-") && stdout.contains(":"));
     }
+
+    #[test]
+    fn test_date_utc_parsing_and_explicit_zone() {
+        let run = |args: &[&str]| {
+            let output = cmd().env("TZ", "Asia/Tokyo").args(args).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        // -u makes a zone-less date UTC for both parsing and output.
+        assert_eq!(
+            run(&["-u", "-d", "2024-01-01 00:00:00", "+%s %H"]),
+            "1704067200 00"
+        );
+        assert_eq!(
+            run(&["-d", "2024-01-01 00:00:00", "+%s %H"]),
+            "1704034800 00"
+        );
+        // An explicit zone in the string wins over both.
+        assert_eq!(
+            run(&["-d", "2024-01-01T00:00:00Z", "+%s %H"]),
+            "1704067200 09"
+        );
+        assert_eq!(run(&["-u", "-d", "2024-01-01 05:00:00+02:00", "+%H"]), "03");
+        assert_eq!(run(&["-d", "2024-01-01 00:00 UTC", "+%H"]), "09");
+    }
+
+    #[test]
+    fn test_date_nanoseconds_and_offsets() {
+        let run = |tz: &str, date: &str, fmt: &str| {
+            let output = cmd()
+                .env("TZ", tz)
+                .args(["-d", date, fmt])
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches('\n')
+                .to_string()
+        };
+        assert_eq!(
+            run("UTC0", "@1700000000.123456789", "+%Y-%m-%dT%H:%M:%S.%N%:z"),
+            "2023-11-14T22:13:20.123456789+00:00"
+        );
+        // A width selects the leading digits; '-' and '_' handle trailing zeros.
+        assert_eq!(
+            run("UTC0", "@1.12", "+%3N|%1N|%12N|%-3N|%_5N|%-N"),
+            "120|1|120000000000|12|12   |120000000"
+        );
+        // Whole seconds print as zeros.
+        assert_eq!(run("UTC0", "@1", "+%N|%3N"), "000000000|000");
+
+        let zones = "+%z|%:z|%::z|%:::z|%-:z|%_z|%010z";
+        assert_eq!(
+            run("<-0330>3:30", "@0", zones),
+            "-0330|-03:30|-03:30:00|-03:30|-3:30| -330|-000000330"
+        );
+        assert_eq!(
+            run("<+0530>-5:30:15", "@0", zones),
+            "+0530|+05:30|+05:30:15|+05:30:15|+5:30| +530|+000000530"
+        );
+        // %:::z drops zero minutes, and colons only combine with z.
+        assert_eq!(run("UTC0", "@0", "+%:::z|%:Z|%::::z"), "+00|%:Z|%::::z");
+        assert_eq!(run("<+09>-9", "@0", "+%:::z"), "+09");
+    }
+
+    #[test]
+    fn test_date_conversion_flags() {
+        let run = |date: &str, fmt: &str| {
+            let output = cmd()
+                .env("TZ", "UTC0")
+                .args(["-u", "-d", date, fmt])
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches('\n')
+                .to_string()
+        };
+        // A modifier or flag before '%' is copied up to that '%', which
+        // then starts the next conversion.
+        assert_eq!(run("2023-11-05", "+%E%O%Ey%Om"), "%E%O2311");
+        assert_eq!(
+            run("2023-11-05", "+%^%|%5%|%05%|%-5%"),
+            "%^%|   %5%|00%05%|%-5%"
+        );
+        // '+' signs a year once the width or value exceeds its usual digits.
+        assert_eq!(
+            run("1970-01-01", "+%+4C|%+3C|%+C|%+4Y|%+5Y"),
+            "+019|+19|19|1970|+1970"
+        );
+        assert_eq!(run("12345-01-01", "+%+C|%+Y"), "+123|+12345");
+        // '#' lowers %Z and %p even alongside '^'.
+        assert_eq!(run("1970-01-01", "+%^#Z|%#^p|%^Z|%^#a"), "utc|am|UTC|THU");
+    }
+
+    #[test]
+    fn test_date_set_errors() {
+        // Neither case reaches the clock.
+        let output = cmd().args(["-s", "now", "-d", "now"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr).starts_with(
+                "date: the options to print and set the time may not be used together"
+            )
+        );
+
+        let output = cmd().args(["--set=bogus"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "date: invalid date 'bogus'\n"
+        );
+        assert!(output.stdout.is_empty());
+    }
 }
@@ -40,6 +40,8 @@ fn main() {
         }
     };
 
+    dd::install_signal_handlers();
+
     match dd::dd_copy(&config) {
         Ok(_) => {}
         Err(e) => {
@@ -232,4 +234,274 @@ mod tests {
         let output = cmd().arg("invalid=option").output().unwrap();
         assert!(!output.status.success());
     }
+
+    /// Feed `chunks` to dd's stdin as separate pipe writes so that it sees
+    /// short reads, and return its record counts from stderr.
+    fn dd_records(args: &[&str], chunks: &[&[u8]]) -> (Vec<u8>, String) {
+        let (out, stderr) = dd_chunks(args, chunks);
+        let records: Vec<&str> = stderr.lines().take(2).collect();
+        (out, records.join("\n"))
+    }
+
+    /// Feed `chunks` to dd as separate short reads; returns stdout and all of stderr.
+    fn dd_chunks(args: &[&str], chunks: &[&[u8]]) -> (Vec<u8>, String) {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        for chunk in chunks {
+            // dd may stop reading early once count= is reached
+            if stdin.write_all(chunk).is_err() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        drop(stdin);
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        (
+            output.stdout,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        )
+    }
+
+    #[test]
+    fn test_dd_bs_short_reads() {
+        // bs=: one write per read, so each short read is a partial record
+        let (out, records) = dd_records(&["bs=4"], &[b"ab", b"cd"]);
+        assert_eq!(out, b"abcd");
+        assert_eq!(records, "0+2 records in\n0+2 records out");
+    }
+
+    #[test]
+    fn test_dd_ibs_obs_aggregate_short_reads() {
+        let (out, records) = dd_records(&["ibs=4", "obs=4"], &[b"ab", b"cd"]);
+        assert_eq!(out, b"abcd");
+        assert_eq!(records, "0+2 records in\n1+0 records out");
+
+        let (out, records) = dd_records(&["ibs=1", "obs=3"], &[b"abcdefg"]);
+        assert_eq!(out, b"abcdefg");
+        assert_eq!(records, "7+0 records in\n2+1 records out");
+    }
+
+    #[test]
+    fn test_dd_fullblock_short_reads() {
+        let (out, records) = dd_records(&["bs=4", "iflag=fullblock"], &[b"ab", b"cd", b"e"]);
+        assert_eq!(out, b"abcde");
+        assert_eq!(records, "1+1 records in\n1+1 records out");
+    }
+
+    #[test]
+    fn test_dd_count_bytes_short_reads() {
+        // Without fullblock, count=5 with bs=3 is one 3-byte read and one
+        // 2-byte read, however short they come back.
+        let chunks: &[&[u8]] = &[b"ab", b"cde", b"fghij"];
+        let (out, records) = dd_records(&["bs=3", "count=5", "iflag=count_bytes"], chunks);
+        assert_eq!(out, b"abcd");
+        assert_eq!(records, "0+2 records in\n0+2 records out");
+        let (out, records) =
+            dd_records(&["bs=3", "count=5", "iflag=count_bytes,fullblock"], chunks);
+        assert_eq!(out, b"abcde");
+        assert_eq!(records, "1+1 records in\n1+1 records out");
+    }
+
+    #[test]
+    fn test_dd_warns_on_partial_read() {
+        let chunks: &[&[u8]] = &[b"ab", b"cde", b"fghij"];
+        let (out, stderr) = dd_chunks(&["bs=4", "count=2"], chunks);
+        assert_eq!(out, b"abcde");
+        assert!(
+            stderr.starts_with(
+                "dd: warning: partial read (2 bytes); suggest iflag=fullblock\n0+2 records in\n"
+            ),
+            "{}",
+            stderr
+        );
+        // Not when the short read is the last one, nor when reads are aggregated.
+        let (_, stderr) = dd_chunks(&["bs=4", "count=1"], chunks);
+        assert!(stderr.starts_with("0+1 records in\n"), "{}", stderr);
+        let (out, stderr) = dd_chunks(&["bs=4", "count=3", "conv=ucase"], chunks);
+        assert_eq!(out, b"ABCDEFGHI");
+        assert!(
+            stderr.starts_with("1+2 records in\n2+1 records out\n"),
+            "{}",
+            stderr
+        );
+        let (_, stderr) = dd_chunks(&["bs=4", "count=2", "status=none"], chunks);
+        assert_eq!(stderr, "");
+    }
+
+    #[test]
+    fn test_dd_bs_overrides_ibs_obs() {
+        let (out, records) = dd_records(&["ibs=1", "obs=1", "bs=3"], &[b"abcdefg"]);
+        assert_eq!(out, b"abcdefg");
+        assert_eq!(records, "2+1 records in\n2+1 records out");
+    }
+
+    #[test]
+    fn test_dd_conv_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("in.txt");
+        std::fs::write(&src, "hello\nworld, too long\nab\nlast").unwrap();
+        let output = cmd()
+            .args([
+                &format!("if={}", src.display()),
+                "conv=block,ucase",
+                "cbs=8",
+                "ibs=5",
+                "status=noxfer",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"HELLO   WORLD, TAB      LAST    ");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "5+1 records in\n0+1 records out\n1 truncated record\n"
+        );
+    }
+
+    #[test]
+    fn test_dd_conv_swab_across_short_reads() {
+        // A pair split across two reads is still swapped; the odd last byte
+        // is output as is.
+        let (out, records) = dd_records(&["conv=swab"], &[b"abc", b"de"]);
+        assert_eq!(out, b"badce");
+        assert_eq!(records, "0+2 records in\n0+1 records out");
+
+        let (out, _) = dd_chunks(
+            &["conv=swab,unblock", "cbs=4", "status=none"],
+            &[b"abc", b"\nde\n"],
+        );
+        assert_eq!(out, b"ba\nc\ned\n\n");
+    }
+
+    #[test]
+    fn test_dd_conv_unblock() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("in.txt");
+        std::fs::write(&src, "Ab      c d     x   ").unwrap();
+        let output = cmd()
+            .args([
+                &format!("if={}", src.display()),
+                "conv=unblock,lcase",
+                "cbs=8",
+                "ibs=3",
+                "status=none",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"ab\nc d\nx\n");
+
+        let output = cmd()
+            .args(["conv=block,unblock", "cbs=8"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "dd: cannot combine block and unblock\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dd_sigusr1_prints_statistics() {
+        use std::io::{BufRead, Read, Write};
+        use std::process::Stdio;
+        use std::time::Duration;
+        let mut child = cmd()
+            .args(["bs=4"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        let stderr = std::io::BufReader::new(child.stderr.take().unwrap());
+        let (tx, rx) = std::sync::mpsc::channel();
+        let reader = std::thread::spawn(move || {
+            for line in stderr.lines() {
+                tx.send(line.unwrap()).unwrap();
+            }
+        });
+        stdin.write_all(b"abc\n").unwrap();
+        // Once the first block is copied, dd is running with its handler
+        // installed and blocked reading the idle pipe.
+        let mut block = [0u8; 4];
+        stdout.read_exact(&mut block).unwrap();
+        assert_eq!(&block, b"abc\n");
+        // Let dd get back into read() so the signal has to interrupt it.
+        #[cfg(target_os = "linux")]
+        {
+            let syscall = format!("/proc/{}/syscall", child.id());
+            let reading = format!("{} ", libc::SYS_read);
+            let deadline = std::time::Instant::now() + Duration::from_secs(10);
+            while !std::fs::read_to_string(&syscall).is_ok_and(|s| s.starts_with(&reading)) {
+                assert!(
+                    std::time::Instant::now() < deadline,
+                    "dd never blocked in read"
+                );
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        // The report must arrive while dd is still blocked waiting for input.
+        // Resend in case the first signal raced with dd entering read().
+        let mut report = Vec::new();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while report.len() < 3 {
+            if report.is_empty() {
+                unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGUSR1) };
+            }
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(line) => report.push(line),
+                Err(_) if std::time::Instant::now() < deadline => {}
+                Err(_) => panic!("no SIGUSR1 report while blocked: {:?}", report),
+            }
+        }
+        assert_eq!(report[0], "1+0 records in");
+        assert_eq!(report[1], "1+0 records out");
+        assert!(report[2].starts_with("4 bytes copied"), "{}", report[2]);
+        stdin.write_all(b"de").unwrap();
+        drop(stdin);
+        assert!(child.wait().unwrap().success());
+        reader.join().unwrap();
+        // The final statistics come last, after any report for a resent signal.
+        let rest: Vec<String> = rx.try_iter().collect();
+        let last = &rest[rest.len().saturating_sub(3)..];
+        assert_eq!(
+            last[..2],
+            ["1+1 records in", "1+1 records out"],
+            "{:?}",
+            rest
+        );
+        assert!(last[2].starts_with("6 bytes copied"), "{:?}", rest);
+
+        // status=none silences the report as well.
+        let mut child = cmd()
+            .args(["bs=4", "status=none"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        stdin.write_all(b"abc\n").unwrap();
+        stdout.read_exact(&mut block).unwrap();
+        unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGUSR1) };
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        drop(stdin);
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty());
+    }
 }
@@ -10,7 +10,7 @@ use std::process;
 #[cfg(unix)]
 use coreutils_rs::common::reset_sigpipe;
 #[cfg(unix)]
-use coreutils_rs::df::{DfConfig, parse_block_size, parse_output_fields, run_df};
+use coreutils_rs::df::{DfConfig, conflicting_type, parse_block_size, parse_output_fields, run_df};
 
 #[cfg(unix)]
 const TOOL_NAME: &str = "df";
@@ -273,6 +273,14 @@ fn main() {
         }
     }
 
+    if let Some(fstype) = conflicting_type(&config) {
+        eprintln!(
+            "{}: file system type '{}' both selected and excluded",
+            TOOL_NAME, fstype
+        );
+        process::exit(1);
+    }
+
     let exit_code = run_df(&config);
     process::exit(exit_code);
 }
@@ -343,14 +351,71 @@ mod tests {
         assert!(stdout.contains("IUse%"), "Should have IUse% header");
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_df_inodes_percent_and_human() {
+        // Used and free inodes add up to the total, and IUse% rounds up.
+        let output = cmd().args(["-i", "/"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().nth(1).unwrap();
+        let f: Vec<&str> = line.split_whitespace().collect();
+        let total: u64 = f[1].parse().unwrap();
+        let used: u64 = f[2].parse().unwrap();
+        let free: u64 = f[3].parse().unwrap();
+        assert_eq!(used + free, total);
+        if total > 0 {
+            let pct = (used * 100).div_ceil(total);
+            assert_eq!(f[4], format!("{}%", pct));
+        }
+
+        // A filesystem without inodes shows "-" for IUse%.
+        let output = cmd().args(["-i", "/proc"]).output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let f: Vec<&str> = stdout.lines().nth(1).unwrap().split_whitespace().collect();
+        assert_eq!(&f[1..5], &["0", "0", "0", "-"]);
+
+        // -h scales inode counts; -P keeps the IUse% header; -T adds Type.
+        let output = cmd().args(["-ihPT", "/proc"]).output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        assert_eq!(
+            header,
+            [
+                "Filesystem",
+                "Type",
+                "Inodes",
+                "IUsed",
+                "IFree",
+                "IUse%",
+                "Mounted",
+                "on"
+            ]
+        );
+        let f: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        assert_eq!(&f[1..6], &["proc", "0", "0", "0", "-"]);
+
+        let output = cmd().args(["-ih", "/"]).output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let f: Vec<&str> = stdout.lines().nth(1).unwrap().split_whitespace().collect();
+        if total >= 1024 {
+            assert!(f[1].ends_with(|c: char| "KMGT".contains(c)), "{}", f[1]);
+        }
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_df_type_filter() {
         // Filter for tmpfs which should exist on most Linux systems.
         let output = cmd().args(["-t", "tmpfs"]).output().unwrap();
-        assert_eq!(output.status.code(), Some(0));
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // All non-header lines should be tmpfs mounts. If tmpfs not present, just header.
+        if output.status.code() != Some(0) {
+            // No tmpfs mounted: GNU reports that nothing was processed.
+            assert!(stdout.is_empty());
+            return;
+        }
+        // All non-header lines should be tmpfs mounts.
         for line in stdout.lines().skip(1) {
             // When filtering by type, result set is restricted to that type.
             // We don't verify the type column here since -T isn't passed,
@@ -492,4 +557,44 @@ mod tests {
             );
         }
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_df_type_both_selected_and_excluded() {
+        let output = cmd().args(["-t", "ext4", "-x", "ext4"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "df: file system type 'ext4' both selected and excluded\n"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_df_no_file_systems_processed() {
+        let output = cmd()
+            .args(["--sync", "-t", "no-such-fstype"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "df: no file systems processed\n"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_df_type_filter_applies_to_files() {
+        let output = cmd()
+            .args(["--no-sync", "-x", "proc", "/proc"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        let output = cmd().args(["-t", "proc", "/proc"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("/proc"));
+    }
 }
@@ -293,6 +293,13 @@ fn parse_args() -> (DuConfig, Vec<String>) {
         i += 1;
     }
 
+    if config.inodes && config.apparent_size {
+        eprintln!(
+            "{}: warning: options --apparent-size and -b are ineffective with --inodes",
+            TOOL_NAME
+        );
+    }
+
     // Default to current directory if no files specified.
     if files.is_empty() {
         files.push(".".to_string());
@@ -311,6 +318,7 @@ fn main() {
     let mut out = BufWriter::with_capacity(256 * 1024, stdout.lock());
     let mut had_error = false;
     let mut grand_total: u64 = 0;
+    let mut grand_mtime: Option<i64> = None;
     let mut seen_inodes = std::collections::HashSet::new();
 
     for file in &files {
@@ -326,6 +334,7 @@ fn main() {
                 // The last entry for a path is the root's total.
                 if let Some(last) = entries.last() {
                     grand_total += last.size;
+                    grand_mtime = grand_mtime.max(last.mtime);
                 }
             }
             Err(e) => {
@@ -345,7 +354,7 @@ fn main() {
         let total_entry = DuEntry {
             size: grand_total,
             path: std::path::PathBuf::from("total"),
-            mtime: None,
+            mtime: grand_mtime,
         };
         if let Err(e) = print_entry(&mut out, &total_entry, &config) {
             eprintln!("{}: write error: {}", TOOL_NAME, e);
@@ -446,6 +455,51 @@ mod tests {
         assert!(output.status.success());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_du_apparent_size_sparse_and_hardlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path().join("d");
+        std::fs::create_dir(&d).unwrap();
+        let sparse = std::fs::File::create(d.join("sparse")).unwrap();
+        sparse.set_len(10 << 20).unwrap();
+        std::fs::write(d.join("small"), "hello").unwrap();
+        std::fs::hard_link(d.join("small"), d.join("hard")).unwrap();
+        let dir_size = std::fs::metadata(&d).unwrap().len();
+
+        // The directory's own size counts and the hard link only once.
+        let total = dir_size + (10 << 20) + 5;
+        let output = cmd()
+            .args(["-sb", "d"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("{}\td\n", total)
+        );
+        let output = cmd()
+            .args(["-s", "--apparent-size", "-k", "d"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("{}\td\n", total.div_ceil(1024))
+        );
+        let output = cmd()
+            .args(["-s", "--apparent-size", "-h", "d"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(
+            String::from_utf8_lossy(&output.stdout).starts_with("11M\t"),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_du_nonexistent() {
@@ -501,4 +555,86 @@ mod tests {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(stdout.contains("total"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_du_inodes_counts_hardlinks_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("a/x"), "").unwrap();
+        std::fs::write(dir.path().join("a/b/g"), "").unwrap();
+        std::fs::hard_link(dir.path().join("a/x"), dir.path().join("a/y")).unwrap();
+        let output = cmd()
+            .args(["--inodes", "."])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "2\t./a/b\n4\t./a\n5\t.\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_du_inodes_all_and_total() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("a")).unwrap();
+        std::fs::write(dir.path().join("a/f"), "data").unwrap();
+        let output = cmd()
+            .args(["--inodes", "-ac", "a"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1\ta/f\n2\ta\n2\ttotal\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_du_inodes_apparent_size_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = cmd()
+            .args(["--inodes", "-b"])
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "du: warning: options --apparent-size and -b are ineffective with --inodes\n"
+        );
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("1\t"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_du_null_terminated_with_time_and_total() {
+        use std::time::{Duration, UNIX_EPOCH};
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path().join("d");
+        std::fs::create_dir(&d).unwrap();
+        let f = std::fs::File::create(d.join("a\nb")).unwrap();
+        f.set_modified(UNIX_EPOCH + Duration::from_secs(1_010_000_000))
+            .unwrap();
+        std::fs::File::open(&d)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000_000))
+            .unwrap();
+        let output = cmd()
+            .args(["--inodes", "-0ac", "--time", "--time-style=iso", "d"])
+            .current_dir(dir.path())
+            .env("TZ", "UTC")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1\t2002-01-02\td/a\nb\x002\t2002-01-02\td\x002\t2002-01-02\ttotal\x00"
+        );
+    }
 }
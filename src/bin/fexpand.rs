@@ -1,14 +1,12 @@
-use std::io::{self, BufWriter, Write};
+use std::io::{self, BufWriter, Read, Write};
 #[cfg(unix)]
 use std::mem::ManuallyDrop;
 #[cfg(unix)]
 use std::os::unix::io::FromRawFd;
-use std::path::Path;
 use std::process;
 
-use coreutils_rs::common::io::{read_file, read_stdin};
 use coreutils_rs::common::io_error_msg;
-use coreutils_rs::expand::{TabStops, expand_bytes, parse_tab_stops};
+use coreutils_rs::expand::{Expander, TabStops, parse_tab_stops};
 
 struct Cli {
     initial: bool,
@@ -148,6 +146,31 @@ fn enlarge_pipes() {
     }
 }
 
+enum StreamError {
+    Read(io::Error),
+    Write(io::Error),
+}
+
+/// Feed `input` through the expander chunk by chunk.
+fn expand_stream(
+    input: &mut impl Read,
+    expander: &mut Expander,
+    buf: &mut [u8],
+    out: &mut impl Write,
+) -> Result<(), StreamError> {
+    loop {
+        let n = match input.read(buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(StreamError::Read(e)),
+        };
+        expander
+            .expand(&buf[..n], out)
+            .map_err(StreamError::Write)?;
+    }
+}
+
 fn main() {
     coreutils_rs::common::reset_sigpipe();
 
@@ -173,33 +196,38 @@ fn main() {
 
     let mut had_error = false;
 
+    // One expander for all inputs: GNU carries the column across files.
+    let mut expander = Expander::new(cli.tabs, cli.initial);
+    let mut buf = vec![0u8; 256 * 1024];
+
     for filename in &files {
-        let data = if filename == "-" {
-            match read_stdin() {
-                Ok(d) => coreutils_rs::common::io::FileData::Owned(d),
-                Err(e) => {
-                    eprintln!("expand: standard input: {}", io_error_msg(&e));
-                    had_error = true;
-                    continue;
-                }
-            }
+        let (name, result) = if filename == "-" {
+            let stdin = io::stdin();
+            let mut input = stdin.lock();
+            let r = expand_stream(&mut input, &mut expander, &mut buf, &mut out);
+            ("standard input", r)
         } else {
-            match read_file(Path::new(filename)) {
-                Ok(d) => d,
-                Err(e) => {
-                    eprintln!("expand: {}: {}", filename, io_error_msg(&e));
-                    had_error = true;
-                    continue;
-                }
+            match std::fs::File::open(filename) {
+                Ok(mut f) => (
+                    filename.as_str(),
+                    expand_stream(&mut f, &mut expander, &mut buf, &mut out),
+                ),
+                Err(e) => (filename.as_str(), Err(StreamError::Read(e))),
             }
         };
-
-        if let Err(e) = expand_bytes(&data, &cli.tabs, cli.initial, &mut out) {
-            if e.kind() == io::ErrorKind::BrokenPipe {
-                process::exit(0);
+        match result {
+            Ok(()) => {}
+            Err(StreamError::Read(e)) => {
+                eprintln!("expand: {}: {}", name, io_error_msg(&e));
+                had_error = true;
+            }
+            Err(StreamError::Write(e)) => {
+                if e.kind() == io::ErrorKind::BrokenPipe {
+                    process::exit(0);
+                }
+                eprintln!("expand: write error: {}", io_error_msg(&e));
+                had_error = true;
             }
-            eprintln!("expand: write error: {}", io_error_msg(&e));
-            had_error = true;
         }
     }
 
@@ -347,4 +375,101 @@ mod tests {
         let output = cmd().arg("/nonexistent/file.txt").output().unwrap();
         assert!(!output.status.success());
     }
+
+    fn expand_with(args: &[&str], input: &[u8]) -> std::process::Output {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_expand_tab_list() {
+        let output = expand_with(&["-t", "4,8,16"], b"\t\t\tx\n");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"                x\n");
+        // Blank-separated lists work too; past the last stop a tab is one space
+        let output = expand_with(&["-t", "2 5"], b"a\tb\tc\td\n");
+        assert_eq!(output.stdout, b"a b  c d\n");
+    }
+
+    #[test]
+    fn test_expand_tab_list_extensions() {
+        // /N: stops at multiples of N after the list
+        let output = expand_with(&["-t", "3,/5"], b"\ta\tb\tc\n");
+        assert_eq!(output.stdout, b"   a b    c\n");
+        // +N: stops every N columns after the last explicit stop
+        let output = expand_with(&["-t", "3,+5"], b"\ta\tb\tc\n");
+        assert_eq!(output.stdout, b"   a    b    c\n");
+    }
+
+    #[test]
+    fn test_expand_tab_list_errors() {
+        let output = expand_with(&["-t", "4,4"], b"");
+        assert!(!output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expand: tab sizes must be ascending\n"
+        );
+        let output = expand_with(&["-t", "/4,8"], b"");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expand: '/' specifier only allowed with the last value\n"
+        );
+        let output = expand_with(&["-t", "4,x"], b"");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expand: tab size contains invalid character(s): 'x'\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_column_carries_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        std::fs::write(&first, "ab").unwrap();
+        std::fs::write(&second, "\tx\n").unwrap();
+        let output = cmd().arg(&first).arg(&second).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"ab      x\n");
+    }
+
+    #[test]
+    fn test_expand_backspace_and_newlines_in_runs() {
+        let output = expand_with(&[], b"ab\x08\tx\n\x08\x08\ty\nlong line\nz\tw\n");
+        assert!(output.status.success());
+        assert_eq!(
+            output.stdout,
+            b"ab\x08       x\n\x08\x08        y\nlong line\nz       w\n"
+        );
+        // A backspace ends the leading blanks of --initial.
+        let output = expand_with(&["-i"], b" \x08\tx\n\ty\tz\n");
+        assert_eq!(output.stdout, b" \x08\tx\n        y\tz\n");
+    }
+
+    #[test]
+    fn test_expand_large_streamed_input() {
+        let mut input = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..50_000 {
+            let pad = "x".repeat(i % 11);
+            input.extend_from_slice(format!("{pad}\t{i}\n").as_bytes());
+            let spaces = " ".repeat(8 - (i % 11) % 8);
+            expected.extend_from_slice(format!("{pad}{spaces}{i}\n").as_bytes());
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("big");
+        std::fs::write(&file, &input).unwrap();
+        let output = cmd().arg(&file).output().unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout == expected);
+    }
 }
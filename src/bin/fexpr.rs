@@ -1,11 +1,11 @@
 use std::process;
 
-use coreutils_rs::expr::{EXIT_FAILURE, EXIT_SUCCESS, evaluate_expr};
+use coreutils_rs::expr::{EXIT_EXPR_ERROR, EXIT_FAILURE, EXIT_SUCCESS, evaluate_expr};
 
 fn main() {
     coreutils_rs::common::reset_sigpipe();
 
-    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
 
     // Handle --help and --version before parsing expression
     if args.len() == 1 {
@@ -22,6 +22,15 @@ fn main() {
         }
     }
 
+    if args.first().map(String::as_str) == Some("--") {
+        args.remove(0);
+    }
+    if args.is_empty() {
+        eprintln!("expr: missing operand");
+        eprintln!("Try 'expr --help' for more information.");
+        process::exit(EXIT_EXPR_ERROR);
+    }
+
     match evaluate_expr(&args) {
         Ok(value) => {
             println!("{}", value);
@@ -72,6 +81,8 @@ fn print_help() {
     println!("  substr STRING POS LENGTH  substring of STRING, POS counted from 1");
     println!("  index STRING CHARS  index in STRING where any CHARS is found, or 0");
     println!("  length STRING     length of STRING");
+    println!("  + TOKEN           interpret TOKEN as a string, even if it is a");
+    println!("                      keyword like 'match' or an operator like '/'");
     println!();
     println!("  ( EXPRESSION )    value of EXPRESSION");
     println!();
@@ -221,4 +232,85 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "-2");
     }
+
+    fn run(args: &[&str]) -> (String, i32) {
+        let output = cmd().args(args).output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string();
+        (stdout, output.status.code().unwrap())
+    }
+
+    #[test]
+    fn test_expr_short_circuit_and_null_values() {
+        assert_eq!(run(&["1", "|", "1", "/", "0"]), ("1".into(), 0));
+        assert_eq!(run(&["0", "&", "1", "/", "0"]), ("0".into(), 1));
+        assert_eq!(run(&["", "|", ""]), ("0".into(), 1));
+        assert_eq!(run(&["00", "|", "a"]), ("a".into(), 0));
+        assert_eq!(run(&["05"]), ("05".into(), 0));
+        assert_eq!(run(&["1", ">", "0"]), ("1".into(), 0));
+        assert_eq!(run(&["10", "<", "9"]), ("0".into(), 1));
+        assert_eq!(run(&["10", "<", "9a"]), ("1".into(), 0));
+        assert_eq!(run(&["+1", "=", "1"]), ("0".into(), 1));
+    }
+
+    #[test]
+    fn test_expr_match_anchoring_and_captures() {
+        assert_eq!(run(&["abc", ":", "b"]), ("0".into(), 1));
+        assert_eq!(run(&["abc", ":", "a\\(b\\)c"]), ("b".into(), 0));
+        assert_eq!(run(&["a.b", ":", "\\(a\\.b\\)"]), ("a.b".into(), 0));
+        assert_eq!(run(&["a^b$c", ":", "a^b$c"]), ("5".into(), 0));
+        assert_eq!(run(&["*a", ":", "*a"]), ("2".into(), 0));
+        assert_eq!(
+            run(&["abcd", ":", "\\(abc\\)", ":", "\\(a\\)"]),
+            ("a".into(), 0)
+        );
+        let output = cmd().args(["abc", ":", "\\("]).output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expr: Unmatched ( or \\(\n"
+        );
+    }
+
+    #[test]
+    fn test_expr_syntax_errors() {
+        let output = cmd().args(["(", "1"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expr: syntax error: expecting ')' after '1'\n"
+        );
+        let output = cmd().args(["1", "+"]).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expr: syntax error: missing argument after '+'\n"
+        );
+    }
+
+    #[test]
+    fn test_expr_plus_quotes_token() {
+        // A leading '+' makes the next token a string, keyword or not.
+        assert_eq!(run(&["+", "index"]), ("index".into(), 0));
+        assert_eq!(run(&["+", "/"]), ("/".into(), 0));
+        assert_eq!(run(&["length", "+", "length"]), ("6".into(), 0));
+        assert_eq!(run(&["index", "+", "index", "d"]), ("3".into(), 0));
+        assert_eq!(run(&["+", "-5"]), ("-5".into(), 0));
+        // There is no unary plus: '+' only quotes, so these are still sums.
+        assert_eq!(run(&["+", "1", "+", "2"]), ("3".into(), 0));
+        assert_eq!(run(&["1", "+", "+", "2"]), ("3".into(), 0));
+        // Only the quoted token is affected.
+        let output = cmd().args(["+", "match", "=", "match"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expr: syntax error: missing argument after 'match'\n"
+        );
+        let output = cmd().arg("+").output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "expr: syntax error: missing argument after '+'\n"
+        );
+    }
 }
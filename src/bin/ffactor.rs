@@ -229,6 +229,60 @@ fn try_mmap_stdin() -> Option<memmap2::Mmap> {
     mmap
 }
 
+/// Whitespace that separates numbers on stdin (C `isspace`).
+#[inline]
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+/// Parse a token (optionally prefixed by '+') as a number that fits in u128.
+#[inline]
+fn parse_number(token: &[u8]) -> Option<u128> {
+    let digits = token.strip_prefix(b"+").unwrap_or(token);
+    if digits.is_empty() {
+        return None;
+    }
+    // u64 accumulation first: hardware multiply is much cheaper than u128
+    let mut n64: u64 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        let d = digits[i].wrapping_sub(b'0');
+        if d > 9 {
+            return None;
+        }
+        match n64.checked_mul(10).and_then(|v| v.checked_add(d as u64)) {
+            Some(v) => n64 = v,
+            None => break,
+        }
+        i += 1;
+    }
+    let mut n = n64 as u128;
+    for &b in &digits[i..] {
+        let d = b.wrapping_sub(b'0');
+        if d > 9 {
+            return None;
+        }
+        n = n.checked_mul(10)?.checked_add(d as u128)?;
+    }
+    Some(n)
+}
+
+/// Append the factorization line for `n` to `out_buf`.
+#[inline]
+fn write_number(n: u128, exponents: bool, out_buf: &mut Vec<u8>) {
+    if n <= u64::MAX as u128 {
+        if exponents {
+            write_factors_u64_exp(n as u64, out_buf);
+        } else {
+            factor::write_factors_u64(n as u64, out_buf);
+        }
+    } else if exponents {
+        write_factors_exp(n, out_buf);
+    } else {
+        factor::write_factors(n, out_buf);
+    }
+}
+
 /// Parse and factor a single whitespace-delimited token.
 /// Returns true on error (matching the convention of all other functions in this file).
 #[inline]
@@ -242,94 +296,22 @@ fn factor_token(
         return false;
     }
 
-    // Strip leading '+' (GNU compat)
-    let token = if !token.is_empty() && token[0] == b'+' {
-        &token[1..]
-    } else {
-        token
-    };
-    if token.is_empty() {
-        return report_invalid(b"+", out_buf, out);
-    }
-
-    // Try u64 fast path first (handles all numbers up to u64::MAX = 20 digits).
-    let mut n64: u64 = 0;
-    let mut valid_u64 = true;
-    let mut overflowed = false;
-    for &b in token {
-        let d = b.wrapping_sub(b'0');
-        if d > 9 {
-            valid_u64 = false;
-            break;
-        }
-        n64 = match n64.checked_mul(10) {
-            Some(v) => match v.checked_add(d as u64) {
-                Some(v) => v,
-                None => {
-                    overflowed = true;
-                    break;
-                }
-            },
-            None => {
-                overflowed = true;
-                break;
-            }
-        };
-    }
-    if valid_u64 && !overflowed {
-        if exponents {
-            write_factors_u64_exp(n64, out_buf);
-        } else {
-            factor::write_factors_u64(n64, out_buf);
-        }
+    if let Some(n) = parse_number(token) {
+        write_number(n, exponents, out_buf);
         flush_if_full(out_buf, out);
         return false;
     }
 
-    // u128 path for numbers > u64::MAX
-    if overflowed {
-        let mut n: u128 = 0;
-        let mut valid_u128 = true;
-        for &b in token {
-            let d = b.wrapping_sub(b'0');
-            if d > 9 {
-                valid_u128 = false;
-                break;
-            }
-            n = match n.checked_mul(10) {
-                Some(v) => match v.checked_add(d as u128) {
-                    Some(v) => v,
-                    None => {
-                        valid_u128 = false;
-                        break;
-                    }
-                },
-                None => {
-                    valid_u128 = false;
-                    break;
-                }
-            };
-        }
-        if valid_u128 {
-            if exponents {
-                write_factors_exp(n, out_buf);
-            } else {
-                factor::write_factors(n, out_buf);
-            }
-            flush_if_full(out_buf, out);
-            return false;
-        }
-
-        // Number overflows u128 — try big number path if all digits
-        if token.iter().all(|&b| b.is_ascii_digit()) {
-            if !out_buf.is_empty() {
-                let _ = out.write_all(out_buf);
-                out_buf.clear();
-            }
-            let _ = out.flush();
-            let token_str = std::str::from_utf8(token).unwrap_or("");
-            return process_big_number(token_str, exponents, out);
+    // Number overflows u128 — try big number path if all digits
+    let digits = token.strip_prefix(b"+").unwrap_or(token);
+    if !digits.is_empty() && digits.iter().all(|&b| b.is_ascii_digit()) {
+        if !out_buf.is_empty() {
+            let _ = out.write_all(out_buf);
+            out_buf.clear();
         }
+        let _ = out.flush();
+        let token_str = std::str::from_utf8(digits).unwrap_or("");
+        return process_big_number(token_str, exponents, out);
     }
 
     report_invalid(token, out_buf, out)
@@ -423,12 +405,124 @@ fn flush_if_full(out_buf: &mut Vec<u8>, out: &mut BufWriter<io::StdoutLock>) {
     }
 }
 
+/// Size of the input windows handed to `process_tokens`.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Below this many input bytes a chunk is factored on the calling thread;
+/// spinning up the rayon pool costs more than it saves.
+const PARALLEL_MIN: usize = 64 * 1024;
+
+/// A token the workers leave for the main thread: invalid input (its error
+/// must appear in order with stdout) or a number too large for u128.
+struct Deferred<'a> {
+    /// Offset in the piece's output where the token's result belongs.
+    at: usize,
+    token: &'a [u8],
+}
+
+/// Factor every number in `input` into `out_buf`, recording the tokens that
+/// need the serial path instead.
+fn factor_slice<'a>(
+    input: &'a [u8],
+    exponents: bool,
+    out_buf: &mut Vec<u8>,
+    deferred: &mut Vec<Deferred<'a>>,
+) {
+    let mut pos = 0;
+    let len = input.len();
+    while pos < len {
+        while pos < len && is_space(input[pos]) {
+            pos += 1;
+        }
+        if pos >= len {
+            break;
+        }
+        let start = pos;
+        while pos < len && !is_space(input[pos]) {
+            pos += 1;
+        }
+        let token = &input[start..pos];
+        match parse_number(token) {
+            Some(n) => write_number(n, exponents, out_buf),
+            None => deferred.push(Deferred {
+                at: out_buf.len(),
+                token,
+            }),
+        }
+    }
+}
+
+/// Write a factored piece to `out`, handling its deferred tokens in place.
+/// Returns true if any token was invalid.
+fn emit_piece(
+    piece: &[u8],
+    deferred: &[Deferred],
+    exponents: bool,
+    out: &mut BufWriter<io::StdoutLock>,
+) -> bool {
+    let mut had_error = false;
+    let mut pos = 0;
+    let mut tmp = Vec::new();
+    for d in deferred {
+        if out.write_all(&piece[pos..d.at]).is_err() {
+            process::exit(0);
+        }
+        pos = d.at;
+        if factor_token(d.token, exponents, &mut tmp, out) {
+            had_error = true;
+        }
+        if !tmp.is_empty() {
+            if out.write_all(&tmp).is_err() {
+                process::exit(0);
+            }
+            tmp.clear();
+        }
+    }
+    if out.write_all(&piece[pos..]).is_err() {
+        process::exit(0);
+    }
+    had_error
+}
+
+/// Split `input` into about `parts` pieces, each ending after whitespace so
+/// no token straddles two pieces.
+fn split_at_whitespace(input: &[u8], parts: usize) -> Vec<&[u8]> {
+    let target = input.len().div_ceil(parts).max(1);
+    let mut pieces = Vec::with_capacity(parts);
+    let mut rest = input;
+    while rest.len() > target {
+        let end = match rest[target..].iter().position(|&b| is_space(b)) {
+            Some(p) => target + p + 1,
+            None => rest.len(),
+        };
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
 /// Process byte buffer of whitespace-delimited numbers (used by mmap path).
 fn process_bytes(input: &[u8], exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
-    let mut out_buf = Vec::with_capacity(128 * 1024);
-    let had_error = process_tokens(input, exponents, &mut out_buf, out);
-    if !out_buf.is_empty() && out.write_all(&out_buf).is_err() {
-        process::exit(0);
+    let mut had_error = false;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let end = if rest.len() <= CHUNK_SIZE {
+            rest.len()
+        } else {
+            match rest[CHUNK_SIZE..].iter().position(|&b| is_space(b)) {
+                Some(p) => CHUNK_SIZE + p + 1,
+                None => rest.len(),
+            }
+        };
+        let (chunk, tail) = rest.split_at(end);
+        if process_tokens(chunk, exponents, out) {
+            had_error = true;
+        }
+        rest = tail;
     }
     had_error
 }
@@ -449,16 +543,15 @@ fn process_stdin(exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
     use std::io::Read;
     let stdin = io::stdin();
     let mut reader = stdin.lock();
-    let mut buf = vec![0u8; 256 * 1024];
+    let mut buf = vec![0u8; CHUNK_SIZE];
     let mut leftover = 0usize; // bytes carried over from previous chunk
     let mut had_error = false;
-    let mut out_buf = Vec::with_capacity(128 * 1024);
 
     loop {
         let n = match reader.read(&mut buf[leftover..]) {
             Ok(0) => {
                 // EOF: process any remaining leftover bytes
-                if leftover > 0 && process_tokens(&buf[..leftover], exponents, &mut out_buf, out) {
+                if leftover > 0 && process_tokens(&buf[..leftover], exponents, out) {
                     had_error = true;
                 }
                 break;
@@ -466,10 +559,6 @@ fn process_stdin(exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
             Ok(n) => n,
             Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
             Err(e) => {
-                // Flush any buffered output before reporting the error
-                if !out_buf.is_empty() {
-                    let _ = out.write_all(&out_buf);
-                }
                 let _ = out.flush();
                 eprintln!("{}: read error: {}", TOOL_NAME, e);
                 return true;
@@ -477,39 +566,23 @@ fn process_stdin(exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
         };
 
         let total = leftover + n;
-        // Find last newline boundary to avoid splitting a number
-        let boundary = match memchr::memrchr(b'\n', &buf[..total]) {
+        // Process up to the last whitespace so no number is split; with no
+        // whitespace at all, wait for more input unless the buffer is full
+        // (a single huge token).
+        let boundary = match buf[..total].iter().rposition(|&b| is_space(b)) {
             Some(pos) => pos + 1,
-            None => {
-                // No newline in buffer — carry everything forward
+            None if total < buf.len() => {
                 leftover = total;
-                if leftover >= buf.len() {
-                    // Buffer full with no newline (very long line).
-                    // Find last whitespace to avoid splitting a token.
-                    let split = buf[..leftover]
-                        .iter()
-                        .rposition(|&b| b == b' ' || b == b'\t' || b == b'\r')
-                        .map(|p| p + 1)
-                        .unwrap_or(leftover); // no whitespace: process entire buffer (single huge token)
-                    if process_tokens(&buf[..split], exponents, &mut out_buf, out) {
-                        had_error = true;
-                    }
-                    let remaining = leftover - split;
-                    if remaining > 0 {
-                        buf.copy_within(split..leftover, 0);
-                    }
-                    leftover = remaining;
-                }
                 continue;
             }
+            None => total,
         };
 
-        // Process complete lines
-        if process_tokens(&buf[..boundary], exponents, &mut out_buf, out) {
+        if process_tokens(&buf[..boundary], exponents, out) {
             had_error = true;
         }
 
-        // Move leftover (incomplete line) to start of buffer
+        // Move leftover (incomplete token) to start of buffer
         let remaining = total - boundary;
         if remaining > 0 {
             buf.copy_within(boundary..total, 0);
@@ -517,55 +590,39 @@ fn process_stdin(exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
         leftover = remaining;
     }
 
-    if !out_buf.is_empty() && out.write_all(&out_buf).is_err() {
-        process::exit(0);
-    }
-
     had_error
 }
 
-/// Process a chunk of bytes containing whitespace-delimited numbers.
+/// Process a chunk of bytes containing whitespace-delimited numbers, which
+/// must not end in the middle of a token. Large chunks are split across the
+/// rayon pool; the pieces are written back in input order.
 /// Returns true if any error occurred.
-fn process_tokens(
-    input: &[u8],
-    exponents: bool,
-    out_buf: &mut Vec<u8>,
-    out: &mut BufWriter<io::StdoutLock>,
-) -> bool {
-    let mut had_error = false;
-    let mut pos = 0;
-    let len = input.len();
+fn process_tokens(input: &[u8], exponents: bool, out: &mut BufWriter<io::StdoutLock>) -> bool {
+    let threads = rayon::current_num_threads();
+    if threads <= 1 || input.len() < PARALLEL_MIN {
+        let mut piece = Vec::with_capacity(input.len() * 2);
+        let mut deferred = Vec::new();
+        factor_slice(input, exponents, &mut piece, &mut deferred);
+        return emit_piece(&piece, &deferred, exponents, out);
+    }
+
+    use rayon::prelude::*;
+    let results: Vec<(Vec<u8>, Vec<Deferred>)> = split_at_whitespace(input, threads * 4)
+        .into_par_iter()
+        .map(|slice| {
+            let mut piece = Vec::with_capacity(slice.len() * 2);
+            let mut deferred = Vec::new();
+            factor_slice(slice, exponents, &mut piece, &mut deferred);
+            (piece, deferred)
+        })
+        .collect();
 
-    while pos < len {
-        // Skip whitespace
-        while pos < len
-            && (input[pos] == b' '
-                || input[pos] == b'\n'
-                || input[pos] == b'\r'
-                || input[pos] == b'\t')
-        {
-            pos += 1;
-        }
-        if pos >= len {
-            break;
-        }
-
-        // Scan token
-        let start = pos;
-        while pos < len
-            && input[pos] != b' '
-            && input[pos] != b'\n'
-            && input[pos] != b'\r'
-            && input[pos] != b'\t'
-        {
-            pos += 1;
-        }
-
-        if factor_token(&input[start..pos], exponents, out_buf, out) {
+    let mut had_error = false;
+    for (piece, deferred) in &results {
+        if emit_piece(piece, deferred, exponents, out) {
             had_error = true;
         }
     }
-
     had_error
 }
 
@@ -748,4 +805,122 @@ mod tests {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert_eq!(stdout.trim(), "2147483647: 2147483647");
     }
+
+    #[test]
+    fn test_factor_carmichael_numbers() {
+        // Fermat liars for every coprime base; Miller-Rabin must still reject them.
+        // 3825123056546413051 is a strong pseudoprime to bases 2 through 23.
+        let output = cmd()
+            .args([
+                "561",
+                "41041",
+                "825265",
+                "5394826801",
+                "3825123056546413051",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "561: 3 11 17\n\
+             41041: 7 11 13 41\n\
+             825265: 5 7 17 19 73\n\
+             5394826801: 7 13 17 23 31 67 73\n\
+             3825123056546413051: 149491 747451 34233211\n"
+        );
+    }
+
+    #[test]
+    fn test_factor_u64_primes_and_semiprimes() {
+        let output = cmd()
+            .args([
+                "9223372036854775783",
+                "18446744073709551557",
+                "9223371873002223329",
+                "9223372047592193861",
+                "18446744030759878681",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "9223372036854775783: 9223372036854775783\n\
+             18446744073709551557: 18446744073709551557\n\
+             9223371873002223329: 3037000453 3037000493\n\
+             9223372047592193861: 2147483659 4294967279\n\
+             18446744030759878681: 4294967291 4294967291\n"
+        );
+    }
+
+    #[test]
+    fn test_factor_stdin_any_whitespace() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"6 10\t14\x0b15\x0c+21\r\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "6: 2 3\n10: 2 5\n14: 2 7\n15: 3 5\n21: 3 7\n"
+        );
+    }
+
+    #[test]
+    fn test_factor_stdin_invalid_token_continues() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"4 x1 9\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "4: 2 2\n9: 3 3\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "factor: \u{2018}x1\u{2019} is not a valid positive integer\n"
+        );
+    }
+
+    #[test]
+    fn test_factor_stdin_parallel_preserves_order() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut input: String = (1..=50000).map(|i| format!("{}\n", i)).collect();
+        input.push_str("bogus\n");
+        let mut child = cmd()
+            .env("RAYON_NUM_THREADS", "4")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()).unwrap());
+        let output = child.wait_with_output().unwrap();
+        writer.join().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 50000);
+        for (i, line) in lines.iter().enumerate() {
+            assert!(line.starts_with(&format!("{}:", i + 1)), "{}", line);
+        }
+        assert_eq!(lines[9999], "10000: 2 2 2 2 5 5 5 5");
+    }
 }
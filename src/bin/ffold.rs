@@ -336,6 +336,32 @@ mod tests {
         assert_eq!(lines[1], "def");
     }
 
+    #[test]
+    fn test_fold_bytes_ignores_column_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("in");
+        // Tab, backspace and CR are one byte each, and a UTF-8 sequence may be split.
+        std::fs::write(&file, b"ab\tcd\x08ef\rgh\nh\xc3\xa9llo").unwrap();
+        let output = cmd().args(["-b", "-w", "4"]).arg(&file).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"ab\tc\nd\x08ef\n\rgh\nh\xc3\xa9l\nlo");
+        let output = cmd().args(["-b", "-w", "2"]).arg(&file).output().unwrap();
+        assert!(output.stdout.ends_with(b"\nh\xc3\n\xa9l\nlo"));
+    }
+
+    #[test]
+    fn test_fold_bytes_spaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("in");
+        std::fs::write(&file, "ab cd\tef ghij\n").unwrap();
+        // With -b the tab is a single byte, so "cd\t" still fits in 4.
+        let output = cmd().args(["-bs", "-w", "4"]).arg(&file).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"ab \ncd\t\nef \nghij\n");
+        let output = cmd().args(["-s", "-w", "4"]).arg(&file).output().unwrap();
+        assert_eq!(output.stdout, b"ab \ncd\n\t\nef \nghij\n");
+    }
+
     #[test]
     fn test_fold_spaces() {
         use std::io::Write;
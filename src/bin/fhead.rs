@@ -6,16 +6,18 @@ use coreutils_rs::head::{self, HeadConfig, HeadMode};
 
 struct Cli {
     config: HeadConfig,
-    quiet: bool,
-    verbose: bool,
     files: Vec<String>,
 }
 
+/// -q and -v override each other; the last one given wins.
+fn set_quiet(config: &mut HeadConfig, quiet: bool) {
+    config.quiet = quiet;
+    config.verbose = !quiet;
+}
+
 fn parse_args() -> Cli {
     let mut cli = Cli {
         config: HeadConfig::default(),
-        quiet: false,
-        verbose: false,
         files: Vec::new(),
     };
 
@@ -32,46 +34,38 @@ fn parse_args() -> Cli {
         }
         if bytes.starts_with(b"--") {
             let s = arg.to_string_lossy();
-            if let Some(val) = s.strip_prefix("--lines=") {
-                parse_lines_value(val, &mut cli.config);
-                // mode set
-            } else if let Some(val) = s.strip_prefix("--bytes=") {
-                parse_bytes_value(val, &mut cli.config);
-                // mode set
-            } else {
-                match bytes {
-                    b"--lines" => {
-                        let val = args.next().unwrap_or_else(|| {
-                            eprintln!("head: option '--lines' requires an argument");
-                            process::exit(1);
-                        });
-                        parse_lines_value(&val.to_string_lossy(), &mut cli.config);
-                        // mode set
-                    }
-                    b"--bytes" => {
-                        let val = args.next().unwrap_or_else(|| {
-                            eprintln!("head: option '--bytes' requires an argument");
-                            process::exit(1);
-                        });
-                        parse_bytes_value(&val.to_string_lossy(), &mut cli.config);
-                        // mode set
-                    }
-                    b"--quiet" | b"--silent" => cli.quiet = true,
-                    b"--verbose" => cli.verbose = true,
-                    b"--zero-terminated" => cli.config.zero_terminated = true,
-                    b"--help" => {
-                        print_help();
-                        process::exit(0);
-                    }
-                    b"--version" => {
-                        println!("head (fcoreutils) {}", env!("CARGO_PKG_VERSION"));
-                        process::exit(0);
-                    }
-                    _ => {
-                        eprintln!("head: unrecognized option '{}'", s);
-                        eprintln!("Try 'head --help' for more information.");
-                        process::exit(1);
-                    }
+            let (name, value) = match s[2..].split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (&s[2..], None),
+            };
+            let opt = resolve_long_option(name, &s);
+            let takes_value = matches!(opt, "bytes" | "lines");
+            if !takes_value && value.is_some() {
+                usage_error(&format!("option '--{}' doesn't allow an argument", opt));
+            }
+            let mut value = || match value {
+                Some(v) => v.to_string(),
+                None => args
+                    .next()
+                    .unwrap_or_else(|| {
+                        usage_error(&format!("option '--{}' requires an argument", opt))
+                    })
+                    .to_string_lossy()
+                    .into_owned(),
+            };
+            match opt {
+                "lines" => parse_lines_value(&value(), &mut cli.config),
+                "bytes" => parse_bytes_value(&value(), &mut cli.config),
+                "quiet" | "silent" => set_quiet(&mut cli.config, true),
+                "verbose" => set_quiet(&mut cli.config, false),
+                "zero-terminated" => cli.config.zero_terminated = true,
+                "help" => {
+                    print_help();
+                    process::exit(0);
+                }
+                _ => {
+                    println!("head (fcoreutils) {}", env!("CARGO_PKG_VERSION"));
+                    process::exit(0);
                 }
             }
         } else if bytes.len() > 1 && bytes[0] == b'-' {
@@ -87,8 +81,7 @@ fn parse_args() -> Cli {
                         } else {
                             args.next()
                                 .unwrap_or_else(|| {
-                                    eprintln!("head: option requires an argument -- 'n'");
-                                    process::exit(1);
+                                    usage_error("option requires an argument -- 'n'")
                                 })
                                 .to_string_lossy()
                                 .into_owned()
@@ -103,8 +96,7 @@ fn parse_args() -> Cli {
                         } else {
                             args.next()
                                 .unwrap_or_else(|| {
-                                    eprintln!("head: option requires an argument -- 'c'");
-                                    process::exit(1);
+                                    usage_error("option requires an argument -- 'c'")
                                 })
                                 .to_string_lossy()
                                 .into_owned()
@@ -113,8 +105,8 @@ fn parse_args() -> Cli {
                         // mode set
                         break;
                     }
-                    'q' => cli.quiet = true,
-                    'v' => cli.verbose = true,
+                    'q' => set_quiet(&mut cli.config, true),
+                    'v' => set_quiet(&mut cli.config, false),
                     'z' => cli.config.zero_terminated = true,
                     '0'..='9' => {
                         // Legacy: head -N means head -n N
@@ -123,11 +115,7 @@ fn parse_args() -> Cli {
                         // mode set
                         break;
                     }
-                    _ => {
-                        eprintln!("head: invalid option -- '{}'", chars[i]);
-                        eprintln!("Try 'head --help' for more information.");
-                        process::exit(1);
-                    }
+                    _ => usage_error(&format!("invalid option -- '{}'", chars[i])),
                 }
                 i += 1;
             }
@@ -139,42 +127,79 @@ fn parse_args() -> Cli {
     cli
 }
 
+const LONG_OPTIONS: &[&str] = &[
+    "bytes",
+    "lines",
+    "quiet",
+    "silent",
+    "verbose",
+    "zero-terminated",
+    "help",
+    "version",
+];
+
+/// Resolve a long option name, accepting unambiguous abbreviations as
+/// getopt_long does (`--lin=5`, `--z`). `arg` is the whole argument, for errors.
+fn resolve_long_option(name: &str, arg: &str) -> &'static str {
+    if let Some(&opt) = LONG_OPTIONS.iter().find(|&&opt| opt == name) {
+        return opt;
+    }
+    let candidates: Vec<&'static str> = LONG_OPTIONS
+        .iter()
+        .copied()
+        .filter(|opt| opt.starts_with(name))
+        .collect();
+    match candidates.as_slice() {
+        [opt] => opt,
+        [] => usage_error(&format!("unrecognized option '{}'", arg)),
+        _ => {
+            let possibilities: Vec<String> = candidates
+                .iter()
+                .map(|opt| format!("'--{}'", opt))
+                .collect();
+            usage_error(&format!(
+                "option '{}' is ambiguous; possibilities: {}",
+                arg,
+                possibilities.join(" ")
+            ))
+        }
+    }
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("head: {}", msg);
+    eprintln!("Try 'head --help' for more information.");
+    process::exit(1);
+}
+
 fn parse_lines_value(val: &str, config: &mut HeadConfig) {
     if let Some(stripped) = val.strip_prefix('-') {
-        match head::parse_size(stripped) {
-            Ok(n) => config.mode = HeadMode::LinesFromEnd(n),
-            Err(_) => {
-                eprintln!("head: invalid number of lines: '{}'", val);
-                process::exit(1);
-            }
-        }
+        config.mode = HeadMode::LinesFromEnd(parse_count(stripped, "lines"));
     } else {
-        match head::parse_size(val) {
-            Ok(n) => config.mode = HeadMode::Lines(n),
-            Err(_) => {
-                eprintln!("head: invalid number of lines: '{}'", val);
-                process::exit(1);
-            }
-        }
+        config.mode = HeadMode::Lines(parse_count(val, "lines"));
     }
 }
 
 fn parse_bytes_value(val: &str, config: &mut HeadConfig) {
     if let Some(stripped) = val.strip_prefix('-') {
-        match head::parse_size(stripped) {
-            Ok(n) => config.mode = HeadMode::BytesFromEnd(n),
-            Err(_) => {
-                eprintln!("head: invalid number of bytes: '{}'", val);
-                process::exit(1);
-            }
-        }
+        config.mode = HeadMode::BytesFromEnd(parse_count(stripped, "bytes"));
     } else {
-        match head::parse_size(val) {
-            Ok(n) => config.mode = HeadMode::Bytes(n),
-            Err(_) => {
-                eprintln!("head: invalid number of bytes: '{}'", val);
-                process::exit(1);
-            }
+        config.mode = HeadMode::Bytes(parse_count(val, "bytes"));
+    }
+}
+
+/// Parse a -c/-n count (without its leading '-'), exiting with GNU's
+/// diagnostic on failure.
+fn parse_count(val: &str, what: &str) -> u64 {
+    match head::parse_size(val) {
+        Ok(n) => n,
+        Err(e) => {
+            let detail = match e {
+                head::SizeError::Invalid => "",
+                head::SizeError::TooLarge => ": Value too large for defined data type",
+            };
+            eprintln!("head: invalid number of {}: '{}'{}", what, val, detail);
+            process::exit(1);
         }
     }
 }
@@ -231,34 +256,16 @@ fn main() {
     };
 
     let tool_name = "head";
-    let show_headers = if cli.quiet {
-        false
-    } else if cli.verbose {
-        true
-    } else {
-        files.len() > 1
-    };
+    let show_headers = cli.config.show_headers(files.len());
 
     let stdout = io::stdout();
     let mut out = BufWriter::with_capacity(256 * 1024, stdout.lock());
     let mut had_error = false;
-    let mut first = true;
+    let mut first_header = true;
 
     for filename in &files {
-        if show_headers {
-            if !first {
-                let _ = out.write_all(b"\n");
-            }
-            let display_name = if filename == "-" {
-                "standard input"
-            } else {
-                filename.as_str()
-            };
-            let _ = writeln!(out, "==> {} <==", display_name);
-        }
-        first = false;
-
-        match head::head_file(filename, &cli.config, &mut out, tool_name) {
+        let header = show_headers.then_some(&mut first_header);
+        match head::head_file(filename, &cli.config, &mut out, tool_name, header) {
             Ok(true) => {}
             Ok(false) => had_error = true,
             Err(e) => {
@@ -518,4 +525,204 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(output.stdout, b"no newline");
     }
+
+    #[test]
+    fn test_head_verbose_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one\n").unwrap();
+        let output = cmd().arg("-v").arg(&file).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("==> {} <==\none\n", file.display())
+        );
+    }
+
+    #[test]
+    fn test_head_last_of_quiet_verbose_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one\n").unwrap();
+        let output = cmd().args(["-v", "-q"]).arg(&file).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "one\n");
+        let output = cmd().args(["-q", "-v"]).arg(&file).output().unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("==> "));
+    }
+
+    #[test]
+    fn test_head_headers_stdin_and_separator() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "hello\n").unwrap();
+        let missing = dir.path().join("missing");
+        let mut child = cmd()
+            .args(["-c", "3", "-"])
+            .arg(&missing)
+            .arg(&file)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"xyz\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        // No header for the unreadable file; blank line only between sections
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!(
+                "==> standard input <==\nxyz\n==> {} <==\nhel",
+                file.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_head_size_suffixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data");
+        std::fs::write(&file, vec![b'x'; 3000]).unwrap();
+        for (size, expected) in [("1KiB", 1024), ("1kB", 1000), ("2KB", 2000), ("1K", 1024)] {
+            let output = cmd().args(["-c", size]).arg(&file).output().unwrap();
+            assert!(output.status.success());
+            assert_eq!(output.stdout.len(), expected, "-c {}", size);
+        }
+        let lines: String = (1..=1100).map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&file, lines).unwrap();
+        let output = cmd().args(["-n", "1K"]).arg(&file).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).lines().count(),
+            1024
+        );
+    }
+
+    #[test]
+    fn test_head_invalid_size_suffix() {
+        let output = cmd().args(["-c", "1KiBx"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "head: invalid number of bytes: '1KiBx'\n"
+        );
+        let output = cmd().args(["-n", "-16E"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "head: invalid number of lines: '16E': Value too large for defined data type\n"
+        );
+    }
+
+    #[test]
+    fn test_head_long_options_all_but_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("z.txt");
+        std::fs::write(&file, "1\x002\x003\x004\x00").unwrap();
+        let output = cmd()
+            .args(["-z", "--lines=-2"])
+            .arg(&file)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"1\x002\x00");
+        // Abbreviated long options, with the value attached or separate.
+        let output = cmd()
+            .args(["--zero", "--lin", "-3"])
+            .arg(&file)
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"1\x00");
+        let output = cmd().arg("--b=-4").arg(&file).output().unwrap();
+        assert_eq!(output.stdout, b"1\x002\x00");
+        // A pipe goes through the all-but-last buffering instead of the file size.
+        let output = cmd()
+            .args(["-z", "--lines=-2"])
+            .stdin(std::fs::File::open(&file).unwrap())
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"1\x002\x00");
+    }
+
+    #[test]
+    fn test_head_long_option_errors() {
+        for (args, msg) in [
+            (
+                &["--v"][..],
+                "head: option '--v' is ambiguous; possibilities: '--verbose' '--version'\n",
+            ),
+            (
+                &["--ver=2"],
+                "head: option '--ver=2' is ambiguous; possibilities: '--verbose' '--version'\n",
+            ),
+            (
+                &["--quiet=x"],
+                "head: option '--quiet' doesn't allow an argument\n",
+            ),
+            (&["--lin"], "head: option '--lines' requires an argument\n"),
+            (&["--nope=1"], "head: unrecognized option '--nope=1'\n"),
+        ] {
+            let output = cmd().args(args).output().unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                format!("{}Try 'head --help' for more information.\n", msg)
+            );
+        }
+    }
+
+    #[test]
+    fn test_head_zero_terminated_headers() {
+        // Records stay NUL-terminated, but like GNU the headers and the
+        // separator between sections remain newline text.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), b"a1\0a2\0").unwrap();
+        std::fs::write(dir.path().join("b"), b"b1\0b2").unwrap();
+        let output = cmd()
+            .args(["-z", "-n", "1", "a", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"==> a <==\na1\0\n==> b <==\nb1\0");
+        let output = cmd()
+            .args(["-zv", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"==> b <==\nb1\0b2");
+        let output = cmd()
+            .args(["-zq", "a", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"a1\0a2\0b1\0b2");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_head_unreadable_operands() {
+        // A directory opens, so it gets a header and a read error; a
+        // missing file fails to open and gets no header.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("f"), "x\n").unwrap();
+        for args in [
+            &["sub", "missing", "f"][..],
+            &["-c", "1", "sub", "missing", "f"],
+        ] {
+            let output = cmd().args(args).current_dir(dir.path()).output().unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "head: error reading 'sub': Is a directory\n\
+                 head: cannot open 'missing' for reading: No such file or directory\n"
+            );
+            assert!(
+                String::from_utf8_lossy(&output.stdout).starts_with("==> sub <==\n\n==> f <==\n")
+            );
+        }
+    }
 }
@@ -18,7 +18,8 @@ use std::process;
 
 #[cfg(unix)]
 use coreutils_rs::install::{
-    BackupMode, InstallConfig, install_directories, install_file, parse_backup_mode, parse_mode,
+    BackupMode, InstallConfig, has_extra_mode_bits, install_directory, install_file,
+    parse_backup_mode, parse_mode, validate_ownership,
 };
 
 #[cfg(unix)]
@@ -299,6 +300,18 @@ fn main() {
         process::exit(1);
     }
 
+    if config.compare && has_extra_mode_bits(config.mode) {
+        eprintln!(
+            "{}: the --compare (-C) option is ignored when you specify a mode with non-permission bits",
+            TOOL_NAME
+        );
+    }
+
+    if let Err(e) = validate_ownership(&config) {
+        eprintln!("{}: {}", TOOL_NAME, e);
+        process::exit(1);
+    }
+
     // -d mode: create directories
     if config.directory_mode {
         if operands.is_empty() {
@@ -306,12 +319,14 @@ fn main() {
             eprintln!("Try '{} --help' for more information.", TOOL_NAME);
             process::exit(1);
         }
-        let dirs: Vec<&Path> = operands.iter().map(|s| Path::new(s.as_str())).collect();
-        if let Err(e) = install_directories(&dirs, &config) {
-            eprintln!("{}: {}", TOOL_NAME, coreutils_rs::common::io_error_msg(&e));
-            process::exit(1);
+        let mut exit_code = 0;
+        for dir in &operands {
+            if let Err(e) = install_directory(Path::new(dir), &config) {
+                eprintln!("{}: {}", TOOL_NAME, e);
+                exit_code = 1;
+            }
         }
-        return;
+        process::exit(exit_code);
     }
 
     if operands.is_empty() {
@@ -679,6 +694,58 @@ mod tests {
         assert_eq!(fs::read_to_string(&dst).unwrap(), "new content");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_install_compare_mode_and_backup() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::{Duration, SystemTime};
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dst = dir.path().join("dst");
+        let link = dir.path().join("link");
+        fs::write(&src, "same").unwrap();
+        std::os::unix::fs::symlink(&src, &link).unwrap();
+        let install = |args: &[&str], from: &std::path::Path| {
+            let output = cmd().args(args).arg(from).arg(&dst).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stderr).into_owned()
+        };
+        // Backdate the destination, so a copy shows up as a new mtime.
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let backdate = || {
+            let f = fs::File::options().write(true).open(&dst).unwrap();
+            f.set_modified(old).unwrap();
+        };
+        let copied = || fs::metadata(&dst).unwrap().modified().unwrap() != old;
+
+        // An up-to-date destination is neither replaced nor backed up.
+        install(&["-m", "644"], &src);
+        backdate();
+        install(&["-C", "-b", "-m", "644"], &src);
+        assert!(!copied());
+        assert!(!dir.path().join("dst~").exists());
+
+        // A different mode means the file is installed again.
+        install(&["-C", "-b", "-m", "600"], &src);
+        assert!(copied());
+        let mode = fs::metadata(&dst).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o600);
+        assert!(dir.path().join("dst~").exists());
+
+        // Set-ID and sticky bits disable the comparison, with a warning.
+        install(&["-m", "1600"], &src);
+        backdate();
+        let stderr = install(&["-C", "-m", "1600"], &src);
+        assert!(stderr.contains("the --compare (-C) option is ignored"));
+        assert!(copied());
+
+        // A symlink source is always copied.
+        install(&["-m", "644"], &src);
+        backdate();
+        install(&["-C", "-m", "644"], &link);
+        assert!(copied());
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_install_verbose() {
@@ -864,4 +931,71 @@ mod tests {
         );
         assert!(nested.is_dir());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_directory_existing_gets_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("existing");
+        fs::create_dir(&existing).unwrap();
+        let nested = dir.path().join("p").join("q");
+
+        let output = cmd()
+            .args([
+                "-d",
+                "-m",
+                "0700",
+                existing.to_str().unwrap(),
+                nested.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let mode = |p: &std::path::Path| fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&existing), 0o700);
+        assert_eq!(mode(&nested), 0o700);
+        // Parents keep default permissions.
+        assert_ne!(mode(&dir.path().join("p")), 0o700);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_directory_partial_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        let blocked = file.join("sub");
+        let ok = dir.path().join("ok");
+
+        let output = cmd()
+            .args(["-d", blocked.to_str().unwrap(), ok.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            format!(
+                "install: cannot create directory '{}': Not a directory\n",
+                file.display()
+            )
+        );
+        assert!(ok.is_dir(), "later operands are still processed");
+
+        let output = cmd()
+            .args([
+                "-d",
+                "-o",
+                "no_such_user_xyz",
+                ok.join("x").to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "install: invalid user 'no_such_user_xyz'\n"
+        );
+        assert!(!ok.join("x").exists());
+    }
 }
@@ -25,6 +25,52 @@ fn parse_field_num(s: &str, flag: &str) -> usize {
     }
 }
 
+/// Add the comma-separated FIELDs of a -1, -2 or -j argument to a file's
+/// join fields. Several fields make a composite key; a field already
+/// present is not added again, so repeating the same option is harmless.
+fn add_join_fields(fields: &mut Vec<usize>, list: &str, flag: &str) {
+    for part in list.split(',') {
+        let field = parse_field_num(part, flag);
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+}
+
+/// Parse the FILENUM of -a/-v. GNU reads it with xstrtol, so leading
+/// blanks, a sign and leading zeros are accepted.
+fn parse_file_num(s: &str) -> u8 {
+    match s
+        .trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '\x0b')
+        .parse::<i64>()
+    {
+        Ok(1) => 1,
+        Ok(2) => 2,
+        _ => {
+            eprintln!("join: invalid field number: '{}'", s);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a -t argument. As in GNU join, an empty string makes the whole
+/// line the join field (newline never occurs inside a line) and `\0`
+/// selects NUL.
+fn parse_separator(val: &[u8]) -> u8 {
+    match val {
+        [] => b'\n',
+        [b] => *b,
+        b"\\0" => b'\0',
+        _ => {
+            eprintln!(
+                "join: multi-character tab '{}'",
+                String::from_utf8_lossy(val)
+            );
+            process::exit(1);
+        }
+    }
+}
+
 fn parse_output_format(s: &str) -> Vec<OutputSpec> {
     let mut specs = Vec::new();
     for token in s.split([',', ' ']) {
@@ -68,6 +114,8 @@ fn parse_args() -> Cli {
         config: JoinConfig::default(),
         files: Vec::new(),
     };
+    let mut fields1: Vec<usize> = Vec::new();
+    let mut fields2: Vec<usize> = Vec::new();
 
     let mut args = std::env::args_os().skip(1);
     #[allow(clippy::while_let_on_iterator)]
@@ -119,13 +167,9 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     };
-                    match val.as_str() {
-                        "1" => cli.config.print_unpaired1 = true,
-                        "2" => cli.config.print_unpaired2 = true,
-                        _ => {
-                            eprintln!("join: invalid file number: '{}'", val);
-                            process::exit(1);
-                        }
+                    match parse_file_num(&val) {
+                        1 => cli.config.print_unpaired1 = true,
+                        _ => cli.config.print_unpaired2 = true,
                     }
                 }
                 b'v' => {
@@ -140,13 +184,9 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     };
-                    match val.as_str() {
-                        "1" => cli.config.only_unpaired1 = true,
-                        "2" => cli.config.only_unpaired2 = true,
-                        _ => {
-                            eprintln!("join: invalid file number: '{}'", val);
-                            process::exit(1);
-                        }
+                    match parse_file_num(&val) {
+                        1 => cli.config.only_unpaired1 = true,
+                        _ => cli.config.only_unpaired2 = true,
                     }
                 }
                 b'e' => {
@@ -178,9 +218,8 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     };
-                    let field = parse_field_num(&val, "-j");
-                    cli.config.field1 = field;
-                    cli.config.field2 = field;
+                    add_join_fields(&mut fields1, &val, "-j");
+                    add_join_fields(&mut fields2, &val, "-j");
                 }
                 b'o' => {
                     let val = if chars_bytes.len() > 1 {
@@ -206,21 +245,25 @@ fn parse_args() -> Cli {
                     }
                 }
                 b't' => {
-                    let val = if chars_bytes.len() > 1 {
-                        chars_bytes[1]
+                    let val: Vec<u8> = if chars_bytes.len() > 1 {
+                        chars_bytes[1..].to_vec()
                     } else {
-                        let next = args.next().unwrap_or_else(|| {
-                            eprintln!("join: option requires an argument -- 't'");
-                            process::exit(1);
-                        });
-                        let b = next.as_encoded_bytes();
-                        if b.is_empty() {
-                            eprintln!("join: empty separator");
-                            process::exit(1);
-                        }
-                        b[0]
+                        args.next()
+                            .unwrap_or_else(|| {
+                                eprintln!("join: option requires an argument -- 't'");
+                                process::exit(1);
+                            })
+                            .as_encoded_bytes()
+                            .to_vec()
                     };
-                    cli.config.separator = Some(val);
+                    let sep = parse_separator(&val);
+                    if let Some(prev) = cli.config.separator
+                        && prev != sep
+                    {
+                        eprintln!("join: incompatible tabs");
+                        process::exit(1);
+                    }
+                    cli.config.separator = Some(sep);
                 }
                 b'z' => {
                     cli.config.zero_terminated = true;
@@ -237,7 +280,7 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     };
-                    cli.config.field1 = parse_field_num(&val, "-1");
+                    add_join_fields(&mut fields1, &val, "-1");
                 }
                 b'2' => {
                     let val = if chars_bytes.len() > 1 {
@@ -251,7 +294,7 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     };
-                    cli.config.field2 = parse_field_num(&val, "-2");
+                    add_join_fields(&mut fields2, &val, "-2");
                 }
                 _ => {
                     eprintln!(
@@ -267,6 +310,21 @@ fn parse_args() -> Cli {
         }
     }
 
+    if !fields1.is_empty() {
+        cli.config.fields1 = fields1;
+    }
+    if !fields2.is_empty() {
+        cli.config.fields2 = fields2;
+    }
+    if cli.config.fields1.len() != cli.config.fields2.len() {
+        eprintln!(
+            "join: files 1 and 2 need the same number of join fields, not {} and {}",
+            cli.config.fields1.len(),
+            cli.config.fields2.len()
+        );
+        process::exit(1);
+    }
+
     cli
 }
 
@@ -295,7 +353,9 @@ fn print_help() {
          \x20     --help        display this help and exit\n\
          \x20     --version     output version information and exit\n\n\
          Unless -t CHAR is given, leading blanks separate fields and are ignored\n\
-         in comparison.  Otherwise, fields are separated by CHAR.\n"
+         in comparison.  Otherwise, fields are separated by CHAR.\n\n\
+         FIELD may be a comma-separated list such as '1,2' to join on several\n\
+         fields at once; -1 and -2 must then list the same number of fields.\n"
     );
 }
 
@@ -375,7 +435,10 @@ fn main() {
                 process::exit(1);
             }
             if had_order_error {
-                eprintln!("{}: input is not in sorted order", tool_name);
+                // --check-order already stopped at the offending line.
+                if cli.config.order_check == OrderCheck::Default {
+                    eprintln!("{}: input is not in sorted order", tool_name);
+                }
                 process::exit(1);
             }
         }
@@ -533,4 +596,246 @@ mod tests {
         let lines: Vec<&str> = stdout.lines().collect();
         assert_eq!(lines.len(), 2); // Only keys 2 and 3 match
     }
+
+    #[test]
+    fn test_join_ignore_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "Apple 1\nbanana 2\n").unwrap();
+        std::fs::write(&f2, "apple x\nBANANA y\n").unwrap();
+        let output = cmd()
+            .args([f1.to_str().unwrap(), f2.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).trim().is_empty());
+        let output = cmd()
+            .args(["-i", f1.to_str().unwrap(), f2.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "Apple 1 x\nbanana 2 y\n"
+        );
+    }
+
+    #[test]
+    fn test_join_ignore_case_order_check() {
+        // Sorted by `sort -f` ('_' sorts after letters when folded to upper case)
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a 1\nB 2\n_ 3\n").unwrap();
+        std::fs::write(&f2, "A x\nb y\n_ z\n").unwrap();
+        let output = cmd()
+            .args([
+                "-i",
+                "--check-order",
+                f1.to_str().unwrap(),
+                f2.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a 1 x\nB 2 y\n_ 3 z\n"
+        );
+    }
+
+    #[test]
+    fn test_join_empty_join_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, ",,one\nk,,two\n").unwrap();
+        std::fs::write(&f2, ",x\nk,y\n").unwrap();
+        let output = cmd()
+            .args([
+                "-t",
+                ",",
+                "-e",
+                "E",
+                f1.to_str().unwrap(),
+                f2.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "E,E,one,x\nk,E,two,y\n"
+        );
+    }
+
+    #[test]
+    fn test_join_empty_separator_whole_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a b\nc d\n").unwrap();
+        std::fs::write(&f2, "a b\nc e\n").unwrap();
+        let output = cmd()
+            .args(["-t", "", f1.to_str().unwrap(), f2.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a b\n");
+    }
+
+    #[test]
+    fn test_join_multi_character_tab() {
+        let output = cmd().args(["-t", "::", "a", "b"]).output().unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("multi-character tab"));
+    }
+
+    #[test]
+    fn test_join_check_order_stops_at_unsorted_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        std::fs::write(&f1, "a 1\nc 2\nb 3\n").unwrap();
+        let f1 = f1.to_str().unwrap();
+        let output = cmd().args(["--check-order", f1, f1]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a 1 1\n");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("a.txt:3: is not sorted: b 3"));
+        assert!(!stderr.contains("input is not in sorted order"));
+    }
+
+    #[test]
+    fn test_join_default_order_check_after_unpairable() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a 1\nc 2\nb 3\n").unwrap();
+        std::fs::write(&f2, "b q\nd r\n").unwrap();
+        // Identical unsorted inputs pair up fully and are not reported.
+        let f1 = f1.to_str().unwrap();
+        let output = cmd().args([f1, f1]).output().unwrap();
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty());
+        // Once a line fails to pair, the rest is checked and reported once.
+        let output = cmd()
+            .args(["-a1", f1, f2.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a 1\nc 2\nb 3\n");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("a.txt:3: is not sorted: b 3"));
+        assert!(stderr.ends_with("input is not in sorted order\n"));
+    }
+
+    #[test]
+    fn test_join_full_outer_and_only_unpaired() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a 1\nb 2\nd 4\nd 5\nf 6\n").unwrap();
+        std::fs::write(&f2, "b x\nc y\nd z\ng v\n").unwrap();
+        let (f1, f2) = (f1.to_str().unwrap(), f2.to_str().unwrap());
+        let run = |args: &[&str]| {
+            let output = cmd().args(args).args([f1, f2]).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        // Unpaired lines from either file come out in sorted position.
+        assert_eq!(
+            run(&["-a1", "-a", "2"]),
+            "a 1\nb 2 x\nc y\nd 4 z\nd 5 z\nf 6\ng v\n"
+        );
+        assert_eq!(
+            run(&["-a1", "-a2", "-o", "0,1.2,2.2", "-e", "-"]),
+            "a 1 -\nb 2 x\nc - y\nd 4 z\nd 5 z\nf 6 -\ng - v\n"
+        );
+        assert_eq!(run(&["-v1"]), "a 1\nf 6\n");
+        assert_eq!(run(&["-v2", "-o", "auto", "-e", "?"]), "c ? y\ng ? v\n");
+        // -v suppresses paired lines even alongside -a.
+        assert_eq!(run(&["-v", "1", "-v2"]), "a 1\nc y\nf 6\ng v\n");
+        assert_eq!(run(&["-a2", "-v1"]), "a 1\nc y\nf 6\ng v\n");
+        // FILENUM is read like a C integer.
+        assert_eq!(run(&["-v", "+01"]), "a 1\nf 6\n");
+    }
+
+    #[test]
+    fn test_join_invalid_file_number() {
+        for (opt, val) in [("-a", "3"), ("-v", "0"), ("-a", "1x"), ("-v", "")] {
+            let output = cmd()
+                .args([opt, val, "/dev/null", "/dev/null"])
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                format!("join: invalid field number: '{}'\n", val)
+            );
+        }
+    }
+
+    #[test]
+    fn test_join_composite_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        std::fs::write(&f1, "a x 1\na y 2\nb x 3\n").unwrap();
+        std::fs::write(&f2, "p a x\nq a z\nr b x\n").unwrap();
+        let (f1, f2) = (f1.to_str().unwrap(), f2.to_str().unwrap());
+        let run = |args: &[&str]| {
+            let output = cmd().args(args).args([f1, f2]).output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            String::from_utf8(output.stdout).unwrap()
+        };
+        assert_eq!(run(&["-1", "1,2", "-2", "2,3"]), "a x 1 p\nb x 3 r\n");
+        assert_eq!(
+            run(&["-1", "1", "-1", "2", "-2", "2,3", "-a1", "-a", "2"]),
+            "a x 1 p\na y 2\na z q\nb x 3 r\n"
+        );
+        // -o 0 prints every key field; FILENUM.FIELD may name one of them.
+        assert_eq!(
+            run(&["-1", "1,2", "-2", "2,3", "-o", "0,2.1,1.2"]),
+            "a x p x\nb x r x\n"
+        );
+        assert_eq!(
+            run(&["-1", "1,2", "-2", "2,3", "-o", "auto", "-e", "-", "-v1"]),
+            "a y 2 -\n"
+        );
+
+        // Key fields compare one by one, so "ab","c" does not match "a","bc",
+        // and the sort order check uses the whole key.
+        let f3 = dir.path().join("c.txt");
+        let f4 = dir.path().join("d.txt");
+        std::fs::write(&f3, "ab,c,1\n").unwrap();
+        std::fs::write(&f4, "a,bc,2\nab,c,3\n").unwrap();
+        let output = cmd()
+            .args(["-t,", "-j", "1,2", "--check-order", "-a2"])
+            .args([&f3, &f4])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a,bc,2\nab,c,1,3\n"
+        );
+        std::fs::write(&f3, "a y 1\na x 2\n").unwrap();
+        let output = cmd()
+            .args(["--check-order", "-j1,2"])
+            .args([&f3, &f4])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            format!("join: {}:2: is not sorted: a x 2\n", f3.display())
+        );
+
+        let output = cmd().args(["-1", "1,2", f1, f2]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "join: files 1 and 2 need the same number of join fields, not 2 and 1\n"
+        );
+    }
 }
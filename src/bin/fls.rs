@@ -12,7 +12,7 @@ use coreutils_rs::common::reset_sigpipe;
 #[cfg(unix)]
 use coreutils_rs::ls::{
     ClassifyMode, ColorMode, HyperlinkMode, IndicatorStyle, LsConfig, OutputFormat, QuotingStyle,
-    SortBy, TimeField, TimeStyle, atty_stdout, ls_main,
+    SortBy, TimeField, TimeStyle, atty_stdout, ls_main, parse_color_mode,
 };
 
 #[cfg(unix)]
@@ -24,20 +24,26 @@ fn print_help() {
          \x20 -a, --all                  do not ignore entries starting with .\n\
          \x20 -A, --almost-all           do not list implied . and ..\n\
          \x20 -b, --escape               print C-style escapes for nongraphic characters\n\
+         \x20     --block-size=SIZE      with -l, scale sizes by SIZE when printing them;\n\
+         \x20                              e.g., '--block-size=M'\n\
          \x20 -B, --ignore-backups       do not list implied entries ending with ~\n\
          \x20 -c                         sort by/show ctime\n\
          \x20 -C                         list entries by columns\n\
          \x20     --color[=WHEN]         colorize output; WHEN: always, auto, never\n\
          \x20 -d, --directory            list directories themselves, not their contents\n\
+         \x20 -D, --dired                generate output designed for Emacs' dired mode\n\
          \x20 -F, --classify[=WHEN]      append indicator (one of */=>@|) to entries\n\
          \x20 -g                         like -l, but do not list owner\n\
          \x20 -G, --no-group             in -l listing, don't print group names\n\
          \x20     --group-directories-first  group directories before files\n\
+         \x20     --format=WORD          across -x, commas -m, horizontal -x, long -l,\n\
+         \x20                              single-column -1, verbose -l, vertical -C\n\
          \x20     --full-time            like -l --time-style=full-iso\n\
          \x20 -h, --human-readable       with -l, print sizes like 1K 234M 2G etc.\n\
          \x20 -i, --inode                print the index number of each file\n\
          \x20 -I, --ignore=PATTERN       do not list entries matching PATTERN\n\
-         \x20 -k, --kibibytes            default to 1024-byte blocks\n\
+         \x20 -k, --kibibytes            default to 1024-byte blocks for file system usage;\n\
+         \x20                              used only with -s and per directory totals\n\
          \x20 -l                         use a long listing format\n\
          \x20 -L, --dereference          show info for link references\n\
          \x20 -m                         fill width with a comma separated list of entries\n\
@@ -80,12 +86,7 @@ fn get_terminal_width() -> Option<usize> {
     if ret == 0 && ws.ws_col > 0 {
         return Some(ws.ws_col as usize);
     }
-    if let Ok(val) = std::env::var("COLUMNS")
-        && let Ok(w) = val.parse::<usize>()
-    {
-        return Some(w);
-    }
-    None
+    coreutils_rs::ls::columns_env_width("ls")
 }
 
 #[cfg(unix)]
@@ -138,15 +139,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
         config.color = ColorMode::Never;
     }
 
-    // Detect terminal width
-    if is_tty
-        && let Some(w) = get_terminal_width()
-        && w > 0
-    {
-        config.width = w;
-    }
-
-    let mut explicit_format = false;
+    let mut width_set = false;
 
     let mut args = std::env::args_os().skip(1);
     #[allow(clippy::while_let_on_iterator)]
@@ -182,6 +175,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                 "escape" => config.quoting_style = QuotingStyle::Escape,
                 "ignore-backups" => config.ignore_backups = true,
                 "directory" => config.directory = true,
+                "dired" => config.dired = true,
                 "classify" => {
                     let mode = eq_val.unwrap_or("always");
                     match mode {
@@ -206,8 +200,8 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                 }
                 "no-group" => config.show_group = false,
                 "group-directories-first" => config.group_directories_first = true,
-                "human-readable" => config.human_readable = true,
-                "si" => config.si = true,
+                "human-readable" => coreutils_rs::ls::set_human_readable(&mut config, false),
+                "si" => coreutils_rs::ls::set_human_readable(&mut config, true),
                 "inode" => config.show_inode = true,
                 "ignore" => {
                     let val = eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
@@ -226,9 +220,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                 "numeric-uid-gid" => {
                     config.numeric_ids = true;
                     config.long_format = true;
-                    if !explicit_format {
-                        config.format = OutputFormat::Long;
-                    }
+                    config.format = OutputFormat::Long;
                 }
                 "literal" => {
                     config.literal = true;
@@ -241,15 +233,13 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                 "size" => config.show_size = true,
                 "context" => config.context = true,
                 "color" => {
-                    let val = eq_val.unwrap_or("always");
-                    config.color = match val {
-                        "always" | "yes" | "force" => ColorMode::Always,
-                        "auto" | "tty" | "if-tty" => ColorMode::Auto,
-                        "never" | "no" | "none" => ColorMode::Never,
-                        _ => {
-                            eprintln!("ls: invalid argument '{}' for '--color'", val);
+                    config.color = match eq_val {
+                        None => ColorMode::Always,
+                        Some(val) => parse_color_mode(val).unwrap_or_else(|e| {
+                            eprintln!("ls: {}", e);
+                            eprintln!("Try 'ls --help' for more information.");
                             process::exit(1);
-                        }
+                        }),
                     };
                 }
                 "sort" => {
@@ -318,10 +308,27 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                         }
                     };
                 }
+                "format" => {
+                    let val = eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
+                        args.next()
+                            .unwrap_or_else(|| {
+                                eprintln!("ls: option '--format' requires an argument");
+                                eprintln!("Try 'ls --help' for more information.");
+                                process::exit(2);
+                            })
+                            .to_string_lossy()
+                            .into_owned()
+                    });
+                    config.format = coreutils_rs::ls::parse_format(&val).unwrap_or_else(|e| {
+                        eprintln!("ls: {}", e);
+                        eprintln!("Try 'ls --help' for more information.");
+                        process::exit(1);
+                    });
+                    config.long_format = config.format == OutputFormat::Long;
+                }
                 "full-time" => {
                     config.long_format = true;
                     config.format = OutputFormat::Long;
-                    explicit_format = true;
                     config.time_style = TimeStyle::FullIso;
                 }
                 "tabsize" => {
@@ -347,6 +354,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                             .into_owned()
                     });
                     config.width = parse_num(&val).unwrap_or(80);
+                    width_set = true;
                 }
                 "hyperlink" => {
                     let val = eq_val.unwrap_or("always");
@@ -411,7 +419,6 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                     config.hide_control_chars = false;
                     if config.format != OutputFormat::Long {
                         config.format = OutputFormat::SingleColumn;
-                        explicit_format = true;
                     }
                     config.color = ColorMode::Never;
                 }
@@ -425,15 +432,9 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                             .to_string_lossy()
                             .into_owned()
                     });
-                    match coreutils_rs::ls::parse_block_size(&val) {
-                        Ok((bs, suffix)) => {
-                            config.block_size = Some(bs);
-                            config.block_size_suffix = suffix;
-                        }
-                        Err(msg) => {
-                            eprintln!("ls: {}", msg);
-                            process::exit(2);
-                        }
+                    if let Err(msg) = coreutils_rs::ls::apply_block_size(&mut config, &val) {
+                        eprintln!("ls: {}", msg);
+                        process::exit(2);
                     }
                 }
                 _ => {
@@ -453,9 +454,10 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                     b'c' => config.time_field = TimeField::Ctime,
                     b'C' => {
                         config.format = OutputFormat::Columns;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'd' => config.directory = true,
+                    b'D' => config.dired = true,
                     b'f' => {
                         config.all = true;
                         config.sort_by = SortBy::None;
@@ -467,30 +469,25 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                     b'g' => {
                         config.long_format = true;
                         config.show_owner = false;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'G' => config.show_group = false,
-                    b'h' => config.human_readable = true,
+                    b'h' => coreutils_rs::ls::set_human_readable(&mut config, false),
                     b'i' => config.show_inode = true,
                     b'k' => config.kibibytes = true,
                     b'l' => {
                         config.long_format = true;
                         config.format = OutputFormat::Long;
-                        explicit_format = true;
                     }
                     b'L' => config.dereference = true,
                     b'm' => {
                         config.format = OutputFormat::Comma;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'n' => {
                         config.long_format = true;
                         config.numeric_ids = true;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'N' => {
                         config.literal = true;
@@ -499,9 +496,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                     b'o' => {
                         config.long_format = true;
                         config.show_group = false;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'p' => config.indicator_style = IndicatorStyle::Slash,
                     b'q' => config.hide_control_chars = true,
@@ -516,14 +511,15 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                     b'v' => config.sort_by = SortBy::Version,
                     b'x' => {
                         config.format = OutputFormat::Across;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'X' => config.sort_by = SortBy::Extension,
                     b'Z' => config.context = true,
-                    b'1' => {
+                    // -1 has no effect after -l, as in GNU ls.
+                    b'1' if config.format != OutputFormat::Long => {
                         config.format = OutputFormat::SingleColumn;
-                        explicit_format = true;
                     }
+                    b'1' => {}
                     b'I' => {
                         let val = take_short_value(bytes, i + 1, &mut args, "I");
                         config.ignore_patterns.push(val);
@@ -535,6 +531,7 @@ fn parse_args() -> (LsConfig, Vec<String>) {
                             eprintln!("ls: invalid line width: '{}'", val);
                             process::exit(2);
                         });
+                        width_set = true;
                         break;
                     }
                     b'T' => {
@@ -558,6 +555,17 @@ fn parse_args() -> (LsConfig, Vec<String>) {
         }
     }
 
+    // The terminal (or COLUMNS) width only matters to the layouts that fill lines.
+    if !width_set
+        && coreutils_rs::ls::format_uses_width(config.format)
+        && let Some(w) = get_terminal_width()
+    {
+        config.width = w;
+    }
+
+    coreutils_rs::ls::apply_time_sort_default(&mut config);
+    coreutils_rs::ls::apply_block_size_env(&mut config);
+
     (config, paths)
 }
 
@@ -581,8 +589,8 @@ fn main() {
     };
 
     match ls_main(&file_args, &config) {
-        Ok(true) => {}
-        Ok(false) => process::exit(1),
+        Ok(0) => {}
+        Ok(status) => process::exit(status),
         Err(e) => {
             if e.kind() == std::io::ErrorKind::BrokenPipe {
                 // Exit with 141 (128 + SIGPIPE) to match GNU coreutils behavior
@@ -793,4 +801,576 @@ mod tests {
         // -i should show inode numbers (digits before filename)
         assert!(stdout.chars().any(|c| c.is_ascii_digit()));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_inode_and_size_with_commas() {
+        use std::os::unix::fs::MetadataExt;
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), "x").unwrap();
+        std::fs::write(dir.path().join("b"), vec![0u8; 100_000]).unwrap();
+        let ino = |name: &str| std::fs::metadata(dir.path().join(name)).unwrap().ino();
+        let output = cmd().args(["-1is"]).arg(dir.path()).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let rows: Vec<Vec<&str>> = stdout
+            .lines()
+            .skip(1)
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows[0][0], ino("a").to_string());
+        assert_eq!(rows[1][0], ino("b").to_string());
+        // -m keeps both fields but drops the column padding.
+        let output = cmd()
+            .args(["-mis", "-w0"])
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        let expected: Vec<String> = rows.iter().map(|r| r.join(" ")).collect();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!(
+                "{}\n{}\n",
+                stdout.lines().next().unwrap(),
+                expected.join(", ")
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_human_readable_alignment() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("b"), vec![0u8; 2 * 1024 * 1024 + 1]).unwrap();
+        let output = cmd()
+            .args(["-lh", "--time-style=long-iso", dir.path().to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().skip(1).collect();
+        assert_eq!(lines.len(), 2);
+        // Sizes round up and are right-aligned to a common width
+        assert!(lines[0].contains("  100 "), "{}", lines[0]);
+        assert!(lines[1].contains(" 2.1M "), "{}", lines[1]);
+        assert_eq!(lines[0].len(), lines[1].len());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_block_size() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("f"), vec![0u8; 1024 * 1024 + 1]).unwrap();
+        let output = cmd()
+            .args([
+                "-l",
+                "--block-size=M",
+                "--time-style=long-iso",
+                dir.path().to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(" 2M "), "{}", stdout);
+
+        // --si uses powers of 1000 and a lowercase k; the last option wins
+        let output = cmd()
+            .args([
+                "-l",
+                "--block-size=M",
+                "--si",
+                "--time-style=long-iso",
+                dir.path().to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(" 1.1M "), "{}", stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_block_size_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("f"), vec![0u8; 3000]).unwrap();
+        let output = cmd()
+            .args(["-l", "--time-style=long-iso", dir.path().to_str().unwrap()])
+            .env("LS_BLOCK_SIZE", "K")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(" 3K "), "{}", stdout);
+
+        // An option overrides the environment
+        let output = cmd()
+            .args(["-l", "--block-size=1", "--time-style=long-iso"])
+            .arg(dir.path())
+            .env("LS_BLOCK_SIZE", "K")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(" 3000 "), "{}", stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_size_with_block_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("f");
+        std::fs::write(&f, vec![1u8; 8192]).unwrap();
+        let output = cmd()
+            .args(["-s", "--block-size=512", f.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let blocks: u64 = stdout.split_whitespace().next().unwrap().parse().unwrap();
+        let kib: u64 =
+            String::from_utf8_lossy(&cmd().args(["-s"]).arg(&f).output().unwrap().stdout)
+                .split_whitespace()
+                .next()
+                .unwrap()
+                .parse()
+                .unwrap();
+        assert_eq!(blocks, kib * 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_hyperlink() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a b"), "").unwrap();
+        let output = cmd()
+            .args(["--hyperlink=always", "--quoting-style=shell"])
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let abs = std::fs::canonicalize(dir.path()).unwrap();
+        // Quotes stay outside the link and the URL is percent-encoded
+        let url_tail = format!("{}/a%20b\x1b\\a b\x1b]8;;\x1b\\'\n", abs.display());
+        assert!(stdout.starts_with("'\x1b]8;;file://"), "{:?}", stdout);
+        assert!(stdout.ends_with(&url_tail), "{:?}", stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_hyperlink_auto_not_tty() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("f"), "").unwrap();
+        let output = cmd()
+            .arg("--hyperlink=auto")
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "f\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_hyperlink_inside_color() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("d")).unwrap();
+        let output = cmd()
+            .args(["--hyperlink", "--color=always"])
+            .arg(dir.path())
+            .env("LS_COLORS", "di=01;34")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.starts_with("\x1b[0m\x1b[01;34m\x1b]8;;file://"),
+            "{:?}",
+            stdout
+        );
+        assert!(
+            stdout.ends_with("/d\x1b\\d\x1b]8;;\x1b\\\x1b[0m\n"),
+            "{:?}",
+            stdout
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_colors_types_suffixes_and_normal() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("d")).unwrap();
+        for name in ["a.c", "b.C", "n.TXT", "plain", "x"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        std::fs::set_permissions(dir.path().join("x"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        // Suffixes differing only in case with different colours match exactly,
+        // others ignore case; an empty `ex` leaves executables uncoloured.
+        let output = cmd()
+            .args(["--color=always", "-1"])
+            .arg(dir.path())
+            .env("LS_COLORS", "ex=:*.c=31:*.C=32:*.txt=\\e[4:no=07")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "\x1b[0m\x1b[07m\x1b[m\x1b[31ma.c\x1b[0m\n\
+             \x1b[07m\x1b[m\x1b[32mb.C\x1b[0m\n\
+             \x1b[07m\x1b[m\x1b[01;34md\x1b[0m\n\
+             \x1b[07m\x1b[m\x1b[\x1b[4mn.TXT\x1b[0m\n\
+             \x1b[07mplain\x1b[0m\n\
+             \x1b[07mx\x1b[0m\n"
+        );
+
+        // Custom lc/rc are closed again at the end of the listing.
+        let output = cmd()
+            .args(["--color=always", "-d"])
+            .arg(dir.path().join("d"))
+            .env("LS_COLORS", "lc=L:rc=R:ec=E")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("EL01;34R"), "{:?}", stdout);
+        assert!(stdout.ends_with("/dE\nLR"), "{:?}", stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_colors_symlinks_and_errors() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("x"), "").unwrap();
+        std::fs::set_permissions(dir.path().join("x"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        std::os::unix::fs::symlink("x", dir.path().join("l")).unwrap();
+        std::os::unix::fs::symlink("missing", dir.path().join("br")).unwrap();
+        let output = cmd()
+            .args(["--color=always", "-l"])
+            .arg(dir.path())
+            .env("LS_COLORS", "ln=target:or=41:mi=05")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains(" \x1b[0m\x1b[41mbr\x1b[0m -> \x1b[05mmissing\x1b[0m\n"),
+            "{:?}",
+            stdout
+        );
+        assert!(
+            stdout.contains(" \x1b[01;32ml\x1b[0m -> \x1b[01;32mx\x1b[0m\n"),
+            "{:?}",
+            stdout
+        );
+
+        let output = cmd()
+            .args(["--color=always", "-1"])
+            .arg(dir.path())
+            .env("LS_COLORS", "di=01;34:zz=1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "br\nl\nx\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "ls: unrecognized prefix: 'zz'\n\
+             ls: unparsable value for LS_COLORS environment variable\n"
+        );
+
+        let output = cmd().args(["--color=sometimes"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr).starts_with(
+                "ls: invalid argument 'sometimes' for '--color'\nValid arguments are:\n"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_ls_recursive_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("t/b/c")).unwrap();
+        std::fs::create_dir(dir.path().join("t/a")).unwrap();
+        std::fs::write(dir.path().join("t/a/x"), "").unwrap();
+        std::fs::write(dir.path().join("t/f"), "").unwrap();
+        let output = cmd()
+            .args(["-R", "t"])
+            .current_dir(dir.path())
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "t:\na\nb\nf\n\nt/a:\nx\n\nt/b:\nc\n\nt/b/c:\n"
+        );
+    }
+
+    #[test]
+    fn test_ls_recursive_all_shows_dot_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("t/s")).unwrap();
+        let output = cmd()
+            .args(["-Ra", "t"])
+            .current_dir(dir.path())
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "t:\n.\n..\ns\n\nt/s:\n.\n..\n"
+        );
+    }
+
+    #[test]
+    fn test_ls_recursive_unsorted_keeps_operand_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("b")).unwrap();
+        std::fs::create_dir(dir.path().join("a")).unwrap();
+        let output = cmd()
+            .args(["-RU", "b", "a"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "b:\n\na:\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_recursive_dereference_symlink_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("t/a")).unwrap();
+        std::os::unix::fs::symlink("..", dir.path().join("t/a/up")).unwrap();
+        let output = cmd()
+            .args(["-RL", "t"])
+            .current_dir(dir.path())
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        // A loop is a serious error, as in GNU.
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "t:\na\n\nt/a:\nup\n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "ls: t/a/up: not listing already-listed directory\n"
+        );
+
+        // The listing so far is flushed before the diagnostic.
+        let output = Command::new("sh")
+            .args(["-c", "\"$0\" -RL t 2>&1"])
+            .arg(cmd().get_program())
+            .current_dir(dir.path())
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "t:\na\n\nt/a:\nup\nls: t/a/up: not listing already-listed directory\n"
+        );
+    }
+
+    /// Parse the offsets on a `//DIRED//` or `//SUBDIRED//` trailer line and
+    /// return the output text each start/end pair points at.
+    fn dired_spans<'a>(stdout: &'a str, label: &str) -> Vec<&'a str> {
+        let line = stdout
+            .lines()
+            .find(|l| l.starts_with(label))
+            .unwrap_or_else(|| panic!("no {} line in {:?}", label, stdout));
+        let offsets: Vec<usize> = line[label.len()..]
+            .split_whitespace()
+            .map(|n| n.parse().unwrap())
+            .collect();
+        offsets.chunks(2).map(|p| &stdout[p[0]..p[1]]).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_dired_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a b"), "").unwrap();
+        std::fs::write(dir.path().join("\u{e9}t\u{e9}"), "").unwrap();
+        std::os::unix::fs::symlink("a b", dir.path().join("lnk")).unwrap();
+        let output = cmd()
+            .args(["-l", "--dired", "-Q"])
+            .current_dir(dir.path())
+            .env("LC_ALL", "C.UTF-8")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("  total 0\n  "), "{}", stdout);
+        assert_eq!(
+            dired_spans(&stdout, "//DIRED//"),
+            ["\"a b\"", "\"lnk\"", "\"\u{e9}t\u{e9}\""]
+        );
+        assert!(stdout.ends_with("\n//DIRED-OPTIONS// --quoting-style=c\n"));
+
+        // Without -l the option has no effect.
+        let output = cmd()
+            .arg("--dired")
+            .current_dir(dir.path())
+            .env("LC_ALL", "C.UTF-8")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a b\nlnk\n\u{e9}t\u{e9}\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_dired_recursive_subdired() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("d/sub")).unwrap();
+        std::fs::write(dir.path().join("d/sub/f"), "").unwrap();
+        let output = cmd()
+            .args(["-lRD", "d"])
+            .current_dir(dir.path())
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("  d:\n  total "), "{}", stdout);
+        assert!(stdout.contains("\n\n  d/sub:\n"), "{}", stdout);
+        assert_eq!(dired_spans(&stdout, "//DIRED//"), ["sub", "f"]);
+        assert_eq!(dired_spans(&stdout, "//SUBDIRED//"), ["d", "d/sub"]);
+    }
+
+    #[test]
+    fn test_ls_format_word() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["aa", "bb", "cc"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        let run = |args: &[&str], columns: &str| {
+            cmd()
+                .args(args)
+                .current_dir(dir.path())
+                .env("LC_ALL", "C")
+                .env("COLUMNS", columns)
+                .output()
+                .unwrap()
+        };
+
+        let output = run(&["--format=commas"], "10");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "aa, bb,\ncc\n");
+        let output = run(&["--format=across", "-w", "0"], "10");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "aa  bb  cc\n");
+        let output = run(&["--format=single", "-C"], "80");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "aa  bb\tcc\n");
+
+        // -1 does not override an earlier long format.
+        let output = run(&["--format=long", "-1"], "80");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("total 0\n-"), "{}", stdout);
+        let output = run(&["-l", "-m"], "80");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "aa, bb, cc\n");
+
+        let output = run(&["-x"], "bogus");
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "ls: ignoring invalid width in environment variable COLUMNS: 'bogus'\n"
+        );
+
+        let output = run(&["--format=ver"], "80");
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.starts_with("ls: ambiguous argument 'ver' for '--format'\n"),
+            "{}",
+            stderr
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_atime_sort_without_long_format() {
+        use std::time::{Duration, SystemTime};
+        let dir = tempfile::tempdir().unwrap();
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        // Newest first: by mtime cc, bb, aa; by atime bb, aa, cc.
+        for (name, mtime, atime) in [("aa", 0, 100), ("bb", 100, 200), ("cc", 200, 0)] {
+            let f = std::fs::File::create(dir.path().join(name)).unwrap();
+            let times = std::fs::FileTimes::new()
+                .set_modified(base + Duration::from_secs(mtime))
+                .set_accessed(base + Duration::from_secs(atime));
+            f.set_times(times).unwrap();
+        }
+        let run = |args: &[&str]| {
+            let output = cmd()
+                .args(args)
+                .arg(dir.path())
+                .env("TZ", "UTC")
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+
+        // Without -l, -u also sorts by access time, newest first.
+        assert_eq!(run(&["-1u"]), "bb\naa\ncc\n");
+        assert_eq!(run(&["-1", "--time=atime", "-r"]), "cc\naa\nbb\n");
+        assert_eq!(run(&["-m", "-u"]), "bb, aa, cc\n");
+        assert_eq!(run(&["-1ut"]), "bb\naa\ncc\n");
+        assert_eq!(run(&["-1t"]), "cc\nbb\naa\n");
+        // An explicit sort wins.
+        assert_eq!(run(&["-1uX"]), "aa\nbb\ncc\n");
+        assert_eq!(run(&["-1u", "--sort=size"]), "aa\nbb\ncc\n");
+
+        // With -l, -u only changes the displayed time; -t is needed to sort.
+        let names = |out: String| -> Vec<String> {
+            out.lines()
+                .skip(1)
+                .map(|l| l.rsplit(' ').next().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names(run(&["-lu"])), ["aa", "bb", "cc"]);
+        assert_eq!(names(run(&["-ltu"])), ["bb", "aa", "cc"]);
+        assert_eq!(names(run(&["-ltu", "-r"])), ["cc", "aa", "bb"]);
+        let stdout = run(&["-lu", "--time-style=full-iso"]);
+        assert!(
+            stdout.contains(" 2020-09-13 12:30:00.000000000 +0000 bb\n"),
+            "{}",
+            stdout
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_directory_operands() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("d1/sub")).unwrap();
+        std::fs::create_dir(dir.path().join("d2")).unwrap();
+        std::fs::write(dir.path().join("d1/x"), "").unwrap();
+        std::fs::write(dir.path().join("f1"), "").unwrap();
+        std::fs::write(dir.path().join(".h"), "").unwrap();
+        std::os::unix::fs::symlink("d1", dir.path().join("link")).unwrap();
+        let run = |args: &[&str]| {
+            let output = cmd().args(args).current_dir(dir.path()).output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+
+        // Directories are listed like files, in one sorted list.
+        assert_eq!(run(&["-d"]), ".\n");
+        assert_eq!(run(&["--directory", "-a"]), ".\n");
+        assert_eq!(run(&["-d1", "f1", "d2", "d1"]), "d1\nd2\nf1\n");
+        assert_eq!(run(&["-dR", "d1"]), "d1\n");
+        assert!(run(&["-ld", "d1"]).starts_with('d'));
+        // A symlink operand is not followed, unless written with a slash.
+        assert!(run(&["-ld", "link"]).starts_with('l'));
+        assert_eq!(run(&["-dF", "link", "link/"]), "link@\nlink//\n");
+    }
 }
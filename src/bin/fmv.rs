@@ -16,7 +16,9 @@ use std::path::Path;
 use std::process;
 
 #[cfg(unix)]
-use coreutils_rs::mv::{BackupMode, MvConfig, mv_file, parse_backup_mode, strip_trailing_slashes};
+use coreutils_rs::mv::{
+    BackupMode, MvConfig, backup_suffix, mv_file, parse_backup_mode, strip_trailing_slashes,
+};
 
 #[cfg(unix)]
 const TOOL_NAME: &str = "mv";
@@ -30,6 +32,11 @@ fn main() {
     let mut config = MvConfig::default();
     let mut operands: Vec<String> = Vec::new();
     let mut saw_dashdash = false;
+    // As in GNU, -b/--backup/-S only request backups; the CONTROL value
+    // (or VERSION_CONTROL) is resolved once all options are read.
+    let mut make_backups = false;
+    let mut backup_control: Option<String> = None;
+    let mut suffix: Option<String> = None;
 
     let args: Vec<String> = std::env::args().skip(1).collect();
     let mut i = 0;
@@ -67,7 +74,7 @@ fn main() {
             }
             "-v" | "--verbose" => config.verbose = true,
             "-u" | "--update" => config.update = true,
-            "-b" => config.backup = Some(BackupMode::Simple),
+            "-b" => make_backups = true,
             "--strip-trailing-slashes" => config.strip_trailing_slashes = true,
             "-T" | "--no-target-directory" => config.no_target_directory = true,
             "-t" => {
@@ -86,27 +93,24 @@ fn main() {
                     eprintln!("Try '{} --help' for more information.", TOOL_NAME);
                     process::exit(1);
                 }
-                config.suffix = args[i].clone();
+                make_backups = true;
+                suffix = Some(args[i].clone());
             }
             _ if arg.starts_with("--backup=") => {
-                let val = &arg["--backup=".len()..];
-                match parse_backup_mode(val) {
-                    Some(mode) => config.backup = Some(mode),
-                    None => {
-                        eprintln!("{}: invalid backup type '{}'", TOOL_NAME, val);
-                        process::exit(1);
-                    }
-                }
+                make_backups = true;
+                backup_control = Some(arg["--backup=".len()..].to_string());
             }
-            "--backup" => config.backup = Some(BackupMode::Existing),
+            "--backup" => make_backups = true,
             _ if arg.starts_with("--target-directory=") => {
                 config.target_directory = Some(arg["--target-directory=".len()..].to_string());
             }
             _ if arg.starts_with("--suffix=") => {
-                config.suffix = arg["--suffix=".len()..].to_string();
+                make_backups = true;
+                suffix = Some(arg["--suffix=".len()..].to_string());
             }
             _ if arg.starts_with("-S") && arg.len() > 2 => {
-                config.suffix = arg[2..].to_string();
+                make_backups = true;
+                suffix = Some(arg[2..].to_string());
             }
             _ if arg.starts_with("-t") && arg.len() > 2 => {
                 config.target_directory = Some(arg[2..].to_string());
@@ -134,7 +138,7 @@ fn main() {
                         }
                         'v' => config.verbose = true,
                         'u' => config.update = true,
-                        'b' => config.backup = Some(BackupMode::Simple),
+                        'b' => make_backups = true,
                         'T' => config.no_target_directory = true,
                         'S' => {
                             let rest: String = chars[j + 1..].iter().collect();
@@ -145,10 +149,11 @@ fn main() {
                                     eprintln!("Try '{} --help' for more information.", TOOL_NAME);
                                     process::exit(1);
                                 }
-                                config.suffix = args[i].clone();
+                                suffix = Some(args[i].clone());
                             } else {
-                                config.suffix = rest;
+                                suffix = Some(rest);
                             }
+                            make_backups = true;
                             break;
                         }
                         't' => {
@@ -180,6 +185,36 @@ fn main() {
         i += 1;
     }
 
+    if make_backups {
+        // An empty CONTROL or VERSION_CONTROL means the default, as in GNU.
+        let env_control = std::env::var("VERSION_CONTROL").ok();
+        let control = backup_control.as_deref().filter(|c| !c.is_empty());
+        let env_control = env_control.as_deref().filter(|c| !c.is_empty());
+        let parsed = match (control, env_control) {
+            (Some(control), _) => parse_backup_mode(control, "backup type"),
+            (None, Some(control)) => parse_backup_mode(control, "$VERSION_CONTROL"),
+            (None, None) => Ok(BackupMode::Existing),
+        };
+        match parsed {
+            Ok(mode) => config.backup = Some(mode),
+            Err(e) => {
+                eprintln!("{}: {}", TOOL_NAME, e);
+                eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                process::exit(1);
+            }
+        }
+        config.suffix = backup_suffix(suffix.as_deref());
+    }
+
+    if make_backups && config.no_clobber {
+        eprintln!(
+            "{}: options --backup and --no-clobber are mutually exclusive",
+            TOOL_NAME
+        );
+        eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+        process::exit(1);
+    }
+
     if operands.is_empty() {
         eprintln!("{}: missing file operand", TOOL_NAME);
         eprintln!("Try '{} --help' for more information.", TOOL_NAME);
@@ -217,13 +252,7 @@ fn main() {
                 .unwrap_or_else(|| source.clone());
             let dst = Path::new(dir).join(&basename);
             if let Err(e) = mv_file(src_path, &dst, &config) {
-                eprintln!(
-                    "{}: cannot move '{}' to '{}': {}",
-                    TOOL_NAME,
-                    source,
-                    dst.display(),
-                    coreutils_rs::common::io_error_msg(&e)
-                );
+                report_move_error(source, &dst.to_string_lossy(), &e);
                 exit_code = 1;
             }
         }
@@ -252,13 +281,7 @@ fn main() {
             process::exit(1);
         }
         if let Err(e) = mv_file(src, dst, &config) {
-            eprintln!(
-                "{}: cannot move '{}' to '{}': {}",
-                TOOL_NAME,
-                operands[0],
-                operands[1],
-                coreutils_rs::common::io_error_msg(&e)
-            );
+            report_move_error(&operands[0], &operands[1], &e);
             process::exit(1);
         }
     } else if operands.len() == 1 {
@@ -289,23 +312,11 @@ fn main() {
                 .unwrap_or_else(|| operands[0].clone());
             let final_dst = dst.join(&basename);
             if let Err(e) = mv_file(src, &final_dst, &config) {
-                eprintln!(
-                    "{}: cannot move '{}' to '{}': {}",
-                    TOOL_NAME,
-                    operands[0],
-                    final_dst.display(),
-                    coreutils_rs::common::io_error_msg(&e)
-                );
+                report_move_error(&operands[0], &final_dst.to_string_lossy(), &e);
                 exit_code = 1;
             }
         } else if let Err(e) = mv_file(src, dst, &config) {
-            eprintln!(
-                "{}: cannot move '{}' to '{}': {}",
-                TOOL_NAME,
-                operands[0],
-                operands[1],
-                coreutils_rs::common::io_error_msg(&e)
-            );
+            report_move_error(&operands[0], &operands[1], &e);
             exit_code = 1;
         }
     } else {
@@ -331,13 +342,7 @@ fn main() {
                 .unwrap_or_else(|| source.clone());
             let final_dst = Path::new(dir).join(&basename);
             if let Err(e) = mv_file(src_path, &final_dst, &config) {
-                eprintln!(
-                    "{}: cannot move '{}' to '{}': {}",
-                    TOOL_NAME,
-                    source,
-                    final_dst.display(),
-                    coreutils_rs::common::io_error_msg(&e)
-                );
+                report_move_error(source, &final_dst.to_string_lossy(), &e);
                 exit_code = 1;
             }
         }
@@ -348,6 +353,23 @@ fn main() {
     }
 }
 
+/// Report a failed move. A same-file error already names both files, so it
+/// is printed without the "cannot move" prefix, as GNU does.
+#[cfg(unix)]
+fn report_move_error(src: &str, dst: &str, e: &std::io::Error) {
+    if e.kind() == std::io::ErrorKind::Other {
+        eprintln!("{}: {}", TOOL_NAME, e);
+    } else {
+        eprintln!(
+            "{}: cannot move '{}' to '{}': {}",
+            TOOL_NAME,
+            src,
+            dst,
+            coreutils_rs::common::io_error_msg(e)
+        );
+    }
+}
+
 #[cfg(unix)]
 fn print_help() {
     println!("Usage: {} [OPTION]... [-T] SOURCE DEST", TOOL_NAME);
@@ -755,4 +777,162 @@ mod tests {
             "inside"
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mv_update_with_numbered_backup() {
+        use std::time::{Duration, SystemTime};
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dst = dir.path().join("dst.txt");
+        let set_mtime = |path: &std::path::Path, secs: u64| {
+            let f = fs::File::options().write(true).open(path).unwrap();
+            f.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+        fs::write(&src, "new").unwrap();
+        fs::write(&dst, "old").unwrap();
+        fs::write(dir.path().join("dst.txt.~3~"), "older").unwrap();
+        let args = [
+            "-u",
+            "--backup=numbered",
+            src.to_str().unwrap(),
+            dst.to_str().unwrap(),
+        ];
+
+        // A destination that is not older is kept, and nothing is backed up.
+        set_mtime(&src, 1_000_000);
+        set_mtime(&dst, 1_000_000);
+        let output = cmd().args(args).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "old");
+        assert!(src.exists());
+        assert!(!dir.path().join("dst.txt.~4~").exists());
+
+        // A newer source replaces it, backed up past the highest number.
+        set_mtime(&src, 2_000_000);
+        let output = cmd().args(args).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "new");
+        assert!(!src.exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dst.txt.~4~")).unwrap(),
+            "old"
+        );
+        assert!(!dir.path().join("dst.txt.~1~").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mv_backup_version_control() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dst = dir.path().join("dst.txt");
+        fs::write(&dst, "v1").unwrap();
+
+        fs::write(&src, "v2").unwrap();
+        let output = cmd()
+            .args(["-b", src.to_str().unwrap(), dst.to_str().unwrap()])
+            .env("VERSION_CONTROL", "t")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dst.txt.~1~")).unwrap(),
+            "v1"
+        );
+
+        // -S alone requests a backup; "existing" goes numbered now.
+        fs::write(&src, "v3").unwrap();
+        let output = cmd()
+            .args(["-S", ".bak", src.to_str().unwrap(), dst.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dst.txt.~2~")).unwrap(),
+            "v2"
+        );
+
+        fs::write(&src, "v4").unwrap();
+        let output = cmd()
+            .args(["--backup=n", src.to_str().unwrap(), dst.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("mv: ambiguous argument 'n' for 'backup type'\n"));
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "v3");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mv_backup_same_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("s");
+        let link = dir.path().join("h");
+        fs::write(&src, "data").unwrap();
+        fs::hard_link(&src, &link).unwrap();
+
+        // Moving a file onto itself is refused before any backup is made.
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["-b", "s", "s"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "mv: 's' and 's' are the same file\n"
+        );
+        assert!(src.exists());
+        assert!(!dir.path().join("s~").exists());
+
+        // Hard links are the same file too, unless a backup keeps the name.
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["s", "h"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "mv: 's' and 'h' are the same file\n"
+        );
+        let output = cmd()
+            .current_dir(dir.path())
+            .args(["-b", "s", "h"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(!src.exists());
+        assert_eq!(fs::read_to_string(&link).unwrap(), "data");
+        assert_eq!(fs::read_to_string(dir.path().join("h~")).unwrap(), "data");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mv_backup_no_clobber_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dst = dir.path().join("dst.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dst, "old").unwrap();
+
+        for opts in [&["-n", "-b"][..], &["--backup=none", "--no-clobber"][..]] {
+            let output = cmd()
+                .args(opts)
+                .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "mv: options --backup and --no-clobber are mutually exclusive\n\
+                 Try 'mv --help' for more information.\n"
+            );
+        }
+        assert!(src.exists());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "old");
+    }
 }
@@ -1,3 +1,4 @@
+use std::num::IntErrorKind;
 use std::path::Path;
 use std::process;
 
@@ -59,47 +60,28 @@ fn parse_args() -> Cli {
                 }
                 cli.config.section_delimiter = bytes;
             } else if let Some(val) = s.strip_prefix("--line-increment=") {
-                match val.parse::<i64>() {
-                    Ok(n) => cli.config.line_increment = n,
-                    Err(_) => {
-                        eprintln!("nl: invalid line increment: '{}'", val);
-                        process::exit(1);
-                    }
-                }
+                cli.config.line_increment =
+                    parse_number(val, "line number increment", i64::MIN, i64::MAX);
             } else if let Some(val) = s.strip_prefix("--join-blank-lines=") {
-                match val.parse::<usize>() {
-                    Ok(n) if n > 0 => cli.config.join_blank_lines = n,
-                    _ => {
-                        eprintln!("nl: invalid line number of blank lines: '{}'", val);
-                        process::exit(1);
-                    }
-                }
+                cli.config.join_blank_lines =
+                    parse_number(val, "line number of blank lines", 1, i64::MAX) as usize;
             } else if let Some(val) = s.strip_prefix("--number-format=") {
                 match nl::parse_number_format(val) {
                     Ok(fmt) => cli.config.number_format = fmt,
                     Err(e) => {
                         eprintln!("nl: {}", e);
+                        eprintln!("Try 'nl --help' for more information.");
                         process::exit(1);
                     }
                 }
             } else if let Some(val) = s.strip_prefix("--number-separator=") {
                 cli.config.number_separator = val.as_bytes().to_vec();
             } else if let Some(val) = s.strip_prefix("--starting-line-number=") {
-                match val.parse::<i64>() {
-                    Ok(n) => cli.config.starting_line_number = n,
-                    Err(_) => {
-                        eprintln!("nl: invalid starting line number: '{}'", val);
-                        process::exit(1);
-                    }
-                }
+                cli.config.starting_line_number =
+                    parse_number(val, "starting line number", i64::MIN, i64::MAX);
             } else if let Some(val) = s.strip_prefix("--number-width=") {
-                match val.parse::<usize>() {
-                    Ok(n) if n > 0 => cli.config.number_width = n,
-                    _ => {
-                        eprintln!("nl: invalid line number field width: '{}'", val);
-                        process::exit(1);
-                    }
-                }
+                cli.config.number_width =
+                    parse_number(val, "line number field width", 1, i32::MAX as i64) as usize;
             } else {
                 match bytes {
                     b"--body-numbering" => {
@@ -142,23 +124,13 @@ fn parse_args() -> Cli {
                     }
                     b"--line-increment" => {
                         let val = require_arg(&mut args, "--line-increment");
-                        match val.parse::<i64>() {
-                            Ok(n) => cli.config.line_increment = n,
-                            Err(_) => {
-                                eprintln!("nl: invalid line increment: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.line_increment =
+                            parse_number(&val, "line number increment", i64::MIN, i64::MAX);
                     }
                     b"--join-blank-lines" => {
                         let val = require_arg(&mut args, "--join-blank-lines");
-                        match val.parse::<usize>() {
-                            Ok(n) if n > 0 => cli.config.join_blank_lines = n,
-                            _ => {
-                                eprintln!("nl: invalid line number of blank lines: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.join_blank_lines =
+                            parse_number(&val, "line number of blank lines", 1, i64::MAX) as usize;
                     }
                     b"--number-format" => {
                         let val = require_arg(&mut args, "--number-format");
@@ -166,6 +138,7 @@ fn parse_args() -> Cli {
                             Ok(fmt) => cli.config.number_format = fmt,
                             Err(e) => {
                                 eprintln!("nl: {}", e);
+                                eprintln!("Try 'nl --help' for more information.");
                                 process::exit(1);
                             }
                         }
@@ -177,23 +150,14 @@ fn parse_args() -> Cli {
                     }
                     b"--starting-line-number" => {
                         let val = require_arg(&mut args, "--starting-line-number");
-                        match val.parse::<i64>() {
-                            Ok(n) => cli.config.starting_line_number = n,
-                            Err(_) => {
-                                eprintln!("nl: invalid starting line number: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.starting_line_number =
+                            parse_number(&val, "starting line number", i64::MIN, i64::MAX);
                     }
                     b"--number-width" => {
                         let val = require_arg(&mut args, "--number-width");
-                        match val.parse::<usize>() {
-                            Ok(n) if n > 0 => cli.config.number_width = n,
-                            _ => {
-                                eprintln!("nl: invalid line number field width: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.number_width =
+                            parse_number(&val, "line number field width", 1, i32::MAX as i64)
+                                as usize;
                     }
                     b"--help" => {
                         print_help();
@@ -262,24 +226,14 @@ fn parse_args() -> Cli {
                     }
                     'i' => {
                         let val = short_opt_value(&s, &chars, i, &mut args, 'i');
-                        match val.parse::<i64>() {
-                            Ok(n) => cli.config.line_increment = n,
-                            Err(_) => {
-                                eprintln!("nl: invalid line increment: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.line_increment =
+                            parse_number(&val, "line number increment", i64::MIN, i64::MAX);
                         break;
                     }
                     'l' => {
                         let val = short_opt_value(&s, &chars, i, &mut args, 'l');
-                        match val.parse::<usize>() {
-                            Ok(n) if n > 0 => cli.config.join_blank_lines = n,
-                            _ => {
-                                eprintln!("nl: invalid line number of blank lines: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.join_blank_lines =
+                            parse_number(&val, "line number of blank lines", 1, i64::MAX) as usize;
                         break;
                     }
                     'n' => {
@@ -288,6 +242,7 @@ fn parse_args() -> Cli {
                             Ok(fmt) => cli.config.number_format = fmt,
                             Err(e) => {
                                 eprintln!("nl: {}", e);
+                                eprintln!("Try 'nl --help' for more information.");
                                 process::exit(1);
                             }
                         }
@@ -301,24 +256,15 @@ fn parse_args() -> Cli {
                     }
                     'v' => {
                         let val = short_opt_value(&s, &chars, i, &mut args, 'v');
-                        match val.parse::<i64>() {
-                            Ok(n) => cli.config.starting_line_number = n,
-                            Err(_) => {
-                                eprintln!("nl: invalid starting line number: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.starting_line_number =
+                            parse_number(&val, "starting line number", i64::MIN, i64::MAX);
                         break;
                     }
                     'w' => {
                         let val = short_opt_value(&s, &chars, i, &mut args, 'w');
-                        match val.parse::<usize>() {
-                            Ok(n) if n > 0 => cli.config.number_width = n,
-                            _ => {
-                                eprintln!("nl: invalid line number field width: '{}'", val);
-                                process::exit(1);
-                            }
-                        }
+                        cli.config.number_width =
+                            parse_number(&val, "line number field width", 1, i32::MAX as i64)
+                                as usize;
                         break;
                     }
                     _ => {
@@ -337,6 +283,28 @@ fn parse_args() -> Cli {
     cli
 }
 
+/// Parse a numeric option value within `min..=max`, exiting with GNU's
+/// diagnostics on failure.
+fn parse_number(val: &str, what: &str, min: i64, max: i64) -> i64 {
+    let t = val.trim_start();
+    let suffix = match t.strip_prefix('+').unwrap_or(t).parse::<i64>() {
+        Ok(n) if (min..=max).contains(&n) => return n,
+        Ok(n) if n < min => ": Numerical result out of range",
+        Ok(_) => ": Value too large for defined data type",
+        Err(e)
+            if matches!(
+                e.kind(),
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+            ) =>
+        {
+            ": Value too large for defined data type"
+        }
+        Err(_) => "",
+    };
+    eprintln!("nl: invalid {}: '{}'{}", what, val, suffix);
+    process::exit(1);
+}
+
 /// Get value for a short option that takes an argument.
 fn short_opt_value(
     s: &str,
@@ -431,7 +399,7 @@ fn main() {
     };
 
     let mut had_error = false;
-    let mut line_number = cli.config.starting_line_number;
+    let mut state = nl::NlState::new(&cli.config);
 
     for filename in &files {
         let data = if filename == "-" {
@@ -454,7 +422,7 @@ fn main() {
             }
         };
 
-        let output = nl::nl_to_vec_with_state(&data, &cli.config, &mut line_number);
+        let output = nl::nl_to_vec_with_state(&data, &cli.config, &mut state);
         if let Err(e) = write_all_raw(&output) {
             if e.kind() == std::io::ErrorKind::BrokenPipe {
                 process::exit(0);
@@ -462,6 +430,10 @@ fn main() {
             eprintln!("nl: write error: {}", io_error_msg(&e));
             had_error = true;
         }
+        if state.overflowed {
+            eprintln!("nl: line number overflow");
+            process::exit(1);
+        }
     }
 
     if had_error {
@@ -827,4 +799,75 @@ mod tests {
             lines[1]
         );
     }
+
+    fn run_nl(args: &[&str], input: &[u8]) -> std::process::Output {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_nl_custom_numbering() {
+        let output = run_nl(
+            &["-v", "100", "-i", "10", "-w4", "-nrz", "-s: "],
+            b"1\n2\n3\n",
+        );
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "0100: 1\n0110: 2\n0120: 3\n"
+        );
+
+        // Negative numbers keep the sign ahead of the zero padding, and a
+        // number wider than the field is printed in full.
+        let output = run_nl(&["-v", "-5", "-i", "99997", "-w4", "-nrz"], b"a\nb\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "-005\ta\n99992\tb\n"
+        );
+        let output = run_nl(&["-v", "-5", "-w4", "-nln", "-s|"], b"a\n");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "-5  |a\n");
+    }
+
+    #[test]
+    fn test_nl_line_number_overflow() {
+        let output = run_nl(&["-v", "9223372036854775807"], b"1\n\n2\n");
+        assert_eq!(output.status.code(), Some(1));
+        // The blank line is unnumbered, so it is still printed.
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "9223372036854775807\t1\n       \n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "nl: line number overflow\n"
+        );
+    }
+
+    #[test]
+    fn test_nl_invalid_numeric_options() {
+        for (args, err) in [
+            (
+                ["-w", "0"],
+                "nl: invalid line number field width: '0': Numerical result out of range\n",
+            ),
+            (["-i", "x"], "nl: invalid line number increment: 'x'\n"),
+            (
+                ["-n", "xx"],
+                "nl: invalid line numbering format: 'xx'\nTry 'nl --help' for more information.\n",
+            ),
+        ] {
+            let output = run_nl(&args, b"a\n");
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(String::from_utf8_lossy(&output.stderr), err);
+        }
+    }
 }
@@ -8,7 +8,7 @@
 use std::io::{self, BufWriter, Write};
 use std::process;
 
-use coreutils_rs::numfmt::{self, InvalidMode, NumfmtConfig};
+use coreutils_rs::numfmt::{self, InvalidMode, NumfmtConfig, RoundMethod};
 
 const TOOL_NAME: &str = "numfmt";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -18,6 +18,7 @@ fn print_help() {
     println!("Reformat NUMBER(s), or the numbers from standard input if none are specified.");
     println!();
     println!("Mandatory arguments to long options are mandatory for short options too.");
+    println!("      --debug          print warnings about invalid input");
     println!("  -d, --delimiter=X    use X instead of whitespace for field delimiter");
     println!("      --field=FIELDS   replace the numbers in these input fields (default=1);");
     println!("                         see FIELDS below");
@@ -55,12 +56,34 @@ fn print_help() {
     println!("               1K = 1024, 1M = 1048576, ...");
     println!("  iec-i      accept optional two-letter suffix:");
     println!("               1Ki = 1024, 1Mi = 1048576, ...");
+    println!();
+    println!("FORMAT must be suitable for printing one floating-point argument '%f'.");
+    println!("Optional quote (%'f) will enable --grouping (if supported by current locale).");
+    println!("Optional width value (%10f) will pad output. Optional zero (%010f) width");
+    println!("will zero pad the number. Optional negative values (%-10f) will left align.");
+    println!("Optional precision (%.1f) will override the input determined precision.");
 }
 
 fn print_version() {
     println!("{} (fcoreutils) {}", TOOL_NAME, VERSION);
 }
 
+/// Parse a --round METHOD, exiting with GNU's argmatch diagnostics on error.
+fn parse_round(val: &str) -> RoundMethod {
+    match numfmt::parse_round_method(val) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("{}: {}", TOOL_NAME, e);
+            eprintln!("Valid arguments are:");
+            for name in ["up", "down", "from-zero", "towards-zero", "nearest"] {
+                eprintln!("  - '{}'", name);
+            }
+            eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+            process::exit(1);
+        }
+    }
+}
+
 fn parse_args() -> (NumfmtConfig, Vec<String>) {
     let mut config = NumfmtConfig::default();
     let mut positional: Vec<String> = Vec::new();
@@ -89,6 +112,9 @@ fn parse_args() -> (NumfmtConfig, Vec<String>) {
             "--grouping" => {
                 config.grouping = true;
             }
+            "--debug" => {
+                config.debug = true;
+            }
             _ => {
                 if let Some(val) = arg.strip_prefix("--from=") {
                     match numfmt::parse_scale_unit(val) {
@@ -130,18 +156,27 @@ fn parse_args() -> (NumfmtConfig, Vec<String>) {
                             process::exit(1);
                         }
                     }
-                } else if let Some(val) = arg.strip_prefix("--round=") {
-                    match numfmt::parse_round_method(val) {
-                        Ok(m) => config.round = m,
-                        Err(e) => {
-                            eprintln!("{}: {}", TOOL_NAME, e);
+                } else if arg == "--round" {
+                    match args.next() {
+                        Some(val) => config.round = parse_round(&val),
+                        None => {
+                            eprintln!("{}: option '--round' requires an argument", TOOL_NAME);
+                            eprintln!("Try '{} --help' for more information.", TOOL_NAME);
                             process::exit(1);
                         }
                     }
+                } else if let Some(val) = arg.strip_prefix("--round=") {
+                    config.round = parse_round(val);
                 } else if let Some(val) = arg.strip_prefix("--suffix=") {
                     config.suffix = Some(val.to_string());
                 } else if let Some(val) = arg.strip_prefix("--format=") {
-                    config.format = Some(val.to_string());
+                    match numfmt::parse_format(val) {
+                        Ok(f) => config.format = Some(f),
+                        Err(e) => {
+                            eprintln!("{}: {}", TOOL_NAME, e);
+                            process::exit(1);
+                        }
+                    }
                 } else if let Some(val) = arg.strip_prefix("--field=") {
                     match numfmt::parse_fields(val) {
                         Ok(f) => config.field = f,
@@ -217,6 +252,16 @@ fn parse_args() -> (NumfmtConfig, Vec<String>) {
         }
     }
 
+    if config.grouping && config.format.is_some() {
+        eprintln!("{}: --grouping cannot be combined with --format", TOOL_NAME);
+        process::exit(1);
+    }
+    let grouping = config.grouping || config.format.as_ref().is_some_and(|f| f.grouping);
+    if grouping && config.to != numfmt::ScaleUnit::None {
+        eprintln!("{}: grouping cannot be combined with --to", TOOL_NAME);
+        process::exit(1);
+    }
+
     (config, positional)
 }
 
@@ -225,6 +270,20 @@ fn main() {
 
     let (config, positional) = parse_args();
 
+    if config.debug {
+        if config.from == numfmt::ScaleUnit::None
+            && config.to == numfmt::ScaleUnit::None
+            && !config.grouping
+            && config.padding.is_none()
+            && config.format.is_none()
+        {
+            eprintln!("{}: no conversion option specified", TOOL_NAME);
+        }
+        if config.header > 0 && !positional.is_empty() {
+            eprintln!("{}: --header ignored with command-line input", TOOL_NAME);
+        }
+    }
+
     if positional.is_empty() {
         // Read from stdin.
         let stdin = io::stdin();
@@ -241,36 +300,26 @@ fn main() {
         let stdout = io::stdout();
         let mut writer = BufWriter::with_capacity(8 * 1024, stdout.lock());
         let terminator = if config.zero_terminated { '\0' } else { '\n' };
-        let mut had_error = false;
+        let mut all_valid = true;
 
         for number in &positional {
-            match numfmt::process_line(number, &config) {
+            match numfmt::process_line(number, &config, &mut all_valid) {
                 Ok(result) => {
                     let _ = write!(writer, "{}{}", result, terminator);
                 }
-                Err(e) => match config.invalid {
-                    InvalidMode::Abort => {
-                        eprintln!("{}: {}", TOOL_NAME, e);
-                        process::exit(2);
-                    }
-                    InvalidMode::Fail => {
-                        eprintln!("{}: {}", TOOL_NAME, e);
-                        let _ = write!(writer, "{}{}", number, terminator);
-                        had_error = true;
-                    }
-                    InvalidMode::Warn => {
-                        eprintln!("{}: {}", TOOL_NAME, e);
-                        let _ = write!(writer, "{}{}", number, terminator);
-                    }
-                    InvalidMode::Ignore => {
-                        let _ = write!(writer, "{}{}", number, terminator);
-                    }
-                },
+                Err(e) => {
+                    let _ = writer.flush();
+                    eprintln!("{}: {}", TOOL_NAME, e);
+                    process::exit(2);
+                }
             }
         }
 
         let _ = writer.flush();
-        if had_error {
+        if !all_valid && config.debug {
+            eprintln!("{}: failed to convert some of the input numbers", TOOL_NAME);
+        }
+        if !all_valid && config.invalid == InvalidMode::Fail {
             process::exit(2);
         }
     }
@@ -425,4 +474,214 @@ mod tests {
         let output = child.wait_with_output().unwrap();
         assert!(output.status.success());
     }
+
+    #[test]
+    fn test_numfmt_format_width_precision() {
+        let output = cmd().args(["--format=%10.2f", "1234.5"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "   1234.50\n");
+    }
+
+    #[test]
+    fn test_numfmt_format_left_align_and_literals() {
+        let output = cmd().args(["--format=[%-6f]", "1.5"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "[1.5   ]\n");
+    }
+
+    #[test]
+    fn test_numfmt_format_grouping_flag() {
+        let output = cmd().args(["--format=%'f", "1234567"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "1,234,567\n");
+    }
+
+    #[test]
+    fn test_numfmt_format_with_to_iec() {
+        // Width covers the number and its scale suffix.
+        let output = cmd()
+            .args(["--to=iec", "--format=%8f", "5000"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "    4.9K\n");
+
+        let output = cmd()
+            .args(["--to=iec", "--format=%.2f", "5000"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "4.89K\n");
+    }
+
+    #[test]
+    fn test_numfmt_format_invalid() {
+        for fmt in ["--format=%d", "--format=abc", "--format=%f%f"] {
+            let output = cmd().args([fmt, "5"]).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{}", fmt);
+        }
+    }
+
+    #[test]
+    fn test_numfmt_round_methods() {
+        let cases: [(&[&str], &str); 7] = [
+            (&["--round=up", "--to=si", "1001"], "1.1K\n"),
+            (&["--round=down", "--to=si", "1999"], "1.9K\n"),
+            (&["--round=from-zero", "--to=si", "--", "-1001"], "-1.1K\n"),
+            (
+                &["--round", "towards-zero", "--to=si", "--", "-1999"],
+                "-1.9K\n",
+            ),
+            (&["--round=nearest", "--to=si", "999.5"], "1.0K\n"),
+            (&["--round=n", "--to=si", "1.25"], "1\n"),
+            (&["--round=up", "1.04"], "1.04\n"),
+        ];
+        for (args, expected) in cases {
+            let output = cmd().args(args).output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_numfmt_round_invalid() {
+        let output = cmd().args(["--round=bogus", "1"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("numfmt: invalid argument 'bogus' for '--round'\n"));
+        assert!(stderr.contains("  - 'towards-zero'\n"));
+
+        let output = cmd().args(["--round=", "1"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("ambiguous argument"));
+    }
+
+    #[test]
+    fn test_numfmt_suffix_stripped_and_reappended() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["--suffix=W", "--to=si", "3000W"], "3.0KW\n"),
+            (&["--suffix=W", "--from=si", "2KW"], "2000W\n"),
+        ];
+        for &(args, expected) in cases {
+            let output = cmd().args(args).output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+
+        let mut child = cmd()
+            .args(["--suffix=W", "--field=2", "--to=si"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"1000W 1000W\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "1000W 1.0KW\n");
+    }
+
+    #[test]
+    fn test_numfmt_invalid_suffix_errors() {
+        let cases: &[(&[&str], &str)] = &[
+            (
+                &["--suffix=W", "3WW"],
+                "numfmt: invalid suffix in input: '3W'\n",
+            ),
+            (&["1e3"], "numfmt: invalid suffix in input: '1e3'\n"),
+            (
+                &["3K"],
+                "numfmt: rejecting suffix in input: '3K' (consider using --from)\n",
+            ),
+            (
+                &["--from=iec-i", "3K"],
+                "numfmt: missing 'i' suffix in input: '3K' (e.g Ki/Mi/Gi)\n",
+            ),
+            (&["abc"], "numfmt: invalid number: 'abc'\n"),
+        ];
+        for &(args, expected) in cases {
+            let output = cmd().args(args).output().unwrap();
+            assert_eq!(output.status.code(), Some(2), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_numfmt_debug_warnings() {
+        let cases: &[(&[&str], &str, &str, i32)] = &[
+            (
+                &[
+                    "--debug",
+                    "--to=si",
+                    "1234567890123456789",
+                    "1.5000000000000000000000",
+                ],
+                "1.3E\n2\n",
+                "numfmt: large input value '1234567890123456789': possible precision loss\n\
+                 numfmt: large input value '1.5000000000000000000000': possible precision loss\n",
+                0,
+            ),
+            (
+                &["--debug", "--to=si", "123456789012345678"],
+                "124P\n",
+                "",
+                0,
+            ),
+            (
+                &["--debug", "--header", "1000"],
+                "1000\n",
+                "numfmt: no conversion option specified\n\
+                 numfmt: --header ignored with command-line input\n",
+                0,
+            ),
+            (
+                &["--debug", "--from=si", "--invalid=warn", "1K", "1Ki"],
+                "1000\n1Ki\n",
+                "numfmt: invalid suffix in input '1Ki': 'i'\n\
+                 numfmt: failed to convert some of the input numbers\n",
+                0,
+            ),
+            (
+                &["--invalid=fail", "x", "1"],
+                "x\n1\n",
+                "numfmt: invalid number: 'x'\n",
+                2,
+            ),
+        ];
+        for &(args, stdout, stderr, code) in cases {
+            let output = cmd().args(args).output().unwrap();
+            assert_eq!(output.status.code(), Some(code), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                stdout,
+                "{:?}",
+                args
+            );
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                stderr,
+                "{:?}",
+                args
+            );
+        }
+    }
 }
@@ -8,7 +8,7 @@ use std::process;
 
 use coreutils_rs::common::reset_sigpipe;
 use coreutils_rs::od::{
-    AddressRadix, Endian, OdConfig, OutputFormat, od_process, parse_format_type,
+    AddressRadix, Endian, OdConfig, OutputFormat, od_process, od_process_file, parse_format_types,
 };
 
 const TOOL_NAME: &str = "od";
@@ -117,15 +117,30 @@ fn main() {
                 z_flags.push(false);
             }
 
+            "--address-radix" => {
+                i += 1;
+                if i < args.len() {
+                    address_radix = Some(parse_radix(&args[i]));
+                } else {
+                    eprintln!(
+                        "{}: option '--address-radix' requires an argument",
+                        TOOL_NAME
+                    );
+                    eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                    process::exit(1);
+                }
+            }
             _ if arg.starts_with("--address-radix=") => {
                 address_radix = Some(parse_radix(&arg["--address-radix=".len()..]));
             }
             _ if arg.starts_with("--format=") => {
                 let fmt_str = &arg["--format=".len()..];
-                match parse_format_type(fmt_str) {
-                    Ok((f, z)) => {
-                        formats.push(f);
-                        z_flags.push(z);
+                match parse_format_types(fmt_str) {
+                    Ok(parsed) => {
+                        for (f, z) in parsed {
+                            formats.push(f);
+                            z_flags.push(z);
+                        }
                     }
                     Err(e) => {
                         eprintln!("{}: {}", TOOL_NAME, e);
@@ -134,10 +149,10 @@ fn main() {
                 }
             }
             _ if arg.starts_with("--skip-bytes=") => {
-                skip_bytes = parse_offset(&arg["--skip-bytes=".len()..]);
+                skip_bytes = parse_offset(&arg["--skip-bytes=".len()..], "--skip-bytes");
             }
             _ if arg.starts_with("--read-bytes=") => {
-                read_bytes = Some(parse_offset(&arg["--read-bytes=".len()..]));
+                read_bytes = Some(parse_offset(&arg["--read-bytes=".len()..], "--read-bytes"));
             }
             _ if arg == "--width" => {
                 // --width without =
@@ -160,17 +175,20 @@ fn main() {
                 while j < bytes.len() {
                     match bytes[j] {
                         b'A' => {
-                            j += 1;
-                            if j < bytes.len() {
-                                address_radix =
-                                    Some(parse_radix(&String::from_utf8_lossy(&bytes[j..j + 1])));
-                                j += 1;
+                            let rest = &arg[j + 1..];
+                            if !rest.is_empty() {
+                                address_radix = Some(parse_radix(rest));
                             } else {
                                 i += 1;
                                 if i < args.len() {
                                     address_radix = Some(parse_radix(&args[i]));
+                                } else {
+                                    eprintln!("{}: option requires an argument -- 'A'", TOOL_NAME);
+                                    eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                                    process::exit(1);
                                 }
                             }
+                            j = bytes.len();
                             continue;
                         }
                         b'j' => {
@@ -179,10 +197,14 @@ fn main() {
                             if rest.is_empty() {
                                 i += 1;
                                 if i < args.len() {
-                                    skip_bytes = parse_offset(&args[i]);
+                                    skip_bytes = parse_offset(&args[i], "-j");
+                                } else {
+                                    eprintln!("{}: option requires an argument -- 'j'", TOOL_NAME);
+                                    eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                                    process::exit(1);
                                 }
                             } else {
-                                skip_bytes = parse_offset(&rest);
+                                skip_bytes = parse_offset(&rest, "-j");
                             }
                             j = bytes.len(); // consumed rest
                             continue;
@@ -193,10 +215,14 @@ fn main() {
                             if rest.is_empty() {
                                 i += 1;
                                 if i < args.len() {
-                                    read_bytes = Some(parse_offset(&args[i]));
+                                    read_bytes = Some(parse_offset(&args[i], "-N"));
+                                } else {
+                                    eprintln!("{}: option requires an argument -- 'N'", TOOL_NAME);
+                                    eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                                    process::exit(1);
                                 }
                             } else {
-                                read_bytes = Some(parse_offset(&rest));
+                                read_bytes = Some(parse_offset(&rest, "-N"));
                             }
                             j = bytes.len();
                             continue;
@@ -215,10 +241,12 @@ fn main() {
                             } else {
                                 rest
                             };
-                            match parse_format_type(&fmt_str) {
-                                Ok((f, z)) => {
-                                    formats.push(f);
-                                    z_flags.push(z);
+                            match parse_format_types(&fmt_str) {
+                                Ok(parsed) => {
+                                    for (f, z) in parsed {
+                                        formats.push(f);
+                                        z_flags.push(z);
+                                    }
                                 }
                                 Err(e) => {
                                     eprintln!("{}: {}", TOOL_NAME, e);
@@ -304,14 +332,22 @@ fn main() {
                 process::exit(1);
             }
             _ if arg.starts_with('+') => {
-                // Traditional offset: +OFFSET[.][b]
-                skip_bytes = parse_offset(&arg[1..]);
+                // Traditional offset: +OFFSET[.][b]; anything else is a file.
+                match scan_offset(&arg[1..]) {
+                    Ok(n) => skip_bytes = n,
+                    Err(_) => operands.push(arg.clone()),
+                }
             }
             _ => operands.push(arg.clone()),
         }
         i += 1;
     }
 
+    if read_bytes.is_some_and(|n| skip_bytes.checked_add(n).is_none()) {
+        eprintln!("{}: skip-bytes + read-bytes is too large", TOOL_NAME);
+        process::exit(1);
+    }
+
     let config = OdConfig {
         address_radix: address_radix.unwrap_or(AddressRadix::Octal),
         formats: if formats.is_empty() {
@@ -364,6 +400,21 @@ fn main() {
             eprintln!("{}: {}", TOOL_NAME, e);
             process::exit(1);
         }
+    } else if operands.len() == 1 && (config.skip_bytes > 0 || config.read_bytes.is_some()) {
+        // Windowed dump of a single file: seek past the skipped bytes and
+        // read only the requested ones.
+        match std::fs::File::open(&operands[0]) {
+            Ok(file) => {
+                if let Err(e) = od_process_file(file, &mut out, &config) {
+                    eprintln!("{}: {}", TOOL_NAME, e);
+                    process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}: {}", TOOL_NAME, operands[0], e);
+                process::exit(1);
+            }
+        }
     } else if operands.len() == 1 && operands[0] != "-" {
         // Single file: read_file uses O_NOATIME + exact-size preallocation
         match coreutils_rs::common::io::read_file(std::path::Path::new(&operands[0])) {
@@ -412,61 +463,124 @@ fn main() {
     }
 }
 
+/// Parse an `-A` argument. Like GNU, only the first character is significant.
 fn parse_radix(s: &str) -> AddressRadix {
-    match s {
-        "o" => AddressRadix::Octal,
-        "d" => AddressRadix::Decimal,
-        "x" => AddressRadix::Hex,
-        "n" => AddressRadix::None,
+    let c = s.chars().next().unwrap_or('\0');
+    match c {
+        'o' => AddressRadix::Octal,
+        'd' => AddressRadix::Decimal,
+        'x' => AddressRadix::Hex,
+        'n' => AddressRadix::None,
         _ => {
             eprintln!(
-                "{}: invalid address radix '{}'; it must be one character from [doxn]",
-                TOOL_NAME, s
+                "{}: invalid output address radix '{}'; it must be one character from [doxn]",
+                TOOL_NAME, c
             );
             process::exit(1);
         }
     }
 }
 
-fn parse_offset(s: &str) -> u64 {
-    let s = s.trim();
-    let (num_str, multiplier) = if let Some(n) = s.strip_suffix("GiB") {
-        (n, 1_073_741_824u64)
-    } else if let Some(n) = s.strip_suffix("GB") {
-        (n, 1_000_000_000u64)
-    } else if let Some(n) = s.strip_suffix('G') {
-        (n, 1_073_741_824u64)
-    } else if let Some(n) = s.strip_suffix("MiB") {
-        (n, 1_048_576u64)
-    } else if let Some(n) = s.strip_suffix("MB") {
-        (n, 1_000_000u64)
-    } else if let Some(n) = s.strip_suffix('M') {
-        (n, 1_048_576u64)
-    } else if let Some(n) = s.strip_suffix("KiB") {
-        (n, 1_024u64)
-    } else if let Some(n) = s.strip_suffix("KB") {
-        (n, 1_000u64)
-    } else if let Some(n) = s.strip_suffix("kB") {
-        (n, 1_000u64)
-    } else if let Some(n) = s.strip_suffix('K') {
-        (n, 1_024u64)
-    } else if let Some(n) = s.strip_suffix('k') {
-        (n, 1_024u64)
-    } else if let Some(n) = s.strip_suffix('b') {
-        (n, 512u64)
+/// Why a byte count could not be parsed, in GNU xstrtoumax's terms.
+enum OffsetError {
+    Invalid,
+    InvalidSuffix,
+    TooLarge,
+}
+
+/// Parse a `-j`/`-N` argument, exiting with GNU's diagnostic if it is not a
+/// valid byte count. `option` names the option in the message.
+fn parse_offset(s: &str, option: &str) -> u64 {
+    match scan_offset(s) {
+        Ok(n) => n,
+        Err(e) => {
+            match e {
+                OffsetError::Invalid => {
+                    eprintln!("{}: invalid {} argument '{}'", TOOL_NAME, option, s)
+                }
+                OffsetError::InvalidSuffix => eprintln!(
+                    "{}: invalid suffix in {} argument '{}'",
+                    TOOL_NAME, option, s
+                ),
+                OffsetError::TooLarge => {
+                    eprintln!("{}: {} argument '{}' too large", TOOL_NAME, option, s)
+                }
+            }
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a byte count like GNU xstrtoumax: a decimal, octal (leading 0) or
+/// hex (leading 0x) number, optionally followed by a multiplier suffix. `b`
+/// is 512; `K`, `M`, `G`, ... are powers of 1024, or of 1000 when followed
+/// by `B` (`KiB` is 1024 again). A suffix alone stands for one unit.
+fn scan_offset(s: &str) -> Result<u64, OffsetError> {
+    let s = s.trim_start_matches([' ', '\t', '\n', '\x0b', '\x0c', '\r']);
+    if s.starts_with('-') {
+        return Err(OffsetError::Invalid);
+    }
+    let s = s.strip_prefix('+').unwrap_or(s);
+    let b = s.as_bytes();
+    let (radix, start) =
+        if b.len() > 2 && b[0] == b'0' && matches!(b[1], b'x' | b'X') && b[2].is_ascii_hexdigit() {
+            (16, 2)
+        } else if b.first() == Some(&b'0') {
+            (8, 0)
+        } else {
+            (10, 0)
+        };
+    let len = b[start..]
+        .iter()
+        .take_while(|&&c| (c as char).is_digit(radix))
+        .count();
+    let mut val = Some(0u64);
+    for &c in &b[start..start + len] {
+        let digit = u64::from((c as char).to_digit(radix).unwrap());
+        val = val.and_then(|v| v.checked_mul(u64::from(radix))?.checked_add(digit));
+    }
+    let suffix = &b[start + len..];
+    if len == 0 {
+        if suffix.is_empty() || !b"bEGKkMmPTYZ".contains(&suffix[0]) {
+            return Err(OffsetError::Invalid);
+        }
+        val = Some(1);
+    }
+    let Some((&unit, rest)) = suffix.split_first() else {
+        return val.ok_or(OffsetError::TooLarge);
+    };
+    let power = match unit {
+        b'b' => 0,
+        b'K' | b'k' => 1,
+        b'M' | b'm' => 2,
+        b'G' => 3,
+        b'T' => 4,
+        b'P' => 5,
+        b'E' => 6,
+        b'Z' => 7,
+        b'Y' => 8,
+        _ => return Err(OffsetError::InvalidSuffix),
+    };
+    let (multiplier, rest): (u64, &[u8]) = if unit == b'b' {
+        (512, rest)
+    } else if let Some(rest) = rest.strip_prefix(b"iB") {
+        (1024, rest)
+    } else if let Some(rest) = rest.strip_prefix(b"B").or_else(|| rest.strip_prefix(b"D")) {
+        (1000, rest)
     } else {
-        (s, 1u64)
+        (1024, rest)
     };
-
-    let base_val = if num_str.starts_with("0x") || num_str.starts_with("0X") {
-        u64::from_str_radix(&num_str[2..], 16).unwrap_or(0)
-    } else if num_str.starts_with('0') && num_str.len() > 1 {
-        u64::from_str_radix(&num_str[1..], 8).unwrap_or(0)
+    if !rest.is_empty() {
+        return Err(OffsetError::InvalidSuffix);
+    }
+    let scale = if unit == b'b' {
+        Some(multiplier)
     } else {
-        num_str.parse::<u64>().unwrap_or(0)
+        multiplier.checked_pow(power)
     };
-
-    base_val * multiplier
+    val.zip(scale)
+        .and_then(|(v, m)| v.checked_mul(m))
+        .ok_or(OffsetError::TooLarge)
 }
 
 fn print_help() {
@@ -691,4 +805,210 @@ mod tests {
         // Hex address radix
         assert!(stdout.contains("000000"));
     }
+
+    #[test]
+    fn test_od_address_radix_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, b"abcdefghijklmnopqrstu").unwrap();
+        let run = |args: &[&str]| {
+            let output = cmd().args(args).arg(&path).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        assert_eq!(
+            run(&["-Ad", "-tx1", "-w10"]),
+            "0000000 61 62 63 64 65 66 67 68 69 6a\n\
+             0000010 6b 6c 6d 6e 6f 70 71 72 73 74\n\
+             0000020 75\n\
+             0000021\n"
+        );
+        assert_eq!(
+            run(&["-A", "x", "-c", "-j", "14"]),
+            "00000e   o   p   q   r   s   t   u\n000015\n"
+        );
+        assert_eq!(run(&["--address-radix", "o", "-N", "0"]), "0000000\n");
+        // -A n drops the offset column and the trailing offset line.
+        assert_eq!(run(&["-An", "-c", "-j", "17"]), "   r   s   t   u\n");
+        // Only the first character of the radix is significant.
+        assert_eq!(run(&["-Axv", "-N", "1", "-c"]), "000000   a\n000001\n");
+
+        let output = cmd().args(["-A", "z"]).arg(&path).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "od: invalid output address radix 'z'; it must be one character from [doxn]\n"
+        );
+        let output = cmd().arg("-A").output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("od: option requires an argument -- 'A'\n")
+        );
+    }
+
+    #[test]
+    fn test_od_window_of_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, b"abcdefghij").unwrap();
+        let output = cmd()
+            .args(["-c", "-j", "3", "-N", "4"])
+            .arg(&path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "0000003   d   e   f   g\n0000007\n"
+        );
+    }
+
+    #[test]
+    fn test_od_skip_past_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, b"abc").unwrap();
+        let output = cmd().args(["-c", "-j", "4"]).arg(&path).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "od: cannot skip past end of combined input\n"
+        );
+        // Skipping exactly to the end is fine.
+        let output = cmd().args(["-c", "-j", "3"]).arg(&path).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "0000003\n");
+    }
+
+    #[test]
+    fn test_od_byte_count_suffixes_and_radix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, vec![b'a'; 2000]).unwrap();
+        // Hex and octal counts, and suffixes in powers of 1024 or 1000.
+        for (skip, offset) in [
+            ("0x10b", "0000413"),
+            ("010", "0000010"),
+            ("2b", "0002000"),
+            ("1k", "0002000"),
+            ("1KiB", "0002000"),
+            ("1kB", "0001750"),
+            (" +3", "0000003"),
+        ] {
+            let output = cmd()
+                .args(["-c", "-N0", "-j", skip])
+                .arg(&path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{}", skip);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                format!("{}\n", offset),
+                "{}",
+                skip
+            );
+        }
+    }
+
+    #[test]
+    fn test_od_invalid_byte_counts() {
+        for (args, err) in [
+            (&["-j", "x"][..], "od: invalid -j argument 'x'\n"),
+            (&["-j", "-1"], "od: invalid -j argument '-1'\n"),
+            (&["-j", "1x"], "od: invalid suffix in -j argument '1x'\n"),
+            (&["-j", "3k "], "od: invalid suffix in -j argument '3k '\n"),
+            (&["-N", "x"], "od: invalid -N argument 'x'\n"),
+            (
+                &["--read-bytes=1bB"],
+                "od: invalid suffix in --read-bytes argument '1bB'\n",
+            ),
+            (&["--skip-bytes="], "od: invalid --skip-bytes argument ''\n"),
+            (
+                &["-j", "99999999999G"],
+                "od: -j argument '99999999999G' too large\n",
+            ),
+            (
+                &["-N", "18446744073709551616"],
+                "od: -N argument '18446744073709551616' too large\n",
+            ),
+            (
+                &["-j", "18446744073709551615", "-N", "1"],
+                "od: skip-bytes + read-bytes is too large\n",
+            ),
+        ] {
+            let output = cmd().args(args).arg("/dev/null").output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert!(output.stdout.is_empty(), "{:?}", args);
+            assert_eq!(String::from_utf8_lossy(&output.stderr), err);
+        }
+    }
+
+    #[test]
+    fn test_od_skip_spans_multiple_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, b"abcdefghij").unwrap();
+        std::fs::write(&b, b"KLMNOP").unwrap();
+        let output = cmd()
+            .args(["-c", "-j", "8", "-N", "5"])
+            .arg(&a)
+            .arg(&b)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "0000010   i   j   K   L   M\n0000015\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_od_named_chars_mask_high_bit() {
+        let mut child = cmd()
+            .args(["-An", "-ta"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b" \t\n\x7f\x80\xc3\xa9")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "  sp  ht  nl del nul   C   )\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_od_multiple_types_in_one_spec() {
+        let mut child = cmd()
+            .args(["-An", "-tca"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"\0\\\xe9").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "  \\0   \\ 351\n nul   \\   i\n"
+        );
+        let output = cmd().args(["-t", "xq"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains("invalid character 'q' in type string 'xq'")
+        );
+    }
 }
@@ -29,7 +29,7 @@ fn parse_args() -> Cli {
         if bytes.starts_with(b"--") {
             let s = arg.to_string_lossy();
             if let Some(val) = s.strip_prefix("--delimiters=") {
-                cli.config.delimiters = paste::parse_delimiters(val);
+                cli.config.delimiters = delimiters_or_exit(val);
             } else {
                 match bytes {
                     b"--delimiters" => {
@@ -37,7 +37,7 @@ fn parse_args() -> Cli {
                             eprintln!("paste: option '--delimiters' requires an argument");
                             process::exit(1);
                         });
-                        cli.config.delimiters = paste::parse_delimiters(&val.to_string_lossy());
+                        cli.config.delimiters = delimiters_or_exit(&val.to_string_lossy());
                     }
                     b"--serial" => cli.config.serial = true,
                     b"--zero-terminated" => cli.config.zero_terminated = true,
@@ -76,7 +76,7 @@ fn parse_args() -> Cli {
                                 .to_string_lossy()
                                 .into_owned()
                         };
-                        cli.config.delimiters = paste::parse_delimiters(&val);
+                        cli.config.delimiters = delimiters_or_exit(&val);
                         break; // consumed rest of arg
                     }
                     's' => cli.config.serial = true,
@@ -97,6 +97,13 @@ fn parse_args() -> Cli {
     cli
 }
 
+fn delimiters_or_exit(list: &str) -> Vec<u8> {
+    paste::parse_delimiters(list).unwrap_or_else(|e| {
+        eprintln!("paste: {}", e);
+        process::exit(1);
+    })
+}
+
 fn print_help() {
     print!(
         "Usage: paste [OPTION]... [FILE]...\n\
@@ -191,7 +198,10 @@ fn main() {
                 Err(e) => {
                     eprintln!("paste: {}: {}", filename, io_error_msg(&e));
                     had_error = true;
-                    file_data.push(coreutils_rs::common::io::FileData::Owned(Vec::new()));
+                    // Serial mode skips the file; parallel mode keeps its column.
+                    if !cli.config.serial {
+                        file_data.push(coreutils_rs::common::io::FileData::Owned(Vec::new()));
+                    }
                 }
             }
         }
@@ -200,11 +210,15 @@ fn main() {
     // Build reference slices
     let data_refs: Vec<&[u8]> = file_data.iter().map(|d| &**d).collect();
 
-    // Build output buffer
-    let output = paste::paste_to_vec(&data_refs, &cli.config);
-
-    // Write output using raw write for minimal syscall overhead
-    if let Err(e) = write_all_raw(&output) {
+    let result = if !cli.config.serial && data_refs.len() == 2 {
+        // Two files: write lines straight from the input buffers.
+        write_two_files(data_refs[0], data_refs[1], &cli.config)
+    } else {
+        // Build output buffer, then write it using raw write for minimal
+        // syscall overhead
+        write_all_raw(&paste::paste_to_vec(&data_refs, &cli.config))
+    };
+    if let Err(e) = result {
         if e.kind() == std::io::ErrorKind::BrokenPipe {
             process::exit(0);
         }
@@ -239,6 +253,24 @@ fn distribute_stdin_lines(data: &[u8], count: usize, terminator: u8) -> Vec<Vec<
     parts
 }
 
+/// Paste two files to stdout with writev. Uses the raw fd so the slices are
+/// not copied again into a buffered stdout.
+#[cfg(unix)]
+fn write_two_files(a: &[u8], b: &[u8], config: &PasteConfig) -> std::io::Result<()> {
+    use std::os::unix::io::FromRawFd;
+    let mut raw = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(1) });
+    paste::paste_two_files(a, b, config, &mut *raw)
+}
+
+#[cfg(not(unix))]
+fn write_two_files(a: &[u8], b: &[u8], config: &PasteConfig) -> std::io::Result<()> {
+    use std::io::Write;
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::with_capacity(256 * 1024, stdout.lock());
+    paste::paste_two_files(a, b, config, &mut out)?;
+    out.flush()
+}
+
 /// Write the full buffer to stdout, retrying on partial/interrupted writes.
 #[cfg(unix)]
 fn write_all_raw(data: &[u8]) -> std::io::Result<()> {
@@ -439,4 +471,88 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(String::from_utf8_lossy(&output.stdout), "1\t2\na\tb\n");
     }
+
+    #[test]
+    fn test_paste_serial_cycles_delimiters_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        let f2 = dir.path().join("b.txt");
+        let empty = dir.path().join("empty.txt");
+        std::fs::write(&f1, "1\n2\n3\n4\n5\n").unwrap();
+        std::fs::write(&f2, "a\nb\nc").unwrap();
+        std::fs::write(&empty, "").unwrap();
+        let output = cmd()
+            .args([
+                "-s",
+                "-d",
+                ",\\0:",
+                f1.to_str().unwrap(),
+                empty.to_str().unwrap(),
+                f2.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1,23:4,5\n\na,bc\n"
+        );
+    }
+
+    #[test]
+    fn test_paste_serial_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let f1 = dir.path().join("a.txt");
+        std::fs::write(&f1, "1\n2\n").unwrap();
+        let missing = dir.path().join("missing");
+        let output = cmd()
+            .args(["-s", missing.to_str().unwrap(), f1.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "1\t2\n");
+
+        let output = cmd()
+            .args(["-s", "-d", "a\\", f1.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "paste: delimiter list ends with an unescaped backslash: a\\\n"
+        );
+    }
+
+    #[test]
+    fn test_paste_two_files_unequal_lengths() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        let paste = |args: &[&str]| {
+            let output = cmd().args(args).arg(&a).arg(&b).output().unwrap();
+            assert!(output.status.success());
+            output.stdout
+        };
+
+        // Short lines are staged; the second file has no final newline.
+        std::fs::write(&a, "1\n2\n3\n").unwrap();
+        std::fs::write(&b, "x\ny").unwrap();
+        assert_eq!(paste(&[]), b"1\tx\n2\ty\n3\t\n");
+        assert_eq!(paste(&["-d", "\\0"]), b"1x\n2y\n3\n");
+        std::fs::write(&a, "1").unwrap();
+        std::fs::write(&b, "x\n\ny\n").unwrap();
+        assert_eq!(paste(&["-d,"]), b"1,x\n,\n,y\n");
+
+        // Long lines are written from the input buffers with writev.
+        let long = "z".repeat(5000);
+        std::fs::write(&a, format!("{long}\n{long}")).unwrap();
+        std::fs::write(&b, format!("{long}\n{long}\n\n{long}")).unwrap();
+        let expected = format!("{long}\t{long}\n{long}\t{long}\n\t\n\t{long}\n");
+        assert_eq!(String::from_utf8(paste(&[])).unwrap(), expected);
+        assert_eq!(
+            String::from_utf8(paste(&["-z", "-d", ""])).unwrap(),
+            format!("{long}\n{long}{long}\n{long}\n\n{long}\0")
+        );
+    }
 }
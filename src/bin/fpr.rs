@@ -81,13 +81,17 @@ fn parse_args() -> Cli {
             } else if let Some(val) = s.strip_prefix("--header=") {
                 cli.config.header = Some(val.to_string());
             } else if let Some(val) = s.strip_prefix("--length=") {
-                cli.config.page_length = val.parse().unwrap_or(pr::DEFAULT_PAGE_LENGTH);
+                cli.config.page_length = parse_count(val, "-l PAGE_LENGTH", "number of lines", 1);
             } else if let Some(val) = s.strip_prefix("--first-line-number=") {
-                cli.config.first_line_number = val.parse().unwrap_or(1);
+                cli.config.first_line_number =
+                    parse_count(val, "-N NUMBER", "starting line number", 0);
             } else if let Some(val) = s.strip_prefix("--indent=") {
-                cli.config.indent = val.parse().unwrap_or(0);
+                cli.config.indent = parse_count(val, "-o MARGIN", "line offset", 0);
+            } else if let Some(val) = s.strip_prefix("--number-lines=") {
+                cli.config.number_lines = Some(parse_number_spec(val));
             } else if let Some(val) = s.strip_prefix("--page-width=") {
-                cli.config.page_width = val.parse().unwrap_or(pr::DEFAULT_PAGE_WIDTH);
+                cli.config.page_width =
+                    parse_count(val, "-W PAGE_WIDTH", "number of characters", 1);
                 cli.config.truncate_lines = true;
             } else if let Some(val) = s.strip_prefix("--separator=") {
                 cli.config.separator = val.chars().next();
@@ -134,10 +138,7 @@ fn parse_args() -> Cli {
                     b'c' => cli.config.show_control_chars = true,
                     b'd' => cli.config.double_space = true,
                     b'D' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.date_format = args[i].clone();
-                        }
+                        cli.config.date_format = option_value(&args, &mut i, j);
                         break;
                     }
                     b'e' => {
@@ -161,10 +162,7 @@ fn parse_args() -> Cli {
                     }
                     b'F' | b'f' => cli.config.form_feed = true,
                     b'h' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.header = Some(args[i].clone());
-                        }
+                        cli.config.header = Some(option_value(&args, &mut i, j));
                         break;
                     }
                     b'i' => {
@@ -187,44 +185,25 @@ fn parse_args() -> Cli {
                     }
                     b'J' => cli.config.join_lines = true,
                     b'l' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.page_length =
-                                args[i].parse().unwrap_or(pr::DEFAULT_PAGE_LENGTH);
-                        }
+                        let val = option_value(&args, &mut i, j);
+                        cli.config.page_length =
+                            parse_count(&val, "-l PAGE_LENGTH", "number of lines", 1);
                         break;
                     }
                     b'm' => cli.config.merge = true,
                     b'n' => {
-                        cli.config.number_lines = Some(('\t', 5));
-                        if j + 1 < bytes.len() {
-                            let rest = &arg[j + 1..];
-                            let mut chars = rest.chars();
-                            if let Some(ch) = chars.next() {
-                                if !ch.is_ascii_digit() {
-                                    let digits_str: String = chars.collect();
-                                    let digits = digits_str.parse().unwrap_or(5);
-                                    cli.config.number_lines = Some((ch, digits));
-                                } else {
-                                    let digits: usize = rest.parse().unwrap_or(5);
-                                    cli.config.number_lines = Some(('\t', digits));
-                                }
-                            }
-                            break;
-                        }
+                        cli.config.number_lines = Some(parse_number_spec(&arg[j + 1..]));
+                        break;
                     }
                     b'N' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.first_line_number = args[i].parse().unwrap_or(1);
-                        }
+                        let val = option_value(&args, &mut i, j);
+                        cli.config.first_line_number =
+                            parse_count(&val, "-N NUMBER", "starting line number", 0);
                         break;
                     }
                     b'o' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.indent = args[i].parse().unwrap_or(0);
-                        }
+                        let val = option_value(&args, &mut i, j);
+                        cli.config.indent = parse_count(&val, "-o MARGIN", "line offset", 0);
                         break;
                     }
                     b'r' => cli.config.no_file_warnings = true,
@@ -251,20 +230,16 @@ fn parse_args() -> Cli {
                     }
                     b'v' => cli.config.show_nonprinting = true,
                     b'w' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.page_width =
-                                args[i].parse().unwrap_or(pr::DEFAULT_PAGE_WIDTH);
-                        }
+                        let val = option_value(&args, &mut i, j);
+                        cli.config.page_width =
+                            parse_count(&val, "-w PAGE_WIDTH", "number of characters", 1);
                         break;
                     }
                     b'W' => {
-                        i += 1;
-                        if i < args.len() {
-                            cli.config.page_width =
-                                args[i].parse().unwrap_or(pr::DEFAULT_PAGE_WIDTH);
-                            cli.config.truncate_lines = true;
-                        }
+                        let val = option_value(&args, &mut i, j);
+                        cli.config.page_width =
+                            parse_count(&val, "-W PAGE_WIDTH", "number of characters", 1);
+                        cli.config.truncate_lines = true;
                         break;
                     }
                     _ => {
@@ -287,6 +262,73 @@ fn parse_args() -> Cli {
     cli
 }
 
+/// Value of the short option at `args[*i][j]`: the rest of that argument if
+/// non-empty, otherwise the next argument (advancing `i`).
+#[cfg(unix)]
+fn option_value(args: &[String], i: &mut usize, j: usize) -> String {
+    let arg = &args[*i];
+    if j + 1 < arg.len() {
+        return arg[j + 1..].to_string();
+    }
+    *i += 1;
+    args.get(*i).cloned().unwrap_or_else(|| {
+        eprintln!(
+            "pr: option requires an argument -- '{}'",
+            arg.as_bytes()[j] as char
+        );
+        eprintln!("Try 'pr --help' for more information.");
+        process::exit(1);
+    })
+}
+
+/// Parse a numeric option argument that must be at least `min`, with GNU's
+/// "'-l PAGE_LENGTH' invalid number of lines: ..." style diagnostics.
+#[cfg(unix)]
+fn parse_count(val: &str, option: &str, what: &str, min: usize) -> usize {
+    let t = val.trim_start();
+    let suffix = match t.strip_prefix('+').unwrap_or(t).parse::<i64>() {
+        Ok(n) if n >= min as i64 && n <= i32::MAX as i64 => return n as usize,
+        Ok(n) if n < min as i64 => ": Numerical result out of range",
+        Ok(_) => ": Value too large for defined data type",
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow
+            ) =>
+        {
+            ": Value too large for defined data type"
+        }
+        Err(_) => "",
+    };
+    eprintln!("pr: '{}' invalid {}: '{}'{}", option, what, val, suffix);
+    process::exit(1);
+}
+
+/// Parse the optional argument of -n/--number-lines: [SEP[DIGITS]].
+#[cfg(unix)]
+fn parse_number_spec(spec: &str) -> (char, usize) {
+    let mut chars = spec.chars();
+    let (sep, digits) = match chars.next() {
+        None => return ('\t', 5),
+        Some(c) if c.is_ascii_digit() => ('\t', spec),
+        Some(c) => (c, chars.as_str()),
+    };
+    if digits.is_empty() {
+        return (sep, 5);
+    }
+    match digits.parse::<usize>() {
+        Ok(n) if n > 0 => (sep, n),
+        _ => {
+            eprintln!(
+                "pr: '-n' extra characters or invalid number in the argument: '{}'",
+                digits
+            );
+            eprintln!("Try 'pr --help' for more information.");
+            process::exit(1);
+        }
+    }
+}
+
 #[cfg(unix)]
 fn print_help() {
     print!(
@@ -589,4 +631,106 @@ mod tests {
         let output = cmd().arg(f.to_str().unwrap()).output().unwrap();
         assert!(output.status.success());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pr_number_lines_across_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("test.txt");
+        let content: String = (1..=30).map(|i| format!("line{}\n", i)).collect();
+        std::fs::write(&f, &content).unwrap();
+        // Body is 10 lines per page; page 2 is skipped but still counted.
+        let output = cmd()
+            .args(["+3", "-n:3", "-l", "20", f.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let body: Vec<&str> = stdout.lines().skip(5).take(2).collect();
+        assert_eq!(body, [" 21:line21", " 22:line22"]);
+
+        // In multi-column mode each column entry is numbered, continuing
+        // from the previous page.
+        let output = cmd()
+            .args([
+                "-2",
+                "-t",
+                "-n:2",
+                "-l",
+                "3",
+                "-w",
+                "20",
+                f.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let rows: Vec<&str> = stdout.lines().skip(2).take(2).collect();
+        assert_eq!(rows, [" 3:line3   6:line6", " 7:line7  10:line10"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pr_header_option_forms() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("test.txt");
+        std::fs::write(&f, "hello\n").unwrap();
+        let output = cmd()
+            .args(["-hTitle", f.to_str().unwrap()])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.lines().nth(2).unwrap().contains(" Title "));
+
+        let output = cmd()
+            .args(["-h", "", f.to_str().unwrap()])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let header = stdout.lines().nth(2).unwrap();
+        assert!(!header.contains("test.txt"));
+        assert!(header.ends_with("Page 1"));
+
+        let output = cmd().arg("-h").output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("pr: option requires an argument -- 'h'\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pr_page_length_and_form_feed() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("test.txt");
+        std::fs::write(&f, "a\nb\n").unwrap();
+        // -F ends each page with a form feed instead of padding it.
+        let output = cmd().args(["-F", f.to_str().unwrap()]).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.ends_with("\n\n\na\nb\n\x0c"), "{stdout:?}");
+
+        for (arg, msg) in [
+            (
+                "0",
+                "pr: '-l PAGE_LENGTH' invalid number of lines: '0': Numerical result out of range\n",
+            ),
+            ("x", "pr: '-l PAGE_LENGTH' invalid number of lines: 'x'\n"),
+        ] {
+            let output = cmd()
+                .args(["-l", arg, f.to_str().unwrap()])
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(String::from_utf8_lossy(&output.stderr), msg);
+        }
+
+        let output = cmd().args(["-n0", f.to_str().unwrap()]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr
+                .starts_with("pr: '-n' extra characters or invalid number in the argument: '0'\n")
+        );
+    }
 }
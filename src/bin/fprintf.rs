@@ -32,11 +32,14 @@ fn print_help() {
     println!("  \\v      vertical tab");
     println!("  \\NNN    byte with octal value NNN (1 to 3 digits)");
     println!("  \\xHH    byte with hexadecimal value HH (1 to 2 digits)");
-    println!("  \\uHHHH  Unicode character with hex value HHHH (1 to 4 digits)");
-    println!("  \\UHHHHHHHH  Unicode character with hex value HHHHHHHH (1 to 8 digits)");
+    println!("  \\uHHHH  Unicode character with hex value HHHH (4 digits)");
+    println!("  \\UHHHHHHHH  Unicode character with hex value HHHHHHHH (8 digits)");
     println!("  %%      a single %");
     println!();
-    println!("  %b      ARGUMENT as a string with '\\' escapes interpreted");
+    println!("  %b      ARGUMENT as a string with '\\' escapes interpreted,");
+    println!("          except that octal escapes are of the form \\0 or \\0NNN");
+    println!("  %(FMT)T ARGUMENT (seconds since the epoch; -1 now, -2 shell start)");
+    println!("          formatted with strftime(3) FMT, default '%a %b %e %H:%M:%S %Z %Y'");
     println!();
     println!("and all C format specifications ending with one of diouxXeEfgGcs.");
     println!();
@@ -219,4 +222,272 @@ mod tests {
         assert!(output.status.success());
         assert_eq!(String::from_utf8_lossy(&output.stdout), "-42");
     }
+
+    #[test]
+    fn test_printf_strftime() {
+        let output = cmd()
+            .args(["%(%Y-%m-%d %H:%M:%S)T\n", "86400", "0", "-1801"])
+            .env("TZ", "UTC")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1970-01-02 00:00:00\n1970-01-01 00:00:00\n1969-12-31 23:29:59\n"
+        );
+    }
+
+    #[test]
+    fn test_printf_strftime_default_format_and_padding() {
+        let output = cmd()
+            .args(["[%()T] [%12.4(%Y%m%d)T]", "0", "0"])
+            .env("TZ", "UTC")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "[Thu Jan  1 00:00:00 UTC 1970] [        1970]"
+        );
+    }
+
+    #[test]
+    fn test_printf_strftime_now() {
+        let before = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        for args in [&["%(%s)T", "-1"][..], &["%(%s)T"][..]] {
+            let output = cmd().args(args).output().unwrap();
+            assert!(output.status.success());
+            let secs: u64 = String::from_utf8_lossy(&output.stdout).parse().unwrap();
+            assert!(secs >= before && secs <= before + 5, "{}", secs);
+        }
+        // -2 (shell start) is never later than now
+        let output = cmd().args(["%(%s)T", "-2"]).output().unwrap();
+        let secs: u64 = String::from_utf8_lossy(&output.stdout).parse().unwrap();
+        assert!(secs <= before + 5);
+    }
+
+    #[test]
+    fn test_printf_invalid_number_diagnostics() {
+        // The value converted so far is printed, with a warning and exit 1
+        let output = cmd()
+            .args(["%d|%d|%i|%.1f|%d\n", "9z", "z", "0x1g", "1.5x", ""])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "9|0|1|1.5|0\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "printf: '9z': value not completely converted\n\
+             printf: 'z': expected a numeric value\n\
+             printf: '0x1g': value not completely converted\n\
+             printf: '1.5x': value not completely converted\n"
+        );
+        let output = cmd()
+            .args([
+                "%d %d %u %g\n",
+                "99999999999999999999",
+                "-0x8000000000000001",
+                "-99999999999999999999",
+                "1e5000",
+            ])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "9223372036854775807 -9223372036854775808 18446744073709551615 inf\n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr)
+                .matches("Numerical result out of range")
+                .count(),
+            4
+        );
+        // Floating-point values are held in an f64, so its limits apply.
+        let output = cmd().args(["%g %e\n", "1e400", "1e-400"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "inf 0.000000e+00\n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "printf: '1e400': Numerical result out of range\n\
+             printf: '1e-400': Numerical result out of range\n"
+        );
+        let output = cmd()
+            .args(["%g %g %g\n", "1.7e308", "2.3e-308", "0x1p1023"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1.7e+308 2.3e-308 8.98847e+307\n"
+        );
+        let output = cmd()
+            .args(["%d %d %u\n", " 12", "-0x8000000000000000", "-1"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "12 -9223372036854775808 18446744073709551615\n"
+        );
+    }
+
+    #[test]
+    fn test_printf_char_constants() {
+        let output = cmd()
+            .env("LC_ALL", "C")
+            .args(["%d %d %d\n", "'a", "\"b", "'ab"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "97 98 97\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "printf: warning: b: character(s) following character constant have been ignored\n"
+        );
+        let output = cmd()
+            .env("LC_ALL", "C")
+            .args(["%d\n", "'\u{e9}"])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "195\n");
+        let output = cmd()
+            .env("LC_ALL", "C.UTF-8")
+            .args(["%d\n", "'\u{e9}"])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "233\n");
+    }
+
+    #[test]
+    fn test_printf_c_and_b_conversions() {
+        // %c prints the first byte; an empty argument gives NUL
+        let output = cmd()
+            .args(["%c|%3c|%-2c|%c|", "hello", "x", "y", ""])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"h|  x|y |\0|");
+        let output = cmd().args(["%c", "\u{e9}"]).output().unwrap();
+        assert_eq!(output.stdout, b"\xc3");
+        // %b takes \0NNN octal; the format string itself takes \NNN
+        let output = cmd()
+            .args(["\\0101|%b|%b|%b", "\\0101\\101", "\\\"\\q\\E", "\\08"])
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"\x081|AA|\"\\q\\E|\x008");
+        // \c in a %b argument stops all output
+        let output = cmd().args(["%b-%s\n", "a\\cb", "x", "y"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"a");
+    }
+
+    #[test]
+    fn test_printf_fatal_errors() {
+        for (args, stdout, stderr) in [
+            (
+                &["ab%bcd", "\\x"][..],
+                "ab",
+                "printf: missing hexadecimal number in escape\n",
+            ),
+            (
+                &["a\\u41"][..],
+                "a",
+                "printf: missing hexadecimal number in escape\n",
+            ),
+            (
+                &["\\ud800"][..],
+                "",
+                "printf: invalid universal character name \\ud800\n",
+            ),
+            (
+                &["x%5bx", "1"][..],
+                "x",
+                "printf: %5b: invalid conversion specification\n",
+            ),
+            (
+                &["%.2c", "1"][..],
+                "",
+                "printf: %.2c: invalid conversion specification\n",
+            ),
+            (
+                &["%#d", "1"][..],
+                "",
+                "printf: %#d: invalid conversion specification\n",
+            ),
+            (
+                &["%z\\n"][..],
+                "",
+                "printf: %z\\: invalid conversion specification\n",
+            ),
+            (
+                &["%"][..],
+                "",
+                "printf: %: invalid conversion specification\n",
+            ),
+            (
+                &["%*d", "99999999999", "1"][..],
+                "",
+                "printf: invalid field width: '99999999999'\n",
+            ),
+            (
+                &["%.*d", "99999999999", "1"][..],
+                "",
+                "printf: invalid precision: '99999999999'\n",
+            ),
+        ] {
+            let output = cmd().args(args).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                stdout,
+                "{:?}",
+                args
+            );
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                stderr,
+                "{:?}",
+                args
+            );
+        }
+        // Length modifiers are accepted and ignored
+        let output = cmd()
+            .args(["%ld %hhd %zu %Lf\n", "1", "2", "3", "4"])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "1 2 3 4.000000\n");
+    }
+
+    #[test]
+    fn test_printf_excess_arguments_and_nonfinite() {
+        let output = cmd().args(["x\\377", "12"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"x\xff");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "printf: warning: ignoring excess arguments, starting with '12'\n"
+        );
+        let output = cmd()
+            .args([
+                "[%05f] [%+f] [%e] [%G] [%g]\n",
+                "inf",
+                "nan",
+                "-inf",
+                "-nan",
+                "1e-310",
+            ])
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "[  inf] [+nan] [-inf] [-NAN] [1e-310]\n"
+        );
+    }
 }
@@ -380,6 +380,39 @@ mod tests {
         assert!(content.iter().all(|&b| b == 0));
     }
 
+    #[test]
+    fn test_shred_size_limits_region() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("header.bin");
+        std::fs::write(&file, "hello world").unwrap();
+
+        // Only the first 2 bytes are overwritten; no rounding or truncation.
+        let output = cmd()
+            .args(["-n", "0", "-z", "-s", "2", file.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read(&file).unwrap(), b"\0\0llo world");
+
+        // A size past the end extends the file to exactly that size.
+        let output = cmd()
+            .args(["-n", "0", "-z", "--size=1KB", file.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::metadata(&file).unwrap().len(), 1000);
+
+        let output = cmd()
+            .args(["-s", "1Kx", file.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "shred: invalid file size: '1Kx'\n"
+        );
+    }
+
     #[test]
     fn test_shred_file_removed() {
         let dir = tempfile::tempdir().unwrap();
@@ -413,6 +446,30 @@ mod tests {
             output
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shred_force_remove_and_open_errors() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("readonly.txt");
+        std::fs::write(&file, "readonly data").unwrap();
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o444)).unwrap();
+        let output = cmd().arg("-fu").arg(&file).output().unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        assert!(!file.exists());
+
+        let output = cmd().arg("-f").arg(&file).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            format!(
+                "shred: {}: failed to open for writing: No such file or directory\n",
+                file.display()
+            )
+        );
+    }
+
     #[test]
     fn test_shred_matches_gnu_behavior() {
         // Both should fail on nonexistent files
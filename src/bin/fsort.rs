@@ -74,14 +74,14 @@ struct Cli {
     month_sort: bool,
     numeric_sort: bool,
     random_sort: bool,
+    random_source: Option<String>,
     reverse: bool,
     version_sort: bool,
     keys: Vec<String>,
     field_separator: Option<String>,
     unique: bool,
     stable: bool,
-    check: Option<String>,
-    check_quiet: bool,
+    check: Option<CheckMode>,
     merge: bool,
     output: Option<String>,
     temp_dir: Option<String>,
@@ -115,6 +115,76 @@ fn take_value(
     }
 }
 
+/// Record a -t value. GNU rejects conflicting separators given more than once.
+fn set_field_separator(slot: &mut Option<String>, val: String) {
+    if slot.as_ref().is_some_and(|prev| *prev != val) {
+        eprintln!("sort: incompatible tabs");
+        process::exit(2);
+    }
+    *slot = Some(val);
+}
+
+/// Record -c/-C/--check; GNU rejects mixing the diagnosing and quiet forms.
+fn set_check(check: &mut Option<CheckMode>, mode: CheckMode) {
+    if check.is_some_and(|c| c != mode) {
+        eprintln!("sort: options '-cC' are incompatible");
+        process::exit(2);
+    }
+    *check = Some(mode);
+}
+
+/// Parse the --check argument, accepting unambiguous abbreviations.
+fn parse_check_arg(val: &str) -> CheckMode {
+    const CHECK_ARGS: [(&str, CheckMode); 3] = [
+        ("quiet", CheckMode::Quiet),
+        ("silent", CheckMode::Quiet),
+        ("diagnose-first", CheckMode::Diagnose),
+    ];
+    if let Some(&(_, mode)) = CHECK_ARGS.iter().find(|(name, _)| *name == val) {
+        return mode;
+    }
+    let mut matches = CHECK_ARGS
+        .iter()
+        .filter(|(name, _)| name.starts_with(val))
+        .map(|&(_, mode)| mode);
+    let first = matches.next();
+    let problem = match first {
+        Some(mode) if !val.is_empty() && matches.all(|m| m == mode) => return mode,
+        Some(_) => "ambiguous",
+        None => "invalid",
+    };
+    eprintln!("sort: {} argument '{}' for '--check'", problem, val);
+    eprintln!("Valid arguments are:\n  - 'quiet', 'silent'\n  - 'diagnose-first'");
+    eprintln!("Try 'sort --help' for more information.");
+    process::exit(1);
+}
+
+/// Parse the --sort argument, accepting unambiguous abbreviations, and
+/// return the equivalent short option letter.
+fn parse_sort_arg(val: &str) -> u8 {
+    const SORT_ARGS: [(&str, u8); 6] = [
+        ("general-numeric", b'g'),
+        ("human-numeric", b'h'),
+        ("month", b'M'),
+        ("numeric", b'n'),
+        ("random", b'R'),
+        ("version", b'V'),
+    ];
+    let mut matches = SORT_ARGS.iter().filter(|(name, _)| name.starts_with(val));
+    let problem = match (matches.next(), matches.next()) {
+        (Some(&(_, flag)), None) => return flag,
+        _ if val.is_empty() => "ambiguous",
+        _ => "invalid",
+    };
+    eprintln!("sort: {} argument '{}' for '--sort'", problem, val);
+    eprintln!("Valid arguments are:");
+    for (name, _) in SORT_ARGS {
+        eprintln!("  - '{}'", name);
+    }
+    eprintln!("Try 'sort --help' for more information.");
+    process::exit(1);
+}
+
 /// Hand-rolled argument parser — eliminates clap's ~200-300µs initialization.
 fn parse_args() -> Cli {
     let mut cli = Cli {
@@ -127,6 +197,7 @@ fn parse_args() -> Cli {
         month_sort: false,
         numeric_sort: false,
         random_sort: false,
+        random_source: None,
         reverse: false,
         version_sort: false,
         keys: Vec::new(),
@@ -134,7 +205,6 @@ fn parse_args() -> Cli {
         unique: false,
         stable: false,
         check: None,
-        check_quiet: false,
         merge: false,
         output: None,
         temp_dir: None,
@@ -188,7 +258,8 @@ fn parse_args() -> Cli {
                 "zero-terminated" => cli.zero_terminated = true,
                 "debug" => cli.debug = true,
                 "check" => {
-                    cli.check = Some(eq_val.unwrap_or("diagnose").to_string());
+                    let mode = eq_val.map_or(CheckMode::Diagnose, parse_check_arg);
+                    set_check(&mut cli.check, mode);
                 }
                 "key" => {
                     let val = eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
@@ -203,18 +274,16 @@ fn parse_args() -> Cli {
                     cli.keys.push(val);
                 }
                 "field-separator" => {
-                    cli.field_separator =
-                        Some(eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
-                            args.next()
-                                .unwrap_or_else(|| {
-                                    eprintln!(
-                                        "sort: option '--field-separator' requires an argument"
-                                    );
-                                    process::exit(2);
-                                })
-                                .to_string_lossy()
-                                .into_owned()
-                        }));
+                    let val = eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
+                        args.next()
+                            .unwrap_or_else(|| {
+                                eprintln!("sort: option '--field-separator' requires an argument");
+                                process::exit(2);
+                            })
+                            .to_string_lossy()
+                            .into_owned()
+                    });
+                    set_field_separator(&mut cli.field_separator, val);
                 }
                 "output" => {
                     cli.output = Some(eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
@@ -227,6 +296,17 @@ fn parse_args() -> Cli {
                             .into_owned()
                     }));
                 }
+                "random-source" => {
+                    cli.random_source = Some(eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
+                        args.next()
+                            .unwrap_or_else(|| {
+                                eprintln!("sort: option '--random-source' requires an argument");
+                                process::exit(2);
+                            })
+                            .to_string_lossy()
+                            .into_owned()
+                    }));
+                }
                 "temporary-directory" => {
                     cli.temp_dir = Some(eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
                         args.next()
@@ -250,10 +330,15 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     });
-                    cli.parallel = Some(val.parse().unwrap_or_else(|_| {
-                        eprintln!("sort: invalid number of parallel jobs: '{}'", val);
+                    let n: usize = val.parse().unwrap_or_else(|_| {
+                        eprintln!("sort: invalid --parallel argument '{}'", val);
                         process::exit(2);
-                    }));
+                    });
+                    if n == 0 {
+                        eprintln!("sort: number in parallel must be nonzero");
+                        process::exit(2);
+                    }
+                    cli.parallel = Some(n);
                 }
                 "buffer-size" => {
                     cli.buffer_size = Some(eq_val.map(|v| v.to_string()).unwrap_or_else(|| {
@@ -276,17 +361,13 @@ fn parse_args() -> Cli {
                             .to_string_lossy()
                             .into_owned()
                     });
-                    match val.as_str() {
-                        "general-numeric" => cli.general_numeric = true,
-                        "human-numeric" => cli.human_numeric = true,
-                        "month" => cli.month_sort = true,
-                        "numeric" => cli.numeric_sort = true,
-                        "random" => cli.random_sort = true,
-                        "version" => cli.version_sort = true,
-                        _ => {
-                            eprintln!("sort: unknown sort type: '{}'", val);
-                            process::exit(2);
-                        }
+                    match parse_sort_arg(&val) {
+                        b'g' => cli.general_numeric = true,
+                        b'h' => cli.human_numeric = true,
+                        b'M' => cli.month_sort = true,
+                        b'n' => cli.numeric_sort = true,
+                        b'R' => cli.random_sort = true,
+                        _ => cli.version_sort = true,
                     }
                 }
                 "help" => {
@@ -304,11 +385,15 @@ fn parse_args() -> Cli {
                          \x20 -h, --human-numeric-sort     compare human readable numbers (e.g., 2K 1G)\n\
                          \x20 -n, --numeric-sort           compare according to string numerical value\n\
                          \x20 -R, --random-sort            shuffle, but group identical keys\n\
+                         \x20     --random-source=FILE     get random bytes from FILE\n\
                          \x20 -r, --reverse                reverse the result of comparisons\n\
+                         \x20     --sort=WORD              sort according to WORD:\n\
+                         \x20                                general-numeric -g, human-numeric -h, month -M,\n\
+                         \x20                                numeric -n, random -R, version -V\n\
                          \x20 -V, --version-sort           natural sort of (version) numbers within text\n\n\
                          Other options:\n\
-                         \x20 -c, --check                  check for sorted input; do not sort\n\
-                         \x20 -C                           like -c, but do not report first bad line\n\
+                         \x20 -c, --check, --check=diagnose-first  check for sorted input; do not sort\n\
+                         \x20 -C, --check=quiet, --check=silent  like -c, but do not report first bad line\n\
                          \x20 -k, --key=KEYDEF             sort via a key; KEYDEF gives location and type\n\
                          \x20 -m, --merge                  merge already sorted files; do not sort\n\
                          \x20 -o, --output=FILE            write result to FILE instead of standard output\n\
@@ -320,7 +405,10 @@ fn parse_args() -> Cli {
                          \x20 -z, --zero-terminated        line delimiter is NUL, not newline\n\
                          \x20     --parallel=N             change the number of sorts run concurrently to N\n\
                          \x20     --help                   display this help and exit\n\
-                         \x20     --version                output version information and exit\n"
+                         \x20     --version                output version information and exit\n\n\
+                         Without --random-source, a shuffle is keyed by the FSORT_RANDOM_SEED\n\
+                         environment variable when it holds an unsigned integer, so that the\n\
+                         same seed repeats the same order.\n"
                     );
                     process::exit(0);
                 }
@@ -354,10 +442,8 @@ fn parse_args() -> Cli {
                     b's' => cli.stable = true,
                     b'm' => cli.merge = true,
                     b'z' => cli.zero_terminated = true,
-                    b'c' => {
-                        cli.check = Some("diagnose".to_string());
-                    }
-                    b'C' => cli.check_quiet = true,
+                    b'c' => set_check(&mut cli.check, CheckMode::Diagnose),
+                    b'C' => set_check(&mut cli.check, CheckMode::Quiet),
                     b'k' => {
                         let val = take_value(bytes, i + 1, &mut args, "k");
                         cli.keys.push(val);
@@ -365,7 +451,7 @@ fn parse_args() -> Cli {
                     }
                     b't' => {
                         let val = take_value(bytes, i + 1, &mut args, "t");
-                        cli.field_separator = Some(val);
+                        set_field_separator(&mut cli.field_separator, val);
                         break;
                     }
                     b'o' => {
@@ -437,9 +523,16 @@ fn main() {
 
     let cli = parse_args();
 
-    // Validate -c and -C are not combined
-    if cli.check.is_some() && cli.check_quiet {
-        eprintln!("sort: options '-cC' are incompatible");
+    // Checking reads a single input
+    if let Some(mode) = cli.check
+        && cli.files.len() > 1
+    {
+        let opt = if mode == CheckMode::Quiet { 'C' } else { 'c' };
+        eprintln!(
+            "sort: extra operand '{}' not allowed with -{}",
+            cli.files[1], opt
+        );
+        eprintln!("Try 'sort --help' for more information.");
         process::exit(2);
     }
 
@@ -457,7 +550,10 @@ fn main() {
 
     // Parse field separator
     let separator = cli.field_separator.as_ref().map(|s| {
-        if s.len() == 1 {
+        if s.is_empty() {
+            eprintln!("sort: empty tab");
+            process::exit(2);
+        } else if s.len() == 1 {
             s.as_bytes()[0]
         } else if s == "\\0" {
             b'\0'
@@ -490,17 +586,7 @@ fn main() {
         process::exit(2);
     }
 
-    // Determine check mode
-    let check = if cli.check_quiet {
-        CheckMode::Quiet
-    } else if let Some(ref val) = cli.check {
-        match val.as_str() {
-            "quiet" | "silent" => CheckMode::Quiet,
-            _ => CheckMode::Diagnose,
-        }
-    } else {
-        CheckMode::None
-    };
+    let check = cli.check.unwrap_or(CheckMode::None);
 
     // Parse buffer size
     let buffer_size = cli.buffer_size.as_ref().map(|s| {
@@ -510,7 +596,27 @@ fn main() {
         })
     });
 
-    let random_seed = if cli.random_sort {
+    let any_random = cli.random_sort || keys.iter().any(|k| k.opts.random);
+    let random_seed = if let (true, Some(path)) = (any_random, cli.random_source.as_deref()) {
+        coreutils_rs::sort::read_random_seed(path).unwrap_or_else(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                eprintln!("sort: '{}': end of file", path);
+            } else {
+                eprintln!("sort: open failed: {}: {}", path, io_error_msg(&e));
+            }
+            process::exit(2);
+        })
+    } else if let (true, Some(val)) = (any_random, std::env::var_os("FSORT_RANDOM_SEED")) {
+        val.to_str()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "sort: invalid FSORT_RANDOM_SEED value: '{}'",
+                    val.to_string_lossy()
+                );
+                process::exit(2);
+            })
+    } else if any_random {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_nanos() as u64)
@@ -555,34 +661,18 @@ fn main() {
             );
         }
 
-        // GNU --debug diagnostics: warn about keys that span multiple fields
-        // Note: GNU only warns for n, g, h — NOT for M (month sort)
-        for (i, key_def) in config.keys.iter().enumerate() {
-            let has_numeric_type = key_def.opts.numeric
-                || key_def.opts.general_numeric
-                || key_def.opts.human_numeric
-                || config.global_opts.numeric
-                || config.global_opts.general_numeric
-                || config.global_opts.human_numeric;
-            let spans_multiple = key_def.end_field == 0
-                || key_def.end_field > key_def.start_field
-                || (key_def.end_field == key_def.start_field && key_def.end_char == 0);
-            if has_numeric_type && spans_multiple {
-                eprintln!("sort: key {} is numeric and spans multiple fields", i + 1);
-            }
-        }
-
-        // GNU --debug: warn about decimal point for numeric sorts
-        let has_numeric = config.keys.iter().any(|k| {
-            k.opts.numeric
-                || k.opts.general_numeric
-                || k.opts.human_numeric
-                || config.global_opts.numeric
-                || config.global_opts.general_numeric
-                || config.global_opts.human_numeric
-        });
-        if has_numeric {
-            eprintln!("sort: numbers use \u{2018}.\u{2019} as a decimal point in this locale");
+        let utf8 = {
+            let upper = locale_name.to_ascii_uppercase();
+            upper.contains("UTF-8") || upper.contains("UTF8")
+        };
+        for warning in coreutils_rs::sort::key_warnings(
+            &config.keys,
+            &config.global_opts,
+            config.separator,
+            config.stable || config.unique,
+            utf8,
+        ) {
+            eprintln!("sort: {}", warning);
         }
     }
 
@@ -745,6 +835,92 @@ mod tests {
     }
 
     #[cfg(unix)]
+    #[test]
+    fn test_sort_stable_keeps_input_order_on_every_path() {
+        // Small inputs take the comparison sorts; large ones the radix and
+        // parallel paths. Equal keys must stay in input order on all of them.
+        const KEYS: [&str; 6] = ["10", "9", "1.5", "-0", "0", "b"];
+        let dir = tempfile::tempdir().unwrap();
+        for n in [12, 20_000] {
+            let lines: Vec<String> = (0..n)
+                .map(|i| format!("{} {:05}", KEYS[i * 7 % KEYS.len()], i))
+                .collect();
+            let p = dir.path().join(format!("in{}", n));
+            std::fs::write(&p, lines.join("\n") + "\n").unwrap();
+            let key = |l: &String| l.split(' ').next().unwrap().to_string();
+            let num = |l: &String| key(l).parse::<f64>().unwrap_or(0.0);
+
+            let cases: [(&[&str], bool, bool); 6] = [
+                (&["-s"], false, false),
+                (&["-s", "-k1,1"], false, false),
+                (&["-s", "-k1,1", "-r"], false, true),
+                (&["-s", "-n"], true, false),
+                (&["-s", "-n", "-r"], true, true),
+                (&["-s", "-k1,1nr"], true, true),
+            ];
+            for (args, numeric, reverse) in cases {
+                let mut expected = lines.clone();
+                expected.sort_by(|a, b| {
+                    let ord = if numeric {
+                        num(a).partial_cmp(&num(b)).unwrap()
+                    } else if args.len() > 1 {
+                        key(a).cmp(&key(b))
+                    } else {
+                        a.cmp(b)
+                    };
+                    if reverse { ord.reverse() } else { ord }
+                });
+                let output = cmd()
+                    .args(args)
+                    .arg(&p)
+                    .env("LC_ALL", "C")
+                    .output()
+                    .unwrap();
+                assert!(output.status.success());
+                assert_eq!(
+                    String::from_utf8_lossy(&output.stdout),
+                    expected.join("\n") + "\n",
+                    "{:?} with {} lines",
+                    args,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_stable_unique_and_multiple_keys() {
+        let input = "b 2 x\na 1 y\nb 1 z\na 1 w\nb 2 v\n";
+        let run = |args: &[&str]| {
+            let mut child = cmd()
+                .args(args)
+                .env("LC_ALL", "C")
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(input.as_bytes())
+                .unwrap();
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+        // -u keeps the first line read of each run of equal keys, with or
+        // without -s.
+        assert_eq!(run(&["-s", "-u", "-k1,2"]), "a 1 y\nb 1 z\nb 2 x\n");
+        assert_eq!(run(&["-u", "-k1,1"]), "a 1 y\nb 2 x\n");
+        assert_eq!(
+            run(&["-s", "-k1,1", "-k2,2nr"]),
+            "a 1 y\na 1 w\nb 2 x\nb 2 v\nb 1 z\n"
+        );
+        // Without -s, a global -r also reverses the last-resort comparison.
+        assert_eq!(run(&["-r", "-k1,1"]), "b 2 x\nb 2 v\nb 1 z\na 1 y\na 1 w\n");
+    }
+
     #[test]
     fn test_sort_numeric_reverse() {
         let mut child = cmd()
@@ -866,6 +1042,44 @@ mod tests {
         assert_eq!(result, "a\nb\nc\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_output_file_is_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        // Large enough that the input would be memory-mapped.
+        let data: String = (0..50_000).rev().map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&input, &data).unwrap();
+        let output = cmd()
+            .arg("-n")
+            .arg("-o")
+            .arg(&input)
+            .arg(&input)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let expected: String = (0..50_000).map(|i| format!("{}\n", i)).collect();
+        assert_eq!(std::fs::read_to_string(&input).unwrap(), expected);
+
+        // A hard link to the output counts as the same file; merge reads it first too.
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        let link = dir.path().join("link");
+        std::fs::write(&a, "1\n3\n5\n").unwrap();
+        std::fs::write(&b, "2\n4\n6\n").unwrap();
+        std::fs::hard_link(&a, &link).unwrap();
+        let output = cmd()
+            .arg("-m")
+            .arg("-o")
+            .arg(&link)
+            .arg(&a)
+            .arg(&b)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "1\n2\n3\n4\n5\n6\n");
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_sort_single_line() {
@@ -903,10 +1117,520 @@ mod tests {
         );
     }
 
+    fn sort_with(args: &[&str], input: &[u8]) -> std::process::Output {
+        let mut child = cmd()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // sort may exit on an error before reading its input.
+        let _ = child.stdin.take().unwrap().write_all(input);
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_sort_blank_fields_include_leading_blanks() {
+        // Without -t, a field includes the blanks that precede it
+        let out = sort_with(&["-k2,2"], b"y a\nx  b\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "x  b\ny a\n");
+        // -b skips them
+        let out = sort_with(&["-k2b,2"], b"x  b\ny a\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "y a\nx  b\n");
+        // Character positions count from the start of those blanks
+        let out = sort_with(&["-k2.2,2.2"], b"z c\ny   a\nx  b\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "x  b\ny   a\nz c\n");
+    }
+
+    #[test]
+    fn test_sort_char_offset_beyond_field() {
+        // Character offsets may run past the end of the field, up to the
+        // end of the line, as in GNU sort.
+        let out = sort_with(&["-k1.2,1.2"], b"  b 2\n a 1\nc  0\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "  b 2\nc  0\n a 1\n");
+    }
+
+    #[test]
+    fn test_sort_separator_not_part_of_field() {
+        // With -t, blanks are ordinary field content and the separator
+        // itself belongs to no field.
+        let out = sort_with(&["-t", ":", "-k2"], b"x:\tb\ny: a\nz:c\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "x:\tb\ny: a\nz:c\n");
+        let out = sort_with(&["-t", ":", "-k2b"], b"x:\tb\ny: a\nz:c\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "y: a\nx:\tb\nz:c\n");
+        // An empty field yields an empty key
+        let out = sort_with(&["-t", ":", "-k2,2"], b"a:b\nc:\nb::a\n");
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "b::a\nc:\na:b\n");
+    }
+
+    #[test]
+    fn test_sort_tab_errors() {
+        let out = sort_with(&["-t", ""], b"");
+        assert_eq!(out.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("empty tab"));
+        let out = sort_with(&["-t", "ab"], b"");
+        assert_eq!(out.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("multi-character tab"));
+        let out = sort_with(&["-t", ":", "-t", ","], b"");
+        assert_eq!(out.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("incompatible tabs"));
+        let out = sort_with(&["-t", ":", "-t", ":"], b"a\n");
+        assert!(out.status.success());
+    }
+
+    #[test]
+    fn test_sort_key_spec_errors() {
+        let cases = [
+            ("0", "field number is zero: invalid field specification '0'"),
+            (
+                "2.0",
+                "character offset is zero: invalid field specification '2.0'",
+            ),
+            (
+                "1,0",
+                "field number is zero: invalid field specification '1,0'",
+            ),
+            (
+                ".3",
+                "invalid number at field start: invalid count at start of '.3'",
+            ),
+            (
+                "2,x",
+                "invalid number after ',': invalid count at start of 'x'",
+            ),
+            (
+                "1,1.x",
+                "invalid number after '.': invalid count at start of 'x'",
+            ),
+            (
+                "2n,3x",
+                "stray character in field spec: invalid field specification '2n,3x'",
+            ),
+        ];
+        for (spec, msg) in cases {
+            let out = sort_with(&["-k", spec], b"");
+            assert_eq!(out.status.code(), Some(2), "{}", spec);
+            assert_eq!(
+                String::from_utf8_lossy(&out.stderr),
+                format!("sort: {}\n", msg)
+            );
+        }
+        // A zero end character offset means the end of the field.
+        let out = sort_with(&["-k", "1.2,1.0"], b"ab\nba\naa\n");
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "aa\nba\nab\n");
+    }
+
+    #[test]
+    fn test_sort_debug_key_warnings() {
+        let warnings = |args: &[&str]| {
+            let mut cmd = cmd();
+            cmd.arg("--debug").args(args).env("LC_ALL", "C");
+            let out = cmd.stdin(Stdio::null()).output().unwrap();
+            assert!(out.status.success(), "{:?}", args);
+            String::from_utf8_lossy(&out.stderr)
+                .strip_prefix("sort: text ordering performed using simple byte comparison\n")
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            warnings(&["-k2"]),
+            "sort: leading blanks are significant in key 1; consider also specifying 'b'\n"
+        );
+        assert_eq!(warnings(&["-k2b"]), "");
+        assert_eq!(
+            warnings(&["-k3,2", "-k2n"]),
+            "sort: key 1 has zero width and will be ignored\n\
+             sort: key 2 is numeric and spans multiple fields\n\
+             sort: note numbers use '.' as a decimal point in this locale\n"
+        );
+        assert_eq!(
+            warnings(&["-t.", "-k1n"]),
+            "sort: key 1 is numeric and spans multiple fields\n\
+             sort: field separator '.' is treated as a decimal point in numbers\n"
+        );
+        assert_eq!(
+            warnings(&["-fbdr", "-k1,1n"]),
+            "sort: note numbers use '.' as a decimal point in this locale\n\
+             sort: options '-bdf' are ignored\n\
+             sort: option '-r' only applies to last-resort comparison\n"
+        );
+        assert_eq!(
+            warnings(&["-rs", "-k1,1n"]),
+            "sort: note numbers use '.' as a decimal point in this locale\n\
+             sort: option '-r' is ignored\n"
+        );
+        // A key without options inherits the global ones; no key at all
+        // leaves nothing to warn about.
+        assert_eq!(warnings(&["-b", "-k2"]), "");
+        assert_eq!(warnings(&["-rs"]), "");
+    }
+
+    #[test]
+    fn test_sort_random_source_reproducible() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::write(&source, b"0123456789abcdef").unwrap();
+        let arg = format!("--random-source={}", source.display());
+        let input: String = (0..50).map(|i| format!("{}\n", i % 7)).collect();
+        let first = sort_with(&["-R", &arg], input.as_bytes());
+        let second = sort_with(&["-R", &arg], input.as_bytes());
+        assert!(first.status.success());
+        assert_eq!(first.stdout, second.stdout);
+        // Identical lines are contiguous: each value forms a single run
+        let lines: Vec<&[u8]> = first.stdout.split(|&b| b == b'\n').collect();
+        let runs = lines.windows(2).filter(|w| w[0] != w[1]).count();
+        assert_eq!(runs, 7);
+    }
+
+    #[test]
+    fn test_sort_random_groups_by_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::write(&source, b"fedcba9876543210").unwrap();
+        let arg = format!("--random-source={}", source.display());
+        let input: String = (0..40).map(|i| format!("{} {}\n", i % 4, i)).collect();
+        let out = sort_with(&["-k1,1R", &arg], input.as_bytes());
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let keys: Vec<&str> = stdout.lines().map(|l| &l[..1]).collect();
+        let runs = keys.windows(2).filter(|w| w[0] != w[1]).count();
+        assert_eq!(runs, 3, "keys not grouped: {:?}", keys);
+    }
+
+    #[test]
+    fn test_sort_random_seed_env() {
+        let input: String = (0..50).map(|i| format!("{}\n", i)).collect();
+        let shuffle = |seed: &str, args: &[&str]| {
+            let mut child = cmd()
+                .args(args)
+                .env("FSORT_RANDOM_SEED", seed)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap();
+            let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+            child.wait_with_output().unwrap()
+        };
+        let first = shuffle("42", &["-R"]);
+        assert!(first.status.success());
+        assert_eq!(first.stdout, shuffle("42", &["--sort=random"]).stdout);
+        assert_eq!(first.stdout, shuffle("42", &["-k1,1R"]).stdout);
+        assert_ne!(first.stdout, shuffle("43", &["-R"]).stdout);
+        let mut lines: Vec<&[u8]> = first.stdout.split(|&b| b == b'\n').collect();
+        lines.sort();
+        assert_eq!(lines.len(), 51);
+
+        // --random-source takes precedence over the environment.
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::write(&source, b"0123456789abcdef").unwrap();
+        let arg = format!("--random-source={}", source.display());
+        assert_eq!(
+            shuffle("42", &["-R", &arg]).stdout,
+            shuffle("43", &["-R", &arg]).stdout
+        );
+
+        let out = shuffle("x", &["-R"]);
+        assert_eq!(out.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&out.stderr),
+            "sort: invalid FSORT_RANDOM_SEED value: 'x'\n"
+        );
+        // Without a random sort the variable is not looked at.
+        assert!(shuffle("x", &["-n"]).status.success());
+    }
+
+    #[test]
+    fn test_sort_word_argument() {
+        let input = b"v1.10\nv1.9\nv1.2\n";
+        assert_eq!(
+            sort_with(&["--sort=version"], input).stdout,
+            sort_with(&["-V"], input).stdout
+        );
+        assert_eq!(
+            sort_with(&["--sort=v"], input).stdout,
+            b"v1.2\nv1.9\nv1.10\n"
+        );
+        assert_eq!(
+            sort_with(&["--sort", "human"], b"2K\n1M\n3\n").stdout,
+            b"3\n2K\n1M\n"
+        );
+
+        let out = sort_with(&["--sort=numeric", "-V"], b"1\n");
+        assert_eq!(out.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&out.stderr),
+            "sort: options '-nV' are incompatible\n"
+        );
+        let out = sort_with(&["-R", "--sort=n"], b"1\n");
+        assert_eq!(
+            String::from_utf8_lossy(&out.stderr),
+            "sort: options '-nR' are incompatible\n"
+        );
+
+        let out = sort_with(&["--sort=bogus"], b"1\n");
+        assert_eq!(out.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&out.stderr),
+            "sort: invalid argument 'bogus' for '--sort'\nValid arguments are:\n  \
+             - 'general-numeric'\n  - 'human-numeric'\n  - 'month'\n  - 'numeric'\n  \
+             - 'random'\n  - 'version'\nTry 'sort --help' for more information.\n"
+        );
+        let out = sort_with(&["--sort="], b"1\n");
+        assert!(
+            String::from_utf8_lossy(&out.stderr)
+                .starts_with("sort: ambiguous argument '' for '--sort'\n")
+        );
+    }
+
+    #[test]
+    fn test_sort_random_source_too_short() {
+        let out = sort_with(&["-R", "--random-source=/dev/null"], b"a\n");
+        assert_eq!(out.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("end of file"));
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_sort_nonexistent_file() {
         let output = cmd().arg("/nonexistent_xyz_sort").output().unwrap();
         assert!(!output.status.success());
     }
+
+    fn check_with(args: &[&str], input: &[u8]) -> std::process::Output {
+        let mut child = cmd()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // sort may exit on an error before reading its input.
+        let _ = child.stdin.take().unwrap().write_all(input);
+        child.wait_with_output().unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_check_quiet() {
+        for args in [
+            &["-C"][..],
+            &["--check=quiet"],
+            &["--check=silent"],
+            &["-C", "--check=q"],
+        ] {
+            let output = check_with(args, b"b\na\n");
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert!(output.stderr.is_empty(), "{:?}", args);
+            assert!(output.stdout.is_empty());
+            let output = check_with(args, b"a\nb\n");
+            assert_eq!(output.status.code(), Some(0), "{:?}", args);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_check_diagnose() {
+        for args in [&["-c"][..], &["--check"], &["--check=diagnose-first"]] {
+            let output = check_with(args, b"a\nc\nb\nd\n");
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "sort: -:3: disorder: b\n"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_check_unique_flags_equal_keys() {
+        let output = check_with(&["-cu"], b"a\na\n");
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "sort: -:2: disorder: a\n"
+        );
+        // Key-equal under -f counts as a duplicate too
+        let output = check_with(&["-Cuf"], b"a\nA\n");
+        assert_eq!(output.status.code(), Some(1));
+        let output = check_with(&["-c"], b"a\na\n");
+        assert_eq!(output.status.code(), Some(0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_check_usage_errors() {
+        let output = check_with(&["-c", "--check=quiet"], b"");
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "sort: options '-cC' are incompatible\n"
+        );
+        let output = check_with(&["-C", "a", "b"], b"");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("sort: extra operand 'b' not allowed with -C\n")
+        );
+        let output = check_with(&["--check=x"], b"");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("sort: invalid argument 'x' for '--check'\n")
+        );
+    }
+
+    #[test]
+    fn test_sort_parallel_thread_counts() {
+        // Enough lines to take the parallel sort path.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("in.txt");
+        let mut lines: Vec<String> = (0..30_000u64)
+            .map(|i| format!("{:x}", i.wrapping_mul(2_654_435_761) % 1_000_003))
+            .collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        lines.sort();
+        let expected = lines.join("\n") + "\n";
+        let p = path.to_str().unwrap();
+
+        for args in [["--parallel=1", p], ["--parallel=3", p]] {
+            let output = cmd().args(args).env("LC_ALL", "C").output().unwrap();
+            assert!(output.status.success());
+            assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+        }
+        let output = cmd()
+            .arg(p)
+            .env("OMP_NUM_THREADS", "1")
+            .env("LC_ALL", "C")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+    }
+
+    #[test]
+    fn test_sort_parallel_huge_count() {
+        // Thread counts beyond the CPUs are capped rather than all started.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("in.txt");
+        std::fs::write(&path, "d\nb\nc\na\n").unwrap();
+        let start = std::time::Instant::now();
+        let output = cmd()
+            .args(["--parallel=100000", path.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"a\nb\nc\nd\n");
+        let out = sort_with(&["--parallel=100000"], b"d\nb\nc\na\n");
+        assert!(out.status.success());
+        assert_eq!(out.stdout, b"a\nb\nc\nd\n");
+        let mut child = cmd()
+            .env("OMP_NUM_THREADS", "100000")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"y\nx\n").unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success());
+        assert_eq!(out.stdout, b"x\ny\n");
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(10),
+            "{:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_sort_parallel_invalid() {
+        let output = cmd().args(["--parallel=0", "/dev/null"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "sort: number in parallel must be nonzero\n"
+        );
+        let output = cmd().args(["--parallel=x", "/dev/null"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "sort: invalid --parallel argument 'x'\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_zero_terminated_embedded_newlines() {
+        // Records are split on NUL only; embedded newlines stay in the record.
+        let out = sort_with(&["-z"], b"b\nx\0a\ny\0");
+        assert_eq!(out.stdout, b"a\ny\0b\nx\0");
+        // Without -t, a newline separates fields like a blank.
+        let out = sort_with(&["-z", "-k2,2"], b"1\nb\x002\na\0");
+        assert_eq!(out.stdout, b"2\na\x001\nb\0");
+        // With -t, a newline is ordinary field content.
+        let out = sort_with(&["-z", "-t:", "-k2"], b"x:b\nz\0y:b\na\0");
+        assert_eq!(out.stdout, b"y:b\na\0x:b\nz\0");
+        // Numbers may be preceded by newlines, which count as blanks.
+        let out = sort_with(&["-z", "-n"], b"10\0 \n2\0\n\n\0");
+        assert_eq!(out.stdout, b"\n\n\0 \n2\x0010\0");
+        // The disorder report echoes the record with its NUL terminator.
+        let out = sort_with(&["-z", "-c"], b"a\nb\0a\na\0");
+        assert_eq!(out.status.code(), Some(1));
+        assert_eq!(out.stderr, b"sort: -:2: disorder: a\na\0");
+
+        // Large enough for the parallel line scan.
+        let n = 300_000;
+        let input: Vec<u8> = (0..n)
+            .rev()
+            .flat_map(|i| format!("{:06}\nrecord\0", i).into_bytes())
+            .collect();
+        let expected: Vec<u8> = (0..n)
+            .flat_map(|i| format!("{:06}\nrecord\0", i).into_bytes())
+            .collect();
+        let out = sort_with(&["-z"], &input);
+        assert!(out.status.success());
+        assert!(out.stdout == expected);
+    }
+
+    #[test]
+    fn test_sort_dictionary_and_nonprinting() {
+        let stdout = |args: &[&str], input: &[u8]| sort_with(args, input).stdout;
+        // -d compares only blanks and alphanumerics: "a-b" ties with "ab"
+        // and the whole-line comparison breaks the tie.
+        assert_eq!(
+            stdout(&["-d"], b"ab\n-ac\na-b\naa\n"),
+            b"aa\na-b\nab\n-ac\n"
+        );
+        assert_eq!(stdout(&["-t:", "-k2d"], b"1:a-c\n2:ab\n"), b"2:ab\n1:a-c\n");
+        // -i ignores nonprinting bytes; with -d as well, -d wins and the
+        // tab still counts.
+        assert_eq!(stdout(&["-i"], b"b\x01b\nbc\nba\n"), b"ba\nb\x01b\nbc\n");
+        assert_eq!(stdout(&["-i"], b"x\tb\nxa\n"), b"xa\nx\tb\n");
+        assert_eq!(stdout(&["-di"], b"xa\nx\tb\n"), b"x\tb\nxa\n");
+        assert_eq!(stdout(&["-df"], b"B-a\nab\n"), b"ab\nB-a\n");
+
+        // Version, human-numeric and random keys are filtered first.
+        assert_eq!(
+            stdout(&["-dfV"], b"v1.10\nV-1.9\nv1-.2\n"),
+            b"v1-.2\nV-1.9\nv1.10\n"
+        );
+        assert_eq!(stdout(&["-dV"], b"0\n\x7f\n"), b"\x7f\n0\n");
+        assert_eq!(stdout(&["-fh"], b"1m\n2\n"), b"2\n1m\n");
+        let out = stdout(&["-dR"], b"a-b\nx\ny\nab\nz\n");
+        let lines: Vec<&[u8]> = out.split(|&b| b == b'\n').collect();
+        let a = lines.iter().position(|l| *l == b"a-b").unwrap();
+        let b = lines.iter().position(|l| *l == b"ab").unwrap();
+        assert_eq!(a.abs_diff(b), 1, "{}", String::from_utf8_lossy(&out));
+
+        // GNU rejects -d and -i with the numeric sorts rather than ignoring them.
+        let out = sort_with(&["-d", "-n"], b"1\n");
+        assert_eq!(out.status.code(), Some(2));
+        assert_eq!(
+            String::from_utf8_lossy(&out.stderr),
+            "sort: options '-dn' are incompatible\n"
+        );
+        let out = sort_with(&["-k1,1hi"], b"1\n");
+        assert_eq!(out.status.code(), Some(2));
+    }
 }
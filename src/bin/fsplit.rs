@@ -7,6 +7,118 @@ struct Cli {
     config: SplitConfig,
     input: String,
     separator_set: bool,
+    suffix_length_set: bool,
+    /// --numeric-suffixes=FROM / --hex-suffixes=FROM start value
+    suffix_start: Option<u64>,
+}
+
+/// Parse a FROM start value, written in the suffix alphabet's digits.
+fn parse_suffix_start(val: &str, hex: bool) -> u64 {
+    let valid = !val.is_empty()
+        && val
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (hex && matches!(b, b'a'..=b'f')));
+    let parsed = if valid {
+        u64::from_str_radix(val, if hex { 16 } else { 10 }).ok()
+    } else {
+        None
+    };
+    parsed.unwrap_or_else(|| {
+        eprintln!(
+            "split: '{}': invalid start value for {} suffix",
+            val,
+            if hex { "hexadecimal" } else { "numerical" }
+        );
+        eprintln!("Try 'split --help' for more information.");
+        process::exit(1);
+    })
+}
+
+/// Apply a -t/--separator value: a single byte, or `\0` for NUL.
+fn set_separator(cli: &mut Cli, val: &str) {
+    let new_sep = match val.as_bytes() {
+        [] => {
+            eprintln!("split: empty record separator");
+            process::exit(1);
+        }
+        [b] => *b,
+        b"\\0" => b'\0',
+        _ => {
+            eprintln!("split: multi-character separator '{}'", val);
+            process::exit(1);
+        }
+    };
+    if cli.separator_set && cli.config.separator != new_sep {
+        eprintln!("split: multiple separator characters specified");
+        process::exit(1);
+    }
+    cli.config.separator = new_sep;
+    cli.separator_set = true;
+}
+
+/// Settle the suffix width the way GNU's set_suffix_length does: a start value
+/// or -a fixes it, and a fixed number of output files (-n) sizes it to fit.
+fn resolve_suffix_length(cli: &mut Cli) {
+    let config = &mut cli.config;
+    if let Some(start) = cli.suffix_start {
+        config.suffix_type = match config.suffix_type {
+            SuffixType::Hex(_) => SuffixType::Hex(start),
+            _ => SuffixType::Numeric(start),
+        };
+        config.suffix_auto = false;
+    }
+    if cli.suffix_length_set {
+        config.suffix_auto = false;
+    }
+
+    let base = split::suffix_base(&config.suffix_type);
+    let n_units = match config.mode {
+        SplitMode::Number(n)
+        | SplitMode::NumberExtract(_, n)
+        | SplitMode::LineChunks(n)
+        | SplitMode::LineChunkExtract(_, n)
+        | SplitMode::RoundRobin(n)
+        | SplitMode::RoundRobinExtract(_, n) => n,
+        _ => 0,
+    };
+    if n_units > 0 {
+        let mut end = n_units - 1;
+        if let Some(start) = cli.suffix_start
+            && start < n_units
+        {
+            end = end.saturating_add(start);
+        }
+        let mut needed = 0;
+        loop {
+            needed += 1;
+            end /= base;
+            if end == 0 {
+                break;
+            }
+        }
+        config.suffix_auto = false;
+        if !cli.suffix_length_set {
+            config.suffix_length = config.suffix_length.max(needed);
+        } else if config.suffix_length < needed {
+            eprintln!("split: the suffix length needs to be at least {}", needed);
+            process::exit(1);
+        }
+    }
+
+    if let Some(mut start) = cli.suffix_start {
+        let mut digits = 0;
+        loop {
+            digits += 1;
+            start /= base;
+            if start == 0 {
+                break;
+            }
+        }
+        if digits > config.suffix_length {
+            eprintln!("split: numerical suffix start value is too large for the suffix length");
+            process::exit(1);
+        }
+    }
 }
 
 /// Parse a CHUNKS spec for -n option.
@@ -75,6 +187,8 @@ fn parse_args() -> Cli {
         config: SplitConfig::default(),
         input: "-".to_string(),
         separator_set: false,
+        suffix_length_set: false,
+        suffix_start: None,
     };
 
     let mut args = std::env::args_os().skip(1);
@@ -108,6 +222,7 @@ fn parse_args() -> Cli {
                     eprintln!("split: invalid suffix length: '{}'", val);
                     process::exit(1);
                 });
+                cli.suffix_length_set = true;
             } else if let Some(val) = arg_ref.strip_prefix("--bytes=") {
                 let size = split::parse_size(val).unwrap_or_else(|e| {
                     eprintln!("split: invalid number of bytes: '{}'", e);
@@ -131,38 +246,19 @@ fn parse_args() -> Cli {
             } else if let Some(val) = arg_ref.strip_prefix("--additional-suffix=") {
                 cli.config.additional_suffix = val.to_string();
             } else if let Some(val) = arg_ref.strip_prefix("--numeric-suffixes=") {
-                let from: u64 = val.parse().unwrap_or_else(|_| {
-                    eprintln!("split: invalid start value: '{}'", val);
-                    process::exit(1);
-                });
-                cli.config.suffix_type = SuffixType::Numeric(from);
+                cli.suffix_start = Some(parse_suffix_start(val, false));
+                cli.config.suffix_type = SuffixType::Numeric(0);
             } else if arg_ref == "--numeric-suffixes" {
                 cli.config.suffix_type = SuffixType::Numeric(0);
             } else if let Some(val) = arg_ref.strip_prefix("--hex-suffixes=") {
-                let from: u64 = val.parse().unwrap_or_else(|_| {
-                    eprintln!("split: invalid start value: '{}'", val);
-                    process::exit(1);
-                });
-                cli.config.suffix_type = SuffixType::Hex(from);
+                cli.suffix_start = Some(parse_suffix_start(val, true));
+                cli.config.suffix_type = SuffixType::Hex(0);
             } else if arg_ref == "--hex-suffixes" {
                 cli.config.suffix_type = SuffixType::Hex(0);
             } else if let Some(val) = arg_ref.strip_prefix("--filter=") {
                 cli.config.filter = Some(val.to_string());
             } else if let Some(val) = arg_ref.strip_prefix("--separator=") {
-                let new_sep = if val.len() == 1 {
-                    val.as_bytes()[0]
-                } else if val.is_empty() {
-                    b'\0'
-                } else {
-                    eprintln!("split: multi-character separator '{}'", val);
-                    process::exit(1);
-                };
-                if cli.separator_set && cli.config.separator != new_sep {
-                    eprintln!("split: multiple separator characters specified");
-                    process::exit(1);
-                }
-                cli.config.separator = new_sep;
-                cli.separator_set = true;
+                set_separator(&mut cli, val);
             } else if arg_ref == "--elide-empty-files" {
                 cli.config.elide_empty = true;
             } else if arg_ref == "--verbose" {
@@ -202,6 +298,7 @@ fn parse_args() -> Cli {
                             eprintln!("split: invalid suffix length: '{}'", val);
                             process::exit(1);
                         });
+                        cli.suffix_length_set = true;
                         break; // consumed rest of cluster
                     }
                     'b' => {
@@ -288,20 +385,7 @@ fn parse_args() -> Cli {
                                 .to_string_lossy()
                                 .into_owned()
                         };
-                        let new_sep = if val.len() == 1 {
-                            val.as_bytes()[0]
-                        } else if val.is_empty() {
-                            b'\0'
-                        } else {
-                            eprintln!("split: multi-character separator '{}'", val);
-                            process::exit(1);
-                        };
-                        if cli.separator_set && cli.config.separator != new_sep {
-                            eprintln!("split: multiple separator characters specified");
-                            process::exit(1);
-                        }
-                        cli.config.separator = new_sep;
-                        cli.separator_set = true;
+                        set_separator(&mut cli, &val);
                         break;
                     }
                     'd' => {
@@ -337,6 +421,7 @@ fn parse_args() -> Cli {
         }
     }
 
+    resolve_suffix_length(&mut cli);
     cli
 }
 
@@ -372,6 +457,14 @@ fn main() {
 
     let cli = parse_args();
 
+    // A filter that exits early must not kill split: like GNU, let the write
+    // fail with EPIPE so the remaining files are still produced.
+    if cli.config.filter.is_some() {
+        unsafe {
+            libc::signal(libc::SIGPIPE, libc::SIG_IGN);
+        }
+    }
+
     // Validate zero values (GNU split rejects them)
     match &cli.config.mode {
         SplitMode::Lines(0) => {
@@ -427,12 +520,7 @@ fn main() {
     // GNU split checks if the first output file path resolves to the same inode
     // as the input file and refuses to proceed if so.
     if cli.input != "-" {
-        let first_output = format!(
-            "{}{}{}",
-            cli.config.prefix,
-            split::generate_suffix(0, &cli.config.suffix_type, cli.config.suffix_length),
-            cli.config.additional_suffix
-        );
+        let first_output = split::output_path(&cli.config, 0);
         let would_overwrite = {
             #[cfg(unix)]
             {
@@ -622,8 +710,45 @@ mod tests {
             .output()
             .unwrap();
         assert!(output.status.success());
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.contains("creating"));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "creating file 'xaa'\ncreating file 'xab'\n"
+        );
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_split_verbose_filter_and_elide() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        std::fs::write(&input, "1\n2\n3\n").unwrap();
+        // A filter that ignores its input does not stop later pieces.
+        let output = cmd()
+            .args(["--verbose", "-l", "1", "--filter=true"])
+            .arg(&input)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "executing with FILE=xaa\nexecuting with FILE=xab\nexecuting with FILE=xac\n"
+        );
+        assert!(!dir.path().join("xaa").exists());
+
+        // Elided empty files are neither created nor announced.
+        let output = cmd()
+            .args(["--verbose", "-e", "-n", "r/5"])
+            .arg(&input)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "creating file 'xaa'\ncreating file 'xab'\ncreating file 'xac'\n"
+        );
+        assert!(!dir.path().join("xad").exists());
     }
 
     #[test]
@@ -648,11 +773,11 @@ mod tests {
         );
         assert_eq!(
             std::fs::read_to_string(dir.path().join("xab")).unwrap(),
-            "3\n4\n"
+            "3\n"
         );
         assert_eq!(
             std::fs::read_to_string(dir.path().join("xac")).unwrap(),
-            "5\n"
+            "4\n5\n"
         );
     }
 
@@ -759,6 +884,56 @@ mod tests {
         assert!(output.status.success());
     }
 
+    #[test]
+    fn test_split_nul_separator_escape() {
+        // GNU: -t '\0' splits NUL-terminated records; the last record keeps
+        // no terminator if the input has none.
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.bin");
+        std::fs::write(&input, b"a\0bb\0ccc\0dd").unwrap();
+        let output = cmd()
+            .args(["-t", "\\0", "-l", "2", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(std::fs::read(dir.path().join("xaa")).unwrap(), b"a\0bb\0");
+        assert_eq!(std::fs::read(dir.path().join("xab")).unwrap(), b"ccc\0dd");
+
+        let output = cmd().args(["-t", "", "-l", "2"]).output().unwrap();
+        assert!(!output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "split: empty record separator\n"
+        );
+    }
+
+    #[test]
+    fn test_split_separator_with_line_chunks() {
+        // GNU: -n l/3 on 13 bytes breaks after the records covering bytes
+        // 4 and 8; the last chunk takes the rest.
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        std::fs::write(&input, "a;b;c;d;e;f;g").unwrap();
+        let output = cmd()
+            .args(["-t", ";", "-n", "l/3", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("xaa"), "a;b;");
+        assert_eq!(read("xab"), "c;d;");
+        assert_eq!(read("xac"), "e;f;g");
+
+        let output = cmd()
+            .args(["-t", ";", "-n", "r/2/3", input.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "b;e;");
+    }
+
     #[test]
     fn test_split_guard_input_overwrite() {
         // GNU: split should refuse to overwrite input file
@@ -775,4 +950,120 @@ mod tests {
             "split should fail when output would overwrite input"
         );
     }
+
+    #[test]
+    fn test_split_numeric_suffix_auto_widens() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        let data: String = (0..92).map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&input, data).unwrap();
+        let output = cmd()
+            .args(["-l", "1", "-d", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(dir.path().join("x89").exists());
+        assert!(!dir.path().join("x90").exists());
+        assert_eq!(std::fs::read(dir.path().join("x9000")).unwrap(), b"90\n");
+        assert_eq!(std::fs::read(dir.path().join("x9001")).unwrap(), b"91\n");
+    }
+
+    #[test]
+    fn test_split_numeric_suffix_start_and_additional_suffix() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        std::fs::write(&input, "a\nb\n").unwrap();
+        let output = cmd()
+            .args([
+                "-l",
+                "1",
+                "--additional-suffix=.csv",
+                "--numeric-suffixes=1",
+                input.to_str().unwrap(),
+                "part_",
+            ])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            std::fs::read(dir.path().join("part_01.csv")).unwrap(),
+            b"a\n"
+        );
+        assert_eq!(
+            std::fs::read(dir.path().join("part_02.csv")).unwrap(),
+            b"b\n"
+        );
+    }
+
+    #[test]
+    fn test_split_numeric_suffix_start_exhausts() {
+        // A start value disables auto-widening, so 98, 99 and then failure.
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        std::fs::write(&input, "a\nb\nc\n").unwrap();
+        let output = cmd()
+            .args(["-l", "1", "--numeric-suffixes=98", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("suffixes exhausted"));
+        assert!(dir.path().join("x99").exists());
+
+        let output = cmd()
+            .args(["-a", "2", "--numeric-suffixes=100", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("too large"));
+
+        for (opt, err) in [
+            (
+                "--numeric-suffixes=x",
+                "split: 'x': invalid start value for numerical suffix\n",
+            ),
+            (
+                "--hex-suffixes=g",
+                "split: 'g': invalid start value for hexadecimal suffix\n",
+            ),
+        ] {
+            let output = cmd()
+                .args([opt, input.to_str().unwrap()])
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                format!("{}Try 'split --help' for more information.\n", err)
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_suffix_length_fixed() {
+        // -a disables auto-widening
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        let data: String = (0..11).map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&input, data).unwrap();
+        let output = cmd()
+            .args(["-l", "1", "-d", "-a", "1", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(dir.path().join("x9").exists());
+
+        let output = cmd()
+            .args(["-n", "200", "-d", "-a", "2", input.to_str().unwrap()])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("at least 3"));
+    }
 }
@@ -12,7 +12,7 @@ fn main() {
 use std::process;
 
 #[cfg(unix)]
-use coreutils_rs::stat::StatConfig;
+use coreutils_rs::stat::{QuotingStyle, StatConfig};
 
 #[cfg(unix)]
 const TOOL_NAME: &str = "stat";
@@ -28,6 +28,7 @@ fn main() {
     let mut format: Option<String> = None;
     let mut printf_format: Option<String> = None;
     let mut terse = false;
+    let mut quoting_style: Option<QuotingStyle> = None;
     let mut operands: Vec<String> = Vec::new();
     let mut saw_dashdash = false;
 
@@ -61,7 +62,22 @@ fn main() {
                 }
                 format = Some(args[i].clone());
             }
+            "--quoting-style" => {
+                i += 1;
+                if i >= args.len() {
+                    eprintln!(
+                        "{}: option '--quoting-style' requires an argument",
+                        TOOL_NAME
+                    );
+                    eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+                    process::exit(1);
+                }
+                quoting_style = Some(parse_quoting_style_arg(&args[i]));
+            }
             "--" => saw_dashdash = true,
+            _ if arg.starts_with("--quoting-style=") => {
+                quoting_style = Some(parse_quoting_style_arg(&arg["--quoting-style=".len()..]));
+            }
             _ if arg.starts_with("--format=") => {
                 format = Some(arg["--format=".len()..].to_string());
             }
@@ -115,12 +131,32 @@ fn main() {
         process::exit(1);
     }
 
+    // Like GNU, QUOTING_STYLE is only consulted when a format uses %N.
+    let quoting_style =
+        quoting_style.unwrap_or_else(|| {
+            let uses_n = format
+                .iter()
+                .chain(printf_format.iter())
+                .any(|f| f.contains("%N"));
+            match std::env::var("QUOTING_STYLE") {
+            Ok(val) if uses_n => coreutils_rs::stat::parse_quoting_style(&val).unwrap_or_else(|_| {
+                eprintln!(
+                    "{}: ignoring invalid value of environment variable QUOTING_STYLE: '{}'",
+                    TOOL_NAME, val
+                );
+                QuotingStyle::default()
+            }),
+            _ => QuotingStyle::default(),
+        }
+        });
+
     let config = StatConfig {
         dereference,
         filesystem,
         format,
         printf_format,
         terse,
+        quoting_style,
     };
 
     let mut exit_code = 0;
@@ -136,9 +172,9 @@ fn main() {
                     eprintln!("{}: {}", TOOL_NAME, coreutils_rs::common::io_error_msg(&e));
                 } else {
                     eprintln!(
-                        "{}: cannot stat '{}': {}",
+                        "{}: cannot stat {}: {}",
                         TOOL_NAME,
-                        path,
+                        coreutils_rs::stat::quote_name(path),
                         coreutils_rs::common::io_error_msg(&e)
                     );
                 }
@@ -152,6 +188,15 @@ fn main() {
     }
 }
 
+#[cfg(unix)]
+fn parse_quoting_style_arg(s: &str) -> QuotingStyle {
+    coreutils_rs::stat::parse_quoting_style(s).unwrap_or_else(|e| {
+        eprintln!("{}: {}", TOOL_NAME, e);
+        eprintln!("Try '{} --help' for more information.", TOOL_NAME);
+        process::exit(1);
+    })
+}
+
 #[cfg(unix)]
 fn print_help() {
     println!("Usage: {} [OPTION]... FILE...", TOOL_NAME);
@@ -165,6 +210,8 @@ fn print_help() {
     println!("                          and do not output a mandatory trailing newline;");
     println!("                          if you want a newline, include \\n in FORMAT");
     println!("  -t, --terse           print the information in terse form");
+    println!("      --quoting-style=WORD  quote %N names in style WORD; overrides");
+    println!("                          the QUOTING_STYLE environment variable");
     println!("      --help     display this help and exit");
     println!("      --version  output version information and exit");
     println!();
@@ -175,6 +222,8 @@ fn print_help() {
     println!("  %B   the size in bytes of each block reported by %b");
     println!("  %d   device number in decimal");
     println!("  %D   device number in hex");
+    println!("  %Hd  major device number in decimal");
+    println!("  %Ld  minor device number in decimal");
     println!("  %f   raw mode in hex");
     println!("  %F   file type");
     println!("  %g   group ID of owner");
@@ -186,6 +235,10 @@ fn print_help() {
     println!("  %N   quoted file name with dereference if symbolic link");
     println!("  %o   optimal I/O transfer size hint");
     println!("  %s   total size, in bytes");
+    println!("  %r   device type in decimal (st_rdev)");
+    println!("  %R   device type in hex (st_rdev)");
+    println!("  %Hr  major device type in decimal, for character/block device special files");
+    println!("  %Lr  minor device type in decimal, for character/block device special files");
     println!("  %t   major device type in hex, for character/block device special files");
     println!("  %T   minor device type in hex, for character/block device special files");
     println!("  %u   user ID of owner");
@@ -357,4 +410,177 @@ mod tests {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert_eq!(stdout.lines().count(), 1);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stat_symlink_lstat_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("it's"), "hello").unwrap();
+        std::os::unix::fs::symlink("it's", dir.path().join("my link")).unwrap();
+        let output = cmd()
+            .args(["-c", "%N|%F|%s", "my link"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "'my link' -> \"it's\"|symbolic link|4\n"
+        );
+
+        let output = cmd()
+            .args(["-L", "-c", "%N|%F|%s", "my link"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "'my link'|regular file|5\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stat_dereference_broken_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink("gone\tfile", dir.path().join("broken")).unwrap();
+        let output = cmd()
+            .args(["-c", "%N", "broken"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "'broken' -> 'gone'$'\\t''file'\n"
+        );
+
+        let output = cmd()
+            .args(["-L", "broken"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "stat: cannot stat 'broken': No such file or directory\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stat_quoting_style_for_n() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("it's"), "").unwrap();
+        std::fs::write(dir.path().join("nl\nx"), "").unwrap();
+        std::os::unix::fs::symlink("it's", dir.path().join("a b")).unwrap();
+        let cases: &[(&str, &str)] = &[
+            ("literal", "a b -> it's\nit's\nnl\nx\n"),
+            ("shell", "'a b' -> \"it's\"\n\"it's\"\n'nl\nx'\n"),
+            ("shell-always", "'a b' -> \"it's\"\n\"it's\"\n'nl\nx'\n"),
+            (
+                "shell-escape",
+                "'a b' -> \"it's\"\n\"it's\"\n'nl'$'\\n''x'\n",
+            ),
+            ("c", "\"a b\" -> \"it's\"\n\"it's\"\n\"nl\\nx\"\n"),
+            ("escape", "a b -> it's\nit's\nnl\\nx\n"),
+        ];
+        for &(style, expected) in cases {
+            let output = cmd()
+                .args(["-c", "%N", "a b", "it's", "nl\nx"])
+                .env("QUOTING_STYLE", style)
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{}",
+                style
+            );
+        }
+
+        // The option overrides the environment and accepts abbreviations.
+        let output = cmd()
+            .args(["--quoting-style=shell-a", "-c", "%N", "it's"])
+            .env("QUOTING_STYLE", "c")
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "\"it's\"\n");
+    }
+
+    #[test]
+    fn test_stat_quoting_style_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("f"), "").unwrap();
+        let output = cmd()
+            .args(["-c", "%N", "f"])
+            .env("QUOTING_STYLE", "bogus")
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "'f'\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "stat: ignoring invalid value of environment variable QUOTING_STYLE: 'bogus'\n"
+        );
+
+        // Without %N the variable is not looked at.
+        let output = cmd()
+            .args(["-c", "%n", "f"])
+            .env("QUOTING_STYLE", "bogus")
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.stderr.is_empty());
+
+        let output = cmd()
+            .args(["--quoting-style=s", "-c", "%N", "f"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .starts_with("stat: ambiguous argument 's' for '--quoting-style'\n")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stat_block_and_device_number_directives() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test.txt");
+        std::fs::write(&file, "hello").unwrap();
+        let output = cmd()
+            .args(["-c", "%B %o %t %T %r %R %Hr %Lr"])
+            .arg(&file)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // st_rdev is 0 for anything but a device file; %o is st_blksize.
+        use std::os::unix::fs::MetadataExt;
+        let blksize = std::fs::metadata(&file).unwrap().blksize();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("512 {} 0 0 0 0 0 0\n", blksize)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stat_device_number_directives_dev_null() {
+        // On Linux /dev/null is character device 1,3.
+        let output = cmd()
+            .args(["-c", "%t %T %r %R %Hr %Lr", "/dev/null"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"1 3 259 103 1 3\n");
+    }
 }
@@ -9,7 +9,7 @@ fn main() {
 // Usage: stdbuf [OPTION]... COMMAND [ARG]...
 //
 // Adjusts stdin/stdout/stderr buffering of COMMAND by setting environment
-// variables and executing the command.
+// variables, preloading libstdbuf.so and executing the command.
 
 #[cfg(unix)]
 use std::process;
@@ -35,8 +35,16 @@ fn print_help() {
     println!("  0       unbuffered");
     println!("  SIZE    fully buffered with SIZE bytes (supports K, M, G suffixes)");
     println!();
-    println!("NOTE: This implementation sets _STDBUF_I, _STDBUF_O, _STDBUF_E");
-    println!("environment variables. A full implementation requires an LD_PRELOAD library.");
+    println!("If MODE is 'L' the corresponding stream will be line buffered.");
+    println!("This option is invalid with standard input.");
+    println!();
+    println!(
+        "NOTE: The modes are applied by preloading {}, searched for",
+        coreutils_rs::stdbuf::LIBSTDBUF_NAME
+    );
+    println!("next to this program and in the GNU coreutils library directories.");
+    println!("If COMMAND adjusts the buffering of its standard streams ('tee' does");
+    println!("for example) then that will override corresponding changes by 'stdbuf'.");
 }
 
 #[cfg(unix)]
@@ -196,11 +204,27 @@ fn main() {
         process::exit(125);
     }
 
+    let libstdbuf = match std::env::current_exe()
+        .ok()
+        .and_then(|exe| coreutils_rs::stdbuf::find_libstdbuf(&exe))
+    {
+        Some(path) => path,
+        None => {
+            eprintln!(
+                "{}: failed to find '{}'",
+                TOOL_NAME,
+                coreutils_rs::stdbuf::LIBSTDBUF_NAME
+            );
+            process::exit(125);
+        }
+    };
+
     let cmd_idx = command_start.unwrap();
     let config = coreutils_rs::stdbuf::StdbufConfig {
         input: input_mode,
         output: output_mode,
         error: error_mode,
+        libstdbuf,
         command: args[cmd_idx].clone(),
         args: args[cmd_idx + 1..].to_vec(),
     };
@@ -241,7 +265,7 @@ mod tests {
         Command::new(path)
     }
 
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_runs_command() {
         let output = cmd().args(["-o", "L", "echo", "hello"]).output().unwrap();
@@ -254,7 +278,7 @@ mod tests {
         assert_eq!(stdout.trim(), "hello");
     }
 
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_exit_code() {
         let output = cmd().args(["-o", "0", "false"]).output().unwrap();
@@ -274,7 +298,7 @@ mod tests {
             "fstdbuf without a command should fail"
         );
     }
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_matches_gnu_args() {
         // Verify that the same flags are accepted
@@ -292,7 +316,7 @@ mod tests {
         assert_eq!(stdout.trim(), "test");
     }
 
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_line_buffered() {
         let output = cmd().args(["-oL", "echo", "hello"]).output().unwrap();
@@ -314,18 +338,75 @@ mod tests {
         assert!(!output.status.success());
     }
 
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_exit_code_passthrough() {
         let output = cmd().args(["-o0", "sh", "-c", "exit 42"]).output().unwrap();
         assert_eq!(output.status.code(), Some(42));
     }
 
-    #[cfg(unix)]
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_stdbuf_unbuffered() {
         let output = cmd().args(["-o", "0", "echo", "test"]).output().unwrap();
         assert!(output.status.success());
         assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "test");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdbuf_input_line_rejected() {
+        let output = cmd().args(["-iL", "true"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(125));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("line buffering stdin is meaningless"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stdbuf_sets_preload_and_modes() {
+        let output = cmd()
+            .args([
+                "-i0",
+                "-oL",
+                "-e",
+                "4K",
+                "sh",
+                "-c",
+                "echo \"$_STDBUF_I $_STDBUF_O $_STDBUF_E $LD_PRELOAD\"",
+            ])
+            .env("LD_PRELOAD", "")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("0 L 4096 "), "got: {}", stdout);
+        // The shim build.rs compiled is found through its baked-in path.
+        let shim = coreutils_rs::stdbuf::BUILT_LIBSTDBUF.expect("libstdbuf.so was not built");
+        assert!(stdout.trim_end().ends_with(shim), "got: {}", stdout);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stdbuf_bundled_shim_applies_modes() {
+        let shim = coreutils_rs::stdbuf::BUILT_LIBSTDBUF.expect("libstdbuf.so was not built");
+        let run = |mode: &str| {
+            Command::new("sh")
+                .args(["-c", "true"])
+                .env("LD_PRELOAD", shim)
+                .env("_STDBUF_I", "4096")
+                .env("_STDBUF_O", mode)
+                .env("_STDBUF_E", "0")
+                .output()
+                .unwrap()
+        };
+        let output = run("L");
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty(), "{:?}", output.stderr);
+        let output = run("bogus");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "invalid buffering mode bogus for stdout\n"
+        );
+    }
 }
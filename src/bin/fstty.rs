@@ -22,10 +22,15 @@ fn print_help() {
         "  or:  {} [-F DEVICE | --file=DEVICE] [-a|--all]",
         TOOL_NAME
     );
+    println!(
+        "  or:  {} [-F DEVICE | --file=DEVICE] [-g|--save]",
+        TOOL_NAME
+    );
     println!("Print or change terminal line settings.");
     println!();
     println!("  -a, --all          print all current settings in human-readable form");
     println!("  -F, --file=DEVICE  open and use the specified DEVICE instead of stdin");
+    println!("  -g, --save         print all current settings in a stty-readable form");
     println!("      --help         display this help and exit");
     println!("      --version      output version information and exit");
     println!();
@@ -154,6 +159,22 @@ fn main() {
             };
             coreutils_rs::stty::print_all(&termios, fd);
         }
+        coreutils_rs::stty::SttyAction::PrintSaved => {
+            let termios = match coreutils_rs::stty::get_termios(fd) {
+                Ok(t) => t,
+                Err(e) => {
+                    let src = config.device.as_deref().unwrap_or("standard input");
+                    eprintln!(
+                        "{}: {}: {}",
+                        TOOL_NAME,
+                        src,
+                        coreutils_rs::common::io_error_msg(&e)
+                    );
+                    process::exit(1);
+                }
+            };
+            println!("{}", coreutils_rs::stty::format_saved(&termios));
+        }
         coreutils_rs::stty::SttyAction::ApplySettings => {
             let mut termios = match coreutils_rs::stty::get_termios(fd) {
                 Ok(t) => t,
@@ -296,4 +317,88 @@ mod tests {
         // size with piped stdin should fail
         assert!(!output.status.success());
     }
+
+    /// Open a pseudo-terminal pair and return (master fd, slave path).
+    #[cfg(target_os = "linux")]
+    fn open_pty() -> Option<(i32, String)> {
+        unsafe {
+            let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            if master < 0 {
+                return None;
+            }
+            if libc::grantpt(master) != 0 || libc::unlockpt(master) != 0 {
+                libc::close(master);
+                return None;
+            }
+            let name = libc::ptsname(master);
+            if name.is_null() {
+                libc::close(master);
+                return None;
+            }
+            let path = std::ffi::CStr::from_ptr(name)
+                .to_string_lossy()
+                .into_owned();
+            Some((master, path))
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stty_save_not_tty() {
+        let output = cmd().arg("-g").stdin(Stdio::piped()).output().unwrap();
+        assert!(!output.status.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stty_save_and_all_exclusive() {
+        let output = cmd().args(["-a", "-g"]).output().unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("mutually exclusive"), "got: {}", stderr);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stty_save_restore_round_trip() {
+        let Some((master, slave)) = open_pty() else {
+            return;
+        };
+        let save = |dev: &str| {
+            let out = cmd().args(["-F", dev, "-g"]).output().unwrap();
+            assert!(
+                out.status.success(),
+                "{}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+            String::from_utf8(out.stdout)
+                .unwrap()
+                .trim_end()
+                .to_string()
+        };
+        let saved = save(&slave);
+        // 4 flag words followed by the full c_cc array
+        assert_eq!(saved.split(':').count(), 4 + libc::NCCS);
+
+        let status = cmd()
+            .args(["-F", &slave, "raw", "-echo", "intr", "^X"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_ne!(save(&slave), saved);
+
+        let status = cmd().args(["-F", &slave, &saved]).status().unwrap();
+        assert!(status.success());
+        assert_eq!(save(&slave), saved);
+
+        // A truncated saved state is not a valid setting
+        let output = cmd()
+            .args(["-F", &slave, "500:5:bf:8a3b"])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        unsafe {
+            libc::close(master);
+        }
+    }
 }
@@ -164,10 +164,68 @@ fn try_mmap_stdin() -> Option<memmap2::Mmap> {
     mmap
 }
 
+/// Reverse a large regular file window by window so memory stays bounded.
+/// Returns None for inputs below tac::CHUNKED_THRESHOLD, non-regular files
+/// (pipes are buffered whole) and regex separators.
+#[cfg(unix)]
+fn try_tac_chunked(cli: &Cli, filename: &str, out: &mut impl Write) -> Option<io::Result<()>> {
+    if cli.regex {
+        return None;
+    }
+    let separator: &[u8] = match cli.separator.as_deref() {
+        None => b"\n",
+        // GNU tac: -s '' means NUL byte separator
+        Some("") => b"\0",
+        Some(sep) => sep.as_bytes(),
+    };
+    let is_large = |meta: &std::fs::Metadata| {
+        meta.file_type().is_file() && meta.len() >= tac::CHUNKED_THRESHOLD
+    };
+    let stdin;
+    let opened;
+    let file: &std::fs::File = if filename == "-" {
+        stdin = ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(0) });
+        &stdin
+    } else {
+        if !is_large(&std::fs::metadata(filename).ok()?) {
+            return None;
+        }
+        opened = std::fs::File::open(filename).ok()?;
+        &opened
+    };
+    if !is_large(&file.metadata().ok()?) {
+        return None;
+    }
+    Some(tac::tac_file_chunked(
+        file,
+        separator,
+        cli.before,
+        tac::REVERSE_CHUNK_SIZE,
+        out,
+    ))
+}
+
+/// Report a failed reversal; a closed pipe ends tac quietly like GNU.
+fn report_error(e: &io::Error) {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+    eprintln!("tac: write error: {}", io_error_msg(e));
+}
+
 fn run(cli: &Cli, files: &[String], out: &mut impl Write) -> bool {
     let mut had_error = false;
 
     for filename in files {
+        #[cfg(unix)]
+        if let Some(result) = try_tac_chunked(cli, filename, out) {
+            if let Err(e) = result {
+                report_error(&e);
+                had_error = true;
+            }
+            continue;
+        }
+
         let mut data: FileData = if filename == "-" {
             #[cfg(unix)]
             {
@@ -240,10 +298,7 @@ fn run(cli: &Cli, files: &[String], out: &mut impl Write) -> bool {
         };
 
         if let Err(e) = result {
-            if e.kind() == io::ErrorKind::BrokenPipe {
-                process::exit(0);
-            }
-            eprintln!("tac: write error: {}", io_error_msg(&e));
+            report_error(&e);
             had_error = true;
         }
     }
@@ -467,4 +522,71 @@ mod tests {
         assert_eq!(lines[0], "100");
         assert_eq!(lines[99], "1");
     }
+
+    #[test]
+    fn test_tac_overlapping_separator_matches_rightmost() {
+        for (args, expected) in [
+            (&["-s", "aa"][..], "yxaaa"),
+            (&["-b", "-s", "aa"][..], "aayxa"),
+        ] {
+            let output = cmd()
+                .args(args)
+                .arg("-")
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    use std::io::Write;
+                    child.stdin.take().unwrap().write_all(b"xaaay")?;
+                    child.wait_with_output()
+                })
+                .unwrap();
+            assert!(output.status.success());
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tac_chunked_matches_whole_file() {
+        use coreutils_rs::tac;
+        let mut data = Vec::new();
+        for i in 0..2000 {
+            data.extend_from_slice(format!("line {} ab", i).as_bytes());
+            data.extend(std::iter::repeat_n(b'x', i % 37));
+            data.push(if i % 3 == 0 { b'\0' } else { b'\n' });
+        }
+        // One record far longer than a window, and no trailing separator.
+        data.extend(std::iter::repeat_n(b'y', 20000));
+        data.push(b'\n');
+        data.extend_from_slice(b"aaaaa\nlast");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input");
+        std::fs::write(&path, &data).unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+
+        for sep in [&b"\n"[..], b"\0", b"ab", b"aa", b"\nline"] {
+            for before in [false, true] {
+                let mut expected = Vec::new();
+                tac::tac_string_separator(&data, sep, before, &mut expected).unwrap();
+                for chunk in [1, 4096, 10000, 1 << 20] {
+                    let mut got = Vec::new();
+                    tac::tac_file_chunked(&file, sep, before, chunk, &mut got).unwrap();
+                    assert!(
+                        got == expected,
+                        "sep={:?} before={} chunk={}",
+                        sep,
+                        before,
+                        chunk
+                    );
+                }
+            }
+        }
+    }
 }
@@ -47,10 +47,7 @@ fn parse_args() -> Cli {
                     process::exit(1);
                 });
             } else if let Some(val) = s.strip_prefix("--max-unchanged-stats=") {
-                cli.config.max_unchanged_stats = val.parse().unwrap_or_else(|_| {
-                    eprintln!("tail: invalid number: '{}'", val);
-                    process::exit(1);
-                });
+                cli.config.max_unchanged_stats = parse_max_unchanged_stats(val);
             } else if let Some(val) = s.strip_prefix("--follow=") {
                 match val {
                     "name" => cli.config.follow = FollowMode::Name,
@@ -111,10 +108,7 @@ fn parse_args() -> Cli {
                             process::exit(1);
                         });
                         cli.config.max_unchanged_stats =
-                            val.to_string_lossy().parse().unwrap_or_else(|_| {
-                                eprintln!("tail: invalid number: '{}'", val.to_string_lossy());
-                                process::exit(1);
-                            });
+                            parse_max_unchanged_stats(&val.to_string_lossy());
                     }
                     b"--help" => {
                         print_help();
@@ -216,69 +210,59 @@ fn parse_args() -> Cli {
 }
 
 fn parse_lines_value(val: &str, config: &mut TailConfig) {
-    if check_numeric_overflow(val) {
-        eprintln!(
-            "tail: invalid number of lines: \u{2018}{}\u{2019}: Value too large for defined data type",
-            val
-        );
-        process::exit(1);
-    }
-    if let Some(stripped) = val.strip_prefix('+') {
-        match tail::parse_size(stripped) {
-            Ok(n) => config.mode = TailMode::LinesFrom(n),
-            Err(_) => {
-                eprintln!("tail: invalid number of lines: '{}'", val);
-                process::exit(1);
-            }
-        }
+    let n = parse_count(val, "lines");
+    config.mode = if val.starts_with('+') {
+        TailMode::LinesFrom(n)
     } else {
-        let clean = val.strip_prefix('-').unwrap_or(val);
-        match tail::parse_size(clean) {
-            Ok(n) => config.mode = TailMode::Lines(n),
-            Err(_) => {
-                eprintln!("tail: invalid number of lines: '{}'", val);
-                process::exit(1);
-            }
-        }
-    }
+        TailMode::Lines(n)
+    };
 }
 
-fn check_numeric_overflow(val: &str) -> bool {
-    let clean = val
-        .strip_prefix('+')
-        .or_else(|| val.strip_prefix('-'))
-        .unwrap_or(val);
-    let num_end = clean.bytes().take_while(u8::is_ascii_digit).count();
-    let num_part = &clean[..num_end];
-    !num_part.is_empty() && num_part.parse::<u64>().is_err()
+fn parse_bytes_value(val: &str, config: &mut TailConfig) {
+    let n = parse_count(val, "bytes");
+    config.mode = if val.starts_with('+') {
+        TailMode::BytesFrom(n)
+    } else {
+        TailMode::Bytes(n)
+    };
 }
 
-fn parse_bytes_value(val: &str, config: &mut TailConfig) {
-    if check_numeric_overflow(val) {
+/// Parse a [+-]NUM -c/-n count with an optional size suffix, exiting with
+/// GNU's diagnostic on failure. As in GNU, a leading '-' is dropped from the
+/// quoted value while a leading '+' is kept.
+fn parse_count(val: &str, what: &str) -> u64 {
+    let num = val.strip_prefix('-').unwrap_or(val);
+    match tail::parse_size(num) {
+        Ok(n) => n,
+        Err(e) => {
+            let detail = match e {
+                tail::SizeError::Invalid => "",
+                tail::SizeError::TooLarge => ": Value too large for defined data type",
+            };
+            eprintln!("tail: invalid number of {}: '{}'{}", what, num, detail);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_max_unchanged_stats(val: &str) -> u64 {
+    let digits = val.strip_prefix('+').unwrap_or(val);
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        if let Ok(n) = digits.parse() {
+            return n;
+        }
         eprintln!(
-            "tail: invalid number of bytes: \u{2018}{}\u{2019}: Value too large for defined data type",
+            "tail: invalid maximum number of unchanged stats between opens: '{}': \
+             Value too large for defined data type",
             val
         );
-        process::exit(1);
-    }
-    if let Some(stripped) = val.strip_prefix('+') {
-        match tail::parse_size(stripped) {
-            Ok(n) => config.mode = TailMode::BytesFrom(n),
-            Err(_) => {
-                eprintln!("tail: invalid number of bytes: '{}'", val);
-                process::exit(1);
-            }
-        }
     } else {
-        let clean = val.strip_prefix('-').unwrap_or(val);
-        match tail::parse_size(clean) {
-            Ok(n) => config.mode = TailMode::Bytes(n),
-            Err(_) => {
-                eprintln!("tail: invalid number of bytes: '{}'", val);
-                process::exit(1);
-            }
-        }
+        eprintln!(
+            "tail: invalid maximum number of unchanged stats between opens: '{}'",
+            val
+        );
     }
+    process::exit(1);
 }
 
 fn print_help() {
@@ -335,6 +319,14 @@ fn main() {
 
     let cli = parse_args();
 
+    // Like GNU, a zero count from the end reads nothing at all unless
+    // following, so there are no headers and no open errors either.
+    if cli.config.follow == FollowMode::None
+        && matches!(cli.config.mode, TailMode::Lines(0) | TailMode::Bytes(0))
+    {
+        return;
+    }
+
     let files: Vec<String> = if cli.files.is_empty() {
         vec!["-".to_string()]
     } else {
@@ -387,10 +379,12 @@ fn main() {
 
     // Follow mode
     if cli.config.follow != FollowMode::None {
-        for filename in &files {
-            if filename != "-" {
-                let _ = tail::follow_file(filename, &cli.config, &mut out);
-            }
+        let last_shown = Some(files.len() - 1);
+        if let Err(e) = tail::follow_files(&files, &cli.config, show_headers, last_shown, &mut out)
+            && e.kind() != io::ErrorKind::BrokenPipe
+        {
+            eprintln!("{}: error reading: {}", tool_name, io_error_msg(&e));
+            had_error = true;
         }
     }
 
@@ -625,4 +619,299 @@ mod tests {
             stderr
         );
     }
+
+    fn append(path: &std::path::Path, data: &str) {
+        use std::io::Write;
+        let mut f = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(data.as_bytes()).unwrap();
+    }
+
+    fn pause() {
+        std::thread::sleep(std::time::Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_tail_follow_multiple_switches_headers() {
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, "a1\n").unwrap();
+        std::fs::write(&b, "b1\n").unwrap();
+        let mut child = cmd()
+            .args(["-f", "-s", "0.1"])
+            .arg(&a)
+            .arg(&b)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        pause();
+        append(&b, "b2\n");
+        pause();
+        append(&a, "a2\n");
+        pause();
+        append(&a, "a3\n");
+        pause();
+        child.kill().unwrap();
+        let output = child.wait_with_output().unwrap();
+        let (a, b) = (a.display(), b.display());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("==> {a} <==\na1\n\n==> {b} <==\nb1\nb2\n\n==> {a} <==\na2\na3\n")
+        );
+    }
+
+    #[test]
+    fn test_tail_follow_single_file_no_header() {
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        std::fs::write(&a, "a1\n").unwrap();
+        let mut child = cmd()
+            .args(["-f", "-s", "0.1"])
+            .arg(&a)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        pause();
+        append(&a, "a2\n");
+        pause();
+        child.kill().unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.stdout, b"a1\na2\n");
+    }
+
+    #[test]
+    fn test_tail_follow_name_recreated_prints_fresh_header() {
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, "a1\n").unwrap();
+        std::fs::write(&b, "b1\n").unwrap();
+        let mut child = cmd()
+            .args(["-F", "-s", "0.1"])
+            .arg(&a)
+            .arg(&b)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        pause();
+        std::fs::remove_file(&b).unwrap();
+        pause();
+        std::fs::write(&b, "new\n").unwrap();
+        pause();
+        child.kill().unwrap();
+        let output = child.wait_with_output().unwrap();
+        let (a, b) = (a.display(), b.display());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("==> {a} <==\na1\n\n==> {b} <==\nb1\n\n==> {b} <==\nnew\n")
+        );
+        assert!(String::from_utf8_lossy(&output.stderr).contains("has appeared"));
+    }
+
+    /// Collect a child's stdout on a thread, so a test can wait for output
+    /// instead of sleeping for a fixed time.
+    fn collect_stdout(
+        child: &mut std::process::Child,
+    ) -> std::sync::Arc<std::sync::Mutex<Vec<u8>>> {
+        use std::io::Read;
+        let collected = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut stdout = child.stdout.take().unwrap();
+        let sink = std::sync::Arc::clone(&collected);
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stdout.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                sink.lock().unwrap().extend_from_slice(&buf[..n]);
+            }
+        });
+        collected
+    }
+
+    /// Wait until the collected output is `expected`, failing after 10s.
+    fn wait_for_output(collected: &std::sync::Mutex<Vec<u8>>, expected: &str) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            let got = String::from_utf8_lossy(&collected.lock().unwrap()).into_owned();
+            if got == expected {
+                return;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out waiting for {:?}, got {:?}",
+                expected,
+                got
+            );
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_tail_follow_name_max_unchanged_stats() {
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let rotated = dir.path().join("a.1");
+        let follow = |stats: &str| {
+            std::fs::write(&a, "old\n").unwrap();
+            let mut child = cmd()
+                .args(["--follow=name", "-s", "0.1", "--max-unchanged-stats", stats])
+                .arg(&a)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let output = collect_stdout(&mut child);
+            wait_for_output(&output, "old\n");
+            // Rotate: the followed descriptor stays unchanged under the old name.
+            std::fs::rename(&a, &rotated).unwrap();
+            std::fs::write(&a, "new\n").unwrap();
+            (child, output)
+        };
+
+        // Rechecking after every unchanged poll finds the new file.
+        let (mut child, output) = follow("0");
+        wait_for_output(&output, "old\nnew\n");
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // With a high limit the old descriptor is kept. Give tail a few
+        // unchanged polls, then append to the rotated file: once that shows
+        // up, tail has polled after the rotation without switching.
+        let (mut child, output) = follow("1000");
+        pause();
+        append(&rotated, "more\n");
+        wait_for_output(&output, "old\nmore\n");
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_tail_size_suffix_overflow() {
+        let output = cmd().args(["-c", "1Z"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "tail: invalid number of bytes: '1Z': Value too large for defined data type\n"
+        );
+        let output = cmd()
+            .args(["--max-unchanged-stats=x", "/dev/null"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains("invalid maximum number of unchanged stats between opens: 'x'")
+        );
+    }
+
+    #[test]
+    fn test_tail_seekable_stdin_and_named_pipe() {
+        use std::io::{Seek, SeekFrom, Write};
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.txt");
+        let input: String = (1..=50000).map(|i| format!("{}\n", i)).collect();
+        std::fs::write(&file, format!("{input}tail")).unwrap();
+        let run = |args: &[&str], offset: u64| {
+            let mut f = std::fs::File::open(&file).unwrap();
+            f.seek(SeekFrom::Start(offset)).unwrap();
+            let output = cmd().args(args).stdin(f).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        // Redirected stdin is tailed from its current offset.
+        assert_eq!(run(&["-n", "2"], 0), "50000\ntail");
+        assert_eq!(run(&["-c", "6"], 0), "0\ntail");
+        assert_eq!(run(&["-n", "5"], input.len() as u64 - 3), "00\ntail");
+        assert_eq!(run(&["-c", "+3"], input.len() as u64 - 2), "tail");
+
+        // A non-seekable file operand is read through to the end.
+        for (args, expected) in [
+            (["-n", "3"], "49999\n50000\ntail"),
+            (["-c", "3"], "ail"),
+            (["-c", "+288895"], "tail"),
+        ] {
+            let mut child = cmd()
+                .args(args)
+                .arg("/dev/stdin")
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            let mut stdin = child.stdin.take().unwrap();
+            stdin.write_all(input.as_bytes()).unwrap();
+            stdin.write_all(b"tail").unwrap();
+            drop(stdin);
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+        }
+    }
+
+    #[test]
+    fn test_tail_zero_terminated_headers() {
+        // Records stay NUL-terminated, but like GNU the headers and the
+        // separator between sections remain newline text.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), b"a1\0a2\0").unwrap();
+        std::fs::write(dir.path().join("b"), b"b1\0b2").unwrap();
+        let output = cmd()
+            .args(["-z", "-n", "1", "a", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"==> a <==\na2\0\n==> b <==\nb2");
+        let output = cmd()
+            .args(["-zv", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"==> b <==\nb1\0b2");
+        let output = cmd()
+            .args(["-zq", "a", "b"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"a1\0a2\0b1\0b2");
+    }
+
+    #[test]
+    fn test_tail_zero_count() {
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        std::fs::write(&a, "a1\na2\n").unwrap();
+        for args in [&["-n", "0"][..], &["-c", "0"], &["-0"], &["-n", "0", "-v"]] {
+            let output = cmd().args(args).arg(&a).arg("missing").output().unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            assert!(output.stdout.is_empty(), "{:?}", args);
+            assert!(output.stderr.is_empty(), "{:?}", args);
+        }
+        // Counting from the start, +0 and +1 both mean the whole file.
+        for count in ["+0", "+1"] {
+            let output = cmd().args(["-n", count]).arg(&a).output().unwrap();
+            assert_eq!(output.stdout, b"a1\na2\n");
+        }
+
+        // With -f, only what is appended later is shown.
+        let mut child = cmd()
+            .args(["-n", "0", "-f", "-s", "0.1"])
+            .arg(&a)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        pause();
+        append(&a, "a3\n");
+        pause();
+        child.kill().unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.stdout, b"a3\n");
+    }
 }
@@ -353,6 +353,161 @@ mod tests {
         );
     }
 
+    /// Create a file whose mtime is `secs` seconds after the epoch.
+    #[cfg(unix)]
+    fn file_with_mtime(path: &std::path::Path, secs: u64) {
+        let f = std::fs::File::create(path).unwrap();
+        f.set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_newer_older_than() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        file_with_mtime(&old, 1_000_000);
+        file_with_mtime(&new, 2_000_000);
+        let (o, n) = (old.to_str().unwrap(), new.to_str().unwrap());
+        let code = |args: [&str; 3]| cmd().args(args).status().unwrap().code();
+        assert_eq!(code([n, "-nt", o]), Some(0));
+        assert_eq!(code([o, "-nt", n]), Some(1));
+        assert_eq!(code([o, "-ot", n]), Some(0));
+        assert_eq!(code([n, "-ot", o]), Some(1));
+        // Equal mtimes are neither newer nor older
+        assert_eq!(code([o, "-nt", o]), Some(1));
+        assert_eq!(code([o, "-ot", o]), Some(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_newer_older_than_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("exists");
+        file_with_mtime(&file, 1_000_000);
+        let missing = dir.path().join("missing");
+        let (f, m) = (file.to_str().unwrap(), missing.to_str().unwrap());
+        let code = |args: [&str; 3]| cmd().args(args).status().unwrap().code();
+        // A nonexistent file is older than any existing file
+        assert_eq!(code([f, "-nt", m]), Some(0));
+        assert_eq!(code([m, "-nt", f]), Some(1));
+        assert_eq!(code([m, "-ot", f]), Some(0));
+        assert_eq!(code([f, "-ot", m]), Some(1));
+        assert_eq!(code([m, "-nt", m]), Some(1));
+        assert_eq!(code([m, "-ot", m]), Some(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_same_file_ef_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        let hard = dir.path().join("hard");
+        let soft = dir.path().join("soft");
+        let other = dir.path().join("other");
+        std::fs::write(&file, "hello").unwrap();
+        std::fs::write(&other, "hello").unwrap();
+        std::fs::hard_link(&file, &hard).unwrap();
+        std::os::unix::fs::symlink(&file, &soft).unwrap();
+        let (f, h, s, o) = (
+            file.to_str().unwrap(),
+            hard.to_str().unwrap(),
+            soft.to_str().unwrap(),
+            other.to_str().unwrap(),
+        );
+        let code = |args: [&str; 3]| cmd().args(args).status().unwrap().code();
+        assert_eq!(code([f, "-ef", h]), Some(0));
+        // Symlinks are followed to their target
+        assert_eq!(code([s, "-ef", f]), Some(0));
+        assert_eq!(code([s, "-ef", h]), Some(0));
+        // Same content is not the same file
+        assert_eq!(code([f, "-ef", o]), Some(1));
+        assert_eq!(code([f, "-ef", "/nonexistent/file"]), Some(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        let code = |args: &[&str]| cmd().args(args).status().unwrap().code();
+        // a -o b -a c parses as a -o (b -a c)
+        assert_eq!(code(&["x", "-o", "", "-a", ""]), Some(0));
+        assert_eq!(code(&["", "-a", "x", "-o", "x"]), Some(0));
+        assert_eq!(code(&["", "-o", "x", "-a", ""]), Some(1));
+        // Beyond four arguments, ! binds tighter than -a and -o
+        assert_eq!(code(&["!", "x", "-o", "x", "-a", "x"]), Some(0));
+        // With exactly four, a leading ! negates the three-argument rest
+        assert_eq!(code(&["!", "x", "-o", "x"]), Some(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parenthesized_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        std::fs::write(&a, "").unwrap();
+        let a = a.to_str().unwrap();
+        let missing = dir.path().join("c");
+        let c = missing.to_str().unwrap();
+        let code = |args: &[&str]| cmd().args(args).status().unwrap().code();
+        let expr = [
+            "(",
+            "-f",
+            a,
+            "-o",
+            "-d",
+            "/nonexistent",
+            ")",
+            "-a",
+            "!",
+            "-e",
+        ];
+        assert_eq!(code(&[&expr[..], &[c]].concat()), Some(0));
+        assert_eq!(code(&[&expr[..], &[a]].concat()), Some(1));
+        assert_eq!(code(&["(", "x", ")", "-a", "(", "", ")"]), Some(1));
+        assert_eq!(code(&["(", "x", ")", "-o", "(", "", ")"]), Some(0));
+        assert_eq!(code(&["(", "x", "=", "x"]), Some(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_argument_count_rules() {
+        let code = |args: &[&str]| cmd().args(args).status().unwrap().code();
+        // One argument: true if non-empty, even if it looks like an operator
+        assert_eq!(code(&["-n"]), Some(0));
+        assert_eq!(code(&["-a"]), Some(0));
+        assert_eq!(code(&[""]), Some(1));
+        // Two arguments: ! negates a string test
+        assert_eq!(code(&["!", "-a"]), Some(1));
+        assert_eq!(code(&["!", ""]), Some(0));
+        // Three arguments: a binary operator in the middle wins
+        assert_eq!(code(&["=", "=", "="]), Some(0));
+        assert_eq!(code(&["!", "=", "="]), Some(1));
+        assert_eq!(code(&["-a", "=", "-a"]), Some(0));
+        assert_eq!(code(&["(", "", ")"]), Some(1));
+        // Four arguments: ! before a three-argument expression
+        assert_eq!(code(&["!", "=", "=", "="]), Some(1));
+        assert_eq!(code(&["(", "!", "", ")"]), Some(0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_integer_operands() {
+        let code = |args: &[&str]| cmd().args(args).status().unwrap().code();
+        // Surrounding blanks and a leading + are allowed
+        assert_eq!(code(&[" 1", "-lt", "+2 "]), Some(0));
+        // Integers of any size compare correctly
+        assert_eq!(
+            code(&["99999999999999999999", "-gt", "99999999999999999998"]),
+            Some(0)
+        );
+        assert_eq!(code(&["-0", "-eq", "0"]), Some(0));
+        assert_eq!(code(&["1x", "-lt", "2"]), Some(2));
+        // -l STRING is the length of STRING
+        assert_eq!(code(&["-l", "abc", "-eq", "3"]), Some(0));
+        assert_eq!(code(&["2", "-eq", "-l", "ab"]), Some(0));
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_too_many_args() {
@@ -226,8 +226,29 @@ fn parse_args() -> Cli {
         }
     }
 
-    if cli.sets.is_empty() {
-        eprintln!("tr: missing operand");
+    // Like GNU: translating and -ds take two operands, -d and -s one, and
+    // -s may add a second for translation.
+    let min_operands = if cli.delete == cli.squeeze { 2 } else { 1 };
+    let max_operands = if cli.delete && !cli.squeeze { 1 } else { 2 };
+    if cli.sets.len() < min_operands {
+        if cli.sets.is_empty() {
+            eprintln!("tr: missing operand");
+        } else {
+            eprintln!("tr: missing operand after '{}'", cli.sets[0]);
+            if cli.squeeze {
+                eprintln!("Two strings must be given when both deleting and squeezing repeats.");
+            } else {
+                eprintln!("Two strings must be given when translating.");
+            }
+        }
+        eprintln!("Try 'tr --help' for more information.");
+        process::exit(1);
+    }
+    if cli.sets.len() > max_operands {
+        eprintln!("tr: extra operand '{}'", cli.sets[max_operands]);
+        if cli.sets.len() == 2 {
+            eprintln!("Only one string may be given when deleting without squeezing repeats.");
+        }
         eprintln!("Try 'tr --help' for more information.");
         process::exit(1);
     }
@@ -235,6 +256,34 @@ fn parse_args() -> Cli {
     cli
 }
 
+/// GNU tr's rules on where bracket constructs may appear: no `[c*]` in
+/// SET1, at most one in SET2 and only when translating, and when
+/// translating no `[=c=]` in SET2 and no classes there but upper and lower.
+fn check_set_constructs(cli: &Cli, constructs: &[tr::SetConstructs]) -> Result<(), &'static str> {
+    if constructs[0].fill_repeats > 0 {
+        return Err("the [c*] repeat construct may not appear in string1");
+    }
+    let Some(set2) = constructs.get(1) else {
+        return Ok(());
+    };
+    if set2.fill_repeats > 1 {
+        return Err("only one [c*] repeat construct may appear in string2");
+    }
+    if cli.delete {
+        if set2.fill_repeats > 0 {
+            return Err("the [c*] construct may appear in string2 only when translating");
+        }
+    } else if set2.equiv_class {
+        return Err("[=c=] expressions may not appear in string2 when translating");
+    } else if set2.restricted_class {
+        return Err(
+            "when translating, the only character classes that may appear in\n\
+             string2 are 'upper' and 'lower'",
+        );
+    }
+    Ok(())
+}
+
 /// Raw fd stdout for zero-overhead writes on non-Linux Unix.
 /// On Linux, VmspliceWriter is used instead for zero-copy pipe output.
 #[cfg(all(unix, not(target_os = "linux")))]
@@ -390,6 +439,24 @@ fn main() {
 
     let cli = parse_args();
 
+    let mut constructs = Vec::with_capacity(cli.sets.len());
+    for set in &cli.sets {
+        for warning in tr::escape_warnings(set) {
+            eprintln!("tr: warning: {}", warning);
+        }
+        match tr::check_set(set) {
+            Ok(found) => constructs.push(found),
+            Err(msg) => {
+                eprintln!("tr: {}", msg);
+                process::exit(1);
+            }
+        }
+    }
+    if let Err(msg) = check_set_constructs(&cli, &constructs) {
+        eprintln!("tr: {}", msg);
+        process::exit(1);
+    }
+
     let set1_str = &cli.sets[0];
 
     #[cfg(all(unix, not(target_os = "linux")))]
@@ -407,7 +474,15 @@ fn main() {
         if cli.complement {
             set1 = tr::complement(&set1);
             // Complement mode replaces the set entirely — class positions are meaningless.
-            // Skip case class validation (GNU tr also skips it for -c).
+            // Skip case class alignment (GNU tr also skips it for -c), but SET2 still
+            // can't end with a class it would have to be extended past.
+            let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
+            if let Err(msg) =
+                tr::validate_set2_class_at_end(set1.len(), set2_raw.len(), &set2_classes)
+            {
+                eprintln!("tr: {}", msg);
+                process::exit(1);
+            }
         } else {
             // Validate case class alignment before expanding SET2
             let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
@@ -423,6 +498,7 @@ fn main() {
                 process::exit(1);
             }
         }
+        let set1_len = set1.len();
         let set2 = if cli.truncate {
             let raw_set = tr::parse_set(set2_str);
             set1.truncate(raw_set.len());
@@ -430,6 +506,18 @@ fn main() {
         } else {
             tr::expand_set2(set2_str, set1.len())
         };
+        if !cli.truncate
+            && let Err(msg) = tr::validate_set2_nonempty(set1.len(), set2.len())
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
+        if cli.complement
+            && let Err(msg) = tr::validate_complement_classes(set1_str, set1_len, &set2)
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
 
         // Try MAP_PRIVATE mmap first for in-place translate (avoids separate buffer
         // allocation). With MADV_HUGEPAGE, COW faults use 2MB pages — even for 10MB
@@ -571,12 +659,6 @@ fn main() {
 /// Processes data chunk-by-chunk for pipeline parallelism with upstream cat.
 fn run_streaming_mode(cli: &Cli, set1_str: &str, writer: &mut impl Write) -> io::Result<()> {
     if cli.delete && cli.squeeze {
-        if cli.sets.len() < 2 {
-            eprintln!("tr: missing operand after '{}'", set1_str);
-            eprintln!("Two strings must be given when both deleting and squeezing repeats.");
-            eprintln!("Try 'tr --help' for more information.");
-            process::exit(1);
-        }
         let set2_str = &cli.sets[1];
         let set1 = tr::parse_set(set1_str);
         let set2 = tr::parse_set(set2_str);
@@ -587,12 +669,6 @@ fn run_streaming_mode(cli: &Cli, set1_str: &str, writer: &mut impl Write) -> io:
         };
         with_stdin_reader!(reader => tr::delete_squeeze(&delete_set, &set2, &mut reader, writer))
     } else if cli.delete {
-        if cli.sets.len() > 1 {
-            eprintln!("tr: extra operand '{}'", cli.sets[1]);
-            eprintln!("Only one string may be given when deleting without squeezing.");
-            eprintln!("Try 'tr --help' for more information.");
-            process::exit(1);
-        }
         let set1 = tr::parse_set(set1_str);
         let delete_set = if cli.complement {
             tr::complement(&set1)
@@ -608,11 +684,19 @@ fn run_streaming_mode(cli: &Cli, set1_str: &str, writer: &mut impl Write) -> io:
             set1
         };
         with_stdin_reader!(reader => tr::squeeze(&squeeze_set, &mut reader, writer))
-    } else if cli.squeeze {
+    } else {
+        // Squeezing after translation; pure translation is handled in main.
         let set2_str = &cli.sets[1];
         let (mut set1, set1_classes) = tr::parse_set_with_classes(set1_str);
         if cli.complement {
             set1 = tr::complement(&set1);
+            let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
+            if let Err(msg) =
+                tr::validate_set2_class_at_end(set1.len(), set2_raw.len(), &set2_classes)
+            {
+                eprintln!("tr: {}", msg);
+                process::exit(1);
+            }
         } else {
             let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
             if let Err(msg) = tr::validate_case_classes(&set1_classes, &set2_classes) {
@@ -626,6 +710,7 @@ fn run_streaming_mode(cli: &Cli, set1_str: &str, writer: &mut impl Write) -> io:
                 process::exit(1);
             }
         }
+        let set1_len = set1.len();
         let set2 = if cli.truncate {
             let raw_set = tr::parse_set(set2_str);
             set1.truncate(raw_set.len());
@@ -633,12 +718,19 @@ fn run_streaming_mode(cli: &Cli, set1_str: &str, writer: &mut impl Write) -> io:
         } else {
             tr::expand_set2(set2_str, set1.len())
         };
+        if !cli.truncate
+            && let Err(msg) = tr::validate_set2_nonempty(set1.len(), set2.len())
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
+        if cli.complement
+            && let Err(msg) = tr::validate_complement_classes(set1_str, set1_len, &set2)
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
         with_stdin_reader!(reader => tr::translate_squeeze(&set1, &set2, &mut reader, writer))
-    } else {
-        eprintln!("tr: missing operand after '{}'", set1_str);
-        eprintln!("Two strings must be given when translating.");
-        eprintln!("Try 'tr --help' for more information.");
-        process::exit(1);
     }
 }
 
@@ -650,12 +742,6 @@ fn run_mmap_mode(
     writer: &mut impl Write,
 ) -> io::Result<()> {
     if cli.delete && cli.squeeze {
-        if cli.sets.len() < 2 {
-            eprintln!("tr: missing operand after '{}'", set1_str);
-            eprintln!("Two strings must be given when both deleting and squeezing repeats.");
-            eprintln!("Try 'tr --help' for more information.");
-            process::exit(1);
-        }
         let set2_str = &cli.sets[1];
         let set1 = tr::parse_set(set1_str);
         let set2 = tr::parse_set(set2_str);
@@ -666,12 +752,6 @@ fn run_mmap_mode(
         };
         tr::delete_squeeze_mmap(&delete_set, &set2, data, writer)
     } else if cli.delete {
-        if cli.sets.len() > 1 {
-            eprintln!("tr: extra operand '{}'", cli.sets[1]);
-            eprintln!("Only one string may be given when deleting without squeezing.");
-            eprintln!("Try 'tr --help' for more information.");
-            process::exit(1);
-        }
         let set1 = tr::parse_set(set1_str);
         let delete_set = if cli.complement {
             tr::complement(&set1)
@@ -687,11 +767,19 @@ fn run_mmap_mode(
             set1
         };
         tr::squeeze_mmap(&squeeze_set, data, writer)
-    } else if cli.squeeze {
+    } else {
+        // Squeezing after translation; pure translation is handled in main.
         let set2_str = &cli.sets[1];
         let (mut set1, set1_classes) = tr::parse_set_with_classes(set1_str);
         if cli.complement {
             set1 = tr::complement(&set1);
+            let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
+            if let Err(msg) =
+                tr::validate_set2_class_at_end(set1.len(), set2_raw.len(), &set2_classes)
+            {
+                eprintln!("tr: {}", msg);
+                process::exit(1);
+            }
         } else {
             let (set2_raw, set2_classes) = tr::parse_set_with_classes(set2_str);
             if let Err(msg) = tr::validate_case_classes(&set1_classes, &set2_classes) {
@@ -705,6 +793,7 @@ fn run_mmap_mode(
                 process::exit(1);
             }
         }
+        let set1_len = set1.len();
         let set2 = if cli.truncate {
             let raw_set = tr::parse_set(set2_str);
             set1.truncate(raw_set.len());
@@ -712,12 +801,19 @@ fn run_mmap_mode(
         } else {
             tr::expand_set2(set2_str, set1.len())
         };
+        if !cli.truncate
+            && let Err(msg) = tr::validate_set2_nonempty(set1.len(), set2.len())
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
+        if cli.complement
+            && let Err(msg) = tr::validate_complement_classes(set1_str, set1_len, &set2)
+        {
+            eprintln!("tr: {}", msg);
+            process::exit(1);
+        }
         tr::translate_squeeze_mmap(&set1, &set2, data, writer)
-    } else {
-        eprintln!("tr: missing operand after '{}'", set1_str);
-        eprintln!("Two strings must be given when translating.");
-        eprintln!("Try 'tr --help' for more information.");
-        process::exit(1);
     }
 }
 
@@ -863,6 +959,40 @@ mod tests {
         assert_eq!(output.stdout, b"");
     }
 
+    #[test]
+    fn test_tr_complement_delete_class_from_file() {
+        // A regular-file stdin takes the mmap path; mix long kept and deleted
+        // runs with short alternations so every 32-byte block shape is hit.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.txt");
+        let mut data = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..200u32 {
+            let b = (i * 37 % 256) as u8;
+            data.push(b);
+            if b.is_ascii_alphanumeric() {
+                expected.push(b);
+            }
+        }
+        let word = b"Alpha42 beta-gamma, ".repeat(10);
+        data.extend_from_slice(&word);
+        expected.extend(word.iter().filter(|b| b.is_ascii_alphanumeric()));
+        data.extend_from_slice(&[b'.'; 70]);
+        data.extend_from_slice(&[b'z'; 70]);
+        data.extend_from_slice(b"!x");
+        expected.extend_from_slice(&[b'z'; 70]);
+        expected.push(b'x');
+        std::fs::write(&path, &data).unwrap();
+
+        let output = cmd()
+            .args(["-cd", "[:alnum:]"])
+            .stdin(std::fs::File::open(&path).unwrap())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, expected);
+    }
+
     #[test]
     fn test_tr_delete_newlines() {
         use std::io::Write;
@@ -938,4 +1068,275 @@ mod tests {
         // -t truncates set1 to match set2 length; only a→x, b→y; c unchanged
         assert_eq!(String::from_utf8_lossy(&output.stdout), "xycxyc\n");
     }
+
+    #[test]
+    fn test_tr_complement_translate_to_one_char() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-c", "a-zA-Z", "\\n"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"hello, World 42!\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello\n\nWorld\n\n\n\n\n");
+
+        // Every byte value through the file (mmap) path, long enough for the
+        // vector loops: everything outside a-z becomes '\n'.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.bin");
+        let data: Vec<u8> = (0..4099u32).map(|i| (i * 7 % 256) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+        let expected: Vec<u8> = data
+            .iter()
+            .map(|&b| if b.is_ascii_lowercase() { b } else { b'\n' })
+            .collect();
+        let output = cmd()
+            .args(["-c", "a-z", "\\n"])
+            .stdin(std::fs::File::open(&path).unwrap())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, expected);
+        let output = cmd()
+            .args(["-c", "[:lower:]", "[\\n*]"])
+            .stdin(std::fs::File::open(&path).unwrap())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, expected);
+    }
+
+    #[test]
+    fn test_tr_complement_class_needs_single_target() {
+        for args in [
+            ["-c", "[:alpha:]", "xy"],
+            ["-c", "[:alpha:]", "[x*]y"],
+            ["-ct", "[:alpha:]", "x"],
+            ["-cs", "[:alpha:]", "xy"],
+        ] {
+            let output = cmd().args(args).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "tr: when translating with complemented character classes,\n\
+                 string2 must map all characters in the domain to one\n"
+            );
+        }
+        // Without a class the complement keeps its byte order, so SET2 may vary.
+        let output = cmd()
+            .args(["-c", "abc", "xyz"])
+            .stdin(std::process::Stdio::null())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let output = cmd().args(["-c", "a", "[:upper:]"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains("the latter string must not end with a character class")
+        );
+    }
+
+    #[test]
+    fn test_tr_empty_set2_requires_truncate() {
+        for args in [
+            &["abc", ""][..],
+            &["-s", "abc", ""],
+            &["-c", "abc", ""],
+            &["[:upper:]", ""],
+        ] {
+            let output = cmd()
+                .args(args)
+                .stdin(std::process::Stdio::null())
+                .output()
+                .unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert!(output.stdout.is_empty());
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                "tr: when not truncating set1, string2 must be non-empty\n"
+            );
+        }
+        // -t, an empty SET1, or a single set for -s or -ds are all fine.
+        for args in [
+            &["-t", "abc", ""][..],
+            &["", ""],
+            &["-s", "abc"],
+            &["-ds", "abc", ""],
+        ] {
+            let output = cmd()
+                .args(args)
+                .stdin(std::process::Stdio::null())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_tr_octal_escapes_and_nul() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.bin");
+        let data = b"a\0b\0\0c\x07\\\n".repeat(1000);
+        std::fs::write(&path, &data).unwrap();
+        let map =
+            |f: fn(u8) -> Option<u8>| -> Vec<u8> { data.iter().filter_map(|&b| f(b)).collect() };
+
+        let cases: [(&[&str], Vec<u8>); 5] = [
+            (
+                &["\\000", " "],
+                map(|b| Some(if b == 0 { b' ' } else { b })),
+            ),
+            (
+                &["\\0\\7", "xy"],
+                map(|b| {
+                    Some(match b {
+                        0 => b'x',
+                        7 => b'y',
+                        _ => b,
+                    })
+                }),
+            ),
+            (&["-d", "\\0"], map(|b| (b != 0).then_some(b))),
+            (
+                &["-d", "\\a\\\\\\n"],
+                map(|b| (!b"\x07\\\n".contains(&b)).then_some(b)),
+            ),
+            (&["-s", "\\000"], b"a\0b\0c\x07\\\n".repeat(1000)),
+        ];
+        for (args, expected) in &cases {
+            // A regular-file stdin takes the mmap path, a pipe the streaming one.
+            let output = cmd()
+                .args(*args)
+                .stdin(std::fs::File::open(&path).unwrap())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            assert!(output.stdout == *expected, "mmap {:?}", args);
+
+            let mut child = cmd()
+                .args(*args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child.stdin.take().unwrap().write_all(&data).unwrap();
+            let output = child.wait_with_output().unwrap();
+            assert!(output.stdout == *expected, "streaming {:?}", args);
+        }
+
+        // \400 is \40 followed by '0'; a trailing backslash is literal.
+        let output = cmd()
+            .args(["\\400a\\", "xyzw"])
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "tr: warning: the ambiguous octal escape \\400 is being\n\
+             \tinterpreted as the 2-byte sequence \\040, 0\n\
+             tr: warning: an unescaped backslash at end of string is not portable\n"
+        );
+        let mut child = cmd()
+            .args(["\\400a\\", "xyzw"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b" 0a\\").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.stdout, b"xyzw");
+    }
+
+    #[test]
+    fn test_tr_bracket_construct_errors() {
+        use std::process::Stdio;
+        let cases: [(&[&str], &str); 10] = [
+            (&["[::]", "x"], "tr: missing character class name '[::]'\n"),
+            (
+                &["[==]", "x"],
+                "tr: missing equivalence class character '[==]'\n",
+            ),
+            (&["[:foo:]", "x"], "tr: invalid character class 'foo'\n"),
+            (
+                &["a", "[x*08]"],
+                "tr: invalid repeat count '08' in [c*n] construct\n",
+            ),
+            (
+                &["[x*]", "a"],
+                "tr: the [c*] repeat construct may not appear in string1\n",
+            ),
+            (
+                &["ab", "[x*][y*]"],
+                "tr: only one [c*] repeat construct may appear in string2\n",
+            ),
+            (
+                &["-ds", "ab", "[x*]"],
+                "tr: the [c*] construct may appear in string2 only when translating\n",
+            ),
+            (
+                &["ab", "[=a=]"],
+                "tr: [=c=] expressions may not appear in string2 when translating\n",
+            ),
+            (
+                &["a", "[:digit:]"],
+                "tr: when translating, the only character classes that may appear in\n\
+                 string2 are 'upper' and 'lower'\n",
+            ),
+            (
+                &["-d", "a", "b"],
+                "tr: extra operand 'b'\n\
+                 Only one string may be given when deleting without squeezing repeats.\n\
+                 Try 'tr --help' for more information.\n",
+            ),
+        ];
+        for (args, expected) in &cases {
+            let output = cmd().args(*args).stdin(Stdio::null()).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stderr),
+                *expected,
+                "{:?}",
+                args
+            );
+        }
+
+        // Unterminated constructs are literal, and a blank-led count is accepted.
+        for (args, input, expected) in [
+            (&["[:alpha", "x"][..], "[a:", "xxx"),
+            (&["a", "[x* 3]"][..], "a", "x"),
+            (&["[:lower:]", "[:upper:]"][..], "ab", "AB"),
+        ] {
+            let output = cmd()
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    use std::io::Write;
+                    child.stdin.take().unwrap().write_all(input.as_bytes())?;
+                    child.wait_with_output()
+                })
+                .unwrap();
+            assert!(output.status.success(), "{:?}", args);
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+    }
 }
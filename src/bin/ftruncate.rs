@@ -140,13 +140,9 @@ fn compute_new_size(current: u64, mode: SizeMode, size: u64) -> u64 {
                 current
             }
         }
-        SizeMode::RoundDown => {
-            if size == 0 {
-                current
-            } else {
-                (current / size) * size
-            }
-        }
+        SizeMode::RoundDown => current
+            .checked_div(size)
+            .map_or(current, |blocks| blocks * size),
         SizeMode::RoundUp => {
             if size == 0 {
                 current
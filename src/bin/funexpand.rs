@@ -376,4 +376,48 @@ mod tests {
         let output = cmd().arg("/nonexistent_xyz_unexpand").output().unwrap();
         assert!(!output.status.success());
     }
+
+    #[test]
+    fn test_unexpand_tab_list_increment() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-t", "2,+4"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"          x\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        // Stops at 2, 6, 10: all ten blanks become tabs
+        assert_eq!(output.stdout, b"\t\t\tx\n");
+    }
+
+    #[test]
+    fn test_unexpand_tab_list_past_last_stop() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-t", "4,8,16"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"a\tb\tc\td\te\n                  x  y\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        // Blanks at or past the last stop (16) are copied as they are.
+        assert_eq!(output.stdout, b"a\tb\tc\td\te\n\t\t\t  x  y\n");
+    }
 }
@@ -41,6 +41,10 @@ struct Cli {
     #[arg(short = 'w', long = "words")]
     words: bool,
 
+    /// Line delimiter is NUL, not newline
+    #[arg(short = 'z', long = "zero-terminated")]
+    zero_terminated: bool,
+
     /// Read input from the files specified by NUL-terminated names in file F
     #[arg(long = "files0-from", value_name = "F")]
     files0_from: Option<String>,
@@ -86,8 +90,9 @@ const LINE_PARALLEL_THRESHOLD: usize = 2 * 1024 * 1024;
 /// Lines-only fast path: mmap + parallel SIMD memchr for maximum throughput.
 /// For files > 2MB, splits data across all CPU cores for parallel newline counting.
 /// Uses populate() to pre-fault pages and MADV_HUGEPAGE to reduce TLB misses.
+/// With `zero_terminated`, counts NUL-terminated records instead of newlines.
 /// Returns (line_count, byte_count).
-fn count_lines_streaming(path: &Path) -> io::Result<(u64, u64)> {
+fn count_lines_streaming(path: &Path, zero_terminated: bool) -> io::Result<(u64, u64)> {
     let file = std::fs::File::open(path)?;
     let meta = file.metadata()?;
     let file_bytes = meta.len();
@@ -126,6 +131,9 @@ fn count_lines_streaming(path: &Path) -> io::Result<(u64, u64)> {
         }
 
         // Parallel counting for large files: split across CPU cores
+        if zero_terminated {
+            return Ok((wc::count_lines_zero(&mmap), file_bytes));
+        }
         let lines = if mmap.len() >= LINE_PARALLEL_THRESHOLD {
             let num_threads = rayon::current_num_threads().max(1);
             // Use 1MB min chunk size — amortizes rayon scheduling overhead
@@ -153,7 +161,9 @@ fn count_lines_streaming(path: &Path) -> io::Result<(u64, u64)> {
             );
         }
     }
+    let delim = if zero_terminated { b'\0' } else { b'\n' };
     let mut lines = 0u64;
+    let mut last = delim;
     let mut buf = vec![0u8; 2 * 1024 * 1024]; // 2MB — matches huge page size for aligned I/O
     let mut reader = file;
     loop {
@@ -161,7 +171,12 @@ fn count_lines_streaming(path: &Path) -> io::Result<(u64, u64)> {
         if n == 0 {
             break;
         }
-        lines += memchr_iter(b'\n', &buf[..n]).count() as u64;
+        lines += memchr_iter(delim, &buf[..n]).count() as u64;
+        last = buf[n - 1];
+    }
+    // A final NUL-terminated record without its terminator still counts
+    if zero_terminated && last != delim {
+        lines += 1;
     }
     Ok((lines, file_bytes))
 }
@@ -231,22 +246,11 @@ fn main() {
         max_line_length: cli.max_line_length,
     };
 
-    let total_mode = cli.total.as_str();
-
-    // Validate --total value (GNU wc rejects invalid values)
-    match total_mode {
-        "auto" | "always" | "only" | "never" => {}
-        _ => {
-            eprintln!("wc: invalid argument '{}' for '--total'", cli.total);
-            eprintln!("Valid arguments are:");
-            eprintln!("  - 'auto'");
-            eprintln!("  - 'always'");
-            eprintln!("  - 'only'");
-            eprintln!("  - 'never'");
-            eprintln!("Try 'wc --help' for more information.");
-            process::exit(1);
-        }
-    }
+    let total_mode = wc::TotalMode::parse(&cli.total).unwrap_or_else(|e| {
+        eprintln!("wc: {}", e);
+        eprintln!("Try 'wc --help' for more information.");
+        process::exit(1);
+    });
 
     // Collect files to process
     let files: Vec<String> = if let Some(ref f0f) = cli.files0_from {
@@ -297,7 +301,7 @@ fn main() {
         // Fast path: -l only on regular files — stream through with memchr
         // Avoids mmap overhead (page tables) and rayon thread pool init
         if show.lines_only() && filename != "-" {
-            match count_lines_streaming(Path::new(filename)) {
+            match count_lines_streaming(Path::new(filename), cli.zero_terminated) {
                 Ok((lines, bytes)) => {
                     let counts = wc::WcCounts {
                         lines,
@@ -360,7 +364,7 @@ fn main() {
         // cost (~0.5-1ms per process) which dominates for single-file benchmarks.
         let use_parallel = data.len() >= WC_PARALLEL_THRESHOLD;
 
-        let counts = if show.max_line_length && (show.lines || show.words) {
+        let mut counts = if show.max_line_length && (show.lines || show.words) {
             // All metrics including max_line_length: use fused parallel count_all
             if use_parallel {
                 let mut c = wc::count_all_parallel(&data, utf8_locale);
@@ -460,6 +464,16 @@ fn main() {
             }
         };
 
+        if cli.zero_terminated {
+            // Only lines and max line length depend on the delimiter
+            if show.lines || counts.lines != 0 {
+                counts.lines = wc::count_lines_zero(&data);
+            }
+            if show.max_line_length {
+                counts.max_line_length = wc::max_line_length_zero(&data, utf8_locale);
+            }
+        }
+
         total.lines += counts.lines;
         total.words += counts.words;
         total.bytes += counts.bytes;
@@ -479,17 +493,11 @@ fn main() {
     // Phase 2: Compute column width
     // GNU wc uses the digit width of the largest value across all computed metrics
     // (including bytes, which is always computed) for column alignment.
-    // Special case: single file + single column + no total = natural width.
+    // Special case: single file + single column = natural width, even with
+    // a total line.
     // For stdin with no files, GNU uses a default minimum width of 7.
     // --total=only: GNU uses width 1 (natural width, no padding).
-
-    // Determine whether to print total line (needed for width calculation)
-    let show_total = match total_mode {
-        "always" => true,
-        "never" => false,
-        "only" => true,
-        _ => results.len() > 1, // "auto"
-    };
+    let show_total = total_mode.shows_total(files.len());
 
     let num_columns = show.lines as usize
         + show.words as usize
@@ -497,22 +505,15 @@ fn main() {
         + show.chars as usize
         + show.max_line_length as usize;
 
-    let num_output_rows = if total_mode == "only" {
-        if show_total { 1 } else { 0 }
-    } else {
-        results.len() + if show_total { 1 } else { 0 }
-    };
-
     let min_width = if has_stdin && results.len() == 1 {
         7
     } else {
         1
     };
 
-    let width = if total_mode == "only" {
-        // --total=only: GNU uses width 1 (natural width, no padding)
+    let width = if total_mode == wc::TotalMode::Only {
         1
-    } else if num_columns <= 1 && num_output_rows <= 1 {
+    } else if num_columns <= 1 && files.len() <= 1 {
         // Single value output: no alignment needed, use natural width
         // min_width (7 for stdin) only applies to multi-column output
         let single_val = if show.lines {
@@ -554,14 +555,18 @@ fn main() {
     let mut out = BufWriter::with_capacity(64 * 1024, io::stdout().lock());
 
     // --total=only: suppress individual file output
-    if total_mode != "only" {
+    if total_mode != wc::TotalMode::Only {
         for (counts, name) in &results {
             print_counts_fmt(&mut out, counts, name, width, &show);
         }
     }
 
     if show_total {
-        let label = if total_mode == "only" { "" } else { "total" };
+        let label = if total_mode == wc::TotalMode::Only {
+            ""
+        } else {
+            "total"
+        };
         print_counts_fmt(&mut out, &total, label, width, &show);
     }
 
@@ -968,4 +973,108 @@ mod tests {
             stdout.trim()
         );
     }
+
+    #[test]
+    fn test_wc_zero_terminated_lines() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .arg("-lz")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"a\nb\0c\0d")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "3");
+    }
+
+    #[test]
+    fn test_wc_zero_terminated_file_fast_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("records");
+        std::fs::write(&path, b"one\0two\0three\0").unwrap();
+        let output = cmd().args(["-l", "-z"]).arg(&path).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("3 "), "got: {}", stdout);
+    }
+
+    #[test]
+    fn test_wc_zero_terminated_max_length_and_bytes() {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = cmd()
+            .args(["-zLc"])
+            .env("LC_ALL", "C")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"ab\0abcde\0")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let fields: Vec<&str> = stdout.split_whitespace().collect();
+        assert_eq!(fields, ["9", "5"]);
+    }
+
+    #[test]
+    fn test_wc_total_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, "hello world\nfoo\n").unwrap();
+        std::fs::write(&b, "x\n".repeat(1000)).unwrap();
+        let run = |args: &[&str], files: &[&std::path::Path]| {
+            let output = cmd()
+                .args(args)
+                .args(files)
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+        // `only` prints the sum at its natural width.
+        assert_eq!(run(&["--total=only"], &[&a, &b]), "1002 1003 2016\n");
+        assert_eq!(run(&["--total=o", "-l"], &[&a, &b]), "1002\n");
+        assert_eq!(
+            run(&["--total=never"], &[&a, &b]).lines().count(),
+            2,
+            "no total line"
+        );
+        let stdout = run(&["--total=always", "-c"], &[&a]);
+        assert!(stdout.ends_with("\n16 total\n"), "{:?}", stdout);
+        // `auto` counts operands that could not be read.
+        let missing = dir.path().join("missing");
+        let stdout = run(&[], &[&missing, &a]);
+        assert!(stdout.ends_with(" total\n"), "{:?}", stdout);
+        assert_eq!(run(&["--total=only"], &[&missing, &a]), "2 3 16\n");
+    }
+
+    #[test]
+    fn test_wc_total_invalid_argument() {
+        for (arg, kind) in [("--total=a", "ambiguous"), ("--total=sometimes", "invalid")] {
+            let output = cmd().arg(arg).output().unwrap();
+            assert_eq!(output.status.code(), Some(1));
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                stderr.starts_with(&format!("wc: {} argument", kind)),
+                "{}",
+                stderr
+            );
+            assert!(stderr.contains("  - 'only'\n"), "{}", stderr);
+        }
+    }
 }
@@ -12,10 +12,43 @@ pub struct CatConfig {
     pub show_tabs: bool,
     pub show_nonprinting: bool,
     pub squeeze_blank: bool,
+    /// -u: deliver output as input arrives instead of batching it
+    pub unbuffered: bool,
 }
 
 impl CatConfig {
-    /// Returns true if no special processing is needed (plain cat)
+    /// Apply a short option letter. The combined forms expand like GNU cat:
+    /// `-A` is `-vET`, `-e` is `-vE` and `-t` is `-vT`. Returns false if `c`
+    /// is not a cat option.
+    pub fn apply_short_option(&mut self, c: u8) -> bool {
+        match c {
+            b'A' => {
+                self.show_nonprinting = true;
+                self.show_ends = true;
+                self.show_tabs = true;
+            }
+            b'b' => self.number_nonblank = true,
+            b'e' => {
+                self.show_nonprinting = true;
+                self.show_ends = true;
+            }
+            b'E' => self.show_ends = true,
+            b'n' => self.number = true,
+            b's' => self.squeeze_blank = true,
+            b't' => {
+                self.show_nonprinting = true;
+                self.show_tabs = true;
+            }
+            b'T' => self.show_tabs = true,
+            b'u' => self.unbuffered = true,
+            b'v' => self.show_nonprinting = true,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns true if no special processing is needed (plain cat).
+    /// -u does not count: the plain paths already write each read straight through.
     pub fn is_plain(&self) -> bool {
         !self.number
             && !self.number_nonblank
@@ -26,6 +59,31 @@ impl CatConfig {
     }
 }
 
+/// Output state carried from one read (and one file) to the next, so that
+/// numbering and squeezing treat all input as a single stream.
+#[derive(Clone, Debug)]
+pub struct CatState {
+    /// Number given to the next numbered line.
+    pub line_num: u64,
+    /// The input so far ended in the middle of a line.
+    pub mid_line: bool,
+    /// The last line written was blank (for -s).
+    pub prev_blank: bool,
+    /// A trailing CR is held back until we know whether LF follows (-E).
+    pub pending_cr: bool,
+}
+
+impl Default for CatState {
+    fn default() -> Self {
+        Self {
+            line_num: 1,
+            mid_line: false,
+            prev_blank: false,
+            pending_cr: false,
+        }
+    }
+}
+
 /// Use splice for zero-copy file→stdout on Linux (file → pipe)
 #[cfg(target_os = "linux")]
 pub fn splice_file_to_stdout(path: &Path) -> io::Result<bool> {
@@ -40,6 +98,7 @@ pub fn splice_file_to_stdout(path: &Path) -> io::Result<bool> {
         return Ok(false);
     }
     let stdout_is_pipe = (stat.st_mode & libc::S_IFMT) == libc::S_IFIFO;
+    let stdout_is_regular = (stat.st_mode & libc::S_IFMT) == libc::S_IFREG;
 
     let file = std::fs::OpenOptions::new()
         .read(true)
@@ -49,6 +108,9 @@ pub fn splice_file_to_stdout(path: &Path) -> io::Result<bool> {
 
     let in_fd = file.as_raw_fd();
     let metadata = file.metadata()?;
+    if metadata.is_dir() {
+        return Err(io::Error::from_raw_os_error(libc::EISDIR));
+    }
     let file_size = metadata.len() as usize;
 
     if file_size == 0 {
@@ -80,24 +142,115 @@ pub fn splice_file_to_stdout(path: &Path) -> io::Result<bool> {
                     continue;
                 }
                 // splice not supported — fall through to sendfile
-                return sendfile_to_stdout(in_fd, file_size, out_fd);
+                return sendfile_to_stdout(in_fd, &mut 0, file_size, out_fd).map(|()| true);
             }
         }
         Ok(true)
+    } else if stdout_is_regular && metadata.is_file() {
+        // copy_file_range: in-kernel file→file copy
+        copy_range_to_stdout(&file, file_size, out_fd)
     } else {
         // sendfile: zero-copy file→socket/file
-        sendfile_to_stdout(in_fd, file_size, out_fd)
+        sendfile_to_stdout(in_fd, &mut 0, file_size, out_fd).map(|()| true)
     }
 }
 
+/// Whether a copy_file_range/sendfile error just means "not supported here",
+/// so the copy can continue with the next, slower method.
 #[cfg(target_os = "linux")]
-fn sendfile_to_stdout(in_fd: i32, file_size: usize, out_fd: i32) -> io::Result<bool> {
-    let mut offset: libc::off_t = 0;
+fn is_unsupported_copy(err: &io::Error) -> bool {
+    matches!(
+        err.raw_os_error(),
+        Some(libc::EINVAL | libc::ENOSYS | libc::EXDEV | libc::EBADF | libc::EOPNOTSUPP)
+    )
+}
+
+/// Copy a regular file to a regular-file stdout with copy_file_range, looping
+/// over partial copies. If the kernel refuses, the rest is sent with sendfile
+/// and then plain read/write, each picking up where the previous one stopped.
+#[cfg(target_os = "linux")]
+fn copy_range_to_stdout(file: &std::fs::File, file_size: usize, out_fd: i32) -> io::Result<bool> {
+    use std::os::unix::fs::FileExt;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    let in_fd = file.as_raw_fd();
+    let mut offset: libc::off64_t = 0;
     let mut remaining = file_size;
 
+    while remaining > 0 {
+        let chunk = remaining.min(0x4000_0000);
+        // SAFETY: both fds are open; the output offset is null so the kernel
+        // uses and advances stdout's own file position.
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_copy_file_range,
+                in_fd,
+                &mut offset as *mut libc::off64_t,
+                out_fd,
+                std::ptr::null_mut::<libc::off64_t>(),
+                chunk,
+                0u32,
+            )
+        };
+        if ret > 0 {
+            remaining -= ret as usize;
+        } else if ret == 0 {
+            // File shrank since fstat; everything left has been copied.
+            return Ok(true);
+        } else {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            if !is_unsupported_copy(&err) {
+                return Err(err);
+            }
+            break;
+        }
+    }
+    if remaining == 0 {
+        return Ok(true);
+    }
+
+    let mut sf_offset = offset as libc::off_t;
+    match sendfile_to_stdout(in_fd, &mut sf_offset, remaining, out_fd) {
+        Ok(()) => return Ok(true),
+        Err(e) if !is_unsupported_copy(&e) => return Err(e),
+        Err(_) => {}
+    }
+
+    // Plain read/write for the remainder, e.g. when stdout is O_APPEND.
+    // SAFETY: fd 1 stays open; ManuallyDrop keeps this File from closing it.
+    let mut stdout_file =
+        std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(out_fd) });
+    let mut pos = sf_offset as u64;
+    let mut buf = vec![0u8; 128 * 1024];
+    loop {
+        let n = match file.read_at(&mut buf, pos) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        stdout_file.write_all(&buf[..n])?;
+        pos += n as u64;
+    }
+    Ok(true)
+}
+
+/// sendfile from `*offset` for up to `len` bytes, advancing `*offset`.
+#[cfg(target_os = "linux")]
+fn sendfile_to_stdout(
+    in_fd: i32,
+    offset: &mut libc::off_t,
+    len: usize,
+    out_fd: i32,
+) -> io::Result<()> {
+    let mut remaining = len;
+
     while remaining > 0 {
         let chunk = remaining.min(0x7ffff000);
-        let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
+        let ret = unsafe { libc::sendfile(out_fd, in_fd, offset, chunk) };
         if ret > 0 {
             remaining -= ret as usize;
         } else if ret == 0 {
@@ -111,7 +264,7 @@ fn sendfile_to_stdout(in_fd: i32, file_size: usize, out_fd: i32) -> io::Result<b
         }
     }
 
-    Ok(true)
+    Ok(())
 }
 
 /// Plain cat for a single file — tries splice/sendfile, then falls back to mmap+write
@@ -358,11 +511,13 @@ fn cat_show_all_fast(
 }
 
 /// Cat with options (numbering, show-ends, show-tabs, show-nonprinting, squeeze)
+///
+/// Line numbers are computed from the input line before any `-v`/`-T`/`-E`
+/// visualisation, so the number itself is never escaped.
 pub fn cat_with_options(
     data: &[u8],
     config: &CatConfig,
-    line_num: &mut u64,
-    pending_cr: &mut bool,
+    state: &mut CatState,
     out: &mut impl Write,
 ) -> io::Result<()> {
     if data.is_empty() {
@@ -380,13 +535,12 @@ pub fn cat_with_options(
     let estimated = data.len() + data.len() / 10 + 1024;
     let mut buf = Vec::with_capacity(estimated.min(16 * 1024 * 1024));
 
-    let mut prev_blank = false;
     let mut pos = 0;
     let mut itoa_buf = itoa::Buffer::new();
 
     // Handle pending CR from previous file (only relevant for show_ends without show_nonprinting)
-    if *pending_cr {
-        *pending_cr = false;
+    if state.pending_cr {
+        state.pending_cr = false;
         if config.show_ends
             && !(config.show_nonprinting || config.show_tabs)
             && !data.is_empty()
@@ -395,6 +549,7 @@ pub fn cat_with_options(
             // CR from previous file + this LF = CRLF line ending → ^M$\n
             buf.extend_from_slice(b"^M$\n");
             pos = 1;
+            state.mid_line = false;
         } else {
             // CR not followed by LF, emit literally
             buf.push(b'\r');
@@ -408,33 +563,38 @@ pub fn cat_with_options(
             .unwrap_or(data.len());
 
         let line = &data[pos..line_end];
-        let is_blank = line == b"\n" || line.is_empty();
+        // The rest of a line begun by earlier input is neither blank nor numbered
+        let continued = state.mid_line;
+        let is_blank = !continued && line == b"\n";
+        state.mid_line = line.last() != Some(&b'\n');
 
         // Squeeze blank lines
-        if config.squeeze_blank && is_blank && prev_blank {
+        if config.squeeze_blank && is_blank && state.prev_blank {
             pos = line_end;
             continue;
         }
-        prev_blank = is_blank;
+        state.prev_blank = is_blank;
 
         // Line numbering - use itoa for fast integer formatting
-        if config.number_nonblank {
+        if continued {
+            // Numbered when the line started
+        } else if config.number_nonblank {
             if !is_blank {
-                let s = itoa_buf.format(*line_num);
+                let s = itoa_buf.format(state.line_num);
                 // Right-align in 6-char field
                 let pad = if s.len() < 6 { 6 - s.len() } else { 0 };
                 buf.extend(std::iter::repeat_n(b' ', pad));
                 buf.extend_from_slice(s.as_bytes());
                 buf.push(b'\t');
-                *line_num += 1;
+                state.line_num += 1;
             }
         } else if config.number {
-            let s = itoa_buf.format(*line_num);
+            let s = itoa_buf.format(state.line_num);
             let pad = if s.len() < 6 { 6 - s.len() } else { 0 };
             buf.extend(std::iter::repeat_n(b' ', pad));
             buf.extend_from_slice(s.as_bytes());
             buf.push(b'\t');
-            *line_num += 1;
+            state.line_num += 1;
         }
 
         // Process line content
@@ -482,7 +642,7 @@ pub fn cat_with_options(
                     // Trailing CR at end of data without following LF — hold as pending.
                     // It might pair with next file's LF to form CRLF line ending.
                     buf.extend_from_slice(&content[..content.len() - 1]);
-                    *pending_cr = true;
+                    state.pending_cr = true;
                 } else {
                     buf.extend_from_slice(content);
                 }
@@ -511,12 +671,72 @@ pub fn cat_with_options(
     Ok(())
 }
 
+/// Cat with options from a stream for -u: each read is processed and flushed
+/// as soon as it completes a line, instead of reading to EOF first. A trailing
+/// partial line (and with -s a trailing run of blank lines) is held back so
+/// numbering and squeezing still see whole lines. Read errors are reported
+/// against `display_name`; write errors are returned.
+fn cat_stream_with_options(
+    reader: &mut impl Read,
+    display_name: &str,
+    config: &CatConfig,
+    state: &mut CatState,
+    out: &mut impl Write,
+    tool_name: &str,
+) -> io::Result<bool> {
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut carry: Vec<u8> = Vec::new();
+    let mut ok = true;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                eprintln!(
+                    "{}: {}: {}",
+                    tool_name,
+                    display_name,
+                    crate::common::io_error_msg(&e)
+                );
+                ok = false;
+                break;
+            }
+        };
+        // Only the new bytes need scanning: carry holds an unfinished line,
+        // possibly after blank lines -s held back, which the loop below
+        // would hold back again without a newer line end.
+        let scanned = carry.len();
+        carry.extend_from_slice(&buf[..n]);
+        let Some(last_nl) = memchr::memrchr(b'\n', &carry[scanned..]).map(|i| scanned + i) else {
+            continue;
+        };
+        let mut split = last_nl + 1;
+        if config.squeeze_blank {
+            // carry always starts at a line start, so a '\n' at 0 is a blank line
+            while split > 0
+                && carry[split - 1] == b'\n'
+                && (split == 1 || carry[split - 2] == b'\n')
+            {
+                split -= 1;
+            }
+        }
+        if split > 0 {
+            cat_with_options(&carry[..split], config, state, out)?;
+            out.flush()?;
+            carry.drain(..split);
+        }
+    }
+    cat_with_options(&carry, config, state, out)?;
+    out.flush()?;
+    Ok(ok)
+}
+
 /// Process a single file for cat
 pub fn cat_file(
     filename: &str,
     config: &CatConfig,
-    line_num: &mut u64,
-    pending_cr: &mut bool,
+    state: &mut CatState,
     out: &mut impl Write,
     tool_name: &str,
 ) -> io::Result<bool> {
@@ -537,9 +757,21 @@ pub fn cat_file(
                 }
             }
         }
+        if config.unbuffered {
+            let stdin = io::stdin();
+            let mut reader = stdin.lock();
+            return cat_stream_with_options(
+                &mut reader,
+                "standard input",
+                config,
+                state,
+                out,
+                tool_name,
+            );
+        }
         match read_stdin() {
             Ok(data) => {
-                cat_with_options(&data, config, line_num, pending_cr, out)?;
+                cat_with_options(&data, config, state, out)?;
                 Ok(true)
             }
             Err(e) => {
@@ -554,28 +786,37 @@ pub fn cat_file(
     } else {
         let path = Path::new(filename);
 
-        // Check if it's a directory
-        match std::fs::metadata(path) {
-            Ok(meta) if meta.is_dir() => {
-                eprintln!("{}: {}: Is a directory", tool_name, filename);
+        // Open the operand and fstat it, so a directory is reported without
+        // ever reaching the read or copy paths.
+        let file_meta = match std::fs::File::open(path).and_then(|f| f.metadata()) {
+            Ok(meta) => meta,
+            Err(e) => {
+                eprintln!(
+                    "{}: {}: {}",
+                    tool_name,
+                    filename,
+                    crate::common::io_error_msg(&e)
+                );
                 return Ok(false);
             }
-            _ => {}
+        };
+        if file_meta.is_dir() {
+            eprintln!("{}: {}: Is a directory", tool_name, filename);
+            return Ok(false);
         }
+        let is_regular = file_meta.is_file();
 
         // GNU cat: detect when input file is the same as stdout (e.g. cat file >> file)
         #[cfg(unix)]
         {
             use std::os::unix::fs::MetadataExt;
-            if let Ok(file_meta) = std::fs::metadata(path) {
-                let mut stdout_stat: libc::stat = unsafe { std::mem::zeroed() };
-                if unsafe { libc::fstat(1, &mut stdout_stat) } == 0
-                    && file_meta.dev() == stdout_stat.st_dev as u64
-                    && file_meta.ino() == stdout_stat.st_ino as u64
-                {
-                    eprintln!("{}: {}: input file is output file", tool_name, filename);
-                    return Ok(false);
-                }
+            let mut stdout_stat: libc::stat = unsafe { std::mem::zeroed() };
+            if unsafe { libc::fstat(1, &mut stdout_stat) } == 0
+                && file_meta.dev() == stdout_stat.st_dev as u64
+                && file_meta.ino() == stdout_stat.st_ino as u64
+            {
+                eprintln!("{}: {}: input file is output file", tool_name, filename);
+                return Ok(false);
             }
         }
 
@@ -598,9 +839,27 @@ pub fn cat_file(
             }
         }
 
+        // -u on a FIFO or device: stream rather than wait for EOF
+        if config.unbuffered && !is_regular {
+            return match std::fs::File::open(path) {
+                Ok(mut file) => {
+                    cat_stream_with_options(&mut file, filename, config, state, out, tool_name)
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{}: {}: {}",
+                        tool_name,
+                        filename,
+                        crate::common::io_error_msg(&e)
+                    );
+                    Ok(false)
+                }
+            };
+        }
+
         match read_file(path) {
             Ok(data) => {
-                cat_with_options(&data, config, line_num, pending_cr, out)?;
+                cat_with_options(&data, config, state, out)?;
                 Ok(true)
             }
             Err(e) => {
@@ -1,11 +1,14 @@
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
+use crate::chown::SymlinkFollow;
+
 /// Configuration for chmod operations.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ChmodConfig {
     /// Report only when a change is made.
     pub changes: bool,
@@ -17,6 +20,22 @@ pub struct ChmodConfig {
     pub preserve_root: bool,
     /// Operate recursively.
     pub recursive: bool,
+    /// Which symlinks to directories -R traverses (-H/-L/-P). Symlinks named
+    /// on the command line are followed unless -P is given.
+    pub symlink_follow: SymlinkFollow,
+}
+
+impl Default for ChmodConfig {
+    fn default() -> Self {
+        Self {
+            changes: false,
+            quiet: false,
+            verbose: false,
+            preserve_root: false,
+            recursive: false,
+            symlink_follow: SymlinkFollow::CommandLine,
+        }
+    }
 }
 
 // Permission bit constants
@@ -405,12 +424,35 @@ pub fn chmod_file(path: &Path, mode: u32, config: &ChmodConfig) -> Result<bool,
         return Ok(false);
     }
 
+    chmod_with_metadata(path, &metadata, mode, config)
+}
+
+/// Apply `mode` to `path`, whose current metadata is `metadata` (that of the
+/// symlink target when following links).
+fn chmod_with_metadata(
+    path: &Path,
+    metadata: &fs::Metadata,
+    mode: u32,
+    config: &ChmodConfig,
+) -> Result<bool, io::Error> {
     let old_mode = metadata.mode() & 0o7777;
     let changed = old_mode != mode;
 
     if changed {
         let perms = fs::Permissions::from_mode(mode);
-        fs::set_permissions(path, perms)?;
+        if let Err(e) = fs::set_permissions(path, perms) {
+            if config.verbose {
+                println!(
+                    "failed to change mode of '{}' from {} ({}) to {} ({})",
+                    path.display(),
+                    format_mode(old_mode),
+                    format_symbolic(old_mode),
+                    format_mode(mode),
+                    format_symbolic(mode)
+                );
+            }
+            return Err(e);
+        }
     }
 
     let path_display = path.display();
@@ -446,10 +488,42 @@ pub fn chmod_file(path: &Path, mode: u32, config: &ChmodConfig) -> Result<bool,
     Ok(changed)
 }
 
+/// Report on stdout, under -v, a file whose status could not be read.
+///
+/// Like the change reports, this is independent of -f, which only
+/// silences the diagnostics on stderr.
+pub fn report_not_accessed(path: &Path, config: &ChmodConfig) {
+    if config.verbose {
+        println!("'{}' could not be accessed", path.display());
+    }
+}
+
+/// Report a symlink whose target does not exist, which chmod cannot change.
+pub fn report_dangling_symlink(path: &Path, config: &ChmodConfig) {
+    if !config.quiet {
+        eprintln!(
+            "chmod: cannot operate on dangling symlink '{}'",
+            path.display()
+        );
+    }
+    report_not_accessed(path, config);
+}
+
+/// Report on stdout, under -v, a symlink that -R left alone.
+fn report_symlink_skipped(path: &Path, config: &ChmodConfig) {
+    if config.verbose {
+        println!(
+            "neither symbolic link '{}' nor referent has been changed",
+            path.display()
+        );
+    }
+}
+
 /// Recursively apply a mode string to a directory tree.
 ///
 /// The mode is re-parsed for each file using its current mode, which matters
 /// for symbolic modes (e.g. `a+X` behaves differently for files vs directories).
+/// Failures are reported as they happen and make the result `Ok(false)`.
 pub fn chmod_recursive(
     path: &Path,
     mode_str: &str,
@@ -462,36 +536,65 @@ pub fn chmod_recursive(
     }
 
     let mut had_error = false;
+    let follow_arg = config.symlink_follow != SymlinkFollow::Never;
 
     // Process the path itself first
-    match process_entry(path, mode_str, config) {
-        Ok(()) => {}
-        Err(e) => {
-            if !config.quiet {
-                eprintln!("chmod: cannot access '{}': {}", path.display(), e);
-            }
-            had_error = true;
+    let metadata = if follow_arg {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    };
+    let accessible = metadata.is_ok();
+    let dangling = !accessible && fs::symlink_metadata(path).is_ok_and(|m| m.is_symlink());
+    if dangling {
+        report_dangling_symlink(path, config);
+        had_error = true;
+    } else if let Err(e) = metadata.and_then(|_| process_entry(path, mode_str, config, follow_arg))
+    {
+        if !config.quiet {
+            eprintln!(
+                "chmod: cannot access '{}': {}",
+                path.display(),
+                crate::common::io_error_msg(&e)
+            );
+        }
+        if !accessible {
+            report_not_accessed(path, config);
         }
+        had_error = true;
     }
 
     // Walk the directory tree
-    if path.is_dir() {
-        walk_dir(path, mode_str, config, &mut had_error);
-    }
-
-    if had_error {
-        Err(io::Error::other("some operations failed"))
+    let is_dir = if follow_arg {
+        path.is_dir()
     } else {
-        Ok(true)
+        fs::symlink_metadata(path).is_ok_and(|m| m.is_dir())
+    };
+    if is_dir {
+        walk_dir(path, mode_str, config, &mut had_error, &mut HashSet::new());
     }
+
+    Ok(!had_error)
 }
 
 /// Process a single entry: read its mode, parse the mode string, and apply.
-fn process_entry(path: &Path, mode_str: &str, config: &ChmodConfig) -> Result<(), io::Error> {
-    let metadata = fs::symlink_metadata(path)?;
+/// With `follow`, a symlink's target is changed instead of skipping it.
+fn process_entry(
+    path: &Path,
+    mode_str: &str,
+    config: &ChmodConfig,
+    follow: bool,
+) -> Result<(), io::Error> {
+    let metadata = if follow {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    };
+    let metadata = metadata?;
 
     // Skip symlinks
     if metadata.file_type().is_symlink() {
+        report_symlink_skipped(path, config);
         return Ok(());
     }
 
@@ -509,15 +612,26 @@ fn process_entry(path: &Path, mode_str: &str, config: &ChmodConfig) -> Result<()
         new_mode |= existing_special;
     }
 
-    chmod_file(path, new_mode, config)?;
+    chmod_with_metadata(path, &metadata, new_mode, config)?;
     Ok(())
 }
 
 /// Walk a directory recursively, applying the mode to each entry.
 /// Uses rayon for parallel processing when verbose/changes output is not needed.
-fn walk_dir(dir: &Path, mode_str: &str, config: &ChmodConfig, had_error: &mut bool) {
+///
+/// With -L, `ancestors` holds the (device, inode) of each directory on the
+/// current path so a symlink leading back up the tree is not followed again.
+fn walk_dir(
+    dir: &Path,
+    mode_str: &str,
+    config: &ChmodConfig,
+    had_error: &mut bool,
+    ancestors: &mut HashSet<(u64, u64)>,
+) {
+    let follow_all = config.symlink_follow == SymlinkFollow::Always;
+
     // For non-verbose mode, use parallel traversal with rayon
-    if !config.verbose && !config.changes {
+    if !config.verbose && !config.changes && !follow_all {
         let error_flag = std::sync::atomic::AtomicBool::new(false);
         walk_dir_parallel(dir, mode_str, config, &error_flag);
         if error_flag.load(std::sync::atomic::Ordering::Relaxed) {
@@ -527,6 +641,35 @@ fn walk_dir(dir: &Path, mode_str: &str, config: &ChmodConfig, had_error: &mut bo
     }
 
     // Sequential path for verbose/changes mode (output ordering matters)
+    // and for -L, which tracks the directories being visited.
+    let key = if follow_all {
+        fs::metadata(dir).ok().map(|m| (m.dev(), m.ino()))
+    } else {
+        None
+    };
+    if let Some(key) = key
+        && !ancestors.insert(key)
+    {
+        // Directory cycle through a symlink: already being processed.
+        return;
+    }
+
+    walk_entries(dir, mode_str, config, had_error, ancestors);
+
+    if let Some(key) = key {
+        ancestors.remove(&key);
+    }
+}
+
+/// Sequential body of `walk_dir`.
+fn walk_entries(
+    dir: &Path,
+    mode_str: &str,
+    config: &ChmodConfig,
+    had_error: &mut bool,
+    ancestors: &mut HashSet<(u64, u64)>,
+) {
+    let follow_all = config.symlink_follow == SymlinkFollow::Always;
     let entries = match fs::read_dir(dir) {
         Ok(entries) => entries,
         Err(e) => {
@@ -567,11 +710,25 @@ fn walk_dir(dir: &Path, mode_str: &str, config: &ChmodConfig, had_error: &mut bo
             }
         };
 
-        if file_type.is_symlink() {
+        let follow = file_type.is_symlink() && follow_all;
+        if file_type.is_symlink() && !follow {
+            report_symlink_skipped(&entry_path, config);
+            continue;
+        }
+        if follow && let Err(e) = fs::metadata(&entry_path) {
+            report_not_accessed(&entry_path, config);
+            if !config.quiet {
+                eprintln!(
+                    "chmod: cannot dereference '{}': {}",
+                    entry_path.display(),
+                    crate::common::io_error_msg(&e)
+                );
+            }
+            *had_error = true;
             continue;
         }
 
-        match process_entry(&entry_path, mode_str, config) {
+        match process_entry(&entry_path, mode_str, config, follow) {
             Ok(()) => {}
             Err(e) => {
                 if !config.quiet {
@@ -585,8 +742,13 @@ fn walk_dir(dir: &Path, mode_str: &str, config: &ChmodConfig, had_error: &mut bo
             }
         }
 
-        if file_type.is_dir() {
-            walk_dir(&entry_path, mode_str, config, had_error);
+        let is_dir = if follow {
+            entry_path.is_dir()
+        } else {
+            file_type.is_dir()
+        };
+        if is_dir {
+            walk_dir(&entry_path, mode_str, config, had_error, ancestors);
         }
     }
 }
@@ -626,7 +788,7 @@ fn walk_dir_parallel(
             return;
         }
 
-        if process_entry(&entry_path, mode_str, config).is_err() {
+        if process_entry(&entry_path, mode_str, config, false).is_err() {
             had_error.store(true, std::sync::atomic::Ordering::Relaxed);
         }
 
@@ -6,7 +6,7 @@ use std::io::{self, Write};
 pub enum OrderCheck {
     /// Default: check, warn once per file, continue, exit 1
     Default,
-    /// --check-order: check, error, stop immediately
+    /// --check-order: check from the first line, error, stop immediately
     Strict,
     /// --nocheck-order: no checking
     None,
@@ -122,24 +122,35 @@ pub fn comm(
     let mut had_order_error = false;
     let mut warned1 = false;
     let mut warned2 = false;
+    // Like GNU, the default mode only starts checking once a line unique to
+    // one file has been seen; until then the inputs could be identical.
+    let mut seen_unpairable = false;
     let ci = config.case_insensitive;
 
     let mut buf = Vec::with_capacity((data1.len() + data2.len()).min(4 * 1024 * 1024));
     let flush_threshold = 4 * 1024 * 1024; // Flush output buffer at 4MB to limit memory
 
-    // Macro to check sort order of a file and handle warnings/errors.
-    macro_rules! check_order {
+    // Step a file to its next line and check that line against the previous
+    // one. Output so far is flushed first so the diagnostic appears after it.
+    macro_rules! advance {
         ($warned:ident, $lines:ident, $idx:ident, $file_num:expr) => {
-            if config.order_check != OrderCheck::None
+            $idx += 1;
+            let checking = match config.order_check {
+                OrderCheck::Strict => true,
+                OrderCheck::Default => seen_unpairable,
+                OrderCheck::None => false,
+            };
+            if checking
                 && !$warned
-                && $idx > 0
-                && compare_lines($lines[$idx], $lines[$idx - 1], ci) == Ordering::Less
+                && $idx < $lines.len()
+                && compare_lines($lines[$idx - 1], $lines[$idx], ci) == Ordering::Greater
             {
-                had_order_error = true;
-                $warned = true;
+                out.write_all(&buf)?;
+                buf.clear();
+                out.flush()?;
                 eprintln!("{}: file {} is not in sorted order", tool_name, $file_num);
+                had_order_error = true;
                 if config.order_check == OrderCheck::Strict {
-                    out.write_all(&buf)?;
                     return Ok(CommResult {
                         count1,
                         count2,
@@ -147,6 +158,7 @@ pub fn comm(
                         had_order_error,
                     });
                 }
+                $warned = true;
             }
         };
     }
@@ -154,36 +166,33 @@ pub fn comm(
     while i1 < lines1.len() && i2 < lines2.len() {
         match compare_lines(lines1[i1], lines2[i2], ci) {
             Ordering::Less => {
-                // File1 line is unique — check file1 sort order before consuming
-                check_order!(warned1, lines1, i1, 1);
+                seen_unpairable = true;
                 if !config.suppress_col1 {
                     buf.extend_from_slice(lines1[i1]);
                     buf.push(delim);
                 }
                 count1 += 1;
-                i1 += 1;
+                advance!(warned1, lines1, i1, 1);
             }
             Ordering::Greater => {
-                // File2 line is unique — check file2 sort order before consuming
-                check_order!(warned2, lines2, i2, 2);
+                seen_unpairable = true;
                 if !config.suppress_col2 {
                     buf.extend_from_slice(&prefix2);
                     buf.extend_from_slice(lines2[i2]);
                     buf.push(delim);
                 }
                 count2 += 1;
-                i2 += 1;
+                advance!(warned2, lines2, i2, 2);
             }
             Ordering::Equal => {
-                // Lines match — no sort check needed (GNU comm behavior)
                 if !config.suppress_col3 {
                     buf.extend_from_slice(&prefix3);
                     buf.extend_from_slice(lines1[i1]);
                     buf.push(delim);
                 }
                 count3 += 1;
-                i1 += 1;
-                i2 += 1;
+                advance!(warned1, lines1, i1, 1);
+                advance!(warned2, lines2, i2, 2);
             }
         }
 
@@ -196,59 +205,25 @@ pub fn comm(
 
     // Drain remaining from file 1
     while i1 < lines1.len() {
-        if config.order_check != OrderCheck::None
-            && !warned1
-            && i1 > 0
-            && compare_lines(lines1[i1], lines1[i1 - 1], ci) == Ordering::Less
-        {
-            had_order_error = true;
-            warned1 = true;
-            eprintln!("{}: file 1 is not in sorted order", tool_name);
-            if config.order_check == OrderCheck::Strict {
-                out.write_all(&buf)?;
-                return Ok(CommResult {
-                    count1,
-                    count2,
-                    count3,
-                    had_order_error,
-                });
-            }
-        }
+        seen_unpairable = true;
         if !config.suppress_col1 {
             buf.extend_from_slice(lines1[i1]);
             buf.push(delim);
         }
         count1 += 1;
-        i1 += 1;
+        advance!(warned1, lines1, i1, 1);
     }
 
     // Drain remaining from file 2
     while i2 < lines2.len() {
-        if config.order_check != OrderCheck::None
-            && !warned2
-            && i2 > 0
-            && compare_lines(lines2[i2], lines2[i2 - 1], ci) == Ordering::Less
-        {
-            had_order_error = true;
-            warned2 = true;
-            eprintln!("{}: file 2 is not in sorted order", tool_name);
-            if config.order_check == OrderCheck::Strict {
-                out.write_all(&buf)?;
-                return Ok(CommResult {
-                    count1,
-                    count2,
-                    count3,
-                    had_order_error,
-                });
-            }
-        }
+        seen_unpairable = true;
         if !config.suppress_col2 {
             buf.extend_from_slice(&prefix2);
             buf.extend_from_slice(lines2[i2]);
             buf.push(delim);
         }
         count2 += 1;
-        i2 += 1;
+        advance!(warned2, lines2, i2, 2);
     }
 
     // Total summary line — use itoa for fast integer formatting
@@ -264,12 +239,14 @@ pub fn comm(
         buf.push(delim);
     }
 
+    out.write_all(&buf)?;
+
     // In Default mode, print a final summary message (matches GNU comm behavior)
     if had_order_error && config.order_check == OrderCheck::Default {
+        out.flush()?;
         eprintln!("{}: input is not in sorted order", tool_name);
     }
 
-    out.write_all(&buf)?;
     Ok(CommResult {
         count1,
         count2,
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+/// Backup strategy, following GNU `--backup` semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupMode {
+    /// Numbered backups (~1~, ~2~, ...).
+    Numbered,
+    /// Numbered if numbered backups already exist, otherwise simple.
+    Existing,
+    /// Simple backup with suffix.
+    Simple,
+    /// Never make backups.
+    None,
+}
+
+/// Backup CONTROL names, in the order GNU lists them.
+const BACKUP_CONTROLS: &[(&str, BackupMode)] = &[
+    ("none", BackupMode::None),
+    ("off", BackupMode::None),
+    ("simple", BackupMode::Simple),
+    ("never", BackupMode::Simple),
+    ("existing", BackupMode::Existing),
+    ("nil", BackupMode::Existing),
+    ("numbered", BackupMode::Numbered),
+    ("t", BackupMode::Numbered),
+];
+
+/// Parse a backup CONTROL value from `--backup=CONTROL` or `VERSION_CONTROL`.
+///
+/// Like GNU argmatch, unambiguous abbreviations are accepted. `context` names
+/// the setting in diagnostics: "backup type" or "$VERSION_CONTROL".
+pub fn parse_backup_mode(s: &str, context: &str) -> Result<BackupMode, String> {
+    if let Some(&(_, mode)) = BACKUP_CONTROLS.iter().find(|(name, _)| *name == s) {
+        return Ok(mode);
+    }
+    let mut found = None;
+    let mut ambiguous = false;
+    for &(name, mode) in BACKUP_CONTROLS {
+        if name.starts_with(s) {
+            match found {
+                None => found = Some(mode),
+                Some(prev) if prev != mode => ambiguous = true,
+                Some(_) => {}
+            }
+        }
+    }
+    match found {
+        Some(mode) if !ambiguous => Ok(mode),
+        _ => Err(format!(
+            "{} argument '{}' for '{}'\n\
+             Valid arguments are:\n  \
+             - 'none', 'off'\n  \
+             - 'simple', 'never'\n  \
+             - 'existing', 'nil'\n  \
+             - 'numbered', 't'",
+            if ambiguous { "ambiguous" } else { "invalid" },
+            s,
+            context
+        )),
+    }
+}
+
+/// Resolve the simple backup suffix: `-S SUFFIX` if given, otherwise
+/// `SIMPLE_BACKUP_SUFFIX`, otherwise `~`. As in GNU, a suffix that is empty or
+/// contains a slash is ignored.
+pub fn backup_suffix(suffix: Option<&str>) -> String {
+    let env = std::env::var("SIMPLE_BACKUP_SUFFIX").ok();
+    match suffix.or(env.as_deref()) {
+        Some(s) if !s.is_empty() && !s.contains('/') => s.to_string(),
+        _ => "~".to_string(),
+    }
+}
+
+/// Name to rename `dst` to when backing it up with `mode`. `suffix` is used
+/// for simple backups.
+pub fn make_backup_name(dst: &Path, mode: BackupMode, suffix: &str) -> PathBuf {
+    let simple = || {
+        let mut name = dst.as_os_str().to_os_string();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
+    match mode {
+        BackupMode::Simple | BackupMode::None => simple(),
+        BackupMode::Numbered => numbered_backup_name(dst, highest_numbered_backup(dst) + 1),
+        BackupMode::Existing => match highest_numbered_backup(dst) {
+            0 => simple(),
+            n => numbered_backup_name(dst, n + 1),
+        },
+    }
+}
+
+/// Highest N among existing `DST.~N~` backups, or 0 if there are none.
+/// Like GNU, the next numbered backup is one past the highest, not the first gap.
+fn highest_numbered_backup(dst: &Path) -> u64 {
+    let Some(name) = dst.file_name() else {
+        return 0;
+    };
+    let name = name.as_encoded_bytes();
+    let dir = match dst.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut highest = 0;
+    for entry in entries.flatten() {
+        let entry_name = entry.file_name();
+        let digits = entry_name
+            .as_encoded_bytes()
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix(b".~"))
+            .and_then(|rest| rest.strip_suffix(b"~"));
+        if let Some(digits) = digits
+            && digits.first().is_some_and(|&d| (b'1'..=b'9').contains(&d))
+            && digits.iter().all(u8::is_ascii_digit)
+            && let Some(n) = std::str::from_utf8(digits)
+                .ok()
+                .and_then(|d| d.parse().ok())
+        {
+            highest = highest.max(n);
+        }
+    }
+    highest
+}
+
+fn numbered_backup_name(dst: &Path, n: u64) -> PathBuf {
+    let mut name = dst.as_os_str().to_os_string();
+    name.push(format!(".~{}~", n));
+    PathBuf::from(name)
+}
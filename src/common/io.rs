@@ -40,7 +40,7 @@ static NOATIME_SUPPORTED: AtomicBool = AtomicBool::new(true);
 /// Open a file with O_NOATIME on Linux to avoid atime inode writes.
 /// Caches whether O_NOATIME works to avoid double-open on every file.
 #[cfg(target_os = "linux")]
-fn open_noatime(path: &Path) -> io::Result<File> {
+pub fn open_noatime(path: &Path) -> io::Result<File> {
     use std::os::unix::fs::OpenOptionsExt;
     if NOATIME_SUPPORTED.load(Ordering::Relaxed) {
         match fs::OpenOptions::new()
@@ -60,14 +60,18 @@ fn open_noatime(path: &Path) -> io::Result<File> {
 }
 
 #[cfg(not(target_os = "linux"))]
-fn open_noatime(path: &Path) -> io::Result<File> {
+pub fn open_noatime(path: &Path) -> io::Result<File> {
     File::open(path)
 }
 
 /// Read a file with zero-copy mmap for large files or read() for small files.
 /// Opens once with O_NOATIME, uses fstat for metadata to save a syscall.
 pub fn read_file(path: &Path) -> io::Result<FileData> {
-    let file = open_noatime(path)?;
+    read_opened_file(open_noatime(path)?)
+}
+
+/// Like [`read_file`], for a file the caller has already opened.
+pub fn read_opened_file(file: File) -> io::Result<FileData> {
     let metadata = file.metadata()?;
     let len = metadata.len();
 
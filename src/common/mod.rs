@@ -1,3 +1,4 @@
+pub mod backup;
 pub mod io;
 
 /// Get the GNU-compatible tool name by stripping the 'f' prefix.
@@ -1,6 +1,9 @@
 use std::io;
 use std::path::Path;
 
+use crate::common::backup::make_backup_name;
+pub use crate::common::backup::{BackupMode, backup_suffix, parse_backup_mode};
+
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 #[cfg(unix)]
@@ -27,19 +30,6 @@ pub enum DerefMode {
     Always,
 }
 
-/// Backup strategy, following GNU `--backup` semantics.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum BackupMode {
-    /// Numbered backups (~1~, ~2~, ...).
-    Numbered,
-    /// Numbered if numbered backups already exist, otherwise simple.
-    Existing,
-    /// Simple backup with suffix.
-    Simple,
-    /// Never make backups.
-    None,
-}
-
 /// Reflink (copy-on-write clone) strategy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReflinkMode {
@@ -145,17 +135,6 @@ pub fn apply_no_preserve(list: &str, config: &mut CpConfig) {
     }
 }
 
-/// Parse a `--backup=CONTROL` value.
-pub fn parse_backup_mode(s: &str) -> Result<BackupMode, String> {
-    match s {
-        "none" | "off" => Ok(BackupMode::None),
-        "numbered" | "t" => Ok(BackupMode::Numbered),
-        "existing" | "nil" => Ok(BackupMode::Existing),
-        "simple" | "never" => Ok(BackupMode::Simple),
-        _ => Err(format!("invalid backup type '{}'", s)),
-    }
-}
-
 /// Parse a `--reflink[=WHEN]` value.
 pub fn parse_reflink_mode(s: &str) -> Result<ReflinkMode, String> {
     match s {
@@ -189,58 +168,19 @@ pub fn apply_preserve(list: &str, config: &mut CpConfig) {
 // ---- backup helpers ----
 
 /// Create a backup of `dst` if it exists, according to the configured backup mode.
-/// Returns `Ok(())` when no backup is needed or the backup was made successfully.
-fn make_backup(dst: &Path, config: &CpConfig) -> io::Result<()> {
+/// Returns the backup path, or `None` when no backup was needed.
+fn make_backup(dst: &Path, config: &CpConfig) -> io::Result<Option<std::path::PathBuf>> {
     let mode = match config.backup {
+        Some(BackupMode::None) | None => return Ok(None),
         Some(m) => m,
-        None => return Ok(()),
     };
-    if mode == BackupMode::None {
-        return Ok(());
-    }
-    if !dst.exists() {
-        return Ok(());
+    if dst.symlink_metadata().is_err() {
+        return Ok(None);
     }
 
-    let backup_path = match mode {
-        BackupMode::Simple | BackupMode::None => {
-            let mut p = dst.as_os_str().to_os_string();
-            p.push(&config.suffix);
-            std::path::PathBuf::from(p)
-        }
-        BackupMode::Numbered => numbered_backup_path(dst),
-        BackupMode::Existing => {
-            // Use numbered if any numbered backup already exists.
-            let numbered = numbered_backup_candidate(dst, 1);
-            if numbered.exists() {
-                numbered_backup_path(dst)
-            } else {
-                let mut p = dst.as_os_str().to_os_string();
-                p.push(&config.suffix);
-                std::path::PathBuf::from(p)
-            }
-        }
-    };
-
+    let backup_path = make_backup_name(dst, mode, &config.suffix);
     std::fs::rename(dst, &backup_path)?;
-    Ok(())
-}
-
-fn numbered_backup_path(dst: &Path) -> std::path::PathBuf {
-    let mut n: u64 = 1;
-    loop {
-        let candidate = numbered_backup_candidate(dst, n);
-        if !candidate.exists() {
-            return candidate;
-        }
-        n += 1;
-    }
-}
-
-fn numbered_backup_candidate(dst: &Path, n: u64) -> std::path::PathBuf {
-    let mut p = dst.as_os_str().to_os_string();
-    p.push(format!(".~{}~", n));
-    std::path::PathBuf::from(p)
+    Ok(Some(backup_path))
 }
 
 // ---- attribute preservation ----
@@ -695,11 +635,13 @@ fn copy_recursive(
                 files
                     .par_iter()
                     .try_for_each(|(child_src, child_dst, meta)| {
+                        make_backup(child_dst, config)?;
                         copy_file_with_meta(child_src, child_dst, meta, config)
                     });
             result?;
         } else {
             for (child_src, child_dst, meta) in &files {
+                make_backup(child_dst, config)?;
                 copy_file_with_meta(child_src, child_dst, meta, config)?;
             }
         }
@@ -800,34 +742,47 @@ pub fn run_cp(
             }
         }
 
-        if let Err(e) = do_copy(src, &dst, config) {
-            let inner = strip_os_error(&e);
-            let msg = if inner.contains("are the same file") {
-                // GNU cp: "cp: 'X' and 'Y' are the same file" (no "cannot copy" prefix)
-                format!("cp: {}", inner)
-            } else if inner.contains("omitting directory") {
-                format!("cp: {}", inner)
-            } else {
-                format!(
-                    "cp: cannot copy '{}' to '{}': {}",
-                    src.display(),
-                    dst.display(),
-                    inner
-                )
-            };
-            errors.push(msg);
-            had_error = true;
-        } else if config.verbose {
-            // GNU cp -v outputs to stdout
-            println!("'{}' -> '{}'", src.display(), dst.display());
+        match do_copy(src, &dst, config) {
+            Err(e) => {
+                let inner = strip_os_error(&e);
+                let msg = if inner.contains("are the same file") {
+                    // GNU cp: "cp: 'X' and 'Y' are the same file" (no "cannot copy" prefix)
+                    format!("cp: {}", inner)
+                } else if inner.contains("omitting directory") {
+                    format!("cp: {}", inner)
+                } else {
+                    format!(
+                        "cp: cannot copy '{}' to '{}': {}",
+                        src.display(),
+                        dst.display(),
+                        inner
+                    )
+                };
+                errors.push(msg);
+                had_error = true;
+            }
+            Ok(backup) if config.verbose => {
+                // GNU cp -v outputs to stdout
+                match backup {
+                    Some(b) => println!(
+                        "'{}' -> '{}' (backup: '{}')",
+                        src.display(),
+                        dst.display(),
+                        b.display()
+                    ),
+                    None => println!("'{}' -> '{}'", src.display(), dst.display()),
+                }
+            }
+            Ok(_) => {}
         }
     }
 
     (errors, had_error)
 }
 
-/// Core copy dispatcher for a single source -> destination pair.
-fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
+/// Core copy dispatcher for a single source -> destination pair. Returns the
+/// name the destination was backed up to, if any.
+fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<Option<std::path::PathBuf>> {
     let src_meta = if config.dereference == DerefMode::Always {
         std::fs::metadata(src)?
     } else {
@@ -857,7 +812,7 @@ fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
 
     // No-clobber: skip if destination exists.
     if config.no_clobber && dst.exists() {
-        return Ok(());
+        return Ok(None);
     }
 
     // Update: skip if destination is same age or newer.
@@ -865,7 +820,7 @@ fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
         if let (Ok(src_m), Ok(dst_m)) = (src.metadata(), dst.metadata()) {
             if let (Ok(src_t), Ok(dst_t)) = (src_m.modified(), dst_m.modified()) {
                 if dst_t >= src_t {
-                    return Ok(());
+                    return Ok(None);
                 }
             }
         }
@@ -878,63 +833,22 @@ fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
         io::stdin().read_line(&mut response)?;
         let r = response.trim().to_lowercase();
         if !(r == "y" || r == "yes") {
-            return Ok(());
+            return Ok(None);
         }
     }
 
     // Same-file detection: must come before force removal to prevent data loss.
-    // When backup is enabled, self-copy is allowed: GNU cp renames the destination
-    // to the backup path first, then copies from the (still-open) source.
+    // With --backup and --force, self-copy is allowed: GNU cp renames the
+    // destination to the backup path first, then copies from the backup.
     #[cfg(unix)]
     if !src_meta.is_dir() && dst.exists() {
         if let Ok(dst_meta) = std::fs::metadata(dst) {
             if src_meta.dev() == dst_meta.dev() && src_meta.ino() == dst_meta.ino() {
-                let has_backup = matches!(
-                    config.backup,
-                    Some(BackupMode::Simple | BackupMode::Numbered | BackupMode::Existing)
-                );
-                if has_backup {
-                    // Make the backup (rename dst to backup path), then copy from backup
-                    make_backup(dst, config)?;
-                    // Build backup path to use as source
-                    let backup_src = match config.backup.unwrap() {
-                        BackupMode::Simple | BackupMode::None => {
-                            let mut p = dst.as_os_str().to_os_string();
-                            p.push(&config.suffix);
-                            std::path::PathBuf::from(p)
-                        }
-                        BackupMode::Numbered => {
-                            // Find highest numbered backup
-                            let mut n: u64 = 1;
-                            loop {
-                                let candidate = numbered_backup_candidate(dst, n);
-                                let next = numbered_backup_candidate(dst, n + 1);
-                                if !next.exists() {
-                                    break candidate;
-                                }
-                                n += 1;
-                            }
-                        }
-                        BackupMode::Existing => {
-                            let numbered = numbered_backup_candidate(dst, 1);
-                            if numbered.exists() {
-                                let mut n: u64 = 1;
-                                loop {
-                                    let candidate = numbered_backup_candidate(dst, n);
-                                    let next = numbered_backup_candidate(dst, n + 1);
-                                    if !next.exists() {
-                                        break candidate;
-                                    }
-                                    n += 1;
-                                }
-                            } else {
-                                let mut p = dst.as_os_str().to_os_string();
-                                p.push(&config.suffix);
-                                std::path::PathBuf::from(p)
-                            }
-                        }
-                    };
-                    return copy_file(&backup_src, dst, config);
+                if config.force
+                    && let Some(backup_src) = make_backup(dst, config)?
+                {
+                    copy_file(&backup_src, dst, config)?;
+                    return Ok(Some(backup_src));
                 }
                 return Err(io::Error::new(
                     io::ErrorKind::Other,
@@ -948,6 +862,14 @@ fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
         }
     }
 
+    // Make backup if requested, before --force can remove the destination.
+    // GNU cp does not back up directories.
+    let backup = if src_meta.is_dir() {
+        None
+    } else {
+        make_backup(dst, config)?
+    };
+
     // Force: remove existing destination if it cannot be opened for writing,
     // or when using --link/--symbolic-link (must remove existing to create new link).
     if config.force && dst.exists() {
@@ -965,20 +887,16 @@ fn do_copy(src: &Path, dst: &Path, config: &CpConfig) -> io::Result<()> {
         }
     }
 
-    // Make backup if requested. GNU cp does not back up directories.
-    if !src_meta.is_dir() {
-        make_backup(dst, config)?;
-    }
-
     if src_meta.is_dir() {
         #[cfg(unix)]
         let root_dev = Some(src_meta.dev());
         #[cfg(not(unix))]
         let root_dev: Option<u64> = None;
-        copy_recursive(src, dst, config, root_dev)
+        copy_recursive(src, dst, config, root_dev)?;
     } else {
-        copy_file(src, dst, config)
+        copy_file(src, dst, config)?;
     }
+    Ok(backup)
 }
 
 /// Strip the " (os error N)" suffix from an io::Error for GNU-compatible messages.
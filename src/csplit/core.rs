@@ -1,15 +1,26 @@
-use regex::Regex;
-use std::fs;
-use std::io;
+use regex::bytes::Regex;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use crate::common::io_error_msg;
 
 /// A parsed csplit pattern.
 #[derive(Clone, Debug)]
 pub enum Pattern {
     /// Split before the first line matching the regex, with optional offset.
-    Regex { regex: String, offset: i64 },
+    /// `arg` is the original operand, quoted in diagnostics.
+    Regex {
+        regex: String,
+        offset: i64,
+        arg: String,
+    },
     /// Skip to (but don't include) a line matching the regex, with optional offset.
     /// Lines skipped are not written to any output file.
-    SkipTo { regex: String, offset: i64 },
+    SkipTo {
+        regex: String,
+        offset: i64,
+        arg: String,
+    },
     /// Split at a specific line number.
     LineNumber(usize),
     /// Repeat the previous pattern N times.
@@ -22,7 +33,9 @@ pub enum Pattern {
 #[derive(Clone, Debug)]
 pub struct CsplitConfig {
     pub prefix: String,
+    /// printf-style suffix (-b); when set, `digits` is ignored.
     pub suffix_format: String,
+    /// Width of the default zero-padded numeric suffix (-n).
     pub digits: usize,
     pub keep_files: bool,
     pub quiet: bool,
@@ -43,77 +56,113 @@ impl Default for CsplitConfig {
 }
 
 /// Parse a pattern string into a Pattern enum.
+///
+/// Error messages follow GNU csplit, including its quoting quirks.
 pub fn parse_pattern(s: &str) -> Result<Pattern, String> {
-    let s = s.trim();
-
-    // {*} - repeat forever
-    if s == "{*}" {
-        return Ok(Pattern::RepeatForever);
-    }
-
-    // {N} - repeat N times
-    if s.starts_with('{') && s.ends_with('}') {
-        let inner = &s[1..s.len() - 1];
-        let n: usize = inner
-            .parse()
-            .map_err(|_| format!("invalid repeat count: '{}'", s))?;
-        return Ok(Pattern::Repeat(n));
-    }
-
-    // /REGEX/[OFFSET] - split before matching line
-    if s.starts_with('/') {
-        let rest = &s[1..];
-        if let Some(end_pos) = rest.rfind('/') {
-            let regex_str = &rest[..end_pos];
-            let after = rest[end_pos + 1..].trim();
-            let offset = if after.is_empty() {
-                0
-            } else {
-                after
-                    .parse::<i64>()
-                    .map_err(|_| format!("invalid offset: '{}'", after))?
-            };
-            // Validate regex
-            Regex::new(regex_str).map_err(|e| format!("invalid regex '{}': {}", regex_str, e))?;
-            return Ok(Pattern::Regex {
-                regex: regex_str.to_string(),
-                offset,
-            });
+    // {N} or {*} - repeat the previous pattern
+    if let Some(inner) = s.strip_prefix('{') {
+        let Some(inner) = inner.strip_suffix('}') else {
+            return Err(format!("'{}': '}}' is required in repeat count", s));
+        };
+        if inner == "*" {
+            return Ok(Pattern::RepeatForever);
         }
-        return Err(format!("unmatched '/' in pattern: '{}'", s));
+        return parse_integer(inner)
+            .and_then(|n| usize::try_from(n).ok())
+            .map(Pattern::Repeat)
+            .ok_or_else(|| {
+                format!(
+                    "'{}'}}: integer required between '{{' and '}}'",
+                    &s[..s.len() - 1]
+                )
+            });
     }
 
+    // /REGEX/[OFFSET] - split before matching line
     // %REGEX%[OFFSET] - skip to matching line
-    if s.starts_with('%') {
+    if let Some(delim) = s.chars().next().filter(|&c| c == '/' || c == '%') {
         let rest = &s[1..];
-        if let Some(end_pos) = rest.rfind('%') {
-            let regex_str = &rest[..end_pos];
-            let after = rest[end_pos + 1..].trim();
-            let offset = if after.is_empty() {
-                0
-            } else {
-                after
-                    .parse::<i64>()
-                    .map_err(|_| format!("invalid offset: '{}'", after))?
-            };
-            // Validate regex
-            Regex::new(regex_str).map_err(|e| format!("invalid regex '{}': {}", regex_str, e))?;
-            return Ok(Pattern::SkipTo {
-                regex: regex_str.to_string(),
-                offset,
-            });
-        }
-        return Err(format!("unmatched '%' in pattern: '{}'", s));
+        let Some(end_pos) = rest.rfind(delim) else {
+            return Err(format!("{}: closing delimiter '{}' missing", s, delim));
+        };
+        let regex = &rest[..end_pos];
+        let after = &rest[end_pos + 1..];
+        let offset = if after.is_empty() {
+            0
+        } else {
+            parse_integer(after)
+                .ok_or_else(|| format!("'{}': integer expected after delimiter", s))?
+        };
+        compile_regex(regex, s)?;
+        let (regex, arg) = (regex.to_string(), s.to_string());
+        return Ok(if delim == '/' {
+            Pattern::Regex { regex, offset, arg }
+        } else {
+            Pattern::SkipTo { regex, offset, arg }
+        });
     }
 
     // LINE_NUMBER - split at line number
-    let n: usize = s.parse().map_err(|_| format!("invalid pattern: '{}'", s))?;
+    let n = parse_integer(s)
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| format!("'{}': invalid pattern", s))?;
     if n == 0 {
-        return Err("line number must be positive".to_string());
+        return Err(format!("{}: line number must be greater than zero", s));
     }
     Ok(Pattern::LineNumber(n))
 }
 
+/// Parse a decimal integer the way strtoimax does: leading blanks and an
+/// optional sign are accepted, trailing garbage is not.
+fn parse_integer(s: &str) -> Option<i64> {
+    let s = s.trim_start();
+    s.strip_prefix('+').unwrap_or(s).parse().ok()
+}
+
+/// Compile a pattern's regex, reporting errors against the whole operand.
+fn compile_regex(regex: &str, arg: &str) -> Result<Regex, String> {
+    Regex::new(regex).map_err(|e| {
+        // regex errors span several lines; the last one names the problem.
+        let msg = e.to_string();
+        let reason = msg.lines().last().unwrap_or_default();
+        format!(
+            "'{}': invalid regular expression: {}",
+            arg,
+            reason.strip_prefix("error: ").unwrap_or(reason)
+        )
+    })
+}
+
+/// Parse the -n/--digits argument, with GNU's diagnostics.
+pub fn parse_digits(s: &str) -> Result<usize, String> {
+    use std::num::IntErrorKind;
+
+    let t = s.trim_start();
+    match t.strip_prefix('+').unwrap_or(t).parse::<i64>() {
+        Ok(n) if (0..=i32::MAX as i64).contains(&n) => Ok(n as usize),
+        Ok(n) if n < 0 => Err(format!(
+            "invalid number: '{}': Numerical result out of range",
+            s
+        )),
+        Ok(_) => Err(format!(
+            "invalid number: '{}': Value too large for defined data type",
+            s
+        )),
+        Err(e)
+            if matches!(
+                e.kind(),
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+            ) =>
+        {
+            Err(format!(
+                "invalid number: '{}': Value too large for defined data type",
+                s
+            ))
+        }
+        Err(_) => Err(format!("invalid number: '{}'", s)),
+    }
+}
+
 /// Generate the output filename for a given file index.
 pub fn output_filename(config: &CsplitConfig, index: usize) -> String {
     if config.suffix_format.is_empty() {
@@ -125,460 +174,419 @@ pub fn output_filename(config: &CsplitConfig, index: usize) -> String {
     }
 }
 
-/// Simple sprintf-like formatter for suffix format strings.
-/// Supports %d, %02d, %03d, etc.
+/// Check a --suffix-format string the way GNU csplit does: exactly one
+/// integer conversion (d, i, u, o, x or X) with optional flags, width and
+/// precision, plus any number of `%%`.
+pub fn validate_suffix_format(fmt: &str) -> Result<(), String> {
+    let bytes = fmt.as_bytes();
+    let mut conversions = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        if bytes.get(i) == Some(&b'%') {
+            i += 1;
+            continue;
+        }
+        if conversions > 0 {
+            return Err("too many % conversion specifications in suffix".to_string());
+        }
+        while i < bytes.len() && b"'-#0".contains(&bytes[i]) {
+            i += 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if bytes.get(i) == Some(&b'.') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+        match fmt[i..].chars().next() {
+            Some('d' | 'i' | 'u' | 'o' | 'x' | 'X') => conversions += 1,
+            Some(c) => return Err(format!("invalid conversion specifier in suffix: {}", c)),
+            None => return Err("missing conversion specifier in suffix".to_string()),
+        }
+        i += 1;
+    }
+    if conversions == 0 {
+        return Err("missing % conversion specification in suffix".to_string());
+    }
+    Ok(())
+}
+
+/// sprintf-like formatter for suffix format strings.
+/// Supports the integer conversions d, i, u, o, x and X with the flags
+/// `-`, `0`, `#` and `'`, a field width and a precision.
 pub fn format_suffix(fmt: &str, value: usize) -> String {
     let mut result = String::new();
     let mut chars = fmt.chars().peekable();
 
     while let Some(ch) = chars.next() {
-        if ch == '%' {
-            // Parse width specifier
-            let mut width_str = String::new();
-            let mut zero_pad = false;
+        if ch != '%' {
+            result.push(ch);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            result.push('%');
+            continue;
+        }
 
-            if chars.peek() == Some(&'0') {
-                zero_pad = true;
+        let (mut left, mut zero, mut alt) = (false, false, false);
+        while let Some(&c) = chars.peek() {
+            match c {
+                '-' => left = true,
+                '0' => zero = true,
+                '#' => alt = true,
+                // Thousands grouping: a no-op in the C locale.
+                '\'' => {}
+                _ => break,
+            }
+            chars.next();
+        }
+        let mut width = 0usize;
+        while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+            width = width * 10 + d as usize;
+            chars.next();
+        }
+        let mut precision = None;
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            let mut p = 0usize;
+            while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                p = p * 10 + d as usize;
                 chars.next();
             }
+            precision = Some(p);
+        }
 
-            while let Some(&c) = chars.peek() {
-                if c.is_ascii_digit() {
-                    width_str.push(c);
-                    chars.next();
-                } else {
-                    break;
-                }
+        let conv = chars.next().unwrap_or('d');
+        let mut digits = match conv {
+            'o' => format!("{:o}", value),
+            'x' => format!("{:x}", value),
+            'X' => format!("{:X}", value),
+            _ => value.to_string(),
+        };
+        if let Some(p) = precision {
+            if p == 0 && value == 0 {
+                digits.clear();
+            } else if digits.len() < p {
+                digits = format!("{:0>p$}", digits);
             }
+        }
+        let prefix = match conv {
+            'o' if alt && !digits.starts_with('0') => "0",
+            'x' if alt && value != 0 => "0x",
+            'X' if alt && value != 0 => "0X",
+            _ => "",
+        };
 
-            // Expect 'd'
-            if chars.peek() == Some(&'d') {
-                chars.next();
-                let width: usize = width_str.parse().unwrap_or(0);
-                if zero_pad && width > 0 {
-                    result.push_str(&format!("{:0>width$}", value, width = width));
-                } else if width > 0 {
-                    result.push_str(&format!("{:>width$}", value, width = width));
-                } else {
-                    result.push_str(&format!("{}", value));
-                }
-            } else if chars.peek() == Some(&'%') {
-                chars.next();
-                result.push('%');
-            } else {
-                // Unknown format, just pass through
-                result.push('%');
-                result.push_str(&width_str);
-            }
+        let len = prefix.len() + digits.len();
+        let pad = width.saturating_sub(len);
+        if left {
+            result.push_str(prefix);
+            result.push_str(&digits);
+            result.extend(std::iter::repeat_n(' ', pad));
+        } else if zero && precision.is_none() {
+            result.push_str(prefix);
+            result.extend(std::iter::repeat_n('0', pad));
+            result.push_str(&digits);
         } else {
-            result.push(ch);
+            result.extend(std::iter::repeat_n(' ', pad));
+            result.push_str(prefix);
+            result.push_str(&digits);
         }
     }
 
     result
 }
 
-/// Write lines to a file, returning the number of bytes written.
-fn write_chunk(lines: &[String], filename: &str, config: &CsplitConfig) -> Result<u64, String> {
-    if config.elide_empty && lines.is_empty() {
-        return Ok(0);
-    }
+/// A pattern together with how many extra times it runs:
+/// `Some(n)` for `{n}`, `None` for `{*}`.
+struct Control<'a> {
+    pattern: &'a Pattern,
+    regex: Option<Regex>,
+    repeat: Option<usize>,
+}
 
-    let mut content = String::new();
-    for line in lines {
-        content.push_str(line);
-        content.push('\n');
+/// Split state, modelled on GNU csplit: input lines are consumed from the
+/// front, each step creates an output file before reading into it, and the
+/// size of every file is reported when it is closed.
+struct Splitter<'a, W: Write> {
+    config: &'a CsplitConfig,
+    /// Input lines, each including its terminator (if any).
+    lines: Vec<&'a [u8]>,
+    /// Index of the first line not yet consumed.
+    next: usize,
+    /// Number of the last line examined by a regex search (1-based).
+    current_line: usize,
+    /// Names of the output files created so far.
+    created: Vec<String>,
+    /// The open output file and the number of bytes written to it.
+    output: Option<(BufWriter<File>, u64)>,
+    /// Destination for the byte counts.
+    counts: &'a mut W,
+}
+
+impl<'a, W: Write> Splitter<'a, W> {
+    /// Number of the first line still to be consumed (1-based).
+    fn first_line(&self) -> usize {
+        self.next + 1
     }
-    let bytes = content.len() as u64;
 
-    if config.elide_empty && bytes == 0 {
-        return Ok(0);
+    fn no_more_lines(&self) -> bool {
+        self.next >= self.lines.len()
     }
 
-    fs::write(filename, &content).map_err(|e| format!("cannot write '{}': {}", filename, e))?;
+    fn remove_line(&mut self) -> Option<&'a [u8]> {
+        let line = *self.lines.get(self.next)?;
+        self.next += 1;
+        self.current_line = self.current_line.max(self.next);
+        Some(line)
+    }
 
-    Ok(bytes)
-}
+    fn create_output_file(&mut self) -> Result<(), String> {
+        let name = output_filename(self.config, self.created.len());
+        match File::create(&name) {
+            Ok(file) => {
+                self.created.push(name);
+                self.output = Some((BufWriter::new(file), 0));
+                Ok(())
+            }
+            Err(e) => Err(self.fatal(format!("{}: {}", name, io_error_msg(&e)))),
+        }
+    }
 
-/// Find the first line matching a regex starting from `start`, returning its index.
-fn find_match(lines: &[String], regex: &Regex, start: usize) -> Option<usize> {
-    for (idx, line) in lines.iter().enumerate().skip(start) {
-        if regex.is_match(line) {
-            return Some(idx);
+    fn save_line(&mut self, line: &[u8]) -> Result<(), String> {
+        let (out, bytes) = self.output.as_mut().expect("output file is open");
+        *bytes += line.len() as u64;
+        if let Err(e) = out.write_all(line) {
+            let name = self.created.last().cloned().unwrap_or_default();
+            return Err(self.fatal(format!("{}: {}", name, io_error_msg(&e))));
         }
+        Ok(())
     }
-    None
-}
 
-/// Apply a single regex or skip-to pattern. Returns Ok(true) if matched,
-/// Ok(false) if no match (only used for repeat-forever graceful stop).
-/// For non-repeat patterns, no match is always an error.
-fn apply_regex_pattern(
-    lines: &[String],
-    total_lines: usize,
-    regex: &str,
-    offset: i64,
-    is_skip: bool,
-    current_line: &mut usize,
-    skip_current: &mut bool,
-    sizes: &mut Vec<u64>,
-    created_files: &mut Vec<String>,
-    file_index: &mut usize,
-    config: &CsplitConfig,
-    graceful_no_match: bool,
-) -> Result<bool, String> {
-    let re = Regex::new(regex).map_err(|e| format!("invalid regex: {}", e))?;
-
-    // When skip_current is set, the line at current_line was the match boundary
-    // from a previous regex split — skip it to find the NEXT occurrence.
-    let search_start =
-        if *skip_current && *current_line < total_lines && re.is_match(&lines[*current_line]) {
-            *current_line + 1
-        } else {
-            *current_line
+    /// Close the current output file, removing it if empty under -z and
+    /// otherwise reporting its size.
+    fn close_output_file(&mut self) -> Result<(), String> {
+        let Some((mut out, bytes)) = self.output.take() else {
+            return Ok(());
         };
+        let name = self.created.last().cloned().unwrap_or_default();
+        if let Err(e) = out.flush() {
+            return Err(self.fatal(format!("{}: {}", name, io_error_msg(&e))));
+        }
+        if bytes == 0 && self.config.elide_empty {
+            let _ = fs::remove_file(&name);
+            self.created.pop();
+        } else if !self.config.quiet {
+            let _ = writeln!(self.counts, "{}", bytes);
+        }
+        Ok(())
+    }
 
-    let match_idx = match find_match(lines, &re, search_start) {
-        Some(idx) => idx,
-        None => {
-            if graceful_no_match {
-                return Ok(false);
+    /// Copy every remaining input line to the current output file.
+    fn dump_rest_of_file(&mut self) -> Result<(), String> {
+        while let Some(line) = self.remove_line() {
+            self.save_line(line)?;
+        }
+        Ok(())
+    }
+
+    /// Abandon the run: close the current file (still reporting its size)
+    /// and, unless -k was given, remove every file created so far.
+    /// Returns `msg` for the caller to report.
+    fn fatal(&mut self, msg: String) -> String {
+        if let Some((mut out, bytes)) = self.output.take() {
+            let _ = out.flush();
+            if !self.config.quiet {
+                let _ = writeln!(self.counts, "{}", bytes);
             }
-            return Err(format!("{}: no match", regex));
         }
-    };
+        if !self.config.keep_files {
+            for name in &self.created {
+                let _ = fs::remove_file(name);
+            }
+        }
+        msg
+    }
 
-    let target = match_idx as i64 + offset;
-    let split_at = if target < *current_line as i64 {
-        *current_line
-    } else if target as usize > total_lines {
-        total_lines
-    } else {
-        target as usize
-    };
+    fn line_error(&mut self, lines_required: usize, repetition: usize) -> String {
+        let mut msg = format!("'{}': line number out of range", lines_required);
+        if repetition > 0 {
+            msg.push_str(&format!(" on repetition {}", repetition));
+        }
+        self.fatal(msg)
+    }
 
-    if is_skip {
-        // SkipTo: discard lines from current_line to split_at
-        *current_line = split_at;
-        *skip_current = false;
-    } else {
-        // Regex: write chunk from current_line to split_at
-        let chunk_lines = &lines[*current_line..split_at];
-        let filename = output_filename(config, *file_index);
-        let bytes = write_chunk(chunk_lines, &filename, config)?;
+    /// Copy lines up to (but not including) line `lines_required * (repetition + 1)`
+    /// into a new output file.
+    fn process_line_count(
+        &mut self,
+        lines_required: usize,
+        repetition: usize,
+    ) -> Result<(), String> {
+        let last_line_to_save = lines_required.saturating_mul(repetition + 1);
+        self.create_output_file()?;
+        if self.no_more_lines() {
+            return Err(self.line_error(lines_required, repetition));
+        }
+        let mut linenum = self.first_line();
+        while linenum < last_line_to_save {
+            linenum += 1;
+            match self.remove_line() {
+                Some(line) => self.save_line(line)?,
+                None => return Err(self.line_error(lines_required, repetition)),
+            }
+        }
+        self.close_output_file()?;
+        if self.no_more_lines() {
+            return Err(self.line_error(lines_required, repetition));
+        }
+        Ok(())
+    }
 
-        if !(config.elide_empty && chunk_lines.is_empty()) {
-            created_files.push(filename);
-            sizes.push(bytes);
-            *file_index += 1;
+    /// Consume input up to a line matching `re` (adjusted by `offset`),
+    /// writing it to a new output file unless `skip` is set.
+    ///
+    /// Returns `Ok(false)` when a `{*}` repetition runs out of matches, which
+    /// ends the whole split successfully.
+    fn process_regexp(
+        &mut self,
+        re: &Regex,
+        offset: i64,
+        skip: bool,
+        arg: &str,
+        repetition: usize,
+        forever: bool,
+    ) -> Result<bool, String> {
+        if !skip {
+            self.create_output_file()?;
         }
 
-        *current_line = split_at;
-        // After a regex match with offset 0, current_line is AT the match line
-        *skip_current = offset == 0;
-    }
+        let start = self.current_line.max(self.next);
+        let Some(idx) = (start..self.lines.len()).find(|&i| {
+            let line = self.lines[i];
+            re.is_match(line.strip_suffix(b"\n").unwrap_or(line))
+        }) else {
+            if forever {
+                if !skip {
+                    self.dump_rest_of_file()?;
+                    self.close_output_file()?;
+                }
+                return Ok(false);
+            }
+            let mut msg = format!("'{}': match not found", arg);
+            if repetition > 0 {
+                msg.push_str(&format!(" on repetition {}", repetition));
+            }
+            if !skip {
+                self.dump_rest_of_file()?;
+            }
+            return Err(self.fatal(msg));
+        };
+        self.current_line = idx + 1;
 
-    Ok(true)
+        // Copy (or discard) lines before the break line.
+        let break_line = self.current_line as i64 + offset;
+        if (self.first_line() as i64) > break_line {
+            return Err(self.fatal(format!("'{}': line number out of range", arg)));
+        }
+        for _ in self.first_line() as i64..break_line {
+            match self.remove_line() {
+                Some(line) if !skip => self.save_line(line)?,
+                Some(_) => {}
+                None => return Err(self.fatal(format!("'{}': line number out of range", arg))),
+            }
+        }
+        if !skip {
+            self.close_output_file()?;
+        }
+        if offset > 0 {
+            self.current_line = break_line as usize;
+        }
+        Ok(true)
+    }
 }
 
-/// Split a file based on patterns.
+/// Split `input` according to `patterns`, writing the byte count of each
+/// output file to `counts` as the file is closed (unless quiet).
 ///
-/// Returns the sizes (in bytes) of each created output file.
+/// On error the message is returned after the files created so far have
+/// been removed, or kept when `keep_files` is set.
 pub fn csplit_file(
-    input: &str,
+    input: &[u8],
     patterns: &[Pattern],
     config: &CsplitConfig,
-) -> Result<Vec<u64>, String> {
-    let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
-    let total_lines = lines.len();
-
-    let mut sizes: Vec<u64> = Vec::new();
-    let mut created_files: Vec<String> = Vec::new();
-    let mut file_index: usize = 0;
-    let mut current_line: usize = 0; // 0-based index into lines
-    let mut skip_current = false; // true when current_line is a regex match boundary
-
-    let do_cleanup = |files: &[String], config: &CsplitConfig| {
-        if !config.keep_files {
-            for f in files {
-                let _ = fs::remove_file(f);
-            }
-        }
-    };
-
-    let mut pat_idx = 0;
-    while pat_idx < patterns.len() {
-        match &patterns[pat_idx] {
-            Pattern::LineNumber(n) => {
-                // Split at line number n (1-based).
-                let split_at = *n;
-                if split_at <= current_line {
-                    let msg = format!("{}: line number out of range", split_at);
-                    do_cleanup(&created_files, config);
-                    return Err(msg);
-                }
-
-                let end = if split_at > total_lines {
-                    total_lines
-                } else {
-                    split_at - 1
+    counts: &mut impl Write,
+) -> Result<(), String> {
+    // Attach {N} and {*} to the pattern they follow.
+    let mut controls: Vec<Control> = Vec::new();
+    for pattern in patterns {
+        match pattern {
+            Pattern::Repeat(_) | Pattern::RepeatForever => {
+                let Some(last) = controls.last_mut() else {
+                    return Err("no preceding pattern to repeat".to_string());
                 };
-
-                let chunk_lines = &lines[current_line..end];
-                let filename = output_filename(config, file_index);
-
-                let bytes = write_chunk(chunk_lines, &filename, config).inspect_err(|_| {
-                    do_cleanup(&created_files, config);
-                })?;
-
-                if !(config.elide_empty && chunk_lines.is_empty()) {
-                    created_files.push(filename);
-                    sizes.push(bytes);
-                    file_index += 1;
-                }
-
-                current_line = end;
-                skip_current = false;
-                pat_idx += 1;
-            }
-            Pattern::Regex { regex, offset } => {
-                let regex = regex.clone();
-                let offset = *offset;
-                if let Err(e) = apply_regex_pattern(
-                    &lines,
-                    total_lines,
-                    &regex,
-                    offset,
-                    false,
-                    &mut current_line,
-                    &mut skip_current,
-                    &mut sizes,
-                    &mut created_files,
-                    &mut file_index,
-                    config,
-                    false,
-                ) {
-                    do_cleanup(&created_files, config);
-                    return Err(e);
-                }
-                pat_idx += 1;
-            }
-            Pattern::SkipTo { regex, offset } => {
-                let regex = regex.clone();
-                let offset = *offset;
-                if let Err(e) = apply_regex_pattern(
-                    &lines,
-                    total_lines,
-                    &regex,
-                    offset,
-                    true,
-                    &mut current_line,
-                    &mut skip_current,
-                    &mut sizes,
-                    &mut created_files,
-                    &mut file_index,
-                    config,
-                    false,
-                ) {
-                    do_cleanup(&created_files, config);
-                    return Err(e);
-                }
-                pat_idx += 1;
-            }
-            Pattern::Repeat(n) => {
-                let n = *n;
-                if pat_idx == 0 {
-                    do_cleanup(&created_files, config);
-                    return Err("{N}: no preceding pattern to repeat".to_string());
-                }
-                // Find the preceding non-repeat pattern
-                let prev_pat = find_prev_pattern(patterns, pat_idx);
-                let prev_pat = match prev_pat {
-                    Some(p) => p.clone(),
-                    None => {
-                        do_cleanup(&created_files, config);
-                        return Err("{N}: no preceding pattern to repeat".to_string());
-                    }
+                last.repeat = match pattern {
+                    Pattern::Repeat(n) => Some(*n),
+                    _ => None,
                 };
-                for _ in 0..n {
-                    match &prev_pat {
-                        Pattern::LineNumber(ln) => {
-                            // For repeated line numbers, this doesn't make much sense
-                            // but follow the same logic
-                            let end = if *ln > total_lines {
-                                total_lines
-                            } else {
-                                *ln - 1
-                            };
-                            if end <= current_line {
-                                let msg = format!("{}: line number out of range", ln);
-                                do_cleanup(&created_files, config);
-                                return Err(msg);
-                            }
-                            let chunk_lines = &lines[current_line..end];
-                            let filename = output_filename(config, file_index);
-                            let bytes =
-                                write_chunk(chunk_lines, &filename, config).inspect_err(|_| {
-                                    do_cleanup(&created_files, config);
-                                })?;
-                            if !(config.elide_empty && chunk_lines.is_empty()) {
-                                created_files.push(filename);
-                                sizes.push(bytes);
-                                file_index += 1;
-                            }
-                            current_line = end;
-                            skip_current = false;
-                        }
-                        Pattern::Regex { regex, offset } => {
-                            if let Err(e) = apply_regex_pattern(
-                                &lines,
-                                total_lines,
-                                regex,
-                                *offset,
-                                false,
-                                &mut current_line,
-                                &mut skip_current,
-                                &mut sizes,
-                                &mut created_files,
-                                &mut file_index,
-                                config,
-                                false,
-                            ) {
-                                do_cleanup(&created_files, config);
-                                return Err(e);
-                            }
-                        }
-                        Pattern::SkipTo { regex, offset } => {
-                            if let Err(e) = apply_regex_pattern(
-                                &lines,
-                                total_lines,
-                                regex,
-                                *offset,
-                                true,
-                                &mut current_line,
-                                &mut skip_current,
-                                &mut sizes,
-                                &mut created_files,
-                                &mut file_index,
-                                config,
-                                false,
-                            ) {
-                                do_cleanup(&created_files, config);
-                                return Err(e);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                pat_idx += 1;
             }
-            Pattern::RepeatForever => {
-                if pat_idx == 0 {
-                    do_cleanup(&created_files, config);
-                    return Err("{*}: no preceding pattern to repeat".to_string());
-                }
-                let prev_pat = find_prev_pattern(patterns, pat_idx);
-                let prev_pat = match prev_pat {
-                    Some(p) => p.clone(),
-                    None => {
-                        do_cleanup(&created_files, config);
-                        return Err("{*}: no preceding pattern to repeat".to_string());
-                    }
-                };
-                // Repeat until the pattern fails to match (graceful stop)
-                loop {
-                    match &prev_pat {
-                        Pattern::Regex { regex, offset } => {
-                            match apply_regex_pattern(
-                                &lines,
-                                total_lines,
-                                regex,
-                                *offset,
-                                false,
-                                &mut current_line,
-                                &mut skip_current,
-                                &mut sizes,
-                                &mut created_files,
-                                &mut file_index,
-                                config,
-                                true, // graceful no-match
-                            ) {
-                                Ok(true) => continue,
-                                Ok(false) => break,
-                                Err(e) => {
-                                    do_cleanup(&created_files, config);
-                                    return Err(e);
-                                }
-                            }
-                        }
-                        Pattern::SkipTo { regex, offset } => {
-                            match apply_regex_pattern(
-                                &lines,
-                                total_lines,
-                                regex,
-                                *offset,
-                                true,
-                                &mut current_line,
-                                &mut skip_current,
-                                &mut sizes,
-                                &mut created_files,
-                                &mut file_index,
-                                config,
-                                true,
-                            ) {
-                                Ok(true) => continue,
-                                Ok(false) => break,
-                                Err(e) => {
-                                    do_cleanup(&created_files, config);
-                                    return Err(e);
-                                }
-                            }
-                        }
-                        _ => break,
-                    }
-                }
-                pat_idx += 1;
+            Pattern::Regex { regex, arg, .. } | Pattern::SkipTo { regex, arg, .. } => {
+                controls.push(Control {
+                    pattern,
+                    regex: Some(compile_regex(regex, arg)?),
+                    repeat: Some(0),
+                });
             }
+            Pattern::LineNumber(_) => controls.push(Control {
+                pattern,
+                regex: None,
+                repeat: Some(0),
+            }),
         }
     }
 
-    // Write remaining lines as the final chunk
-    if current_line < total_lines {
-        let chunk_lines = &lines[current_line..total_lines];
-        let filename = output_filename(config, file_index);
-
-        let bytes = write_chunk(chunk_lines, &filename, config).inspect_err(|_| {
-            do_cleanup(&created_files, config);
-        })?;
-
-        if !(config.elide_empty && chunk_lines.is_empty()) {
-            created_files.push(filename);
-            sizes.push(bytes);
-        }
-    } else if !config.elide_empty {
-        // Write an empty final file
-        let filename = output_filename(config, file_index);
-        let bytes = write_chunk(&[], &filename, config).inspect_err(|_| {
-            do_cleanup(&created_files, config);
-        })?;
-        created_files.push(filename);
-        sizes.push(bytes);
-    }
-
-    Ok(sizes)
-}
+    let mut splitter = Splitter {
+        config,
+        lines: input.split_inclusive(|&b| b == b'\n').collect(),
+        next: 0,
+        current_line: 0,
+        created: Vec::new(),
+        output: None,
+        counts,
+    };
 
-/// Find the preceding non-repeat pattern.
-fn find_prev_pattern(patterns: &[Pattern], idx: usize) -> Option<&Pattern> {
-    let mut i = idx;
-    while i > 0 {
-        i -= 1;
-        match &patterns[i] {
-            Pattern::Repeat(_) | Pattern::RepeatForever => continue,
-            other => return Some(other),
+    for control in &controls {
+        let mut repetition = 0;
+        while control.repeat.is_none_or(|n| repetition <= n) {
+            match control.pattern {
+                Pattern::LineNumber(n) => splitter.process_line_count(*n, repetition)?,
+                Pattern::Regex { offset, arg, .. } | Pattern::SkipTo { offset, arg, .. } => {
+                    let skip = matches!(control.pattern, Pattern::SkipTo { .. });
+                    let re = control.regex.as_ref().expect("regex compiled");
+                    let forever = control.repeat.is_none();
+                    if !splitter.process_regexp(re, *offset, skip, arg, repetition, forever)? {
+                        return Ok(());
+                    }
+                }
+                Pattern::Repeat(_) | Pattern::RepeatForever => unreachable!(),
+            }
+            repetition += 1;
         }
     }
-    None
+
+    // Whatever is left goes into the final file.
+    splitter.create_output_file()?;
+    splitter.dump_rest_of_file()?;
+    splitter.close_output_file()
 }
 
 /// Split a file by reading from a path or stdin ("-").
@@ -586,34 +594,14 @@ pub fn csplit_from_path(
     path: &str,
     patterns: &[Pattern],
     config: &CsplitConfig,
-) -> Result<Vec<u64>, String> {
+    counts: &mut impl Write,
+) -> Result<(), String> {
     let input = if path == "-" {
-        let mut buf = String::new();
-        io::stdin()
-            .read_line(&mut buf)
-            .map_err(|e| format!("read error: {}", e))?;
-        // Read all remaining
-        let mut all = buf;
-        let mut line = String::new();
-        while io::stdin()
-            .read_line(&mut line)
-            .map_err(|e| format!("read error: {}", e))?
-            > 0
-        {
-            all.push_str(&line);
-            line.clear();
-        }
-        all
+        crate::common::io::read_stdin().map_err(|e| format!("read error: {}", io_error_msg(&e)))?
     } else {
-        fs::read_to_string(path).map_err(|e| format!("cannot open '{}': {}", path, e))?
+        fs::read(path)
+            .map_err(|e| format!("cannot open '{}' for reading: {}", path, io_error_msg(&e)))?
     };
 
-    csplit_file(&input, patterns, config)
-}
-
-/// Print the sizes of created files to stdout.
-pub fn print_sizes(sizes: &[u64]) {
-    for size in sizes {
-        println!("{}", size);
-    }
+    csplit_file(&input, patterns, config, counts)
 }
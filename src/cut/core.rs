@@ -18,6 +18,8 @@ pub struct CutConfig<'a> {
     pub output_delim: &'a [u8],
     pub suppress_no_delim: bool,
     pub line_delim: u8,
+    /// Split fields on runs of blanks (space/tab) instead of `delim` (-w).
+    pub whitespace: bool,
 }
 
 /// A range specification like 1, 3-5, -3, 4-
@@ -28,67 +30,88 @@ pub struct Range {
 }
 
 /// Parse a LIST specification like "1,3-5,7-" into ranges.
-/// Each range is 1-based. Returns sorted, merged ranges.
+/// Items are separated by commas or blanks. Each range is 1-based. Returns
+/// sorted, merged ranges, so output follows increasing position order
+/// whatever order the LIST gives. Error messages follow GNU cut and name
+/// fields or byte/character positions according to `mode`.
 /// When `no_merge_adjacent` is true, overlapping ranges are still merged but
 /// adjacent ranges (e.g., 1-2,3-4) are kept separate. This is needed when
 /// `--output-delimiter` is specified for byte/char mode so the delimiter is
 /// inserted between originally separate but adjacent ranges.
-pub fn parse_ranges(spec: &str, no_merge_adjacent: bool) -> Result<Vec<Range>, String> {
+pub fn parse_ranges(
+    spec: &str,
+    mode: CutMode,
+    no_merge_adjacent: bool,
+) -> Result<Vec<Range>, String> {
+    let fields = mode == CutMode::Fields;
+    let numbered_from_1 = || {
+        if fields {
+            "fields are numbered from 1".to_string()
+        } else {
+            "byte/character positions are numbered from 1".to_string()
+        }
+    };
+    let parse_num = |s: &str| -> Result<usize, String> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(if fields {
+                format!("invalid field value '{}'", s)
+            } else {
+                format!("invalid byte/character position '{}'", s)
+            });
+        }
+        s.parse::<usize>().map_err(|_| {
+            if fields {
+                format!("field number '{}' is too large", s)
+            } else {
+                format!("byte/character offset '{}' is too large", s)
+            }
+        })
+    };
+
     let mut ranges = Vec::new();
 
-    for part in spec.split(',') {
-        let part = part.trim();
+    for part in spec.split([',', ' ', '\t']) {
         if part.is_empty() {
-            continue;
+            return Err(numbered_from_1());
         }
 
-        if let Some(idx) = part.find('-') {
-            let left = &part[..idx];
-            let right = &part[idx + 1..];
+        if let Some((left, right)) = part.split_once('-') {
+            if right.contains('-') {
+                return Err(if fields {
+                    "invalid field range".to_string()
+                } else {
+                    "invalid byte or character range".to_string()
+                });
+            }
 
             // Reject bare "-" (both sides empty)
             if left.is_empty() && right.is_empty() {
                 return Err("invalid range with no endpoint: -".to_string());
             }
 
-            let start = if left.is_empty() {
-                1
-            } else {
-                left.parse::<usize>()
-                    .map_err(|_| format!("invalid range: '{}'", part))?
-            };
-
+            let start = if left.is_empty() { 1 } else { parse_num(left)? };
+            if start == 0 {
+                return Err(numbered_from_1());
+            }
             let end = if right.is_empty() {
                 usize::MAX
             } else {
-                right
-                    .parse::<usize>()
-                    .map_err(|_| format!("invalid range: '{}'", part))?
+                parse_num(right)?
             };
-
-            if start == 0 {
-                return Err("fields and positions are numbered from 1".to_string());
-            }
             if start > end {
-                return Err(format!("invalid decreasing range: '{}'", part));
+                return Err("invalid decreasing range".to_string());
             }
 
             ranges.push(Range { start, end });
         } else {
-            let n = part
-                .parse::<usize>()
-                .map_err(|_| format!("invalid field: '{}'", part))?;
+            let n = parse_num(part)?;
             if n == 0 {
-                return Err("fields and positions are numbered from 1".to_string());
+                return Err(numbered_from_1());
             }
             ranges.push(Range { start: n, end: n });
         }
     }
 
-    if ranges.is_empty() {
-        return Err("you must specify a list of bytes, characters, or fields".to_string());
-    }
-
     // Sort and merge overlapping/adjacent ranges
     ranges.sort_by_key(|r| (r.start, r.end));
     let mut merged = vec![ranges[0].clone()];
@@ -343,7 +366,7 @@ fn multi_select_chunk(
 ) {
     // When delim == line_delim, fall back to two-level approach
     if delim == line_delim {
-        buf.reserve(data.len());
+        buf.reserve(data.len() + 1);
         let base = data.as_ptr();
         let mut start = 0;
         for end_pos in memchr_iter(line_delim, data) {
@@ -358,7 +381,7 @@ fn multi_select_chunk(
         return;
     }
 
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let base = data.as_ptr();
     let data_len = data.len();
 
@@ -612,6 +635,47 @@ fn multi_select_line(
 // ── Fast path: field extraction with batched output ──────────────────────
 
 /// Optimized field extraction with early exit and batched output.
+/// Field extraction for -w: a field is a maximal run of non-blank bytes, so
+/// runs of spaces/tabs count as one separator and leading or trailing blanks
+/// never produce empty fields. Lines holding fewer than two fields count as
+/// undelimited and are printed unchanged unless -s is given.
+fn process_fields_whitespace(data: &[u8], cfg: &CutConfig, out: &mut impl Write) -> io::Result<()> {
+    let line_delim = cfg.line_delim;
+    let is_blank = |b: &u8| *b == b' ' || *b == b'\t';
+    let mut buf = Vec::with_capacity(data.len() + 1);
+    let mut start = 0;
+    while start < data.len() {
+        let end = memchr::memchr(line_delim, &data[start..]).map_or(data.len(), |p| start + p);
+        let line = &data[start..end];
+        start = end + 1;
+
+        let mut fields = line.split(is_blank).filter(|f| !f.is_empty());
+        let first = fields.next();
+        let second = fields.next();
+        if second.is_none() {
+            if !cfg.suppress_no_delim {
+                buf.extend_from_slice(line);
+                buf.push(line_delim);
+            }
+            continue;
+        }
+
+        let mut first_output = true;
+        let all = first.into_iter().chain(second).chain(fields);
+        for (i, field) in all.enumerate() {
+            if in_ranges(cfg.ranges, i + 1) != cfg.complement {
+                if !first_output {
+                    buf.extend_from_slice(cfg.output_delim);
+                }
+                buf.extend_from_slice(field);
+                first_output = false;
+            }
+        }
+        buf.push(line_delim);
+    }
+    out.write_all(&buf)
+}
+
 fn process_fields_fast(data: &[u8], cfg: &CutConfig, out: &mut impl Write) -> io::Result<()> {
     let delim = cfg.delim;
     let line_delim = cfg.line_delim;
@@ -724,6 +788,13 @@ fn process_fields_fast(data: &[u8], cfg: &CutConfig, out: &mut impl Write) -> io
         return process_fields_multi_select(data, delim, line_delim, ranges, suppress, out);
     }
 
+    // Remaining non-complement selections (custom output delimiter, open-ended
+    // multi-range lists): zero-copy field slices with the output delimiter
+    // interleaved between them.
+    if !complement && delim != line_delim && data.len() < PARALLEL_THRESHOLD {
+        return fields_zerocopy(data, delim, line_delim, ranges, output_delim, suppress, out);
+    }
+
     // General field extraction
     let max_field = if complement {
         usize::MAX
@@ -806,7 +877,7 @@ fn process_fields_chunk(
     // For complement or unbounded ranges, use single-pass memchr2_iter which
     // needs to process all delimiters anyway.
     if delim != line_delim && max_field < usize::MAX && !complement {
-        buf.reserve(data.len());
+        buf.reserve(data.len() + 1);
         let mut start = 0;
         for end_pos in memchr_iter(line_delim, data) {
             let line = &data[start..end_pos];
@@ -845,7 +916,7 @@ fn process_fields_chunk(
     // delimiter and line_delim in one SIMD scan.
     // Uses raw pointer arithmetic to eliminate bounds checking in the hot loop.
     if delim != line_delim {
-        buf.reserve(data.len());
+        buf.reserve(data.len() + 1);
 
         let data_len = data.len();
         let base = data.as_ptr();
@@ -1038,9 +1109,9 @@ fn process_single_field(
             return Ok(());
         }
 
-        // Two-level approach for field N: outer newline scan + inner delim scan
-        // with early exit at target_idx. Faster than memchr2 single-pass because
-        // we only scan delimiters up to target_idx per line (not all of them).
+        // Field N: scan delimiters up to target_idx, then jump to the line end.
+        // Parallel chunks copy into per-thread buffers; the sequential path
+        // writes the field slices zero-copy.
         if data.len() >= FIELD_PARALLEL_MIN {
             let chunks = split_for_scope(data, line_delim);
             let n = chunks.len();
@@ -1062,11 +1133,11 @@ fn process_single_field(
                 .collect();
             write_ioslices(out, &slices)?;
         } else {
-            let mut buf = Vec::with_capacity(data.len().min(4 * 1024 * 1024));
-            process_single_field_chunk(data, delim, target_idx, line_delim, suppress, &mut buf);
-            if !buf.is_empty() {
-                out.write_all(&buf)?;
-            }
+            let ranges = [Range {
+                start: target,
+                end: target,
+            }];
+            fields_zerocopy(data, delim, line_delim, &ranges, &[delim], suppress, out)?;
         }
         return Ok(());
     }
@@ -1156,7 +1227,7 @@ fn complement_range_chunk(
     buf: &mut Vec<u8>,
 ) {
     // Pre-reserve entire chunk capacity to eliminate per-line reserve overhead.
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let mut start = 0;
     for end_pos in memchr_iter(line_delim, data) {
         let line = &data[start..end_pos];
@@ -1346,7 +1417,7 @@ fn complement_single_field_chunk(
 ) {
     // When delim == line_delim, fall back to per-line approach
     if delim == line_delim {
-        buf.reserve(data.len());
+        buf.reserve(data.len() + 1);
         let mut start = 0;
         for end_pos in memchr_iter(line_delim, data) {
             let line = &data[start..end_pos];
@@ -1366,7 +1437,7 @@ fn complement_single_field_chunk(
         return;
     }
 
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let base = data.as_ptr();
     let data_len = data.len();
     let need_before = skip_idx; // delimiters before skip field
@@ -1701,14 +1772,12 @@ fn fields_prefix_zerocopy(
 
     for end_pos in memchr_iter(line_delim, data) {
         let line = &data[start..end_pos];
-        let mut field_count = 1;
         let mut truncate_at: Option<usize> = None;
-        for dpos in memchr_iter(delim, line) {
+        for (field_count, dpos) in (1..).zip(memchr_iter(delim, line)) {
             if field_count >= last_field {
                 truncate_at = Some(start + dpos);
                 break;
             }
-            field_count += 1;
         }
 
         if let Some(trunc_pos) = truncate_at {
@@ -1729,14 +1798,12 @@ fn fields_prefix_zerocopy(
     // Handle last line without terminator
     if start < data.len() {
         let line = &data[start..];
-        let mut field_count = 1;
         let mut truncate_at: Option<usize> = None;
-        for dpos in memchr_iter(delim, line) {
+        for (field_count, dpos) in (1..).zip(memchr_iter(delim, line)) {
             if field_count >= last_field {
                 truncate_at = Some(start + dpos);
                 break;
             }
-            field_count += 1;
         }
         if let Some(trunc_pos) = truncate_at {
             if run_start < start {
@@ -1772,7 +1839,7 @@ fn fields_prefix_chunk(
     suppress: bool,
     buf: &mut Vec<u8>,
 ) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let mut start = 0;
     for end_pos in memchr_iter(line_delim, data) {
         let line = &data[start..end_pos];
@@ -1806,10 +1873,9 @@ fn fields_prefix_line(
     // Note: no per-line buf.reserve — fields_prefix_chunk already reserves data.len()
     let base = line.as_ptr();
 
-    let mut field_count = 1usize;
     let mut has_delim = false;
 
-    for pos in memchr_iter(delim, line) {
+    for (field_count, pos) in (1usize..).zip(memchr_iter(delim, line)) {
         has_delim = true;
         if field_count >= last_field {
             unsafe {
@@ -1818,7 +1884,6 @@ fn fields_prefix_line(
             }
             return;
         }
-        field_count += 1;
     }
 
     if !has_delim {
@@ -1883,7 +1948,7 @@ fn fields_suffix_chunk(
     suppress: bool,
     buf: &mut Vec<u8>,
 ) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let mut start = 0;
     for end_pos in memchr_iter(line_delim, data) {
         let line = &data[start..end_pos];
@@ -2026,7 +2091,7 @@ fn fields_mid_range_chunk(
 ) {
     // When delim == line_delim, fall back to per-line approach
     if delim == line_delim {
-        buf.reserve(data.len());
+        buf.reserve(data.len() + 1);
         let mut start = 0;
         for end_pos in memchr_iter(line_delim, data) {
             let line = &data[start..end_pos];
@@ -2055,7 +2120,7 @@ fn fields_mid_range_chunk(
         return;
     }
 
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let base = data.as_ptr();
     let skip_before = start_field - 1; // delimiters to skip before range
     let target_end_delim = skip_before + (end_field - start_field) + 1;
@@ -2133,6 +2198,7 @@ fn fields_mid_range_chunk(
                         buf,
                         std::slice::from_raw_parts(base.add(line_start), data.len() - line_start),
                     );
+                    buf_push(buf, line_delim);
                 }
             }
         } else if delim_count >= skip_before {
@@ -2144,7 +2210,10 @@ fn fields_mid_range_chunk(
                     buf,
                     std::slice::from_raw_parts(base.add(range_start), data.len() - range_start),
                 );
+                buf_push(buf, line_delim);
             }
+        } else {
+            unsafe { buf_push(buf, line_delim) };
         }
     }
 }
@@ -2447,7 +2516,7 @@ fn process_single_field_chunk(
     buf: &mut Vec<u8>,
 ) {
     // Pre-reserve chunk capacity to eliminate per-line reserve overhead.
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let mut start = 0;
     for end_pos in memchr_iter(line_delim, data) {
         let line = &data[start..end_pos];
@@ -2637,6 +2706,247 @@ fn extract_fields_to_buf(
     }
 }
 
+// ── Zero-copy field extraction with writev ───────────────────────────────
+
+/// Pieces shorter than this are copied into the staging buffer instead of
+/// getting their own IoSlice; per-iovec kernel overhead outweighs a short memcpy.
+const ZEROCOPY_MIN: usize = 256;
+
+/// Staging buffer size for short pieces.
+const STAGING_CAP: usize = 256 * 1024;
+
+/// One queued output piece: a range of the source data or of the staging buffer.
+#[derive(Clone, Copy)]
+enum Piece {
+    Src(usize, usize),
+    Staged(usize, usize),
+}
+
+/// Batched writev output that references the source data directly.
+/// Adjacent source pieces are coalesced into one run, so a line passed through
+/// unchanged, or a field followed by its own line terminator, is a single piece.
+/// Long runs are written zero-copy; short runs and output delimiters are copied
+/// into a staging buffer so high-density data still produces few iovecs.
+struct IovBatch<'a> {
+    data: &'a [u8],
+    pieces: Vec<Piece>,
+    staging: Vec<u8>,
+    /// Start of the staged bytes not yet recorded as a piece.
+    staged_from: usize,
+    run_start: usize,
+    run_end: usize,
+}
+
+impl<'a> IovBatch<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pieces: Vec::with_capacity(MAX_IOV),
+            staging: Vec::with_capacity(STAGING_CAP.min(data.len() + 1)),
+            staged_from: 0,
+            run_start: 0,
+            run_end: 0,
+        }
+    }
+
+    /// Queue `data[start..end]`, extending the pending run when contiguous.
+    #[inline(always)]
+    fn push_src(&mut self, start: usize, end: usize, out: &mut impl Write) -> io::Result<()> {
+        if start != self.run_end {
+            self.close_run(out)?;
+            self.run_start = start;
+        }
+        self.run_end = end;
+        Ok(())
+    }
+
+    /// Queue bytes that do not come from the source (output delimiter, newline).
+    #[inline(always)]
+    fn push_extra(&mut self, bytes: &[u8], out: &mut impl Write) -> io::Result<()> {
+        self.close_run(out)?;
+        self.stage(bytes, out)
+    }
+
+    #[inline(always)]
+    fn close_run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let (start, end) = (self.run_start, self.run_end);
+        self.run_start = end;
+        if end - start >= ZEROCOPY_MIN {
+            self.close_staged();
+            if self.pieces.len() >= MAX_IOV {
+                self.flush(out)?;
+            }
+            self.pieces.push(Piece::Src(start, end));
+            Ok(())
+        } else {
+            let data = self.data;
+            self.stage(&data[start..end], out)
+        }
+    }
+
+    /// Copy short bytes into the staging buffer.
+    #[inline(always)]
+    fn stage(&mut self, bytes: &[u8], out: &mut impl Write) -> io::Result<()> {
+        if self.staging.capacity() - self.staging.len() < bytes.len() {
+            self.flush(out)?;
+            self.staging.reserve(bytes.len());
+        }
+        unsafe { buf_extend(&mut self.staging, bytes) };
+        Ok(())
+    }
+
+    /// Record the open staged bytes as a piece.
+    #[inline(always)]
+    fn close_staged(&mut self) {
+        if self.staging.len() > self.staged_from {
+            self.pieces
+                .push(Piece::Staged(self.staged_from, self.staging.len()));
+            self.staged_from = self.staging.len();
+        }
+    }
+
+    fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.close_staged();
+        let slices: Vec<IoSlice> = self
+            .pieces
+            .iter()
+            .map(|p| match *p {
+                Piece::Src(s, e) => IoSlice::new(&self.data[s..e]),
+                Piece::Staged(s, e) => IoSlice::new(&self.staging[s..e]),
+            })
+            .collect();
+        write_ioslices(out, &slices)?;
+        self.pieces.clear();
+        self.staging.clear();
+        self.staged_from = 0;
+        Ok(())
+    }
+
+    fn finish(mut self, out: &mut impl Write) -> io::Result<()> {
+        self.close_run(out)?;
+        self.flush(out)
+    }
+}
+
+/// Per-line state for [`fields_zerocopy`].
+struct ZerocopyLine {
+    field_num: usize,
+    field_start: usize,
+    /// End of the last selected field, if any field was output on this line.
+    prev_end: Option<usize>,
+}
+
+impl ZerocopyLine {
+    /// Queue the output delimiter (after an earlier selected field) and a field.
+    #[inline(always)]
+    fn emit_field(
+        &mut self,
+        batch: &mut IovBatch,
+        end: usize,
+        output_delim: &[u8],
+        same_delim: bool,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        if let Some(pe) = self.prev_end {
+            if same_delim {
+                batch.push_src(pe, pe + 1, out)?;
+            } else {
+                batch.push_extra(output_delim, out)?;
+            }
+        }
+        batch.push_src(self.field_start, end, out)?;
+        self.prev_end = Some(end);
+        Ok(())
+    }
+}
+
+/// Zero-copy field extraction for a single-byte delimiter (e.g., `cut -d, -f3`
+/// or `cut -d, -f2-4 --output-delimiter=';'`).
+/// A single memchr2 pass jumps between delimiters and line terminators; each
+/// selected field is queued as a range of the source, with the output delimiter
+/// interleaved between selected fields. Line terminators are taken from the
+/// source so they coalesce with the preceding field; only an unterminated last
+/// line needs a synthetic one.
+fn fields_zerocopy(
+    data: &[u8],
+    delim: u8,
+    line_delim: u8,
+    ranges: &[Range],
+    output_delim: &[u8],
+    suppress: bool,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let max_field = ranges.last().map_or(0, |r| r.end);
+    let field_mask = compute_field_mask(ranges, false);
+    // With the default output delimiter the delimiter between two selected
+    // fields is also taken from the source, keeping contiguous fields in one run.
+    let same_delim = output_delim.len() == 1 && output_delim[0] == delim;
+    let mut batch = IovBatch::new(data);
+    let mut line_start = 0;
+    let mut line = ZerocopyLine {
+        field_num: 1,
+        field_start: 0,
+        prev_end: None,
+    };
+
+    let mut cursor = 0;
+    while let Some(off) = memchr::memchr2(delim, line_delim, &data[cursor..]) {
+        let mut pos = cursor + off;
+        if data[pos] == delim {
+            if is_selected(line.field_num, field_mask, ranges, false) {
+                line.emit_field(&mut batch, pos, output_delim, same_delim, out)?;
+            }
+            line.field_num += 1;
+            line.field_start = pos + 1;
+            if line.field_num <= max_field {
+                cursor = pos + 1;
+                continue;
+            }
+            // Past the last selected field: jump straight to the line end.
+            match memchr::memchr(line_delim, &data[pos + 1..]) {
+                Some(nl) => pos += 1 + nl,
+                None => break,
+            }
+        }
+        if line.field_num == 1 {
+            // No delimiter on this line: pass it through unless -s.
+            if !suppress {
+                batch.push_src(line_start, pos + 1, out)?;
+            }
+        } else {
+            if line.field_num <= max_field && is_selected(line.field_num, field_mask, ranges, false)
+            {
+                line.emit_field(&mut batch, pos, output_delim, same_delim, out)?;
+            }
+            batch.push_src(pos, pos + 1, out)?;
+        }
+        line_start = pos + 1;
+        cursor = line_start;
+        line = ZerocopyLine {
+            field_num: 1,
+            field_start: line_start,
+            prev_end: None,
+        };
+    }
+
+    // Last line without terminator
+    let end = data.len();
+    if line_start < end {
+        if line.field_num == 1 {
+            if suppress {
+                return batch.finish(out);
+            }
+            batch.push_src(line_start, end, out)?;
+        } else if line.field_num <= max_field
+            && is_selected(line.field_num, field_mask, ranges, false)
+        {
+            line.emit_field(&mut batch, end, output_delim, same_delim, out)?;
+        }
+        batch.push_extra(&[line_delim], out)?;
+    }
+    batch.finish(out)
+}
+
 // ── Fast path: byte/char extraction with batched output ──────────────────
 
 /// Ultra-fast path for `cut -b1-N`: single from-start byte range.
@@ -2798,7 +3108,7 @@ fn bytes_from_start_zerocopy(
 fn bytes_from_start_chunk(data: &[u8], max_bytes: usize, line_delim: u8, buf: &mut Vec<u8>) {
     // Output is always <= input size (we only truncate, never expand).
     // Single reserve eliminates ALL per-line capacity checks.
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
 
     let src = data.as_ptr();
     let dst_base = buf.as_mut_ptr();
@@ -2905,7 +3215,7 @@ fn bytes_from_offset_zerocopy(
 /// Single reserve + deferred set_len for zero per-line overhead.
 #[inline]
 fn bytes_from_offset_chunk(data: &[u8], skip_bytes: usize, line_delim: u8, buf: &mut Vec<u8>) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
 
     let src = data.as_ptr();
     let dst_base = buf.as_mut_ptr();
@@ -2993,7 +3303,7 @@ fn bytes_mid_range_chunk(
     line_delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
 
     let src = data.as_ptr();
     let dst_base = buf.as_mut_ptr();
@@ -3081,7 +3391,7 @@ fn bytes_complement_mid_chunk(
     line_delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
 
     let src = data.as_ptr();
     let dst_base = buf.as_mut_ptr();
@@ -3256,7 +3566,7 @@ fn process_bytes_chunk(
     line_delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    buf.reserve(data.len());
+    buf.reserve(data.len() + 1);
     let base = data.as_ptr();
     let mut start = 0;
     for end_pos in memchr_iter(line_delim, data) {
@@ -3524,9 +3834,48 @@ pub fn cut_field1_inplace(data: &mut [u8], delim: u8, line_delim: u8, suppress:
     wp
 }
 
+/// Field mode when the field delimiter is also the line delimiter
+/// (`-d $'\n'`, or `-d ''` with -z). As in GNU cut the whole input is then
+/// one record: every delimiter separates fields, except a final one, which
+/// only ends the record. With -s, an input whose sole delimiter is that
+/// final one counts as undelimited unless field 1 is selected.
+fn process_fields_one_record(data: &[u8], cfg: &CutConfig, out: &mut impl Write) -> io::Result<()> {
+    let delim = cfg.delim;
+    if data.is_empty() {
+        return Ok(());
+    }
+    if memchr::memchr(delim, data).is_none() {
+        if !cfg.suppress_no_delim {
+            out.write_all(data)?;
+            out.write_all(&[delim])?;
+        }
+        return Ok(());
+    }
+    let record = data.strip_suffix(&[delim]).unwrap_or(data);
+    let mut buf = Vec::with_capacity(record.len() + 1);
+    let mut any_selected = false;
+    for (i, field) in record.split(|&b| b == delim).enumerate() {
+        if in_ranges(cfg.ranges, i + 1) != cfg.complement {
+            if any_selected {
+                buf.extend_from_slice(cfg.output_delim);
+            }
+            buf.extend_from_slice(field);
+            any_selected = true;
+        }
+    }
+    let sole_final_delim = memchr::memchr(delim, record).is_none();
+    if !any_selected && cfg.suppress_no_delim && sole_final_delim {
+        return Ok(());
+    }
+    buf.push(delim);
+    out.write_all(&buf)
+}
+
 /// Process a full data buffer (from mmap or read) with cut operation.
 pub fn process_cut_data(data: &[u8], cfg: &CutConfig, out: &mut impl Write) -> io::Result<()> {
     match cfg.mode {
+        CutMode::Fields if cfg.whitespace => process_fields_whitespace(data, cfg, out),
+        CutMode::Fields if cfg.delim == cfg.line_delim => process_fields_one_record(data, cfg, out),
         CutMode::Fields => process_fields_fast(data, cfg, out),
         CutMode::Bytes | CutMode::Characters => process_bytes_fast(data, cfg, out),
     }
@@ -3544,6 +3893,13 @@ pub fn process_cut_reader<R: BufRead>(
     const CHUNK_SIZE: usize = 16 * 1024 * 1024; // 16MB read chunks
     let line_delim = cfg.line_delim;
 
+    // A delimiter that also ends lines makes the whole input one record.
+    if cfg.mode == CutMode::Fields && !cfg.whitespace && cfg.delim == line_delim {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        return process_cut_data(&data, cfg, out);
+    }
+
     // Read large chunks and process in batch.
     // We keep a buffer; after processing complete lines, we shift leftover to the front.
     let mut buf = Vec::with_capacity(CHUNK_SIZE + 4096);
@@ -3621,7 +3977,7 @@ fn read_fully<R: BufRead>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
 /// path because GNU cut always adds a trailing line delimiter, and the in-place
 /// buffer cannot grow beyond the input size.
 pub fn process_cut_data_mut(data: &mut [u8], cfg: &CutConfig) -> Option<usize> {
-    if cfg.complement {
+    if cfg.complement || cfg.whitespace {
         return None;
     }
     // If input doesn't end with line_delim, the output may need an extra byte
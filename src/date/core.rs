@@ -65,12 +65,23 @@ pub fn parse_rfc3339_format(s: &str) -> Result<Rfc3339Format, String> {
 
 /// Format a `SystemTime` using the given format string.
 ///
-/// Uses libc `strftime` for most specifiers. Handles `%N` (nanoseconds) manually
-/// since strftime does not support it.
+/// Each conversion accepts GNU's `%[flags][width][E|O][:...]conv` syntax.
+/// Uses libc `strftime` for most conversions; `%N`, `%s`, `%q`, `%P` and the
+/// `%z` family are rendered here since strftime lacks or mishandles them.
 pub fn format_date(time: &SystemTime, format: &str, utc: bool) -> String {
-    let dur = time.duration_since(UNIX_EPOCH).unwrap_or_default();
-    let secs = dur.as_secs() as i64;
-    let nanos = dur.subsec_nanos();
+    // Times before the epoch keep a non-negative nanosecond part, so
+    // -1.5 seconds is -2 seconds plus 500000000 nanoseconds.
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(dur) => (dur.as_secs() as i64, dur.subsec_nanos()),
+        Err(e) => {
+            let dur = e.duration();
+            let secs = (dur.as_secs() as i64).wrapping_neg();
+            match dur.subsec_nanos() {
+                0 => (secs, 0),
+                n => (secs - 1, 1_000_000_000 - n),
+            }
+        }
+    };
 
     let mut tm: libc::tm = unsafe { std::mem::zeroed() };
     if utc {
@@ -83,88 +94,275 @@ pub fn format_date(time: &SystemTime, format: &str, utc: bool) -> String {
         }
     }
 
-    // Process the format string, handling %N, %-X, %_X specially and passing
-    // everything else through to strftime.
     let mut result = String::with_capacity(format.len() * 2);
     let chars: Vec<char> = format.chars().collect();
     let mut i = 0;
 
     while i < chars.len() {
-        if chars[i] == '%' && i + 1 < chars.len() {
-            // Check for GNU format modifiers: %-X (no pad), %_X (space pad), %0X (zero pad)
-            let modifier = if i + 2 < chars.len()
-                && (chars[i + 1] == '-' || chars[i + 1] == '_' || chars[i + 1] == '0')
-                && chars[i + 2].is_ascii_alphabetic()
-            {
-                let m = chars[i + 1];
-                i += 1; // skip the modifier, will process specifier next
-                Some(m)
+        if chars[i] != '%' {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let (spec, end) = parse_conversion(&chars, i);
+        let literal: String = chars[i..end].iter().collect();
+        i = end;
+        let Some(conv) = spec.conv else {
+            // A trailing '%' (possibly with flags) is copied as is.
+            result.push_str(&literal);
+            continue;
+        };
+
+        if spec.colons > 0 {
+            if conv == 'z' && spec.colons <= 3 {
+                result.push_str(&format_tz_offset(tm.tm_gmtoff as i64, spec.colons, &spec));
             } else {
-                None
-            };
+                result.push_str(&literal);
+            }
+            continue;
+        }
 
-            match chars[i + 1] {
-                's' => {
-                    // Unix timestamp — output directly to avoid mktime timezone issues.
-                    // strftime("%s") calls mktime() internally which treats the tm struct
-                    // as local time, causing wrong results when tm was filled with gmtime_r().
-                    result.push_str(&secs.to_string());
-                    i += 2;
-                }
-                'N' => {
-                    // Nanoseconds (9 digits, zero-padded)
-                    result.push_str(&format!("{:09}", nanos));
-                    i += 2;
-                }
-                'q' => {
-                    // Quarter (1-4), not in standard strftime
-                    let month = tm.tm_mon; // 0-11
-                    let quarter = (month / 3) + 1;
-                    result.push_str(&quarter.to_string());
-                    i += 2;
-                }
-                'P' => {
-                    // am/pm (lowercase), not always available in strftime
-                    let ampm = if tm.tm_hour < 12 { "am" } else { "pm" };
-                    result.push_str(ampm);
-                    i += 2;
-                }
-                'Z' if utc => {
-                    // Force "UTC" instead of platform-dependent "GMT"
-                    result.push_str("UTC");
-                    i += 2;
-                }
-                'n' => {
-                    result.push('\n');
-                    i += 2;
-                }
-                't' => {
-                    result.push('\t');
-                    i += 2;
-                }
-                _ => {
-                    // Pass this specifier to strftime
-                    let spec = format!("%{}", chars[i + 1]);
-                    let formatted = strftime_single(&tm, &spec);
-                    // Apply modifier if present
-                    let formatted = if let Some(mod_char) = modifier {
-                        apply_format_modifier(&formatted, mod_char)
-                    } else {
-                        formatted
-                    };
-                    result.push_str(&formatted);
-                    i += 2;
-                }
+        let raw = match conv {
+            'N' => {
+                result.push_str(&format_nanos(nanos, &spec));
+                continue;
+            }
+            'z' => {
+                result.push_str(&format_tz_offset(tm.tm_gmtoff as i64, 0, &spec));
+                continue;
             }
+            '%' if literal.len() == 2 => "%".to_string(),
+            '%' => {
+                // Like GNU, a malformed "%...%" is copied up to the second
+                // '%', which then starts the next conversion.
+                result.push_str(&pad_text(&literal[..literal.len() - 1], &spec));
+                i -= 1;
+                continue;
+            }
+            // Unix timestamp — output directly to avoid mktime timezone issues.
+            // strftime("%s") calls mktime() internally which treats the tm struct
+            // as local time, causing wrong results when tm was filled with gmtime_r().
+            's' => secs.to_string(),
+            // Quarter (1-4), not in standard strftime
+            'q' => (tm.tm_mon / 3 + 1).to_string(),
+            // am/pm (lowercase), not always available in strftime
+            'P' => if tm.tm_hour < 12 { "am" } else { "pm" }.to_string(),
+            // Force "UTC" instead of platform-dependent "GMT"
+            'Z' if utc => "UTC".to_string(),
+            'n' => "\n".to_string(),
+            't' => "\t".to_string(),
+            _ => {
+                let fmt = match spec.alt {
+                    Some(alt) => format!("%{}{}", alt, conv),
+                    None => format!("%{}", conv),
+                };
+                strftime_single(&tm, &fmt)
+            }
+        };
+
+        let formatted = if "CdgGHIjmMSUVWyYsqekl".contains(conv) {
+            let default_pad = if "ekl".contains(conv) { ' ' } else { '0' };
+            let year_digits = match conv {
+                'C' => Some(2),
+                'G' | 'Y' => Some(4),
+                _ => None,
+            };
+            pad_number(&raw, &spec, default_pad, year_digits)
         } else {
-            result.push(chars[i]);
-            i += 1;
-        }
+            // '#' lowers %p and %Z even when '^' is also given.
+            let cased = if spec.swapcase && matches!(conv, 'p' | 'Z') {
+                raw.to_lowercase()
+            } else if (spec.upcase && conv != 'P')
+                || (spec.swapcase && matches!(conv, 'a' | 'A' | 'b' | 'B' | 'h'))
+            {
+                raw.to_uppercase()
+            } else {
+                raw
+            };
+            pad_text(&cased, &spec)
+        };
+        result.push_str(&formatted);
     }
 
     result
 }
 
+/// A parsed `%` conversion: flags, field width, E/O modifier and colons.
+#[derive(Default)]
+struct Conversion {
+    /// The last padding flag given: '-', '_', '0' or '+'.
+    pad: Option<char>,
+    /// '^': convert to upper case.
+    upcase: bool,
+    /// '#': use the opposite case where that makes sense.
+    swapcase: bool,
+    width: Option<usize>,
+    /// 'E' or 'O' alternative representation modifier.
+    alt: Option<char>,
+    /// Number of ':' before the conversion character (only valid for `z`).
+    colons: usize,
+    /// The conversion character, or `None` at the end of the format.
+    conv: Option<char>,
+}
+
+/// Parse the conversion starting at the '%' at `start`, returning it and the
+/// index just past its last character.
+fn parse_conversion(chars: &[char], start: usize) -> (Conversion, usize) {
+    let mut spec = Conversion::default();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '-' | '_' | '0' | '+' => spec.pad = Some(chars[i]),
+            '^' => spec.upcase = true,
+            '#' => spec.swapcase = true,
+            _ => break,
+        }
+        i += 1;
+    }
+    let digits_start = i;
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i > digits_start {
+        let digits: String = chars[digits_start..i].iter().collect();
+        spec.width = Some(digits.parse().unwrap_or(usize::MAX));
+    }
+    if i < chars.len() && (chars[i] == 'E' || chars[i] == 'O') {
+        spec.alt = Some(chars[i]);
+        i += 1;
+    }
+    while i < chars.len() && chars[i] == ':' {
+        spec.colons += 1;
+        i += 1;
+    }
+    if i < chars.len() {
+        spec.conv = Some(chars[i]);
+        i += 1;
+    }
+    (spec, i)
+}
+
+/// Pad a numeric conversion to its field width.
+///
+/// Without an explicit width the natural width of `raw` is kept. For the
+/// year conversions, `year_digits` is their usual digit count (2 for `%C`,
+/// 4 for `%Y`); with '+', a value or width beyond that gets a leading sign.
+fn pad_number(
+    raw: &str,
+    spec: &Conversion,
+    default_pad: char,
+    year_digits: Option<usize>,
+) -> String {
+    let digits = raw.trim_start_matches([' ', '0']);
+    let digits = if digits.is_empty() { "0" } else { digits };
+    let natural = raw.len();
+    let pad = match spec.pad {
+        Some('-') => return digits.to_string(),
+        Some('_') => ' ',
+        Some('0') | Some('+') => '0',
+        _ => default_pad,
+    };
+    let width = spec.width.unwrap_or(natural);
+    let sign = spec.pad == Some('+')
+        && year_digits.is_some_and(|n| digits.len() > n || spec.width.is_some_and(|w| w > n));
+    let body_width = width - usize::from(sign);
+    let mut out = String::with_capacity(width.max(digits.len()));
+    if sign {
+        out.push('+');
+    }
+    for _ in digits.len()..body_width {
+        out.push(pad);
+    }
+    out.push_str(digits);
+    out
+}
+
+/// Pad a textual conversion on the left to its field width.
+fn pad_text(text: &str, spec: &Conversion) -> String {
+    let pad = match spec.pad {
+        Some('-') => return text.to_string(),
+        Some('0') | Some('+') => '0',
+        _ => ' ',
+    };
+    let len = text.chars().count();
+    let width = spec.width.unwrap_or(0);
+    let mut out = String::with_capacity(width.max(text.len()));
+    for _ in len..width {
+        out.push(pad);
+    }
+    out.push_str(text);
+    out
+}
+
+/// Format nanoseconds for `%N`.
+///
+/// The width (default 9) selects how many leading digits to print, and a
+/// width above 9 appends padding. With '-' or '_' trailing zeros are dropped
+/// or turned into spaces; `%-N` on its own prints all 9 digits, like GNU date.
+fn format_nanos(nanos: u32, spec: &Conversion) -> String {
+    let (width, pad) = match (spec.width, spec.pad) {
+        (None, Some('-')) => (9, '0'),
+        (width, Some('-')) => (width.unwrap_or(9), '-'),
+        (width, Some('_')) => (width.unwrap_or(9), ' '),
+        (width, _) => (width.unwrap_or(9), '0'),
+    };
+    let mut n = nanos;
+    let mut ndigs = 9;
+    while width < ndigs || (ndigs > 1 && n.is_multiple_of(10)) {
+        ndigs -= 1;
+        n /= 10;
+    }
+    let mut out = format!("{:0ndigs$}", n, ndigs = ndigs);
+    if pad != '-' {
+        for _ in ndigs..width {
+            out.push(pad);
+        }
+    }
+    out
+}
+
+/// Format a UTC offset in seconds for `%z` (`+hhmm`), `%:z` (`+hh:mm`),
+/// `%::z` (`+hh:mm:ss`) or `%:::z` (only as precise as needed, e.g. `+05`).
+fn format_tz_offset(offset: i64, colons: usize, spec: &Conversion) -> String {
+    let abs = offset.unsigned_abs();
+    let (hh, mm, ss) = (abs / 3600, abs / 60 % 60, abs % 60);
+    let colons = match colons {
+        3 if ss != 0 => 2,
+        3 if mm != 0 => 1,
+        c => c,
+    };
+    // Natural width (including the sign), a mask of the digit positions
+    // (from the right) preceded by a colon, and the value to print.
+    let (natural, mut colon_mask, mut value) = match colons {
+        0 => (5, 0u32, hh * 100 + mm),
+        1 => (6, 0o4, hh * 100 + mm),
+        2 => (9, 0o24, hh * 10000 + mm * 100 + ss),
+        _ => (3, 0, hh),
+    };
+    let mut body = Vec::new();
+    loop {
+        if colon_mask & 1 != 0 {
+            body.push(':');
+        }
+        colon_mask >>= 1;
+        body.push(char::from(b'0' + (value % 10) as u8));
+        value /= 10;
+        if value == 0 && colon_mask == 0 {
+            break;
+        }
+    }
+    let body: String = body.iter().rev().collect();
+    let sign = if offset < 0 { '-' } else { '+' };
+
+    let width = spec.width.unwrap_or(natural);
+    let fill = width.saturating_sub(body.len() + 1);
+    match spec.pad {
+        Some('-') => format!("{}{}", sign, body),
+        Some('_') => format!("{}{}{}", " ".repeat(fill), sign, body),
+        _ => format!("{}{}{}", sign, "0".repeat(fill), body),
+    }
+}
+
 /// Call libc strftime for a single format specifier.
 fn strftime_single(tm: &libc::tm, fmt: &str) -> String {
     let c_fmt = match std::ffi::CString::new(fmt) {
@@ -189,53 +387,6 @@ fn strftime_single(tm: &libc::tm, fmt: &str) -> String {
     String::from_utf8_lossy(&buf).into_owned()
 }
 
-/// Apply a GNU format modifier to a strftime result.
-/// '-' removes leading zeros/spaces (no padding).
-/// '_' replaces leading zeros with spaces.
-/// '0' replaces leading spaces with zeros.
-fn apply_format_modifier(formatted: &str, modifier: char) -> String {
-    match modifier {
-        '-' => {
-            // Remove leading zeros and spaces (no padding)
-            let trimmed = formatted.trim_start_matches(['0', ' ']);
-            if trimmed.is_empty() {
-                "0".to_string()
-            } else {
-                trimmed.to_string()
-            }
-        }
-        '_' => {
-            // Replace leading zeros with spaces
-            let mut result = String::with_capacity(formatted.len());
-            let mut leading = true;
-            for ch in formatted.chars() {
-                if leading && ch == '0' {
-                    result.push(' ');
-                } else {
-                    leading = false;
-                    result.push(ch);
-                }
-            }
-            result
-        }
-        '0' => {
-            // Replace leading spaces with zeros
-            let mut result = String::with_capacity(formatted.len());
-            let mut leading = true;
-            for ch in formatted.chars() {
-                if leading && ch == ' ' {
-                    result.push('0');
-                } else {
-                    leading = false;
-                    result.push(ch);
-                }
-            }
-            result
-        }
-        _ => formatted.to_string(),
-    }
-}
-
 /// Format a SystemTime in ISO 8601 format.
 pub fn format_iso(time: &SystemTime, precision: &IsoFormat, utc: bool) -> String {
     match precision {
@@ -255,13 +406,7 @@ pub fn format_iso(time: &SystemTime, precision: &IsoFormat, utc: bool) -> String
             let tz = format_timezone_colon(time, utc);
             format!("{}{}", date_part, tz)
         }
-        IsoFormat::Ns => {
-            let dur = time.duration_since(UNIX_EPOCH).unwrap_or_default();
-            let nanos = dur.subsec_nanos();
-            let date_part = format_date(time, "%Y-%m-%dT%H:%M:%S", utc);
-            let tz = format_timezone_colon(time, utc);
-            format!("{},{:09}{}", date_part, nanos, tz)
-        }
+        IsoFormat::Ns => format_date(time, "%Y-%m-%dT%H:%M:%S,%N%:z", utc),
     }
 }
 
@@ -279,28 +424,13 @@ pub fn format_rfc3339(time: &SystemTime, precision: &Rfc3339Format, utc: bool) -
             let tz = format_timezone_colon(time, utc);
             format!("{}{}", date_part, tz)
         }
-        Rfc3339Format::Ns => {
-            let dur = time.duration_since(UNIX_EPOCH).unwrap_or_default();
-            let nanos = dur.subsec_nanos();
-            let date_part = format_date(time, "%Y-%m-%d %H:%M:%S", utc);
-            let tz = format_timezone_colon(time, utc);
-            format!("{}.{:09}{}", date_part, nanos, tz)
-        }
+        Rfc3339Format::Ns => format_date(time, "%Y-%m-%d %H:%M:%S.%N%:z", utc),
     }
 }
 
 /// Format a timezone offset with a colon (e.g., +05:30).
 fn format_timezone_colon(time: &SystemTime, utc: bool) -> String {
-    if utc {
-        return "+00:00".to_string();
-    }
-    let raw = format_date(time, "%z", false);
-    // raw is like "+0530" or "-0800"
-    if raw.len() >= 5 {
-        format!("{}:{}", &raw[..3], &raw[3..5])
-    } else {
-        raw
-    }
+    format_date(time, "%:z", utc)
 }
 
 /// Parse a date string into a SystemTime.
@@ -315,14 +445,24 @@ pub fn parse_date_string(s: &str, utc: bool) -> Result<SystemTime, String> {
 
     // Handle epoch format: @SECONDS
     if let Some(epoch_str) = s.strip_prefix('@') {
-        let secs: i64 = epoch_str
-            .trim()
-            .parse()
-            .map_err(|_| format!("invalid date '@{}'", epoch_str))?;
-        if secs >= 0 {
-            return Ok(UNIX_EPOCH + Duration::from_secs(secs as u64));
+        // Seconds may carry a fraction, kept to nanosecond resolution.
+        let invalid = || format!("invalid date '@{}'", epoch_str);
+        let trimmed = epoch_str.trim();
+        let (int_part, frac) = trimmed.split_once(['.', ',']).unwrap_or((trimmed, ""));
+        let secs: i64 = int_part.parse().map_err(|_| invalid())?;
+        if !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let nanos = frac
+            .bytes()
+            .chain(std::iter::repeat(b'0'))
+            .take(9)
+            .fold(0u32, |n, b| n * 10 + u32::from(b - b'0'));
+        let offset = Duration::new(secs.unsigned_abs(), nanos);
+        if int_part.starts_with('-') {
+            return Ok(UNIX_EPOCH - offset);
         } else {
-            return Ok(UNIX_EPOCH - Duration::from_secs((-secs) as u64));
+            return Ok(UNIX_EPOCH + offset);
         }
     }
 
@@ -396,27 +536,8 @@ fn try_parse_time_only(s: &str, utc: bool) -> Option<SystemTime> {
     let mut use_utc = utc;
     let mut tz_offset_secs: i64 = 0;
     if let Some(tz) = tz_str {
-        if tz.eq_ignore_ascii_case("UTC") || tz == "Z" {
-            use_utc = true;
-        } else if (tz.starts_with('+') || tz.starts_with('-')) && (tz.len() == 5 || tz.len() == 3) {
-            let sign: i64 = if tz.starts_with('-') { -1 } else { 1 };
-            let digits = &tz[1..];
-            if !digits.chars().all(|c| c.is_ascii_digit()) {
-                return None;
-            }
-            let (oh, om) = if digits.len() == 4 {
-                let h: i64 = digits[..2].parse().ok()?;
-                let m: i64 = digits[2..].parse().ok()?;
-                (h, m)
-            } else {
-                let h: i64 = digits.parse().ok()?;
-                (h, 0)
-            };
-            tz_offset_secs = sign * (oh * 3600 + om * 60);
-            use_utc = true;
-        } else {
-            return None;
-        }
+        tz_offset_secs = parse_tz_offset(tz)?;
+        use_utc = true;
     }
 
     // Get today's date
@@ -460,6 +581,32 @@ fn try_parse_time_only(s: &str, utc: bool) -> Option<SystemTime> {
     }
 }
 
+/// Parse an explicit time zone suffix ("Z", "UTC", "+HH", "+HHMM", "+HH:MM")
+/// into seconds east of UTC.
+fn parse_tz_offset(tz: &str) -> Option<i64> {
+    if tz.eq_ignore_ascii_case("UTC") || tz.eq_ignore_ascii_case("GMT") || tz == "Z" || tz == "z" {
+        return Some(0);
+    }
+    let sign: i64 = match tz.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = tz[1..].replacen(':', "", 1);
+    if !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (h, m): (i64, i64) = match digits.len() {
+        2 => (digits.parse().ok()?, 0),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        _ => return None,
+    };
+    if tz.contains(':') && digits.len() != 4 {
+        return None;
+    }
+    Some(sign * (h * 3600 + m * 60))
+}
+
 /// Try to parse a relative time expression.
 fn try_parse_relative(s: &str, now: &SystemTime) -> Option<SystemTime> {
     let lower = s.to_lowercase();
@@ -501,14 +648,7 @@ fn try_parse_relative(s: &str, now: &SystemTime) -> Option<SystemTime> {
 /// Try to parse an ISO-like date string.
 fn try_parse_iso(s: &str, utc: bool) -> Option<SystemTime> {
     // Split on space or T
-    let s = s.replace('T', " ");
-    let parts: Vec<&str> = s.splitn(2, ' ').collect();
-    let date_part = parts[0];
-    let time_part = if parts.len() > 1 {
-        parts[1]
-    } else {
-        "00:00:00"
-    };
+    let (date_part, time_part) = s.split_once([' ', 'T']).unwrap_or((s, "00:00:00"));
 
     let date_fields: Vec<&str> = date_part.split('-').collect();
     if date_fields.len() != 3 {
@@ -523,14 +663,18 @@ fn try_parse_iso(s: &str, utc: bool) -> Option<SystemTime> {
         return None;
     }
 
-    // Parse time (strip timezone info for simplicity)
-    let time_clean = time_part
-        .split('+')
-        .next()
-        .unwrap_or(time_part)
-        .split('Z')
-        .next()
-        .unwrap_or(time_part);
+    // Split off an explicit zone, which takes precedence over -u/local time
+    let time_part = time_part.trim();
+    let time_len = time_part
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or(time_part.len());
+    let (time_clean, zone) = time_part.split_at(time_len);
+    let zone = zone.trim();
+    let tz_offset = if zone.is_empty() {
+        None
+    } else {
+        Some(parse_tz_offset(zone)?)
+    };
     let time_fields: Vec<&str> = time_clean.split(':').collect();
     let hour: u32 = time_fields
         .first()
@@ -557,7 +701,7 @@ fn try_parse_iso(s: &str, utc: bool) -> Option<SystemTime> {
     tm.tm_sec = second as i32;
     tm.tm_isdst = -1; // Let mktime determine DST
 
-    let epoch_secs = if utc {
+    let epoch_secs = if utc || tz_offset.is_some() {
         unsafe { libc::timegm(&mut tm) }
     } else {
         unsafe { libc::mktime(&mut tm) }
@@ -565,6 +709,7 @@ fn try_parse_iso(s: &str, utc: bool) -> Option<SystemTime> {
     if epoch_secs == -1 {
         return None;
     }
+    let epoch_secs = epoch_secs as i64 - tz_offset.unwrap_or(0);
 
     if epoch_secs >= 0 {
         Some(UNIX_EPOCH + Duration::from_secs(epoch_secs as u64))
@@ -573,6 +718,33 @@ fn try_parse_iso(s: &str, utc: bool) -> Option<SystemTime> {
     }
 }
 
+/// Set the system clock (CLOCK_REALTIME) to `time`. Requires CAP_SYS_TIME.
+pub fn set_system_time(time: &SystemTime) -> std::io::Result<()> {
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos() as i64),
+        Err(e) => {
+            let d = e.duration();
+            if d.subsec_nanos() == 0 {
+                (-(d.as_secs() as i64), 0)
+            } else {
+                (
+                    -(d.as_secs() as i64) - 1,
+                    1_000_000_000 - d.subsec_nanos() as i64,
+                )
+            }
+        }
+    };
+    let ts = libc::timespec {
+        tv_sec: secs as libc::time_t,
+        tv_nsec: nanos as _,
+    };
+    if unsafe { libc::clock_settime(libc::CLOCK_REALTIME, &ts) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
 /// Get the modification time of a file.
 pub fn file_mod_time(path: &str) -> Result<SystemTime, String> {
     std::fs::metadata(path)
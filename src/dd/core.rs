@@ -1,7 +1,11 @@
 use std::fs::{File, OpenOptions};
 use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
+/// Set by the SIGUSR1 handler; the copy loops print statistics when they see it.
+static INFO_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 /// Status output level for dd.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum StatusLevel {
@@ -76,6 +80,9 @@ pub struct DdConfig {
     pub ibs: usize,
     /// Output block size in bytes.
     pub obs: usize,
+    /// Block size given with `bs=`: each input block is then written as its
+    /// own output block instead of being aggregated into `obs`-sized writes.
+    pub bs: Option<usize>,
     /// Conversion block size (for block/unblock).
     pub cbs: usize,
     /// Copy only this many input blocks (None = unlimited).
@@ -101,6 +108,7 @@ impl Default for DdConfig {
             output: None,
             ibs: 512,
             obs: 512,
+            bs: None,
             cbs: 0,
             count: None,
             skip: 0,
@@ -126,6 +134,8 @@ pub struct DdStats {
     pub records_out_partial: u64,
     /// Total bytes copied.
     pub bytes_copied: u64,
+    /// Number of records conv=block cut short to fit `cbs`.
+    pub truncated_records: u64,
 }
 
 /// Parse a GNU dd SIZE string with optional suffix and `x` multiplier.
@@ -220,6 +230,7 @@ pub fn parse_dd_args(args: &[String]) -> Result<DdConfig, String> {
                     let size = parse_size(value)? as usize;
                     config.ibs = size;
                     config.obs = size;
+                    config.bs = Some(size);
                     bs_set = true;
                 }
                 "ibs" => {
@@ -284,7 +295,10 @@ pub fn parse_dd_args(args: &[String]) -> Result<DdConfig, String> {
 
     // Validate conflicting options
     if config.conv.lcase && config.conv.ucase {
-        return Err("conv=lcase and conv=ucase are mutually exclusive".to_string());
+        return Err("cannot combine lcase and ucase".to_string());
+    }
+    if config.conv.block && config.conv.unblock {
+        return Err("cannot combine block and unblock".to_string());
     }
     if config.conv.excl && config.conv.nocreat {
         return Err("conv=excl and conv=nocreat are mutually exclusive".to_string());
@@ -315,6 +329,103 @@ fn parse_flag(flag: &str, flags: &mut DdFlags) -> Result<(), String> {
     Ok(())
 }
 
+#[cfg(unix)]
+extern "C" fn request_info(_sig: libc::c_int) {
+    INFO_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Make SIGUSR1 request a statistics report instead of killing dd, as in
+/// GNU dd. The handler only sets a flag; the copy loops do the printing.
+/// A SIGUSR1 that was ignored when dd started stays ignored.
+pub fn install_signal_handlers() {
+    #[cfg(unix)]
+    unsafe {
+        let mut old: libc::sigaction = std::mem::zeroed();
+        if libc::sigaction(libc::SIGUSR1, std::ptr::null(), &mut old) == 0
+            && old.sa_sigaction == libc::SIG_IGN
+        {
+            return;
+        }
+        let mut act: libc::sigaction = std::mem::zeroed();
+        act.sa_sigaction = request_info as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        // No SA_RESTART: a read blocked on an idle pipe must return EINTR so
+        // the report is printed right away rather than after the next block.
+        act.sa_flags = 0;
+        libc::sigemptyset(&mut act.sa_mask);
+        libc::sigaction(libc::SIGUSR1, &act, std::ptr::null_mut());
+    }
+}
+
+/// Print the statistics so far if SIGUSR1 arrived since the last check.
+fn report_if_requested(stats: &DdStats, start_time: Instant, status: StatusLevel) {
+    if INFO_REQUESTED.swap(false, Ordering::Relaxed) && status != StatusLevel::None {
+        print_stats(stats, start_time.elapsed(), status);
+    }
+}
+
+/// Read one input block. Without iflag=fullblock this is a single read, so
+/// a short read from a pipe yields a partial block like GNU dd; with it,
+/// partial reads are retried until the block is full or EOF. As in GNU
+/// dd, `report` runs before each read (so a SIGUSR1 that is already
+/// pending is reported even if the read then blocks) and a read
+/// interrupted by a signal is retried.
+fn read_block(
+    reader: &mut dyn Read,
+    buf: &mut [u8],
+    fullblock: bool,
+    report: &dyn Fn(),
+) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        report();
+        match reader.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => {
+                total += n;
+                if !fullblock {
+                    break;
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
+}
+
+/// Whether to warn (once) about a short read, as GNU dd does when blocks are
+/// copied one read at a time (`two_bufs` false) and a short read would throw
+/// off count= or skip=. iflag=fullblock avoids both the miscount and the warning.
+fn warns_partial_read(config: &DdConfig, two_bufs: bool) -> bool {
+    let counted = match config.count {
+        Some(count) if config.iflag.count_bytes => count / config.ibs as u64 > 0,
+        Some(count) => count > 0,
+        None => false,
+    };
+    !two_bufs
+        && !config.iflag.fullblock
+        && config.status != StatusLevel::None
+        && (config.skip > 0 || counted || config.iflag.direct || config.oflag.direct)
+}
+
+/// GNU only warns once a short read is followed by another successful one
+/// (so a short read at EOF is fine), and reports the short read's size.
+/// `prev` is the previous read's size, updated here to `n`.
+fn check_partial_read(warn: &mut bool, prev: &mut usize, n: usize, size: usize) {
+    if !*warn || n == 0 {
+        return;
+    }
+    if *prev > 0 && *prev < size {
+        eprintln!(
+            "dd: warning: partial read ({} byte{}); suggest iflag=fullblock",
+            *prev,
+            if *prev == 1 { "" } else { "s" }
+        );
+        *warn = false;
+    }
+    *prev = n;
+}
+
 /// Read a full block from the reader, retrying on partial reads.
 /// Returns the number of bytes actually read (0 means EOF).
 fn read_full_block(reader: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
@@ -330,16 +441,9 @@ fn read_full_block(reader: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
     Ok(total)
 }
 
-/// Apply conversion options to a data block in-place.
+/// Apply the case conversions to a data block in-place. conv=swab pairs
+/// bytes across reads, so it is done separately by `swab_bytes`.
 pub fn apply_conversions(data: &mut [u8], conv: &DdConv) {
-    if conv.swab {
-        // Swap every pair of bytes
-        let pairs = data.len() / 2;
-        for i in 0..pairs {
-            data.swap(i * 2, i * 2 + 1);
-        }
-    }
-
     if conv.lcase {
         for b in data.iter_mut() {
             b.make_ascii_lowercase();
@@ -351,6 +455,117 @@ pub fn apply_conversions(data: &mut [u8], conv: &DdConv) {
     }
 }
 
+/// conv=swab: append `data` to `out` with every pair of bytes swapped. Pairs
+/// run across reads, so an odd byte left at the end is kept in `saved` for
+/// the next call, like GNU dd's saved byte.
+fn swab_bytes(data: &[u8], saved: &mut Option<u8>, out: &mut Vec<u8>) {
+    let mut pending = saved.take();
+    for &b in data {
+        match pending.take() {
+            Some(first) => out.extend_from_slice(&[b, first]),
+            None => pending = Some(b),
+        }
+    }
+    *saved = pending;
+}
+
+/// Position within the current record, carried across input blocks by
+/// conv=block and conv=unblock.
+#[derive(Default)]
+struct RecordState {
+    /// Bytes of the current record seen so far.
+    col: usize,
+    /// Spaces held back by unblock until a non-space shows they are not trailing.
+    pending_spaces: usize,
+}
+
+/// conv=block: pad each newline-terminated record with spaces to `cbs` bytes.
+/// Bytes past `cbs` are dropped, and each shortened record is counted once.
+fn copy_with_block(
+    data: &[u8],
+    cbs: usize,
+    state: &mut RecordState,
+    out: &mut Vec<u8>,
+    stats: &mut DdStats,
+) {
+    for &b in data {
+        if b == b'\n' {
+            if state.col < cbs {
+                out.resize(out.len() + cbs - state.col, b' ');
+            }
+            state.col = 0;
+        } else {
+            if state.col == cbs {
+                stats.truncated_records += 1;
+            } else if state.col < cbs {
+                out.push(b);
+            }
+            state.col += 1;
+        }
+    }
+}
+
+/// conv=unblock: split the input into `cbs`-byte records, dropping trailing
+/// spaces and ending each with a newline.  The newline is only written once
+/// the next record starts (or at EOF), as GNU dd does.
+fn copy_with_unblock(data: &[u8], cbs: usize, state: &mut RecordState, out: &mut Vec<u8>) {
+    for &b in data {
+        if state.col == cbs {
+            state.col = 0;
+            state.pending_spaces = 0;
+            out.push(b'\n');
+        }
+        state.col += 1;
+        if b == b' ' {
+            state.pending_spaces += 1;
+        } else {
+            out.resize(out.len() + state.pending_spaces, b' ');
+            state.pending_spaces = 0;
+            out.push(b);
+        }
+    }
+}
+
+/// Complete the final record at EOF: block pads a record that lacked its
+/// newline, unblock terminates whatever record was in progress.
+fn finish_records(conv: &DdConv, cbs: usize, state: &RecordState, out: &mut Vec<u8>) {
+    if state.col == 0 {
+        return;
+    }
+    if conv.block {
+        if state.col < cbs {
+            out.resize(out.len() + cbs - state.col, b' ');
+        }
+    } else {
+        out.push(b'\n');
+    }
+}
+
+/// Write every complete `obs`-sized block buffered in `obuf`, keeping the rest.
+fn drain_full_blocks(
+    output: &mut dyn Write,
+    obuf: &mut Vec<u8>,
+    obs: usize,
+    stats: &mut DdStats,
+) -> io::Result<()> {
+    let mut consumed = 0;
+    while obuf.len() - consumed >= obs {
+        output.write_all(&obuf[consumed..consumed + obs])?;
+        stats.records_out_full += 1;
+        stats.bytes_copied += obs as u64;
+        consumed += obs;
+    }
+    if consumed > 0 {
+        // Shift remaining bytes to front (more efficient than drain for large buffers)
+        let remaining = obuf.len() - consumed;
+        if remaining > 0 {
+            obuf.copy_within(consumed.., 0);
+        }
+        obuf.truncate(remaining);
+    }
+    Ok(())
+}
+
 /// Skip input blocks by reading and discarding them.
 fn skip_input(reader: &mut dyn Read, blocks: u64, block_size: usize) -> io::Result<()> {
     let mut discard_buf = vec![0u8; block_size];
@@ -411,7 +626,7 @@ fn has_conversions(conv: &DdConv) -> bool {
 
 /// Check if any iflag/oflag fields require the generic path.
 /// Note: noatime is excluded because the raw path already uses O_NOATIME.
-/// fullblock is excluded because the raw read loop already reads full blocks.
+/// fullblock is excluded because the raw read loop honours it.
 #[cfg(target_os = "linux")]
 fn has_flags(flags: &DdFlags) -> bool {
     flags.append
@@ -431,6 +646,10 @@ fn has_flags(flags: &DdFlags) -> bool {
 /// ibs == obs, no conversions, and no iflag/oflag are set, bypass
 /// Box<dyn Read/Write> and use libc::read/write directly. Handles
 /// char devices (e.g. /dev/zero) that copy_file_range can't handle.
+///
+/// Each read is written straight back out, which is only right for `bs=`
+/// or for regular files (where reads are never short); other inputs need
+/// the generic path to aggregate partial reads into `obs`-sized writes.
 #[cfg(target_os = "linux")]
 fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
     if config.input.is_none() || config.output.is_none() {
@@ -443,6 +662,11 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
     if has_flags(&config.iflag) || has_flags(&config.oflag) {
         return None;
     }
+    if config.bs.is_none()
+        && !std::fs::metadata(config.input.as_ref().unwrap()).is_ok_and(|m| m.is_file())
+    {
+        return None;
+    }
 
     let start_time = Instant::now();
     let in_path = config.input.as_ref().unwrap();
@@ -585,6 +809,8 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
     let bs = config.ibs;
     let mut ibuf = vec![0u8; bs];
     let count_limit = config.count;
+    let mut warn_partial = warns_partial_read(config, config.bs.is_none());
+    let mut prev_read = 0usize;
 
     loop {
         if let Some(limit) = count_limit {
@@ -606,11 +832,15 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
             };
             if ret > 0 {
                 total_read += ret as usize;
+                if !config.iflag.fullblock {
+                    break; // one read per block, like GNU dd
+                }
             } else if ret == 0 {
                 break; // EOF
             } else {
                 let err = io::Error::last_os_error();
                 if err.kind() == io::ErrorKind::Interrupted {
+                    report_if_requested(&stats, start_time, config.status);
                     continue;
                 }
                 if config.conv.noerror {
@@ -626,6 +856,8 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
             }
         }
 
+        report_if_requested(&stats, start_time, config.status);
+
         // conv=noerror: skip entire bad block (GNU behavior)
         if read_error {
             stats.records_in_partial += 1;
@@ -641,6 +873,7 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
         } else {
             stats.records_in_partial += 1;
         }
+        check_partial_read(&mut warn_partial, &mut prev_read, total_read, bs);
 
         // Raw write — retry on EINTR, treat write(0) as error
         let mut written = 0usize;
@@ -667,6 +900,7 @@ fn try_raw_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
             } else {
                 let err = io::Error::last_os_error();
                 if err.kind() == io::ErrorKind::Interrupted {
+                    report_if_requested(&stats, start_time, config.status);
                     continue;
                 }
                 unsafe {
@@ -774,6 +1008,7 @@ fn try_copy_file_range_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
 
     let mut bytes_remaining = total_to_copy;
     loop {
+        report_if_requested(&stats, start_time, config.status);
         let chunk = match bytes_remaining {
             Some(0) => break,
             Some(r) => r.min(block_size as u64 * 1024) as usize, // copy in large chunks
@@ -798,6 +1033,9 @@ fn try_copy_file_range_dd(config: &DdConfig) -> Option<io::Result<DdStats>> {
 
         if ret < 0 {
             let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
             if err.raw_os_error() == Some(libc::EINVAL)
                 || err.raw_os_error() == Some(libc::ENOSYS)
                 || err.raw_os_error() == Some(libc::EXDEV)
@@ -953,39 +1191,53 @@ pub fn dd_copy(config: &DdConfig) -> io::Result<DdStats> {
     let mut stats = DdStats::default();
     let mut ibuf = vec![0u8; config.ibs];
     let mut obuf: Vec<u8> = Vec::with_capacity(config.obs);
-    let mut unblock_buf: Vec<u8> = Vec::new();
-    // For count_bytes mode, track total bytes read
-    let mut bytes_read_total: u64 = 0;
+    // block/unblock only take effect with a conversion block size; they change
+    // record lengths, so they always aggregate output
+    let records = config.cbs > 0 && (config.conv.block || config.conv.unblock);
+    // Like GNU, case conversion and swab also aggregate reads into obs blocks.
+    let two_bufs = config.bs.is_none()
+        || records
+        || config.conv.lcase
+        || config.conv.ucase
+        || config.conv.swab;
+    let direct_output = !two_bufs;
+    let mut warn_partial = warns_partial_read(config, two_bufs);
+    let mut prev_read = 0usize;
+    let mut record_state = RecordState::default();
+    let mut conv_buf: Vec<u8> = Vec::new();
+    let mut swab_buf: Vec<u8> = Vec::new();
+    let mut swab_saved: Option<u8> = None;
+    // As in GNU dd, count_bytes becomes count/ibs whole reads plus one read
+    // of the remainder, so without fullblock a short read still uses one up.
+    let (max_records, max_bytes) = match config.count {
+        Some(count) if config.iflag.count_bytes => (
+            count / config.ibs as u64,
+            (count % config.ibs as u64) as usize,
+        ),
+        Some(count) => (count, 0),
+        None => (u64::MAX, 0),
+    };
 
     loop {
-        // Check count limit
-        if let Some(count) = config.count {
-            if config.iflag.count_bytes {
-                if bytes_read_total >= count {
-                    break;
-                }
-            } else if stats.records_in_full + stats.records_in_partial >= count {
-                break;
-            }
+        let records_read = stats.records_in_full + stats.records_in_partial;
+        if records_read >= max_records.saturating_add((max_bytes > 0) as u64) {
+            break;
         }
-
-        // When count_bytes is active, limit the read to the remaining bytes
-        let read_size = if config.iflag.count_bytes {
-            if let Some(count) = config.count {
-                let remaining = count.saturating_sub(bytes_read_total);
-                std::cmp::min(config.ibs, remaining as usize)
-            } else {
-                config.ibs
-            }
+        let read_size = if records_read >= max_records {
+            max_bytes
         } else {
             config.ibs
         };
-        if read_size == 0 {
-            break;
-        }
 
         // Read one input block
-        let n = match read_full_block(&mut input, &mut ibuf[..read_size]) {
+        let n = read_block(
+            &mut input,
+            &mut ibuf[..read_size],
+            config.iflag.fullblock,
+            &|| report_if_requested(&stats, start_time, config.status),
+        );
+        report_if_requested(&stats, start_time, config.status);
+        let n = match n {
             Ok(n) => n,
             Err(e) => {
                 if config.conv.noerror {
@@ -1008,8 +1260,7 @@ pub fn dd_copy(config: &DdConfig) -> io::Result<DdStats> {
         if n == 0 {
             break;
         }
-
-        bytes_read_total += n as u64;
+        check_partial_read(&mut warn_partial, &mut prev_read, n, read_size);
 
         // Track full vs partial blocks
         if n == config.ibs {
@@ -1018,11 +1269,7 @@ pub fn dd_copy(config: &DdConfig) -> io::Result<DdStats> {
             stats.records_in_partial += 1;
             // Pad if conv=sync: spaces for block/unblock, NULs otherwise
             if config.conv.sync {
-                let pad_byte = if config.conv.block || config.conv.unblock {
-                    b' '
-                } else {
-                    0u8
-                };
+                let pad_byte = if records { b' ' } else { 0u8 };
                 ibuf[n..config.ibs].fill(pad_byte);
             }
         }
@@ -1030,37 +1277,38 @@ pub fn dd_copy(config: &DdConfig) -> io::Result<DdStats> {
         // Determine the data slice to use and apply conversions in-place
         let effective_len = if config.conv.sync { config.ibs } else { n };
         apply_conversions(&mut ibuf[..effective_len], &config.conv);
+        let data: &[u8] = if config.conv.swab {
+            swab_buf.clear();
+            swab_bytes(&ibuf[..effective_len], &mut swab_saved, &mut swab_buf);
+            &swab_buf
+        } else {
+            &ibuf[..effective_len]
+        };
 
-        // Apply unblock conversion: split fixed-length records into
-        // newline-terminated records with trailing spaces stripped
-        let write_data: &[u8] = if config.conv.unblock && config.cbs > 0 {
-            unblock_buf.clear();
-            let data = &ibuf[..effective_len];
-            let mut pos = 0;
-            while pos < data.len() {
-                let end = std::cmp::min(pos + config.cbs, data.len());
-                let record = &data[pos..end];
-                // Strip trailing spaces
-                let trimmed_len = record
-                    .iter()
-                    .rposition(|&b| b != b' ')
-                    .map(|p| p + 1)
-                    .unwrap_or(0);
-                unblock_buf.extend_from_slice(&record[..trimmed_len]);
-                unblock_buf.push(b'\n');
-                pos = end;
+        // Apply block/unblock: convert between newline-terminated and
+        // fixed-length cbs-sized records
+        let write_data: &[u8] = if records {
+            conv_buf.clear();
+            if config.conv.block {
+                copy_with_block(
+                    data,
+                    config.cbs,
+                    &mut record_state,
+                    &mut conv_buf,
+                    &mut stats,
+                );
+            } else {
+                copy_with_unblock(data, config.cbs, &mut record_state, &mut conv_buf);
             }
-            &unblock_buf
+            &conv_buf
         } else {
-            &ibuf[..effective_len]
+            data
         };
 
-        // Buffer output and flush when we have enough for a full output block.
-        // Use efficient buffer management: write directly from ibuf when possible,
-        // only buffer when ibs != obs.
+        // With bs= each input block is written as-is (one write per read);
+        // otherwise buffer output and flush whenever a full obs block is ready.
         let wd_len = write_data.len();
-        if config.ibs == config.obs && obuf.is_empty() && !config.conv.unblock {
-            // Fast path: ibs == obs, write directly
+        if direct_output {
             output.write_all(write_data)?;
             if wd_len == config.obs {
                 stats.records_out_full += 1;
@@ -1073,21 +1321,24 @@ pub fn dd_copy(config: &DdConfig) -> io::Result<DdStats> {
         }
 
         obuf.extend_from_slice(write_data);
-        let mut consumed = 0;
-        while obuf.len() - consumed >= config.obs {
-            output.write_all(&obuf[consumed..consumed + config.obs])?;
-            stats.records_out_full += 1;
-            stats.bytes_copied += config.obs as u64;
-            consumed += config.obs;
-        }
-        if consumed > 0 {
-            // Shift remaining bytes to front (more efficient than drain for large buffers)
-            let remaining = obuf.len() - consumed;
-            if remaining > 0 {
-                obuf.copy_within(consumed.., 0);
-            }
-            obuf.truncate(remaining);
+        drain_full_blocks(&mut output, &mut obuf, config.obs, &mut stats)?;
+    }
+
+    // Like GNU, an odd byte left over by conv=swab is output unswapped.
+    if let Some(b) = swab_saved {
+        if !records {
+            obuf.push(b);
+        } else if config.conv.block {
+            copy_with_block(&[b], config.cbs, &mut record_state, &mut obuf, &mut stats);
+        } else {
+            copy_with_unblock(&[b], config.cbs, &mut record_state, &mut obuf);
         }
+        drain_full_blocks(&mut output, &mut obuf, config.obs, &mut stats)?;
+    }
+
+    if records {
+        finish_records(&config.conv, config.cbs, &record_state, &mut obuf);
+        drain_full_blocks(&mut output, &mut obuf, config.obs, &mut stats)?;
     }
 
     // Flush remaining partial output block
@@ -1129,6 +1380,17 @@ fn print_stats(stats: &DdStats, elapsed: std::time::Duration, status: StatusLeve
         "{}+{} records out",
         stats.records_out_full, stats.records_out_partial
     );
+    if stats.truncated_records > 0 {
+        eprintln!(
+            "{} truncated record{}",
+            stats.truncated_records,
+            if stats.truncated_records == 1 {
+                ""
+            } else {
+                "s"
+            }
+        );
+    }
 
     if status == StatusLevel::NoXfer {
         return;
@@ -1195,10 +1457,13 @@ Copy a file, converting and formatting according to the operands.
 
 Each CONV symbol may be:
 
+  block     pad newline-terminated records with spaces to cbs-size
+  unblock   replace trailing spaces in cbs-size records with newline
   lcase     change upper case to lower case
   ucase     change lower case to upper case
   swab      swap every pair of input bytes
-  sync      pad every input block with NULs to ibs-size
+  sync      pad every input block with NULs to ibs-size; when used
+            with block or unblock, pad with spaces rather than NULs
   noerror   continue after read errors
   notrunc   do not truncate the output file
   fdatasync physically write output file data before finishing
@@ -1222,6 +1487,9 @@ Each FLAG symbol may be:
   count_bytes  treat 'count=N' as a byte count (iflag only)
   skip_bytes   treat 'skip=N' as a byte count (iflag only)
 
+Sending a USR1 signal to a running 'dd' process makes it
+print I/O statistics to standard error and then resume copying.
+
   --help     display this help and exit
   --version  output version information and exit
 "
@@ -207,14 +207,12 @@ fn statvfs_info(mount: &MountEntry) -> Option<FsInfo> {
         }
     };
 
+    // Like GNU df, IFree is f_ffree (inodes free to root), so IUsed + IFree
+    // always equals the total.
     let itotal = stat.f_files as u64;
-    let ifree = stat.f_ffree as u64;
-    let iused = itotal.saturating_sub(ifree);
-    let iuse_percent = if itotal == 0 {
-        -1.0
-    } else {
-        (iused as f64 / itotal as f64) * 100.0
-    };
+    let ifree = (stat.f_ffree as u64).min(itotal);
+    let iused = itotal - ifree;
+    let iuse_percent = inode_percent(iused, ifree);
 
     Some(FsInfo {
         source: mount.source.clone(),
@@ -278,9 +276,29 @@ fn is_pseudo(fstype: &str) -> bool {
     PSEUDO_FS_TYPES.contains(&fstype)
 }
 
+/// Check a filesystem type against the -t (include) and -x (exclude) filters.
+fn type_selected(fstype: &str, config: &DfConfig) -> bool {
+    (config.type_filter.is_empty() || config.type_filter.contains(fstype))
+        && !config.exclude_type.contains(fstype)
+}
+
+/// Return a filesystem type given to both -t and -x, if any.
+pub fn conflicting_type(config: &DfConfig) -> Option<&str> {
+    config
+        .type_filter
+        .iter()
+        .filter(|t| config.exclude_type.contains(*t))
+        .min()
+        .map(|t| t.as_str())
+}
+
 /// Get filesystem info for all relevant mount points.
 /// Returns (filesystems, had_error) where had_error is true if any file was not found.
 pub fn get_filesystems(config: &DfConfig) -> (Vec<FsInfo>, bool) {
+    if config.sync_before {
+        unsafe { libc::sync() };
+    }
+
     let mounts = read_mounts();
     let mut had_error = false;
 
@@ -291,6 +309,9 @@ pub fn get_filesystems(config: &DfConfig) -> (Vec<FsInfo>, bool) {
         for file in &config.files {
             match find_mount_for_file(file, &mounts) {
                 Some(mount) => {
+                    if !type_selected(&mount.fstype, config) {
+                        continue;
+                    }
                     if let Some(mut info) = statvfs_info(mount) {
                         info.file = file.clone();
                         result.push(info);
@@ -308,16 +329,19 @@ pub fn get_filesystems(config: &DfConfig) -> (Vec<FsInfo>, bool) {
     let mut result = Vec::new();
     let mut seen_sources = HashSet::new();
 
-    for mount in &mounts {
-        // Filter by type.
-        if !config.type_filter.is_empty() && !config.type_filter.contains(&mount.fstype) {
-            continue;
-        }
-
-        // Exclude by type.
-        if config.exclude_type.contains(&mount.fstype) {
+    for (i, first) in mounts.iter().enumerate() {
+        // A mount point mounted more than once is listed once, in the position
+        // of its first entry but describing the topmost mount, unless --all.
+        let mount = if config.all {
+            first
+        } else if mounts[..i].iter().any(|m| m.target == first.target) {
             continue;
-        }
+        } else {
+            mounts[i..]
+                .iter()
+                .rfind(|m| m.target == first.target)
+                .unwrap_or(first)
+        };
 
         // Skip remote filesystems if --local.
         if config.local_only && is_remote(&mount.fstype) {
@@ -329,6 +353,11 @@ pub fn get_filesystems(config: &DfConfig) -> (Vec<FsInfo>, bool) {
             continue;
         }
 
+        // Filter by type (-t) and exclude by type (-x).
+        if !type_selected(&mount.fstype, config) {
+            continue;
+        }
+
         // Skip duplicate sources unless --all (keep last mount for a given device).
         if !config.all {
             if mount.source == "none" || mount.source == "tmpfs" || mount.source == "devtmpfs" {
@@ -415,6 +444,29 @@ pub fn format_size(bytes: u64, config: &DfConfig) -> String {
     }
 }
 
+/// Format an inode count: plain, or scaled like sizes under -h/-H.
+/// Inode counts are never divided by the block size.
+pub fn format_inodes(count: u64, config: &DfConfig) -> String {
+    if config.human_readable {
+        human_readable_1024(count)
+    } else if config.si {
+        human_readable_1000(count)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Compute IUse% as GNU df does: used / (used + free) * 100, rounded up,
+/// in integer arithmetic. Returns -1.0 (shown as "-") when there are no inodes.
+fn inode_percent(used: u64, free: u64) -> f64 {
+    let total = used as u128 + free as u128;
+    if total == 0 {
+        return -1.0;
+    }
+    let u100 = used as u128 * 100;
+    (u100.div_ceil(total)) as f64
+}
+
 /// Format a percentage for display.
 /// Returns "-" when pct < 0.0 (sentinel for pseudo-filesystems with 0 blocks).
 fn format_percent(pct: f64) -> String {
@@ -531,9 +583,9 @@ pub(crate) fn build_row(info: &FsInfo, config: &DfConfig) -> Vec<String> {
             .map(|f| match f.as_str() {
                 "source" => info.source.clone(),
                 "fstype" => info.fstype.clone(),
-                "itotal" => format!("{}", info.itotal),
-                "iused" => format!("{}", info.iused),
-                "iavail" => format!("{}", info.iavail),
+                "itotal" => format_inodes(info.itotal, config),
+                "iused" => format_inodes(info.iused, config),
+                "iavail" => format_inodes(info.iavail, config),
                 "ipcent" => format_percent(info.iuse_percent),
                 "size" => format_size(info.total, config),
                 "used" => format_size(info.used, config),
@@ -547,14 +599,18 @@ pub(crate) fn build_row(info: &FsInfo, config: &DfConfig) -> Vec<String> {
     }
 
     if config.inodes {
-        vec![
-            info.source.clone(),
-            format!("{}", info.itotal),
-            format!("{}", info.iused),
-            format!("{}", info.iavail),
+        let mut row = vec![info.source.clone()];
+        if config.print_type {
+            row.push(info.fstype.clone());
+        }
+        row.extend([
+            format_inodes(info.itotal, config),
+            format_inodes(info.iused, config),
+            format_inodes(info.iavail, config),
             format_percent(info.iuse_percent),
             info.target.clone(),
-        ]
+        ]);
+        row
     } else if config.print_type {
         vec![
             info.source.clone(),
@@ -600,23 +656,27 @@ pub(crate) fn build_header_row(config: &DfConfig) -> Vec<String> {
             .collect();
     }
 
-    let pct_header = if config.portability {
-        "Capacity"
-    } else if config.inodes {
+    let pct_header = if config.inodes {
         "IUse%"
+    } else if config.portability {
+        "Capacity"
     } else {
         "Use%"
     };
 
     if config.inodes {
-        vec![
-            "Filesystem".to_string(),
+        let mut row = vec!["Filesystem".to_string()];
+        if config.print_type {
+            row.push("Type".to_string());
+        }
+        row.extend([
             "Inodes".to_string(),
             "IUsed".to_string(),
             "IFree".to_string(),
             pct_header.to_string(),
             "Mounted on".to_string(),
-        ]
+        ]);
+        row
     } else if config.print_type {
         let avail_header = if config.human_readable || config.si {
             "Avail"
@@ -666,21 +726,21 @@ fn build_total_row(filesystems: &[FsInfo], config: &DfConfig) -> Vec<String> {
             (total_used as f64 / denom as f64) * 100.0
         }
     };
-    let iuse_pct = if total_itotal == 0 {
-        0.0
-    } else {
-        (total_iused as f64 / total_itotal as f64) * 100.0
-    };
+    let iuse_pct = inode_percent(total_iused, total_iavail);
 
     if config.inodes {
-        vec![
-            "total".to_string(),
-            format!("{}", total_itotal),
-            format!("{}", total_iused),
-            format!("{}", total_iavail),
+        let mut row = vec!["total".to_string()];
+        if config.print_type {
+            row.push("-".to_string());
+        }
+        row.extend([
+            format_inodes(total_itotal, config),
+            format_inodes(total_iused, config),
+            format_inodes(total_iavail, config),
             format_percent(iuse_pct),
             "-".to_string(),
-        ]
+        ]);
+        row
     } else if config.print_type {
         vec![
             "total".to_string(),
@@ -903,6 +963,16 @@ pub fn run_df(config: &DfConfig) -> i32 {
 
     let (filesystems, had_error) = get_filesystems(config);
 
+    // GNU df prints nothing, not even the header, when no file system was
+    // selected.
+    if filesystems.is_empty() {
+        if had_error {
+            return 1;
+        }
+        eprintln!("df: no file systems processed");
+        return 1;
+    }
+
     let header = build_header_row(config);
     let mut rows: Vec<Vec<String>> = Vec::new();
     for info in &filesystems {
@@ -119,7 +119,8 @@ fn is_excluded(path: &Path, config: &DuConfig) -> bool {
         .any(|pat| glob_match(pat, &basename) || glob_match(pat, &path_str))
 }
 
-/// Recursive traversal core. Returns the cumulative size of the subtree at `path`.
+/// Recursive traversal core. Returns the cumulative size of the subtree at `path`
+/// and its newest modification time (`i64::MIN` when nothing was counted).
 fn du_recursive(
     path: &Path,
     config: &DuConfig,
@@ -128,11 +129,11 @@ fn du_recursive(
     depth: usize,
     root_dev: Option<u64>,
     had_error: &mut bool,
-) -> io::Result<u64> {
+) -> io::Result<(u64, i64)> {
     // Check exclude patterns against this path (GNU du applies exclude to all entries
     // including the root argument itself).
     if is_excluded(path, config) {
-        return Ok(0);
+        return Ok((0, i64::MIN));
     }
 
     // For depth 0 (command-line arguments), dereference_args means follow symlinks.
@@ -145,7 +146,7 @@ fn du_recursive(
     // Check one-file-system: skip entries on different devices.
     if let Some(dev) = root_dev {
         if meta.dev() != dev && config.one_file_system {
-            return Ok(0);
+            return Ok((0, i64::MIN));
         }
     }
 
@@ -153,16 +154,16 @@ fn du_recursive(
     let ino_key = (meta.dev(), meta.ino());
     if meta.nlink() > 1 && !config.count_links {
         if !seen.insert(ino_key) {
-            return Ok(0);
+            return Ok((0, i64::MIN));
         }
     }
 
     let size = if config.inodes {
         1
     } else if config.apparent_size {
-        // GNU du uses 0 for directory entries themselves in apparent-size mode;
-        // only file content sizes are counted.
-        if meta.is_dir() { 0 } else { meta.len() }
+        // Like GNU, a directory's own st_size counts, so sparse files and
+        // directories alike report their logical length.
+        meta.len()
     } else {
         meta.blocks() * 512
     };
@@ -175,6 +176,9 @@ fn du_recursive(
         let mut subtree_size: u64 = size;
         // For separate_dirs, display size only includes this dir + direct files, not subdirs.
         let mut display_size: u64 = size;
+        // Like GNU, a directory's time is the newest in what its size covers.
+        let mut subtree_mtime = mtime;
+        let mut display_mtime = mtime;
 
         let read_dir = match std::fs::read_dir(path) {
             Ok(rd) => rd,
@@ -193,7 +197,7 @@ fn du_recursive(
                         mtime: if config.show_time { Some(mtime) } else { None },
                     });
                 }
-                return Ok(size);
+                return Ok((size, mtime));
             }
         };
 
@@ -220,7 +224,7 @@ fn du_recursive(
             // Check if child is a directory (for separate_dirs logic).
             let child_is_dir = child_path.symlink_metadata().map_or(false, |m| m.is_dir());
 
-            let child_size = du_recursive(
+            let (child_size, child_mtime) = du_recursive(
                 &child_path,
                 config,
                 seen,
@@ -230,10 +234,12 @@ fn du_recursive(
                 had_error,
             )?;
             subtree_size += child_size;
+            subtree_mtime = subtree_mtime.max(child_mtime);
             if config.separate_dirs && child_is_dir {
                 // Don't add subdirectory sizes to display size.
             } else {
                 display_size += child_size;
+                display_mtime = display_mtime.max(child_mtime);
             }
         }
 
@@ -242,11 +248,15 @@ fn du_recursive(
             entries.push(DuEntry {
                 size: display_size,
                 path: path.to_path_buf(),
-                mtime: if config.show_time { Some(mtime) } else { None },
+                mtime: if config.show_time {
+                    Some(display_mtime)
+                } else {
+                    None
+                },
             });
         }
 
-        Ok(subtree_size)
+        Ok((subtree_size, subtree_mtime))
     } else {
         // Regular file / symlink / special file.
         // Always report top-level arguments (depth 0), or all files if --all.
@@ -257,7 +267,7 @@ fn du_recursive(
                 mtime: if config.show_time { Some(mtime) } else { None },
             });
         }
-        Ok(size)
+        Ok((size, mtime))
     }
 }
 
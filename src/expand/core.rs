@@ -5,8 +5,21 @@ use std::io::Write;
 pub enum TabStops {
     /// Regular interval (default 8)
     Regular(usize),
-    /// Explicit list of tab stop positions (0-indexed columns)
-    List(Vec<usize>),
+    /// Explicit list of tab stop positions (0-indexed columns), and how stops
+    /// continue after the last one
+    List(Vec<usize>, TabRepeat),
+}
+
+/// Tab stops after the last explicit stop of a list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabRepeat {
+    /// No further stops: expand replaces such tabs with one space and
+    /// unexpand stops converting blanks
+    None,
+    /// `/N`: a stop at every multiple of N
+    Multiple(usize),
+    /// `+N`: a stop every N columns after the last explicit stop
+    Increment(usize),
 }
 
 impl TabStops {
@@ -20,17 +33,21 @@ impl TabStops {
                 }
                 *n - (column % *n)
             }
-            TabStops::List(stops) => {
+            TabStops::List(stops, repeat) => {
                 // Find the first tab stop > current column
-                match stops.binary_search(&(column + 1)) {
-                    Ok(idx) => stops[idx] - column,
-                    Err(idx) => {
-                        if idx < stops.len() {
-                            stops[idx] - column
-                        } else {
-                            // Past all tab stops: GNU uses 1 space
-                            1
-                        }
+                let idx = match stops.binary_search(&(column + 1)) {
+                    Ok(idx) | Err(idx) => idx,
+                };
+                if idx < stops.len() {
+                    return stops[idx] - column;
+                }
+                match *repeat {
+                    // Past all tab stops: GNU uses 1 space
+                    TabRepeat::None => 1,
+                    TabRepeat::Multiple(n) => n - column % n,
+                    TabRepeat::Increment(n) => {
+                        let last = stops.last().copied().unwrap_or(0);
+                        n - (column - last) % n
                     }
                 }
             }
@@ -42,66 +59,85 @@ impl TabStops {
     fn next_tab_stop(&self, column: usize) -> usize {
         column + self.spaces_to_next(column)
     }
+
+    /// Column of the last tab stop, or `usize::MAX` if stops never end.
+    fn last_tab_stop(&self) -> usize {
+        match self {
+            TabStops::List(stops, TabRepeat::None) => stops.last().copied().unwrap_or(0),
+            _ => usize::MAX,
+        }
+    }
 }
 
 /// Parse a tab specification string (e.g., "4", "4,8,12", "4 8 12").
+///
+/// As in GNU, the last value may be prefixed with `/N` (stops at every
+/// multiple of N after the list) or `+N` (stops every N columns after the
+/// last explicit stop). A single value is a uniform tab size.
 pub fn parse_tab_stops(spec: &str) -> Result<TabStops, String> {
     let spec = spec.trim();
     if spec.is_empty() {
         return Ok(TabStops::Regular(8));
     }
 
-    // Check if it's a single number (regular interval)
-    if let Ok(n) = spec.parse::<usize>() {
-        if n == 0 {
-            return Err("tab size cannot be 0".to_string());
-        }
-        return Ok(TabStops::Regular(n));
-    }
-
-    // Parse as comma or space-separated list
     let mut stops: Vec<usize> = Vec::new();
-    for part in spec.split([',', ' ']) {
-        let part = part.trim();
+    let mut repeat = TabRepeat::None;
+    let mut repeat_char = '/';
+    for part in spec.split([',', ' ', '\t']) {
         if part.is_empty() {
             continue;
         }
-        // Handle / prefix for repeating tab stops
-        if let Some(rest) = part.strip_prefix('/') {
-            let n: usize = rest
-                .parse()
-                .map_err(|_| format!("'{}' is not a valid number", part))?;
-            if n == 0 {
-                return Err("tab size cannot be 0".to_string());
-            }
-            let last = stops.last().copied().unwrap_or(0);
-            let mut pos = last + n;
-            while pos < 10000 {
-                stops.push(pos);
-                pos += n;
-            }
-            continue;
+        if repeat != TabRepeat::None {
+            return Err(format!(
+                "'{}' specifier only allowed with the last value",
+                repeat_char
+            ));
+        }
+        let (prefix, digits) = match part.as_bytes()[0] {
+            b'/' => (Some('/'), &part[1..]),
+            b'+' => (Some('+'), &part[1..]),
+            _ => (None, part),
+        };
+        if let Some(c) = digits.chars().find(|&c| c == '/' || c == '+') {
+            return Err(format!(
+                "'{}' specifier not at start of number: '{}'",
+                c, part
+            ));
+        }
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!(
+                "tab size contains invalid character(s): '{}'",
+                part
+            ));
+        }
+        let n: usize = digits
+            .parse()
+            .map_err(|_| format!("tab stop is too large '{}'", part))?;
+        if n == 0 {
+            return Err("tab size cannot be 0".to_string());
         }
-        match part.parse::<usize>() {
-            Ok(n) => {
-                if !stops.is_empty() && n <= *stops.last().unwrap() {
+        match prefix {
+            Some('/') => repeat = TabRepeat::Multiple(n),
+            Some(_) => repeat = TabRepeat::Increment(n),
+            None => {
+                if stops.last().is_some_and(|&last| n <= last) {
                     return Err("tab sizes must be ascending".to_string());
                 }
                 stops.push(n);
             }
-            Err(_) => return Err(format!("'{}' is not a valid number", part)),
+        }
+        if let Some(c) = prefix {
+            repeat_char = c;
         }
     }
 
-    if stops.is_empty() {
-        return Err("tab specification is empty".to_string());
-    }
-
-    if stops.len() == 1 {
-        return Ok(TabStops::Regular(stops[0]));
+    match (stops.len(), repeat) {
+        (0, TabRepeat::None) => Err("tab specification is empty".to_string()),
+        // "/N" or "+N" alone: stops every N columns
+        (0, TabRepeat::Multiple(n) | TabRepeat::Increment(n)) => Ok(TabStops::Regular(n)),
+        (1, TabRepeat::None) => Ok(TabStops::Regular(stops[0])),
+        _ => Ok(TabStops::List(stops, repeat)),
     }
-
-    Ok(TabStops::List(stops))
 }
 
 // Pre-computed spaces buffer for fast tab expansion (avoids per-tab allocation)
@@ -119,280 +155,161 @@ fn push_spaces(output: &mut Vec<u8>, n: usize) {
     }
 }
 
-/// Write N spaces to a Write stream using pre-computed buffer.
-#[inline]
-fn write_spaces(out: &mut impl Write, n: usize) -> std::io::Result<()> {
-    let mut remaining = n;
-    while remaining > 0 {
-        let chunk = remaining.min(SPACES.len());
-        out.write_all(&SPACES[..chunk])?;
-        remaining -= chunk;
-    }
-    Ok(())
-}
-
-/// Expand tabs to spaces using SIMD scanning.
-/// Uses memchr2 to find tabs and newlines, bulk-copying everything between them.
-pub fn expand_bytes(
-    data: &[u8],
-    tabs: &TabStops,
+/// Streaming tab expander.
+///
+/// Carries the output column and the `--initial` state from one chunk to the
+/// next, so input may be fed in arbitrary pieces; like GNU, the state also
+/// carries across input files. Runs between tabs and newlines are found with
+/// memchr (SIMD) and copied in bulk; a backspace steps the column back by one.
+pub struct Expander {
+    tabs: TabStops,
+    /// `tab_size - 1` for power-of-2 regular stops (AND instead of modulo)
+    tab_mask: Option<usize>,
     initial_only: bool,
-    out: &mut impl Write,
-) -> std::io::Result<()> {
-    if data.is_empty() {
-        return Ok(());
-    }
-
-    // Fast path: no tabs in data → just copy through
-    if memchr::memchr(b'\t', data).is_none() {
-        return out.write_all(data);
-    }
-
-    // For regular tab stops, use fast SIMD paths
-    if let TabStops::Regular(tab_size) = tabs {
-        if initial_only {
-            // --initial mode processes line-by-line anyway, so handle backspace
-            // per-line instead of scanning the whole buffer.
-            return expand_initial_fast(data, *tab_size, out);
-        } else if memchr::memchr(b'\x08', data).is_none() {
-            return expand_regular_fast(data, *tab_size, out);
-        }
-    }
-
-    // Generic path for backspace handling or tab lists.
-    // For Regular tabs, we only reach here when the memchr check at line 157 found
-    // backspaces, so has_backspace=true avoids a redundant O(n) scan.
-    // For List tabs, we haven't scanned yet, so check now.
-    let has_backspace = match tabs {
-        TabStops::Regular(_) => true,
-        TabStops::List(_) => memchr::memchr(b'\x08', data).is_some(),
-    };
-    expand_generic(data, tabs, initial_only, has_backspace, out)
+    column: usize,
+    in_initial: bool,
+    output: Vec<u8>,
 }
 
-/// Fast expand for regular tab stops without -i flag.
-/// Accumulates output into a buffer and flushes periodically (every 256KB) to bound memory.
-/// Uses memchr2 SIMD scanning to skip non-tab/non-newline runs in bulk.
-fn expand_regular_fast(data: &[u8], tab_size: usize, out: &mut impl Write) -> std::io::Result<()> {
-    debug_assert!(tab_size > 0, "tab_size must be > 0");
+impl Expander {
     const FLUSH_THRESHOLD: usize = 256 * 1024;
-    let cap = data.len().min(FLUSH_THRESHOLD) + data.len().min(FLUSH_THRESHOLD) / 8;
-    let mut output = Vec::with_capacity(cap);
-    let mut column: usize = 0;
-    let mut pos: usize = 0;
-
-    // For power-of-2 tab sizes, use bitwise AND instead of modulo (avoids DIV instruction)
-    let is_pow2 = tab_size.is_power_of_two();
-    let mask = tab_size - 1; // only valid when is_pow2
 
-    while pos < data.len() {
-        match memchr::memchr2(b'\t', b'\n', &data[pos..]) {
-            Some(offset) => {
-                // Copy non-special bytes in bulk
-                if offset > 0 {
-                    output.extend_from_slice(&data[pos..pos + offset]);
-                    column += offset;
-                }
-                let byte = data[pos + offset];
-                pos += offset + 1;
-
-                if byte == b'\n' {
-                    output.push(b'\n');
-                    column = 0;
-                } else {
-                    // Tab: append spaces to buffer
-                    let rem = if is_pow2 {
-                        column & mask
-                    } else {
-                        column % tab_size
-                    };
-                    let spaces = tab_size - rem;
-                    push_spaces(&mut output, spaces);
-                    column += spaces;
-                }
-
-                // Flush periodically to bound memory usage
-                if output.len() >= FLUSH_THRESHOLD {
-                    out.write_all(&output)?;
-                    output.clear();
-                }
-            }
-            None => {
-                output.extend_from_slice(&data[pos..]);
-                break;
-            }
+    pub fn new(tabs: TabStops, initial_only: bool) -> Self {
+        let tab_mask = match tabs {
+            TabStops::Regular(n) if n.is_power_of_two() => Some(n - 1),
+            _ => None,
+        };
+        Expander {
+            tabs,
+            tab_mask,
+            initial_only,
+            column: 0,
+            in_initial: true,
+            output: Vec::new(),
         }
     }
 
-    if !output.is_empty() {
-        out.write_all(&output)?;
-    }
-    Ok(())
-}
-
-/// Fast expand for --initial mode with regular tab stops.
-/// Only expands tabs in the leading whitespace of each line, bulk-copying the rest.
-/// Uses memchr (SIMD) to find line boundaries. Leading-whitespace expansion is scalar.
-/// Handles backspace per-line: lines containing \x08 fall back to generic expand.
-fn expand_initial_fast(data: &[u8], tab_size: usize, out: &mut impl Write) -> std::io::Result<()> {
-    debug_assert!(tab_size > 0, "tab_size must be > 0");
-    let tabs = TabStops::Regular(tab_size);
-    let mut pos: usize = 0;
-
-    while pos < data.len() {
-        // Find end of this line
-        let line_end = memchr::memchr(b'\n', &data[pos..])
-            .map(|off| pos + off + 1)
-            .unwrap_or(data.len());
-
-        let line = &data[pos..line_end];
-        debug_assert!(!line.is_empty());
-
-        // Fast skip: if line doesn't start with tab or space, write it whole
-        let first = line[0];
-        if first != b'\t' && first != b' ' {
-            out.write_all(line)?;
-            pos = line_end;
-            continue;
+    /// Expand one chunk of input, writing the result to `out`.
+    pub fn expand(&mut self, data: &[u8], out: &mut impl Write) -> std::io::Result<()> {
+        let has_backspace = !self.initial_only && memchr::memchr(b'\x08', data).is_some();
+        let mut pos = 0;
+        while pos < data.len() {
+            pos += if self.initial_only {
+                self.expand_initial(&data[pos..])
+            } else {
+                self.expand_all(&data[pos..], has_backspace)
+            };
+            if self.output.len() >= Self::FLUSH_THRESHOLD {
+                out.write_all(&self.output)?;
+                self.output.clear();
+            }
         }
-
-        // If this line contains a backspace, fall back to generic for this line only
-        if memchr::memchr(b'\x08', line).is_some() {
-            expand_generic(line, &tabs, true, true, out)?;
-            pos = line_end;
-            continue;
+        if !self.output.is_empty() {
+            out.write_all(&self.output)?;
+            self.output.clear();
         }
+        Ok(())
+    }
 
-        // Expand only leading tabs/spaces in this line
-        let mut column: usize = 0;
-        let mut i = 0; // offset within line
-        while i < line.len() {
-            let byte = line[i];
-            if byte == b'\t' {
-                let spaces = tab_size - (column % tab_size);
-                write_spaces(out, spaces)?;
-                column += spaces;
-                i += 1;
-            } else if byte == b' ' {
-                // Batch consecutive spaces from source data
-                let space_start = i;
-                while i < line.len() && line[i] == b' ' {
-                    i += 1;
-                }
-                out.write_all(&line[space_start..i])?;
-                column += i - space_start;
+    /// Expand input until enough output has accumulated to be flushed;
+    /// returns the bytes consumed. Only tabs, newlines and (when
+    /// `has_backspace`) backspaces affect the column specially, so the runs
+    /// between them are copied in bulk.
+    fn expand_all(&mut self, data: &[u8], has_backspace: bool) -> usize {
+        let mut pos = 0;
+        while pos < data.len() && self.output.len() < Self::FLUSH_THRESHOLD {
+            // Bound each run so long tab-free input is still flushed regularly.
+            let end = data.len().min(pos + Self::FLUSH_THRESHOLD);
+            let rest = &data[pos..end];
+            let found = if has_backspace {
+                memchr::memchr3(b'\t', b'\n', b'\x08', rest)
             } else {
-                // First non-blank: write the rest of the line unchanged
+                memchr::memchr2(b'\t', b'\n', rest)
+            };
+            let Some(offset) = found else {
+                self.output.extend_from_slice(rest);
+                self.column += rest.len();
+                pos = end;
                 break;
+            };
+            self.output.extend_from_slice(&rest[..offset]);
+            self.column += offset;
+            pos += offset + 1;
+            match rest[offset] {
+                b'\t' => {
+                    let spaces = match self.tab_mask {
+                        Some(mask) => mask + 1 - (self.column & mask),
+                        None => self.tabs.spaces_to_next(self.column),
+                    };
+                    push_spaces(&mut self.output, spaces);
+                    self.column += spaces;
+                }
+                b'\n' => {
+                    self.output.push(b'\n');
+                    self.column = 0;
+                }
+                _ => {
+                    self.output.push(b'\x08');
+                    self.column = self.column.saturating_sub(1);
+                }
             }
         }
-
-        // Write remainder of line as-is (zero-copy)
-        if i < line.len() {
-            out.write_all(&line[i..])?;
-        }
-
-        pos = line_end;
+        pos
     }
 
-    Ok(())
-}
-
-/// Generic expand with support for -i flag and tab lists.
-/// Uses memchr2 SIMD scanning when no backspaces are present.
-/// `has_backspace` hint avoids a redundant O(n) scan when the caller already knows.
-fn expand_generic(
-    data: &[u8],
-    tabs: &TabStops,
-    initial_only: bool,
-    has_backspace: bool,
-    out: &mut impl Write,
-) -> std::io::Result<()> {
-    const FLUSH_THRESHOLD: usize = 256 * 1024;
-    let cap = data.len().min(FLUSH_THRESHOLD) + data.len().min(FLUSH_THRESHOLD) / 8;
-    let mut output = Vec::with_capacity(cap);
-
-    // If no backspaces present and not initial-only, use SIMD bulk scanning
-    if !initial_only && !has_backspace {
-        let mut column: usize = 0;
-        let mut pos: usize = 0;
-
-        while pos < data.len() {
-            match memchr::memchr2(b'\t', b'\n', &data[pos..]) {
+    /// `--initial`: expand tabs among the leading blanks of a line and copy
+    /// the rest of the line through; returns the bytes consumed.
+    fn expand_initial(&mut self, data: &[u8]) -> usize {
+        if !self.in_initial {
+            // Nothing more to convert before the next line.
+            return match memchr::memchr(b'\n', data) {
                 Some(offset) => {
-                    if offset > 0 {
-                        output.extend_from_slice(&data[pos..pos + offset]);
-                        column += offset;
-                    }
-                    let byte = data[pos + offset];
-                    pos += offset + 1;
-
-                    if byte == b'\n' {
-                        output.push(b'\n');
-                        column = 0;
-                    } else {
-                        let spaces = tabs.spaces_to_next(column);
-                        push_spaces(&mut output, spaces);
-                        column += spaces;
-                    }
-                    if output.len() >= FLUSH_THRESHOLD {
-                        out.write_all(&output)?;
-                        output.clear();
-                    }
+                    self.output.extend_from_slice(&data[..=offset]);
+                    self.column = 0;
+                    self.in_initial = true;
+                    offset + 1
                 }
                 None => {
-                    output.extend_from_slice(&data[pos..]);
-                    break;
+                    self.output.extend_from_slice(data);
+                    data.len()
                 }
-            }
+            };
         }
-    } else {
-        // Byte-by-byte fallback for backspace handling or initial-only mode
-        let mut column: usize = 0;
-        let mut in_initial = true;
-
-        for &byte in data {
+        for (i, &byte) in data.iter().enumerate() {
             match byte {
                 b'\t' => {
-                    if initial_only && !in_initial {
-                        output.push(b'\t');
-                        column = tabs.next_tab_stop(column);
-                    } else {
-                        let spaces = tabs.spaces_to_next(column);
-                        push_spaces(&mut output, spaces);
-                        column += spaces;
-                    }
+                    let spaces = self.tabs.spaces_to_next(self.column);
+                    push_spaces(&mut self.output, spaces);
+                    self.column += spaces;
                 }
-                b'\n' => {
-                    output.push(b'\n');
-                    column = 0;
-                    in_initial = true;
-                    if output.len() >= FLUSH_THRESHOLD {
-                        out.write_all(&output)?;
-                        output.clear();
-                    }
+                b' ' => {
+                    self.output.push(b' ');
+                    self.column += 1;
                 }
-                b'\x08' => {
-                    output.push(b'\x08');
-                    if column > 0 {
-                        column -= 1;
-                    }
+                b'\n' => {
+                    self.output.push(b'\n');
+                    self.column = 0;
                 }
                 _ => {
-                    if initial_only && in_initial && byte != b' ' {
-                        in_initial = false;
-                    }
-                    output.push(byte);
-                    column += 1;
+                    // Any other byte, a backspace included, ends the
+                    // leading blanks.
+                    self.in_initial = false;
+                    return i;
                 }
             }
         }
+        data.len()
     }
+}
 
-    if !output.is_empty() {
-        out.write_all(&output)?;
-    }
-    Ok(())
+/// Expand tabs to spaces in a complete buffer.
+pub fn expand_bytes(
+    data: &[u8],
+    tabs: &TabStops,
+    initial_only: bool,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    Expander::new(tabs.clone(), initial_only).expand(data, out)
 }
 
 /// Unexpand spaces to tabs.
@@ -555,13 +472,31 @@ fn unexpand_generic(
 ) -> std::io::Result<()> {
     let tab_size = match tabs {
         TabStops::Regular(n) => *n,
-        TabStops::List(_) => 0, // handled by is_tab_stop/next_tab_stop
+        TabStops::List(..) => 0, // handled by is_tab_stop/next_tab_stop
     };
     let mut column: usize = 0;
     let mut space_start_col: Option<usize> = None;
     let mut in_initial = true;
+    // Like GNU, a blank at or past the last explicit tab stop ends conversion
+    // for the rest of the line: blanks from there on are copied as they are.
+    let last_stop = tabs.last_tab_stop();
+    let mut verbatim = false;
 
     for &byte in data {
+        if matches!(byte, b' ' | b'\t') && (all || in_initial) && (verbatim || column >= last_stop)
+        {
+            if let Some(start_col) = space_start_col.take() {
+                emit_blanks_tablist(out, start_col, column - start_col, tabs)?;
+            }
+            out.write_all(&[byte])?;
+            column = if byte == b' ' {
+                column + 1
+            } else {
+                tabs.next_tab_stop(column)
+            };
+            verbatim = true;
+            continue;
+        }
         match byte {
             b' ' => {
                 if !all && !in_initial {
@@ -607,6 +542,7 @@ fn unexpand_generic(
                     out.write_all(b"\n")?;
                     column = 0;
                     in_initial = true;
+                    verbatim = false;
                 } else if byte == b'\x08' {
                     out.write_all(b"\x08")?;
                     if column > 0 {
@@ -650,10 +586,7 @@ fn emit_blanks_tablist(
     let mut col = start_col;
 
     // Get the last defined tab stop to know when to stop converting to tabs
-    let last_stop = match tabs {
-        TabStops::List(stops) => stops.last().copied().unwrap_or(0),
-        TabStops::Regular(_) => usize::MAX,
-    };
+    let last_stop = tabs.last_tab_stop();
 
     while col < last_stop {
         let next_tab = tabs.next_tab_stop(col);
@@ -8,7 +8,7 @@ pub const EXIT_SUCCESS: i32 = 0;
 pub const EXIT_FAILURE: i32 = 1;
 /// Exit code: expression is syntactically invalid.
 pub const EXIT_EXPR_ERROR: i32 = 2;
-/// Exit code: regex error.
+/// Exit code: the regex engine failed on a valid pattern.
 pub const EXIT_REGEX_ERROR: i32 = 3;
 
 /// A value produced by evaluating an expr expression.
@@ -28,11 +28,15 @@ impl fmt::Display for ExprValue {
 }
 
 impl ExprValue {
-    /// Returns true if this value is considered "null" (empty string or integer 0).
+    /// Returns true if this value is considered "null": the empty string, or
+    /// zero written as an optional '-' followed only by '0' digits.
     pub fn is_null(&self) -> bool {
         match self {
             ExprValue::Integer(n) => *n == 0,
-            ExprValue::Str(s) => s.is_empty() || s == "0",
+            ExprValue::Str(s) => {
+                let digits = s.strip_prefix('-').unwrap_or(s);
+                s.is_empty() || (!digits.is_empty() && digits.bytes().all(|b| b == b'0'))
+            }
         }
     }
 
@@ -45,23 +49,14 @@ impl ExprValue {
     }
 }
 
-/// Parse an integer from a string, accepting optional leading sign and digits only.
+/// Parse an integer from a string: an optional '-' followed by digits only,
+/// as GNU expr requires (no '+' and no surrounding whitespace).
 fn parse_integer(s: &str) -> Option<i64> {
-    let s = s.trim();
-    if s.is_empty() {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
         return None;
     }
-    let (sign, digits) = if let Some(rest) = s.strip_prefix('-') {
-        (-1i64, rest)
-    } else if let Some(rest) = s.strip_prefix('+') {
-        (1i64, rest)
-    } else {
-        (1i64, s)
-    };
-    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
-        return None;
-    }
-    digits.parse::<i64>().ok().map(|v| sign * v)
+    s.parse::<i64>().ok()
 }
 
 /// Errors that can occur during expression evaluation.
@@ -72,6 +67,8 @@ pub enum ExprError {
     /// Division by zero.
     DivisionByZero,
     /// Invalid regex pattern.
+    InvalidRegex(String),
+    /// The regex engine could not handle a pattern (e.g. a back-reference).
     RegexError(String),
     /// Non-integer argument where integer was required.
     NonIntegerArgument,
@@ -84,7 +81,7 @@ impl fmt::Display for ExprError {
         match self {
             ExprError::Syntax(msg) => write!(f, "syntax error: {}", msg),
             ExprError::DivisionByZero => write!(f, "division by zero"),
-            ExprError::RegexError(msg) => write!(f, "{}", msg),
+            ExprError::InvalidRegex(msg) | ExprError::RegexError(msg) => write!(f, "{}", msg),
             ExprError::NonIntegerArgument => write!(f, "non-integer argument"),
             ExprError::MissingOperand => write!(f, "missing operand"),
         }
@@ -101,7 +98,25 @@ impl ExprError {
     }
 }
 
-/// Recursive descent parser for expr expressions.
+/// Precedence of a binary operator, loosest first. All binary operators are
+/// left-associative.
+fn binary_precedence(op: &str) -> Option<u8> {
+    match op {
+        "|" => Some(1),
+        "&" => Some(2),
+        "<" | "<=" | "=" | "==" | "!=" | ">=" | ">" => Some(3),
+        "+" | "-" => Some(4),
+        "*" | "/" | "%" => Some(5),
+        ":" => Some(6),
+        _ => None,
+    }
+}
+
+/// Precedence-climbing parser for expr expressions.
+///
+/// Like GNU expr, every method takes an `evaluate` flag: the operand of `|`
+/// or `&` that cannot affect the result is still parsed, but not evaluated,
+/// so it raises no division-by-zero or non-integer errors.
 struct ExprParser<'a> {
     args: &'a [String],
     pos: usize,
@@ -113,526 +128,385 @@ impl<'a> ExprParser<'a> {
     }
 
     /// Peek at the current token without consuming it.
-    fn peek(&self) -> Option<&str> {
-        if self.pos < self.args.len() {
-            Some(self.args[self.pos].as_str())
-        } else {
-            None
-        }
+    fn peek(&self) -> Option<&'a str> {
+        self.args.get(self.pos).map(String::as_str)
     }
 
     /// Consume the current token and advance.
-    fn consume(&mut self) -> Option<&str> {
-        if self.pos < self.args.len() {
-            let tok = self.args[self.pos].as_str();
-            self.pos += 1;
-            Some(tok)
-        } else {
-            None
-        }
+    fn consume(&mut self) -> Option<&'a str> {
+        let tok = self.peek()?;
+        self.pos += 1;
+        Some(tok)
     }
 
-    /// Expect a specific token, returning an error if not found.
-    fn expect(&mut self, expected: &str) -> Result<(), ExprError> {
-        match self.consume() {
-            Some(tok) if tok == expected => Ok(()),
-            Some(tok) => Err(ExprError::Syntax(format!(
-                "expected '{}', found '{}'",
-                expected, tok
-            ))),
-            None => Err(ExprError::Syntax(format!("expected '{}'", expected))),
+    /// The error for running out of arguments after the last consumed token.
+    fn missing_argument(&self) -> ExprError {
+        match self.pos.checked_sub(1) {
+            Some(prev) => {
+                ExprError::Syntax(format!("missing argument after '{}'", self.args[prev]))
+            }
+            None => ExprError::MissingOperand,
         }
     }
 
-    /// Parse the top-level: OR expression.
-    /// OR: AND ( '|' AND )*
-    fn parse_or(&mut self) -> Result<ExprValue, ExprError> {
-        let mut left = self.parse_and()?;
-        while self.peek() == Some("|") {
+    /// Parse a sequence of binary operations binding at least as tightly as
+    /// `min_prec`.
+    fn parse_binary(&mut self, min_prec: u8, evaluate: bool) -> Result<ExprValue, ExprError> {
+        let mut left = self.parse_primary(evaluate)?;
+        while let Some(op) = self.peek()
+            && let Some(prec) = binary_precedence(op)
+            && prec >= min_prec
+        {
             self.consume();
-            let right = self.parse_and()?;
-            left = if !left.is_null() { left } else { right };
-        }
-        Ok(left)
-    }
-
-    /// Parse AND expression.
-    /// AND: COMPARISON ( '&' COMPARISON )*
-    fn parse_and(&mut self) -> Result<ExprValue, ExprError> {
-        let mut left = self.parse_comparison()?;
-        while self.peek() == Some("&") {
-            self.consume();
-            let right = self.parse_comparison()?;
-            left = if !left.is_null() && !right.is_null() {
-                left
-            } else {
-                ExprValue::Integer(0)
-            };
-        }
-        Ok(left)
-    }
-
-    /// Parse comparison expression.
-    /// COMPARISON: ADDITION ( ('<'|'<='|'='|'!='|'>='|'>') ADDITION )*
-    fn parse_comparison(&mut self) -> Result<ExprValue, ExprError> {
-        let mut left = self.parse_addition()?;
-        while matches!(
-            self.peek(),
-            Some("<") | Some("<=") | Some("=") | Some("!=") | Some(">=") | Some(">")
-        ) {
-            let op = self.consume().unwrap().to_string();
-            let right = self.parse_addition()?;
-            let result = compare_values(&left, &right, &op);
-            left = ExprValue::Integer(if result { 1 } else { 0 });
-        }
-        Ok(left)
-    }
-
-    /// Parse addition/subtraction.
-    /// ADDITION: MULTIPLICATION ( ('+'|'-') MULTIPLICATION )*
-    fn parse_addition(&mut self) -> Result<ExprValue, ExprError> {
-        let mut left = self.parse_multiplication()?;
-        while matches!(self.peek(), Some("+") | Some("-")) {
-            let op = self.consume().unwrap().to_string();
-            let right = self.parse_multiplication()?;
-            let lv = left.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-            let rv = right.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-            left = match op.as_str() {
-                "+" => ExprValue::Integer(
-                    lv.checked_add(rv)
-                        .ok_or_else(|| ExprError::Syntax("integer result too large".into()))?,
-                ),
-                "-" => ExprValue::Integer(
-                    lv.checked_sub(rv)
-                        .ok_or_else(|| ExprError::Syntax("integer result too large".into()))?,
-                ),
-                _ => unreachable!(),
+            let eval_right = match op {
+                "|" => evaluate && left.is_null(),
+                "&" => evaluate && !left.is_null(),
+                _ => evaluate,
             };
+            let right = self.parse_binary(prec + 1, eval_right)?;
+            if evaluate {
+                left = apply_binary(op, left, right)?;
+            }
         }
         Ok(left)
     }
 
-    /// Parse multiplication/division/modulo.
-    /// MULTIPLICATION: MATCH ( ('*'|'/'|'%') MATCH )*
-    fn parse_multiplication(&mut self) -> Result<ExprValue, ExprError> {
-        let mut left = self.parse_match()?;
-        while matches!(self.peek(), Some("*") | Some("/") | Some("%")) {
-            let op = self.consume().unwrap().to_string();
-            let right = self.parse_match()?;
-            let lv = left.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-            let rv = right.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-            left = match op.as_str() {
-                "*" => ExprValue::Integer(
-                    lv.checked_mul(rv)
-                        .ok_or_else(|| ExprError::Syntax("integer result too large".into()))?,
-                ),
-                "/" => {
-                    if rv == 0 {
-                        return Err(ExprError::DivisionByZero);
-                    }
-                    ExprValue::Integer(
-                        lv.checked_div(rv)
-                            .ok_or_else(|| ExprError::Syntax("integer result too large".into()))?,
-                    )
-                }
-                "%" => {
-                    if rv == 0 {
-                        return Err(ExprError::DivisionByZero);
-                    }
-                    ExprValue::Integer(
-                        lv.checked_rem(rv)
-                            .ok_or_else(|| ExprError::Syntax("integer result too large".into()))?,
-                    )
+    /// Parse a primary expression: a keyword function, a `+`-quoted token, a
+    /// parenthesized expression, or a plain string.
+    fn parse_primary(&mut self, evaluate: bool) -> Result<ExprValue, ExprError> {
+        let Some(tok) = self.consume() else {
+            return Err(self.missing_argument());
+        };
+        match tok {
+            "match" => {
+                let string = self.parse_primary(evaluate)?;
+                let pattern = self.parse_primary(evaluate)?;
+                if evaluate {
+                    do_match(&string.to_string(), &pattern.to_string())
+                } else {
+                    Ok(string)
                 }
-                _ => unreachable!(),
-            };
-        }
-        Ok(left)
-    }
-
-    /// Parse match/colon expression.
-    /// MATCH: PRIMARY ( ':' PRIMARY )?
-    fn parse_match(&mut self) -> Result<ExprValue, ExprError> {
-        let left = self.parse_primary()?;
-        if self.peek() == Some(":") {
-            self.consume();
-            let right = self.parse_primary()?;
-            let pattern_str = match &right {
-                ExprValue::Str(s) => s.clone(),
-                ExprValue::Integer(n) => n.to_string(),
-            };
-            let string = match &left {
-                ExprValue::Str(s) => s.clone(),
-                ExprValue::Integer(n) => n.to_string(),
-            };
-            return do_match(&string, &pattern_str);
-        }
-        Ok(left)
-    }
-
-    /// Parse primary expression: keyword functions, parenthesized expressions, or atoms.
-    fn parse_primary(&mut self) -> Result<ExprValue, ExprError> {
-        match self.peek() {
-            None => Err(ExprError::MissingOperand),
-            Some("(") => {
-                self.consume();
-                let val = self.parse_or()?;
-                self.expect(")")?;
-                Ok(val)
-            }
-            Some("match") => {
-                self.consume();
-                let string_val = self.parse_primary()?;
-                let pattern_val = self.parse_primary()?;
-                let string = match &string_val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                let pattern = match &pattern_val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                do_match(&string, &pattern)
             }
-            Some("substr") => {
-                self.consume();
-                let string_val = self.parse_primary()?;
-                let pos_val = self.parse_primary()?;
-                let len_val = self.parse_primary()?;
-                let string = match &string_val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                let pos = pos_val.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-                let len = len_val.as_integer().ok_or(ExprError::NonIntegerArgument)?;
-                Ok(do_substr(&string, pos, len))
+            "substr" => {
+                let string = self.parse_primary(evaluate)?;
+                let pos = self.parse_primary(evaluate)?;
+                let len = self.parse_primary(evaluate)?;
+                Ok(match (pos.as_integer(), len.as_integer()) {
+                    (Some(pos), Some(len)) => do_substr(&string.to_string(), pos, len),
+                    _ => ExprValue::Str(String::new()),
+                })
             }
-            Some("index") => {
-                self.consume();
-                let string_val = self.parse_primary()?;
-                let chars_val = self.parse_primary()?;
-                let string = match &string_val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                let chars = match &chars_val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                Ok(do_index(&string, &chars))
+            "index" => {
+                let string = self.parse_primary(evaluate)?;
+                let chars = self.parse_primary(evaluate)?;
+                Ok(do_index(&string.to_string(), &chars.to_string()))
             }
-            Some("length") => {
-                self.consume();
-                let val = self.parse_primary()?;
-                let s = match &val {
-                    ExprValue::Str(s) => s.clone(),
-                    ExprValue::Integer(n) => n.to_string(),
-                };
-                Ok(ExprValue::Integer(s.len() as i64))
+            "length" => {
+                let val = self.parse_primary(evaluate)?;
+                Ok(ExprValue::Integer(val.to_string().len() as i64))
             }
-            Some("+") => {
-                // GNU expr extension: '+' is a quoting prefix that treats the
-                // next token as a literal string, even if it would otherwise be
-                // interpreted as a keyword (match, length, substr, index).
-                self.consume();
+            "+" => {
+                // GNU expr extension: '+' quotes the next token, so that even
+                // a keyword or operator is taken as a plain string.
                 match self.consume() {
-                    Some(tok) => {
-                        let tok = tok.to_string();
-                        if let Some(n) = parse_integer(&tok) {
-                            Ok(ExprValue::Integer(n))
-                        } else {
-                            Ok(ExprValue::Str(tok))
-                        }
-                    }
-                    None => Err(ExprError::Syntax("missing argument after '+'".to_string())),
+                    Some(tok) => Ok(ExprValue::Str(tok.to_string())),
+                    None => Err(self.missing_argument()),
                 }
             }
-            _ => {
-                // Atom: a literal string or number.
-                let tok = self.consume().unwrap().to_string();
-                if let Some(n) = parse_integer(&tok) {
-                    Ok(ExprValue::Integer(n))
-                } else {
-                    Ok(ExprValue::Str(tok))
+            "(" => {
+                let val = self.parse_binary(1, evaluate)?;
+                match self.consume() {
+                    Some(")") => Ok(val),
+                    Some(tok) => Err(ExprError::Syntax(format!(
+                        "expecting ')' instead of '{}'",
+                        tok
+                    ))),
+                    None => Err(ExprError::Syntax(format!(
+                        "expecting ')' after '{}'",
+                        self.args[self.pos - 1]
+                    ))),
                 }
             }
+            ")" => Err(ExprError::Syntax("unexpected ')'".to_string())),
+            _ => Ok(ExprValue::Str(tok.to_string())),
         }
     }
 }
 
+/// Apply an evaluated binary operator.
+fn apply_binary(op: &str, left: ExprValue, right: ExprValue) -> Result<ExprValue, ExprError> {
+    let too_large = || ExprError::Syntax("integer result too large".into());
+    Ok(match op {
+        "|" => {
+            if !left.is_null() {
+                left
+            } else if !right.is_null() {
+                right
+            } else {
+                ExprValue::Integer(0)
+            }
+        }
+        "&" => {
+            if left.is_null() || right.is_null() {
+                ExprValue::Integer(0)
+            } else {
+                left
+            }
+        }
+        ":" => do_match(&left.to_string(), &right.to_string())?,
+        "+" | "-" | "*" | "/" | "%" => {
+            let lv = left.as_integer().ok_or(ExprError::NonIntegerArgument)?;
+            let rv = right.as_integer().ok_or(ExprError::NonIntegerArgument)?;
+            if rv == 0 && (op == "/" || op == "%") {
+                return Err(ExprError::DivisionByZero);
+            }
+            let result = match op {
+                "+" => lv.checked_add(rv),
+                "-" => lv.checked_sub(rv),
+                "*" => lv.checked_mul(rv),
+                "/" => lv.checked_div(rv),
+                _ => lv.checked_rem(rv),
+            };
+            ExprValue::Integer(result.ok_or_else(too_large)?)
+        }
+        _ => ExprValue::Integer(compare_values(&left, &right, op) as i64),
+    })
+}
+
 /// Compare two ExprValues. If both are integers, compare numerically;
 /// otherwise compare as strings lexicographically.
 fn compare_values(left: &ExprValue, right: &ExprValue, op: &str) -> bool {
-    let left_int = left.as_integer();
-    let right_int = right.as_integer();
-
-    if let (Some(lv), Some(rv)) = (left_int, right_int) {
-        match op {
-            "<" => lv < rv,
-            "<=" => lv <= rv,
-            "=" => lv == rv,
-            "!=" => lv != rv,
-            ">=" => lv >= rv,
-            ">" => lv > rv,
-            _ => false,
-        }
-    } else {
-        let ls = left.to_string();
-        let rs = right.to_string();
-        match op {
-            "<" => ls < rs,
-            "<=" => ls <= rs,
-            "=" => ls == rs,
-            "!=" => ls != rs,
-            ">=" => ls >= rs,
-            ">" => ls > rs,
-            _ => false,
-        }
+    let ordering = match (left.as_integer(), right.as_integer()) {
+        (Some(lv), Some(rv)) => lv.cmp(&rv),
+        _ => left.to_string().cmp(&right.to_string()),
+    };
+    match op {
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        "=" | "==" => ordering.is_eq(),
+        "!=" => ordering.is_ne(),
+        ">=" => ordering.is_ge(),
+        ">" => ordering.is_gt(),
+        _ => false,
     }
 }
 
-/// Convert a POSIX BRE (Basic Regular Expression) pattern to a Rust regex pattern.
-/// BRE differences from ERE:
-/// - `\(` and `\)` are group delimiters (not `(` and `)`)
-/// - `\{` and `\}` are interval delimiters
-/// - `(` and `)` are literal in BRE
-/// - `{` and `}` are literal in BRE
-/// - `\+`, `\?` are special in BRE (some implementations)
-/// - `+`, `?` are literal in BRE
-/// - The match is always anchored at the beginning (as if `^` is prepended).
+/// Translate a POSIX basic regular expression, read the way GNU expr reads
+/// it, into `regex` crate syntax. The result is anchored at the start and
+/// `.` also matches a newline.
 ///
-/// When inside a `\(` ... `\)` group, `\.` is treated as a literal dot insertion
-/// that does not consume input. It is excluded from the regex and instead tracked
-/// separately so that the match result can be reconstructed with literal dots.
-fn bre_to_rust_regex(pattern: &str) -> String {
-    let mut result = String::with_capacity(pattern.len() + 2);
-    // BRE patterns in expr are implicitly anchored at the start
-    result.push('^');
+/// `\(` `\)`, `\{` `\}`, `\|`, `\+` and `\?` are operators, while their
+/// unescaped forms are literals. `^` is an anchor only at the start of the
+/// pattern, a group or an alternative, `$` only at the end of one, and `*`
+/// is literal where there is nothing to repeat.
+fn bre_to_rust_regex(pattern: &str) -> Result<String, ExprError> {
+    let invalid = |msg: &str| ExprError::InvalidRegex(msg.to_string());
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut result = String::with_capacity(pattern.len() + 12);
+    result.push_str("(?s)^(?:");
 
-    let bytes = pattern.as_bytes();
     let mut i = 0;
-    let mut group_depth = 0u32;
-    while i < bytes.len() {
-        if bytes[i] == b'\\' && i + 1 < bytes.len() {
-            match bytes[i + 1] {
-                b'(' => {
-                    group_depth += 1;
+    let mut depth = 0u32;
+    // Nothing to repeat yet: at the start of the pattern, a group or an
+    // alternative, or right after a leading `^`.
+    let mut at_start = true;
+    // The last item was a leading `^`, after which `^` is literal.
+    let mut after_anchor = false;
+    // The last item was a repetition; GNU folds a repeated repetition.
+    let mut after_repeat = false;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let mut repeat = None;
+        let mut opens = false;
+        let mut anchor = false;
+        if c == '\\' {
+            let Some(&e) = chars.get(i) else {
+                return Err(invalid("Trailing backslash"));
+            };
+            i += 1;
+            match e {
+                '(' => {
+                    depth += 1;
                     result.push('(');
-                    i += 2;
+                    opens = true;
                 }
-                b')' => {
-                    group_depth = group_depth.saturating_sub(1);
+                ')' => {
+                    if depth == 0 {
+                        return Err(invalid("Unmatched ) or \\)"));
+                    }
+                    depth -= 1;
                     result.push(')');
-                    i += 2;
-                }
-                b'{' => {
-                    result.push('{');
-                    i += 2;
                 }
-                b'}' => {
-                    result.push('}');
-                    i += 2;
+                '|' => {
+                    result.push('|');
+                    opens = true;
                 }
-                b'+' => {
-                    result.push('+');
-                    i += 2;
+                '{' if !at_start => {
+                    let close = (i + 1..chars.len())
+                        .find(|&j| chars[j - 1] == '\\' && chars[j] == '}')
+                        .ok_or_else(|| invalid("Unmatched \\{"))?;
+                    let content: String = chars[i..close - 1].iter().collect();
+                    repeat = Some(
+                        parse_interval(&content)
+                            .ok_or_else(|| invalid("Invalid content of \\{\\}"))?,
+                    );
+                    i = close + 1;
                 }
-                b'?' => {
-                    result.push('?');
-                    i += 2;
-                }
-                b'1'..=b'9' => {
-                    // Backreference: \1 through \9
+                '+' | '?' if !at_start => repeat = Some(e.to_string()),
+                '1'..='9' | 'w' | 'W' | 's' | 'S' | 'b' | 'B' => {
                     result.push('\\');
-                    result.push(bytes[i + 1] as char);
-                    i += 2;
-                }
-                b'n' => {
-                    result.push_str("\\n");
-                    i += 2;
-                }
-                b't' => {
-                    result.push_str("\\t");
-                    i += 2;
-                }
-                b'.' => {
-                    if group_depth > 0 {
-                        // Inside a group, \. is a literal dot insertion that
-                        // does not consume input — skip it in the regex.
-                        i += 2;
-                    } else {
-                        result.push('\\');
-                        result.push('.');
-                        i += 2;
-                    }
-                }
-                b'*' | b'\\' | b'[' | b']' | b'^' | b'$' | b'|' => {
-                    result.push('\\');
-                    result.push(bytes[i + 1] as char);
-                    i += 2;
-                }
-                _ => {
-                    // Unknown escape: pass through literally
-                    result.push('\\');
-                    result.push(bytes[i + 1] as char);
-                    i += 2;
+                    result.push(e);
                 }
+                '<' => result.push_str("\\b{start}"),
+                '>' => result.push_str("\\b{end}"),
+                '`' => result.push_str("\\A"),
+                '\'' => result.push_str("\\z"),
+                _ => result.push_str(&regex::escape(e.encode_utf8(&mut [0; 4]))),
             }
         } else {
-            match bytes[i] {
-                b'(' => {
-                    // Literal in BRE
-                    result.push_str("\\(");
-                    i += 1;
-                }
-                b')' => {
-                    // Literal in BRE
-                    result.push_str("\\)");
-                    i += 1;
+            match c {
+                '[' => i = push_bracket(&chars, i, &mut result)?,
+                '*' if !at_start => repeat = Some("*".to_string()),
+                '^' if at_start && !after_anchor => {
+                    result.push('^');
+                    opens = true;
+                    anchor = true;
                 }
-                b'{' => {
-                    // Literal in BRE
-                    result.push_str("\\{");
-                    i += 1;
+                '$' if i == chars.len()
+                    || (chars[i] == '\\' && matches!(chars.get(i + 1), Some(')' | '|'))) =>
+                {
+                    result.push('$')
                 }
-                b'}' => {
-                    // Literal in BRE
-                    result.push_str("\\}");
-                    i += 1;
-                }
-                b'+' => {
-                    // Literal in BRE (not a quantifier)
-                    result.push_str("\\+");
-                    i += 1;
-                }
-                b'?' => {
-                    // Literal in BRE (not a quantifier)
-                    result.push_str("\\?");
-                    i += 1;
-                }
-                b'|' => {
-                    // Literal in BRE (not alternation)
-                    result.push_str("\\|");
-                    i += 1;
-                }
-                _ => {
-                    result.push(bytes[i] as char);
-                    i += 1;
+                '.' => result.push('.'),
+                _ => result.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        match repeat {
+            Some(op) => {
+                if !after_repeat {
+                    result.push_str(&op);
                 }
+                after_repeat = true;
             }
+            None => after_repeat = false,
         }
+        at_start = opens;
+        after_anchor = anchor;
     }
-    result
+    if depth > 0 {
+        return Err(invalid("Unmatched ( or \\("));
+    }
+    result.push(')');
+    Ok(result)
 }
 
-/// Extract a template for the first `\(` ... `\)` group in a BRE pattern.
-/// The template is a list of entries: `true` means a literal dot insertion (from `\.`),
-/// `false` means a character matched from the input.
-/// Returns None if there is no group.
-fn bre_group_template(pattern: &str) -> Option<Vec<bool>> {
-    let bytes = pattern.as_bytes();
-    let mut i = 0;
-    let mut in_group = false;
-    let mut template = Vec::new();
+/// Parse the inside of a `\{...\}` interval into `regex` crate syntax.
+fn parse_interval(content: &str) -> Option<String> {
+    let bound = |s: &str| -> Option<Option<u32>> {
+        if s.is_empty() {
+            return Some(None);
+        }
+        if !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok().filter(|&n| n <= 32767).map(Some)
+    };
+    match content.split_once(',') {
+        None => Some(format!("{{{}}}", bound(content)??)),
+        Some((min, max)) => {
+            let min = bound(min)?.unwrap_or(0);
+            match bound(max)? {
+                Some(max) if max < min => None,
+                Some(max) => Some(format!("{{{},{}}}", min, max)),
+                None => Some(format!("{{{},}}", min)),
+            }
+        }
+    }
+}
 
-    while i < bytes.len() {
-        if bytes[i] == b'\\' && i + 1 < bytes.len() {
-            match bytes[i + 1] {
-                b'(' if !in_group => {
-                    in_group = true;
-                    i += 2;
-                }
-                b')' if in_group => {
-                    return Some(template);
-                }
-                b'.' if in_group => {
-                    // \. inside group = literal dot insertion (not consuming input)
-                    template.push(true);
-                    i += 2;
-                }
-                _ if in_group => {
-                    // Any other escape inside the group consumes a character from input
-                    template.push(false);
-                    i += 2;
+/// Translate the bracket expression whose contents start at `chars[start]`
+/// (just past the `[`), returning the index after its closing `]`.
+/// Backslashes are literal inside a bracket expression.
+fn push_bracket(chars: &[char], start: usize, result: &mut String) -> Result<usize, ExprError> {
+    let unmatched = || ExprError::InvalidRegex("Unmatched [, [^, [:, [., or [=".to_string());
+    let mut i = start;
+    result.push('[');
+    if chars.get(i) == Some(&'^') {
+        result.push('^');
+        i += 1;
+    }
+    let first = i;
+    if first == chars.len() {
+        return Err(ExprError::InvalidRegex(
+            "Invalid regular expression".to_string(),
+        ));
+    }
+    loop {
+        let &c = chars.get(i).ok_or_else(unmatched)?;
+        if c == ']' && i > first {
+            result.push(']');
+            return Ok(i + 1);
+        }
+        if c == '['
+            && let Some(&kind @ (':' | '.' | '=')) = chars.get(i + 1)
+        {
+            let end = (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == kind && chars[j + 1] == ']')
+                .ok_or_else(unmatched)?;
+            let name: String = chars[i + 2..end].iter().collect();
+            if kind == ':' {
+                const CLASSES: [&str; 12] = [
+                    "alnum", "alpha", "blank", "cntrl", "digit", "graph", "lower", "print",
+                    "punct", "space", "upper", "xdigit",
+                ];
+                if !CLASSES.contains(&name.as_str()) {
+                    return Err(ExprError::InvalidRegex(
+                        "Invalid character class name".to_string(),
+                    ));
                 }
-                _ => {
-                    i += 2;
+                result.push_str(&format!("[:{}:]", name));
+            } else {
+                for ch in name.chars() {
+                    push_bracket_char(ch, result);
                 }
             }
-        } else if in_group {
-            // Regular character inside group consumes input
-            template.push(false);
-            i += 1;
+            i = end + 2;
+            continue;
+        }
+        if c == '-' && i > first && chars.get(i + 1).is_some_and(|&n| n != ']') {
+            result.push('-');
         } else {
-            i += 1;
+            push_bracket_char(c, result);
         }
+        i += 1;
     }
-    if in_group { Some(template) } else { None }
 }
 
-/// Check whether a BRE pattern contains `\(` ... `\)` groups.
-fn bre_has_groups(pattern: &str) -> bool {
-    let bytes = pattern.as_bytes();
-    let mut i = 0;
-    while i < bytes.len() {
-        if bytes[i] == b'\\' && i + 1 < bytes.len() && bytes[i + 1] == b'(' {
-            return true;
-        }
-        i += 1;
+/// Push a literal character inside a `regex` crate character class.
+fn push_bracket_char(c: char, result: &mut String) {
+    if c.is_ascii_punctuation() {
+        result.push('\\');
     }
-    false
+    result.push(c);
 }
 
-/// Perform regex match operation.
-/// If the pattern has `\(` ... `\)` groups, returns the first captured group (or empty string).
-/// When the group contains `\.`, literal dots are inserted into the result at those positions
-/// without consuming characters from the input.
-/// Otherwise returns the number of matched characters (or 0).
+/// Perform the `:` match operation, anchored at the start of `string`.
+/// If the pattern has a `\(` ... `\)` group, returns what the first group
+/// captured (or an empty string); otherwise returns the number of matched
+/// characters (or 0).
 fn do_match(string: &str, pattern: &str) -> Result<ExprValue, ExprError> {
-    let has_groups = bre_has_groups(pattern);
-    let rust_pattern = bre_to_rust_regex(pattern);
-
-    let re = Regex::new(&rust_pattern)
+    let re = Regex::new(&bre_to_rust_regex(pattern)?)
         .map_err(|e| ExprError::RegexError(format!("Invalid regular expression: {}", e)))?;
-
-    match re.captures(string) {
-        Some(caps) => {
-            if has_groups {
-                // Return the first captured group, expanded with literal dot insertions
-                match caps.get(1) {
-                    Some(m) => {
-                        let captured = m.as_str();
-                        if let Some(template) = bre_group_template(pattern) {
-                            let mut result = String::new();
-                            let mut char_iter = captured.chars();
-                            for is_literal_dot in &template {
-                                if *is_literal_dot {
-                                    result.push('.');
-                                } else if let Some(ch) = char_iter.next() {
-                                    result.push(ch);
-                                }
-                            }
-                            Ok(ExprValue::Str(result))
-                        } else {
-                            Ok(ExprValue::Str(captured.to_string()))
-                        }
-                    }
-                    None => Ok(ExprValue::Str(String::new())),
-                }
-            } else {
-                // Return the number of matched characters
-                let m = caps.get(0).unwrap();
-                Ok(ExprValue::Integer(m.as_str().len() as i64))
-            }
+    let has_groups = re.captures_len() > 1;
+    Ok(match re.captures(string) {
+        Some(caps) if has_groups => {
+            ExprValue::Str(caps.get(1).map_or("", |m| m.as_str()).to_string())
         }
-        None => {
-            if has_groups {
-                Ok(ExprValue::Str(String::new()))
-            } else {
-                Ok(ExprValue::Integer(0))
-            }
-        }
-    }
+        Some(caps) => ExprValue::Integer(caps[0].len() as i64),
+        None if has_groups => ExprValue::Str(String::new()),
+        None => ExprValue::Integer(0),
+    })
 }
 
 /// Perform the substr operation: extract a substring.
@@ -667,7 +541,7 @@ pub fn evaluate_expr(args: &[String]) -> Result<ExprValue, ExprError> {
         return Err(ExprError::MissingOperand);
     }
     let mut parser = ExprParser::new(args);
-    let result = parser.parse_or()?;
+    let result = parser.parse_binary(1, true)?;
     if parser.pos < parser.args.len() {
         return Err(ExprError::Syntax(format!(
             "unexpected argument '{}'",
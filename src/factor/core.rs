@@ -30,32 +30,81 @@ const PRIMES_TO_997: [u64; 168] = [
 
 // ── u64 fast path ────────────────────────────────────────────────────────
 
-/// Modular multiplication for u64 using u128 intermediate.
-/// Hardware mul + div on u128 is still much faster than software u128×u128.
-#[inline(always)]
-fn mod_mul_u64(a: u64, b: u64, m: u64) -> u64 {
-    ((a as u128) * (b as u128) % (m as u128)) as u64
+/// Montgomery arithmetic modulo an odd u64 (R = 2^64).
+/// Replaces the u128 `%` (a software __umodti3 call) in the Miller-Rabin and
+/// Pollard rho inner loops with two multiplications and a subtraction.
+#[derive(Clone, Copy)]
+struct Montgomery {
+    n: u64,
+    /// n⁻¹ mod R, which cancels the low half of a product in `reduce`.
+    n_inv: u64,
+    /// R² mod n, for converting into Montgomery form.
+    r2: u64,
+    /// R mod n, i.e. 1 in Montgomery form.
+    one: u64,
 }
 
-/// Modular exponentiation for u64.
-#[inline]
-fn mod_pow_u64(mut base: u64, mut exp: u64, m: u64) -> u64 {
-    if m == 1 {
-        return 0;
+impl Montgomery {
+    fn new(n: u64) -> Self {
+        debug_assert!(n & 1 == 1);
+        // Newton's iteration doubles the correct low bits each step; an odd n
+        // is its own inverse modulo 8, so five steps give all 64 bits.
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let one = ((1u128 << 64) % n as u128) as u64;
+        let r2 = ((one as u128 * one as u128) % n as u128) as u64;
+        Montgomery { n, n_inv, r2, one }
+    }
+
+    /// Compute t·R⁻¹ mod n for t < n·R.
+    #[inline(always)]
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn_hi = ((m as u128 * self.n as u128) >> 64) as u64;
+        let t_hi = (t >> 64) as u64;
+        // The low halves of t and m·n are equal, so only the high halves differ.
+        if t_hi < mn_hi {
+            t_hi.wrapping_sub(mn_hi).wrapping_add(self.n)
+        } else {
+            t_hi - mn_hi
+        }
     }
-    let mut result: u64 = 1;
-    base %= m;
-    while exp > 0 {
-        if exp & 1 == 1 {
-            result = mod_mul_u64(result, base, m);
+
+    #[inline(always)]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    #[inline(always)]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let (sum, overflow) = a.overflowing_add(b);
+        if overflow || sum >= self.n {
+            sum.wrapping_sub(self.n)
+        } else {
+            sum
         }
-        exp >>= 1;
-        base = mod_mul_u64(base, base, m);
     }
-    result
+
+    fn encode(&self, a: u64) -> u64 {
+        self.mul(a % self.n, self.r2)
+    }
+
+    fn pow(&self, mut base: u64, mut exp: u64) -> u64 {
+        let mut result = self.one;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            exp >>= 1;
+            base = self.mul(base, base);
+        }
+        result
+    }
 }
 
-/// Deterministic Miller-Rabin for u64. Uses minimal witness set.
+/// Deterministic Miller-Rabin for u64.
 fn is_prime_u64(n: u64) -> bool {
     if n < 2 {
         return false;
@@ -79,32 +128,24 @@ fn is_prime_u64(n: u64) -> bool {
         return true; // All composites < 53² have a prime factor ≤ 47
     }
 
-    let mut d = n - 1;
-    let mut r = 0u32;
-    while d.is_multiple_of(2) {
-        d /= 2;
-        r += 1;
-    }
-
-    // Witnesses sufficient for all n < 3,215,031,751: {2, 3, 5, 7}
-    // For all n < 3,317,044,064,679,887,385,961,981: {2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37}
-    let witnesses: &[u64] = if n < 3_215_031_751 {
-        &[2, 3, 5, 7]
-    } else {
-        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]
-    };
+    let r = (n - 1).trailing_zeros();
+    let d = (n - 1) >> r;
+    let mont = Montgomery::new(n);
+    let minus_one = n - mont.one;
 
-    'witness: for &a in witnesses {
-        if a >= n {
+    // Jim Sinclair's seven bases are a deterministic witness set for all of
+    // u64. A base that is a multiple of n says nothing, so it is skipped.
+    'witness: for a in [2, 325, 9375, 28178, 450775, 9780504, 1795265022] {
+        if a % n == 0 {
             continue;
         }
-        let mut x = mod_pow_u64(a, d, n);
-        if x == 1 || x == n - 1 {
+        let mut x = mont.pow(mont.encode(a), d);
+        if x == mont.one || x == minus_one {
             continue;
         }
-        for _ in 0..r - 1 {
-            x = mod_mul_u64(x, x, n);
-            if x == n - 1 {
+        for _ in 1..r {
+            x = mont.mul(x, x);
+            if x == minus_one {
                 continue 'witness;
             }
         }
@@ -137,32 +178,35 @@ fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
 }
 
 /// Pollard's rho for u64 with Brent's variant + batch GCD.
+/// Iterates x ↦ x² + c in Montgomery form: the map is still a polynomial
+/// modulo every factor of n, and gcd(x·R, n) = gcd(x, n) as n is odd.
 fn pollard_rho_u64(n: u64) -> u64 {
     if n.is_multiple_of(2) {
         return 2;
     }
+    let mont = Montgomery::new(n);
 
-    for c_offset in 1u64..n {
-        let c = c_offset;
-        let mut x: u64 = c_offset.wrapping_mul(6364136223846793005).wrapping_add(1) % n;
+    for c in 1u64..n {
+        let f = |v: u64| mont.add(mont.mul(v, v), c);
+        let mut x: u64 = c.wrapping_mul(6364136223846793005).wrapping_add(1) % n;
         let mut y = x;
         let mut ys = x;
-        let mut q: u64 = 1;
+        let mut q: u64 = mont.one;
         let mut r: u64 = 1;
         let mut d: u64 = 1;
 
         while d == 1 {
             x = y;
             for _ in 0..r {
-                y = (mod_mul_u64(y, y, n)).wrapping_add(c) % n;
+                y = f(y);
             }
             let mut k: u64 = 0;
             while k < r && d == 1 {
                 ys = y;
                 let m = (r - k).min(128);
                 for _ in 0..m {
-                    y = (mod_mul_u64(y, y, n)).wrapping_add(c) % n;
-                    q = mod_mul_u64(q, x.abs_diff(y), n);
+                    y = f(y);
+                    q = mont.mul(q, x.abs_diff(y));
                 }
                 d = gcd_u64(q, n);
                 k += m;
@@ -171,8 +215,9 @@ fn pollard_rho_u64(n: u64) -> u64 {
         }
 
         if d == n {
+            // The batch overshot; retrace one step at a time from its start.
             loop {
-                ys = (mod_mul_u64(ys, ys, n)).wrapping_add(c) % n;
+                ys = f(ys);
                 d = gcd_u64(x.abs_diff(ys), n);
                 if d > 1 {
                     break;
@@ -1617,7 +1617,7 @@ pub fn blake2b_hash_files_parallel(
 
     // Sort largest first: ensures big files start hashing immediately while
     // small files fill in gaps, minimizing tail latency.
-    indexed.sort_by(|a, b| b.2.cmp(&a.2));
+    indexed.sort_by_key(|e| std::cmp::Reverse(e.2));
 
     // Warm page cache for the largest files using async readahead(2).
     // Each hash call handles its own mmap prefaulting, but issuing readahead
@@ -1752,7 +1752,7 @@ pub fn hash_files_parallel(paths: &[&Path], algo: HashAlgorithm) -> Vec<io::Resu
 
     // Sort largest first: ensures big files start hashing immediately while
     // small files fill in gaps, minimizing tail latency.
-    indexed.sort_by(|a, b| b.2.cmp(&a.2));
+    indexed.sort_by_key(|e| std::cmp::Reverse(e.2));
 
     // Warm page cache for the largest files using async readahead(2).
     // Each hash call handles its own mmap prefaulting, but issuing readahead
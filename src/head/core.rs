@@ -3,7 +3,7 @@ use std::path::Path;
 
 use memchr::{memchr_iter, memrchr_iter};
 
-use crate::common::io::{FileData, read_file, read_stdin};
+use crate::common::io::{FileData, open_noatime, read_opened_file, read_stdin};
 
 /// Mode for head operation
 #[derive(Clone, Debug)]
@@ -23,6 +23,10 @@ pub enum HeadMode {
 pub struct HeadConfig {
     pub mode: HeadMode,
     pub zero_terminated: bool,
+    /// -v: always print `==> NAME <==` headers
+    pub verbose: bool,
+    /// -q: never print headers
+    pub quiet: bool,
 }
 
 impl Default for HeadConfig {
@@ -30,80 +34,100 @@ impl Default for HeadConfig {
         Self {
             mode: HeadMode::Lines(10),
             zero_terminated: false,
+            verbose: false,
+            quiet: false,
         }
     }
 }
 
-/// Parse a numeric argument with optional suffix (K, M, G, etc.)
-/// Supports: b(512), kB(1000), K(1024), MB(1e6), M(1048576), GB(1e9), G(1<<30),
-/// TB, T, PB, P, EB, E, ZB, Z, YB, Y
-pub fn parse_size(s: &str) -> Result<u64, String> {
-    let s = s.trim();
-    if s.is_empty() {
-        return Err("empty size".to_string());
-    }
-
-    // Find where the numeric part ends
-    let mut num_end = 0;
-    for (i, c) in s.char_indices() {
-        if c.is_ascii_digit() || (i == 0 && (c == '+' || c == '-')) {
-            num_end = i + c.len_utf8();
+impl HeadConfig {
+    /// Whether file name headers are printed for `file_count` inputs:
+    /// only with several files unless -v or -q says otherwise.
+    pub fn show_headers(&self, file_count: usize) -> bool {
+        if self.quiet {
+            false
         } else {
-            break;
+            self.verbose || file_count > 1
         }
     }
+}
 
-    if num_end == 0 {
-        return Err(format!("invalid number: '{}'", s));
+/// Write the `==> NAME <==` header, separated from the previous file's
+/// output by a blank line unless this is the first header.
+pub fn write_header(out: &mut impl Write, filename: &str, first: bool) -> io::Result<()> {
+    let name = if filename == "-" {
+        "standard input"
+    } else {
+        filename
+    };
+    if !first {
+        out.write_all(b"\n")?;
     }
+    writeln!(out, "==> {} <==", name)
+}
 
-    let num_str = &s[..num_end];
-    let suffix = &s[num_end..];
-
-    let num: u64 = match num_str.parse() {
-        Ok(n) => n,
-        Err(_) => {
-            // If the string is valid digits but overflows u64, clamp to u64::MAX
-            // like GNU coreutils does for huge counts
-            let digits = num_str
-                .strip_prefix('+')
-                .or_else(|| num_str.strip_prefix('-'))
-                .unwrap_or(num_str);
-            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
-                u64::MAX
-            } else {
-                return Err(format!("invalid number: '{}'", num_str));
-            }
-        }
-    };
+/// Why a size argument was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeError {
+    /// Not a number, or an unknown suffix
+    Invalid,
+    /// The value does not fit in 64 bits
+    TooLarge,
+}
 
-    let multiplier: u64 = match suffix {
-        "" => 1,
-        "b" => 512,
-        "kB" => 1000,
-        "k" | "K" | "KiB" => 1024,
-        "MB" => 1_000_000,
-        "M" | "MiB" => 1_048_576,
-        "GB" => 1_000_000_000,
-        "G" | "GiB" => 1_073_741_824,
-        "TB" => 1_000_000_000_000,
-        "T" | "TiB" => 1_099_511_627_776,
-        "PB" => 1_000_000_000_000_000,
-        "P" | "PiB" => 1_125_899_906_842_624,
-        "EB" => 1_000_000_000_000_000_000,
-        "E" | "EiB" => 1_152_921_504_606_846_976,
-        // ZB/Z/YB/Y would overflow u64, treat as max
-        "ZB" | "Z" | "ZiB" | "YB" | "Y" | "YiB" => {
-            if num > 0 {
-                return Ok(u64::MAX);
+/// Parse a numeric argument with optional suffix, like GNU's xstrtoumax.
+/// A unit letter (b, k/K, m/M, G, T, P, E, Z, Y) alone or followed by "iB"
+/// scales by powers of 1024; followed by "B" it scales by powers of 1000.
+/// `b` (512) takes no second suffix.
+pub fn parse_size(s: &str) -> Result<u64, SizeError> {
+    let s = s.trim_start();
+    let digits = s.strip_prefix('+').unwrap_or(s);
+    let num_end = digits.bytes().take_while(u8::is_ascii_digit).count();
+    if num_end == 0 {
+        return Err(SizeError::Invalid);
+    }
+    let (num_str, suffix) = digits.split_at(num_end);
+
+    let mut suffix_chars = suffix.chars();
+    let (exponent, base) = match suffix_chars.next() {
+        None => (0, 1024),
+        Some(unit) => {
+            let exponent = match unit {
+                'b' => {
+                    if !suffix_chars.as_str().is_empty() {
+                        return Err(SizeError::Invalid);
+                    }
+                    return scale(num_str, 512, 1);
+                }
+                'k' | 'K' => 1,
+                'm' | 'M' => 2,
+                'G' => 3,
+                'T' => 4,
+                'P' => 5,
+                'E' => 6,
+                'Z' => 7,
+                'Y' => 8,
+                _ => return Err(SizeError::Invalid),
+            };
+            match suffix_chars.as_str() {
+                "" | "iB" => (exponent, 1024),
+                "B" | "D" => (exponent, 1000),
+                _ => return Err(SizeError::Invalid),
             }
-            return Ok(0);
         }
-        _ => return Err(format!("invalid suffix in '{}'", s)),
     };
+    scale(num_str, base, exponent)
+}
 
-    num.checked_mul(multiplier)
-        .ok_or_else(|| format!("number too large: '{}'", s))
+/// Multiply the decimal `num_str` by `base` raised to `exponent`.
+fn scale(num_str: &str, base: u64, exponent: u32) -> Result<u64, SizeError> {
+    let num: u64 = num_str.parse().map_err(|_| SizeError::TooLarge)?;
+    if num == 0 {
+        return Ok(0);
+    }
+    (0..exponent)
+        .try_fold(num, |n, _| n.checked_mul(base))
+        .ok_or(SizeError::TooLarge)
 }
 
 /// Output first N lines from data
@@ -182,15 +206,7 @@ pub fn head_bytes_from_end(data: &[u8], n: u64, out: &mut impl Write) -> io::Res
 
 /// Use sendfile for zero-copy byte output on Linux
 #[cfg(target_os = "linux")]
-pub fn sendfile_bytes(path: &Path, n: u64, out_fd: i32) -> io::Result<bool> {
-    use std::os::unix::fs::OpenOptionsExt;
-
-    let file = std::fs::OpenOptions::new()
-        .read(true)
-        .custom_flags(libc::O_NOATIME)
-        .open(path)
-        .or_else(|_| std::fs::File::open(path))?;
-
+pub fn sendfile_bytes(file: &std::fs::File, n: u64, out_fd: i32) -> io::Result<bool> {
     let metadata = file.metadata()?;
     let file_size = metadata.len();
     let to_send = n.min(file_size) as usize;
@@ -228,7 +244,7 @@ pub fn sendfile_bytes(path: &Path, n: u64, out_fd: i32) -> io::Result<bool> {
 /// This is the critical fast path: `head -n 10` on a 100MB file
 /// reads only a few KB instead of mapping all 100MB.
 fn head_lines_streaming_file(
-    path: &Path,
+    mut file: std::fs::File,
     n: u64,
     delimiter: u8,
     out: &mut impl Write,
@@ -237,19 +253,6 @@ fn head_lines_streaming_file(
         return Ok(true);
     }
 
-    #[cfg(target_os = "linux")]
-    let file = {
-        use std::os::unix::fs::OpenOptionsExt;
-        std::fs::OpenOptions::new()
-            .read(true)
-            .custom_flags(libc::O_NOATIME)
-            .open(path)
-            .or_else(|_| std::fs::File::open(path))?
-    };
-    #[cfg(not(target_os = "linux"))]
-    let file = std::fs::File::open(path)?;
-
-    let mut file = file;
     let mut buf = [0u8; 65536];
     let mut count = 0u64;
 
@@ -278,85 +281,106 @@ fn head_lines_streaming_file(
 }
 
 /// Process a single file/stdin for head
+///
+/// When `first_header` is given, a header is written once the file has been
+/// opened (so unreadable files get none); the flag is cleared afterwards so
+/// later headers are preceded by a blank line.
 pub fn head_file(
     filename: &str,
     config: &HeadConfig,
     out: &mut impl Write,
     tool_name: &str,
+    first_header: Option<&mut bool>,
 ) -> io::Result<bool> {
     let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
 
-    if filename != "-" {
-        let path = Path::new(filename);
-
-        // Fast paths that avoid reading/mmapping the whole file
-        match &config.mode {
-            HeadMode::Lines(n) => {
-                // Streaming: read small chunks, stop after N lines
-                match head_lines_streaming_file(path, *n, delimiter, out) {
-                    Ok(true) => return Ok(true),
-                    Err(e) => {
-                        eprintln!(
-                            "{}: cannot open '{}' for reading: {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                    _ => {}
-                }
-            }
-            HeadMode::Bytes(n) => {
-                // sendfile: zero-copy, reads only N bytes
-                #[cfg(target_os = "linux")]
-                {
-                    use std::os::unix::io::AsRawFd;
-                    let stdout = io::stdout();
-                    let out_fd = stdout.as_raw_fd();
-                    if let Ok(true) = sendfile_bytes(path, *n, out_fd) {
-                        return Ok(true);
-                    }
-                }
-                // Non-Linux: still avoid full mmap
-                #[cfg(not(target_os = "linux"))]
-                {
-                    if let Ok(true) = head_bytes_streaming_file(path, *n, out) {
-                        return Ok(true);
-                    }
-                }
-            }
-            _ => {
-                // LinesFromEnd and BytesFromEnd need the whole file — use mmap
-            }
-        }
-    }
-
-    // Slow path: read entire file (needed for -n -N, -c -N, or stdin)
-    let data: FileData = if filename == "-" {
-        match read_stdin() {
-            Ok(d) => FileData::Owned(d),
+    // Open once: every read path below works on this descriptor.
+    let file = if filename == "-" {
+        None
+    } else {
+        match open_noatime(Path::new(filename)) {
+            Ok(file) => Some(file),
             Err(e) => {
                 eprintln!(
-                    "{}: standard input: {}",
+                    "{}: cannot open '{}' for reading: {}",
                     tool_name,
+                    filename,
                     crate::common::io_error_msg(&e)
                 );
                 return Ok(false);
             }
         }
-    } else {
-        match read_file(Path::new(filename)) {
-            Ok(d) => d,
+    };
+
+    if let Some(first) = first_header {
+        write_header(out, filename, *first)?;
+        *first = false;
+    }
+
+    let read_error = |e: io::Error| {
+        eprintln!(
+            "{}: error reading '{}': {}",
+            tool_name,
+            filename,
+            crate::common::io_error_msg(&e)
+        );
+        Ok(false)
+    };
+
+    let data: FileData = match file {
+        None => match read_stdin() {
+            Ok(d) => FileData::Owned(d),
             Err(e) => {
                 eprintln!(
-                    "{}: cannot open '{}' for reading: {}",
+                    "{}: standard input: {}",
                     tool_name,
-                    filename,
                     crate::common::io_error_msg(&e)
                 );
                 return Ok(false);
             }
+        },
+        Some(file) => {
+            // Fast paths that avoid reading/mmapping the whole file
+            match &config.mode {
+                HeadMode::Lines(n) => {
+                    // Streaming: read small chunks, stop after N lines
+                    return match head_lines_streaming_file(file, *n, delimiter, out) {
+                        Ok(_) => Ok(true),
+                        Err(e) => read_error(e),
+                    };
+                }
+                HeadMode::Bytes(n) => {
+                    // sendfile: zero-copy, reads only N bytes
+                    #[cfg(target_os = "linux")]
+                    {
+                        use std::os::unix::io::AsRawFd;
+                        // sendfile bypasses `out`, so emit anything buffered first
+                        out.flush()?;
+                        let stdout = io::stdout();
+                        let out_fd = stdout.as_raw_fd();
+                        if let Ok(true) = sendfile_bytes(&file, *n, out_fd) {
+                            return Ok(true);
+                        }
+                    }
+                    // Non-Linux: still avoid full mmap
+                    #[cfg(not(target_os = "linux"))]
+                    {
+                        return match head_bytes_streaming_file(file, *n, out) {
+                            Ok(_) => Ok(true),
+                            Err(e) => read_error(e),
+                        };
+                    }
+                }
+                _ => {
+                    // LinesFromEnd and BytesFromEnd need the whole file — use mmap
+                }
+            }
+            // Slow path: read entire file (needed for -n -N, -c -N, or when
+            // sendfile cannot write to stdout)
+            match read_opened_file(file) {
+                Ok(d) => d,
+                Err(e) => return read_error(e),
+            }
         }
     };
 
@@ -372,8 +396,11 @@ pub fn head_file(
 
 /// Streaming head for positive byte count on non-Linux.
 #[cfg(not(target_os = "linux"))]
-fn head_bytes_streaming_file(path: &Path, n: u64, out: &mut impl Write) -> io::Result<bool> {
-    let mut file = std::fs::File::open(path)?;
+fn head_bytes_streaming_file(
+    mut file: std::fs::File,
+    n: u64,
+    out: &mut impl Write,
+) -> io::Result<bool> {
     let mut remaining = n as usize;
     let mut buf = [0u8; 65536];
 
@@ -1,6 +1,6 @@
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Component, Path};
 
 /// Backup mode for destination files (shared with mv).
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -136,6 +136,12 @@ pub fn install_file(src: &Path, dst: &Path, config: &InstallConfig) -> io::Resul
         }
     }
 
+    // Compare if -C: leave an up-to-date destination (and its timestamps)
+    // alone, before any backup is made.
+    if config.compare && !need_copy(src, dst, config) {
+        return Ok(());
+    }
+
     // Handle backup
     if dst.exists() {
         if let Some(ref mode) = config.backup {
@@ -144,13 +150,6 @@ pub fn install_file(src: &Path, dst: &Path, config: &InstallConfig) -> io::Resul
         }
     }
 
-    // Compare if -C: skip copy if files are identical
-    if config.compare && dst.exists() {
-        if files_are_identical(src, dst)? {
-            return Ok(());
-        }
-    }
-
     // Copy file — use optimized path on Linux
     #[cfg(target_os = "linux")]
     {
@@ -191,30 +190,142 @@ pub fn install_file(src: &Path, dst: &Path, config: &InstallConfig) -> io::Resul
     Ok(())
 }
 
-/// Create directories (install -d).
-pub fn install_directories(dirs: &[&Path], config: &InstallConfig) -> io::Result<()> {
-    for dir in dirs {
-        // Normalize the path to handle trailing "." (e.g. "d1/.") which
-        // causes create_dir_all to fail on Linux.
-        let normalized: std::path::PathBuf = dir.components().collect();
-        let target = if normalized.as_os_str().is_empty() {
-            dir
-        } else {
-            normalized.as_path()
-        };
-        fs::create_dir_all(target)?;
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(target, fs::Permissions::from_mode(config.mode))?;
+/// Create `dir` and any missing parents (install -d), then apply the
+/// configured owner, group and mode to `dir` itself. Parents are created
+/// with default permissions. An existing directory is not an error.
+///
+/// Returns a GNU-style diagnostic naming the path that failed.
+pub fn install_directory(dir: &Path, config: &InstallConfig) -> Result<(), String> {
+    let components: Vec<Component> = dir.components().collect();
+    if components.is_empty() {
+        let e = io::Error::from_raw_os_error(libc::ENOENT);
+        return Err(cannot_create(dir, &e));
+    }
+    let mut prefix = std::path::PathBuf::new();
+    for (k, component) in components.iter().enumerate() {
+        prefix.push(component);
+        if !matches!(component, Component::Normal(_)) {
+            continue;
         }
-        if config.verbose {
-            eprintln!("creating directory '{}'", dir.display());
+        match fs::create_dir(&prefix) {
+            Ok(()) => {
+                if config.verbose {
+                    println!("install: creating directory '{}'", prefix.display());
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if prefix.is_dir() {
+                    continue;
+                }
+                // A non-directory in the middle of the path is reported
+                // like mkdir(2) would for the full path.
+                if k + 1 < components.len() {
+                    return Err(cannot_create(
+                        &prefix,
+                        &io::Error::from_raw_os_error(libc::ENOTDIR),
+                    ));
+                }
+                return Err(cannot_create(&prefix, &e));
+            }
+            Err(e) => return Err(cannot_create(&prefix, &e)),
         }
     }
+
+    // chown before chmod: changing ownership may clear set-id bits.
+    #[cfg(unix)]
+    if config.owner.is_some() || config.group.is_some() {
+        set_ownership(dir, &config.owner, &config.group).map_err(|e| {
+            format!(
+                "cannot change ownership of '{}': {}",
+                dir.display(),
+                crate::common::io_error_msg(&e)
+            )
+        })?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(config.mode)).map_err(|e| {
+            format!(
+                "cannot change permissions of '{}': {}",
+                dir.display(),
+                crate::common::io_error_msg(&e)
+            )
+        })?;
+    }
     Ok(())
 }
 
+fn cannot_create(path: &Path, e: &io::Error) -> String {
+    format!(
+        "cannot create directory '{}': {}",
+        path.display(),
+        crate::common::io_error_msg(e)
+    )
+}
+
+/// Check that the configured owner and group name existing accounts, so
+/// bad names are rejected before anything is created.
+#[cfg(unix)]
+pub fn validate_ownership(config: &InstallConfig) -> Result<(), String> {
+    if let Some(ref name) = config.owner {
+        resolve_uid(name).map_err(|_| format!("invalid user '{}'", name))?;
+    }
+    if let Some(ref name) = config.group {
+        resolve_gid(name).map_err(|_| format!("invalid group '{}'", name))?;
+    }
+    Ok(())
+}
+
+/// Whether `mode` has bits beyond the permission bits (set-user-ID,
+/// set-group-ID or sticky), which make -C always copy.
+pub fn has_extra_mode_bits(mode: u32) -> bool {
+    mode & !0o777 != 0
+}
+
+/// Decide whether -C has to copy `src` to `dst`. Only two regular files
+/// (symlinks are not followed) whose contents match, with the destination
+/// already carrying the mode, owner and group install would give it, are
+/// left alone. Anything that cannot be checked is copied.
+#[cfg(unix)]
+fn need_copy(src: &Path, dst: &Path, config: &InstallConfig) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    if has_extra_mode_bits(config.mode) {
+        return true;
+    }
+    let (Ok(src_meta), Ok(dst_meta)) = (fs::symlink_metadata(src), fs::symlink_metadata(dst))
+    else {
+        return true;
+    };
+    if !src_meta.is_file() || !dst_meta.is_file() {
+        return true;
+    }
+    if src_meta.len() != dst_meta.len() || dst_meta.mode() & 0o7777 != config.mode {
+        return true;
+    }
+
+    // Without -o/-g the copy would belong to the invoking user and group.
+    let uid = match config.owner {
+        Some(ref name) => resolve_uid(name).ok(),
+        None => Some(unsafe { libc::getuid() }),
+    };
+    let gid = match config.group {
+        Some(ref name) => resolve_gid(name).ok(),
+        None => Some(unsafe { libc::getgid() }),
+    };
+    if uid != Some(dst_meta.uid()) || gid != Some(dst_meta.gid()) {
+        return true;
+    }
+
+    !files_are_identical(src, dst).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn need_copy(src: &Path, dst: &Path, _config: &InstallConfig) -> bool {
+    !files_are_identical(src, dst).unwrap_or(false)
+}
+
 /// Check if two files have identical contents.
 fn files_are_identical(a: &Path, b: &Path) -> io::Result<bool> {
     let meta_a = fs::metadata(a)?;
@@ -280,7 +391,7 @@ fn resolve_uid(name: &str) -> io::Result<u32> {
     if pw.is_null() {
         Err(io::Error::new(
             io::ErrorKind::NotFound,
-            format!("invalid user: '{}'", name),
+            format!("invalid user '{}'", name),
         ))
     } else {
         Ok(unsafe { (*pw).pw_uid })
@@ -299,7 +410,7 @@ fn resolve_gid(name: &str) -> io::Result<u32> {
     if gr.is_null() {
         Err(io::Error::new(
             io::ErrorKind::NotFound,
-            format!("invalid group: '{}'", name),
+            format!("invalid group '{}'", name),
         ))
     } else {
         Ok(unsafe { (*gr).gr_gid })
@@ -20,10 +20,10 @@ pub enum OutputSpec {
 
 /// Configuration for the join command.
 pub struct JoinConfig {
-    /// Join field for file 1 (0-indexed)
-    pub field1: usize,
-    /// Join field for file 2 (0-indexed)
-    pub field2: usize,
+    /// Join fields for file 1 (0-indexed); several make a composite key
+    pub fields1: Vec<usize>,
+    /// Join fields for file 2 (0-indexed), as many as for file 1
+    pub fields2: Vec<usize>,
     /// Also print unpairable lines from file 1 (-a 1)
     pub print_unpaired1: bool,
     /// Also print unpairable lines from file 2 (-a 2)
@@ -53,8 +53,8 @@ pub struct JoinConfig {
 impl Default for JoinConfig {
     fn default() -> Self {
         Self {
-            field1: 0,
-            field2: 0,
+            fields1: vec![0],
+            fields2: vec![0],
             print_unpaired1: false,
             print_unpaired2: false,
             only_unpaired1: false,
@@ -177,12 +177,28 @@ fn extract_field<'a>(line: &'a [u8], field_index: usize, separator: Option<u8>)
     }
 }
 
+/// Build a composite join key from several fields. The components are
+/// joined with NUL, which sorts below every other byte, so comparing the
+/// keys compares the fields in turn and "ab","c" never equals "a","bc".
+fn composite_key(line: &[u8], fields: &[usize], separator: Option<u8>) -> Vec<u8> {
+    let mut key = Vec::new();
+    for (n, &field) in fields.iter().enumerate() {
+        if n > 0 {
+            key.push(b'\0');
+        }
+        key.extend_from_slice(extract_field(line, field, separator));
+    }
+    key
+}
+
 /// Compare two keys, optionally case-insensitive.
+/// Case folding maps to upper case like GNU's memcasecmp, so `-i` agrees
+/// with the order produced by `sort -f` for bytes between 'Z' and 'a'.
 #[inline]
 fn compare_keys(a: &[u8], b: &[u8], case_insensitive: bool) -> Ordering {
     if case_insensitive {
         for (&ca, &cb) in a.iter().zip(b.iter()) {
-            match ca.to_ascii_lowercase().cmp(&cb.to_ascii_lowercase()) {
+            match ca.to_ascii_uppercase().cmp(&cb.to_ascii_uppercase()) {
                 Ordering::Equal => continue,
                 other => return other,
             }
@@ -193,32 +209,79 @@ fn compare_keys(a: &[u8], b: &[u8], case_insensitive: bool) -> Ordering {
     }
 }
 
+/// Write one output field, substituting the -e filler for an empty field
+/// as GNU does, whether the field is missing or present but empty.
+#[inline]
+fn push_field(buf: &mut Vec<u8>, field: &[u8], empty: &[u8]) {
+    if field.is_empty() {
+        buf.extend_from_slice(empty);
+    } else {
+        buf.extend_from_slice(field);
+    }
+}
+
+/// Write the join field(s) of a line, separated by out_sep.
+#[inline]
+fn push_key(
+    buf: &mut Vec<u8>,
+    line: &[u8],
+    key_fields: &[usize],
+    separator: Option<u8>,
+    empty: &[u8],
+    out_sep: u8,
+) {
+    for (n, &field) in key_fields.iter().enumerate() {
+        if n > 0 {
+            buf.push(out_sep);
+        }
+        push_field(buf, extract_field(line, field, separator), empty);
+    }
+}
+
+/// Write the join field(s) from an already split line, separated by out_sep.
+#[inline]
+fn push_key_fields(
+    buf: &mut Vec<u8>,
+    fields: &[&[u8]],
+    key_fields: &[usize],
+    empty: &[u8],
+    out_sep: u8,
+) {
+    for (n, &field) in key_fields.iter().enumerate() {
+        if n > 0 {
+            buf.push(out_sep);
+        }
+        push_field(buf, fields.get(field).copied().unwrap_or(b""), empty);
+    }
+}
+
 /// Write a paired output line (default format: join_key + other fields).
 /// Zero-copy: writes directly from line slices without allocating field Vecs.
 fn write_paired_default_zerocopy(
     line1: &[u8],
     line2: &[u8],
-    join_key: &[u8],
-    field1: usize,
-    field2: usize,
+    fields1: &[usize],
+    fields2: &[usize],
     separator: Option<u8>,
+    empty: &[u8],
     out_sep: u8,
     delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    buf.extend_from_slice(join_key);
-    write_other_fields(line1, field1, separator, out_sep, buf);
-    write_other_fields(line2, field2, separator, out_sep, buf);
+    push_key(buf, line1, fields1, separator, empty, out_sep);
+    write_other_fields(line1, fields1, separator, empty, out_sep, buf);
+    write_other_fields(line2, fields2, separator, empty, out_sep, buf);
     buf.push(delim);
 }
 
-/// Write all fields from a line except the join field, prefixed by out_sep.
+/// Write all fields from a line except the join fields, prefixed by out_sep.
 /// Avoids allocating a Vec<&[u8]> for field splitting.
 #[inline]
 fn write_other_fields(
     line: &[u8],
-    skip_field: usize,
+    skip_fields: &[usize],
     separator: Option<u8>,
+    empty: &[u8],
     out_sep: u8,
     buf: &mut Vec<u8>,
 ) {
@@ -226,17 +289,17 @@ fn write_other_fields(
         let mut field_idx = 0;
         let mut start = 0;
         for pos in memchr::memchr_iter(sep, line) {
-            if field_idx != skip_field {
+            if !skip_fields.contains(&field_idx) {
                 buf.push(out_sep);
-                buf.extend_from_slice(&line[start..pos]);
+                push_field(buf, &line[start..pos], empty);
             }
             field_idx += 1;
             start = pos + 1;
         }
         // Last field (no trailing separator)
-        if field_idx != skip_field {
+        if !skip_fields.contains(&field_idx) {
             buf.push(out_sep);
-            buf.extend_from_slice(&line[start..]);
+            push_field(buf, &line[start..], empty);
         }
     } else {
         // Whitespace-delimited
@@ -254,7 +317,7 @@ fn write_other_fields(
             while i < len && line[i] != b' ' && line[i] != b'\t' {
                 i += 1;
             }
-            if field_idx != skip_field {
+            if !skip_fields.contains(&field_idx) {
                 buf.push(out_sep);
                 buf.extend_from_slice(&line[start..i]);
             }
@@ -267,7 +330,7 @@ fn write_other_fields(
 fn write_paired_format(
     fields1: &[&[u8]],
     fields2: &[&[u8]],
-    join_key: &[u8],
+    key_fields1: &[usize],
     specs: &[OutputSpec],
     empty: &[u8],
     out_sep: u8,
@@ -279,14 +342,10 @@ fn write_paired_format(
             buf.push(out_sep);
         }
         match spec {
-            OutputSpec::JoinField => buf.extend_from_slice(join_key),
+            OutputSpec::JoinField => push_key_fields(buf, fields1, key_fields1, empty, out_sep),
             OutputSpec::FileField(file_num, field_idx) => {
                 let fields = if *file_num == 0 { fields1 } else { fields2 };
-                if let Some(f) = fields.get(*field_idx) {
-                    buf.extend_from_slice(f);
-                } else {
-                    buf.extend_from_slice(empty);
-                }
+                push_field(buf, fields.get(*field_idx).copied().unwrap_or(b""), empty);
             }
         }
     }
@@ -296,15 +355,15 @@ fn write_paired_format(
 /// Write an unpaired output line (default format), zero-copy from line.
 fn write_unpaired_default_zerocopy(
     line: &[u8],
-    join_field: usize,
+    join_fields: &[usize],
     separator: Option<u8>,
+    empty: &[u8],
     out_sep: u8,
     delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    let key = extract_field(line, join_field, separator);
-    buf.extend_from_slice(key);
-    write_other_fields(line, join_field, separator, out_sep, buf);
+    push_key(buf, line, join_fields, separator, empty, out_sep);
+    write_other_fields(line, join_fields, separator, empty, out_sep, buf);
     buf.push(delim);
 }
 
@@ -312,36 +371,126 @@ fn write_unpaired_default_zerocopy(
 fn write_unpaired_format(
     fields: &[&[u8]],
     file_num: usize,
-    join_field: usize,
+    join_fields: &[usize],
     specs: &[OutputSpec],
     empty: &[u8],
     out_sep: u8,
     delim: u8,
     buf: &mut Vec<u8>,
 ) {
-    let key = fields.get(join_field).copied().unwrap_or(b"");
     for (i, spec) in specs.iter().enumerate() {
         if i > 0 {
             buf.push(out_sep);
         }
         match spec {
-            OutputSpec::JoinField => buf.extend_from_slice(key),
+            OutputSpec::JoinField => push_key_fields(buf, fields, join_fields, empty, out_sep),
             OutputSpec::FileField(fnum, fidx) => {
-                if *fnum == file_num {
-                    if let Some(f) = fields.get(*fidx) {
-                        buf.extend_from_slice(f);
-                    } else {
-                        buf.extend_from_slice(empty);
-                    }
+                let field = if *fnum == file_num {
+                    fields.get(*fidx).copied().unwrap_or(b"")
                 } else {
-                    buf.extend_from_slice(empty);
-                }
+                    b""
+                };
+                push_field(buf, field, empty);
             }
         }
     }
     buf.push(delim);
 }
 
+/// Write an unpaired line from file `file_num` (0 or 1) in either the
+/// default or the -o format.
+fn write_unpaired(
+    line: &[u8],
+    file_num: usize,
+    join_fields: &[usize],
+    config: &JoinConfig,
+    format: Option<&[OutputSpec]>,
+    buf: &mut Vec<u8>,
+) {
+    let delim = if config.zero_terminated { b'\0' } else { b'\n' };
+    let out_sep = config.separator.unwrap_or(b' ');
+    let empty = config.empty_filler.as_deref().unwrap_or(b"");
+    if let Some(specs) = format {
+        let fields = split_fields(line, config.separator);
+        write_unpaired_format(
+            &fields,
+            file_num,
+            join_fields,
+            specs,
+            empty,
+            out_sep,
+            delim,
+            buf,
+        );
+    } else {
+        write_unpaired_default_zerocopy(
+            line,
+            join_fields,
+            config.separator,
+            empty,
+            out_sep,
+            delim,
+            buf,
+        );
+    }
+}
+
+/// Sort-order checking state, mirroring GNU join: each line is compared
+/// with its predecessor as it is read, and without --check-order the
+/// check only starts once some line has failed to pair.
+struct OrderChecker<'a> {
+    mode: OrderCheck,
+    tool_name: &'a str,
+    names: [&'a str; 2],
+    /// Index of the first line of each file after any --header line.
+    first: [usize; 2],
+    seen_unpairable: bool,
+    warned: [bool; 2],
+    had_error: bool,
+}
+
+impl OrderChecker<'_> {
+    /// Check line `idx` of file `which` as it is read, reporting at most one
+    /// disorder per file. Pending output is flushed before the diagnostic.
+    /// Returns true when --check-order requires stopping.
+    fn check(
+        &mut self,
+        which: usize,
+        idx: usize,
+        keys: &[&[u8]],
+        lines: &[&[u8]],
+        case_insensitive: bool,
+        buf: &mut Vec<u8>,
+        out: &mut impl Write,
+    ) -> io::Result<bool> {
+        let active = match self.mode {
+            OrderCheck::Strict => true,
+            OrderCheck::Default => self.seen_unpairable,
+            OrderCheck::None => false,
+        };
+        if !active
+            || self.warned[which]
+            || idx <= self.first[which]
+            || compare_keys(keys[idx - 1], keys[idx], case_insensitive) != Ordering::Greater
+        {
+            return Ok(false);
+        }
+        out.write_all(buf)?;
+        buf.clear();
+        out.flush()?;
+        eprintln!(
+            "{}: {}:{}: is not sorted: {}",
+            self.tool_name,
+            self.names[which],
+            idx + 1,
+            String::from_utf8_lossy(lines[idx])
+        );
+        self.warned[which] = true;
+        self.had_error = true;
+        Ok(self.mode == OrderCheck::Strict)
+    }
+}
+
 /// Run the join merge algorithm on two sorted inputs.
 pub fn join(
     data1: &[u8],
@@ -367,20 +516,41 @@ pub fn join(
     // Pre-compute all join keys — turns O(field_position) per comparison into O(1).
     // Memory: 16 bytes per fat pointer × (lines1 + lines2). At 1M+1M lines ≈ 32 MB,
     // acceptable for the >2x speedup over repeated extract_field scanning.
-    let keys1: Vec<&[u8]> = lines1
-        .iter()
-        .map(|l| extract_field(l, config.field1, config.separator))
-        .collect();
-    let keys2: Vec<&[u8]> = lines2
-        .iter()
-        .map(|l| extract_field(l, config.field2, config.separator))
-        .collect();
+    // Composite keys are built once into owned buffers and borrowed the same way.
+    let composite = config.fields1.len() > 1;
+    let owned_keys = |lines: &[&[u8]], fields: &[usize]| -> Vec<Vec<u8>> {
+        lines
+            .iter()
+            .map(|l| composite_key(l, fields, config.separator))
+            .collect()
+    };
+    let (owned1, owned2) = if composite {
+        (
+            owned_keys(&lines1, &config.fields1),
+            owned_keys(&lines2, &config.fields2),
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let keys1: Vec<&[u8]> = if composite {
+        owned1.iter().map(Vec::as_slice).collect()
+    } else {
+        lines1
+            .iter()
+            .map(|l| extract_field(l, config.fields1[0], config.separator))
+            .collect()
+    };
+    let keys2: Vec<&[u8]> = if composite {
+        owned2.iter().map(Vec::as_slice).collect()
+    } else {
+        lines2
+            .iter()
+            .map(|l| extract_field(l, config.fields2[0], config.separator))
+            .collect()
+    };
 
     let mut i1 = 0usize;
     let mut i2 = 0usize;
-    let mut had_order_error = false;
-    let mut warned1 = false;
-    let mut warned2 = false;
 
     const FLUSH_THRESHOLD: usize = 256 * 1024;
     let mut buf = Vec::with_capacity((data1.len() + data2.len()).min(FLUSH_THRESHOLD * 2));
@@ -400,12 +570,12 @@ pub fn join(
         let mut specs = Vec::new();
         specs.push(OutputSpec::JoinField);
         for i in 0..fc1 {
-            if i != config.field1 {
+            if !config.fields1.contains(&i) {
                 specs.push(OutputSpec::FileField(0, i));
             }
         }
         for i in 0..fc2 {
-            if i != config.field2 {
+            if !config.fields2.contains(&i) {
                 specs.push(OutputSpec::FileField(1, i));
             }
         }
@@ -418,22 +588,27 @@ pub fn join(
 
     // Handle --header: join first lines without sort check
     if config.header && !lines1.is_empty() && !lines2.is_empty() {
-        let key = extract_field(lines1[0], config.field1, config.separator);
-
         if let Some(specs) = format {
             let fields1 = split_fields(lines1[0], config.separator);
             let fields2 = split_fields(lines2[0], config.separator);
             write_paired_format(
-                &fields1, &fields2, key, specs, empty, out_sep, delim, &mut buf,
+                &fields1,
+                &fields2,
+                &config.fields1,
+                specs,
+                empty,
+                out_sep,
+                delim,
+                &mut buf,
             );
         } else {
             write_paired_default_zerocopy(
                 lines1[0],
                 lines2[0],
-                key,
-                config.field1,
-                config.field2,
+                &config.fields1,
+                &config.fields2,
                 config.separator,
+                empty,
                 out_sep,
                 delim,
                 &mut buf,
@@ -451,6 +626,26 @@ pub fn join(
         }
     }
 
+    let mut oc = OrderChecker {
+        mode: config.order_check,
+        tool_name,
+        names: [file1_name, file2_name],
+        first: [i1, i2],
+        seen_unpairable: false,
+        warned: [false, false],
+        had_error: false,
+    };
+
+    // Check a line as it is read; under --check-order a disorder stops the
+    // join right after the diagnostic, like GNU.
+    macro_rules! read_line {
+        ($which:expr, $idx:expr, $keys:expr, $lines:expr) => {
+            if oc.check($which, $idx, &$keys, &$lines, ci, &mut buf, out)? {
+                return Ok(true);
+            }
+        };
+    }
+
     while i1 < lines1.len() && i2 < lines2.len() {
         debug_assert!(i1 < keys1.len() && i2 < keys2.len());
         // SAFETY: keys1.len() == lines1.len() and keys2.len() == lines2.len(),
@@ -458,307 +653,139 @@ pub fn join(
         let key1 = unsafe { *keys1.get_unchecked(i1) };
         let key2 = unsafe { *keys2.get_unchecked(i2) };
 
-        // Order checks
-        if config.order_check != OrderCheck::None {
-            if !warned1 && i1 > (if config.header { 1 } else { 0 }) {
-                let prev_key = keys1[i1 - 1];
-                if compare_keys(key1, prev_key, ci) == Ordering::Less {
-                    had_order_error = true;
-                    warned1 = true;
-                    eprintln!(
-                        "{}: {}:{}: is not sorted: {}",
-                        tool_name,
-                        file1_name,
-                        i1 + 1,
-                        String::from_utf8_lossy(lines1[i1])
-                    );
-                    if config.order_check == OrderCheck::Strict {
-                        out.write_all(&buf)?;
-                        return Ok(true);
-                    }
-                }
-            }
-            if !warned2 && i2 > (if config.header { 1 } else { 0 }) {
-                let prev_key = keys2[i2 - 1];
-                if compare_keys(key2, prev_key, ci) == Ordering::Less {
-                    had_order_error = true;
-                    warned2 = true;
-                    eprintln!(
-                        "{}: {}:{}: is not sorted: {}",
-                        tool_name,
-                        file2_name,
-                        i2 + 1,
-                        String::from_utf8_lossy(lines2[i2])
-                    );
-                    if config.order_check == OrderCheck::Strict {
-                        out.write_all(&buf)?;
-                        return Ok(true);
-                    }
-                }
-            }
-        }
-
         match compare_keys(key1, key2, ci) {
             Ordering::Less => {
                 if show_unpaired1 {
-                    if let Some(specs) = format {
-                        let fields1 = split_fields(lines1[i1], config.separator);
-                        write_unpaired_format(
-                            &fields1,
-                            0,
-                            config.field1,
-                            specs,
-                            empty,
-                            out_sep,
-                            delim,
-                            &mut buf,
-                        );
-                    } else {
-                        write_unpaired_default_zerocopy(
-                            lines1[i1],
-                            config.field1,
-                            config.separator,
-                            out_sep,
-                            delim,
-                            &mut buf,
-                        );
-                    }
+                    write_unpaired(lines1[i1], 0, &config.fields1, config, format, &mut buf);
                 }
                 i1 += 1;
-                if show_unpaired1 && buf.len() >= FLUSH_THRESHOLD {
-                    out.write_all(&buf)?;
-                    buf.clear();
+                if i1 < lines1.len() {
+                    read_line!(0, i1, keys1, lines1);
                 }
+                oc.seen_unpairable = true;
             }
             Ordering::Greater => {
                 if show_unpaired2 {
-                    if let Some(specs) = format {
-                        let fields2 = split_fields(lines2[i2], config.separator);
-                        write_unpaired_format(
-                            &fields2,
-                            1,
-                            config.field2,
-                            specs,
-                            empty,
-                            out_sep,
-                            delim,
-                            &mut buf,
-                        );
-                    } else {
-                        write_unpaired_default_zerocopy(
-                            lines2[i2],
-                            config.field2,
-                            config.separator,
-                            out_sep,
-                            delim,
-                            &mut buf,
-                        );
-                    }
+                    write_unpaired(lines2[i2], 1, &config.fields2, config, format, &mut buf);
                 }
                 i2 += 1;
-
-                // Periodic flush to limit memory usage for large inputs
-                if buf.len() >= FLUSH_THRESHOLD {
-                    out.write_all(&buf)?;
-                    buf.clear();
+                if i2 < lines2.len() {
+                    read_line!(1, i2, keys2, lines2);
                 }
+                oc.seen_unpairable = true;
             }
             Ordering::Equal => {
-                // Find all consecutive file2 lines with the same key
-                let group_start = i2;
-                let current_key = key2;
-                i2 += 1;
-                while i2 < lines2.len() {
-                    debug_assert!(i2 < keys2.len());
-                    // SAFETY: i2 < lines2.len() == keys2.len()
-                    let next_key = unsafe { *keys2.get_unchecked(i2) };
-                    if compare_keys(next_key, current_key, ci) != Ordering::Equal {
+                // Read both runs of matching keys, plus the first line past
+                // each run, before printing the cross product (GNU order).
+                let mut end1 = i1 + 1;
+                while end1 < lines1.len() {
+                    read_line!(0, end1, keys1, lines1);
+                    if compare_keys(keys1[end1], key1, ci) != Ordering::Equal {
                         break;
                     }
-                    i2 += 1;
+                    end1 += 1;
+                }
+                let mut end2 = i2 + 1;
+                while end2 < lines2.len() {
+                    read_line!(1, end2, keys2, lines2);
+                    if compare_keys(keys2[end2], key2, ci) != Ordering::Equal {
+                        break;
+                    }
+                    end2 += 1;
                 }
 
-                // Pre-cache file2 group fields only for -o format (cross-product needs re-access)
-                let group2_fields: Vec<Vec<&[u8]>> = if print_paired && format.is_some() {
-                    (group_start..i2)
-                        .map(|j| split_fields(lines2[j], config.separator))
-                        .collect()
-                } else {
-                    Vec::new()
-                };
+                if print_paired {
+                    // Pre-cache file2 group fields only for -o format (cross-product needs re-access)
+                    let group2_fields: Vec<Vec<&[u8]>> = if format.is_some() {
+                        (i2..end2)
+                            .map(|j| split_fields(lines2[j], config.separator))
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
 
-                // For each file1 line with the same key, cross-product with file2 group
-                loop {
-                    if print_paired {
-                        let key = extract_field(lines1[i1], config.field1, config.separator);
+                    for &line1 in &lines1[i1..end1] {
                         if let Some(specs) = format {
-                            let fields1 = split_fields(lines1[i1], config.separator);
+                            let fields1 = split_fields(line1, config.separator);
                             for fields2 in &group2_fields {
                                 write_paired_format(
-                                    &fields1, fields2, key, specs, empty, out_sep, delim, &mut buf,
+                                    &fields1,
+                                    fields2,
+                                    &config.fields1,
+                                    specs,
+                                    empty,
+                                    out_sep,
+                                    delim,
+                                    &mut buf,
                                 );
                             }
                         } else {
                             // Zero-copy path: no field Vec allocation
-                            for j in group_start..i2 {
+                            for &line2 in &lines2[i2..end2] {
                                 write_paired_default_zerocopy(
-                                    lines1[i1],
-                                    lines2[j],
-                                    key,
-                                    config.field1,
-                                    config.field2,
+                                    line1,
+                                    line2,
+                                    &config.fields1,
+                                    &config.fields2,
                                     config.separator,
+                                    empty,
                                     out_sep,
                                     delim,
                                     &mut buf,
                                 );
                             }
                         }
-                    }
-                    // Flush inside cross-product loop to bound buffer for N×M groups
-                    if buf.len() >= FLUSH_THRESHOLD {
-                        out.write_all(&buf)?;
-                        buf.clear();
-                    }
-                    i1 += 1;
-                    if i1 >= lines1.len() {
-                        break;
-                    }
-                    debug_assert!(i1 < keys1.len());
-                    // SAFETY: i1 < lines1.len() == keys1.len() (checked above)
-                    let next_key = unsafe { *keys1.get_unchecked(i1) };
-                    let cmp = compare_keys(next_key, current_key, ci);
-                    if cmp != Ordering::Equal {
-                        // Check order: next_key should be > current_key
-                        if config.order_check != OrderCheck::None
-                            && !warned1
-                            && cmp == Ordering::Less
-                        {
-                            had_order_error = true;
-                            warned1 = true;
-                            eprintln!(
-                                "{}: {}:{}: is not sorted: {}",
-                                tool_name,
-                                file1_name,
-                                i1 + 1,
-                                String::from_utf8_lossy(lines1[i1])
-                            );
-                            if config.order_check == OrderCheck::Strict {
-                                out.write_all(&buf)?;
-                                return Ok(true);
-                            }
+                        // Flush inside cross-product loop to bound buffer for N×M groups
+                        if buf.len() >= FLUSH_THRESHOLD {
+                            out.write_all(&buf)?;
+                            buf.clear();
                         }
-                        break;
                     }
                 }
+                i1 = end1;
+                i2 = end2;
             }
         }
-    }
 
-    // Drain remaining from file 1
-    while i1 < lines1.len() {
-        // Check sort order even when draining (GNU join does this)
-        if config.order_check != OrderCheck::None
-            && !warned1
-            && i1 > (if config.header { 1 } else { 0 })
-        {
-            let key1 = keys1[i1];
-            let prev_key = keys1[i1 - 1];
-            if compare_keys(key1, prev_key, ci) == Ordering::Less {
-                had_order_error = true;
-                warned1 = true;
-                eprintln!(
-                    "{}: {}:{}: is not sorted: {}",
-                    tool_name,
-                    file1_name,
-                    i1 + 1,
-                    String::from_utf8_lossy(lines1[i1])
-                );
-                if config.order_check == OrderCheck::Strict {
-                    out.write_all(&buf)?;
-                    return Ok(true);
-                }
-            }
+        // Periodic flush to limit memory usage for large inputs
+        if buf.len() >= FLUSH_THRESHOLD {
+            out.write_all(&buf)?;
+            buf.clear();
         }
+    }
+
+    // Read the tails to finish the order check, printing them when asked.
+    // Once a file has been reported there is nothing left to check in it.
+    let check_tail = config.order_check != OrderCheck::None && !(oc.warned[0] && oc.warned[1]);
+
+    if (show_unpaired1 || check_tail) && i1 < lines1.len() {
         if show_unpaired1 {
-            if let Some(specs) = format {
-                let fields1 = split_fields(lines1[i1], config.separator);
-                write_unpaired_format(
-                    &fields1,
-                    0,
-                    config.field1,
-                    specs,
-                    empty,
-                    out_sep,
-                    delim,
-                    &mut buf,
-                );
-            } else {
-                write_unpaired_default_zerocopy(
-                    lines1[i1],
-                    config.field1,
-                    config.separator,
-                    out_sep,
-                    delim,
-                    &mut buf,
-                );
+            write_unpaired(lines1[i1], 0, &config.fields1, config, format, &mut buf);
+        }
+        for i in i1 + 1..lines1.len() {
+            read_line!(0, i, keys1, lines1);
+            if show_unpaired1 {
+                write_unpaired(lines1[i], 0, &config.fields1, config, format, &mut buf);
+            } else if oc.warned[0] {
+                break;
             }
         }
-        i1 += 1;
     }
 
-    // Drain remaining from file 2
-    while i2 < lines2.len() {
-        // Check sort order even when draining (GNU join does this)
-        if config.order_check != OrderCheck::None
-            && !warned2
-            && i2 > (if config.header { 1 } else { 0 })
-        {
-            let key2 = keys2[i2];
-            let prev_key = keys2[i2 - 1];
-            if compare_keys(key2, prev_key, ci) == Ordering::Less {
-                had_order_error = true;
-                warned2 = true;
-                eprintln!(
-                    "{}: {}:{}: is not sorted: {}",
-                    tool_name,
-                    file2_name,
-                    i2 + 1,
-                    String::from_utf8_lossy(lines2[i2])
-                );
-                if config.order_check == OrderCheck::Strict {
-                    out.write_all(&buf)?;
-                    return Ok(true);
-                }
-            }
-        }
+    if (show_unpaired2 || check_tail) && i2 < lines2.len() {
         if show_unpaired2 {
-            if let Some(specs) = format {
-                let fields2 = split_fields(lines2[i2], config.separator);
-                write_unpaired_format(
-                    &fields2,
-                    1,
-                    config.field2,
-                    specs,
-                    empty,
-                    out_sep,
-                    delim,
-                    &mut buf,
-                );
-            } else {
-                write_unpaired_default_zerocopy(
-                    lines2[i2],
-                    config.field2,
-                    config.separator,
-                    out_sep,
-                    delim,
-                    &mut buf,
-                );
+            write_unpaired(lines2[i2], 1, &config.fields2, config, format, &mut buf);
+        }
+        for i in i2 + 1..lines2.len() {
+            read_line!(1, i, keys2, lines2);
+            if show_unpaired2 {
+                write_unpaired(lines2[i], 1, &config.fields2, config, format, &mut buf);
+            } else if oc.warned[1] {
+                break;
             }
         }
-        i2 += 1;
     }
 
     out.write_all(&buf)?;
-    Ok(had_order_error)
+    Ok(oc.had_error)
 }
@@ -4,7 +4,8 @@ use std::io;
 
 use super::{
     ClassifyMode, ColorMode, HyperlinkMode, IndicatorStyle, LsConfig, OutputFormat, QuotingStyle,
-    SortBy, TimeField, TimeStyle, atty_stdout, ls_main,
+    SortBy, TimeField, TimeStyle, atty_stdout, columns_env_width, format_uses_width, ls_main,
+    parse_color_mode, parse_format,
 };
 
 /// Which variant of ls we are running.
@@ -25,18 +26,13 @@ impl LsFlavor {
     }
 }
 
-fn get_terminal_width() -> Option<usize> {
+fn get_terminal_width(prog: &str) -> Option<usize> {
     let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
     let ret = unsafe { libc::ioctl(1, libc::TIOCGWINSZ, &mut ws) };
     if ret == 0 && ws.ws_col > 0 {
         return Some(ws.ws_col as usize);
     }
-    if let Ok(val) = std::env::var("COLUMNS") {
-        if let Ok(w) = val.parse::<usize>() {
-            return Some(w);
-        }
-    }
-    None
+    columns_env_width(prog)
 }
 
 fn take_short_value(
@@ -83,20 +79,26 @@ fn print_ls_help(flavor: LsFlavor) {
          \x20 -a, --all                  do not ignore entries starting with .\n\
          \x20 -A, --almost-all           do not list implied . and ..\n\
          \x20 -b, --escape               print C-style escapes for nongraphic characters\n\
+         \x20     --block-size=SIZE      with -l, scale sizes by SIZE when printing them;\n\
+         \x20                              e.g., '--block-size=M'\n\
          \x20 -B, --ignore-backups       do not list implied entries ending with ~\n\
          \x20 -c                         sort by/show ctime\n\
          \x20 -C                         list entries by columns\n\
          \x20     --color[=WHEN]         colorize output; WHEN: always, auto, never\n\
          \x20 -d, --directory            list directories themselves, not their contents\n\
+         \x20 -D, --dired                generate output designed for Emacs' dired mode\n\
          \x20 -F, --classify[=WHEN]      append indicator (one of */=>@|) to entries\n\
          \x20 -g                         like -l, but do not list owner\n\
          \x20 -G, --no-group             in -l listing, don't print group names\n\
          \x20     --group-directories-first  group directories before files\n\
+         \x20     --format=WORD          across -x, commas -m, horizontal -x, long -l,\n\
+         \x20                              single-column -1, verbose -l, vertical -C\n\
          \x20     --full-time            like -l --time-style=full-iso\n\
          \x20 -h, --human-readable       with -l, print sizes like 1K 234M 2G etc.\n\
          \x20 -i, --inode                print the index number of each file\n\
          \x20 -I, --ignore=PATTERN       do not list entries matching PATTERN\n\
-         \x20 -k, --kibibytes            default to 1024-byte blocks\n\
+         \x20 -k, --kibibytes            default to 1024-byte blocks for file system usage;\n\
+         \x20                              used only with -s and per directory totals\n\
          \x20 -l                         use a long listing format\n\
          \x20 -L, --dereference          show info for link references\n\
          \x20 -m                         fill width with a comma separated list of entries\n\
@@ -184,15 +186,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
         }
     }
 
-    if is_tty {
-        if let Some(w) = get_terminal_width() {
-            if w > 0 {
-                config.width = w;
-            }
-        }
-    }
-
-    let mut explicit_format = false;
+    let mut width_set = false;
     let mut args = std::env::args_os().skip(1);
     #[allow(clippy::while_let_on_iterator)]
     while let Some(arg) = args.next() {
@@ -225,6 +219,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 "escape" => config.quoting_style = QuotingStyle::Escape,
                 "ignore-backups" => config.ignore_backups = true,
                 "directory" => config.directory = true,
+                "dired" => config.dired = true,
                 "classify" => {
                     let mode = eq_val.unwrap_or("always");
                     match mode {
@@ -247,8 +242,8 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 }
                 "no-group" => config.show_group = false,
                 "group-directories-first" => config.group_directories_first = true,
-                "human-readable" => config.human_readable = true,
-                "si" => config.si = true,
+                "human-readable" => super::set_human_readable(&mut config, false),
+                "si" => super::set_human_readable(&mut config, true),
                 "inode" => config.show_inode = true,
                 "ignore" => {
                     let val = next_opt_val(eq_val, &mut args, prog, "ignore");
@@ -259,9 +254,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 "numeric-uid-gid" => {
                     config.numeric_ids = true;
                     config.long_format = true;
-                    if !explicit_format {
-                        config.format = OutputFormat::Long;
-                    }
+                    config.format = OutputFormat::Long;
                 }
                 "literal" => {
                     config.literal = true;
@@ -274,15 +267,13 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 "size" => config.show_size = true,
                 "context" => config.context = true,
                 "color" => {
-                    let val = eq_val.unwrap_or("always");
-                    config.color = match val {
-                        "always" | "yes" | "force" => ColorMode::Always,
-                        "auto" | "tty" | "if-tty" => ColorMode::Auto,
-                        "never" | "no" | "none" => ColorMode::Never,
-                        _ => {
-                            eprintln!("{}: invalid argument '{}' for '--color'", prog, val);
-                            std::process::exit(2);
-                        }
+                    config.color = match eq_val {
+                        None => ColorMode::Always,
+                        Some(val) => parse_color_mode(val).unwrap_or_else(|e| {
+                            eprintln!("{}: {}", prog, e);
+                            eprintln!("Try '{} --help' for more information.", prog);
+                            std::process::exit(1);
+                        }),
                     };
                 }
                 "sort" => {
@@ -327,10 +318,18 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                         }
                     };
                 }
+                "format" => {
+                    let val = next_opt_val(eq_val, &mut args, prog, "format");
+                    config.format = parse_format(&val).unwrap_or_else(|e| {
+                        eprintln!("{}: {}", prog, e);
+                        eprintln!("Try '{} --help' for more information.", prog);
+                        std::process::exit(1);
+                    });
+                    config.long_format = config.format == OutputFormat::Long;
+                }
                 "full-time" => {
                     config.long_format = true;
                     config.format = OutputFormat::Long;
-                    explicit_format = true;
                     config.time_style = TimeStyle::FullIso;
                 }
                 "tabsize" => {
@@ -340,6 +339,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 "width" => {
                     let val = next_opt_val(eq_val, &mut args, prog, "width");
                     config.width = val.parse().unwrap_or(80);
+                    width_set = true;
                 }
                 "hyperlink" => {
                     let val = eq_val.unwrap_or("always");
@@ -388,15 +388,9 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                 }
                 "block-size" => {
                     let val = next_opt_val(eq_val, &mut args, prog, "block-size");
-                    match super::parse_block_size(&val) {
-                        Ok((bs, suffix)) => {
-                            config.block_size = Some(bs);
-                            config.block_size_suffix = suffix;
-                        }
-                        Err(msg) => {
-                            eprintln!("{}: {}", prog, msg);
-                            std::process::exit(2);
-                        }
+                    if let Err(msg) = super::apply_block_size(&mut config, &val) {
+                        eprintln!("{}: {}", prog, msg);
+                        std::process::exit(2);
                     }
                 }
                 _ => {
@@ -416,9 +410,10 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                     b'c' => config.time_field = TimeField::Ctime,
                     b'C' => {
                         config.format = OutputFormat::Columns;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'd' => config.directory = true,
+                    b'D' => config.dired = true,
                     b'f' => {
                         config.all = true;
                         config.sort_by = SortBy::None;
@@ -430,30 +425,25 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                     b'g' => {
                         config.long_format = true;
                         config.show_owner = false;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'G' => config.show_group = false,
-                    b'h' => config.human_readable = true,
+                    b'h' => super::set_human_readable(&mut config, false),
                     b'i' => config.show_inode = true,
                     b'k' => config.kibibytes = true,
                     b'l' => {
                         config.long_format = true;
                         config.format = OutputFormat::Long;
-                        explicit_format = true;
                     }
                     b'L' => config.dereference = true,
                     b'm' => {
                         config.format = OutputFormat::Comma;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'n' => {
                         config.long_format = true;
                         config.numeric_ids = true;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'N' => {
                         config.literal = true;
@@ -462,9 +452,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                     b'o' => {
                         config.long_format = true;
                         config.show_group = false;
-                        if !explicit_format {
-                            config.format = OutputFormat::Long;
-                        }
+                        config.format = OutputFormat::Long;
                     }
                     b'p' => config.indicator_style = IndicatorStyle::Slash,
                     b'q' => config.hide_control_chars = true,
@@ -479,14 +467,15 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                     b'v' => config.sort_by = SortBy::Version,
                     b'x' => {
                         config.format = OutputFormat::Across;
-                        explicit_format = true;
+                        config.long_format = false;
                     }
                     b'X' => config.sort_by = SortBy::Extension,
                     b'Z' => config.context = true,
-                    b'1' => {
+                    // -1 has no effect after -l, as in GNU ls.
+                    b'1' if config.format != OutputFormat::Long => {
                         config.format = OutputFormat::SingleColumn;
-                        explicit_format = true;
                     }
+                    b'1' => {}
                     b'I' => {
                         let val = take_short_value(bytes, i + 1, &mut args, "I", prog);
                         config.ignore_patterns.push(val);
@@ -498,6 +487,7 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
                             eprintln!("{}: invalid line width: '{}'", prog, val);
                             std::process::exit(2);
                         });
+                        width_set = true;
                         break;
                     }
                     b'T' => {
@@ -521,6 +511,16 @@ pub fn parse_ls_args(flavor: LsFlavor) -> (LsConfig, Vec<String>) {
         }
     }
 
+    // The terminal (or COLUMNS) width only matters to the layouts that fill lines.
+    if !width_set && format_uses_width(config.format) {
+        if let Some(w) = get_terminal_width(prog) {
+            config.width = w;
+        }
+    }
+
+    super::apply_time_sort_default(&mut config);
+    super::apply_block_size_env(&mut config);
+
     (config, paths)
 }
 
@@ -542,8 +542,8 @@ pub fn run_ls(flavor: LsFlavor) {
     };
 
     match ls_main(&file_args, &config) {
-        Ok(true) => {}
-        Ok(false) => std::process::exit(1),
+        Ok(0) => {}
+        Ok(status) => std::process::exit(status),
         Err(e) => {
             if e.kind() == io::ErrorKind::BrokenPipe {
                 std::process::exit(141);
@@ -1,10 +1,13 @@
+use std::cell::Cell;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
 use std::fs::{self, DirEntry, Metadata};
 use std::io::{self, BufWriter, Write};
+use std::os::unix::ffi::OsStrExt;
 use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 use std::time::SystemTime;
 
@@ -59,6 +62,111 @@ pub enum ColorMode {
     Never,
 }
 
+const COLOR_ARGS: &[(&str, ColorMode)] = &[
+    ("always", ColorMode::Always),
+    ("yes", ColorMode::Always),
+    ("force", ColorMode::Always),
+    ("never", ColorMode::Never),
+    ("no", ColorMode::Never),
+    ("none", ColorMode::Never),
+    ("auto", ColorMode::Auto),
+    ("tty", ColorMode::Auto),
+    ("if-tty", ColorMode::Auto),
+];
+
+const FORMAT_ARGS: &[(&str, OutputFormat)] = &[
+    ("verbose", OutputFormat::Long),
+    ("long", OutputFormat::Long),
+    ("commas", OutputFormat::Comma),
+    ("horizontal", OutputFormat::Across),
+    ("across", OutputFormat::Across),
+    ("vertical", OutputFormat::Columns),
+    ("single-column", OutputFormat::SingleColumn),
+];
+
+/// Look `s` up in an argument table like GNU argmatch: an exact match, or an
+/// abbreviation of names that all mean the same thing.
+/// On failure, returns whether the abbreviation was ambiguous.
+fn argmatch<T: Copy + PartialEq>(s: &str, table: &[(&str, T)]) -> Result<T, bool> {
+    if let Some(&(_, value)) = table.iter().find(|(name, _)| *name == s) {
+        return Ok(value);
+    }
+    let mut found = None;
+    let mut ambiguous = false;
+    for &(name, value) in table {
+        if name.starts_with(s) {
+            match found {
+                None => found = Some(value),
+                Some(prev) if prev != value => ambiguous = true,
+                Some(_) => {}
+            }
+        }
+    }
+    match found {
+        Some(value) if !ambiguous => Ok(value),
+        _ => Err(ambiguous),
+    }
+}
+
+/// Parse a `--color=WHEN` value.  Like GNU argmatch, unambiguous abbreviations
+/// are accepted.
+pub fn parse_color_mode(s: &str) -> Result<ColorMode, String> {
+    argmatch(s, COLOR_ARGS).map_err(|ambiguous| {
+        format!(
+            "{} argument '{}' for '--color'\n\
+             Valid arguments are:\n  \
+             - 'always', 'yes', 'force'\n  \
+             - 'never', 'no', 'none'\n  \
+             - 'auto', 'tty', 'if-tty'",
+            if ambiguous { "ambiguous" } else { "invalid" },
+            s
+        )
+    })
+}
+
+/// Parse a `--format=WORD` value, accepting unambiguous abbreviations.
+pub fn parse_format(s: &str) -> Result<OutputFormat, String> {
+    argmatch(s, FORMAT_ARGS).map_err(|ambiguous| {
+        format!(
+            "{} argument '{}' for '--format'\n\
+             Valid arguments are:\n  \
+             - 'verbose', 'long'\n  \
+             - 'commas'\n  \
+             - 'horizontal', 'across'\n  \
+             - 'vertical'\n  \
+             - 'single-column'",
+            if ambiguous { "ambiguous" } else { "invalid" },
+            s
+        )
+    })
+}
+
+/// Whether a layout fills lines up to the screen width.
+pub fn format_uses_width(format: OutputFormat) -> bool {
+    matches!(
+        format,
+        OutputFormat::Columns | OutputFormat::Across | OutputFormat::Comma
+    )
+}
+
+/// Read the line width from the `COLUMNS` environment variable. An unset or
+/// empty variable gives `None`; an invalid one is diagnosed and ignored.
+/// Like `-w`, 0 means no limit, and an overlong number saturates.
+pub fn columns_env_width(prog: &str) -> Option<usize> {
+    let val = std::env::var("COLUMNS").ok()?;
+    if val.is_empty() {
+        return None;
+    }
+    if !val.bytes().all(|b| b.is_ascii_digit()) {
+        eprintln!(
+            "{}: ignoring invalid width in environment variable COLUMNS: '{}'",
+            prog, val
+        );
+        return None;
+    }
+    Some(val.parse().unwrap_or(usize::MAX))
+}
+
 /// Which timestamp to show / sort by.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeField {
@@ -108,6 +216,22 @@ pub enum QuotingStyle {
     Escape,
 }
 
+impl QuotingStyle {
+    /// The `--quoting-style` argument that selects this style.
+    pub fn name(self) -> &'static str {
+        match self {
+            QuotingStyle::Literal => "literal",
+            QuotingStyle::Locale => "locale",
+            QuotingStyle::Shell => "shell",
+            QuotingStyle::ShellAlways => "shell-always",
+            QuotingStyle::ShellEscape => "shell-escape",
+            QuotingStyle::ShellEscapeAlways => "shell-escape-always",
+            QuotingStyle::C => "c",
+            QuotingStyle::Escape => "escape",
+        }
+    }
+}
+
 /// When to emit hyperlinks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HyperlinkMode {
@@ -153,11 +277,16 @@ pub struct LsConfig {
     pub literal: bool,
     /// --zero: use NUL as line terminator instead of newline.
     pub zero: bool,
+    /// -D / --dired: with -l, indent lines and report name offsets for Emacs.
+    pub dired: bool,
     /// --block-size=SIZE: scale sizes by SIZE before printing.
     /// None means not set (use default behavior).
     pub block_size: Option<u64>,
     /// Suffix to append after block-size-scaled values (e.g. "K", "M", "kB").
     pub block_size_suffix: String,
+    /// The block size came from LS_BLOCK_SIZE / BLOCK_SIZE rather than an
+    /// option, so -k still puts block counts in 1024-byte units.
+    pub block_size_from_env: bool,
 }
 
 impl Default for LsConfig {
@@ -196,161 +325,455 @@ impl Default for LsConfig {
             context: false,
             literal: false,
             zero: false,
+            dired: false,
             block_size: None,
             block_size_suffix: String::new(),
+            block_size_from_env: false,
         }
     }
 }
 
 // ---------------------------------------------------------------------------
-// Default LS_COLORS
+// LS_COLORS
 // ---------------------------------------------------------------------------
 
+/// Colour indicators, in the order of GNU's `indicator_name` table.
+/// Some are only ever parsed, but all are kept so that each variant indexes
+/// `INDICATOR_NAMES`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Indicator {
+    Left,
+    Right,
+    End,
+    Reset,
+    Norm,
+    File,
+    Dir,
+    Link,
+    Fifo,
+    Sock,
+    Blk,
+    Chr,
+    Missing,
+    Orphan,
+    Exec,
+    Door,
+    Setuid,
+    Setgid,
+    Sticky,
+    OtherWritable,
+    StickyOtherWritable,
+    Cap,
+    MultiHardlink,
+    ClrToEol,
+}
+
+const INDICATOR_NAMES: [&str; 24] = [
+    "lc", "rc", "ec", "rs", "no", "fi", "di", "ln", "pi", "so", "bd", "cd", "mi", "or", "ex", "do",
+    "su", "sg", "st", "ow", "tw", "ca", "mh", "cl",
+];
+
+const INDICATOR_DEFAULTS: [Option<&[u8]>; 24] = [
+    Some(b"\x1b["),
+    Some(b"m"),
+    None,
+    Some(b"0"),
+    None,
+    None,
+    Some(b"01;34"),
+    Some(b"01;36"),
+    Some(b"33"),
+    Some(b"01;35"),
+    Some(b"01;33"),
+    Some(b"01;33"),
+    None,
+    None,
+    Some(b"01;32"),
+    Some(b"01;35"),
+    Some(b"37;41"),
+    Some(b"30;43"),
+    Some(b"37;44"),
+    Some(b"34;42"),
+    Some(b"30;42"),
+    None,
+    None,
+    Some(b"\x1b[K"),
+];
+
+/// A `*SUFFIX=SEQ` entry.
+#[derive(Debug, Clone)]
+struct ColorExt {
+    suffix: Vec<u8>,
+    seq: Vec<u8>,
+    /// Match the suffix case-sensitively.
+    exact: bool,
+}
+
 /// Parsed colour database.
 #[derive(Debug, Clone)]
 pub struct ColorDb {
-    pub map: HashMap<String, String>,
-    pub dir: String,
-    pub link: String,
-    pub exec: String,
-    pub pipe: String,
-    pub socket: String,
-    pub block_dev: String,
-    pub char_dev: String,
-    pub orphan: String,
-    pub setuid: String,
-    pub setgid: String,
-    pub sticky: String,
-    pub other_writable: String,
-    pub sticky_other_writable: String,
-    pub reset: String,
+    indicators: [Option<Vec<u8>>; 24],
+    /// Suffix entries, most recently defined first.
+    exts: Vec<ColorExt>,
+    /// `ln=target`: colour symlinks like the files they point to.
+    link_as_target: bool,
+    /// Set once any escape has been written; the first one is preceded by a reset.
+    used: Cell<bool>,
 }
 
 impl Default for ColorDb {
     fn default() -> Self {
         ColorDb {
-            map: HashMap::new(),
-            dir: "\x1b[01;34m".to_string(),            // bold blue
-            link: "\x1b[01;36m".to_string(),           // bold cyan
-            exec: "\x1b[01;32m".to_string(),           // bold green
-            pipe: "\x1b[33m".to_string(),              // yellow
-            socket: "\x1b[01;35m".to_string(),         // bold magenta
-            block_dev: "\x1b[01;33m".to_string(),      // bold yellow
-            char_dev: "\x1b[01;33m".to_string(),       // bold yellow
-            orphan: "\x1b[01;31m".to_string(),         // bold red
-            setuid: "\x1b[37;41m".to_string(),         // white on red
-            setgid: "\x1b[30;43m".to_string(),         // black on yellow
-            sticky: "\x1b[37;44m".to_string(),         // white on blue
-            other_writable: "\x1b[34;42m".to_string(), // blue on green
-            sticky_other_writable: "\x1b[30;42m".to_string(), // black on green
-            reset: "\x1b[0m".to_string(),
+            indicators: INDICATOR_DEFAULTS.map(|d| d.map(<[u8]>::to_vec)),
+            exts: Vec::new(),
+            link_as_target: false,
+            used: Cell::new(false),
+        }
+    }
+}
+
+/// Whether a colour-capable terminal is announced by COLORTERM or TERM, checked
+/// against the TERM patterns of the dircolors database.
+fn color_terminal() -> bool {
+    if std::env::var_os("COLORTERM").is_some_and(|v| !v.is_empty()) {
+        return true;
+    }
+    let Some(term) = std::env::var_os("TERM").filter(|t| !t.is_empty()) else {
+        return false;
+    };
+    let Ok(term) = CString::new(term.as_bytes()) else {
+        return false;
+    };
+    include_str!("../bin/dircolors_database.txt")
+        .lines()
+        .filter_map(|line| line.strip_prefix("TERM "))
+        .filter_map(|pat| CString::new(pat.trim()).ok())
+        .any(|pat| unsafe { libc::fnmatch(pat.as_ptr(), term.as_ptr(), 0) } == 0)
+}
+
+/// Decode one LS_COLORS value like GNU's `get_funky_string`: backslash escapes
+/// (`\e`, `\_`, octal and `\x` hex) and caret notation (`^[`).  The value ends
+/// at ':' or the end of input, or also at '=' when it is a `*SUFFIX` key.
+fn parse_funky_string(s: &[u8], p: &mut usize, equals_end: bool) -> Result<Vec<u8>, ()> {
+    let mut out = Vec::new();
+    while let Some(&c) = s.get(*p) {
+        match c {
+            b':' => break,
+            b'=' if equals_end => break,
+            b'\\' => {
+                let &e = s.get(*p + 1).ok_or(())?;
+                *p += 2;
+                let byte = match e {
+                    b'0'..=b'7' => {
+                        let mut num = e - b'0';
+                        while let Some(&d @ b'0'..=b'7') = s.get(*p) {
+                            num = (num << 3).wrapping_add(d - b'0');
+                            *p += 1;
+                        }
+                        num
+                    }
+                    b'x' | b'X' => {
+                        let mut num = 0u8;
+                        while let Some(d) = s.get(*p).and_then(|&d| (d as char).to_digit(16)) {
+                            num = (num << 4).wrapping_add(d as u8);
+                            *p += 1;
+                        }
+                        num
+                    }
+                    b'a' => 0x07,
+                    b'b' => 0x08,
+                    b'e' => 0x1b,
+                    b'f' => 0x0c,
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    b'v' => 0x0b,
+                    b'?' => 0x7f,
+                    b'_' => b' ',
+                    other => other,
+                };
+                out.push(byte);
+            }
+            b'^' => {
+                match s.get(*p + 1) {
+                    Some(&e @ b'@'..=b'~') => out.push(e & 0o37),
+                    Some(b'?') => out.push(0x7f),
+                    _ => return Err(()),
+                }
+                *p += 2;
+            }
+            _ => {
+                out.push(c);
+                *p += 1;
+            }
         }
     }
+    Ok(out)
 }
 
 impl ColorDb {
-    /// Parse from LS_COLORS environment variable.
-    pub fn from_env() -> Self {
+    /// Build the database from LS_COLORS on top of GNU's built-in defaults.
+    ///
+    /// Returns `None` when colouring is off: LS_COLORS is unparsable (diagnosed
+    /// as GNU does), or it is unset and the terminal does not look colour-capable.
+    pub fn from_env() -> Option<Self> {
         let mut db = ColorDb::default();
-        if let Ok(val) = std::env::var("LS_COLORS") {
-            for item in val.split(':') {
-                if let Some((key, code)) = item.split_once('=') {
-                    let esc = format!("\x1b[{}m", code);
-                    match key {
-                        "di" => db.dir = esc,
-                        "ln" => db.link = esc,
-                        "ex" => db.exec = esc,
-                        "pi" | "fi" if key == "pi" => db.pipe = esc,
-                        "so" => db.socket = esc,
-                        "bd" => db.block_dev = esc,
-                        "cd" => db.char_dev = esc,
-                        "or" => db.orphan = esc,
-                        "su" => db.setuid = esc,
-                        "sg" => db.setgid = esc,
-                        "st" => db.sticky = esc,
-                        "ow" => db.other_writable = esc,
-                        "tw" => db.sticky_other_writable = esc,
-                        "rs" => db.reset = esc,
+        match std::env::var_os("LS_COLORS").filter(|v| !v.is_empty()) {
+            Some(val) if db.parse(val.as_bytes()).is_err() => {
+                eprintln!("ls: unparsable value for LS_COLORS environment variable");
+                return None;
+            }
+            None if !color_terminal() => return None,
+            _ => {}
+        }
+        Some(db)
+    }
+
+    fn parse(&mut self, s: &[u8]) -> Result<(), ()> {
+        let mut p = 0;
+        while let Some(&c) = s.get(p) {
+            match c {
+                b':' => p += 1,
+                b'*' => {
+                    p += 1;
+                    let suffix = parse_funky_string(s, &mut p, true)?;
+                    if s.get(p) != Some(&b'=') {
+                        return Err(());
+                    }
+                    p += 1;
+                    let seq = parse_funky_string(s, &mut p, false)?;
+                    self.exts.insert(
+                        0,
+                        ColorExt {
+                            suffix,
+                            seq,
+                            exact: false,
+                        },
+                    );
+                }
+                _ => {
+                    let label = s.get(p..p + 2).ok_or(())?;
+                    p += 2;
+                    if s.get(p) != Some(&b'=') {
+                        return Err(());
+                    }
+                    p += 1;
+                    let idx = INDICATOR_NAMES.iter().position(|n| n.as_bytes() == label);
+                    match idx.map(|i| (i, parse_funky_string(s, &mut p, false))) {
+                        Some((i, Ok(seq))) => self.indicators[i] = Some(seq),
                         _ => {
-                            if key.starts_with('*') {
-                                db.map.insert(key[1..].to_string(), esc);
-                            }
+                            eprintln!(
+                                "ls: unrecognized prefix: '{}'",
+                                String::from_utf8_lossy(label)
+                            );
+                            return Err(());
                         }
                     }
                 }
             }
         }
-        db
-    }
 
-    /// Look up the colour escape for a file entry.
-    fn color_for(&self, entry: &FileEntry) -> &str {
-        let mode = entry.mode;
-        let ft = mode & (libc::S_IFMT as u32);
-
-        // Symlink
-        if ft == libc::S_IFLNK as u32 {
-            if entry.link_target_ok {
-                return &self.link;
-            } else {
-                return &self.orphan;
-            }
+        if self.indicators[Indicator::Link as usize].as_deref() == Some(b"target") {
+            self.link_as_target = true;
         }
 
-        // Directory with special bits
-        if ft == libc::S_IFDIR as u32 {
-            let sticky = mode & (libc::S_ISVTX as u32) != 0;
-            let ow = mode & (libc::S_IWOTH as u32) != 0;
-            if sticky && ow {
-                return &self.sticky_other_writable;
-            }
-            if ow {
-                return &self.other_writable;
+        // Suffixes match case-insensitively unless entries differing only in
+        // case map to different colours.  Later definitions override earlier ones.
+        let mut i = 0;
+        while i < self.exts.len() {
+            let mut case_ignored = false;
+            let mut j = i + 1;
+            while j < self.exts.len() {
+                let (a, b) = (&self.exts[i], &self.exts[j]);
+                if a.suffix == b.suffix {
+                    self.exts.remove(j);
+                    continue;
+                }
+                if a.suffix.eq_ignore_ascii_case(&b.suffix) {
+                    if a.seq == b.seq {
+                        self.exts.remove(j);
+                        case_ignored = true;
+                        continue;
+                    }
+                    self.exts[i].exact = true;
+                    self.exts[j].exact = true;
+                }
+                j += 1;
             }
-            if sticky {
-                return &self.sticky;
+            if case_ignored {
+                self.exts[i].exact = false;
             }
-            return &self.dir;
+            i += 1;
         }
+        Ok(())
+    }
 
-        // Special files
-        if ft == libc::S_IFIFO as u32 {
-            return &self.pipe;
-        }
-        if ft == libc::S_IFSOCK as u32 {
-            return &self.socket;
-        }
-        if ft == libc::S_IFBLK as u32 {
-            return &self.block_dev;
+    fn seq(&self, ind: Indicator) -> Option<&[u8]> {
+        self.indicators[ind as usize].as_deref()
+    }
+
+    fn is_colored(&self, ind: Indicator) -> bool {
+        self.seq(ind)
+            .is_some_and(|s| !matches!(s, b"" | b"0" | b"00"))
+    }
+
+    /// Whether symlink targets must be resolved, as GNU's `check_symlink_mode`.
+    fn checks_link_targets(&self, config: &LsConfig) -> bool {
+        self.is_colored(Indicator::Orphan)
+            || (self.is_colored(Indicator::Exec) && self.link_as_target)
+            || (self.is_colored(Indicator::Missing) && config.format == OutputFormat::Long)
+    }
+
+    fn ext_color(&self, name: &[u8]) -> Option<&[u8]> {
+        self.exts
+            .iter()
+            .find(|e| {
+                name.len() >= e.suffix.len() && {
+                    let tail = &name[name.len() - e.suffix.len()..];
+                    if e.exact {
+                        tail == e.suffix.as_slice()
+                    } else {
+                        tail.eq_ignore_ascii_case(&e.suffix)
+                    }
+                }
+            })
+            .map(|e| e.seq.as_slice())
+    }
+
+    /// Look up the colour sequence for an entry, or for its symlink target when
+    /// `target` is set.  `None` means the name is written uncoloured.
+    fn color_for(&self, entry: &FileEntry, target: bool, config: &LsConfig) -> Option<&[u8]> {
+        use Indicator::*;
+        let is_link = entry.mode & (libc::S_IFMT as u32) == libc::S_IFLNK as u32;
+        // GNU only stats the target when something depends on it.
+        let link_ok = entry.link_target_ok
+            && (!is_link
+                || self.checks_link_targets(config)
+                || (config.format == OutputFormat::Long
+                    && matches!(
+                        config.indicator_style,
+                        IndicatorStyle::FileType | IndicatorStyle::Classify
+                    )));
+        let (name, mode) = if target {
+            let mode = if link_ok { entry.link_target_mode } else { 0 };
+            (entry.link_target.as_deref().unwrap_or(""), mode)
+        } else if is_link && link_ok && self.link_as_target {
+            (entry.name.as_str(), entry.link_target_mode)
+        } else {
+            (entry.name.as_str(), entry.mode)
+        };
+
+        let ind = if target && !link_ok && self.is_colored(Missing) {
+            Missing
+        } else {
+            match mode & (libc::S_IFMT as u32) {
+                m if m == libc::S_IFREG as u32 => {
+                    let exec = libc::S_IXUSR | libc::S_IXGRP | libc::S_IXOTH;
+                    if mode & libc::S_ISUID as u32 != 0 && self.is_colored(Setuid) {
+                        Setuid
+                    } else if mode & libc::S_ISGID as u32 != 0 && self.is_colored(Setgid) {
+                        Setgid
+                    } else if mode & exec as u32 != 0 && self.is_colored(Exec) {
+                        Exec
+                    } else if entry.nlink > 1 && self.is_colored(MultiHardlink) {
+                        MultiHardlink
+                    } else {
+                        File
+                    }
+                }
+                m if m == libc::S_IFDIR as u32 => {
+                    let sticky = mode & libc::S_ISVTX as u32 != 0;
+                    let ow = mode & libc::S_IWOTH as u32 != 0;
+                    if sticky && ow && self.is_colored(StickyOtherWritable) {
+                        StickyOtherWritable
+                    } else if ow && self.is_colored(OtherWritable) {
+                        OtherWritable
+                    } else if sticky && self.is_colored(Sticky) {
+                        Sticky
+                    } else {
+                        Dir
+                    }
+                }
+                m if m == libc::S_IFLNK as u32 => Link,
+                m if m == libc::S_IFIFO as u32 => Fifo,
+                m if m == libc::S_IFSOCK as u32 => Sock,
+                m if m == libc::S_IFBLK as u32 => Blk,
+                m if m == libc::S_IFCHR as u32 => Chr,
+                _ => Orphan,
+            }
+        };
+
+        if ind == File
+            && let Some(seq) = self.ext_color(name.as_bytes())
+        {
+            return Some(seq);
         }
-        if ft == libc::S_IFCHR as u32 {
-            return &self.char_dev;
+        let ind = if ind == Link && !link_ok && (self.link_as_target || self.is_colored(Orphan)) {
+            Orphan
+        } else {
+            ind
+        };
+        self.seq(ind)
+    }
+
+    fn put(&self, out: &mut impl Write, seq: &[u8]) -> io::Result<()> {
+        if !self.used.replace(true) {
+            self.end_color(out)?;
         }
+        out.write_all(seq)
+    }
+
+    fn put_indicator(&self, out: &mut impl Write, ind: Indicator) -> io::Result<()> {
+        self.put(out, self.seq(ind).unwrap_or_default())
+    }
 
-        // Setuid / setgid
-        if mode & (libc::S_ISUID as u32) != 0 {
-            return &self.setuid;
+    /// Terminate a coloured name: `ec`, or `lc rs rc` when it is unset.
+    fn end_color(&self, out: &mut impl Write) -> io::Result<()> {
+        match self.seq(Indicator::End) {
+            Some(ec) => self.put(out, ec),
+            None => {
+                self.put_indicator(out, Indicator::Left)?;
+                self.put_indicator(out, Indicator::Reset)?;
+                self.put_indicator(out, Indicator::Right)
+            }
         }
-        if mode & (libc::S_ISGID as u32) != 0 {
-            return &self.setgid;
+    }
+
+    /// Switch to the `no` colour at the start of an entry, if one is set.
+    fn set_normal_color(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.is_colored(Indicator::Norm) {
+            self.put_indicator(out, Indicator::Left)?;
+            self.put_indicator(out, Indicator::Norm)?;
+            self.put_indicator(out, Indicator::Right)?;
         }
+        Ok(())
+    }
 
-        // Extension match
-        if let Some(ext_pos) = entry.name.rfind('.') {
-            let ext = &entry.name[ext_pos..];
-            if let Some(c) = self.map.get(ext) {
-                return c;
+    /// Start colouring a name with `seq`; returns whether it must be ended.
+    fn start_color(&self, out: &mut impl Write, seq: Option<&[u8]>) -> io::Result<bool> {
+        if let Some(seq) = seq {
+            if self.is_colored(Indicator::Norm) {
+                self.put_indicator(out, Indicator::Left)?;
+                self.put_indicator(out, Indicator::Right)?;
             }
+            self.put_indicator(out, Indicator::Left)?;
+            self.put(out, seq)?;
+            self.put_indicator(out, Indicator::Right)?;
         }
+        Ok(seq.is_some() || self.is_colored(Indicator::Norm))
+    }
 
-        // Executable
-        if ft == libc::S_IFREG as u32
-            && mode & (libc::S_IXUSR as u32 | libc::S_IXGRP as u32 | libc::S_IXOTH as u32) != 0
+    /// Restore the terminal after the listing when `lc`/`rc` were customised.
+    pub fn finish(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.used.get()
+            && (self.seq(Indicator::Left) != Some(b"\x1b[")
+                || self.seq(Indicator::Right) != Some(b"m"))
         {
-            return &self.exec;
+            self.put_indicator(out, Indicator::Left)?;
+            self.put_indicator(out, Indicator::Right)?;
         }
-
-        ""
+        Ok(())
     }
 }
 
@@ -385,6 +808,8 @@ pub struct FileEntry {
     pub link_target_ok: bool,
     /// Whether the symlink target is a directory (for --classify indicator on target).
     pub link_target_is_dir: bool,
+    /// Mode of the symlink target, or 0 when it could not be resolved.
+    pub link_target_mode: u32,
 }
 
 impl FileEntry {
@@ -444,20 +869,21 @@ impl FileEntry {
         let file_type = meta.file_type();
         let is_symlink = file_type.is_symlink();
 
-        let (link_target, link_target_ok, link_target_is_dir) = if is_symlink {
+        let (link_target, link_target_ok, link_target_is_dir, link_target_mode) = if is_symlink {
             match fs::read_link(&path) {
                 Ok(target) => match fs::metadata(&path) {
                     Ok(target_meta) => (
                         Some(target.to_string_lossy().into_owned()),
                         true,
                         target_meta.is_dir(),
+                        target_meta.mode(),
                     ),
-                    Err(_) => (Some(target.to_string_lossy().into_owned()), false, false),
+                    Err(_) => (Some(target.to_string_lossy().into_owned()), false, false, 0),
                 },
-                Err(_) => (None, false, false),
+                Err(_) => (None, false, false, 0),
             }
         } else {
-            (None, true, false)
+            (None, true, false, 0)
         };
 
         let rdev = meta.rdev();
@@ -486,6 +912,7 @@ impl FileEntry {
             link_target,
             link_target_ok,
             link_target_is_dir,
+            link_target_mode,
         })
     }
 
@@ -585,6 +1012,7 @@ impl FileEntry {
             link_target: None,
             link_target_ok: false,
             link_target_is_dir: false,
+            link_target_mode: 0,
         }
     }
 
@@ -1060,44 +1488,109 @@ pub fn parse_block_size(s: &str) -> Result<(u64, String), String> {
     Ok((block_size, suffix))
 }
 
+/// Apply a --block-size / LS_BLOCK_SIZE / BLOCK_SIZE value to the config.
+/// Besides sizes, GNU accepts `human-readable` and `si` (same as -h and --si)
+/// and a leading `'` asking for digit grouping, which the C locale lacks.
+/// Like -h and --si, this replaces whatever scaling was chosen before.
+pub fn apply_block_size(config: &mut LsConfig, s: &str) -> Result<(), String> {
+    let spec = s.strip_prefix('\'').unwrap_or(s);
+    match spec {
+        "human-readable" => set_human_readable(config, false),
+        "si" => set_human_readable(config, true),
+        _ => {
+            let (bs, suffix) = parse_block_size(spec)?;
+            config.human_readable = false;
+            config.si = false;
+            config.block_size = Some(bs);
+            config.block_size_suffix = suffix;
+            config.block_size_from_env = false;
+        }
+    }
+    Ok(())
+}
+
+/// -h (powers of 1024) or --si (powers of 1000); overrides --block-size.
+pub fn set_human_readable(config: &mut LsConfig, si: bool) {
+    config.human_readable = !si;
+    config.si = si;
+    config.block_size = None;
+    config.block_size_suffix.clear();
+    config.block_size_from_env = false;
+}
+
+/// Like GNU ls, let -c, -u and --time=WORD choose the sort key as well when
+/// no sort option was given and the listing is not long: `ls -c` sorts by
+/// ctime, while `ls -lc` shows ctime but sorts by name unless -t is given.
+pub fn apply_time_sort_default(config: &mut LsConfig) {
+    if config.sort_by == SortBy::Name
+        && config.format != OutputFormat::Long
+        && config.time_field != TimeField::Mtime
+    {
+        config.sort_by = SortBy::Time;
+    }
+}
+
+/// Fall back to LS_BLOCK_SIZE, then BLOCK_SIZE, when no option chose how to
+/// scale sizes. Invalid values are ignored, as GNU ls does.
+pub fn apply_block_size_env(config: &mut LsConfig) {
+    if config.block_size.is_some() || config.human_readable || config.si {
+        return;
+    }
+    for var in ["LS_BLOCK_SIZE", "BLOCK_SIZE"] {
+        if let Ok(val) = std::env::var(var) {
+            if apply_block_size(config, &val).is_ok() {
+                config.block_size_from_env = true;
+            }
+            return;
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Size formatting
 // ---------------------------------------------------------------------------
 
+/// Format a size in human-readable form the way GNU does for ls: round up,
+/// one decimal below 10, and lowercase `k` for SI kilo.
+fn format_human(size: u64, si: bool) -> String {
+    let base: u128 = if si { 1000 } else { 1024 };
+    let suffixes: [&str; 7] = if si {
+        ["", "k", "M", "G", "T", "P", "E"]
+    } else {
+        ["", "K", "M", "G", "T", "P", "E"]
+    };
+    let size = size as u128;
+    let mut divisor: u128 = 1;
+    let mut power = 0;
+    while size >= divisor * base && power < suffixes.len() - 1 {
+        divisor *= base;
+        power += 1;
+    }
+    if power == 0 {
+        return size.to_string();
+    }
+    let tenths = (size * 10).div_ceil(divisor);
+    if tenths < 100 {
+        return format!("{}.{}{}", tenths / 10, tenths % 10, suffixes[power]);
+    }
+    let whole = size.div_ceil(divisor);
+    if whole >= base && power + 1 < suffixes.len() {
+        // Rounding up reached the next unit, e.g. 1023.1K -> 1.0M
+        return format!("1.0{}", suffixes[power + 1]);
+    }
+    format!("{}{}", whole, suffixes[power])
+}
+
 /// Format a file size for display.
 pub fn format_size(size: u64, config: &LsConfig) -> String {
-    // --block-size takes precedence over -h / --si / -k
     if let Some(bs) = config.block_size {
-        let scaled = if bs == 0 { size } else { (size + bs - 1) / bs };
+        let scaled = if bs == 0 { size } else { size.div_ceil(bs) };
         return format!("{}{}", scaled, config.block_size_suffix);
     }
     if config.human_readable || config.si {
-        let base: f64 = if config.si { 1000.0 } else { 1024.0 };
-        let suffixes = ["", "K", "M", "G", "T", "P", "E"];
-
-        if size == 0 {
-            return "0".to_string();
-        }
-
-        let mut val = size as f64;
-        let mut idx = 0;
-        while val >= base && idx < suffixes.len() - 1 {
-            val /= base;
-            idx += 1;
-        }
-
-        if idx == 0 {
-            format!("{}", size)
-        } else if val >= 10.0 {
-            format!("{:.0}{}", val, suffixes[idx])
-        } else {
-            format!("{:.1}{}", val, suffixes[idx])
-        }
-    } else if config.kibibytes {
-        // Show blocks in 1K units
-        let blocks_k = (size + 1023) / 1024;
-        format!("{}", blocks_k)
+        format_human(size, config.si)
     } else {
+        // -k only affects block counts, not the size column
         format!("{}", size)
     }
 }
@@ -1105,12 +1598,11 @@ pub fn format_size(size: u64, config: &LsConfig) -> String {
 /// Format blocks for the -s option (in 1K units by default, or --si / -h).
 pub fn format_blocks(blocks_512: u64, config: &LsConfig) -> String {
     let bytes = blocks_512 * 512;
+    if config.kibibytes && config.block_size_from_env {
+        return format!("{}", bytes.div_ceil(1024));
+    }
     if let Some(bs) = config.block_size {
-        let scaled = if bs == 0 {
-            bytes
-        } else {
-            (bytes + bs - 1) / bs
-        };
+        let scaled = if bs == 0 { bytes } else { bytes.div_ceil(bs) };
         return format!("{}{}", scaled, config.block_size_suffix);
     }
     if config.human_readable || config.si {
@@ -1369,8 +1861,8 @@ pub fn read_entries(path: &Path, config: &LsConfig) -> io::Result<Vec<FileEntry>
         if let Ok(e) = FileEntry::from_path_with_name(".".to_string(), path, config) {
             entries.push(e);
         }
-        let parent = path.parent().unwrap_or(path);
-        if let Ok(e) = FileEntry::from_path_with_name("..".to_string(), parent, config) {
+        let parent = path.join("..");
+        if let Ok(e) = FileEntry::from_path_with_name("..".to_string(), &parent, config) {
             entries.push(e);
         }
     }
@@ -1400,16 +1892,171 @@ pub fn read_entries(path: &Path, config: &LsConfig) -> io::Result<Vec<FileEntry>
     Ok(entries)
 }
 
+// ---------------------------------------------------------------------------
+// Names, colours and hyperlinks
+// ---------------------------------------------------------------------------
+
+/// Host name used in `file://` hyperlinks (empty if it cannot be determined).
+fn hyperlink_host() -> &'static str {
+    static HOST: OnceLock<String> = OnceLock::new();
+    HOST.get_or_init(|| {
+        let mut buf = [0u8; 256];
+        let rc = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+        if rc != 0 {
+            return String::new();
+        }
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        String::from_utf8_lossy(&buf[..len]).into_owned()
+    })
+}
+
+/// Build the `file://HOST/ABSPATH` URL for a path, percent-encoding every byte
+/// other than RFC 3986 unreserved characters and `/`.
+pub fn hyperlink_url(path: &Path) -> String {
+    let abs = fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let mut url = String::from("file://");
+    url.push_str(hyperlink_host());
+    for &b in abs.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'/') {
+            url.push(b as char);
+        } else {
+            url.push_str(&format!("%{:02X}", b));
+        }
+    }
+    url
+}
+
+/// Write a quoted name wrapped in an OSC 8 hyperlink to `path`.  Like GNU ls,
+/// enclosing quote characters are kept outside the link text.
+fn write_hyperlink(out: &mut impl Write, quoted: &str, path: &Path) -> io::Result<()> {
+    let bytes = quoted.as_bytes();
+    let quote = match bytes.first() {
+        Some(&q @ (b'\'' | b'"')) if bytes.len() >= 2 && bytes[bytes.len() - 1] == q => {
+            Some(q as char)
+        }
+        _ => None,
+    };
+    let inner = if quote.is_some() {
+        &quoted[1..quoted.len() - 1]
+    } else {
+        quoted
+    };
+    if let Some(q) = quote {
+        write!(out, "{}", q)?;
+    }
+    write!(
+        out,
+        "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+        hyperlink_url(path),
+        inner
+    )?;
+    if let Some(q) = quote {
+        write!(out, "{}", q)?;
+    }
+    Ok(())
+}
+
+/// Write an entry's quoted name with its colour and, if enabled, hyperlink.
+/// The colour escapes enclose the hyperlink escapes.
+fn write_name(
+    out: &mut impl Write,
+    quoted: &str,
+    entry: &FileEntry,
+    config: &LsConfig,
+    color_db: Option<&ColorDb>,
+) -> io::Result<()> {
+    write_colored(out, entry, false, config, color_db, |out| {
+        if config.hyperlink == HyperlinkMode::Always {
+            write_hyperlink(out, quoted, &entry.path)
+        } else {
+            write!(out, "{}", quoted)
+        }
+    })
+}
+
+/// Run `body` between the colour sequences for `entry`, or for its symlink
+/// target when `target` is set.
+fn write_colored<W: Write>(
+    out: &mut W,
+    entry: &FileEntry,
+    target: bool,
+    config: &LsConfig,
+    color_db: Option<&ColorDb>,
+    body: impl FnOnce(&mut W) -> io::Result<()>,
+) -> io::Result<()> {
+    let Some(db) = color_db else {
+        return body(out);
+    };
+    let colored = db.start_color(out, db.color_for(entry, target, config))?;
+    body(out)?;
+    if colored {
+        db.end_color(out)?;
+    }
+    Ok(())
+}
+
+/// Switch to the normal colour (`no` in LS_COLORS) before an entry.
+fn write_normal_color(out: &mut impl Write, color_db: Option<&ColorDb>) -> io::Result<()> {
+    match color_db {
+        Some(db) => db.set_normal_color(out),
+        None => Ok(()),
+    }
+}
+
+/// Byte offsets collected for `--dired`.  `pos` counts the bytes written so
+/// far, leaving out colour escapes as GNU ls does.
+#[derive(Default)]
+struct Dired {
+    pos: usize,
+    names: Vec<usize>,
+    subdirs: Vec<usize>,
+}
+
+impl Dired {
+    /// Record a name that starts `start` bytes into the line being written.
+    fn add_name(&mut self, start: usize, len: usize) {
+        self.names.push(self.pos + start);
+        self.names.push(self.pos + start + len);
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.pos += n;
+    }
+
+    /// Write the `//DIRED//`, `//SUBDIRED//` and `//DIRED-OPTIONS//` trailer.
+    fn write_trailer(&self, out: &mut impl Write, config: &LsConfig) -> io::Result<()> {
+        for (label, offsets) in [("//DIRED//", &self.names), ("//SUBDIRED//", &self.subdirs)] {
+            if offsets.is_empty() {
+                continue;
+            }
+            out.write_all(label.as_bytes())?;
+            for off in offsets {
+                write!(out, " {}", off)?;
+            }
+            writeln!(out)?;
+        }
+        writeln!(
+            out,
+            "//DIRED-OPTIONS// --quoting-style={}",
+            config.quoting_style.name()
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Long format output
 // ---------------------------------------------------------------------------
 
-/// Print entries in long format to the writer.
+/// Print entries in long format to the writer.  With `dired`, every line is
+/// indented by two spaces and the offsets of each name are recorded.
 fn print_long(
     out: &mut impl Write,
     entries: &[FileEntry],
     config: &LsConfig,
     color_db: Option<&ColorDb>,
+    mut dired: Option<&mut Dired>,
 ) -> io::Result<()> {
     if entries.is_empty() {
         return Ok(());
@@ -1514,46 +2161,61 @@ fn print_long(
         .max()
         .unwrap_or(12);
 
+    let mut line = Vec::with_capacity(256);
     for entry in entries {
+        line.clear();
+        write_normal_color(out, color_db)?;
+        if dired.is_some() {
+            line.extend_from_slice(b"  ");
+        }
+
         // Broken dereference placeholder: show l????????? ? ?<pad> ?<pad> ?<pad> ? name
         if entry.is_broken_deref() {
             let quoted = quote_name(&entry.name, config);
             if config.show_inode {
-                write!(out, "{:>width$} ", "?", width = max_inode)?;
+                write!(line, "{:>width$} ", "?", width = max_inode)?;
             }
             if config.show_size {
-                write!(out, "{:>width$} ", "?", width = max_blocks)?;
+                write!(line, "{:>width$} ", "?", width = max_blocks)?;
             }
-            write!(out, "l????????? ")?;
-            write!(out, "{:>width$} ", "?", width = max_nlink)?;
+            write!(line, "l????????? ")?;
+            write!(line, "{:>width$} ", "?", width = max_nlink)?;
             if config.show_owner {
-                write!(out, "{:<width$} ", "?", width = max_owner)?;
+                write!(line, "{:<width$} ", "?", width = max_owner)?;
             }
             if config.show_group {
-                write!(out, "{:<width$} ", "?", width = max_group)?;
+                write!(line, "{:<width$} ", "?", width = max_group)?;
             }
-            write!(out, "{:>width$} ", "?", width = max_size)?;
-            write!(out, "{:>width$} ", "?", width = ts_width)?;
-            writeln!(out, "{}", quoted)?;
+            write!(line, "{:>width$} ", "?", width = max_size)?;
+            write!(line, "{:>width$} ", "?", width = ts_width)?;
+            let name_start = line.len();
+            write_name(&mut line, &quoted, entry, config, color_db)?;
+            let color_len = line.len() - name_start - quoted.len();
+            line.push(b'\n');
+            if let Some(d) = dired.as_deref_mut() {
+                d.add_name(name_start, quoted.len());
+                d.advance(line.len() - color_len);
+            }
+            out.write_all(&line)?;
             continue;
         }
 
         // Inode
         if config.show_inode {
-            write!(out, "{:>width$} ", entry.ino, width = max_inode)?;
+            write!(line, "{:>width$} ", entry.ino, width = max_inode)?;
         }
 
         // Block size
         if config.show_size {
             let bs = format_blocks(entry.blocks, config);
-            write!(out, "{:>width$} ", bs, width = max_blocks)?;
+            write!(line, "{:>width$} ", bs, width = max_blocks)?;
         }
 
         // Permissions
-        write!(out, "{} ", format_permissions(entry.mode))?;
+        write!(line, "{} ", format_permissions(entry.mode))?;
 
         // Hard link count
-        write!(out, "{:>width$} ", entry.nlink, width = max_nlink)?;
+        write!(line, "{:>width$} ", entry.nlink, width = max_nlink)?;
 
         // Owner
         if config.show_owner {
@@ -1562,7 +2224,7 @@ fn print_long(
             } else {
                 lookup_user(entry.uid)
             };
-            write!(out, "{:<width$} ", owner, width = max_owner)?;
+            write!(line, "{:<width$} ", owner, width = max_owner)?;
         }
 
         // Group
@@ -1572,17 +2234,17 @@ fn print_long(
             } else {
                 lookup_group(entry.gid)
             };
-            write!(out, "{:<width$} ", group, width = max_group)?;
+            write!(line, "{:<width$} ", group, width = max_group)?;
         }
 
         // Size or device numbers
         let ft = entry.mode & (libc::S_IFMT as u32);
         if ft == libc::S_IFBLK as u32 || ft == libc::S_IFCHR as u32 {
             let dev = format!("{}, {}", entry.rdev_major, entry.rdev_minor);
-            write!(out, "{:>width$} ", dev, width = max_size)?;
+            write!(line, "{:>width$} ", dev, width = max_size)?;
         } else {
             let sz = format_size(entry.size, config);
-            write!(out, "{:>width$} ", sz, width = max_size)?;
+            write!(line, "{:>width$} ", sz, width = max_size)?;
         }
 
         // Timestamp
@@ -1591,47 +2253,51 @@ fn print_long(
             entry.time_nsec(config.time_field),
             &config.time_style,
         );
-        write!(out, "{} ", ts)?;
+        write!(line, "{} ", ts)?;
 
-        // Name (with colour)
+        // Name (with colour and hyperlink)
         let quoted = quote_name(&entry.name, config);
-        if let Some(db) = color_db {
-            let c = db.color_for(entry);
-            if c.is_empty() {
-                write!(out, "{}", quoted)?;
-            } else {
-                write!(out, "{}{}{}", c, quoted, db.reset)?;
-            }
-        } else {
-            write!(out, "{}", quoted)?;
-        }
+        let name_start = line.len();
+        write_name(&mut line, &quoted, entry, config, color_db)?;
+        // Like GNU, colour escapes do not count towards dired offsets.
+        let mut color_len = line.len() - name_start - quoted.len();
 
         // Indicator
         let ind = entry.indicator(config.indicator_style);
         if !ind.is_empty() {
-            write!(out, "{}", ind)?;
+            write!(line, "{}", ind)?;
         }
 
         // Symlink target
         if let Some(ref target) = entry.link_target {
             let quoted_target = quote_name(target, config);
-            write!(out, " -> {}", quoted_target)?;
+            write!(line, " -> ")?;
+            let target_start = line.len();
+            write_colored(&mut line, entry, true, config, color_db, |out| {
+                write!(out, "{}", quoted_target)
+            })?;
+            color_len += line.len() - target_start - quoted_target.len();
             // Append classify indicator for the target (e.g. '/' if target is a dir)
             if config.indicator_style == IndicatorStyle::Classify
                 || config.indicator_style == IndicatorStyle::FileType
                 || config.indicator_style == IndicatorStyle::Slash
             {
                 if entry.link_target_is_dir {
-                    write!(out, "/")?;
+                    write!(line, "/")?;
                 }
             }
         }
 
         if config.zero {
-            out.write_all(&[0u8])?;
+            line.push(0);
         } else {
-            writeln!(out)?;
+            line.push(b'\n');
+        }
+        if let Some(d) = dired.as_deref_mut() {
+            d.add_name(name_start, quoted.len());
+            d.advance(line.len() - color_len);
         }
+        out.write_all(&line)?;
     }
 
     Ok(())
@@ -1677,7 +2343,9 @@ fn write_entry_prefix(
     config: &LsConfig,
     max_inode_w: usize,
     max_blocks_w: usize,
+    color_db: Option<&ColorDb>,
 ) -> io::Result<()> {
+    write_normal_color(out, color_db)?;
     if config.show_inode {
         write!(out, "{:>width$} ", entry.ino, width = max_inode_w)?;
     }
@@ -1688,7 +2356,7 @@ fn write_entry_prefix(
     Ok(())
 }
 
-/// Write a file name with optional colour.
+/// Write a file name with optional colour and hyperlink.
 fn write_entry_name(
     out: &mut impl Write,
     display: &str,
@@ -1696,15 +2364,10 @@ fn write_entry_name(
     config: &LsConfig,
     color_db: Option<&ColorDb>,
 ) -> io::Result<()> {
-    if let Some(db) = color_db {
-        let c = db.color_for(entry);
+    if color_db.is_some() || config.hyperlink == HyperlinkMode::Always {
         let quoted = quote_name(&entry.name, config);
-        let ind = entry.indicator(config.indicator_style);
-        if c.is_empty() {
-            write!(out, "{}{}", quoted, ind)?;
-        } else {
-            write!(out, "{}{}{}{}", c, quoted, db.reset, ind)?;
-        }
+        write_name(out, &quoted, entry, config, color_db)?;
+        write!(out, "{}", entry.indicator(config.indicator_style))?;
     } else {
         write!(out, "{}", display)?;
     }
@@ -1779,17 +2442,9 @@ fn print_with_separator(
             }
         }
 
-        write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w)?;
-        if let Some(db) = color_db {
-            let c = db.color_for(entry);
-            if c.is_empty() {
-                write!(out, "{}{}", quoted, ind)?;
-            } else {
-                write!(out, "{}{}{}{}", c, quoted, db.reset, ind)?;
-            }
-        } else {
-            write!(out, "{}{}", quoted, ind)?;
-        }
+        write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w, color_db)?;
+        write_name(out, &quoted, entry, config, color_db)?;
+        write!(out, "{}", ind)?;
         pos += len;
     }
     if !entries.is_empty() {
@@ -1940,7 +2595,7 @@ fn print_columns(
                 let (ref display, w, entry) = items[filesno];
                 let max_w = col_arr[col];
 
-                write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w)?;
+                write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w, color_db)?;
                 write_entry_name(out, display, entry, config, color_db)?;
 
                 if n.saturating_sub(num_rows) <= filesno {
@@ -1957,7 +2612,7 @@ fn print_columns(
     } else {
         // Row-major (-x): entries fill across rows first
         let (ref display0, w0, entry0) = items[0];
-        write_entry_prefix(out, entry0, config, max_inode_w, max_blocks_w)?;
+        write_entry_prefix(out, entry0, config, max_inode_w, max_blocks_w, color_db)?;
         write_entry_name(out, display0, entry0, config, color_db)?;
 
         let mut pos: usize = 0;
@@ -1976,7 +2631,7 @@ fn print_columns(
             }
 
             let (ref display, w, entry) = items[filesno];
-            write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w)?;
+            write_entry_prefix(out, entry, config, max_inode_w, max_blocks_w, color_db)?;
             write_entry_name(out, display, entry, config, color_db)?;
 
             prev_w = w;
@@ -2018,6 +2673,7 @@ fn print_single_column(
     };
 
     for entry in entries {
+        write_normal_color(out, color_db)?;
         if config.show_inode {
             write!(out, "{:>width$} ", entry.ino, width = max_inode_w)?;
         }
@@ -2027,16 +2683,7 @@ fn print_single_column(
         }
 
         let quoted = quote_name(&entry.name, config);
-        if let Some(db) = color_db {
-            let c = db.color_for(entry);
-            if c.is_empty() {
-                write!(out, "{}", quoted)?;
-            } else {
-                write!(out, "{}{}{}", c, quoted, db.reset)?;
-            }
-        } else {
-            write!(out, "{}", quoted)?;
-        }
+        write_name(out, &quoted, entry, config, color_db)?;
 
         let ind = entry.indicator(config.indicator_style);
         if !ind.is_empty() {
@@ -2069,8 +2716,16 @@ pub fn print_comma(
     for (i, entry) in entries.iter().enumerate() {
         let quoted = quote_name(&entry.name, config);
         let ind = entry.indicator(config.indicator_style);
+        // GNU leaves the -i/-s fields unpadded here, but they count toward the width.
+        let mut prefix = String::new();
+        if config.show_inode {
+            prefix.push_str(&format!("{} ", entry.ino));
+        }
+        if config.show_size {
+            prefix.push_str(&format!("{} ", format_blocks(entry.blocks, config)));
+        }
         let name_len = if line_length > 0 {
-            quoted.len() + ind.len()
+            prefix.len() + quoted.len() + ind.len()
         } else {
             0
         };
@@ -2090,16 +2745,10 @@ pub fn print_comma(
             }
         }
 
-        if let Some(db) = color_db {
-            let c = db.color_for(entry);
-            if c.is_empty() {
-                write!(out, "{}{}", quoted, ind)?;
-            } else {
-                write!(out, "{}{}{}{}", c, quoted, db.reset, ind)?;
-            }
-        } else {
-            write!(out, "{}{}", quoted, ind)?;
-        }
+        write_normal_color(out, color_db)?;
+        out.write_all(prefix.as_bytes())?;
+        write_name(out, &quoted, entry, config, color_db)?;
+        write!(out, "{}", ind)?;
         pos += name_len;
     }
     if !entries.is_empty() {
@@ -2112,9 +2761,18 @@ pub fn print_comma(
 // Total blocks line
 // ---------------------------------------------------------------------------
 
-fn print_total(out: &mut impl Write, entries: &[FileEntry], config: &LsConfig) -> io::Result<()> {
+fn print_total(
+    out: &mut impl Write,
+    entries: &[FileEntry],
+    config: &LsConfig,
+    dired: Option<&mut Dired>,
+) -> io::Result<()> {
     let total_blocks: u64 = entries.iter().map(|e| e.blocks).sum();
     let formatted = format_blocks(total_blocks, config);
+    if let Some(d) = dired {
+        out.write_all(b"  ")?;
+        d.advance("  total ".len() + formatted.len() + 1);
+    }
     write!(out, "total {}", formatted)?;
     if config.zero {
         out.write_all(&[0u8])
@@ -2127,6 +2785,11 @@ fn print_total(out: &mut impl Write, entries: &[FileEntry], config: &LsConfig) -
 // Main entry point
 // ---------------------------------------------------------------------------
 
+/// Exit status for minor problems, such as a subdirectory that cannot be read.
+pub const LS_MINOR_PROBLEM: i32 = 1;
+/// Exit status for serious trouble, such as a directory loop under `-RL`.
+pub const LS_FAILURE: i32 = 2;
+
 /// List a single directory to the provided writer.
 pub fn ls_dir(
     out: &mut impl Write,
@@ -2135,23 +2798,95 @@ pub fn ls_dir(
     color_db: Option<&ColorDb>,
     show_header: bool,
 ) -> io::Result<bool> {
+    list_dir(
+        out,
+        path,
+        config,
+        color_db,
+        show_header,
+        false,
+        &mut HashSet::new(),
+        None,
+    )
+    .map(|status| status == 0)
+}
+
+/// List a directory and, with `-R`, its subdirectories.
+///
+/// `separate` writes the blank line that precedes the header of every listed
+/// directory but the first; it is only written once the directory has been
+/// read. `active` holds the (dev, ino) of the directories being listed on the
+/// current descent so that symlink loops under `-L` are not followed.
+///
+/// Returns the exit status so far: 0, `LS_MINOR_PROBLEM` or `LS_FAILURE`.
+#[allow(clippy::too_many_arguments)]
+fn list_dir(
+    out: &mut impl Write,
+    path: &Path,
+    config: &LsConfig,
+    color_db: Option<&ColorDb>,
+    show_header: bool,
+    separate: bool,
+    active: &mut HashSet<(u64, u64)>,
+    mut dired: Option<&mut Dired>,
+) -> io::Result<i32> {
+    let mut entries = read_entries(path, config)?;
+
+    let key = if config.recursive {
+        fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+    } else {
+        None
+    };
+    if let Some(key) = key
+        && !active.insert(key)
+    {
+        // GNU treats a loop as a serious error; flush first so the message
+        // lands after the listing that led to it.
+        out.flush()?;
+        eprintln!(
+            "ls: {}: not listing already-listed directory",
+            path.display()
+        );
+        return Ok(LS_FAILURE);
+    }
+
+    if separate {
+        writeln!(out)?;
+        if let Some(d) = dired.as_deref_mut() {
+            d.advance(1);
+        }
+    }
     if show_header {
-        writeln!(out, "{}:", path.display())?;
+        if let Some(d) = dired.as_deref_mut() {
+            let name = path.display().to_string();
+            writeln!(out, "  {}:", name)?;
+            d.subdirs.push(d.pos + 2);
+            d.subdirs.push(d.pos + 2 + name.len());
+            d.advance(name.len() + 4);
+        } else if config.hyperlink == HyperlinkMode::Always {
+            write_hyperlink(out, &path.display().to_string(), path)?;
+            writeln!(out, ":")?;
+        } else {
+            writeln!(out, "{}:", path.display())?;
+        }
     }
 
-    let mut entries = read_entries(path, config)?;
     sort_entries(&mut entries, config);
 
     // Track if any entries have errors (e.g., broken symlink with -L)
-    let has_broken_deref = entries.iter().any(|e| e.is_broken_deref());
+    let mut status = if entries.iter().any(|e| e.is_broken_deref()) {
+        LS_MINOR_PROBLEM
+    } else {
+        0
+    };
 
     // Print total in long / show_size modes
     if config.long_format || config.show_size {
-        print_total(out, &entries, config)?;
+        print_total(out, &entries, config, dired.as_deref_mut())?;
     }
 
     match config.format {
-        OutputFormat::Long => print_long(out, &entries, config, color_db)?,
+        OutputFormat::Long => print_long(out, &entries, config, color_db, dired.as_deref_mut())?,
         OutputFormat::SingleColumn => print_single_column(out, &entries, config, color_db)?,
         OutputFormat::Columns | OutputFormat::Across => {
             print_columns(out, &entries, config, color_db)?
@@ -2173,20 +2908,59 @@ pub fn ls_dir(
             .collect();
 
         for dir in dirs {
-            writeln!(out)?;
-            ls_dir(out, &dir, config, color_db, true)?;
+            match list_dir(
+                out,
+                &dir,
+                config,
+                color_db,
+                true,
+                true,
+                active,
+                dired.as_deref_mut(),
+            ) {
+                Ok(sub) => status = status.max(sub),
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Err(e),
+                Err(e) => {
+                    eprintln!(
+                        "ls: cannot open directory '{}': {}",
+                        dir.display(),
+                        crate::common::io_error_msg(&e)
+                    );
+                    status = status.max(LS_MINOR_PROBLEM);
+                }
+            }
         }
     }
 
-    Ok(!has_broken_deref)
+    if let Some(key) = key {
+        active.remove(&key);
+    }
+
+    Ok(status)
 }
 
 /// Top-level entry: list the given paths.
 ///
-/// Returns `true` if all operations succeeded.
-pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
+/// Returns the exit status: 0 on success, `LS_MINOR_PROBLEM` or `LS_FAILURE`.
+pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<i32> {
     let stdout = io::stdout();
     let is_tty = atty_stdout();
+
+    // Resolve --hyperlink=auto once so the printers only check for Always.
+    let resolved;
+    let config = if config.hyperlink == HyperlinkMode::Auto {
+        resolved = LsConfig {
+            hyperlink: if is_tty {
+                HyperlinkMode::Always
+            } else {
+                HyperlinkMode::Never
+            },
+            ..config.clone()
+        };
+        &resolved
+    } else {
+        config
+    };
     // For pipes: shrink kernel pipe buffer to 4 KB so our writes block once the
     // buffer fills, allowing SIGPIPE to be delivered when the reader closes
     // early (e.g. `ls /big-dir | head -5` → exit 141 like GNU ls).
@@ -2201,18 +2975,18 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
     let mut out = BufWriter::with_capacity(buf_cap, stdout.lock());
 
     let color_db = match config.color {
-        ColorMode::Always => Some(ColorDb::from_env()),
-        ColorMode::Auto => {
-            if atty_stdout() {
-                Some(ColorDb::from_env())
-            } else {
-                None
-            }
-        }
-        ColorMode::Never => None,
+        ColorMode::Always => ColorDb::from_env(),
+        ColorMode::Auto if atty_stdout() => ColorDb::from_env(),
+        ColorMode::Auto | ColorMode::Never => None,
     };
 
-    let mut had_error = false;
+    // --dired only applies to long listings, and GNU drops it for hyperlinks.
+    let mut dired = (config.dired
+        && config.format == OutputFormat::Long
+        && config.hyperlink != HyperlinkMode::Always)
+        .then(Dired::default);
+
+    let mut status = 0;
 
     // Separate files and directories
     let mut file_args: Vec<FileEntry> = Vec::new();
@@ -2233,7 +3007,7 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
                                 p,
                                 crate::common::io_error_msg(&e)
                             );
-                            had_error = true;
+                            status = status.max(LS_MINOR_PROBLEM);
                             file_args.push(FileEntry::broken_deref(p.to_string(), path));
                             continue;
                         }
@@ -2252,7 +3026,7 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
                         Ok(fe) => file_args.push(fe),
                         Err(e) => {
                             eprintln!("ls: cannot access '{}': {}", p, e);
-                            had_error = true;
+                            status = status.max(LS_MINOR_PROBLEM);
                         }
                     }
                 } else {
@@ -2265,7 +3039,7 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
                     p,
                     crate::common::io_error_msg(&e)
                 );
-                had_error = true;
+                status = status.max(LS_MINOR_PROBLEM);
             }
         }
     }
@@ -2276,7 +3050,13 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
     // Print file arguments
     if !file_args.is_empty() {
         match config.format {
-            OutputFormat::Long => print_long(&mut out, &file_args, config, color_db.as_ref())?,
+            OutputFormat::Long => print_long(
+                &mut out,
+                &file_args,
+                config,
+                color_db.as_ref(),
+                dired.as_mut(),
+            )?,
             OutputFormat::SingleColumn => {
                 print_single_column(&mut out, &file_args, config, color_db.as_ref())?
             }
@@ -2288,25 +3068,34 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
     }
 
     // Sort directory args by name using locale-aware comparison
-    dir_args.sort_by(|a, b| {
-        let an = a.to_string_lossy();
-        let bn = b.to_string_lossy();
-        let ord = locale_cmp(&an, &bn);
-        if config.reverse { ord.reverse() } else { ord }
-    });
+    // (-U keeps them in command-line order)
+    if config.sort_by != SortBy::None {
+        dir_args.sort_by(|a, b| {
+            let an = a.to_string_lossy();
+            let bn = b.to_string_lossy();
+            let ord = locale_cmp(&an, &bn);
+            if config.reverse { ord.reverse() } else { ord }
+        });
+    }
 
-    let show_header =
-        dir_args.len() > 1 || (!file_args.is_empty() && !dir_args.is_empty()) || config.recursive;
+    // GNU prints headers whenever there is more than one operand, even if
+    // some of them could not be accessed.
+    let show_header = paths.len() > 1 || config.recursive;
 
     for (i, dir) in dir_args.iter().enumerate() {
-        if i > 0 || !file_args.is_empty() {
-            writeln!(out)?;
-        }
-        match ls_dir(&mut out, dir, config, color_db.as_ref(), show_header) {
-            Ok(true) => {}
-            Ok(false) => {
-                had_error = true;
-            }
+        let separate = i > 0 || !file_args.is_empty();
+        let mut active = HashSet::new();
+        match list_dir(
+            &mut out,
+            dir,
+            config,
+            color_db.as_ref(),
+            show_header,
+            separate,
+            &mut active,
+            dired.as_mut(),
+        ) {
+            Ok(dir_status) => status = status.max(dir_status),
             Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Err(e),
             Err(e) => {
                 eprintln!(
@@ -2314,14 +3103,20 @@ pub fn ls_main(paths: &[String], config: &LsConfig) -> io::Result<bool> {
                     dir.display(),
                     crate::common::io_error_msg(&e)
                 );
-                had_error = true;
+                status = status.max(LS_MINOR_PROBLEM);
             }
         }
     }
 
+    if let Some(ref db) = color_db {
+        db.finish(&mut out)?;
+    }
+    if let Some(ref d) = dired {
+        d.write_trailer(&mut out, config)?;
+    }
     out.flush()?;
 
-    Ok(!had_error)
+    Ok(status)
 }
 
 /// Check if stdout is a TTY.
@@ -2343,7 +3138,7 @@ pub fn collect_entries(path: &Path, config: &LsConfig) -> io::Result<Vec<FileEnt
 /// Render long format lines to a String (for testing).
 pub fn render_long(entries: &[FileEntry], config: &LsConfig) -> io::Result<String> {
     let mut buf = Vec::new();
-    print_long(&mut buf, entries, config, None)?;
+    print_long(&mut buf, entries, config, None, None)?;
     Ok(String::from_utf8_lossy(&buf).into_owned())
 }
 
@@ -2,22 +2,11 @@ use std::fs;
 use std::io;
 use std::path::Path;
 
+pub use crate::common::backup::{BackupMode, backup_suffix, make_backup_name, parse_backup_mode};
+
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
-/// Backup mode for destination files.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum BackupMode {
-    /// Simple backup: append suffix (default `~`).
-    Simple,
-    /// Numbered backup: append `.~N~`.
-    Numbered,
-    /// Existing: numbered if numbered backups exist, otherwise simple.
-    Existing,
-    /// Never make backups (same as not specifying --backup).
-    None,
-}
-
 /// Configuration for mv operations.
 #[derive(Debug, Clone)]
 pub struct MvConfig {
@@ -50,119 +39,122 @@ impl Default for MvConfig {
     }
 }
 
-/// Parse a backup control string (from --backup=CONTROL or VERSION_CONTROL env).
-pub fn parse_backup_mode(s: &str) -> Option<BackupMode> {
-    match s {
-        "none" | "off" => Some(BackupMode::None),
-        "simple" | "never" => Some(BackupMode::Simple),
-        "numbered" | "t" => Some(BackupMode::Numbered),
-        "existing" | "nil" => Some(BackupMode::Existing),
-        _ => Option::None,
-    }
-}
-
-/// Generate a backup file name for a given destination path.
-pub fn make_backup_name(dst: &Path, mode: &BackupMode, suffix: &str) -> std::path::PathBuf {
-    match mode {
-        BackupMode::Simple | BackupMode::None => {
-            let mut name = dst.as_os_str().to_os_string();
-            name.push(suffix);
-            std::path::PathBuf::from(name)
-        }
-        BackupMode::Numbered => make_numbered_backup(dst),
-        BackupMode::Existing => {
-            // If any numbered backup exists, use numbered; otherwise simple.
-            if has_numbered_backup(dst) {
-                make_numbered_backup(dst)
-            } else {
-                let mut name = dst.as_os_str().to_os_string();
-                name.push(suffix);
-                std::path::PathBuf::from(name)
-            }
-        }
-    }
-}
-
-/// Check if any numbered backup (e.g., `file.~1~`) exists for the given path.
-fn has_numbered_backup(path: &Path) -> bool {
-    let file_name = match path.file_name() {
-        Some(n) => n.to_string_lossy().to_string(),
-        None => return false,
-    };
-    let parent = path.parent().unwrap_or_else(|| Path::new("."));
-    if let Ok(entries) = fs::read_dir(parent) {
-        for entry in entries.flatten() {
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with(&format!("{}.~", file_name)) && name.ends_with('~') {
-                // Check that the middle part is a number
-                let middle = &name[file_name.len() + 2..name.len() - 1];
-                if middle.parse::<u64>().is_ok() {
-                    return true;
-                }
-            }
-        }
-    }
-    false
-}
-
-/// Create the next numbered backup name (e.g., `file.~1~`, `file.~2~`, ...).
-fn make_numbered_backup(path: &Path) -> std::path::PathBuf {
-    let mut n = 1u64;
-    loop {
-        let candidate = format!("{}.~{}~", path.display(), n);
-        let p = std::path::PathBuf::from(&candidate);
-        if !p.exists() {
-            return p;
-        }
-        n += 1;
-    }
-}
-
 /// Move a single file or directory from `src` to `dst`.
 ///
 /// Tries `rename()` first (atomic, same filesystem). If that fails with
 /// `EXDEV` (cross-device), falls back to recursive copy + remove.
+///
+/// With `--update`, a destination that is not older than `src` is left alone
+/// and no backup is made. Otherwise an existing destination is backed up just
+/// before it is replaced, and the backup is renamed back if the move fails.
 pub fn mv_file(src: &Path, dst: &Path, config: &MvConfig) -> io::Result<()> {
-    // Check no_clobber / update
-    if dst.exists() {
+    // Like GNU, look at the entries themselves, not symlink targets.
+    if let Ok(dst_meta) = fs::symlink_metadata(dst) {
         if config.no_clobber {
             return Ok(());
         }
-        if config.update {
-            let src_time = fs::metadata(src)?.modified()?;
-            let dst_time = fs::metadata(dst)?.modified()?;
-            if src_time <= dst_time {
-                return Ok(());
-            }
+        #[cfg(unix)]
+        if is_same_file(src, dst, &dst_meta, config)? {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "'{}' and '{}' are the same file",
+                    src.display(),
+                    dst.display()
+                ),
+            ));
+        }
+        if config.update && !is_newer(&fs::symlink_metadata(src)?, &dst_meta)? {
+            return Ok(());
         }
     }
 
     // Handle backup
-    if dst.exists() {
-        if let Some(ref mode) = config.backup {
-            let backup_name = make_backup_name(dst, mode, &config.suffix);
-            fs::rename(dst, &backup_name)?;
-        }
+    let mut backup = None;
+    if fs::symlink_metadata(dst).is_ok()
+        && let Some(mode) = config.backup
+        && mode != BackupMode::None
+    {
+        let backup_name = make_backup_name(dst, mode, &config.suffix);
+        fs::rename(dst, &backup_name)?;
+        backup = Some(backup_name);
     }
 
     // Try rename first (same filesystem, atomic)
-    match fs::rename(src, dst) {
+    let result = match fs::rename(src, dst) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            // Cross-filesystem: copy then remove
+            copy_recursive(src, dst).and_then(|()| remove_recursive(src))
+        }
+        Err(e) => Err(e),
+    };
+    match result {
         Ok(()) => {
             if config.verbose {
-                eprintln!("renamed '{}' -> '{}'", src.display(), dst.display());
+                match &backup {
+                    Some(b) => eprintln!(
+                        "renamed '{}' -> '{}' (backup: '{}')",
+                        src.display(),
+                        dst.display(),
+                        b.display()
+                    ),
+                    None => {
+                        eprintln!("renamed '{}' -> '{}'", src.display(), dst.display())
+                    }
+                }
             }
             Ok(())
         }
-        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
-            // Cross-filesystem: copy then remove
-            copy_recursive(src, dst)?;
-            remove_recursive(src)?;
-            if config.verbose {
-                eprintln!("renamed '{}' -> '{}'", src.display(), dst.display());
+        Err(e) => {
+            if let Some(b) = backup
+                && fs::symlink_metadata(dst).is_err()
+            {
+                let _ = fs::rename(&b, dst);
             }
-            Ok(())
+            Err(e)
         }
-        Err(e) => Err(e),
+    }
+}
+
+/// Whether moving `src` onto `dst` would move a file onto itself. Like GNU,
+/// hard links to the same inode may be replaced when backing up, since the
+/// backup keeps the other name alive; the same directory entry never may.
+#[cfg(unix)]
+fn is_same_file(
+    src: &Path,
+    dst: &Path,
+    dst_meta: &fs::Metadata,
+    config: &MvConfig,
+) -> io::Result<bool> {
+    let src_meta = fs::symlink_metadata(src)?;
+    if src_meta.dev() != dst_meta.dev() || src_meta.ino() != dst_meta.ino() {
+        return Ok(false);
+    }
+    if config.backup.is_none_or(|mode| mode == BackupMode::None) {
+        return Ok(true);
+    }
+    let entry = |p: &Path| {
+        let parent = match p.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::canonicalize(parent)
+            .ok()
+            .map(|dir| (dir, p.file_name().map(|n| n.to_os_string())))
+    };
+    Ok(entry(src) == entry(dst))
+}
+
+/// Whether `src` was modified strictly after `dst`, to nanosecond precision.
+fn is_newer(src: &fs::Metadata, dst: &fs::Metadata) -> io::Result<bool> {
+    #[cfg(unix)]
+    {
+        Ok((src.mtime(), src.mtime_nsec()) > (dst.mtime(), dst.mtime_nsec()))
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(src.modified()? > dst.modified()?)
     }
 }
 
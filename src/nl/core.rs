@@ -57,6 +57,24 @@ impl Default for NlConfig {
     }
 }
 
+/// Numbering state carried from one input file to the next.
+pub struct NlState {
+    /// Number for the next numbered line; `None` once incrementing overflowed.
+    pub line_number: Option<i64>,
+    /// Set when a line needed a number after the overflow. Output stops
+    /// before that line, and GNU nl reports "line number overflow".
+    pub overflowed: bool,
+}
+
+impl NlState {
+    pub fn new(config: &NlConfig) -> Self {
+        Self {
+            line_number: Some(config.starting_line_number),
+            overflowed: false,
+        }
+    }
+}
+
 /// Parse a numbering style string.
 pub fn parse_numbering_style(s: &str) -> Result<NumberingStyle, String> {
     match s {
@@ -80,7 +98,7 @@ pub fn parse_number_format(s: &str) -> Result<NumberFormat, String> {
         "ln" => Ok(NumberFormat::Ln),
         "rn" => Ok(NumberFormat::Rn),
         "rz" => Ok(NumberFormat::Rz),
-        _ => Err(format!("invalid line numbering: '{}'", s)),
+        _ => Err(format!("invalid line numbering format: '{}'", s)),
     }
 }
 
@@ -176,13 +194,13 @@ fn should_number(line: &[u8], style: &NumberingStyle) -> bool {
 
 /// Build the nl output into a Vec.
 pub fn nl_to_vec(data: &[u8], config: &NlConfig) -> Vec<u8> {
-    let mut line_number = config.starting_line_number;
-    nl_to_vec_with_state(data, config, &mut line_number)
+    nl_to_vec_with_state(data, config, &mut NlState::new(config))
 }
 
-/// Build the nl output into a Vec, continuing numbering from `line_number`.
-/// Updates `line_number` in place so callers can continue across multiple files.
-pub fn nl_to_vec_with_state(data: &[u8], config: &NlConfig, line_number: &mut i64) -> Vec<u8> {
+/// Build the nl output into a Vec, continuing numbering from `state`.
+/// Updates `state` in place so callers can continue across multiple files;
+/// check `state.overflowed` afterwards.
+pub fn nl_to_vec_with_state(data: &[u8], config: &NlConfig, state: &mut NlState) -> Vec<u8> {
     if data.is_empty() {
         return Vec::new();
     }
@@ -212,7 +230,7 @@ pub fn nl_to_vec_with_state(data: &[u8], config: &NlConfig, line_number: &mut i6
         // Check for section delimiter
         if let Some(section) = check_section_delimiter(line, &config.section_delimiter) {
             if !config.no_renumber {
-                *line_number = config.starting_line_number;
+                state.line_number = Some(config.starting_line_number);
             }
             current_section = section;
             consecutive_blanks = 0;
@@ -253,15 +271,19 @@ pub fn nl_to_vec_with_state(data: &[u8], config: &NlConfig, line_number: &mut i6
             if is_blank && config.join_blank_lines > 1 {
                 consecutive_blanks = 0;
             }
+            let Some(line_number) = state.line_number else {
+                state.overflowed = true;
+                break;
+            };
             format_number(
-                *line_number,
+                line_number,
                 config.number_format,
                 config.number_width,
                 &mut output,
             );
             output.extend_from_slice(&config.number_separator);
             output.extend_from_slice(line);
-            *line_number = line_number.wrapping_add(config.line_increment);
+            state.line_number = line_number.checked_add(config.line_increment);
         } else {
             // Non-numbered lines: GNU nl outputs width + separator_len total spaces, then content
             let total_pad = config.number_width + config.number_separator.len();
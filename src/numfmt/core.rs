@@ -43,6 +43,25 @@ pub enum InvalidMode {
     Ignore,
 }
 
+/// A parsed --format string: literal text around a single %f directive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Literal text before the directive, with %% already unescaped.
+    pub prefix: String,
+    /// Literal text after the directive, with %% already unescaped.
+    pub suffix: String,
+    /// Minimum width of the number including any scale suffix (0 = none).
+    pub width: usize,
+    /// Pad on the right instead of the left (- flag).
+    pub left_align: bool,
+    /// Pad with zeros after the sign instead of spaces (0 flag).
+    pub zero_pad: bool,
+    /// Group thousands (' flag).
+    pub grouping: bool,
+    /// Digits after the decimal point; None keeps the input's precision.
+    pub precision: Option<usize>,
+}
+
 /// Configuration for the numfmt command.
 pub struct NumfmtConfig {
     pub from: ScaleUnit,
@@ -52,13 +71,14 @@ pub struct NumfmtConfig {
     pub padding: Option<i32>,
     pub round: RoundMethod,
     pub suffix: Option<String>,
-    pub format: Option<String>,
+    pub format: Option<NumberFormat>,
     pub field: Vec<usize>,
     pub delimiter: Option<char>,
     pub header: usize,
     pub invalid: InvalidMode,
     pub grouping: bool,
     pub zero_terminated: bool,
+    pub debug: bool,
 }
 
 impl Default for NumfmtConfig {
@@ -78,10 +98,15 @@ impl Default for NumfmtConfig {
             invalid: InvalidMode::Abort,
             grouping: false,
             zero_terminated: false,
+            debug: false,
         }
     }
 }
 
+/// Significant digits a long double holds (LDBL_DIG); GNU numfmt's --debug
+/// warns when the integer or fraction part of an input has more.
+const MAX_UNSCALED_DIGITS: usize = 18;
+
 /// SI suffix table: suffix char -> multiplier.
 /// GNU coreutils 9.4 uses uppercase 'K' for SI kilo (same suffix letter as IEC, but 1e3 not 1024).
 const SI_SUFFIXES: &[(char, f64)] = &[
@@ -125,14 +150,24 @@ pub fn parse_scale_unit(s: &str) -> Result<ScaleUnit, String> {
 
 /// Parse a round method string.
 pub fn parse_round_method(s: &str) -> Result<RoundMethod, String> {
-    match s {
-        "up" => Ok(RoundMethod::Up),
-        "down" => Ok(RoundMethod::Down),
-        "from-zero" => Ok(RoundMethod::FromZero),
-        "towards-zero" => Ok(RoundMethod::TowardsZero),
-        "nearest" => Ok(RoundMethod::Nearest),
-        _ => Err(format!("invalid rounding method: '{}'", s)),
-    }
+    const METHODS: &[(&str, RoundMethod)] = &[
+        ("up", RoundMethod::Up),
+        ("down", RoundMethod::Down),
+        ("from-zero", RoundMethod::FromZero),
+        ("towards-zero", RoundMethod::TowardsZero),
+        ("nearest", RoundMethod::Nearest),
+    ];
+    if let Some(&(_, method)) = METHODS.iter().find(|(name, _)| *name == s) {
+        return Ok(method);
+    }
+    // Like GNU argmatch, an unambiguous prefix selects a method.
+    let mut matches = METHODS.iter().filter(|(name, _)| name.starts_with(s));
+    let problem = match (matches.next(), matches.next()) {
+        (Some(&(_, method)), None) if !s.is_empty() => return Ok(method),
+        (Some(_), _) => "ambiguous",
+        (None, _) => "invalid",
+    };
+    Err(format!("{} argument '{}' for '--round'", problem, s))
 }
 
 /// Parse an invalid mode string.
@@ -146,6 +181,94 @@ pub fn parse_invalid_mode(s: &str) -> Result<InvalidMode, String> {
     }
 }
 
+/// Unescape %% in literal --format text, rejecting any other directive.
+fn parse_format_literal(text: &str, fmt: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' && chars.next() != Some('%') {
+            return Err(format!("format '{}' has too many % directives", fmt));
+        }
+        out.push(c);
+    }
+    Ok(out)
+}
+
+/// Parse a --format string of the form PREFIX%[0]['][-][N][.][N]fSUFFIX.
+pub fn parse_format(fmt: &str) -> Result<NumberFormat, String> {
+    let bytes = fmt.as_bytes();
+
+    // Find the directive, skipping escaped %% in the prefix.
+    let mut i = 0;
+    loop {
+        match bytes[i..].iter().position(|&b| b == b'%') {
+            None => return Err(format!("format '{}' has no % directive", fmt)),
+            Some(off) if bytes.get(i + off + 1) == Some(&b'%') => i += off + 2,
+            Some(off) => {
+                i += off;
+                break;
+            }
+        }
+    }
+    let mut spec = NumberFormat {
+        prefix: parse_format_literal(&fmt[..i], fmt)?,
+        ..NumberFormat::default()
+    };
+    i += 1; // skip '%'
+    if i >= bytes.len() {
+        return Err(format!("format '{}' ends in %", fmt));
+    }
+
+    // Parse flags.
+    while i < bytes.len() {
+        match bytes[i] {
+            b'0' => spec.zero_pad = true,
+            b'\'' => spec.grouping = true,
+            b'-' => spec.left_align = true,
+            b' ' => {}
+            _ => break,
+        }
+        i += 1;
+    }
+    // printf ignores 0 when - is given.
+    spec.zero_pad &= !spec.left_align;
+
+    // Parse width.
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        spec.width = spec
+            .width
+            .checked_mul(10)
+            .and_then(|w| w.checked_add((bytes[i] - b'0') as usize))
+            .filter(|&w| w <= i32::MAX as usize)
+            .ok_or_else(|| format!("invalid format '{}' (width overflow)", fmt))?;
+        i += 1;
+    }
+
+    // Parse precision; a bare '.' means zero, as in printf.
+    if i < bytes.len() && bytes[i] == b'.' {
+        i += 1;
+        let mut prec: usize = 0;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            prec = prec
+                .checked_mul(10)
+                .and_then(|p| p.checked_add((bytes[i] - b'0') as usize))
+                .filter(|&p| p <= i32::MAX as usize)
+                .ok_or_else(|| format!("invalid precision in format '{}'", fmt))?;
+            i += 1;
+        }
+        spec.precision = Some(prec);
+    }
+
+    if bytes.get(i) != Some(&b'f') {
+        return Err(format!(
+            "invalid format '{}', directive must be %[0]['][-][N][.][N]f",
+            fmt
+        ));
+    }
+    spec.suffix = parse_format_literal(&fmt[i + 1..], fmt)?;
+    Ok(spec)
+}
+
 /// Parse a field specification string like "1", "1,3", "1-5", or "-".
 /// Returns 1-based field indices.
 pub fn parse_fields(s: &str) -> Result<Vec<usize>, String> {
@@ -204,138 +327,131 @@ pub fn parse_fields(s: &str) -> Result<Vec<usize>, String> {
 }
 
 /// Parse a number with optional suffix, returning the raw numeric value.
-/// Handles suffixes like K, M, G, T, P, E, Z, Y (and Ki, Mi, etc. for iec-i).
+/// Like GNU numfmt the number is plain decimal (`-`, digits, `.` digits),
+/// optionally followed by blanks and a scale suffix K, M, G, T, P, E, Z, Y,
+/// R or Q (plus `i` for iec-i, and optionally for auto). Anything else left
+/// over is reported as an invalid suffix.
 fn parse_number_with_suffix(s: &str, unit: ScaleUnit) -> Result<f64, String> {
     let s = s.trim();
-    if s.is_empty() {
-        return Err("invalid number: ''".to_string());
-    }
-
-    // Find where the numeric part ends and the suffix begins.
-    let mut num_end = s.len();
     let bytes = s.as_bytes();
-    let len = s.len();
-
-    // Check for trailing scale suffix characters.
-    if len > 0 {
-        let last_char = bytes[len - 1] as char;
-
-        match unit {
-            ScaleUnit::Auto | ScaleUnit::IecI => {
-                // Check for 'i' suffix (e.g., Ki, Mi).
-                if last_char == 'i' && len >= 2 {
-                    let prefix_char = (bytes[len - 2] as char).to_ascii_uppercase();
-                    if is_scale_suffix(prefix_char) {
-                        num_end = len - 2;
-                    }
-                } else {
-                    let upper = last_char.to_ascii_uppercase();
-                    if is_scale_suffix(upper) {
-                        num_end = len - 1;
-                    }
-                }
-            }
-            ScaleUnit::Si | ScaleUnit::Iec => {
-                let upper = last_char.to_ascii_uppercase();
-                if is_scale_suffix(upper) {
-                    num_end = len - 1;
-                }
-            }
-            ScaleUnit::None => {}
+    let digits_from = |start: usize| {
+        bytes[start.min(bytes.len())..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    // Find where the numeric part ends and the suffix begins. As in GNU's
+    // scanner, the integer part and the fraction must each start with a digit
+    // or '.', though they may hold no digits ("." alone fails on its fraction).
+    let starts_part = |i: usize| matches!(bytes.get(i), Some(b'0'..=b'9' | b'.'));
+    let mut num_end = usize::from(bytes.first() == Some(&b'-'));
+    if !starts_part(num_end) {
+        return Err(format!("invalid number: '{}'", s));
+    }
+    num_end += digits_from(num_end);
+    if bytes.get(num_end) == Some(&b'.') {
+        if !starts_part(num_end + 1) {
+            return Err(format!("invalid number: '{}'", s));
         }
+        num_end += 1 + digits_from(num_end + 1);
     }
-
     let num_str = &s[..num_end];
-    let suffix_str = &s[num_end..];
-
-    // Parse the numeric part.
-    let value: f64 = num_str
-        .parse()
-        .map_err(|_| format!("invalid number: '{}'", s))?;
-
-    // Apply suffix multiplier.
-    let multiplier = if suffix_str.is_empty() {
-        1.0
+    let value: f64 = if num_str.bytes().any(|b| b.is_ascii_digit()) {
+        num_str
+            .parse()
+            .map_err(|_| format!("invalid number: '{}'", s))?
     } else {
-        let suffix_upper = suffix_str.chars().next().unwrap().to_ascii_uppercase();
-        match unit {
-            ScaleUnit::Auto => {
-                // Auto-detect: if suffix ends with 'i', use IEC; otherwise SI.
-                if suffix_str.len() >= 2 && suffix_str.ends_with('i') {
-                    find_iec_multiplier(suffix_upper)?
-                } else {
-                    find_si_multiplier(suffix_upper)?
-                }
+        0.0
+    };
+
+    let rest = s[num_end..].trim_start_matches([' ', '\t']);
+    let Some(suffix) = rest.chars().next() else {
+        return Ok(value);
+    };
+    if !is_scale_suffix(suffix) {
+        return Err(format!("invalid suffix in input: '{}'", s));
+    }
+    let mut rest = &rest[1..];
+    let iec = match unit {
+        ScaleUnit::None => {
+            return Err(format!(
+                "rejecting suffix in input: '{}' (consider using --from)",
+                s
+            ));
+        }
+        ScaleUnit::Si => false,
+        ScaleUnit::Iec => true,
+        ScaleUnit::Auto => match rest.strip_prefix('i') {
+            Some(after) => {
+                rest = after;
+                true
             }
-            ScaleUnit::Si => find_si_multiplier(suffix_upper)?,
-            ScaleUnit::Iec | ScaleUnit::IecI => find_iec_multiplier(suffix_upper)?,
-            ScaleUnit::None => {
-                return Err(format!("invalid number: '{}'", s));
+            None => false,
+        },
+        ScaleUnit::IecI => match rest.strip_prefix('i') {
+            Some(after) => {
+                rest = after;
+                true
             }
-        }
+            None => {
+                return Err(format!(
+                    "missing 'i' suffix in input: '{}' (e.g Ki/Mi/Gi)",
+                    s
+                ));
+            }
+        },
     };
+    if !rest.is_empty() {
+        return Err(format!("invalid suffix in input '{}': '{}'", s, rest));
+    }
 
+    let table = if iec { IEC_SUFFIXES } else { SI_SUFFIXES };
+    let multiplier = table
+        .iter()
+        .find(|&&(c, _)| c == suffix)
+        .map_or(1.0, |&(_, mult)| mult);
     Ok(value * multiplier)
 }
 
-fn is_scale_suffix(c: char) -> bool {
-    matches!(c, 'K' | 'M' | 'G' | 'T' | 'P' | 'E' | 'Z' | 'Y' | 'R' | 'Q')
-}
-
-fn find_si_multiplier(c: char) -> Result<f64, String> {
-    for &(suffix, mult) in SI_SUFFIXES {
-        if suffix.eq_ignore_ascii_case(&c) {
-            return Ok(mult);
-        }
-    }
-    Err(format!("invalid suffix: '{}'", c))
-}
-
-fn find_iec_multiplier(c: char) -> Result<f64, String> {
-    for &(suffix, mult) in IEC_SUFFIXES {
-        if suffix == c {
-            return Ok(mult);
-        }
-    }
-    Err(format!("invalid suffix: '{}'", c))
+/// Whether an input number has more significant digits in its integer or
+/// fraction part than MAX_UNSCALED_DIGITS. Like GNU, digits count from the
+/// first nonzero one, so trailing zeros count but leading zeros do not.
+fn loses_precision(token: &str) -> bool {
+    let s = token.trim_start().trim_start_matches('-');
+    let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let significant = |part: &str| {
+        part.bytes()
+            .take_while(u8::is_ascii_digit)
+            .skip_while(|&b| b == b'0')
+            .count()
+    };
+    significant(integer) > MAX_UNSCALED_DIGITS || significant(fraction) > MAX_UNSCALED_DIGITS
 }
 
-/// Apply rounding according to the specified method.
-fn apply_round(value: f64, method: RoundMethod) -> f64 {
-    match method {
-        RoundMethod::Up => value.ceil(),
-        RoundMethod::Down => value.floor(),
-        RoundMethod::FromZero => {
-            if value >= 0.0 {
-                value.ceil()
-            } else {
-                value.floor()
-            }
-        }
-        RoundMethod::TowardsZero => {
-            if value >= 0.0 {
-                value.floor()
-            } else {
-                value.ceil()
-            }
-        }
-        RoundMethod::Nearest => value.round(),
-    }
+fn is_scale_suffix(c: char) -> bool {
+    matches!(c, 'K' | 'M' | 'G' | 'T' | 'P' | 'E' | 'Z' | 'Y' | 'R' | 'Q')
 }
 
 /// Format a number with scale suffix for output.
-fn format_scaled(value: f64, unit: ScaleUnit, round: RoundMethod) -> String {
+/// `precision` comes from --format and overrides the automatic 1-or-0 decimals.
+fn format_scaled(
+    value: f64,
+    unit: ScaleUnit,
+    round: RoundMethod,
+    precision: Option<usize>,
+) -> String {
     match unit {
         ScaleUnit::None => {
             // Output as plain number.
             format_plain_number(value)
         }
-        ScaleUnit::Si => format_with_scale(value, SI_SUFFIXES, "", round),
-        ScaleUnit::Iec => format_with_scale(value, IEC_SUFFIXES, "", round),
-        ScaleUnit::IecI => format_with_scale(value, IEC_SUFFIXES, "i", round),
+        ScaleUnit::Si => format_with_scale(value, SI_SUFFIXES, "", round, precision),
+        ScaleUnit::Iec => format_with_scale(value, IEC_SUFFIXES, "", round, precision),
+        ScaleUnit::IecI => format_with_scale(value, IEC_SUFFIXES, "i", round, precision),
         ScaleUnit::Auto => {
             // For --to=auto, behave like SI.
-            format_with_scale(value, SI_SUFFIXES, "", round)
+            format_with_scale(value, SI_SUFFIXES, "", round, precision)
         }
     }
 }
@@ -351,75 +467,52 @@ fn format_plain_number(value: f64) -> String {
     }
 }
 
-/// Format a number with appropriate scale suffix.
-/// Matches GNU numfmt behavior:
-/// - If scaled value < 10: display with 1 decimal place ("N.Nk")
-/// - If scaled value >= 10: display as integer ("NNk")
-/// - If integer would be >= 1000: promote to next suffix
+/// Format a number with appropriate scale suffix, following GNU numfmt:
+/// the value is reduced below the base, rounded once (to one decimal when
+/// it is under 10, otherwise to an integer, or to `precision` decimals
+/// capped at the digits the scaling removed), promoted if rounding reached
+/// the base, and printed with one decimal only for a scaled value under 10.
 fn format_with_scale(
     value: f64,
     suffixes: &[(char, f64)],
     i_suffix: &str,
     round: RoundMethod,
+    precision: Option<usize>,
 ) -> String {
-    let abs_value = value.abs();
-    let sign = if value < 0.0 { "-" } else { "" };
-
-    // Find the largest suffix that applies.
-    let mut chosen_idx: Option<usize> = None;
-
-    for (idx, &(_suffix, mult)) in suffixes.iter().enumerate().rev() {
-        if abs_value >= mult {
-            chosen_idx = Some(idx);
-            break;
-        }
-    }
-
-    let Some(mut idx) = chosen_idx else {
-        // Value is smaller than the smallest suffix, output as-is.
-        return format_plain_number(value);
+    let base = suffixes[0].1;
+    let mut power = 0;
+    let mut scaled = value;
+    while scaled.abs() >= base && power < suffixes.len() {
+        scaled /= base;
+        power += 1;
+    }
+
+    let decimals = match precision {
+        Some(prec) => prec.min(power * 3),
+        None if scaled.abs() < 10.0 => 1,
+        None => 0,
     };
+    scaled = apply_round_for_display(scaled, decimals, round);
+    if scaled.abs() >= base && power < suffixes.len() {
+        scaled /= base;
+        power += 1;
+    }
 
-    loop {
-        let (suffix, mult) = suffixes[idx];
-        let scaled = value / mult;
-        let abs_scaled = scaled.abs();
-
-        if abs_scaled < 10.0 {
-            // Display with 1 decimal place: "N.Nk"
-            let rounded = apply_round_for_display(scaled, round);
-            if rounded.abs() >= 10.0 {
-                // Rounding pushed it past 10, switch to integer display.
-                // apply_round_for_display rounds to 1 decimal, so the only
-                // value crossing this boundary is exactly 10.0 — truncation
-                // and rounding agree. Use `as i64` (truncation) which is safe.
-                let int_val = rounded as i64;
-                if int_val.unsigned_abs() >= 1000 && idx + 1 < suffixes.len() {
-                    idx += 1;
-                    continue;
-                }
-                return format!("{sign}{}{}{}", int_val.unsigned_abs(), suffix, i_suffix);
-            }
-            return format!("{sign}{:.1}{}{}", rounded.abs(), suffix, i_suffix);
-        } else {
-            // Display as integer: "NNk"
-            let int_val = apply_round_int(scaled, round);
-            if int_val.unsigned_abs() >= 1000 {
-                if idx + 1 < suffixes.len() {
-                    idx += 1;
-                    continue;
-                }
-                // No next suffix, just output what we have.
-            }
-            return format!("{sign}{}{}{}", int_val.unsigned_abs(), suffix, i_suffix);
-        }
+    let show_decimal_point = scaled != 0.0 && scaled.abs() < 10.0 && power > 0;
+    let prec = precision.unwrap_or(usize::from(show_decimal_point));
+    if power == 0 {
+        return format!("{scaled:.prec$}");
     }
+    format!("{scaled:.prec$}{}{i_suffix}", suffixes[power - 1].0)
 }
 
-/// Apply rounding for display purposes (when formatting scaled output).
-/// Rounds to 1 decimal place.
-fn apply_round_for_display(value: f64, method: RoundMethod) -> f64 {
-    let factor = 10.0;
+/// Apply rounding for display purposes, to `decimals` decimal places.
+fn apply_round_for_display(value: f64, decimals: usize, method: RoundMethod) -> f64 {
+    // Beyond f64's ~15 significant digits there is nothing left to round.
+    if decimals > 15 {
+        return value;
+    }
+    let factor = 10f64.powi(decimals as i32);
     let shifted = value * factor;
     let rounded = match method {
         RoundMethod::Up => shifted.ceil(),
@@ -443,29 +536,6 @@ fn apply_round_for_display(value: f64, method: RoundMethod) -> f64 {
     rounded / factor
 }
 
-/// Apply rounding to get an integer value for display.
-fn apply_round_int(value: f64, method: RoundMethod) -> i64 {
-    match method {
-        RoundMethod::Up => value.ceil() as i64,
-        RoundMethod::Down => value.floor() as i64,
-        RoundMethod::FromZero => {
-            if value >= 0.0 {
-                value.ceil() as i64
-            } else {
-                value.floor() as i64
-            }
-        }
-        RoundMethod::TowardsZero => {
-            if value >= 0.0 {
-                value.floor() as i64
-            } else {
-                value.ceil() as i64
-            }
-        }
-        RoundMethod::Nearest => value.round() as i64,
-    }
-}
-
 /// Insert thousands grouping separators.
 fn group_thousands(s: &str) -> String {
     // Find the integer part (before any decimal point).
@@ -501,267 +571,48 @@ fn group_thousands(s: &str) -> String {
     format!("{}{}{}", sign, result, rest)
 }
 
-/// Apply width/padding from a printf-style format string to an already-scaled string.
-/// Used when both --to and --format are specified.
-fn apply_format_padding(scaled: &str, fmt: &str) -> String {
-    let bytes = fmt.as_bytes();
-    let mut i = 0;
-
-    // Find '%'.
-    while i < bytes.len() && bytes[i] != b'%' {
-        i += 1;
-    }
-    let prefix = &fmt[..i];
-    if i >= bytes.len() {
-        return format!("{}{}", prefix, scaled);
-    }
-    i += 1; // skip '%'
-
-    // Parse flags.
-    let mut left_align = false;
-    while i < bytes.len() {
-        match bytes[i] {
-            b'0' | b'+' | b' ' | b'#' | b'\'' => {}
-            b'-' => left_align = true,
-            _ => break,
-        }
-        i += 1;
-    }
-
-    // Parse width.
-    let mut width: usize = 0;
-    while i < bytes.len() && bytes[i].is_ascii_digit() {
-        width = width
-            .saturating_mul(10)
-            .saturating_add((bytes[i] - b'0') as usize);
-        i += 1;
-    }
-
-    // Skip precision and conversion char.
-    while i < bytes.len() && (bytes[i] == b'.' || bytes[i].is_ascii_digit()) {
-        i += 1;
-    }
-    if i < bytes.len() {
-        i += 1; // skip conversion char
-    }
-    let suffix = &fmt[i..];
-
-    let padded = if width > 0 && scaled.len() < width {
-        let pad_len = width - scaled.len();
-        if left_align {
-            format!("{}{}", scaled, " ".repeat(pad_len))
-        } else {
-            format!("{}{}", " ".repeat(pad_len), scaled)
-        }
-    } else {
-        scaled.to_string()
+/// Number of digits after the decimal point in an input number, which
+/// --format uses when it gives no precision. Suffixed input has none.
+fn input_precision(token: &str) -> usize {
+    let token = token.trim();
+    let Some(dot) = token.find('.') else {
+        return 0;
     };
-
-    format!("{}{}{}", prefix, padded, suffix)
+    let frac = &token[dot + 1..];
+    let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == frac.len() { digits } else { 0 }
 }
 
-/// Apply printf-style format to a number.
-fn apply_format(value: f64, fmt: &str) -> Result<String, String> {
-    // Parse format: %[flags][width][.precision]f
-    let bytes = fmt.as_bytes();
-    let mut i = 0;
-
-    // Find '%'.
-    while i < bytes.len() && bytes[i] != b'%' {
-        i += 1;
-    }
-    let prefix = &fmt[..i];
-    if i >= bytes.len() {
-        return Err(format!("invalid format: '{}'", fmt));
-    }
-    i += 1; // skip '%'
-
-    if i >= bytes.len() {
-        return Err(format!("invalid format: '{}'", fmt));
+/// Pad a number with zeros after its sign up to `width`.
+fn zero_pad(num: &str, width: usize) -> String {
+    if num.len() >= width {
+        return num.to_string();
     }
-
-    // Handle %%
-    if bytes[i] == b'%' {
-        return Ok(format!("{}%", prefix));
+    let zeros = "0".repeat(width - num.len());
+    match num.strip_prefix('-') {
+        Some(rest) => format!("-{}{}", zeros, rest),
+        None => format!("{}{}", zeros, num),
     }
-
-    // Parse flags.
-    let mut zero_pad = false;
-    let mut left_align = false;
-    let mut plus_sign = false;
-    let mut space_sign = false;
-    while i < bytes.len() {
-        match bytes[i] {
-            b'0' => zero_pad = true,
-            b'-' => left_align = true,
-            b'+' => plus_sign = true,
-            b' ' => space_sign = true,
-            b'#' => {}
-            b'\'' => {} // grouping flag, handled separately
-            _ => break,
-        }
-        i += 1;
-    }
-
-    // Parse width.
-    let mut width: usize = 0;
-    while i < bytes.len() && bytes[i].is_ascii_digit() {
-        width = width
-            .saturating_mul(10)
-            .saturating_add((bytes[i] - b'0') as usize);
-        i += 1;
-    }
-
-    // Parse precision.
-    let mut precision: Option<usize> = None;
-    if i < bytes.len() && bytes[i] == b'.' {
-        i += 1;
-        let mut prec: usize = 0;
-        while i < bytes.len() && bytes[i].is_ascii_digit() {
-            prec = prec
-                .saturating_mul(10)
-                .saturating_add((bytes[i] - b'0') as usize);
-            i += 1;
-        }
-        precision = Some(prec);
-    }
-
-    // Parse conversion type.
-    if i >= bytes.len() {
-        return Err(format!("invalid format: '{}'", fmt));
-    }
-    let conv = bytes[i] as char;
-    i += 1;
-    let suffix = &fmt[i..];
-
-    let prec = precision.unwrap_or(6);
-    let formatted = match conv {
-        'f' => format!("{:.prec$}", value, prec = prec),
-        'e' => format_scientific(value, prec, 'e'),
-        'E' => format_scientific(value, prec, 'E'),
-        'g' => format_g(value, prec, false),
-        'G' => format_g(value, prec, true),
-        _ => return Err(format!("invalid format character: '{}'", conv)),
-    };
-
-    // Apply sign prefix.
-    let sign_str = if value < 0.0 {
-        ""
-    } else if plus_sign {
-        "+"
-    } else if space_sign {
-        " "
-    } else {
-        ""
-    };
-
-    let num_str = if !sign_str.is_empty() && !formatted.starts_with('-') {
-        format!("{}{}", sign_str, formatted)
-    } else {
-        formatted
-    };
-
-    // Apply width and padding.
-    let padded = if width > 0 && num_str.len() < width {
-        let pad_len = width - num_str.len();
-        if left_align {
-            format!("{}{}", num_str, " ".repeat(pad_len))
-        } else if zero_pad {
-            if num_str.starts_with('-') || num_str.starts_with('+') || num_str.starts_with(' ') {
-                let (sign, rest) = num_str.split_at(1);
-                format!("{}{}{}", sign, "0".repeat(pad_len), rest)
-            } else {
-                format!("{}{}", "0".repeat(pad_len), num_str)
-            }
-        } else {
-            format!("{}{}", " ".repeat(pad_len), num_str)
-        }
-    } else {
-        num_str
-    };
-
-    Ok(format!("{}{}{}", prefix, padded, suffix))
 }
 
-/// Format in scientific notation.
-fn format_scientific(value: f64, prec: usize, e_char: char) -> String {
-    if value == 0.0 {
-        let sign = if value.is_sign_negative() { "-" } else { "" };
-        if prec == 0 {
-            return format!("{sign}0{e_char}+00");
+/// Convert a single numeric token according to the config.
+fn convert_number(token: &str, config: &NumfmtConfig) -> Result<String, String> {
+    // A --suffix on the input is dropped before parsing; it is added back below.
+    let token = match config.suffix {
+        Some(ref suffix) if token.len() > suffix.len() => {
+            token.strip_suffix(suffix.as_str()).unwrap_or(token)
         }
-        return format!("{sign}0.{:0>prec$}{e_char}+00", "", prec = prec);
-    }
-
-    let abs = value.abs();
-    let sign = if value < 0.0 { "-" } else { "" };
-    let exp = abs.log10().floor() as i32;
-    let mantissa = abs / 10f64.powi(exp);
-
-    let factor = 10f64.powi(prec as i32);
-    let mantissa = (mantissa * factor).round() / factor;
-
-    let (mantissa, exp) = if mantissa >= 10.0 {
-        (mantissa / 10.0, exp + 1)
-    } else {
-        (mantissa, exp)
+        _ => token,
     };
 
-    let exp_sign = if exp >= 0 { '+' } else { '-' };
-    let exp_abs = exp.unsigned_abs();
-
-    if prec == 0 {
-        format!("{sign}{mantissa:.0}{e_char}{exp_sign}{exp_abs:02}")
-    } else {
-        format!(
-            "{sign}{mantissa:.prec$}{e_char}{exp_sign}{exp_abs:02}",
-            prec = prec
-        )
-    }
-}
-
-/// Format using %g - shortest representation.
-fn format_g(value: f64, prec: usize, upper: bool) -> String {
-    let prec = if prec == 0 { 1 } else { prec };
-
-    if value == 0.0 {
-        let sign = if value.is_sign_negative() { "-" } else { "" };
-        return format!("{sign}0");
-    }
-
-    let abs = value.abs();
-    let exp = abs.log10().floor() as i32;
-    let e_char = if upper { 'E' } else { 'e' };
-
-    if exp < -4 || exp >= prec as i32 {
-        let sig_prec = prec.saturating_sub(1);
-        let s = format_scientific(value, sig_prec, e_char);
-        trim_g_zeros(&s)
-    } else {
-        let decimal_prec = if prec as i32 > exp + 1 {
-            (prec as i32 - exp - 1) as usize
-        } else {
-            0
-        };
-        let s = format!("{value:.decimal_prec$}");
-        trim_g_zeros(&s)
-    }
-}
-
-fn trim_g_zeros(s: &str) -> String {
-    if let Some(e_pos) = s.find(['e', 'E']) {
-        let (mantissa, exponent) = s.split_at(e_pos);
-        let trimmed = mantissa.trim_end_matches('0').trim_end_matches('.');
-        format!("{trimmed}{exponent}")
-    } else {
-        s.trim_end_matches('0').trim_end_matches('.').to_string()
-    }
-}
-
-/// Convert a single numeric token according to the config.
-fn convert_number(token: &str, config: &NumfmtConfig) -> Result<String, String> {
     // Parse the input number (with optional suffix).
     let raw_value = parse_number_with_suffix(token, config.from)?;
+    if config.debug && loses_precision(token) {
+        eprintln!(
+            "numfmt: large input value '{}': possible precision loss",
+            token.trim_start()
+        );
+    }
 
     // Apply from-unit scaling.
     let value = raw_value * config.from_unit;
@@ -770,48 +621,65 @@ fn convert_number(token: &str, config: &NumfmtConfig) -> Result<String, String>
     let value = value / config.to_unit;
 
     // Format the output.
-    let mut result = if let Some(ref fmt) = config.format {
-        // If --to is also specified, first scale, then apply format padding.
-        if config.to != ScaleUnit::None {
-            let scaled = format_scaled(value, config.to, config.round);
-            // Extract width from the format string and apply padding.
-            apply_format_padding(&scaled, fmt)
-        } else {
-            let rounded = apply_round(value, config.round);
-            apply_format(rounded, fmt)?
+    let mut result = match config.format {
+        Some(ref fmt) if config.to != ScaleUnit::None => {
+            format_scaled(value, config.to, config.round, fmt.precision)
+        }
+        Some(ref fmt) => {
+            let prec = fmt.precision.unwrap_or_else(|| input_precision(token));
+            let rounded = apply_round_for_display(value, prec, config.round);
+            format!("{rounded:.prec$}")
+        }
+        None if config.to != ScaleUnit::None => format_scaled(value, config.to, config.round, None),
+        None => {
+            let prec = input_precision(token);
+            let rounded = apply_round_for_display(value, prec, config.round);
+            format!("{rounded:.prec$}")
         }
-    } else if config.to != ScaleUnit::None {
-        format_scaled(value, config.to, config.round)
-    } else {
-        let rounded = apply_round(value, config.round);
-        format_plain_number(rounded)
     };
 
     // Apply grouping.
-    if config.grouping {
+    if config.grouping || config.format.as_ref().is_some_and(|f| f.grouping) {
         result = group_thousands(&result);
     }
 
+    // Zero padding from --format covers the number and its scale suffix.
+    if let Some(ref fmt) = config.format
+        && fmt.zero_pad
+    {
+        result = zero_pad(&result, fmt.width);
+    }
+
     // Apply suffix.
     if let Some(ref suffix) = config.suffix {
         result.push_str(suffix);
     }
 
-    // Apply padding.
-    if let Some(pad) = config.padding {
-        let pad_width = pad.unsigned_abs() as usize;
-        if result.len() < pad_width {
-            let deficit = pad_width - result.len();
-            if pad < 0 {
-                // Left-align (pad on right).
-                result = format!("{}{}", result, " ".repeat(deficit));
-            } else {
-                // Right-align (pad on left).
-                result = format!("{}{}", " ".repeat(deficit), result);
-            }
+    // Apply padding. A --format width pads like --padding, so it covers
+    // the scale suffix and --suffix too.
+    let padding = match config.format {
+        Some(ref fmt) if fmt.width > 0 && !fmt.zero_pad => Some((fmt.width, fmt.left_align)),
+        _ => config
+            .padding
+            .map(|pad| (pad.unsigned_abs() as usize, pad < 0)),
+    };
+    if let Some((pad_width, left_align)) = padding
+        && result.len() < pad_width
+    {
+        let deficit = pad_width - result.len();
+        if left_align {
+            // Left-align (pad on right).
+            result = format!("{}{}", result, " ".repeat(deficit));
+        } else {
+            // Right-align (pad on left).
+            result = format!("{}{}", " ".repeat(deficit), result);
         }
     }
 
+    if let Some(ref fmt) = config.format {
+        result = format!("{}{}{}", fmt.prefix, result, fmt.suffix);
+    }
+
     Ok(result)
 }
 
@@ -909,7 +777,13 @@ fn reassemble_fields(
 }
 
 /// Process a single line according to the numfmt configuration.
-pub fn process_line(line: &str, config: &NumfmtConfig) -> Result<String, String> {
+/// A field that fails to convert under a non-abort --invalid mode is kept
+/// as is and clears `all_valid`.
+pub fn process_line(
+    line: &str,
+    config: &NumfmtConfig,
+    all_valid: &mut bool,
+) -> Result<String, String> {
     let fields = split_fields(line, config.delimiter);
 
     if fields.is_empty() {
@@ -926,20 +800,15 @@ pub fn process_line(line: &str, config: &NumfmtConfig) -> Result<String, String>
         if should_convert {
             match convert_number(field, config) {
                 Ok(s) => converted.push(s),
-                Err(e) => match config.invalid {
-                    InvalidMode::Abort => return Err(e),
-                    InvalidMode::Fail => {
-                        eprintln!("numfmt: {}", e);
-                        converted.push(field.to_string());
+                Err(e) => {
+                    match config.invalid {
+                        InvalidMode::Abort => return Err(e),
+                        InvalidMode::Fail | InvalidMode::Warn => eprintln!("numfmt: {}", e),
+                        InvalidMode::Ignore => {}
                     }
-                    InvalidMode::Warn => {
-                        eprintln!("numfmt: {}", e);
-                        converted.push(field.to_string());
-                    }
-                    InvalidMode::Ignore => {
-                        converted.push(field.to_string());
-                    }
-                },
+                    *all_valid = false;
+                    converted.push(field.to_string());
+                }
             }
         } else {
             converted.push(field.to_string());
@@ -964,7 +833,7 @@ pub fn run_numfmt<R: std::io::BufRead, W: Write>(
     let mut header_remaining = config.header;
     let mut buf = Vec::new();
     let mut reader = input;
-    let mut had_error = false;
+    let mut all_valid = true;
 
     loop {
         buf.clear();
@@ -994,57 +863,24 @@ pub fn run_numfmt<R: std::io::BufRead, W: Write>(
             continue;
         }
 
-        match process_line(&line_str, config) {
-            Ok(result) => {
-                output
-                    .write_all(result.as_bytes())
-                    .map_err(|e| format!("write error: {}", e))?;
-                output
-                    .write_all(&[terminator])
-                    .map_err(|e| format!("write error: {}", e))?;
-            }
-            Err(e) => {
-                match config.invalid {
-                    InvalidMode::Abort => {
-                        eprintln!("numfmt: {}", e);
-                        return Err(e);
-                    }
-                    InvalidMode::Fail => {
-                        eprintln!("numfmt: {}", e);
-                        // Output original line.
-                        output
-                            .write_all(line_str.as_bytes())
-                            .map_err(|e| format!("write error: {}", e))?;
-                        output
-                            .write_all(&[terminator])
-                            .map_err(|e| format!("write error: {}", e))?;
-                        had_error = true;
-                    }
-                    InvalidMode::Warn => {
-                        eprintln!("numfmt: {}", e);
-                        output
-                            .write_all(line_str.as_bytes())
-                            .map_err(|e| format!("write error: {}", e))?;
-                        output
-                            .write_all(&[terminator])
-                            .map_err(|e| format!("write error: {}", e))?;
-                    }
-                    InvalidMode::Ignore => {
-                        output
-                            .write_all(line_str.as_bytes())
-                            .map_err(|e| format!("write error: {}", e))?;
-                        output
-                            .write_all(&[terminator])
-                            .map_err(|e| format!("write error: {}", e))?;
-                    }
-                }
-            }
-        }
+        // Only --invalid=abort makes a line fail; other modes keep the field.
+        let result = process_line(&line_str, config, &mut all_valid).inspect_err(|e| {
+            eprintln!("numfmt: {}", e);
+        })?;
+        output
+            .write_all(result.as_bytes())
+            .map_err(|e| format!("write error: {}", e))?;
+        output
+            .write_all(&[terminator])
+            .map_err(|e| format!("write error: {}", e))?;
     }
 
     output.flush().map_err(|e| format!("flush error: {}", e))?;
 
-    if had_error {
+    if !all_valid && config.debug {
+        eprintln!("numfmt: failed to convert some of the input numbers");
+    }
+    if !all_valid && config.invalid == InvalidMode::Fail {
         Err("conversion errors occurred".to_string())
     } else {
         Ok(())
@@ -1,4 +1,4 @@
-use std::io::{self, Read, Write};
+use std::io::{self, Read, Seek, Write};
 
 /// Address radix for the offset column.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -237,12 +237,9 @@ fn write_value(
 ) -> io::Result<()> {
     match fmt {
         OutputFormat::NamedChar => {
-            let b = bytes[0];
-            if b < 128 {
-                write!(out, "{:>w$}", NAMED_CHARS[b as usize], w = width)
-            } else {
-                write!(out, "{:>w$o}", b, w = width)
-            }
+            // Like GNU, only the low 7 bits select the name.
+            let b = bytes[0] & 0x7f;
+            write!(out, "{:>w$}", NAMED_CHARS[b as usize], w = width)
         }
         OutputFormat::PrintableChar => {
             let b = bytes[0];
@@ -374,15 +371,34 @@ fn compute_effective_widths(formats: &[OutputFormat], line_width: usize) -> Vec<
         .map(|fmt| {
             let es = element_size(*fmt);
             let num_elems = line_width / es;
-            if num_elems > 0 {
-                max_chars_per_block / num_elems
-            } else {
-                field_width(*fmt)
-            }
+            max_chars_per_block
+                .checked_div(num_elems)
+                .unwrap_or_else(|| field_width(*fmt))
         })
         .collect()
 }
 
+/// Minimum width of the address column, as in GNU od (zero-padded; larger
+/// offsets simply grow the column).
+fn address_width(radix: AddressRadix) -> usize {
+    match radix {
+        AddressRadix::Octal | AddressRadix::Decimal => 7,
+        AddressRadix::Hex => 6,
+        AddressRadix::None => 0,
+    }
+}
+
+/// Write a file offset in the selected radix (nothing for `-A n`).
+fn write_address(out: &mut impl Write, radix: AddressRadix, offset: u64) -> io::Result<()> {
+    let w = address_width(radix);
+    match radix {
+        AddressRadix::Octal => write!(out, "{:0w$o}", offset),
+        AddressRadix::Decimal => write!(out, "{:0w$}", offset),
+        AddressRadix::Hex => write!(out, "{:0w$x}", offset),
+        AddressRadix::None => Ok(()),
+    }
+}
+
 /// Write one line of output for a given format type directly to the writer.
 fn write_format_line(
     out: &mut impl Write,
@@ -398,19 +414,9 @@ fn write_format_line(
 ) -> io::Result<()> {
     // Address prefix
     if is_first_format {
-        match radix {
-            AddressRadix::Octal => write!(out, "{:07o}", offset)?,
-            AddressRadix::Decimal => write!(out, "{:07}", offset)?,
-            AddressRadix::Hex => write!(out, "{:06x}", offset)?,
-            AddressRadix::None => {}
-        }
-    } else if radix != AddressRadix::None {
-        let addr_width = match radix {
-            AddressRadix::Octal | AddressRadix::Decimal => 7,
-            AddressRadix::Hex => 6,
-            AddressRadix::None => 0,
-        };
-        for _ in 0..addr_width {
+        write_address(out, radix, offset)?;
+    } else {
+        for _ in 0..address_width(radix) {
             out.write_all(b" ")?;
         }
     }
@@ -458,7 +464,48 @@ fn write_format_line(
     Ok(())
 }
 
-/// Parse a format type string (the TYPE argument of -t).
+/// Parse a -t TYPE argument, which may name several formats in a row
+/// (e.g. `x1a` or `ca`), each with an optional size and `z` suffix.
+pub fn parse_format_types(s: &str) -> Result<Vec<(OutputFormat, bool)>, String> {
+    if s.is_empty() {
+        return Ok(vec![parse_format_type(s)?]);
+    }
+    let bytes = s.as_bytes();
+    let mut formats = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let sizes: &[u8] = match bytes[i] {
+            b'a' | b'c' => b"",
+            b'd' | b'o' | b'u' | b'x' => b"CSIL",
+            b'f' => b"FDL",
+            _ => {
+                let bad = s[i..].chars().next().unwrap_or_default();
+                return Err(format!(
+                    "invalid character '{}' in type string '{}'",
+                    bad, s
+                ));
+            }
+        };
+        i += 1;
+        if !sizes.is_empty() {
+            if i < bytes.len() && sizes.contains(&bytes[i]) {
+                i += 1;
+            } else {
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+        }
+        if i < bytes.len() && bytes[i] == b'z' {
+            i += 1;
+        }
+        formats.push(parse_format_type(&s[start..i])?);
+    }
+    Ok(formats)
+}
+
+/// Parse a single format type (one element of the TYPE argument of -t).
 /// Returns the format and whether the 'z' suffix was present.
 pub fn parse_format_type(s: &str) -> Result<(OutputFormat, bool), String> {
     if s.is_empty() {
@@ -563,26 +610,70 @@ fn parse_float_size(s: &str) -> Result<usize, String> {
     }
 }
 
+/// Error for a skip (`-j`) that runs past the end of the input.
+fn skip_past_end() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "cannot skip past end of combined input",
+    )
+}
+
+/// Discard `n` bytes of input by reading them.
+fn skip_input<R: Read>(input: &mut R, n: u64) -> io::Result<()> {
+    let mut to_skip = n;
+    let mut skip_buf = [0u8; 8192];
+    while to_skip > 0 {
+        let chunk_size = std::cmp::min(to_skip, skip_buf.len() as u64) as usize;
+        let n = match input.read(&mut skip_buf[..chunk_size]) {
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if n == 0 {
+            return Err(skip_past_end());
+        }
+        to_skip -= n as u64;
+    }
+    Ok(())
+}
+
 /// Process input and produce od output.
 pub fn od_process<R: Read, W: Write>(
     mut input: R,
     output: &mut W,
     config: &OdConfig,
 ) -> io::Result<()> {
-    // Skip bytes
-    if config.skip_bytes > 0 {
-        let mut to_skip = config.skip_bytes;
-        let mut skip_buf = [0u8; 8192];
-        while to_skip > 0 {
-            let chunk_size = std::cmp::min(to_skip, skip_buf.len() as u64) as usize;
-            let n = input.read(&mut skip_buf[..chunk_size])?;
-            if n == 0 {
-                break;
+    skip_input(&mut input, config.skip_bytes)?;
+    od_dump(input, output, config)
+}
+
+/// Process a file, seeking past the skipped bytes when it is a regular file
+/// instead of reading them. Offsets still count from the start of the file.
+pub fn od_process_file<W: Write>(
+    mut file: std::fs::File,
+    output: &mut W,
+    config: &OdConfig,
+) -> io::Result<()> {
+    let size = file
+        .metadata()
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len());
+    match size {
+        // Files in /proc and the like report a size of 0; read those instead.
+        Some(size) if size > 0 && config.skip_bytes > 0 => {
+            if config.skip_bytes > size {
+                return Err(skip_past_end());
             }
-            to_skip -= n as u64;
+            file.seek(io::SeekFrom::Start(config.skip_bytes))?;
         }
+        _ => skip_input(&mut file, config.skip_bytes)?,
     }
+    od_dump(file, output, config)
+}
 
+/// Dump the input that follows the skipped bytes.
+fn od_dump<R: Read, W: Write>(mut input: R, output: &mut W, config: &OdConfig) -> io::Result<()> {
     // Read all data (respecting read_bytes limit)
     let data = match config.read_bytes {
         Some(limit) => {
@@ -650,15 +741,11 @@ pub fn od_process<R: Read, W: Write>(
         offset += width as u64;
     }
 
-    // Final address line
+    // Final address line: the offset just past the last byte read.
     if config.address_radix != AddressRadix::None {
         let final_offset = config.skip_bytes + data.len() as u64;
-        match config.address_radix {
-            AddressRadix::Octal => writeln!(output, "{:07o}", final_offset)?,
-            AddressRadix::Decimal => writeln!(output, "{:07}", final_offset)?,
-            AddressRadix::Hex => writeln!(output, "{:06x}", final_offset)?,
-            AddressRadix::None => {}
-        }
+        write_address(output, config.address_radix, final_offset)?;
+        writeln!(output)?;
     }
 
     Ok(())
@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::io::{self, IoSlice, Write};
 
 /// Configuration for the paste command.
 pub struct PasteConfig {
@@ -21,45 +21,47 @@ impl Default for PasteConfig {
 }
 
 /// Parse delimiter string with escape sequences.
-/// Supports: \n (newline), \t (tab), \\ (backslash), \0 (NUL), empty string (no delimiter).
-pub fn parse_delimiters(s: &str) -> Vec<u8> {
-    if s.is_empty() {
-        return Vec::new();
-    }
+/// Supports: \b, \f, \n, \r, \t, \v, \\ and \0 (an empty delimiter); a
+/// backslash before any other character stands for that character.
+/// The empty delimiter is stored as NUL, which cannot occur in an argument.
+pub fn parse_delimiters(s: &str) -> Result<Vec<u8>, String> {
     let bytes = s.as_bytes();
     let mut result = Vec::with_capacity(bytes.len());
     let mut i = 0;
     while i < bytes.len() {
-        if bytes[i] == b'\\' && i + 1 < bytes.len() {
-            match bytes[i + 1] {
-                b'n' => {
-                    result.push(b'\n');
-                    i += 2;
-                }
-                b't' => {
-                    result.push(b'\t');
-                    i += 2;
-                }
-                b'\\' => {
-                    result.push(b'\\');
-                    i += 2;
-                }
-                b'0' => {
-                    result.push(0);
-                    i += 2;
-                }
-                _ => {
-                    // Unknown escape: treat backslash as literal
-                    result.push(b'\\');
-                    i += 1;
-                }
-            }
-        } else {
+        if bytes[i] != b'\\' {
             result.push(bytes[i]);
             i += 1;
+            continue;
         }
+        let Some(&c) = bytes.get(i + 1) else {
+            return Err(format!(
+                "delimiter list ends with an unescaped backslash: {}",
+                s
+            ));
+        };
+        result.push(match c {
+            b'0' => 0,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            other => other,
+        });
+        i += 2;
+    }
+    Ok(result)
+}
+
+/// Append delimiter `idx` (cycling) unless it is the empty `\0` delimiter.
+#[inline]
+fn push_delim(output: &mut Vec<u8>, delims: &[u8], idx: usize) {
+    let d = delims[idx % delims.len()];
+    if d != 0 {
+        output.push(d);
     }
-    result
 }
 
 /// Paste files in normal (parallel) mode and return the output buffer.
@@ -104,7 +106,7 @@ pub fn paste_parallel_to_vec(file_data: &[&[u8]], config: &PasteConfig) -> Vec<u
     for _ in 0..max_lines {
         for (file_idx, data) in file_data.iter().enumerate() {
             if file_idx > 0 && !delims.is_empty() {
-                output.push(delims[(file_idx - 1) % delims.len()]);
+                push_delim(&mut output, delims, file_idx - 1);
             }
             let cursor = &mut cursors[file_idx];
             if *cursor < data.len() {
@@ -126,8 +128,133 @@ pub fn paste_parallel_to_vec(file_data: &[&[u8]], config: &PasteConfig) -> Vec<u
     output
 }
 
+/// Write all IoSlices to the writer, handling partial writes correctly.
+fn write_all_vectored(out: &mut impl Write, slices: &[IoSlice<'_>]) -> io::Result<()> {
+    let n = out.write_vectored(slices)?;
+    let expected: usize = slices.iter().map(|s| s.len()).sum();
+    if n >= expected {
+        return Ok(());
+    }
+    if n == 0 && expected > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::WriteZero,
+            "write_vectored returned 0",
+        ));
+    }
+    // Slow path: partial write — fall back to write_all per remaining slice.
+    let mut consumed = n;
+    for slice in slices {
+        if consumed == 0 {
+            out.write_all(slice)?;
+        } else if consumed >= slice.len() {
+            consumed -= slice.len();
+        } else {
+            out.write_all(&slice[consumed..])?;
+            consumed = 0;
+        }
+    }
+    Ok(())
+}
+
+/// Average line length in the first 64 KiB of `data`.
+fn sampled_line_len(data: &[u8], terminator: u8) -> usize {
+    let sample = &data[..data.len().min(64 * 1024)];
+    sample.len() / (memchr::memchr_iter(terminator, sample).count() + 1)
+}
+
+/// Paste two files in parallel mode straight from their buffers.
+/// Each output line is written as IoSlices via writev — the line of `a`, the
+/// delimiter, and the line of `b` together with its terminator — so no line
+/// data is copied in user space. Short lines are cheaper to copy than to
+/// describe one iovec at a time, so they go through a small staging buffer
+/// instead. A file that runs out first contributes empty fields, and a
+/// missing final terminator is supplied.
+pub fn paste_two_files(
+    a: &[u8],
+    b: &[u8],
+    config: &PasteConfig,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    // Below about this many bytes per output line, one iovec per field
+    // costs more than copying the bytes into 1 MiB writes.
+    const MIN_VECTORED_LINE: usize = 2048;
+    // IOV_MAX on Linux; each output line takes at most four slices.
+    const BATCH: usize = 1024;
+    const STAGING: usize = 1024 * 1024;
+    let terminator = [if config.zero_terminated { 0u8 } else { b'\n' }];
+    let delim = match config.delimiters.first() {
+        Some(&d) if d != 0 => Some([d]),
+        _ => None,
+    };
+
+    if sampled_line_len(a, terminator[0]) + sampled_line_len(b, terminator[0]) < MIN_VECTORED_LINE {
+        let mut buf: Vec<u8> = Vec::with_capacity(STAGING + 4096);
+        let (mut ca, mut cb) = (0usize, 0usize);
+        while ca < a.len() || cb < b.len() {
+            if ca < a.len() {
+                let end = memchr::memchr(terminator[0], &a[ca..]).map_or(a.len(), |pos| ca + pos);
+                buf.extend_from_slice(&a[ca..end]);
+                ca = end + 1;
+            }
+            if let Some(d) = &delim {
+                buf.extend_from_slice(d);
+            }
+            if cb < b.len() {
+                let end = memchr::memchr(terminator[0], &b[cb..]).map_or(b.len(), |pos| cb + pos);
+                buf.extend_from_slice(&b[cb..end]);
+                cb = end + 1;
+            }
+            buf.push(terminator[0]);
+            if buf.len() >= STAGING {
+                out.write_all(&buf)?;
+                buf.clear();
+            }
+        }
+        return out.write_all(&buf);
+    }
+
+    let mut slices: Vec<IoSlice<'_>> = Vec::with_capacity(BATCH);
+    let (mut ca, mut cb) = (0usize, 0usize);
+    while ca < a.len() || cb < b.len() {
+        if ca < a.len() {
+            let end = memchr::memchr(terminator[0], &a[ca..]).map_or(a.len(), |pos| ca + pos);
+            if end > ca {
+                slices.push(IoSlice::new(&a[ca..end]));
+            }
+            ca = end + 1;
+        }
+        if let Some(d) = &delim {
+            slices.push(IoSlice::new(d));
+        }
+        if cb < b.len() {
+            match memchr::memchr(terminator[0], &b[cb..]) {
+                Some(pos) => {
+                    slices.push(IoSlice::new(&b[cb..cb + pos + 1]));
+                    cb += pos + 1;
+                }
+                None => {
+                    slices.push(IoSlice::new(&b[cb..]));
+                    slices.push(IoSlice::new(&terminator));
+                    cb = b.len();
+                }
+            }
+        } else {
+            slices.push(IoSlice::new(&terminator));
+        }
+        if slices.len() > BATCH - 4 {
+            write_all_vectored(out, &slices)?;
+            slices.clear();
+        }
+    }
+    if !slices.is_empty() {
+        write_all_vectored(out, &slices)?;
+    }
+    Ok(())
+}
+
 /// Paste files in serial mode and return the output buffer.
-/// For each file, join all lines with the delimiter list (cycling).
+/// For each file, join all lines with the delimiter list (cycling from its
+/// start for every file), so each file yields exactly one output line.
 /// Uses inline memchr scanning — no offset arrays needed.
 pub fn paste_serial_to_vec(file_data: &[&[u8]], config: &PasteConfig) -> Vec<u8> {
     let terminator = if config.zero_terminated { 0u8 } else { b'\n' };
@@ -156,7 +283,7 @@ pub fn paste_serial_to_vec(file_data: &[&[u8]], config: &PasteConfig) -> Vec<u8>
                 Some(pos) => {
                     output.extend_from_slice(&effective[cursor..cursor + pos]);
                     if !delims.is_empty() {
-                        output.push(delims[delim_idx % delims.len()]);
+                        push_delim(&mut output, delims, delim_idx);
                         delim_idx += 1;
                     }
                     cursor += pos + 1;
@@ -372,7 +372,7 @@ pub fn run_pinky(config: &PinkyConfig) -> String {
             .collect();
         // GNU pinky reads sessions from systemd-logind which returns newest first.
         // Sort descending by login time to match that order.
-        user_entries.sort_by(|a, b| b.ut_tv_sec.cmp(&a.ut_tv_sec));
+        user_entries.sort_by_key(|e| std::cmp::Reverse(e.ut_tv_sec));
 
         for entry in &user_entries {
             let _ = writeln!(output, "{}", format_short_entry(entry, config));
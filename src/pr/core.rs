@@ -384,11 +384,12 @@ fn pr_lines_generic<W: Write>(
     // each row shows one value from each column. So up to
     // input_lines_per_page * columns input lines can be consumed per page.
     // actual_lines_per_column = ceil(page_lines / columns) for each page.
-    let lines_consumed_per_page = if columns > 1 && !config.across {
+    let lines_consumed_per_page = if columns > 1 {
         input_lines_per_page * columns
     } else {
         input_lines_per_page
-    };
+    }
+    .max(1);
 
     // Split into pages
     let total_lines = all_lines.len();
@@ -445,6 +446,9 @@ fn pr_lines_generic<W: Write>(
             if !config.omit_header && !config.omit_pagination && !suppress_header {
                 write_footer(output, config)?;
             }
+        } else if config.number_lines.is_some() {
+            // Skipped pages (+FIRST_PAGE) still count toward line numbers.
+            line_number += page_end - line_idx;
         }
 
         line_idx = page_end;
@@ -495,17 +499,19 @@ pub fn pr_merge<W: Write>(
     let num_files = inputs.len();
     let explicit_sep = has_explicit_separator(config);
     let col_sep = get_column_separator(config);
+    // A merged row is numbered once, ahead of the first file's column.
+    let number_width = config
+        .number_lines
+        .map_or(0, |(sep, digits)| number_sep_end(digits, sep));
+    let text_width = config.page_width.saturating_sub(number_width);
     let col_width = if explicit_sep {
         if num_files > 1 {
-            (config
-                .page_width
-                .saturating_sub(col_sep.len() * (num_files - 1)))
-                / num_files
+            (text_width.saturating_sub(col_sep.len() * (num_files - 1))) / num_files
         } else {
-            config.page_width
+            text_width
         }
     } else {
-        config.page_width / num_files
+        text_width / num_files
     };
 
     let max_lines = inputs.iter().map(|f| f.len()).max().unwrap_or(0);
@@ -535,7 +541,7 @@ pub fn pr_merge<W: Write>(
 
                 if let Some((sep, digits)) = config.number_lines {
                     write!(output, "{:>width$}{}", line_number, sep, width = digits)?;
-                    abs_pos += digits + 1;
+                    abs_pos = number_sep_end(abs_pos + digits, sep);
                     line_number += 1;
                 }
 
@@ -567,7 +573,7 @@ pub fn pr_merge<W: Write>(
                         } else {
                             write!(output, "{}", truncated)?;
                             abs_pos += truncated.len();
-                            let target = (fi + 1) * col_width + config.indent;
+                            let target = (fi + 1) * col_width + number_width + config.indent;
                             write_column_padding(output, abs_pos, target)?;
                             abs_pos = target;
                         }
@@ -584,14 +590,23 @@ pub fn pr_merge<W: Write>(
             }
 
             // Pad remaining body lines
-            while body_lines_written < body_lines_per_page {
-                writeln!(output)?;
-                body_lines_written += 1;
+            if !config.omit_header
+                && !config.omit_pagination
+                && !suppress_header
+                && !config.form_feed
+            {
+                while body_lines_written < body_lines_per_page {
+                    writeln!(output)?;
+                    body_lines_written += 1;
+                }
             }
 
             if !config.omit_header && !config.omit_pagination && !suppress_header {
                 write_footer(output, config)?;
             }
+        } else if config.number_lines.is_some() {
+            // Skipped pages (+FIRST_PAGE) still count toward line numbers.
+            line_number += page_end - line_idx;
         }
 
         line_idx = page_end;
@@ -740,8 +755,9 @@ fn write_single_column_body<W: Write>(
         }
     }
 
-    // Pad remaining body lines if not omitting headers
-    if !config.omit_header && !config.omit_pagination {
+    // Pad remaining body lines if not omitting headers; with -F the form feed
+    // ends the page instead.
+    if !config.omit_header && !config.omit_pagination && !config.form_feed {
         while body_lines_written < body_lines_per_page {
             output.write_all(b"\n")?;
             body_lines_written += 1;
@@ -751,6 +767,17 @@ fn write_single_column_body<W: Write>(
     Ok(())
 }
 
+/// Output column after writing the line-number separator `sep` at `pos`:
+/// a tab advances to the next 8-column tab stop.
+#[inline]
+fn number_sep_end(pos: usize, sep: char) -> usize {
+    if sep == '\t' {
+        (pos / 8 + 1) * 8
+    } else {
+        pos + 1
+    }
+}
+
 /// Format a line number with right-aligned padding and separator into a stack buffer.
 /// Returns the formatted slice. Avoids write!() per-line overhead.
 #[inline]
@@ -870,7 +897,7 @@ fn write_multicolumn_body<W: Write>(
                     }
                     if let Some((sep, digits)) = config.number_lines {
                         write!(output, "{:>width$}{}", line_number, sep, width = digits)?;
-                        abs_pos += digits + 1;
+                        abs_pos = number_sep_end(abs_pos + digits, sep);
                         *line_number += 1;
                     }
                     let content = lines[li];
@@ -940,9 +967,9 @@ fn write_multicolumn_body<W: Write>(
                         abs_pos += col_sep.len();
                     }
                     if let Some((sep, digits)) = config.number_lines {
-                        let num = config.first_line_number + li;
+                        let num = *line_number + li;
                         write!(output, "{:>width$}{}", num, sep, width = digits)?;
-                        abs_pos += digits + 1;
+                        abs_pos = number_sep_end(abs_pos + digits, sep);
                     }
                     let content = lines[li];
                     let truncated = if config.truncate_lines && content.len() > col_width {
@@ -983,8 +1010,8 @@ fn write_multicolumn_body<W: Write>(
         }
     }
 
-    // Pad remaining body lines
-    if !config.omit_header && !config.omit_pagination {
+    // Pad remaining body lines (not needed when a form feed ends the page)
+    if !config.omit_header && !config.omit_pagination && !config.form_feed {
         while body_lines_written < body_lines_per_page {
             output.write_all(b"\n")?;
             body_lines_written += 1;
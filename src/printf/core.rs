@@ -3,11 +3,10 @@
 /// Processes a printf format string with the given arguments, returning the
 /// raw output bytes. The format string is reused if there are more arguments
 /// than a single pass consumes.
-
-/// Sentinel returned inside `process_format_string` when `\c` is encountered.
-const STOP_OUTPUT: u8 = 0xFF;
-
 use std::cell::Cell;
+use std::io::Write;
+
+use crate::stat::{QuotingStyle, quote_name_style};
 
 thread_local! {
     /// Set to true when a numeric conversion warning occurs (invalid argument).
@@ -24,16 +23,24 @@ pub fn had_conv_error() -> bool {
     CONV_ERROR.with(|c| c.get())
 }
 
-fn mark_conv_error(s: &str) {
-    eprintln!("printf: '{}': expected a numeric value", s);
-    CONV_ERROR.with(|c| c.set(true));
+/// Quote an argument for a diagnostic, like gnulib's `quote`.
+fn quote(s: &str) -> String {
+    quote_name_style(s, QuotingStyle::Locale)
 }
 
-fn mark_range_error(s: &str) {
-    eprintln!("printf: '{}': Numerical result out of range", s);
+/// Report a diagnostic that makes printf exit 1 once output is done.
+fn report_error(msg: &str) {
+    eprintln!("printf: {}", msg);
     CONV_ERROR.with(|c| c.set(true));
 }
 
+/// Report a fatal diagnostic. The caller stops producing output, so only
+/// what was printed before the error is written.
+fn fatal_error(msg: &str) -> bool {
+    report_error(msg);
+    true
+}
+
 /// Process a printf format string with the given arguments, returning raw bytes.
 ///
 /// The format string repeats if there are more arguments than one pass consumes.
@@ -43,34 +50,24 @@ pub fn process_format_string(format: &str, args: &[&str]) -> Vec<u8> {
     let mut output = Vec::with_capacity(256);
     let fmt_bytes = format.as_bytes();
 
-    if args.is_empty() {
-        // Single pass with no arguments
-        let stop = format_one_pass(fmt_bytes, args, &mut 0, &mut output);
-        if stop {
-            // remove trailing STOP_OUTPUT sentinel if present
-            if output.last() == Some(&STOP_OUTPUT) {
-                output.pop();
-            }
-        }
-        return output;
-    }
-
     let mut arg_idx: usize = 0;
     loop {
         let start_idx = arg_idx;
-        let stop = format_one_pass(fmt_bytes, args, &mut arg_idx, &mut output);
-        if stop {
-            if output.last() == Some(&STOP_OUTPUT) {
-                output.pop();
-            }
-            break;
+        if format_one_pass(fmt_bytes, args, &mut arg_idx, &mut output) {
+            return output;
         }
-        // If no arguments were consumed, or we've used them all, stop
+        // Stop once the arguments are used up, or if a pass consumed none
         if arg_idx == start_idx || arg_idx >= args.len() {
             break;
         }
     }
 
+    if arg_idx < args.len() {
+        eprintln!(
+            "printf: warning: ignoring excess arguments, starting with {}",
+            quote(args[arg_idx])
+        );
+    }
     output
 }
 
@@ -82,11 +79,7 @@ fn format_one_pass(fmt: &[u8], args: &[&str], arg_idx: &mut usize, output: &mut
         match fmt[i] {
             b'%' => {
                 i += 1;
-                if i >= fmt.len() {
-                    output.push(b'%');
-                    break;
-                }
-                if fmt[i] == b'%' {
+                if fmt.get(i) == Some(&b'%') {
                     output.push(b'%');
                     i += 1;
                     continue;
@@ -98,7 +91,7 @@ fn format_one_pass(fmt: &[u8], args: &[&str], arg_idx: &mut usize, output: &mut
             }
             b'\\' => {
                 i += 1;
-                let stop = process_format_escape(fmt, &mut i, output);
+                let stop = process_escape(fmt, &mut i, false, output);
                 if stop {
                     return true;
                 }
@@ -113,7 +106,8 @@ fn format_one_pass(fmt: &[u8], args: &[&str], arg_idx: &mut usize, output: &mut
 }
 
 /// Process a conversion specifier (the part after `%`).
-/// `i` points to the first character after `%`. Returns true if `\c` stop was hit.
+/// `i` points to the first character after `%`. Returns true if output
+/// should stop (`\c`, or a fatal error).
 fn process_conversion(
     fmt: &[u8],
     i: &mut usize,
@@ -121,6 +115,24 @@ fn process_conversion(
     arg_idx: &mut usize,
     output: &mut Vec<u8>,
 ) -> bool {
+    let start = *i - 1;
+
+    // As in GNU, %b and %q take no flags, width or precision.
+    match fmt.get(*i) {
+        Some(b'b') => {
+            *i += 1;
+            let (bytes, stop) = process_b_argument(consume_arg(args, arg_idx));
+            output.extend_from_slice(&bytes);
+            return stop;
+        }
+        Some(b'q') => {
+            *i += 1;
+            output.extend_from_slice(shell_quote(consume_arg(args, arg_idx)).as_bytes());
+            return false;
+        }
+        _ => {}
+    }
+
     // Parse flags
     let mut flags = FormatFlags::default();
     while *i < fmt.len() {
@@ -130,27 +142,28 @@ fn process_conversion(
             b' ' => flags.space_sign = true,
             b'0' => flags.zero_pad = true,
             b'#' => flags.alternate = true,
+            // Thousands grouping: a no-op in the C locale
+            b'\'' | b'I' => {}
             _ => break,
         }
         *i += 1;
     }
 
     // Parse width (may be '*' for dynamic width from args)
-    let (width, dyn_left_align) = if *i < fmt.len() && fmt[*i] == b'*' {
+    let width = if *i < fmt.len() && fmt[*i] == b'*' {
         *i += 1;
         let width_arg = consume_arg(args, arg_idx);
-        let w: i64 = width_arg.parse().unwrap_or(0);
+        let w = parse_integer(width_arg);
+        if !(i64::from(i32::MIN)..=i64::from(i32::MAX)).contains(&w) {
+            return fatal_error(&format!("invalid field width: {}", quote(width_arg)));
+        }
         if w < 0 {
-            ((-w) as usize, true) // negative width → left-align
-        } else {
-            (w as usize, false)
+            flags.left_align = true; // negative width → left-align
         }
+        w.unsigned_abs() as usize
     } else {
-        (parse_decimal(fmt, i), false)
+        parse_decimal(fmt, i)
     };
-    if dyn_left_align {
-        flags.left_align = true;
-    }
 
     // Parse precision (may be '*' for dynamic precision from args)
     let precision = if *i < fmt.len() && fmt[*i] == b'.' {
@@ -158,8 +171,12 @@ fn process_conversion(
         if *i < fmt.len() && fmt[*i] == b'*' {
             *i += 1;
             let prec_arg = consume_arg(args, arg_idx);
-            let p: i64 = prec_arg.parse().unwrap_or(0);
-            Some(if p < 0 { 0 } else { p as usize })
+            let p = parse_integer(prec_arg);
+            if p > i64::from(i32::MAX) {
+                return fatal_error(&format!("invalid precision: {}", quote(prec_arg)));
+            }
+            // A negative precision is taken as if it were omitted
+            (p >= 0).then_some(p as usize)
         } else {
             Some(parse_decimal(fmt, i))
         }
@@ -167,9 +184,38 @@ fn process_conversion(
         None
     };
 
-    // Parse conversion character
-    if *i >= fmt.len() {
-        return false;
+    // %(FORMAT)T: strftime conversion (bash extension)
+    if fmt.get(*i) == Some(&b'(') {
+        if let Some(len) = fmt[*i + 1..].iter().position(|&b| b == b')')
+            && fmt.get(*i + 1 + len + 1) == Some(&b'T')
+        {
+            let time_fmt = String::from_utf8_lossy(&fmt[*i + 1..*i + 1 + len]).into_owned();
+            *i += len + 3;
+            let arg = (*arg_idx < args.len()).then(|| consume_arg(args, arg_idx));
+            let formatted = format_time_conversion(&time_fmt, arg);
+            output.extend_from_slice(&apply_string_format(&formatted, &flags, width, precision));
+            return false;
+        }
+    }
+
+    // Length modifiers are accepted and ignored
+    while *i < fmt.len() && matches!(fmt[*i], b'h' | b'l' | b'L' | b'j' | b't' | b'z') {
+        *i += 1;
+    }
+
+    // Reject what GNU's printf would not pass on to the C library
+    let conv = fmt.get(*i).copied();
+    let valid = match conv {
+        Some(b'c') => !flags.alternate && !flags.zero_pad && precision.is_none(),
+        Some(b's') => !flags.alternate && !flags.zero_pad,
+        Some(b'd' | b'i' | b'u') => !flags.alternate,
+        Some(c) => b"oxXfFeEgGaA".contains(&c),
+        None => false,
+    };
+    if !valid {
+        let end = (*i + 1).min(fmt.len());
+        let spec = String::from_utf8_lossy(&fmt[start..end]);
+        return fatal_error(&format!("{}: invalid conversion specification", spec));
     }
     let conv = fmt[*i];
     *i += 1;
@@ -182,25 +228,10 @@ fn process_conversion(
             let formatted = apply_string_format(s, &flags, width, precision);
             output.extend_from_slice(&formatted);
         }
-        b'b' => {
-            let (bytes, stop) = process_b_argument(arg);
-            let formatted = apply_string_format_bytes(&bytes, &flags, width, precision);
-            output.extend_from_slice(&formatted);
-            if stop {
-                return true;
-            }
-        }
         b'c' => {
-            if let Some(ch) = arg.chars().next() {
-                let mut buf = [0u8; 4];
-                let encoded = ch.encode_utf8(&mut buf);
-                let formatted = apply_string_format(encoded, &flags, width, precision);
-                output.extend_from_slice(&formatted);
-            } else {
-                // empty arg: output a NUL byte (GNU compat)
-                let formatted = apply_string_format_bytes(&[0], &flags, width, precision);
-                output.extend_from_slice(&formatted);
-            }
+            // The first byte of the argument; NUL for an empty one
+            let byte = arg.as_bytes().first().copied().unwrap_or(0);
+            output.extend_from_slice(&apply_padding(&[byte], &flags, width));
         }
         b'd' | b'i' => {
             let val = parse_integer(arg);
@@ -250,49 +281,34 @@ fn process_conversion(
             let formatted = apply_numeric_format(&full, false, &flags, width, precision);
             output.extend_from_slice(formatted.as_bytes());
         }
-        b'f' => {
-            let val = parse_float(arg);
-            let prec = precision.unwrap_or(6);
-            let s = format!("{:.prec$}", val, prec = prec);
-            let formatted = apply_float_format(&s, val < 0.0, &flags, width);
-            output.extend_from_slice(formatted.as_bytes());
-        }
-        b'e' => {
-            let val = parse_float(arg);
-            let prec = precision.unwrap_or(6);
-            let s = format_scientific(val, prec, 'e');
-            let formatted = apply_float_format(&s, val < 0.0, &flags, width);
-            output.extend_from_slice(formatted.as_bytes());
-        }
-        b'E' => {
-            let val = parse_float(arg);
-            let prec = precision.unwrap_or(6);
-            let s = format_scientific(val, prec, 'E');
-            let formatted = apply_float_format(&s, val < 0.0, &flags, width);
-            output.extend_from_slice(formatted.as_bytes());
-        }
-        b'g' => {
-            let val = parse_float(arg);
-            let prec = precision.unwrap_or(6);
-            let s = format_g(val, prec, false);
-            let formatted = apply_float_format(&s, val < 0.0, &flags, width);
-            output.extend_from_slice(formatted.as_bytes());
-        }
-        b'G' => {
+        b'f' | b'F' | b'e' | b'E' | b'g' | b'G' => {
             let val = parse_float(arg);
             let prec = precision.unwrap_or(6);
-            let s = format_g(val, prec, true);
+            let upper = conv.is_ascii_uppercase();
+            let s = if val.is_finite() {
+                match conv {
+                    b'f' | b'F' => format!("{:.prec$}", val, prec = prec),
+                    b'e' | b'E' => format_scientific(val, prec, if upper { 'E' } else { 'e' }),
+                    _ => format_g(val, prec, upper),
+                }
+            } else {
+                // C spells these inf/nan, keeping the sign even of a NaN,
+                // and never zero-pads them
+                flags.zero_pad = false;
+                let sign = if val.is_sign_negative() { "-" } else { "" };
+                let name = match (val.is_nan(), upper) {
+                    (true, false) => "nan",
+                    (true, true) => "NAN",
+                    (false, false) => "inf",
+                    (false, true) => "INF",
+                };
+                format!("{}{}", sign, name)
+            };
             let formatted = apply_float_format(&s, val < 0.0, &flags, width);
             output.extend_from_slice(formatted.as_bytes());
         }
-        b'q' => {
-            let s = arg;
-            let quoted = shell_quote(s);
-            let formatted = apply_string_format(&quoted, &flags, width, precision);
-            output.extend_from_slice(&formatted);
-        }
         _ => {
-            // Unknown conversion: output literally
+            // %a/%A: not implemented, output literally
             output.push(b'%');
             output.push(conv);
         }
@@ -300,6 +316,84 @@ fn process_conversion(
     false
 }
 
+/// Format used by `%()T` when the parenthesized part is empty (as in bash).
+const DEFAULT_TIME_FORMAT: &str = "%a %b %e %H:%M:%S %Z %Y";
+
+/// Expand `%(FORMAT)T` for one argument: seconds since the epoch, with `-1`
+/// (or no argument) meaning now and `-2` the start time of the invoking shell.
+fn format_time_conversion(time_fmt: &str, arg: Option<&str>) -> String {
+    let now = || {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    };
+    let secs = match arg {
+        None => now(),
+        Some(a) => match parse_integer(a) {
+            -1 => now(),
+            -2 => shell_start_time().unwrap_or_else(now),
+            n => n,
+        },
+    };
+    let time_fmt = if time_fmt.is_empty() {
+        DEFAULT_TIME_FORMAT
+    } else {
+        time_fmt
+    };
+    strftime_local(secs, time_fmt)
+}
+
+/// Format `secs` in the local time zone with the date module's formatter.
+#[cfg(unix)]
+fn strftime_local(secs: i64, format: &str) -> String {
+    let offset = std::time::Duration::from_secs(secs.unsigned_abs());
+    let time = if secs >= 0 {
+        std::time::UNIX_EPOCH.checked_add(offset)
+    } else {
+        std::time::UNIX_EPOCH.checked_sub(offset)
+    };
+    match time {
+        Some(time) => crate::date::format_date(&time, format, false),
+        None => String::new(),
+    }
+}
+
+/// Without the date module, `%(FORMAT)T` prints the raw seconds.
+#[cfg(not(unix))]
+fn strftime_local(secs: i64, _format: &str) -> String {
+    secs.to_string()
+}
+
+/// Start time of the parent process (normally the invoking shell), from
+/// `/proc/PPID/stat` and the boot time in `/proc/stat`.
+#[cfg(target_os = "linux")]
+fn shell_start_time() -> Option<i64> {
+    let ppid = unsafe { libc::getppid() };
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", ppid)).ok()?;
+    // Fields after the parenthesized command name start at field 3;
+    // starttime is field 22, in clock ticks since boot.
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let start_ticks: i64 = rest.split_whitespace().nth(19)?.parse().ok()?;
+    let boot = std::fs::read_to_string("/proc/stat").ok()?;
+    let btime: i64 = boot
+        .lines()
+        .find_map(|l| l.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks <= 0 {
+        return None;
+    }
+    Some(btime + start_ticks / ticks as i64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn shell_start_time() -> Option<i64> {
+    None
+}
+
 /// Consume the next argument, returning "" if exhausted.
 fn consume_arg<'a>(args: &[&'a str], arg_idx: &mut usize) -> &'a str {
     if *arg_idx < args.len() {
@@ -311,95 +405,74 @@ fn consume_arg<'a>(args: &[&'a str], arg_idx: &mut usize) -> &'a str {
     }
 }
 
-/// Process an escape sequence in the format string.
-/// `i` points to the character after `\`. Returns true if `\c` was encountered.
-fn process_format_escape(fmt: &[u8], i: &mut usize, output: &mut Vec<u8>) -> bool {
-    if *i >= fmt.len() {
+/// Process a backslash escape, in the format string or in a `%b` argument.
+/// `i` points to the character after `\`. In `%b` (`octal_0`), `\0NNN` takes
+/// up to three octal digits after the `0`; in the format string it is `\NNN`.
+/// Returns true if output should stop (`\c`, or a fatal error).
+fn process_escape(data: &[u8], i: &mut usize, octal_0: bool, output: &mut Vec<u8>) -> bool {
+    let Some(&ch) = data.get(*i) else {
         output.push(b'\\');
         return false;
-    }
-    match fmt[*i] {
-        b'\\' => {
-            output.push(b'\\');
-            *i += 1;
-        }
-        b'"' => {
-            output.push(b'"');
-            *i += 1;
-        }
-        b'a' => {
-            output.push(0x07);
-            *i += 1;
-        }
-        b'b' => {
-            output.push(0x08);
-            *i += 1;
-        }
-        b'c' => {
-            return true;
-        }
-        b'e' | b'E' => {
-            output.push(0x1B);
-            *i += 1;
-        }
-        b'f' => {
-            output.push(0x0C);
-            *i += 1;
-        }
-        b'n' => {
-            output.push(b'\n');
-            *i += 1;
-        }
-        b'r' => {
-            output.push(b'\r');
-            *i += 1;
-        }
-        b't' => {
-            output.push(b'\t');
-            *i += 1;
-        }
-        b'v' => {
-            output.push(0x0B);
-            *i += 1;
-        }
-        b'0' => {
-            // Octal: \0NNN (up to 3 octal digits after the leading 0)
-            *i += 1;
-            let val = parse_octal_digits(fmt, i, 3);
-            output.push(val);
-        }
-        b'1'..=b'7' => {
-            // Octal: \NNN (up to 3 octal digits)
-            let val = parse_octal_digits(fmt, i, 3);
-            output.push(val);
-        }
+    };
+    match ch {
         b'x' => {
             *i += 1;
-            let val = parse_hex_digits(fmt, i, 2);
+            let start = *i;
+            let val = parse_hex_digits(data, i, 2);
+            if *i == start {
+                return fatal_error("missing hexadecimal number in escape");
+            }
             output.push(val as u8);
         }
-        b'u' => {
-            *i += 1;
-            let val = parse_hex_digits(fmt, i, 4);
-            if let Some(ch) = char::from_u32(val) {
-                let mut buf = [0u8; 4];
-                let encoded = ch.encode_utf8(&mut buf);
-                output.extend_from_slice(encoded.as_bytes());
+        b'0'..=b'7' => {
+            if octal_0 && ch == b'0' {
+                *i += 1;
             }
+            output.push(parse_octal_digits(data, i, 3));
+        }
+        b'c' => return true,
+        b'"' | b'\\' | b'a' | b'b' | b'e' | b'f' | b'n' | b'r' | b't' | b'v' => {
+            *i += 1;
+            output.push(match ch {
+                b'a' => 0x07,
+                b'b' => 0x08,
+                b'e' => 0x1B,
+                b'f' => 0x0C,
+                b'n' => b'\n',
+                b'r' => b'\r',
+                b't' => b'\t',
+                b'v' => 0x0B,
+                _ => ch,
+            });
         }
-        b'U' => {
+        b'u' | b'U' => {
+            // Exactly 4 or 8 hex digits
             *i += 1;
-            let val = parse_hex_digits(fmt, i, 8);
+            let digits = if ch == b'u' { 4 } else { 8 };
+            let start = *i;
+            let val = parse_hex_digits(data, i, digits);
+            if *i - start < digits {
+                return fatal_error("missing hexadecimal number in escape");
+            }
+            if (val <= 0x9F && !matches!(val, 0x24 | 0x40 | 0x60))
+                || (0xD800..=0xDFFF).contains(&val)
+            {
+                return fatal_error(&format!(
+                    "invalid universal character name \\{}{:0width$x}",
+                    ch as char,
+                    val,
+                    width = digits
+                ));
+            }
             if let Some(ch) = char::from_u32(val) {
                 let mut buf = [0u8; 4];
-                let encoded = ch.encode_utf8(&mut buf);
-                output.extend_from_slice(encoded.as_bytes());
+                output.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
             }
         }
         _ => {
             // Unknown escape: output backslash and the character
             output.push(b'\\');
-            output.push(fmt[*i]);
+            output.push(ch);
             *i += 1;
         }
     }
@@ -407,7 +480,7 @@ fn process_format_escape(fmt: &[u8], i: &mut usize, output: &mut Vec<u8>) -> boo
 }
 
 /// Process backslash escapes in a %b argument string.
-/// Returns (bytes, stop) where stop is true if \c was found.
+/// Returns (bytes, stop) where stop is true if output should stop.
 fn process_b_argument(arg: &str) -> (Vec<u8>, bool) {
     let bytes = arg.as_bytes();
     let mut output = Vec::with_capacity(bytes.len());
@@ -415,70 +488,8 @@ fn process_b_argument(arg: &str) -> (Vec<u8>, bool) {
     while i < bytes.len() {
         if bytes[i] == b'\\' {
             i += 1;
-            if i >= bytes.len() {
-                output.push(b'\\');
-                break;
-            }
-            match bytes[i] {
-                b'\\' => {
-                    output.push(b'\\');
-                    i += 1;
-                }
-                b'a' => {
-                    output.push(0x07);
-                    i += 1;
-                }
-                b'b' => {
-                    output.push(0x08);
-                    i += 1;
-                }
-                b'c' => {
-                    return (output, true);
-                }
-                b'e' | b'E' => {
-                    output.push(0x1B);
-                    i += 1;
-                }
-                b'f' => {
-                    output.push(0x0C);
-                    i += 1;
-                }
-                b'n' => {
-                    output.push(b'\n');
-                    i += 1;
-                }
-                b'r' => {
-                    output.push(b'\r');
-                    i += 1;
-                }
-                b't' => {
-                    output.push(b'\t');
-                    i += 1;
-                }
-                b'v' => {
-                    output.push(0x0B);
-                    i += 1;
-                }
-                b'0' => {
-                    i += 1;
-                    let val = parse_octal_digits(bytes, &mut i, 3);
-                    output.push(val);
-                }
-                b'1'..=b'7' => {
-                    let val = parse_octal_digits(bytes, &mut i, 3);
-                    output.push(val);
-                }
-                b'x' => {
-                    i += 1;
-                    let val = parse_hex_digits(bytes, &mut i, 2);
-                    output.push(val as u8);
-                }
-                _ => {
-                    // In %b, unknown escapes pass through literally
-                    output.push(b'\\');
-                    output.push(bytes[i]);
-                    i += 1;
-                }
+            if process_escape(bytes, &mut i, true, &mut output) {
+                return (output, true);
             }
         } else {
             output.push(bytes[i]);
@@ -543,193 +554,283 @@ fn parse_decimal(data: &[u8], i: &mut usize) -> usize {
     val
 }
 
-/// Parse an integer argument. Supports decimal, octal (0-prefix), hex (0x-prefix),
-/// and single-character constants ('c' or "c").
-fn parse_integer(s: &str) -> i64 {
-    let s = s.trim();
-    if s.is_empty() {
-        return 0;
+/// Report a numeric argument that strto* could not fully convert, as GNU's
+/// `verify_numeric` does. `end` is where the conversion stopped; the value
+/// converted so far is still printed.
+fn verify_numeric(s: &str, end: usize, out_of_range: bool) {
+    if out_of_range {
+        report_error(&format!("{}: Numerical result out of range", quote(s)));
+    } else if end < s.len() {
+        if end == 0 {
+            report_error(&format!("{}: expected a numeric value", quote(s)));
+        } else {
+            report_error(&format!("{}: value not completely converted", quote(s)));
+        }
     }
+}
 
-    // Character constants: 'X or "X
-    if (s.starts_with('\'') || s.starts_with('"')) && s.len() >= 2 {
-        return s[1..].chars().next().map_or(0, |c| c as i64);
+/// The value of a character constant argument ('c or "c), if `s` is one.
+///
+/// The value is that of the first character (in a UTF-8 locale) or byte;
+/// anything after it is ignored with a warning, unless POSIXLY_CORRECT is set.
+fn char_constant(s: &str) -> Option<u32> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 2 || (bytes[0] != b'\'' && bytes[0] != b'"') {
+        return None;
     }
+    let (val, len) = match s[1..].chars().next() {
+        Some(ch) if crate::wc::is_utf8_locale() => (ch as u32, ch.len_utf8()),
+        _ => (u32::from(bytes[1]), 1),
+    };
+    let rest = &bytes[1 + len..];
+    if !rest.is_empty() && std::env::var_os("POSIXLY_CORRECT").is_none() {
+        let mut msg = b"printf: warning: ".to_vec();
+        msg.extend_from_slice(rest);
+        msg.extend_from_slice(b": character(s) following character constant have been ignored\n");
+        let _ = std::io::stderr().write_all(&msg);
+    }
+    Some(val)
+}
 
-    // Try to detect sign
-    let (negative, digits) = if let Some(rest) = s.strip_prefix('-') {
-        (true, rest)
-    } else if let Some(rest) = s.strip_prefix('+') {
-        (false, rest)
+/// Skip the leading white space strto* functions allow (C `isspace`).
+fn skip_c_space(s: &[u8]) -> usize {
+    s.iter()
+        .position(|&b| !matches!(b, b' ' | b'\t' | b'\n' | 0x0B | 0x0C | b'\r'))
+        .unwrap_or(s.len())
+}
+
+/// Scan the longest prefix of `s` that strtoimax/strtoumax accept with base
+/// 0 (decimal, 0-prefixed octal or 0x-prefixed hex).
+///
+/// Returns the sign, the magnitude (`None` past `u64::MAX`) and where the
+/// conversion ended (0 when no digits were found).
+fn scan_c_integer(s: &[u8]) -> (bool, Option<u64>, usize) {
+    let mut i = skip_c_space(s);
+    let negative = s.get(i) == Some(&b'-');
+    if matches!(s.get(i), Some(b'-' | b'+')) {
+        i += 1;
+    }
+    let (radix, start) = if s.get(i) == Some(&b'0')
+        && matches!(s.get(i + 1), Some(b'x' | b'X'))
+        && s.get(i + 2).is_some_and(u8::is_ascii_hexdigit)
+    {
+        (16, i + 2)
+    } else if s.get(i) == Some(&b'0') {
+        (8, i)
     } else {
-        (false, s)
+        (10, i)
     };
-
-    // digits must be non-empty and parseable
-    if digits.is_empty() {
-        mark_conv_error(s);
-        return 0;
+    let mut end = start;
+    let mut magnitude = Some(0u64);
+    while let Some(digit) = s.get(end).and_then(|&b| (b as char).to_digit(radix)) {
+        magnitude = magnitude
+            .and_then(|m| m.checked_mul(u64::from(radix)))
+            .and_then(|m| m.checked_add(u64::from(digit)));
+        end += 1;
     }
+    if end == start {
+        return (false, Some(0), 0);
+    }
+    (negative, magnitude, end)
+}
 
-    let magnitude = if let Some(hex) = digits
-        .strip_prefix("0x")
-        .or_else(|| digits.strip_prefix("0X"))
-    {
-        match u64::from_str_radix(hex, 16) {
-            Ok(v) => v,
-            Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                mark_range_error(s);
-                if negative {
-                    return i64::MIN;
-                }
-                return i64::MAX;
-            }
-            Err(_) => {
-                mark_conv_error(s);
-                0
-            }
-        }
-    } else if let Some(oct) = digits.strip_prefix('0') {
-        if oct.is_empty() {
-            0
-        } else {
-            match u64::from_str_radix(oct, 8) {
-                Ok(v) => v,
-                Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                    mark_range_error(s);
-                    if negative {
-                        return i64::MIN;
-                    }
-                    return i64::MAX;
-                }
-                Err(_) => {
-                    mark_conv_error(s);
-                    0
-                }
-            }
-        }
+/// Parse an integer argument like GNU's `vstrtoimax`: decimal, octal
+/// (0-prefix), hex (0x-prefix) or a character constant. Out-of-range values
+/// are clamped; invalid input is diagnosed and converts as far as it can.
+fn parse_integer(s: &str) -> i64 {
+    if let Some(val) = char_constant(s) {
+        return i64::from(val);
+    }
+    let (negative, magnitude, end) = scan_c_integer(s.as_bytes());
+    let limit = if negative {
+        1u64 << 63
     } else {
-        match digits.parse::<u64>() {
-            Ok(v) => v,
-            Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                mark_range_error(s);
-                if negative {
-                    return i64::MIN;
-                }
-                return i64::MAX;
-            }
-            Err(_) => {
-                mark_conv_error(s);
-                0
-            }
+        i64::MAX as u64
+    };
+    let (val, out_of_range) = match magnitude {
+        Some(m) if m <= limit => {
+            let val = if negative {
+                (m as i64).wrapping_neg()
+            } else {
+                m as i64
+            };
+            (val, false)
         }
+        _ if negative => (i64::MIN, true),
+        _ => (i64::MAX, true),
     };
+    verify_numeric(s, end, out_of_range);
+    val
+}
 
-    if negative {
-        -(magnitude as i64)
-    } else {
-        magnitude as i64
+/// Parse an unsigned integer argument like GNU's `vstrtoumax`; negative
+/// values wrap around as in C.
+fn parse_unsigned(s: &str) -> u64 {
+    if let Some(val) = char_constant(s) {
+        return u64::from(val);
     }
+    let (negative, magnitude, end) = scan_c_integer(s.as_bytes());
+    let (val, out_of_range) = match magnitude {
+        Some(m) if negative => (m.wrapping_neg(), false),
+        Some(m) => (m, false),
+        None => (u64::MAX, true),
+    };
+    verify_numeric(s, end, out_of_range);
+    val
 }
 
-/// Parse an unsigned integer argument.
-fn parse_unsigned(s: &str) -> u64 {
-    let s = s.trim();
-    if s.is_empty() {
-        return 0;
+/// Parse a floating-point argument like GNU's `vstrtold`.
+///
+/// GNU converts to `long double`, so the range diagnostics follow its limits
+/// even though the value itself is held in an `f64`.
+fn parse_float(s: &str) -> f64 {
+    if let Some(val) = char_constant(s) {
+        return f64::from(val);
     }
+    let (val, end, out_of_range) = scan_c_float(s.as_bytes());
+    verify_numeric(s, end, out_of_range);
+    val
+}
 
-    // Character constants
-    if (s.starts_with('\'') || s.starts_with('"')) && s.len() >= 2 {
-        return s[1..].chars().next().map_or(0, |c| c as u64);
+/// `double` limits, since values are held in an f64: largest finite decimal
+/// exponent and mantissa, smallest normal, and the binary exponents bounding
+/// normal and subnormal values.
+const DBL_MAX_10_EXP: i64 = 308;
+const DBL_MAX_MANTISSA: f64 = 1.797_693_134_862_315_7;
+const DBL_MIN_MANTISSA: f64 = 2.225_073_858_507_201_4;
+const DBL_MAX_EXP: i64 = 1024;
+const DBL_MIN_EXP: i64 = -1022;
+const DBL_TRUE_MIN_EXP: i64 = -1074;
+
+/// Scan the longest prefix of `s` that strtold accepts: a decimal or hex
+/// float, `inf`/`infinity` or `nan[(chars)]`.
+///
+/// Returns the value, where the conversion ended (0 when nothing matched)
+/// and whether the value overflows or underflows the f64 it is held in, as
+/// strtod would report with ERANGE.
+fn scan_c_float(s: &[u8]) -> (f64, usize, bool) {
+    let mut i = skip_c_space(s);
+    let negative = s.get(i) == Some(&b'-');
+    if matches!(s.get(i), Some(b'-' | b'+')) {
+        i += 1;
+    }
+    let signed = |val: f64| if negative { -val } else { val };
+    let rest = &s[i..];
+    let starts_with =
+        |word: &[u8]| rest.len() >= word.len() && rest[..word.len()].eq_ignore_ascii_case(word);
+
+    if starts_with(b"inf") {
+        let len = if starts_with(b"infinity") { 8 } else { 3 };
+        return (signed(f64::INFINITY), i + len, false);
+    }
+    if starts_with(b"nan") {
+        let mut end = i + 3;
+        if s.get(end) == Some(&b'(')
+            && let Some(len) = s[end + 1..]
+                .iter()
+                .position(|&b| !(b.is_ascii_alphanumeric() || b == b'_'))
+            && s[end + 1 + len] == b')'
+        {
+            end += len + 2;
+        }
+        return (signed(f64::NAN), end, false);
     }
 
-    // Negative values wrap around like C unsigned
-    let (negative, digits) = if let Some(rest) = s.strip_prefix('-') {
-        (true, rest)
-    } else if let Some(rest) = s.strip_prefix('+') {
-        (false, rest)
+    let hex = rest.len() > 2
+        && rest[0] == b'0'
+        && matches!(rest[1], b'x' | b'X')
+        && (rest[2].is_ascii_hexdigit()
+            || (rest[2] == b'.' && rest.get(3).is_some_and(u8::is_ascii_hexdigit)));
+    let (radix, exp_char, start) = if hex {
+        (16, b'p', i + 2)
     } else {
-        (false, s)
+        (10, b'e', i)
     };
 
-    // digits must be non-empty and parseable
+    // Mantissa digits, with an optional radix point
+    let mut digits: Vec<u32> = Vec::new();
+    let mut int_len = 0;
+    let mut end = start;
+    let mut seen_point = false;
+    loop {
+        match s.get(end) {
+            Some(&b) if (b as char).is_digit(radix) => {
+                digits.push((b as char).to_digit(radix).unwrap());
+                if !seen_point {
+                    int_len += 1;
+                }
+            }
+            Some(b'.') if !seen_point => seen_point = true,
+            _ => break,
+        }
+        end += 1;
+    }
     if digits.is_empty() {
-        mark_conv_error(s);
-        return 0;
+        return (0.0, 0, false);
     }
 
-    let magnitude = if let Some(hex) = digits
-        .strip_prefix("0x")
-        .or_else(|| digits.strip_prefix("0X"))
+    // Exponent, only if digits follow the marker
+    let mut exp: i64 = 0;
+    if s.get(end)
+        .is_some_and(|b| b.to_ascii_lowercase() == exp_char)
     {
-        match u64::from_str_radix(hex, 16) {
-            Ok(v) => v,
-            Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                mark_range_error(s);
-                u64::MAX
-            }
-            Err(_) => {
-                mark_conv_error(s);
-                0
-            }
-        }
-    } else if let Some(oct) = digits.strip_prefix('0') {
-        if oct.is_empty() {
-            0
-        } else {
-            match u64::from_str_radix(oct, 8) {
-                Ok(v) => v,
-                Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                    mark_range_error(s);
-                    u64::MAX
-                }
-                Err(_) => {
-                    mark_conv_error(s);
-                    0
-                }
-            }
+        let mut j = end + 1;
+        let exp_negative = s.get(j) == Some(&b'-');
+        if matches!(s.get(j), Some(b'-' | b'+')) {
+            j += 1;
         }
-    } else {
-        match digits.parse::<u64>() {
-            Ok(v) => v,
-            Err(e) if e.kind() == &std::num::IntErrorKind::PosOverflow => {
-                mark_range_error(s);
-                u64::MAX
+        if s.get(j).is_some_and(u8::is_ascii_digit) {
+            while let Some(&b) = s.get(j).filter(|b| b.is_ascii_digit()) {
+                exp = exp.saturating_mul(10).saturating_add(i64::from(b - b'0'));
+                j += 1;
             }
-            Err(_) => {
-                mark_conv_error(s);
-                0
+            if exp_negative {
+                exp = -exp;
             }
+            end = j;
         }
-    };
-
-    if negative {
-        magnitude.wrapping_neg()
-    } else {
-        magnitude
     }
-}
 
-/// Parse a floating-point argument.
-fn parse_float(s: &str) -> f64 {
-    let s = s.trim();
-    if s.is_empty() {
-        return 0.0;
-    }
-
-    // Character constants
-    if (s.starts_with('\'') || s.starts_with('"')) && s.len() >= 2 {
-        return s[1..].chars().next().map_or(0.0, |c| c as u32 as f64);
-    }
-
-    // Handle hex float prefix for parsing
-    if s.starts_with("0x") || s.starts_with("0X") || s.starts_with("-0x") || s.starts_with("-0X") {
-        // Rust doesn't parse hex floats natively; parse as integer
-        return parse_integer(s) as f64;
+    let Some(first) = digits.iter().position(|&d| d != 0) else {
+        return (signed(0.0), end, false);
+    };
+    let int_len = int_len as i64;
+    if hex {
+        // Binary exponents of the highest and lowest set bits
+        let last = digits.iter().rposition(|&d| d != 0).unwrap();
+        let top = 4 * (int_len - 1 - first as i64) + (31 - digits[first].leading_zeros()) as i64;
+        let low = 4 * (int_len - 1 - last as i64) + digits[last].trailing_zeros() as i64;
+        let (top, low) = (top.saturating_add(exp), low.saturating_add(exp));
+        let out_of_range = top >= DBL_MAX_EXP || (top < DBL_MIN_EXP && low < DBL_TRUE_MIN_EXP);
+        // The leading 64 bits are plenty for an f64
+        let mut mantissa: u64 = 0;
+        let mut scale = 4 * (int_len - first as i64);
+        for &d in digits[first..].iter().take(16) {
+            mantissa = mantissa * 16 + u64::from(d);
+            scale -= 4;
+        }
+        let scale = scale.saturating_add(exp).clamp(-2000, 2000) as i32;
+        let val = mantissa as f64 * 2f64.powi(scale);
+        (signed(val), end, out_of_range)
+    } else {
+        // Decimal exponent of the leading significant digit
+        let e10 = (int_len - 1 - first as i64).saturating_add(exp);
+        let lead: String = digits[first..]
+            .iter()
+            .take(17)
+            .map(|&d| char::from_digit(d, 10).unwrap())
+            .collect();
+        let lead: f64 = format!("{}.{}", &lead[..1], &lead[1..])
+            .parse()
+            .unwrap_or(0.0);
+        let out_of_range = e10 > DBL_MAX_10_EXP
+            || (e10 == DBL_MAX_10_EXP && lead > DBL_MAX_MANTISSA)
+            || e10 < -DBL_MAX_10_EXP
+            || (e10 == -DBL_MAX_10_EXP && lead < DBL_MIN_MANTISSA);
+        let text = std::str::from_utf8(&s[i..end]).unwrap_or("0");
+        let val = text.parse::<f64>().unwrap_or(0.0);
+        (signed(val), end, out_of_range)
     }
-
-    s.parse::<f64>().unwrap_or(0.0)
 }
 
 #[derive(Default)]
@@ -765,22 +866,6 @@ fn apply_string_format(
     apply_padding(truncated.as_bytes(), flags, width)
 }
 
-/// Apply string formatting for raw bytes.
-fn apply_string_format_bytes(
-    s: &[u8],
-    flags: &FormatFlags,
-    width: usize,
-    precision: Option<usize>,
-) -> Vec<u8> {
-    let data = if let Some(prec) = precision {
-        if s.len() > prec { &s[..prec] } else { s }
-    } else {
-        s
-    };
-
-    apply_padding(data, flags, width)
-}
-
 /// Apply padding (left or right) to reach the desired width.
 fn apply_padding(data: &[u8], flags: &FormatFlags, width: usize) -> Vec<u8> {
     if width == 0 || data.len() >= width {
@@ -887,39 +972,13 @@ fn apply_float_format(
 
 /// Format in scientific notation matching C's %e.
 fn format_scientific(value: f64, prec: usize, e_char: char) -> String {
-    if value == 0.0 {
-        let sign = if value.is_sign_negative() { "-" } else { "" };
-        if prec == 0 {
-            return format!("{sign}0{e_char}+00");
-        }
-        return format!("{sign}0.{:0>prec$}{e_char}+00", "", prec = prec);
-    }
-
-    let abs = value.abs();
-    let sign = if value < 0.0 { "-" } else { "" };
-    let exp = abs.log10().floor() as i32;
-    let mantissa = abs / 10f64.powi(exp);
-
-    let factor = 10f64.powi(prec as i32);
-    let mantissa = (mantissa * factor).round() / factor;
-
-    let (mantissa, exp) = if mantissa >= 10.0 {
-        (mantissa / 10.0, exp + 1)
-    } else {
-        (mantissa, exp)
-    };
-
-    let exp_sign = if exp >= 0 { '+' } else { '-' };
-    let exp_abs = exp.unsigned_abs();
-
-    if prec == 0 {
-        format!("{sign}{mantissa:.0}{e_char}{exp_sign}{exp_abs:02}")
-    } else {
-        format!(
-            "{sign}{mantissa:.prec$}{e_char}{exp_sign}{exp_abs:02}",
-            prec = prec
-        )
-    }
+    // Rust's `{:e}` is exactly rounded; C writes the exponent with a sign
+    // and at least two digits.
+    let s = format!("{:.prec$e}", value, prec = prec);
+    let (mantissa, exp) = s.split_once('e').unwrap_or((&s, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let exp_sign = if exp < 0 { '-' } else { '+' };
+    format!("{mantissa}{e_char}{exp_sign}{:02}", exp.unsigned_abs())
 }
 
 /// Format using %g - shortest representation.
@@ -931,20 +990,18 @@ fn format_g(value: f64, prec: usize, upper: bool) -> String {
         return format!("{sign}0");
     }
 
-    let abs = value.abs();
-    let exp = abs.log10().floor() as i32;
+    // C picks the style from the exponent %e would print at this precision
+    let sci = format!("{:.prec$e}", value, prec = prec - 1);
+    let exp: i32 = sci
+        .split_once('e')
+        .map_or(0, |(_, e)| e.parse().unwrap_or(0));
     let e_char = if upper { 'E' } else { 'e' };
 
     if exp < -4 || exp >= prec as i32 {
-        let sig_prec = prec.saturating_sub(1);
-        let s = format_scientific(value, sig_prec, e_char);
+        let s = format_scientific(value, prec - 1, e_char);
         trim_g_trailing_zeros(&s)
     } else {
-        let decimal_prec = if prec as i32 > exp + 1 {
-            (prec as i32 - exp - 1) as usize
-        } else {
-            0
-        };
+        let decimal_prec = (prec as i32 - 1 - exp) as usize;
         let s = format!("{value:.decimal_prec$}");
         trim_g_trailing_zeros(&s)
     }
@@ -109,47 +109,67 @@ pub fn fill_random(buf: &mut [u8]) {
     rng.fill(buf);
 }
 
-/// Shred a single file according to the given configuration.
-pub fn shred_file(path: &Path, config: &ShredConfig) -> io::Result<()> {
-    // If force, make writable if needed
-    if config.force {
-        if let Ok(meta) = fs::metadata(path) {
-            let mut perms = meta.permissions();
+/// Open `path` for writing. Like GNU shred, when that is refused and
+/// `force` is set, the file is made owner-writable (mode 0200, since it is
+/// about to be destroyed) and opened again; a failing chmod is the error
+/// reported.
+fn open_for_writing(path: &Path, force: bool) -> io::Result<fs::File> {
+    match fs::OpenOptions::new().write(true).open(path) {
+        Err(e) if force && e.kind() == io::ErrorKind::PermissionDenied => {
             #[cfg(unix)]
-            {
+            let perms = {
                 use std::os::unix::fs::PermissionsExt;
-                let mode = perms.mode();
-                if mode & 0o200 == 0 {
-                    perms.set_mode(mode | 0o200);
-                    let _ = fs::set_permissions(path, perms);
-                }
-            }
+                fs::Permissions::from_mode(0o200)
+            };
             #[cfg(not(unix))]
-            {
+            let perms = {
+                let mut perms = fs::metadata(path)?.permissions();
                 #[allow(clippy::permissions_set_readonly_false)]
-                if perms.readonly() {
-                    perms.set_readonly(false);
-                    let _ = fs::set_permissions(path, perms);
-                }
-            }
+                perms.set_readonly(false);
+                perms
+            };
+            fs::set_permissions(path, perms)?;
+            fs::OpenOptions::new().write(true).open(path)
         }
+        result => result,
     }
+}
 
-    let file_size = if let Some(s) = config.size {
-        s
-    } else {
-        fs::metadata(path)?.len()
-    };
+/// Shred a single file according to the given configuration.
+pub fn shred_file(path: &Path, config: &ShredConfig) -> io::Result<()> {
+    let mut file = open_for_writing(path, config.force).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "failed to open for writing: {}",
+                crate::common::io_error_msg(&e)
+            ),
+        )
+    })?;
 
-    let write_size = if config.exact {
-        file_size
-    } else {
-        // Round up to 512-byte block boundary
-        let block = 512u64;
-        (file_size + block - 1) / block * block
+    // An explicit -s size is written exactly as given (extending a shorter
+    // file); otherwise the whole file is overwritten, rounded up to the
+    // filesystem block size unless -x is given, to clear slack space.
+    let write_size = match config.size {
+        Some(size) => size,
+        None => {
+            let meta = file.metadata()?;
+            let file_size = meta.len();
+            if config.exact {
+                file_size
+            } else {
+                #[cfg(unix)]
+                let block = {
+                    use std::os::unix::fs::MetadataExt;
+                    meta.blksize().max(512)
+                };
+                #[cfg(not(unix))]
+                let block = 512u64;
+                file_size.div_ceil(block) * block
+            }
+        }
     };
 
-    let mut file = fs::OpenOptions::new().write(true).open(path)?;
     // Use 1MB buffer for fewer read/write syscalls
     let buf_size = 1024 * 1024usize;
     let mut rng_buf = vec![0u8; buf_size];
@@ -250,45 +270,75 @@ pub fn shred_file(path: &Path, config: &ShredConfig) -> io::Result<()> {
     Ok(())
 }
 
-/// Parse a size string with optional suffix (K, M, G, etc.).
+/// Parse a -s/--size argument like GNU shred: decimal, octal (leading 0) or
+/// hex (0x) digits with an optional suffix: c (1), b (512), B or K/k (1024),
+/// M, G, T, P, E (powers of 1024, or of 1000 when followed by "B", e.g. "KB").
 pub fn parse_size(s: &str) -> Result<u64, String> {
-    if s.is_empty() {
-        return Err("invalid size: ''".to_string());
-    }
-
-    let s = s.trim();
+    let invalid = || format!("invalid file size: '{}'", s);
+    let too_large = || {
+        format!(
+            "invalid file size: '{}': Value too large for defined data type",
+            s
+        )
+    };
 
-    // Check for suffix
-    let (num_str, multiplier) = if s.ends_with("GB") || s.ends_with("gB") {
-        (&s[..s.len() - 2], 1_000_000_000u64)
-    } else if s.ends_with("MB") {
-        (&s[..s.len() - 2], 1_000_000u64)
-    } else if s.ends_with("KB") {
-        (&s[..s.len() - 2], 1_000u64)
-    } else if s.ends_with('G') || s.ends_with('g') {
-        (&s[..s.len() - 1], 1_073_741_824u64)
-    } else if s.ends_with('M') || s.ends_with('m') {
-        (&s[..s.len() - 1], 1_048_576u64)
-    } else if s.ends_with('K') || s.ends_with('k') {
-        (&s[..s.len() - 1], 1_024u64)
+    let t = s.trim_start();
+    let digits_end = if t.starts_with("0x") || t.starts_with("0X") {
+        2 + t[2..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(t.len() - 2)
     } else {
-        (s, 1u64)
+        t.find(|c: char| !c.is_ascii_digit()).unwrap_or(t.len())
     };
+    let (num_str, suffix) = t.split_at(digits_end);
+    if num_str.is_empty() {
+        return Err(invalid());
+    }
 
-    // Parse with hex (0x/0X) and octal (0) prefix support
-    let value: u64 = if num_str.starts_with("0x") || num_str.starts_with("0X") {
-        u64::from_str_radix(&num_str[2..], 16).map_err(|_| format!("invalid size: '{}'", s))?
-    } else if num_str.starts_with('0') && num_str.len() > 1 {
-        u64::from_str_radix(num_str, 8).map_err(|_| format!("invalid size: '{}'", s))?
+    let value = if let Some(hex) = num_str
+        .strip_prefix("0x")
+        .or_else(|| num_str.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if num_str.len() > 1 && num_str.starts_with('0') {
+        u64::from_str_radix(num_str, 8)
     } else {
-        num_str
-            .parse()
-            .map_err(|_| format!("invalid size: '{}'", s))?
+        num_str.parse::<u64>()
+    };
+    let value = match value {
+        Ok(v) => v,
+        Err(e) if *e.kind() == std::num::IntErrorKind::PosOverflow => return Err(too_large()),
+        Err(_) => return Err(invalid()),
+    };
+
+    let multiplier: u64 = match suffix {
+        "" | "c" => 1,
+        "b" => 512,
+        "B" => 1024,
+        _ => {
+            let mut chars = suffix.chars();
+            let power = match chars.next() {
+                Some('k' | 'K') => 1,
+                Some('M') => 2,
+                Some('G') => 3,
+                Some('T') => 4,
+                Some('P') => 5,
+                Some('E') => 6,
+                _ => return Err(invalid()),
+            };
+            let base: u64 = match chars.as_str() {
+                "" | "iB" => 1024,
+                "B" => 1000,
+                _ => return Err(invalid()),
+            };
+            base.pow(power)
+        }
     };
 
     value
         .checked_mul(multiplier)
-        .ok_or_else(|| format!("size too large: '{}'", s))
+        .filter(|&n| n <= i64::MAX as u64)
+        .ok_or_else(too_large)
 }
 
 /// Parse a --remove[=HOW] argument.
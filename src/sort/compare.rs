@@ -3,13 +3,18 @@ use std::cmp::Ordering;
 
 use super::key::KeyOpts;
 
-/// Strip leading blanks (space and tab).
+/// Blank test used for -b and number parsing. Like GNU, newline counts as a
+/// blank; it can only occur inside a line with -z.
+#[inline(always)]
+pub fn is_sort_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\n'
+}
+
+/// Strip leading blanks (space, tab and newline).
 #[inline(always)]
 pub fn skip_leading_blanks(s: &[u8]) -> &[u8] {
     let mut i = 0;
-    while i < s.len()
-        && (unsafe { *s.get_unchecked(i) } == b' ' || unsafe { *s.get_unchecked(i) } == b'\t')
-    {
+    while i < s.len() && is_sort_blank(unsafe { *s.get_unchecked(i) }) {
         i += 1;
     }
     &s[i..]
@@ -495,6 +500,8 @@ fn parse_human_numeric_tiered(s: &[u8]) -> (f64, u8) {
 /// This ensures: -1G < -1M < -1K < -1 < 0 < 1 < 1K < 1M < 1G
 pub fn human_numeric_to_sortable_u64(s: &[u8]) -> u64 {
     let (val, tier) = parse_human_numeric_tiered(s);
+    // -0 compares equal to 0.
+    let val = val + 0.0;
 
     // Convert the numeric value to a sortable u64 (preserves float ordering)
     let sf = {
@@ -583,6 +590,10 @@ fn file_prefixlen(s: &[u8]) -> usize {
 /// Version sort (-V): GNU filevercmp-compatible version comparison.
 /// Implements the exact same algorithm as GNU coreutils' filevercmp.
 pub fn compare_version(a: &[u8], b: &[u8]) -> Ordering {
+    // An empty version sorts before any other, even one of only zeros.
+    if a.is_empty() || b.is_empty() {
+        return a.is_empty().cmp(&b.is_empty()).reverse();
+    }
     // GNU filevercmp: skip hidden-file dot prefix, compare, then break tie
     // by including the prefix.
     let a_prefix = if a.first() == Some(&b'.') { 1 } else { 0 };
@@ -698,28 +709,35 @@ fn ver_order(s: &[u8], pos: usize, len: usize) -> i32 {
 }
 
 /// Random sort (-R): hash-based shuffle that groups identical keys.
+/// Keys whose hashes collide are ordered by their bytes, so distinct keys
+/// are never interleaved.
 pub fn compare_random(a: &[u8], b: &[u8], seed: u64) -> Ordering {
-    let ha = fnv1a_hash(a, seed);
-    let hb = fnv1a_hash(b, seed);
-    ha.cmp(&hb)
+    let ha = keyed_hash(a, seed);
+    let hb = keyed_hash(b, seed);
+    ha.cmp(&hb).then_with(|| a.cmp(b))
 }
 
-/// FNV-1a hash with seed mixing.
+/// FNV-1a hash keyed by `seed`, with a final avalanche so that keys sharing
+/// a prefix are spread over the whole output range.
 #[inline]
-fn fnv1a_hash(data: &[u8], seed: u64) -> u64 {
+fn keyed_hash(data: &[u8], seed: u64) -> u64 {
     let mut hash = 0xcbf29ce484222325u64 ^ seed;
     for &b in data {
         hash ^= b as u64;
         hash = hash.wrapping_mul(0x100000001b3);
     }
-    hash
+    // splitmix64 finalizer, re-keyed so the seed affects every output bit
+    hash ^= seed.rotate_left(32);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
 }
 
 /// Compare with text filtering (-d, -i, -f flags in any combination).
 /// Allocation-free: uses iterator filtering.
 #[inline]
 fn is_dict_char(b: u8) -> bool {
-    b.is_ascii_alphanumeric() || b == b' ' || b == b'\t'
+    b.is_ascii_alphanumeric() || is_sort_blank(b)
 }
 
 #[inline]
@@ -737,6 +755,8 @@ fn compare_text_filtered(
     if !dict && !no_print && !fold_case {
         return a.cmp(b);
     }
+    // As in GNU sort, -d takes precedence over -i.
+    let no_print = no_print && !dict;
 
     let mut ai = a.iter().copied();
     let mut bi = b.iter().copied();
@@ -816,6 +836,64 @@ pub fn compare_ignore_nonprinting(a: &[u8], b: &[u8], ignore_case: bool) -> Orde
     compare_text_filtered(a, b, false, true, ignore_case)
 }
 
+/// Whether -d, -i or -f must be applied to a copy of the key before a
+/// human-numeric, version or random comparison, as GNU sort does. (-d and -i
+/// are rejected with the other numeric types, where -f has no effect.)
+#[inline]
+fn filters_typed_key(opts: &KeyOpts) -> bool {
+    (opts.human_numeric || opts.version || opts.random)
+        && (opts.dictionary_order || opts.ignore_nonprinting || opts.ignore_case)
+}
+
+/// The bytes of `s` that survive -d (or else -i), upper-cased for -f.
+fn filter_key(s: &[u8], dict: bool, no_print: bool, fold_case: bool) -> Vec<u8> {
+    let no_print = no_print && !dict;
+    s.iter()
+        .copied()
+        .filter(|&b| (!dict || is_dict_char(b)) && (!no_print || is_printable(b)))
+        .map(|b| if fold_case { b.to_ascii_uppercase() } else { b })
+        .collect()
+}
+
+const FILTERED_HUMAN: u8 = 0;
+const FILTERED_VERSION: u8 = 1;
+const FILTERED_RANDOM: u8 = 2;
+
+/// Filter both keys, then compare them as the key type `KIND` (the random
+/// comparison uses the seed stored by `select_comparator`).
+fn compare_filtered_key<
+    const KIND: u8,
+    const DICT: bool,
+    const NO_PRINT: bool,
+    const FOLD: bool,
+>(
+    a: &[u8],
+    b: &[u8],
+) -> Ordering {
+    let a = filter_key(a, DICT, NO_PRINT, FOLD);
+    let b = filter_key(b, DICT, NO_PRINT, FOLD);
+    match KIND {
+        FILTERED_HUMAN => compare_human_numeric(&a, &b),
+        FILTERED_VERSION => compare_version(&a, &b),
+        _ => random_compare_with_static_seed(&a, &b),
+    }
+}
+
+/// Pick the `compare_filtered_key` instance for the key's -d/-i/-f flags.
+fn filtered_comparator<const KIND: u8>(opts: &KeyOpts) -> CompareFn {
+    match (
+        opts.dictionary_order,
+        opts.ignore_nonprinting,
+        opts.ignore_case,
+    ) {
+        (true, _, false) => compare_filtered_key::<KIND, true, false, false>,
+        (true, _, true) => compare_filtered_key::<KIND, true, false, true>,
+        (false, true, false) => compare_filtered_key::<KIND, false, true, false>,
+        (false, true, true) => compare_filtered_key::<KIND, false, true, true>,
+        (false, false, _) => compare_filtered_key::<KIND, false, false, true>,
+    }
+}
+
 /// Master comparison function that dispatches based on KeyOpts.
 pub fn compare_with_opts(a: &[u8], b: &[u8], opts: &KeyOpts, random_seed: u64) -> Ordering {
     let a = if opts.ignore_leading_blanks {
@@ -828,6 +906,21 @@ pub fn compare_with_opts(a: &[u8], b: &[u8], opts: &KeyOpts, random_seed: u64) -
     } else {
         b
     };
+    let filtered;
+    let (a, b) = if filters_typed_key(opts) {
+        let (dict, no_print, fold) = (
+            opts.dictionary_order,
+            opts.ignore_nonprinting,
+            opts.ignore_case,
+        );
+        filtered = (
+            filter_key(a, dict, no_print, fold),
+            filter_key(b, dict, no_print, fold),
+        );
+        (filtered.0.as_slice(), filtered.1.as_slice())
+    } else {
+        (a, b)
+    };
 
     let result = if opts.numeric {
         compare_numeric(a, b)
@@ -876,16 +969,29 @@ pub fn select_comparator(opts: &KeyOpts, random_seed: u64) -> (CompareFn, bool,
     let needs_blank = opts.ignore_leading_blanks;
     let needs_reverse = opts.reverse;
 
+    let filtered = filters_typed_key(opts);
+
     let cmp: CompareFn = if opts.numeric {
         compare_numeric
     } else if opts.general_numeric {
         compare_general_numeric
     } else if opts.human_numeric {
-        compare_human_numeric
+        if filtered {
+            filtered_comparator::<FILTERED_HUMAN>(opts)
+        } else {
+            compare_human_numeric
+        }
     } else if opts.month {
         compare_month
     } else if opts.version {
-        compare_version
+        if filtered {
+            filtered_comparator::<FILTERED_VERSION>(opts)
+        } else {
+            compare_version
+        }
+    } else if opts.random && filtered {
+        RANDOM_SEED.store(random_seed, std::sync::atomic::Ordering::Relaxed);
+        filtered_comparator::<FILTERED_RANDOM>(opts)
     } else if opts.random {
         // Random needs seed — wrap in a closure-like pattern
         // Since we need random_seed, we use a special case
@@ -18,8 +18,9 @@ use rayon::prelude::*;
 use crate::common::io_error_msg;
 
 use super::compare::{
-    compare_with_opts, human_numeric_to_sortable_u64, int_to_sortable_u64, parse_general_numeric,
-    parse_numeric_value, select_comparator, skip_leading_blanks, try_parse_integer,
+    compare_with_opts, human_numeric_to_sortable_u64, int_to_sortable_u64, is_sort_blank,
+    parse_general_numeric, parse_numeric_value, select_comparator, skip_leading_blanks,
+    try_parse_integer,
 };
 use super::key::{KeyDef, KeyOpts, extract_key_z};
 
@@ -154,7 +155,7 @@ pub struct SortConfig {
     pub debug: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CheckMode {
     None,
     Diagnose,
@@ -183,6 +184,14 @@ impl Default for SortConfig {
     }
 }
 
+/// Derive the -R hash key from the first 8 bytes of a --random-source file,
+/// so that repeated runs with the same source give the same order.
+pub fn read_random_seed(path: &str) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    File::open(path)?.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
 /// Compare two lines using the full key chain, global options, and last-resort.
 /// Used for sorting (determines order of all lines).
 #[inline]
@@ -244,7 +253,7 @@ fn compare_lines_inner(
 
         // All keys equal: last-resort comparison (whole line) unless -s or dedup
         if !stable {
-            return a.cmp(b);
+            return last_resort(a, b, config.reverse);
         }
 
         Ordering::Equal
@@ -253,13 +262,20 @@ fn compare_lines_inner(
         let result = compare_with_opts(a, b, &config.global_opts, config.random_seed);
         // Last-resort whole-line comparison for deterministic order (unless -s or dedup)
         if result == Ordering::Equal && !stable {
-            a.cmp(b)
+            last_resort(a, b, config.reverse)
         } else {
             result
         }
     }
 }
 
+/// GNU's last-resort comparison of whole lines, made when all keys compare
+/// equal. Only the global -r reverses it; a key's own `r` covers that key.
+#[inline]
+fn last_resort(a: &[u8], b: &[u8], reverse: bool) -> Ordering {
+    if reverse { b.cmp(a) } else { a.cmp(b) }
+}
+
 /// Parallel line boundary detection for large files (>4MB).
 /// Splits data into thread-count chunks aligned at delimiter boundaries,
 /// then scans each chunk concurrently with SIMD memchr.
@@ -344,16 +360,19 @@ fn find_lines_parallel(data: &[u8], delimiter: u8) -> (Vec<(usize, usize)>, bool
 
 /// Read all input into a single contiguous buffer and compute line offsets.
 /// Uses mmap for single-file input (zero-copy), Vec for stdin/multi-file.
+/// `no_mmap` forces an owned copy, for when the output file is about to
+/// truncate the input the mapping would point into.
 /// Returns (buffer, offsets, has_cr) where has_cr indicates CRLF line endings were found.
 #[allow(clippy::type_complexity)]
 fn read_all_input(
     inputs: &[String],
     zero_terminated: bool,
+    no_mmap: bool,
 ) -> io::Result<(FileData, Vec<(usize, usize)>, bool)> {
     let delimiter = if zero_terminated { b'\0' } else { b'\n' };
 
     // Single file (non-stdin): use mmap directly for zero-copy
-    let buffer = if inputs.len() == 1 && inputs[0] != "-" {
+    let buffer = if inputs.len() == 1 && inputs[0] != "-" && !no_mmap {
         let file = File::open(&inputs[0]).map_err(|e| {
             io::Error::new(
                 e.kind(),
@@ -422,7 +441,7 @@ fn read_all_input(
     // Use parallel scanning for large files (>4MB) to leverage multiple cores
     // Also track whether any CRLF endings were found (avoids O(n) memchr scan later)
     let data = &*buffer;
-    let (offsets, has_cr) = if data.len() > 2 * 1024 * 1024 {
+    let (offsets, has_cr) = if data.len() > 2 * 1024 * 1024 && rayon::current_num_threads() > 1 {
         find_lines_parallel(data, delimiter)
     } else {
         let dp = data.as_ptr();
@@ -456,6 +475,32 @@ fn read_all_input(
     Ok((buffer, offsets, has_cr))
 }
 
+/// Whether the -o file is one of the inputs (same device and inode), so
+/// creating it would truncate data that hasn't been read yet.
+fn output_is_input(inputs: &[String], config: &SortConfig) -> bool {
+    let Some(ref output) = config.output_file else {
+        return false;
+    };
+    let Ok(out_meta) = std::fs::metadata(output) else {
+        return false;
+    };
+    inputs
+        .iter()
+        .filter(|input| *input != "-")
+        .any(|input| std::fs::metadata(input).is_ok_and(|meta| same_file(&meta, &out_meta)))
+}
+
+#[cfg(unix)]
+fn same_file(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &std::fs::Metadata, _b: &std::fs::Metadata) -> bool {
+    false
+}
+
 /// Read all lines from inputs (legacy API, used by merge_sorted).
 pub fn read_lines(inputs: &[String], zero_terminated: bool) -> io::Result<Vec<Vec<u8>>> {
     let delimiter = if zero_terminated { b'\0' } else { b'\n' };
@@ -506,7 +551,7 @@ fn read_delimited_lines<R: Read>(
 
 /// Check if input is sorted using the optimized buffer path.
 pub fn check_sorted(inputs: &[String], config: &SortConfig) -> io::Result<bool> {
-    let (buffer, offsets, _has_cr) = read_all_input(inputs, config.zero_terminated)?;
+    let (buffer, offsets, _has_cr) = read_all_input(inputs, config.zero_terminated, false)?;
     let data = &*buffer;
 
     let dp = data.as_ptr();
@@ -531,13 +576,17 @@ pub fn check_sorted(inputs: &[String], config: &SortConfig) -> io::Result<bool>
         };
         if bad {
             if config.check == CheckMode::Diagnose {
-                let line_display = String::from_utf8_lossy(&data[s2..e2]);
                 let filename = if inputs.is_empty() || inputs[0] == "-" {
                     "-"
                 } else {
                     &inputs[0]
                 };
-                eprintln!("sort: {}:{}: disorder: {}", filename, i + 1, line_display);
+                // Like GNU, echo the line's raw bytes and its terminator.
+                let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
+                let mut err = io::stderr().lock();
+                let _ = write!(err, "sort: {}:{}: disorder: ", filename, i + 1);
+                let _ = err.write_all(&data[s2..e2]);
+                let _ = err.write_all(&[delimiter]);
             }
             return Ok(false);
         }
@@ -555,17 +604,9 @@ struct MergeEntry {
     seq: u64,
 }
 
-/// Merge already-sorted files using a BinaryHeap for O(n log k) performance.
-/// Previous implementation used O(k) linear scan per output line.
-pub fn merge_sorted(
-    inputs: &[String],
-    config: &SortConfig,
-    writer: &mut impl Write,
-) -> io::Result<()> {
-    let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
-    let terminator: &[u8] = if config.zero_terminated { b"\0" } else { b"\n" };
-
-    // Open readers for all files
+/// Open a reader for each merge input. With `preload`, files are read into
+/// memory up front, as needed when the output file is also an input.
+fn open_merge_inputs(inputs: &[String], preload: bool) -> io::Result<Vec<Box<dyn BufRead>>> {
     let mut readers: Vec<Box<dyn BufRead>> = Vec::with_capacity(inputs.len());
     for input in inputs {
         if input == "-" {
@@ -573,11 +614,35 @@ pub fn merge_sorted(
                 256 * 1024,
                 io::stdin().lock(),
             )));
+        } else if preload {
+            readers.push(Box::new(io::Cursor::new(std::fs::read(input)?)));
         } else {
             let file = File::open(input)?;
             readers.push(Box::new(BufReader::with_capacity(256 * 1024, file)));
         }
     }
+    Ok(readers)
+}
+
+/// Merge already-sorted files using a BinaryHeap for O(n log k) performance.
+/// Previous implementation used O(k) linear scan per output line.
+pub fn merge_sorted(
+    inputs: &[String],
+    config: &SortConfig,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let readers = open_merge_inputs(inputs, false)?;
+    merge_readers(readers, config, writer)
+}
+
+/// Heap merge over already-opened inputs; see `merge_sorted`.
+fn merge_readers(
+    mut readers: Vec<Box<dyn BufRead>>,
+    config: &SortConfig,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
+    let terminator: &[u8] = if config.zero_terminated { b"\0" } else { b"\n" };
 
     // Helper to read next line from a reader
     let read_next = |reader: &mut dyn BufRead, delim: u8| -> io::Result<Option<Vec<u8>>> {
@@ -599,7 +664,7 @@ pub fn merge_sorted(
     // BinaryHeap is a max-heap, so we wrap comparison in Reverse-like logic
     let mut seq: u64 = 0;
     let mut heap: BinaryHeap<std::cmp::Reverse<MergeEntryOrd>> =
-        BinaryHeap::with_capacity(inputs.len());
+        BinaryHeap::with_capacity(readers.len());
     let config_arc = Arc::new(config.clone());
 
     for (i, reader) in readers.iter_mut().enumerate() {
@@ -962,13 +1027,14 @@ fn parse_to_sortable_u64(slice: &[u8], opts: &KeyOpts) -> u64 {
 
 /// Convert f64 to a u64 whose natural ordering matches float ordering.
 /// This enables branchless u64::cmp instead of f64::partial_cmp.
-/// NaN sorts before all other values (for -g compatibility).
+/// NaN sorts before all other values (for -g compatibility), and -0 is
+/// folded into 0, which compares equal to it.
 #[inline]
 fn float_to_sortable_u64(f: f64) -> u64 {
     if f.is_nan() {
         return 0; // NaN sorts first
     }
-    let bits = f.to_bits();
+    let bits = (f + 0.0).to_bits();
     if (bits >> 63) == 0 {
         bits ^ 0x8000000000000000 // positive: flip sign bit
     } else {
@@ -976,6 +1042,35 @@ fn float_to_sortable_u64(f: f64) -> u64 {
     }
 }
 
+/// Parse -n keys into (sortable u64, index) entries. Keys are encoded as
+/// i64 when every one is an integer and as f64 otherwise: the two encodings
+/// do not order against each other, so they must never be mixed.
+fn numeric_sortable_entries(
+    data: &[u8],
+    ranges: &[(usize, usize)],
+    parallel: bool,
+) -> Vec<(u64, usize)> {
+    let int_entry = |(i, &(s, e)): (usize, &(usize, usize))| {
+        try_parse_integer(&data[s..e]).map(|v| (int_to_sortable_u64(v), i))
+    };
+    let ints: Option<Vec<(u64, usize)>> = if parallel {
+        ranges.par_iter().enumerate().map(int_entry).collect()
+    } else {
+        ranges.iter().enumerate().map(int_entry).collect()
+    };
+    if let Some(entries) = ints {
+        return entries;
+    }
+    let float_entry = |(i, &(s, e)): (usize, &(usize, usize))| {
+        (float_to_sortable_u64(parse_numeric_value(&data[s..e])), i)
+    };
+    if parallel {
+        ranges.par_iter().enumerate().map(float_entry).collect()
+    } else {
+        ranges.iter().enumerate().map(float_entry).collect()
+    }
+}
+
 /// Write sorted indices to output, with optional unique dedup.
 /// Zero-copy writev: writes directly from mmap data through BufWriter.
 /// Eliminates ~110MB intermediate buffer allocation for 100MB files.
@@ -993,7 +1088,7 @@ fn is_debug_no_match(key: &[u8], key_opts: &KeyOpts, global_opts: &KeyOpts) -> b
 
     if is_numeric || is_human_numeric {
         // Numeric: skip leading blanks, then check for digit, sign, or decimal point
-        let trimmed = key.iter().position(|&b| b != b' ' && b != b'\t');
+        let trimmed = key.iter().position(|&b| !is_sort_blank(b));
         match trimmed {
             None => true,
             Some(pos) => {
@@ -1003,7 +1098,7 @@ fn is_debug_no_match(key: &[u8], key_opts: &KeyOpts, global_opts: &KeyOpts) -> b
         }
     } else if is_general_numeric {
         // General numeric: skip blanks, check for digit, sign, decimal, NaN, Inf
-        let trimmed = key.iter().position(|&b| b != b' ' && b != b'\t');
+        let trimmed = key.iter().position(|&b| !is_sort_blank(b));
         match trimmed {
             None => true,
             Some(pos) => {
@@ -1022,7 +1117,7 @@ fn is_debug_no_match(key: &[u8], key_opts: &KeyOpts, global_opts: &KeyOpts) -> b
         // Month: check if the trimmed key starts with a valid month abbreviation
         let trimmed: Vec<u8> = key
             .iter()
-            .skip_while(|&&b| b == b' ' || b == b'\t')
+            .skip_while(|&&b| is_sort_blank(b))
             .take(3)
             .map(|&b| b.to_ascii_uppercase())
             .collect();
@@ -1288,6 +1383,25 @@ fn write_sorted_entries(
     Ok(())
 }
 
+/// Apply -r to entries sorted ascending, whose runs of equal keys are in
+/// input order under -s and in ascending line order otherwise. `key_reverse`
+/// flips the key order; `tie_reverse` is the order wanted within each run.
+fn reverse_sorted<T>(
+    entries: &mut [T],
+    key_reverse: bool,
+    tie_reverse: bool,
+    same: impl FnMut(&T, &T) -> bool,
+) {
+    if key_reverse {
+        entries.reverse();
+    }
+    if key_reverse != tie_reverse {
+        for run in entries.chunk_by_mut(same) {
+            run.reverse();
+        }
+    }
+}
+
 /// Full 4-pass LSD (Least Significant Digit) radix sort for (u64, usize) entries.
 /// Sorts purely by the u64 key in O(n) time with ZERO comparisons.
 /// Uses 256-bucket passes on each byte of the u64 (4 passes on 16-bit groups).
@@ -1632,6 +1746,16 @@ fn do_sort(
 /// 3. Single-key sorts: pre-extracts key offsets + optional numeric pre-parse
 /// 4. General: index-based sort with full comparison function
 pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()> {
+    // Like GNU, -u skips the last-resort comparison, so each run of equal keys
+    // stays in input order and the line kept is the first one read.
+    if config.unique && !config.stable {
+        let config = SortConfig {
+            stable: true,
+            ..config.clone()
+        };
+        return sort_and_output(inputs, &config);
+    }
+
     // Enlarge pipe buffers on Linux for higher throughput when reading from stdin.
     // 8MB matches other tools (ftac, fbase64, ftr, fcut) for consistent syscall reduction.
     #[cfg(target_os = "linux")]
@@ -1643,23 +1767,86 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         }
     }
 
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if let Some(n) = sort_thread_limit(config.parallel, cpus, |name| std::env::var(name).ok()) {
+        // Building a pool costs more than sorting a small input, so such
+        // inputs only get one when it has to keep everything on this thread.
+        if n > 1 && small_input(inputs) {
+            return sort_in_pool(inputs, config);
+        }
+        if let Some(pool) = sort_thread_pool(n) {
+            return pool.install(|| sort_in_pool(inputs, config));
+        }
+    }
+
     // Pre-initialize rayon thread pool in background thread.
     // Overlaps ~300-500µs thread pool creation with file open + mmap (which
     // takes ~1-2ms for MAP_POPULATE on cached files). Without this, the first
     // par_iter call pays the full initialization penalty synchronously.
-    let parallel_count = config.parallel;
-    std::thread::spawn(move || match parallel_count {
-        Some(n) => {
-            rayon::ThreadPoolBuilder::new()
-                .num_threads(n.max(1))
-                .build_global()
-                .ok();
+    std::thread::spawn(|| {
+        rayon::ThreadPoolBuilder::new().build_global().ok();
+    });
+
+    sort_in_pool(inputs, config)
+}
+
+/// Thread count requested by --parallel, or failing that by OMP_NUM_THREADS
+/// capped by OMP_THREAD_LIMIT, the overrides GNU sort also honours for its
+/// default. Either way no more than `cpus` threads are used, since extra
+/// threads only add start-up cost. `None` means use the default global pool.
+/// `env` looks up an environment variable.
+fn sort_thread_limit(
+    parallel: Option<usize>,
+    cpus: usize,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<usize> {
+    let cpus = cpus.max(1);
+    if let Some(n) = parallel {
+        return Some(n.clamp(1, cpus));
+    }
+    let env_count = |name: &str| {
+        env(name)
+            .and_then(|v| v.split(',').next()?.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+    };
+    let limit = env_count("OMP_THREAD_LIMIT");
+    let n = env_count("OMP_NUM_THREADS")
+        .or_else(|| limit.map(|_| cpus))?
+        .min(cpus);
+    Some(limit.map_or(n, |limit| n.min(limit)))
+}
+
+/// Whether every input is a regular file and together they are too small
+/// for a dedicated thread pool to pay off. Standard input counts as large,
+/// since its size is not known up front.
+fn small_input(inputs: &[String]) -> bool {
+    const SMALL_INPUT_BYTES: u64 = 1024 * 1024;
+    let mut total = 0;
+    for path in inputs {
+        if path == "-" {
+            return false;
         }
-        None => {
-            rayon::ThreadPoolBuilder::new().build_global().ok();
+        match std::fs::metadata(path) {
+            Ok(meta) if meta.is_file() => total += meta.len(),
+            _ => return false,
         }
-    });
+    }
+    !inputs.is_empty() && total < SMALL_INPUT_BYTES
+}
+
+/// Dedicated pool for an explicit thread count. It includes the calling
+/// thread, so a count of 1 runs every rayon call inline on the main thread
+/// and N uses exactly N threads.
+fn sort_thread_pool(threads: usize) -> Option<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .use_current_thread()
+        .build()
+        .ok()
+}
 
+/// Body of `sort_and_output`, run inside whichever rayon pool was chosen.
+fn sort_in_pool(inputs: &[String], config: &SortConfig) -> io::Result<()> {
     if config.check != CheckMode::None {
         let sorted = check_sorted(inputs, config)?;
         if !sorted {
@@ -1668,7 +1855,12 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         return Ok(());
     }
 
+    // Like GNU sort, `-o FILE` may name an input: everything is read (and
+    // copied out of any mapping) before FILE is truncated.
+    let output_is_input = output_is_input(inputs, config);
+
     if config.merge {
+        let readers = open_merge_inputs(inputs, output_is_input)?;
         let mut writer = if let Some(ref path) = config.output_file {
             SortOutput::File(BufWriter::with_capacity(
                 OUTPUT_BUF_SIZE,
@@ -1677,11 +1869,12 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         } else {
             SortOutput::stdout()
         };
-        return merge_sorted(inputs, config, &mut writer);
+        return merge_readers(readers, config, &mut writer);
     }
 
     // Read all input BEFORE opening output file (supports -o same-file)
-    let (buffer, offsets, has_cr) = read_all_input(inputs, config.zero_terminated)?;
+    let (buffer, offsets, has_cr) =
+        read_all_input(inputs, config.zero_terminated, output_is_input)?;
     let data: &[u8] = &buffer;
     let num_lines = offsets.len();
 
@@ -1812,7 +2005,9 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                 match cmp {
                     Ordering::Greater => asc = false,
                     Ordering::Less => desc = false,
-                    _ => {}
+                    // Reversing would swap lines that -s keeps in input order.
+                    Ordering::Equal if config.stable => desc = false,
+                    Ordering::Equal => {}
                 }
                 if !asc && !desc {
                     break;
@@ -2383,14 +2578,18 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                         j += 1;
                     }
                     if j - i > 1 {
-                        entries[i..j].sort_unstable_by(fold_cmp);
+                        if stable {
+                            entries[i..j].sort_by(fold_cmp);
+                        } else {
+                            entries[i..j].sort_unstable_by(fold_cmp);
+                        }
                     }
                     i = j;
                 }
             }
-            if reverse {
-                entries.reverse();
-            }
+            reverse_sorted(&mut entries, reverse, reverse && !stable, |a, b| {
+                fold_cmp(a, b) == Ordering::Equal
+            });
             write_sorted_entries(data, &offsets, &entries, config, &mut writer, terminator)?;
         } else {
             let fold_cmp_rev = |a: &(u64, usize), b: &(u64, usize)| -> Ordering {
@@ -2412,29 +2611,7 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         let stable = config.stable;
 
         let mut entries: Vec<(u64, usize)> = if gopts.numeric {
-            // Single-pass numeric parse: try integer first, fall back to f64 inline.
-            // This avoids the two-pass penalty when some lines have decimals —
-            // instead of parsing ALL lines twice, each line is parsed exactly once.
-            let parse_numeric_inline = |i: usize, &(s, e): &(usize, usize)| -> (u64, usize) {
-                let line = &data[s..e];
-                match try_parse_integer(line) {
-                    Some(v) => (int_to_sortable_u64(v), i),
-                    None => (float_to_sortable_u64(parse_numeric_value(line)), i),
-                }
-            };
-            if num_lines > 10_000 {
-                offsets
-                    .par_iter()
-                    .enumerate()
-                    .map(|(i, off)| parse_numeric_inline(i, off))
-                    .collect()
-            } else {
-                offsets
-                    .iter()
-                    .enumerate()
-                    .map(|(i, off)| parse_numeric_inline(i, off))
-                    .collect()
-            }
+            numeric_sortable_entries(data, &offsets, num_lines > 10_000)
         } else {
             // General numeric (-g) or human numeric (-h): use sortable u64
             if num_lines > 10_000 {
@@ -2456,11 +2633,20 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         // O(n) u64 comparisons instead of O(n) string-parsing comparisons.
         // For reverse mode, check descending order.
         if entries.len() > 1 {
-            let is_sorted = if reverse {
-                entries.windows(2).all(|w| w[0].0 >= w[1].0)
-            } else {
-                entries.windows(2).all(|w| w[0].0 <= w[1].0)
+            let in_order = |w: &[(u64, usize)]| {
+                let ord = w[0].0.cmp(&w[1].0);
+                let ord = if ord == Ordering::Equal && !stable {
+                    let (sa, ea) = offsets[w[0].1];
+                    let (sb, eb) = offsets[w[1].1];
+                    last_resort(&data[sa..ea], &data[sb..eb], reverse)
+                } else if reverse {
+                    ord.reverse()
+                } else {
+                    ord
+                };
+                ord != Ordering::Greater
             };
+            let is_sorted = entries.windows(2).all(in_order);
             if is_sorted {
                 // Data is already sorted by numeric value
                 if !config.unique
@@ -2490,9 +2676,7 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
         if n > 256 {
             // Always sort ascending in the radix sort; apply reverse at output time.
             let mut entries = radix_sort_numeric_entries(entries, data, &offsets, stable, false);
-            if reverse {
-                entries.reverse();
-            }
+            reverse_sorted(&mut entries, reverse, reverse && !stable, |a, b| a.0 == b.0);
             write_sorted_entries(data, &offsets, &entries, config, &mut writer, terminator)?;
         } else {
             let dp_ns = data.as_ptr();
@@ -2505,8 +2689,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                     let (sa, ea) = offsets[a.1];
                     let (sb, eb) = offsets[b.1];
                     unsafe {
-                        std::slice::from_raw_parts(dp_ns.add(sa), ea - sa)
-                            .cmp(std::slice::from_raw_parts(dp_ns.add(sb), eb - sb))
+                        last_resort(
+                            std::slice::from_raw_parts(dp_ns.add(sa), ea - sa),
+                            std::slice::from_raw_parts(dp_ns.add(sb), eb - sb),
+                            config.reverse,
+                        )
                     }
                 } else {
                     Ordering::Equal
@@ -2553,30 +2740,7 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
             let stable = config.stable;
 
             let mut entries: Vec<(u64, usize)> = if is_pure_numeric {
-                // Single-pass numeric parse: try integer first, fall back to f64 inline.
-                let parse_entry = |i: usize, &(s, e): &(usize, usize)| -> (u64, usize) {
-                    if s == e {
-                        return (int_to_sortable_u64(0), i);
-                    }
-                    let line = &data[s..e];
-                    match try_parse_integer(line) {
-                        Some(v) => (int_to_sortable_u64(v), i),
-                        None => (float_to_sortable_u64(parse_numeric_value(line)), i),
-                    }
-                };
-                if num_lines > 10_000 {
-                    key_offs
-                        .par_iter()
-                        .enumerate()
-                        .map(|(i, ko)| parse_entry(i, ko))
-                        .collect()
-                } else {
-                    key_offs
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ko)| parse_entry(i, ko))
-                        .collect()
-                }
+                numeric_sortable_entries(data, &key_offs, num_lines > 10_000)
             } else {
                 let parse_entry = |i: usize, &(s, e): &(usize, usize)| {
                     let u = if s == e {
@@ -2612,9 +2776,8 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
             if n > 256 {
                 let mut entries =
                     radix_sort_numeric_entries(entries, data, &offsets, stable, false);
-                if reverse {
-                    entries.reverse();
-                }
+                let tie_reverse = config.reverse && !stable;
+                reverse_sorted(&mut entries, reverse, tie_reverse, |a, b| a.0 == b.0);
                 write_sorted_entries(data, &offsets, &entries, config, &mut writer, terminator)?;
             } else {
                 let dp_skn = data.as_ptr();
@@ -2627,8 +2790,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                         let (sa, ea) = offsets[a.1];
                         let (sb, eb) = offsets[b.1];
                         unsafe {
-                            std::slice::from_raw_parts(dp_skn.add(sa), ea - sa)
-                                .cmp(std::slice::from_raw_parts(dp_skn.add(sb), eb - sb))
+                            last_resort(
+                                std::slice::from_raw_parts(dp_skn.add(sa), ea - sa),
+                                std::slice::from_raw_parts(dp_skn.add(sb), eb - sb),
+                                config.reverse,
+                            )
                         }
                     } else {
                         Ordering::Equal
@@ -2660,6 +2826,10 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                 if num_lines > 1 {
                     let mut is_sorted_fwd = true;
                     let mut is_sorted_rev = true;
+                    // Order of the whole lines within runs of equal keys.
+                    let mut has_ties = false;
+                    let mut ties_asc = true;
+                    let mut ties_desc = true;
                     let mut prev_pfx = if key_offs[0].0 < key_offs[0].1 {
                         line_prefix(data, key_offs[0].0, key_offs[0].1)
                     } else {
@@ -2684,12 +2854,13 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                                 let ck = &data[ks..ke];
                                 if pk > ck {
                                     is_sorted_fwd = false;
-                                } else if !config.stable && pk == ck {
+                                } else if pk == ck {
                                     let (ls, le) = offsets[i - 1];
                                     let (cs, ce) = offsets[i];
-                                    if data[ls..le] > data[cs..ce] {
-                                        is_sorted_fwd = false;
-                                    }
+                                    let tie = data[ls..le].cmp(&data[cs..ce]);
+                                    ties_asc &= tie != Ordering::Greater;
+                                    ties_desc &= tie != Ordering::Less;
+                                    has_ties = true;
                                 }
                             }
                         }
@@ -2702,12 +2873,13 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                                 let ck = &data[ks..ke];
                                 if pk < ck {
                                     is_sorted_rev = false;
-                                } else if !config.stable && pk == ck {
+                                } else if pk == ck && !is_sorted_fwd {
                                     let (ls, le) = offsets[i - 1];
                                     let (cs, ce) = offsets[i];
-                                    if data[ls..le] < data[cs..ce] {
-                                        is_sorted_rev = false;
-                                    }
+                                    let tie = data[ls..le].cmp(&data[cs..ce]);
+                                    ties_asc &= tie != Ordering::Greater;
+                                    ties_desc &= tie != Ordering::Less;
+                                    has_ties = true;
                                 }
                             }
                         }
@@ -2719,8 +2891,26 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                     // - reverse sorted + -r: output directly
                     // - forward sorted + -r: output in reverse
                     // - reverse sorted + no -r: output in reverse
-                    let already_correct = (is_sorted_fwd && !reverse) || (is_sorted_rev && reverse);
-                    let needs_reverse = (is_sorted_fwd && reverse) || (is_sorted_rev && !reverse);
+                    // Equal keys must end up in input order under -s, and
+                    // otherwise in line order, descending only for a global -r.
+                    let ties_kept = if config.stable {
+                        true
+                    } else if config.reverse {
+                        ties_desc
+                    } else {
+                        ties_asc
+                    };
+                    let ties_flipped = if config.stable {
+                        !has_ties
+                    } else if config.reverse {
+                        ties_asc
+                    } else {
+                        ties_desc
+                    };
+                    let already_correct =
+                        ((is_sorted_fwd && !reverse) || (is_sorted_rev && reverse)) && ties_kept;
+                    let needs_reverse =
+                        ((is_sorted_fwd && reverse) || (is_sorted_rev && !reverse)) && ties_flipped;
 
                     if already_correct || needs_reverse {
                         let forward = already_correct;
@@ -2858,8 +3048,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                         let (lb, rb) = unsafe { *off.add(b.3 as usize) };
                         unsafe {
                             let dp = data_addr as *const u8;
-                            std::slice::from_raw_parts(dp.add(la), ra - la)
-                                .cmp(std::slice::from_raw_parts(dp.add(lb), rb - lb))
+                            last_resort(
+                                std::slice::from_raw_parts(dp.add(la), ra - la),
+                                std::slice::from_raw_parts(dp.add(lb), rb - lb),
+                                config.reverse,
+                            )
                         }
                     } else {
                         Ordering::Equal
@@ -2998,10 +3191,14 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                             }
                         }
                     });
+                } else if stable {
+                    if num_lines > 10_000 {
+                        entries.par_sort_by(packed_cmp);
+                    } else {
+                        entries.sort_by(packed_cmp);
+                    }
                 } else if num_lines > 10_000 {
                     entries.par_sort_unstable_by(packed_cmp);
-                } else if stable {
-                    entries.sort_by(packed_cmp);
                 } else {
                     entries.sort_unstable_by(packed_cmp);
                 }
@@ -3017,8 +3214,7 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                         let mut pos = 0usize;
                         let mut prev: Option<u32> = None;
                         for j in 0..n {
-                            let idx = if reverse { n - 1 - j } else { j };
-                            let ent = &entries[idx];
+                            let ent = &entries[j];
                             let li = ent.3 as usize;
                             let (s, e) = offsets[li];
                             let len = e - s;
@@ -3058,13 +3254,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                         let bptr = buf.as_mut_ptr();
                         let mut pos = 0usize;
                         for j in 0..n {
-                            let actual = if reverse { n - 1 - j } else { j };
                             if j + 16 < n {
-                                let ahead_idx = if reverse { n - 1 - (j + 16) } else { j + 16 };
-                                let (ps, _) = offsets[entries[ahead_idx].3 as usize];
+                                let (ps, _) = offsets[entries[j + 16].3 as usize];
                                 prefetch_read(unsafe { dp.add(ps) });
                             }
-                            let ent = &entries[actual];
+                            let ent = &entries[j];
                             let (s, e) = offsets[ent.3 as usize];
                             let len = e - s;
                             unsafe {
@@ -3141,8 +3335,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                             let (la, ra) = offsets[a];
                             let (lb, rb) = offsets[b];
                             unsafe {
-                                std::slice::from_raw_parts(dp.add(la), ra - la)
-                                    .cmp(std::slice::from_raw_parts(dp.add(lb), rb - lb))
+                                last_resort(
+                                    std::slice::from_raw_parts(dp.add(la), ra - la),
+                                    std::slice::from_raw_parts(dp.add(lb), rb - lb),
+                                    config.reverse,
+                                )
                             }
                         } else {
                             ord
@@ -3182,8 +3379,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                             let (la, ra) = offsets[a];
                             let (lb, rb) = offsets[b];
                             unsafe {
-                                std::slice::from_raw_parts(dp.add(la), ra - la)
-                                    .cmp(std::slice::from_raw_parts(dp.add(lb), rb - lb))
+                                last_resort(
+                                    std::slice::from_raw_parts(dp.add(la), ra - la),
+                                    std::slice::from_raw_parts(dp.add(lb), rb - lb),
+                                    config.reverse,
+                                )
                             }
                         } else {
                             ord
@@ -3391,8 +3591,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                 let (sa, ea) = offsets[a];
                 let (sb, eb) = offsets[b];
                 unsafe {
-                    std::slice::from_raw_parts(dp.add(sa), ea - sa)
-                        .cmp(std::slice::from_raw_parts(dp.add(sb), eb - sb))
+                    last_resort(
+                        std::slice::from_raw_parts(dp.add(sa), ea - sa),
+                        std::slice::from_raw_parts(dp.add(sb), eb - sb),
+                        config.reverse,
+                    )
                 }
             } else {
                 Ordering::Equal
@@ -3463,7 +3666,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
                     return result;
                 }
             }
-            if !stable { la.cmp(lb) } else { Ordering::Equal }
+            if !stable {
+                last_resort(la, lb, config.reverse)
+            } else {
+                Ordering::Equal
+            }
         });
 
         write_sorted_output(data, &offsets, &indices, config, &mut writer, terminator)?;
@@ -3496,8 +3703,11 @@ pub fn sort_and_output(inputs: &[String], config: &SortConfig) -> io::Result<()>
             // Last-resort whole-line comparison for deterministic order (unless -s)
             if ord == Ordering::Equal && !stable {
                 unsafe {
-                    std::slice::from_raw_parts(dp.add(sa), ea - sa)
-                        .cmp(std::slice::from_raw_parts(dp.add(sb), eb - sb))
+                    last_resort(
+                        std::slice::from_raw_parts(dp.add(sa), ea - sa),
+                        std::slice::from_raw_parts(dp.add(sb), eb - sb),
+                        config.reverse,
+                    )
                 }
             } else {
                 ord
@@ -3541,3 +3751,73 @@ pub fn parse_buffer_size(s: &str) -> Result<usize, String> {
 
     Ok(base * multiplier)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_thread_limit_sources() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        // --parallel wins over the environment.
+        assert_eq!(
+            sort_thread_limit(Some(3), 16, env(&[("OMP_NUM_THREADS", "5")])),
+            Some(3)
+        );
+        assert_eq!(sort_thread_limit(None, 16, env(&[])), None);
+        assert_eq!(
+            sort_thread_limit(None, 16, env(&[("OMP_NUM_THREADS", "2,4")])),
+            Some(2)
+        );
+        assert_eq!(
+            sort_thread_limit(
+                None,
+                16,
+                env(&[("OMP_NUM_THREADS", "8"), ("OMP_THREAD_LIMIT", "3")])
+            ),
+            Some(3)
+        );
+        // Counts beyond the available CPUs are capped.
+        assert_eq!(sort_thread_limit(Some(100_000), 4, env(&[])), Some(4));
+        assert_eq!(
+            sort_thread_limit(None, 4, env(&[("OMP_NUM_THREADS", "1000")])),
+            Some(4)
+        );
+        assert_eq!(
+            sort_thread_limit(None, 4, env(&[("OMP_THREAD_LIMIT", "8")])),
+            Some(4)
+        );
+        // Invalid or zero counts fall back to the default pool.
+        assert_eq!(
+            sort_thread_limit(None, 16, env(&[("OMP_NUM_THREADS", "0")])),
+            None
+        );
+        assert_eq!(
+            sort_thread_limit(None, 16, env(&[("OMP_NUM_THREADS", "x")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sort_thread_pool_size() {
+        for n in [1, 3] {
+            // The pool adopts the building thread, so give each its own.
+            let sizes = std::thread::spawn(move || {
+                let pool = sort_thread_pool(n).unwrap();
+                (
+                    pool.current_num_threads(),
+                    pool.install(rayon::current_num_threads),
+                )
+            })
+            .join()
+            .unwrap();
+            assert_eq!(sizes, (n, n));
+        }
+    }
+}
@@ -133,36 +133,55 @@ pub struct KeyDef {
     pub end_field: usize,
     pub end_char: usize,
     pub opts: KeyOpts,
+    /// Whether 'b' was given on START and on END. Comparison applies
+    /// `opts.ignore_leading_blanks` to both; GNU's --debug warnings tell
+    /// the two positions apart.
+    pub start_blanks: bool,
+    pub end_blanks: bool,
 }
 
 impl KeyDef {
     /// Parse a KEYDEF string like "2,2n" or "1.3,1.5" or "3,3rn".
+    ///
+    /// Diagnostics follow GNU sort: a malformed number names the position
+    /// it was expected at, and a zero field or start character offset
+    /// rejects the whole spec. An end character offset of 0 means the end
+    /// of the field.
     pub fn parse(spec: &str) -> Result<KeyDef, String> {
-        let parts: Vec<&str> = spec.splitn(2, ',').collect();
-
-        let (start_field, start_char, start_opts) = parse_field_spec(parts[0])?;
-
-        let (end_field, end_char, end_opts) = if parts.len() > 1 {
-            parse_field_spec(parts[1])?
-        } else {
-            (0, 0, String::new())
-        };
-
+        let bad_spec = |msg: &str| format!("{}: invalid field specification '{}'", msg, spec);
         let mut opts = KeyOpts::default();
-        opts.parse_flags(&start_opts);
-        opts.parse_flags(&end_opts);
 
+        let (start_field, rest) = parse_field_count(spec, "invalid number at field start")?;
         if start_field == 0 {
-            return Err("field number is zero: invalid field specification".to_string());
+            return Err(bad_spec("field number is zero"));
         }
+        let (start_char, rest) = match rest.strip_prefix('.') {
+            Some(after) => {
+                let (n, rest) = parse_field_count(after, "invalid number after '.'")?;
+                if n == 0 {
+                    return Err(bad_spec("character offset is zero"));
+                }
+                (n, rest)
+            }
+            None => (0, rest),
+        };
+        let (mut rest, start_blanks) = parse_ordering(rest, &mut opts);
 
-        // GNU sort rejects character offset 0 in the START position only.
-        // A 0 in the end position is valid (treated as end of field).
-        if start_char == 0 && parts[0].contains('.') {
-            return Err(format!(
-                "character offset is zero: invalid field specification '{}'",
-                spec
-            ));
+        let (mut end_field, mut end_char, mut end_blanks) = (0, 0, false);
+        if let Some(after) = rest.strip_prefix(',') {
+            let (n, after) = parse_field_count(after, "invalid number after ','")?;
+            if n == 0 {
+                return Err(bad_spec("field number is zero"));
+            }
+            end_field = n;
+            rest = after;
+            if let Some(after) = rest.strip_prefix('.') {
+                (end_char, rest) = parse_field_count(after, "invalid number after '.'")?;
+            }
+            (rest, end_blanks) = parse_ordering(rest, &mut opts);
+        }
+        if !rest.is_empty() {
+            return Err(bad_spec("stray character in field spec"));
         }
 
         // Validate per-key option compatibility
@@ -174,50 +193,216 @@ impl KeyDef {
             end_field,
             end_char,
             opts,
+            start_blanks,
+            end_blanks,
         })
     }
 }
 
-/// Parse a single field spec like "2" or "1.3" or "2n" or "1.3bf".
-fn parse_field_spec(s: &str) -> Result<(usize, usize, String), String> {
-    let mut field_str = String::new();
-    let mut char_str = String::new();
-    let mut opts = String::new();
-    let mut in_char = false;
-
-    for c in s.chars() {
-        if c == '.' && !in_char && opts.is_empty() {
-            in_char = true;
-        } else if c.is_ascii_digit() && opts.is_empty() {
-            if in_char {
-                char_str.push(c);
-            } else {
-                field_str.push(c);
-            }
-        } else if c.is_ascii_alphabetic() {
-            opts.push(c);
-        } else {
-            return Err(format!("invalid character '{}' in key spec", c));
-        }
+/// Parse the decimal count at the start of `s`, returning it with the rest
+/// of the string. Like strtoumax, leading whitespace and a '+' are allowed
+/// and an oversized count saturates. `what` names the expected number in
+/// the diagnostic.
+fn parse_field_count<'a>(s: &'a str, what: &str) -> Result<(usize, &'a str), String> {
+    let trimmed = s.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    let unsigned = trimmed.strip_prefix('+').unwrap_or(trimmed);
+    let digits = unsigned.len()
+        - unsigned
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    if digits == 0 {
+        return Err(format!("{}: invalid count at start of '{}'", what, s));
     }
+    let n = unsigned[..digits].bytes().fold(0usize, |n, d| {
+        n.saturating_mul(10).saturating_add((d - b'0') as usize)
+    });
+    Ok((n, &unsigned[digits..]))
+}
 
-    let field = if field_str.is_empty() {
-        0
+/// Consume the ordering option letters at the start of `s` into `opts`,
+/// returning whatever follows them and whether 'b' was among them.
+fn parse_ordering<'a>(s: &'a str, opts: &mut KeyOpts) -> (&'a str, bool) {
+    let rest = s.trim_start_matches(|c: char| "bdfgiMhnRrV".contains(c));
+    let flags = &s[..s.len() - rest.len()];
+    opts.parse_flags(flags);
+    (rest, flags.contains('b'))
+}
+
+/// The warnings GNU `sort --debug` gives about the key definitions: keys
+/// that are empty or depend on leading blanks, numeric keys running across
+/// fields, and global options that no key uses. With no keys the global
+/// options form the only key. `utf8` selects the locale's quotation marks.
+pub fn key_warnings(
+    keys: &[KeyDef],
+    global: &KeyOpts,
+    separator: Option<u8>,
+    stable_or_unique: bool,
+    utf8: bool,
+) -> Vec<String> {
+    let quote = |c: char| {
+        if utf8 {
+            format!("\u{2018}{}\u{2019}", c)
+        } else {
+            format!("'{}'", c)
+        }
+    };
+    let gkey_only = keys.is_empty();
+    let default_key;
+    let keys = if gkey_only {
+        default_key = KeyDef {
+            start_field: 1,
+            start_char: 0,
+            end_field: 0,
+            end_char: 0,
+            opts: global.clone(),
+            start_blanks: global.ignore_leading_blanks,
+            end_blanks: global.ignore_leading_blanks,
+        };
+        std::slice::from_ref(&default_key)
     } else {
-        field_str
-            .parse::<usize>()
-            .map_err(|_| "invalid field number".to_string())?
+        keys
     };
 
-    let char_pos = if char_str.is_empty() {
-        0
-    } else {
-        char_str
-            .parse::<usize>()
-            .map_err(|_| "invalid character position".to_string())?
+    let mut warnings = Vec::new();
+    let mut unused = global.clone();
+    let (mut unused_start_blanks, mut unused_end_blanks) =
+        (global.ignore_leading_blanks, global.ignore_leading_blanks);
+    // Like GNU, -d and -i pick one table of ignored characters, -d winning.
+    let ignore_table = |o: &KeyOpts| {
+        if o.dictionary_order {
+            Some('d')
+        } else if o.ignore_nonprinting {
+            Some('i')
+        } else {
+            None
+        }
     };
+    let mut unused_ignore = ignore_table(global);
+    let (mut basic_numeric, mut general_numeric) = (false, false);
+    let (mut basic_span, mut general_span) = (false, false);
+    for (i, key) in keys.iter().enumerate() {
+        let keynum = i + 1;
+        // A key with no options of its own inherits the global ones.
+        let (opts, start_blanks, end_blanks) = if key.opts.has_any_option() {
+            (&key.opts, key.start_blanks, key.end_blanks)
+        } else {
+            (
+                global,
+                global.ignore_leading_blanks,
+                global.ignore_leading_blanks,
+            )
+        };
+        let numeric = opts.numeric || opts.general_numeric || opts.human_numeric;
+        if numeric {
+            if opts.general_numeric {
+                general_numeric = true;
+            } else {
+                basic_numeric = true;
+            }
+        }
+
+        let zero_width = key.end_field != 0 && key.end_field < key.start_field;
+        if zero_width {
+            warnings.push(format!("key {} has zero width and will be ignored", keynum));
+        }
+
+        let implicit_skip = numeric || opts.month;
+        let line_offset = key.end_field == 1 && key.end_char != 0;
+        if !zero_width
+            && !gkey_only
+            && separator.is_none()
+            && !line_offset
+            && ((!start_blanks && (!implicit_skip || key.start_char > 1))
+                || (!end_blanks && key.end_char != 0))
+        {
+            warnings.push(format!(
+                "leading blanks are significant in key {}; consider also specifying 'b'",
+                keynum
+            ));
+        }
+
+        if !gkey_only && numeric && (key.end_field == 0 || key.start_field < key.end_field) {
+            warnings.push(format!(
+                "key {} is numeric and spans multiple fields",
+                keynum
+            ));
+            if opts.general_numeric {
+                general_span = true;
+            } else {
+                basic_span = true;
+            }
+        }
 
-    Ok((field, char_pos, opts))
+        unused_start_blanks &= !start_blanks;
+        unused_end_blanks &= !end_blanks;
+        if unused_ignore == ignore_table(opts) {
+            unused_ignore = None;
+        }
+        unused.ignore_case &= !opts.ignore_case;
+        unused.general_numeric &= !opts.general_numeric;
+        unused.human_numeric &= !opts.human_numeric;
+        unused.month &= !opts.month;
+        unused.numeric &= !opts.numeric;
+        unused.random &= !opts.random;
+        unused.reverse &= !opts.reverse;
+        unused.version &= !opts.version;
+    }
+
+    let mut number_locale_warned = false;
+    if basic_span || general_span {
+        match separator {
+            Some(b'.') => {
+                warnings.push(format!(
+                    "field separator {} is treated as a decimal point in numbers",
+                    quote('.')
+                ));
+                number_locale_warned = true;
+            }
+            Some(b'-') => warnings.push(format!(
+                "field separator {} is treated as a minus sign in numbers",
+                quote('-')
+            )),
+            Some(b'+') if general_span => warnings.push(format!(
+                "field separator {} is treated as a plus sign in numbers",
+                quote('+')
+            )),
+            _ => {}
+        }
+    }
+    if (basic_numeric || general_numeric) && !number_locale_warned {
+        warnings.push(format!(
+            "{}numbers use {} as a decimal point in this locale",
+            if separator == Some(b'.') { "" } else { "note " },
+            quote('.')
+        ));
+    }
+
+    let ignored: String = [
+        (unused_start_blanks || unused_end_blanks, 'b'),
+        (unused_ignore == Some('d'), 'd'),
+        (unused.ignore_case, 'f'),
+        (unused.general_numeric, 'g'),
+        (unused.human_numeric, 'h'),
+        (unused_ignore == Some('i'), 'i'),
+        (unused.month, 'M'),
+        (unused.numeric, 'n'),
+        (unused.random, 'R'),
+        (unused.reverse && stable_or_unique, 'r'),
+        (unused.version, 'V'),
+    ]
+    .iter()
+    .filter(|&&(set, _)| set)
+    .map(|&(_, c)| c)
+    .collect();
+    match ignored.len() {
+        0 => {}
+        1 => warnings.push(format!("option '-{}' is ignored", ignored)),
+        _ => warnings.push(format!("options '-{}' are ignored", ignored)),
+    }
+    if unused.reverse && !stable_or_unique {
+        warnings.push("option '-r' only applies to last-resort comparison".to_string());
+    }
+    warnings
 }
 
 /// Find the byte range of the Nth field (0-indexed) in a line.
@@ -382,27 +567,31 @@ pub fn extract_key_z<'a>(
     zero_terminated: bool,
 ) -> &'a [u8] {
     let sf = key.start_field.saturating_sub(1);
-    let (sf_start, sf_end) = find_nth_field_z(line, sf, separator, zero_terminated);
+    let (sf_start, _) = find_nth_field_z(line, sf, separator, zero_terminated);
 
     if sf_start >= line.len() {
         return b"";
     }
 
-    let blank_fn: fn(u8) -> bool = if zero_terminated && separator.is_none() {
+    // -b skips newlines too in -z mode, even with an explicit -t separator.
+    let blank_fn: fn(u8) -> bool = if zero_terminated {
         is_blank_z
     } else {
         is_blank
     };
 
+    // Like GNU, blank skipping and character offsets are bounded by the end
+    // of the line rather than the end of the field, so `-k1.3` on "a bc"
+    // starts at 'b'. Without -t a field includes its leading blanks.
     let start_byte = if key.start_char > 0 {
         let effective_start = if ignore_leading_blanks {
-            skip_blanks_from_fn(line, sf_start, sf_end, blank_fn)
+            skip_blanks_from_fn(line, sf_start, line.len(), blank_fn)
         } else {
             sf_start
         };
-        let field_len = sf_end - effective_start;
-        let char_offset = (key.start_char - 1).min(field_len);
-        effective_start + char_offset
+        effective_start
+            .saturating_add(key.start_char - 1)
+            .min(line.len())
     } else {
         sf_start
     };
@@ -412,13 +601,11 @@ pub fn extract_key_z<'a>(
         let (ef_start, ef_end) = find_nth_field_z(line, ef, separator, zero_terminated);
         if key.end_char > 0 {
             let effective_start = if ignore_leading_blanks {
-                skip_blanks_from_fn(line, ef_start, ef_end, blank_fn)
+                skip_blanks_from_fn(line, ef_start, line.len(), blank_fn)
             } else {
                 ef_start
             };
-            let field_len = ef_end - effective_start;
-            let char_offset = key.end_char.min(field_len);
-            effective_start + char_offset
+            effective_start.saturating_add(key.end_char).min(line.len())
         } else {
             ef_end
         }
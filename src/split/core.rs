@@ -6,11 +6,11 @@ use std::process::{Command, Stdio};
 /// Suffix type for output filenames.
 #[derive(Clone, Debug, PartialEq)]
 pub enum SuffixType {
-    /// Alphabetic suffixes: aa, ab, ..., zz, aaa, ...
+    /// Alphabetic suffixes: aa, ab, ..., yz, zaaa, ...
     Alphabetic,
-    /// Numeric suffixes: 00, 01, ..., 99, 000, ...
+    /// Numeric suffixes from a start value: 00, 01, ..., 89, 9000, ...
     Numeric(u64),
-    /// Hexadecimal suffixes: 00, 01, ..., ff, 000, ...
+    /// Hexadecimal suffixes from a start value: 00, 01, ..., ef, f000, ...
     Hex(u64),
 }
 
@@ -43,6 +43,8 @@ pub struct SplitConfig {
     pub mode: SplitMode,
     pub suffix_type: SuffixType,
     pub suffix_length: usize,
+    /// Widen suffixes when they run out instead of failing (no -a or FROM given).
+    pub suffix_auto: bool,
     pub additional_suffix: String,
     pub prefix: String,
     pub elide_empty: bool,
@@ -57,6 +59,7 @@ impl Default for SplitConfig {
             mode: SplitMode::Lines(1000),
             suffix_type: SuffixType::Alphabetic,
             suffix_length: 2,
+            suffix_auto: true,
             additional_suffix: String::new(),
             prefix: "x".to_string(),
             elide_empty: false,
@@ -150,17 +153,67 @@ pub fn generate_suffix(index: u64, suffix_type: &SuffixType, suffix_length: usiz
     }
 }
 
+/// Number of distinct symbols in each suffix position.
+pub fn suffix_base(suffix_type: &SuffixType) -> u64 {
+    match suffix_type {
+        SuffixType::Alphabetic => 26,
+        SuffixType::Numeric(_) => 10,
+        SuffixType::Hex(_) => 16,
+    }
+}
+
 /// Compute the maximum number of chunks supported for a given suffix configuration.
 pub fn max_chunks(suffix_type: &SuffixType, suffix_length: usize) -> u64 {
+    let total = suffix_base(suffix_type).saturating_pow(suffix_length as u32);
     match suffix_type {
-        SuffixType::Alphabetic => 26u64.saturating_pow(suffix_length as u32),
-        SuffixType::Numeric(_) | SuffixType::Hex(_) => 10u64.saturating_pow(suffix_length as u32),
+        SuffixType::Alphabetic => total,
+        SuffixType::Numeric(start) | SuffixType::Hex(start) => total.saturating_sub(*start),
+    }
+}
+
+/// Chunk limit for a configuration; auto-widening suffixes never run out.
+fn chunk_limit(config: &SplitConfig) -> u64 {
+    if config.suffix_auto {
+        u64::MAX
+    } else {
+        max_chunks(&config.suffix_type, config.suffix_length)
+    }
+}
+
+/// Generate the suffix for a chunk index, widening it like GNU split when
+/// auto-widening is on: once the first symbol would become the last one in
+/// the alphabet, that symbol moves into the prefix and the suffix grows by
+/// one, so "yz" is followed by "zaaa" and "89" by "9000".
+fn chunk_suffix(config: &SplitConfig, index: u64) -> String {
+    if !config.suffix_auto || config.suffix_length == 0 {
+        return generate_suffix(index, &config.suffix_type, config.suffix_length);
     }
+    let base = suffix_base(&config.suffix_type);
+    let last = match config.suffix_type {
+        SuffixType::Alphabetic => 'z',
+        SuffixType::Numeric(_) => '9',
+        SuffixType::Hex(_) => 'f',
+    };
+    let mut index = index;
+    let mut width = config.suffix_length;
+    let mut widened = String::new();
+    loop {
+        // Suffixes of this width whose first symbol is not the last one.
+        let count = (base - 1).saturating_mul(base.saturating_pow(width as u32 - 1));
+        if index < count {
+            break;
+        }
+        index -= count;
+        width += 1;
+        widened.push(last);
+    }
+    widened.push_str(&generate_suffix(index, &config.suffix_type, width));
+    widened
 }
 
 /// Build the output file path for a given chunk index.
-fn output_path(config: &SplitConfig, index: u64) -> String {
-    let suffix = generate_suffix(index, &config.suffix_type, config.suffix_length);
+pub fn output_path(config: &SplitConfig, index: u64) -> String {
+    let suffix = chunk_suffix(config, index);
     format!("{}{}{}", config.prefix, suffix, config.additional_suffix)
 }
 
@@ -221,11 +274,18 @@ impl FilterChunkWriter {
 }
 
 impl Write for FilterChunkWriter {
+    /// Like GNU, data for a filter that has closed its input is discarded.
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         if let Some(ref mut stdin) = self.child.stdin {
-            stdin.write(buf)
+            match stdin.write(buf) {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                    self.child.stdin.take();
+                    Ok(buf.len())
+                }
+                r => r,
+            }
         } else {
-            Err(io::Error::new(io::ErrorKind::BrokenPipe, "stdin closed"))
+            Ok(buf.len())
         }
     }
 
@@ -253,12 +313,25 @@ impl ChunkWriter for FilterChunkWriter {
     }
 }
 
+/// For `--verbose`, report on stdout an output file about to be opened, or
+/// the `$FILE` name a filter is about to be run with.
+fn announce(config: &SplitConfig, path: &str) -> io::Result<()> {
+    if !config.verbose {
+        return Ok(());
+    }
+    let mut out = io::stdout().lock();
+    if config.filter.is_some() {
+        writeln!(out, "executing with FILE={}", path)?;
+    } else {
+        writeln!(out, "creating file '{}'", path)?;
+    }
+    out.flush()
+}
+
 /// Create a chunk writer for the given chunk index.
 fn create_writer(config: &SplitConfig, index: u64) -> io::Result<Box<dyn ChunkWriter>> {
     let path = output_path(config, index);
-    if config.verbose {
-        eprintln!("creating file '{}'", path);
-    }
+    announce(config, &path)?;
     if let Some(ref filter_cmd) = config.filter {
         Ok(Box::new(FilterChunkWriter::create(filter_cmd, &path)?))
     } else {
@@ -274,7 +347,7 @@ fn split_by_lines(
     config: &SplitConfig,
     lines_per_chunk: u64,
 ) -> io::Result<()> {
-    let limit = max_chunks(&config.suffix_type, config.suffix_length);
+    let limit = chunk_limit(config);
     let mut chunk_index: u64 = 0;
     let mut lines_in_chunk: u64 = 0;
     let mut writer: Option<Box<dyn ChunkWriter>> = None;
@@ -351,7 +424,7 @@ fn split_by_bytes(
     config: &SplitConfig,
     bytes_per_chunk: u64,
 ) -> io::Result<()> {
-    let limit = max_chunks(&config.suffix_type, config.suffix_length);
+    let limit = chunk_limit(config);
     let mut chunk_index: u64 = 0;
     let mut bytes_in_chunk: u64 = 0;
     let mut writer: Option<Box<dyn ChunkWriter>> = None;
@@ -417,7 +490,7 @@ fn split_by_line_bytes(
     config: &SplitConfig,
     max_bytes: u64,
 ) -> io::Result<()> {
-    let limit = max_chunks(&config.suffix_type, config.suffix_length);
+    let limit = chunk_limit(config);
     let max = max_bytes as usize;
     let sep = config.separator;
 
@@ -473,7 +546,7 @@ fn split_by_line_bytes(
 /// Split input into exactly N chunks by byte count.
 /// Reads the whole file to determine size, then distributes bytes evenly.
 fn split_by_number(input_path: &str, config: &SplitConfig, n_chunks: u64) -> io::Result<()> {
-    let limit = max_chunks(&config.suffix_type, config.suffix_length);
+    let limit = chunk_limit(config);
     if n_chunks > limit {
         return Err(io::Error::other("output file suffixes exhausted"));
     }
@@ -570,15 +643,11 @@ fn read_input_data(input_path: &str) -> io::Result<Vec<u8>> {
 fn compute_line_chunk_boundaries(data: &[u8], n_chunks: u64, sep: u8) -> Vec<u64> {
     let total = data.len() as u64;
     let base_chunk_size = total / n_chunks;
-    let remainder = total % n_chunks;
 
-    // Precompute target end boundaries for each chunk
-    let mut boundaries = Vec::with_capacity(n_chunks as usize);
-    let mut target_end: u64 = 0;
-    for i in 0..n_chunks {
-        target_end += base_chunk_size + if i < remainder { 1 } else { 0 };
-        boundaries.push(target_end);
-    }
+    // Precompute target end boundaries for each chunk: multiples of
+    // total/N, with the last chunk taking whatever remains.
+    let mut boundaries: Vec<u64> = (1..n_chunks).map(|i| i * base_chunk_size).collect();
+    boundaries.push(total);
 
     // Now read lines and assign to chunks
     let mut chunk_ends = Vec::with_capacity(n_chunks as usize);
@@ -714,9 +783,14 @@ fn split_by_round_robin(input_path: &str, config: &SplitConfig, n_chunks: u64) -
 }
 
 /// Extract Kth round-robin chunk of N (r/K/N format). Output goes to stdout.
-fn split_by_round_robin_extract(input_path: &str, k: u64, n: u64) -> io::Result<()> {
+fn split_by_round_robin_extract(
+    input_path: &str,
+    config: &SplitConfig,
+    k: u64,
+    n: u64,
+) -> io::Result<()> {
     let data = read_input_data(input_path)?;
-    let sep = b'\n';
+    let sep = config.separator;
 
     let stdout = io::stdout();
     let mut out = stdout.lock();
@@ -746,7 +820,7 @@ fn split_lines_preloaded(
     config: &SplitConfig,
     lines_per_chunk: u64,
 ) -> io::Result<()> {
-    let limit = max_chunks(&config.suffix_type, config.suffix_length);
+    let limit = chunk_limit(config);
     let sep = config.separator;
     let mut chunk_index: u64 = 0;
     let mut chunk_start: usize = 0;
@@ -760,9 +834,7 @@ fn split_lines_preloaded(
                 return Err(io::Error::other("output file suffixes exhausted"));
             }
             let path = output_path(config, chunk_index);
-            if config.verbose {
-                eprintln!("creating file '{}'", path);
-            }
+            announce(config, &path)?;
             let mut file = File::create(&path)?;
             file.write_all(&data[chunk_start..chunk_end])?;
             chunk_start = chunk_end;
@@ -777,9 +849,7 @@ fn split_lines_preloaded(
             return Err(io::Error::other("output file suffixes exhausted"));
         }
         let path = output_path(config, chunk_index);
-        if config.verbose {
-            eprintln!("creating file '{}'", path);
-        }
+        announce(config, &path)?;
         let mut file = File::create(&path)?;
         file.write_all(&data[chunk_start..])?;
     }
@@ -807,7 +877,7 @@ pub fn split_file(input_path: &str, config: &SplitConfig) -> io::Result<()> {
         return split_by_round_robin(input_path, config, n);
     }
     if let SplitMode::RoundRobinExtract(k, n) = config.mode {
-        return split_by_round_robin_extract(input_path, k, n);
+        return split_by_round_robin_extract(input_path, config, k, n);
     }
 
     // Fast path: read+memchr line splitting for regular files (no filter).
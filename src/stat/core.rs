@@ -9,6 +9,8 @@ pub struct StatConfig {
     pub format: Option<String>,
     pub printf_format: Option<String>,
     pub terse: bool,
+    /// Quoting style for `%N`.
+    pub quoting_style: QuotingStyle,
 }
 
 /// Extract the fsid value from a libc::fsid_t as a u64.
@@ -113,12 +115,12 @@ fn stat_stdin(config: &StatConfig) -> Result<String, io::Error> {
             display_path,
             &meta,
             &st,
-            config.dereference,
+            config,
         ));
     }
 
     if let Some(ref fmt) = config.format {
-        let result = format_file_specifiers(fmt, display_path, &meta, &st, config.dereference);
+        let result = format_file_specifiers(fmt, display_path, &meta, &st, config);
         return Ok(result + "\n");
     }
 
@@ -153,17 +155,11 @@ fn stat_regular(path: &str, config: &StatConfig) -> Result<String, io::Error> {
 
     if let Some(ref fmt) = config.printf_format {
         let expanded = expand_backslash_escapes(fmt);
-        return Ok(format_file_specifiers(
-            &expanded,
-            path,
-            &meta,
-            &st,
-            config.dereference,
-        ));
+        return Ok(format_file_specifiers(&expanded, path, &meta, &st, config));
     }
 
     if let Some(ref fmt) = config.format {
-        let result = format_file_specifiers(fmt, path, &meta, &st, config.dereference);
+        let result = format_file_specifiers(fmt, path, &meta, &st, config);
         return Ok(result + "\n");
     }
 
@@ -209,7 +205,7 @@ fn format_file_default(
     dereference: bool,
 ) -> String {
     let mode = meta.mode();
-    let file_type_str = file_type_label(mode);
+    let file_type_str = file_type_name(meta);
     let perms_str = mode_to_human(mode);
     let uid = meta.uid();
     let gid = meta.gid();
@@ -401,8 +397,9 @@ fn format_file_specifiers(
     path: &str,
     meta: &std::fs::Metadata,
     st: &libc::stat,
-    dereference: bool,
+    config: &StatConfig,
 ) -> String {
+    let dereference = config.dereference;
     let mut result = String::new();
     let chars: Vec<char> = fmt.chars().collect();
     let mut i = 0;
@@ -476,7 +473,7 @@ fn format_file_specifiers(
                     result.push_str(&format!("{:x}", meta.mode()));
                 }
                 'F' => {
-                    result.push_str(file_type_label(meta.mode()));
+                    result.push_str(file_type_name(meta));
                 }
                 'g' => {
                     result.push_str(&meta.gid().to_string());
@@ -497,22 +494,27 @@ fn format_file_specifiers(
                     result.push_str(path);
                 }
                 'N' => {
-                    if meta.file_type().is_symlink() {
-                        match std::fs::read_link(path) {
-                            Ok(target) => {
-                                result.push_str(&format!("'{}' -> '{}'", path, target.display()));
-                            }
-                            Err(_) => {
-                                result.push_str(&format!("'{}'", path));
-                            }
-                        }
-                    } else {
-                        result.push_str(&format!("'{}'", path));
+                    result.push_str(&quote_name_style(path, config.quoting_style));
+                    // With -L `meta` describes the target, so no arrow is shown
+                    if meta.file_type().is_symlink()
+                        && let Ok(target) = std::fs::read_link(path)
+                    {
+                        result.push_str(" -> ");
+                        result.push_str(&quote_name_style(
+                            &target.to_string_lossy(),
+                            config.quoting_style,
+                        ));
                     }
                 }
                 'o' => {
                     result.push_str(&meta.blksize().to_string());
                 }
+                'r' => {
+                    result.push_str(&meta.rdev().to_string());
+                }
+                'R' => {
+                    result.push_str(&format!("{:x}", meta.rdev()));
+                }
                 's' => {
                     result.push_str(&meta.size().to_string());
                 }
@@ -719,6 +721,257 @@ pub fn file_type_label(mode: u32) -> &'static str {
     }
 }
 
+/// File type as reported by `%F`: like `file_type_label`, but GNU calls a
+/// zero-length regular file a "regular empty file".
+fn file_type_name(meta: &std::fs::Metadata) -> &'static str {
+    if meta.file_type().is_file() && meta.size() == 0 {
+        "regular empty file"
+    } else {
+        file_type_label(meta.mode())
+    }
+}
+
+/// How `%N` quotes file names: GNU's quoting styles, chosen with
+/// `--quoting-style` or the `QUOTING_STYLE` environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuotingStyle {
+    Literal,
+    Shell,
+    ShellAlways,
+    ShellEscape,
+    #[default]
+    ShellEscapeAlways,
+    C,
+    CMaybe,
+    Escape,
+    Locale,
+    Clocale,
+}
+
+const QUOTING_STYLES: &[(&str, QuotingStyle)] = &[
+    ("literal", QuotingStyle::Literal),
+    ("shell", QuotingStyle::Shell),
+    ("shell-always", QuotingStyle::ShellAlways),
+    ("shell-escape", QuotingStyle::ShellEscape),
+    ("shell-escape-always", QuotingStyle::ShellEscapeAlways),
+    ("c", QuotingStyle::C),
+    ("c-maybe", QuotingStyle::CMaybe),
+    ("escape", QuotingStyle::Escape),
+    ("locale", QuotingStyle::Locale),
+    ("clocale", QuotingStyle::Clocale),
+];
+
+/// Parse a quoting style name.  Like GNU argmatch, unambiguous abbreviations
+/// are accepted.
+pub fn parse_quoting_style(s: &str) -> Result<QuotingStyle, String> {
+    if let Some(&(_, style)) = QUOTING_STYLES.iter().find(|(name, _)| *name == s) {
+        return Ok(style);
+    }
+    let mut matches = QUOTING_STYLES
+        .iter()
+        .filter(|(name, _)| name.starts_with(s));
+    match (matches.next(), matches.next()) {
+        (Some(&(_, style)), None) => Ok(style),
+        (first, _) => {
+            let mut msg = format!(
+                "{} argument '{}' for '--quoting-style'\nValid arguments are:",
+                if first.is_some() {
+                    "ambiguous"
+                } else {
+                    "invalid"
+                },
+                s
+            );
+            for (name, _) in QUOTING_STYLES {
+                msg.push_str(&format!("\n  - '{}'", name));
+            }
+            Err(msg)
+        }
+    }
+}
+
+/// Quote a file name like GNU's shell-escape-always style, as used by `%N`
+/// by default and in error messages: names are always quoted, a name holding
+/// a single quote but nothing special to the shell goes in double quotes, and
+/// control or non-ASCII bytes become `$'...'` escape segments.
+pub fn quote_name(name: &str) -> String {
+    quote_name_style(name, QuotingStyle::ShellEscapeAlways)
+}
+
+/// Quote a file name in the given style, following GNU quotearg in the C
+/// locale, where bytes outside printable ASCII are unprintable.
+pub fn quote_name_style(name: &str, style: QuotingStyle) -> String {
+    String::from_utf8_lossy(&quotearg(name.as_bytes(), style)).into_owned()
+}
+
+fn quotearg(arg: &[u8], style: QuotingStyle) -> Vec<u8> {
+    use QuotingStyle::*;
+    // (any shell style, backslash escapes, elide outer quotes, quote)
+    let (shell, backslash, elide, quote): (bool, bool, bool, &[u8]) = match style {
+        Literal => (false, false, false, b""),
+        Shell => (true, false, true, b"'"),
+        ShellAlways => (true, false, false, b"'"),
+        ShellEscape => (true, true, true, b"'"),
+        ShellEscapeAlways => (true, true, false, b"'"),
+        C | Clocale => (false, true, false, b"\""),
+        CMaybe => (false, true, true, b"\""),
+        Escape => (false, true, false, b""),
+        Locale => (false, true, false, b"'"),
+    };
+    // A name that cannot go unquoted is redone in the matching quoted style.
+    let forced = match (shell, backslash) {
+        (true, true) => ShellEscapeAlways,
+        (true, false) => ShellAlways,
+        _ => C,
+    };
+    if shell && elide && arg.is_empty() {
+        return quotearg(arg, forced);
+    }
+
+    let mut out = Vec::with_capacity(arg.len() + 2);
+    if !elide {
+        out.extend_from_slice(quote);
+    }
+    let mut pending_shell_escape_end = false;
+    let mut encountered_single_quote = false;
+    let mut all_compat = true;
+
+    for (i, &c) in arg.iter().enumerate() {
+        let is_right_quote =
+            backslash && !shell && !quote.is_empty() && arg[i..].starts_with(quote);
+        if is_right_quote && elide {
+            return quotearg(arg, forced);
+        }
+        let mut compat = false;
+        // Some(letter) writes a backslash escape, None the byte itself.
+        let esc = match c {
+            0x07 | 0x08 | 0x0b | 0x0c | b'\n' | b'\r' | b'\t' => {
+                let letter = match c {
+                    0x07 => b'a',
+                    0x08 => b'b',
+                    0x0b => b'v',
+                    0x0c => b'f',
+                    b'\n' => b'n',
+                    b'\r' => b'r',
+                    _ => b't',
+                };
+                if shell && elide && matches!(c, b'\n' | b'\r' | b'\t') {
+                    return quotearg(arg, forced);
+                }
+                backslash.then_some(letter)
+            }
+            b'\\' => {
+                if shell {
+                    if elide {
+                        return quotearg(arg, forced);
+                    }
+                    None
+                } else {
+                    backslash.then_some(b'\\')
+                }
+            }
+            b'{' | b'}' if arg.len() != 1 => None,
+            b'#' | b'~' if i != 0 => None,
+            b'{' | b'}' | b'#' | b'~' | b' ' | b'!' | b'"' | b'$' | b'&' | b'(' | b')' | b'*'
+            | b';' | b'<' | b'=' | b'>' | b'[' | b'^' | b'`' | b'|' => {
+                compat = matches!(c, b'{' | b'}' | b'#' | b'~' | b' ');
+                if shell && elide {
+                    return quotearg(arg, forced);
+                }
+                None
+            }
+            b'?' => {
+                if shell && elide {
+                    return quotearg(arg, forced);
+                }
+                None
+            }
+            b'\'' => {
+                encountered_single_quote = true;
+                compat = true;
+                if shell {
+                    if elide {
+                        return quotearg(arg, forced);
+                    }
+                    out.extend_from_slice(b"'\\'");
+                    pending_shell_escape_end = false;
+                }
+                None
+            }
+            b'%'
+            | b'+'
+            | b','
+            | b'-'
+            | b'.'
+            | b'/'
+            | b'0'..=b'9'
+            | b':'
+            | b'A'..=b'Z'
+            | b']'
+            | b'_'
+            | b'a'..=b'z' => {
+                compat = true;
+                None
+            }
+            _ => {
+                let printable = (0x20..0x7f).contains(&c);
+                compat = printable;
+                if backslash && !printable {
+                    if elide {
+                        return quotearg(arg, forced);
+                    }
+                    if shell && !pending_shell_escape_end {
+                        out.extend_from_slice(b"'$'");
+                        pending_shell_escape_end = true;
+                    }
+                    out.extend_from_slice(&[
+                        b'\\',
+                        b'0' + (c >> 6),
+                        b'0' + ((c >> 3) & 7),
+                        b'0' + (c & 7),
+                    ]);
+                    all_compat &= compat;
+                    continue;
+                }
+                None
+            }
+        };
+        all_compat &= compat;
+
+        match esc {
+            Some(letter) => {
+                if elide {
+                    return quotearg(arg, forced);
+                }
+                if shell && !pending_shell_escape_end {
+                    out.extend_from_slice(b"'$'");
+                    pending_shell_escape_end = true;
+                }
+                out.push(b'\\');
+                out.push(letter);
+            }
+            None => {
+                if pending_shell_escape_end {
+                    out.extend_from_slice(b"''");
+                    pending_shell_escape_end = false;
+                }
+                if is_right_quote {
+                    out.push(b'\\');
+                }
+                out.push(c);
+            }
+        }
+    }
+
+    if shell && !elide && encountered_single_quote && all_compat {
+        return quotearg(arg, C);
+    }
+    if !elide {
+        out.extend_from_slice(quote);
+    }
+    out
+}
+
 /// Format a Unix timestamp as `YYYY-MM-DD HH:MM:SS.NNNNNNNNN +ZZZZ`.
 fn format_timestamp(secs: i64, nsec: i64) -> String {
     // Use libc localtime_r for timezone-aware formatting
@@ -1,12 +1,31 @@
 /// stdbuf -- run a command with modified buffering for its standard streams
 ///
 /// Sets environment variables _STDBUF_I, _STDBUF_O, _STDBUF_E to communicate
-/// the desired buffering modes to the child process. A full implementation would
-/// use LD_PRELOAD with a shared library that reads these variables and calls
-/// setvbuf(). This implementation sets the environment and execs the command.
+/// the desired buffering modes to the child process, and injects the
+/// libstdbuf.so shim via LD_PRELOAD. The shim runs inside the child before
+/// main(), reads these variables and calls setvbuf() on the standard streams.
 use std::io;
+use std::path::{Path, PathBuf};
 use std::process;
 
+/// File name of the LD_PRELOAD shim that applies the buffering modes.
+pub const LIBSTDBUF_NAME: &str = "libstdbuf.so";
+
+/// The shim build.rs compiled from libstdbuf.c, if it could.
+pub const BUILT_LIBSTDBUF: Option<&str> = option_env!("FSTDBUF_LIBSTDBUF");
+
+/// Directories searched for the shim, relative to the directory holding the
+/// stdbuf binary. The first entry covers a shim installed side by side.
+const LIBSTDBUF_RELATIVE_DIRS: &[&str] = &[".", "../libexec/fcoreutils", "../lib/fcoreutils"];
+
+/// GNU coreutils install locations. The GNU shim reads the same _STDBUF_*
+/// variables, so it is used when no fcoreutils shim is installed.
+const LIBSTDBUF_SYSTEM_DIRS: &[&str] = &[
+    "/usr/local/libexec/coreutils",
+    "/usr/libexec/coreutils",
+    "/usr/lib/coreutils",
+];
+
 /// Buffering mode specification.
 #[derive(Clone, Debug)]
 pub enum BufferMode {
@@ -35,6 +54,8 @@ pub struct StdbufConfig {
     pub input: Option<BufferMode>,
     pub output: Option<BufferMode>,
     pub error: Option<BufferMode>,
+    /// Path to the libstdbuf.so shim injected via LD_PRELOAD.
+    pub libstdbuf: PathBuf,
     pub command: String,
     pub args: Vec<String>,
 }
@@ -100,10 +121,43 @@ pub fn parse_buffer_mode(s: &str) -> Result<BufferMode, String> {
     }
 }
 
+/// Locate the libstdbuf.so shim.
+///
+/// The shim built with this binary is tried first, then the directory of
+/// `exe` (symlinks resolved) and the GNU coreutils install locations.
+/// Returns None if no shim is found.
+pub fn find_libstdbuf(exe: &Path) -> Option<PathBuf> {
+    let built = BUILT_LIBSTDBUF.map(PathBuf::from);
+    let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+    let installed = exe
+        .parent()
+        .into_iter()
+        .flat_map(|dir| LIBSTDBUF_RELATIVE_DIRS.iter().map(move |rel| dir.join(rel)))
+        .chain(LIBSTDBUF_SYSTEM_DIRS.iter().map(PathBuf::from))
+        .map(|dir| dir.join(LIBSTDBUF_NAME));
+    built
+        .into_iter()
+        .chain(installed)
+        .find(|path| path.is_file())
+}
+
+/// Build the LD_PRELOAD value: any existing preloads are kept ahead of the
+/// shim, matching GNU stdbuf.
+fn preload_value(libstdbuf: &Path) -> std::ffi::OsString {
+    let mut value = std::ffi::OsString::new();
+    if let Some(old) = std::env::var_os("LD_PRELOAD").filter(|v| !v.is_empty()) {
+        value.push(old);
+        value.push(":");
+    }
+    value.push(libstdbuf);
+    value
+}
+
 /// Run the stdbuf command: set environment variables and exec the child process.
 pub fn run_stdbuf(config: &StdbufConfig) -> io::Result<()> {
     let mut cmd = process::Command::new(&config.command);
     cmd.args(&config.args);
+    cmd.env("LD_PRELOAD", preload_value(&config.libstdbuf));
 
     if let Some(ref mode) = config.input {
         cmd.env("_STDBUF_I", mode.to_env_value());
@@ -0,0 +1,71 @@
+/*
+ * libstdbuf.so -- LD_PRELOAD shim used by fstdbuf
+ *
+ * Runs before the program's main() and applies the buffering modes that
+ * stdbuf passes in _STDBUF_I, _STDBUF_O and _STDBUF_E to stdin, stdout and
+ * stderr. A mode is "0" (unbuffered), "L" (line buffered) or a buffer size
+ * in bytes; stdbuf has already validated and expanded it.
+ *
+ * Built by build.rs with the system C compiler and placed next to the
+ * fstdbuf binary, where stdbuf::find_libstdbuf looks for it first.
+ */
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static const char *stream_name(FILE *stream) {
+    if (stream == stdin)
+        return "stdin";
+    if (stream == stdout)
+        return "stdout";
+    return "stderr";
+}
+
+static void apply_mode(FILE *stream, const char *mode) {
+    char *buf = NULL;
+    size_t size = 0;
+    int how;
+
+    if (mode[0] == '0' && mode[1] == '\0') {
+        how = _IONBF;
+    } else if (mode[0] == 'L' && mode[1] == '\0') {
+        how = _IOLBF;
+    } else {
+        char *end;
+        unsigned long long n;
+
+        errno = 0;
+        n = strtoull(mode, &end, 10);
+        if (errno != 0 || end == mode || *end != '\0' || n == 0 || n > (size_t)-1) {
+            fprintf(stderr, "invalid buffering mode %s for %s\n", mode, stream_name(stream));
+            return;
+        }
+        size = (size_t)n;
+        buf = malloc(size);
+        if (buf == NULL) {
+            fprintf(stderr, "failed to allocate a %zu byte stdio buffer\n", size);
+            return;
+        }
+        how = _IOFBF;
+    }
+
+    /* The buffer stays in use for the life of the stream, so it is never
+       freed unless setvbuf refuses it. */
+    if (setvbuf(stream, buf, how, size) != 0) {
+        fprintf(stderr, "could not set buffering of %s to mode %s\n", stream_name(stream), mode);
+        free(buf);
+    }
+}
+
+__attribute__((constructor)) static void fstdbuf_init(void) {
+    const char *e_mode = getenv("_STDBUF_E");
+    const char *i_mode = getenv("_STDBUF_I");
+    const char *o_mode = getenv("_STDBUF_O");
+
+    if (e_mode != NULL)
+        apply_mode(stderr, e_mode);
+    if (i_mode != NULL)
+        apply_mode(stdin, i_mode);
+    if (o_mode != NULL)
+        apply_mode(stdout, o_mode);
+}
@@ -675,9 +675,80 @@ pub fn apply_flag(termios: &mut libc::termios, name: &str) -> bool {
     false
 }
 
+/// Format the termios state in the stty-readable form used by `stty -g`.
+///
+/// The layout matches GNU: the input, output, control and local flags as
+/// hex words, followed by every entry of the `c_cc` array, all separated by
+/// colons. Saved strings are therefore interchangeable with GNU stty.
+pub fn format_saved(termios: &libc::termios) -> String {
+    let mut out = format!(
+        "{:x}:{:x}:{:x}:{:x}",
+        termios.c_iflag, termios.c_oflag, termios.c_cflag, termios.c_lflag
+    );
+    for &c in termios.c_cc.iter() {
+        out.push_str(&format!(":{:x}", c));
+    }
+    out
+}
+
+/// Restore a termios state from a string produced by `stty -g`.
+/// Returns false if the string is not in the stty-readable format, in which
+/// case `termios` is left untouched.
+pub fn parse_saved(termios: &mut libc::termios, s: &str) -> bool {
+    let fields: Vec<&str> = s.split(':').collect();
+    if fields.len() != 4 + termios.c_cc.len() {
+        return false;
+    }
+    let mut flags = [0 as libc::tcflag_t; 4];
+    for (slot, field) in flags.iter_mut().zip(&fields[..4]) {
+        match parse_saved_hex(field) {
+            Some(v) => match libc::tcflag_t::try_from(v) {
+                Ok(v) => *slot = v,
+                Err(_) => return false,
+            },
+            None => return false,
+        }
+    }
+    let mut cc = termios.c_cc;
+    for (slot, field) in cc.iter_mut().zip(&fields[4..]) {
+        match parse_saved_hex(field) {
+            Some(v) => match libc::cc_t::try_from(v) {
+                Ok(v) => *slot = v,
+                Err(_) => return false,
+            },
+            None => return false,
+        }
+    }
+    termios.c_iflag = flags[0];
+    termios.c_oflag = flags[1];
+    termios.c_cflag = flags[2];
+    termios.c_lflag = flags[3];
+    termios.c_cc = cc;
+    // The speed lives in c_cflag; keep the separate speed fields in sync so
+    // that tcsetattr applies the saved speed rather than the current one.
+    #[cfg(target_os = "linux")]
+    {
+        let speed = termios.c_cflag & (libc::CBAUD | libc::CBAUDEX);
+        unsafe {
+            libc::cfsetispeed(termios, speed);
+            libc::cfsetospeed(termios, speed);
+        }
+    }
+    true
+}
+
+/// Parse one hex field of a saved state, rejecting empty or signed input.
+fn parse_saved_hex(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(s, 16).ok()
+}
+
 /// The result of parsing stty arguments.
 pub enum SttyAction {
     PrintAll,
+    PrintSaved,
     PrintSize,
     PrintSpeed,
     ApplySettings,
@@ -697,12 +768,20 @@ pub fn parse_args(args: &[String]) -> Result<SttyConfig, String> {
     let mut settings: Vec<String> = Vec::new();
     let mut i = 0;
     let mut has_explicit_action = false;
+    let mut verbose = false;
+    let mut recoverable = false;
 
     while i < args.len() {
         match args[i].as_str() {
             "-a" | "--all" => {
                 action = SttyAction::PrintAll;
                 has_explicit_action = true;
+                verbose = true;
+            }
+            "-g" | "--save" => {
+                action = SttyAction::PrintSaved;
+                has_explicit_action = true;
+                recoverable = true;
             }
             "-F" | "--file" => {
                 i += 1;
@@ -732,6 +811,16 @@ pub fn parse_args(args: &[String]) -> Result<SttyConfig, String> {
         i += 1;
     }
 
+    if verbose && recoverable {
+        return Err(
+            "the options for verbose and stty-readable output styles are mutually exclusive"
+                .to_string(),
+        );
+    }
+    if (verbose || recoverable) && !settings.is_empty() {
+        return Err("when specifying an output style, modes may not be set".to_string());
+    }
+
     if !has_explicit_action && settings.is_empty() {
         action = SttyAction::PrintAll;
     }
@@ -821,6 +910,13 @@ pub fn apply_settings(termios: &mut libc::termios, settings: &[String]) -> Resul
                     continue;
                 }
 
+                // Try as a saved state from `stty -g`
+                if parse_saved(termios, arg) {
+                    changed = true;
+                    i += 1;
+                    continue;
+                }
+
                 // Try as a flag
                 if !apply_flag(termios, arg) {
                     return Err(format!("invalid argument '{}'", arg));
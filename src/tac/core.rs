@@ -1,4 +1,8 @@
+#[cfg(unix)]
+use std::fs::File;
 use std::io::{self, IoSlice, Write};
+#[cfg(unix)]
+use std::os::unix::fs::FileExt;
 
 use rayon::prelude::*;
 
@@ -13,6 +17,13 @@ const PARALLEL_THRESHOLD: usize = 64 * 1024 * 1024;
 /// Used by string/regex separator paths.
 const IOSLICE_BATCH_SIZE: usize = 1024;
 
+/// Files at least this large are reversed window by window with
+/// `tac_file_chunked` instead of being mapped and indexed as a whole.
+pub const CHUNKED_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// Size of each window mapped by `tac_file_chunked` (8MB).
+pub const REVERSE_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
 /// Reverse records separated by a single byte.
 /// For large data (>= 8MB): parallel chunk-local reversal with contiguous buffers.
 /// For small data: single-threaded forward SIMD scan + contiguous output buffer.
@@ -43,12 +54,14 @@ pub fn tac_bytes_owned(
     tac_bytes(data, separator, before, out)
 }
 
-/// Collect multi-byte separator positions with pre-allocated Vec.
+/// Collect multi-byte separator positions, last first.
+/// Like GNU tac, the scan runs backward, so overlapping candidates such as
+/// "aa" in "aaa" resolve to the rightmost match.
 #[inline]
 fn collect_positions_str(data: &[u8], separator: &[u8]) -> Vec<usize> {
     let estimated = data.len() / 40 + 64;
     let mut positions = Vec::with_capacity(estimated);
-    for pos in memchr::memmem::find_iter(data, separator) {
+    for pos in memchr::memmem::rfind_iter(data, separator) {
         positions.push(pos);
     }
     positions
@@ -309,7 +322,7 @@ fn tac_string_after(
     let mut slices: Vec<IoSlice<'_>> = Vec::with_capacity(IOSLICE_BATCH_SIZE);
     let mut end = data.len();
 
-    for &pos in positions.iter().rev() {
+    for &pos in &positions {
         let rec_start = pos + sep_len;
         if rec_start < end {
             slices.push(IoSlice::new(&data[rec_start..end]));
@@ -345,7 +358,7 @@ fn tac_string_before(
     let mut slices: Vec<IoSlice<'_>> = Vec::with_capacity(IOSLICE_BATCH_SIZE);
     let mut end = data.len();
 
-    for &pos in positions.iter().rev() {
+    for &pos in &positions {
         if pos < end {
             slices.push(IoSlice::new(&data[pos..end]));
             if slices.len() >= IOSLICE_BATCH_SIZE {
@@ -364,6 +377,117 @@ fn tac_string_before(
     Ok(())
 }
 
+/// Reverse the records of a seekable file without mapping or indexing it whole.
+///
+/// The file is mapped in `chunk_size` windows starting from the end, and each
+/// window is scanned backward for `separator`, so memory stays bounded by the
+/// window size. A record that starts in one window and ends in a later one is
+/// stitched together by reading its remaining bytes back from the file.
+/// Separators are matched rightmost first, as GNU tac does.
+#[cfg(unix)]
+pub fn tac_file_chunked(
+    file: &File,
+    separator: &[u8],
+    before: bool,
+    chunk_size: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let len = file.metadata()?.len();
+    if len == 0 || separator.is_empty() {
+        return Ok(());
+    }
+    let sep_len = separator.len() as u64;
+    let page = (unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).max(4096) as u64;
+    let finder = memchr::memmem::FinderRev::new(separator);
+    let mut tail_buf = Vec::new();
+
+    // Records before `rec_end` have not been written yet; separators ending
+    // after `search_end` have already been consumed.
+    let mut rec_end = len;
+    let mut search_end = len;
+    let mut hi = len;
+
+    while hi > 0 {
+        let lo = hi.saturating_sub(chunk_size.max(1) as u64) / page * page;
+        // Overlap the next window by sep_len - 1 bytes so that a separator
+        // straddling the boundary is still found.
+        let map_end = (hi + sep_len - 1).min(len);
+        let map = unsafe {
+            memmap2::MmapOptions::new()
+                .offset(lo)
+                .len((map_end - lo) as usize)
+                .map(file)?
+        };
+        #[cfg(target_os = "linux")]
+        let _ = map.advise(memmap2::Advice::WillNeed);
+
+        search_end = search_end.min(map_end);
+        loop {
+            let hay = &map[..(search_end - lo) as usize];
+            let found = if separator.len() == 1 {
+                memchr::memrchr(separator[0], hay)
+            } else {
+                finder.rfind(hay)
+            };
+            let Some(pos) = found else { break };
+            let sep_start = lo + pos as u64;
+            let rec_start = if before {
+                sep_start
+            } else {
+                sep_start + sep_len
+            };
+            if rec_start < rec_end {
+                write_file_range(file, &map, lo, rec_start, rec_end, &mut tail_buf, out)?;
+            }
+            rec_end = rec_start;
+            search_end = sep_start;
+            if search_end <= lo {
+                break;
+            }
+        }
+        if lo == 0 && rec_end > 0 {
+            write_file_range(file, &map, 0, 0, rec_end, &mut tail_buf, out)?;
+        }
+        hi = lo;
+    }
+    Ok(())
+}
+
+/// Write bytes `start..end` of `file`, where `start` lies in the window mapped
+/// at offset `lo`. Bytes past the window belong to earlier (higher) windows
+/// and are read back from the file.
+#[cfg(unix)]
+fn write_file_range(
+    file: &File,
+    map: &[u8],
+    lo: u64,
+    start: u64,
+    end: u64,
+    buf: &mut Vec<u8>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let map_end = lo + map.len() as u64;
+    let mapped_end = end.min(map_end);
+    out.write_all(&map[(start - lo) as usize..(mapped_end - lo) as usize])?;
+    let mut offset = mapped_end;
+    if offset < end && buf.is_empty() {
+        buf.resize(256 * 1024, 0);
+    }
+    while offset < end {
+        let want = ((end - offset) as usize).min(buf.len());
+        let n = file.read_at(&mut buf[..want], offset)?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "file shrank while reading",
+            ));
+        }
+        out.write_all(&buf[..n])?;
+        offset += n as u64;
+    }
+    Ok(())
+}
+
 /// Find regex matches using backward scanning, replicating GNU tac's re_search behavior.
 ///
 /// GNU tac searches backward position by position. At each position, it tries to
@@ -1,9 +1,12 @@
+use std::collections::VecDeque;
 use std::io::{self, Read, Seek, Write};
+#[cfg(target_os = "linux")]
 use std::path::Path;
 
 use memchr::{memchr_iter, memrchr_iter};
 
-use crate::common::io::{FileData, read_file, read_stdin};
+/// Read size for inputs that cannot be tailed by seeking.
+const STREAM_CHUNK: usize = 256 * 1024;
 
 /// Open a file with O_NOATIME on Linux, falling back if not permitted.
 #[cfg(target_os = "linux")]
@@ -17,10 +20,11 @@ fn open_noatime(path: &Path) -> io::Result<std::fs::File> {
 }
 
 /// Scan backward from EOF to find the byte offset where the last N delimited
-/// lines begin. Returns 0 when the file has fewer than N lines (output all).
-/// Platform-agnostic — tested on all CI targets.
+/// lines begin. Returns `file_start` when the file has fewer than N lines
+/// after it (output all). Platform-agnostic — tested on all CI targets.
 fn find_tail_start_byte(
     reader: &mut (impl Read + Seek),
+    file_start: u64,
     file_size: u64,
     n: u64,
     delimiter: u8,
@@ -30,8 +34,8 @@ fn find_tail_start_byte(
     let mut count = 0u64;
     let mut buf = vec![0u8; CHUNK as usize];
 
-    while pos > 0 {
-        let read_start = if pos > CHUNK { pos - CHUNK } else { 0 };
+    while pos > file_start {
+        let read_start = pos.saturating_sub(CHUNK).max(file_start);
         let read_len = (pos - read_start) as usize;
 
         reader.seek(io::SeekFrom::Start(read_start))?;
@@ -54,7 +58,7 @@ fn find_tail_start_byte(
         pos = read_start;
     }
 
-    Ok(0)
+    Ok(file_start)
 }
 
 /// Mode for tail operation
@@ -104,8 +108,10 @@ impl Default for TailConfig {
     }
 }
 
+pub use crate::head::SizeError;
+
 /// Parse a numeric argument with optional suffix, same as head
-pub fn parse_size(s: &str) -> Result<u64, String> {
+pub fn parse_size(s: &str) -> Result<u64, SizeError> {
     crate::head::parse_size(s)
 }
 
@@ -193,236 +199,300 @@ pub fn tail_bytes_from(data: &[u8], n: u64, out: &mut impl Write) -> io::Result<
     }
 }
 
-/// Use sendfile for zero-copy byte output on Linux (last N bytes)
-#[cfg(target_os = "linux")]
-pub fn sendfile_tail_bytes(path: &Path, n: u64, out_fd: i32) -> io::Result<bool> {
-    let file = open_noatime(path)?;
+/// Failure while tailing one input. Read errors are reported against the
+/// file and processing continues; write errors go back to the caller.
+enum TailError {
+    Read(io::Error),
+    Write(io::Error),
+}
 
-    let metadata = file.metadata()?;
-    let file_size = metadata.len();
+/// Offsets `(start, end)` of the data left in a seekable input: a regular
+/// file with bytes past its current offset. Pipes, FIFOs and files that
+/// report no size (e.g. /proc entries) return None and are streamed instead.
+fn seekable_range(file: &mut std::fs::File) -> Option<(u64, u64)> {
+    let meta = file.metadata().ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    let start = file.stream_position().ok()?;
+    let end = meta.len();
+    (start < end).then_some((start, end))
+}
 
-    if file_size == 0 {
-        return Ok(true);
+/// Read into `buf`, retrying on EINTR.
+fn read_retry(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, TailError> {
+    loop {
+        match reader.read(buf) {
+            Ok(n) => return Ok(n),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(TailError::Read(e)),
+        }
     }
+}
 
-    let n = n.min(file_size);
-    let start = file_size - n;
-
-    use std::os::unix::io::AsRawFd;
-    let in_fd = file.as_raw_fd();
-    let _ = unsafe {
-        libc::posix_fadvise(
-            in_fd,
-            start as libc::off_t,
-            n as libc::off_t,
-            libc::POSIX_FADV_SEQUENTIAL,
-        )
-    };
-    let mut offset: libc::off_t = start as libc::off_t;
-    let mut remaining = n;
-
-    while remaining > 0 {
-        let chunk = remaining.min(0x7fff_f000) as usize;
-        let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
-        if ret > 0 {
-            remaining -= ret as u64;
-        } else if ret == 0 {
-            break;
-        } else {
-            let err = io::Error::last_os_error();
-            if err.kind() == io::ErrorKind::Interrupted {
-                continue;
-            }
-            return Err(err);
+/// Copy everything left in `reader` to `out`.
+fn copy_rest(reader: &mut impl Read, out: &mut impl Write) -> Result<(), TailError> {
+    let mut buf = vec![0u8; STREAM_CHUNK];
+    loop {
+        let n = read_retry(reader, &mut buf)?;
+        if n == 0 {
+            return Ok(());
         }
+        out.write_all(&buf[..n]).map_err(TailError::Write)?;
     }
+}
 
-    Ok(true)
+/// Copy bytes `start..end` of a seekable file to the output.
+/// On Linux this is zero-copy via sendfile straight to stdout (fd 1), so `out`
+/// is flushed first; outputs sendfile rejects (e.g. O_APPEND) fall back to
+/// read/write.
+fn copy_range(
+    file: &mut std::fs::File,
+    start: u64,
+    end: u64,
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    if start >= end {
+        return Ok(());
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+        out.flush().map_err(TailError::Write)?;
+        let in_fd = file.as_raw_fd();
+        let _ = unsafe {
+            libc::posix_fadvise(
+                in_fd,
+                start as libc::off_t,
+                (end - start) as libc::off_t,
+                libc::POSIX_FADV_SEQUENTIAL,
+            )
+        };
+        let mut offset = start as libc::off_t;
+        let mut left = end - start;
+        while left > 0 {
+            let chunk = left.min(0x7fff_f000) as usize;
+            let ret = unsafe { libc::sendfile(1, in_fd, &mut offset, chunk) };
+            if ret > 0 {
+                left -= ret as u64;
+            } else if ret == 0 {
+                // File shrank underneath us.
+                return Ok(());
+            } else {
+                let err = io::Error::last_os_error();
+                match err.raw_os_error() {
+                    Some(libc::EINTR) => continue,
+                    Some(libc::EINVAL) | Some(libc::ENOSYS) => break,
+                    Some(libc::EPIPE) => return Err(TailError::Write(err)),
+                    _ => return Err(TailError::Read(err)),
+                }
+            }
+        }
+        if left == 0 {
+            return Ok(());
+        }
+        file.seek(io::SeekFrom::Start(offset as u64))
+            .map_err(TailError::Read)?;
+        return copy_rest(&mut Read::take(file, left), out);
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        file.seek(io::SeekFrom::Start(start))
+            .map_err(TailError::Read)?;
+        copy_rest(&mut Read::take(file, end - start), out)
+    }
 }
 
-/// Streaming tail -n N via sendfile on Linux. Caller opens the file so that
-/// open errors can be reported as "cannot open" and I/O errors as "error reading".
-#[cfg(target_os = "linux")]
-fn sendfile_tail_lines(
-    file: std::fs::File,
-    file_size: u64,
+/// Last N lines of a non-seekable input, keeping only as many trailing
+/// chunks as can contain them.
+fn stream_tail_lines(
+    reader: &mut impl Read,
     n: u64,
     delimiter: u8,
-    out_fd: i32,
-) -> io::Result<bool> {
-    use std::os::unix::io::AsRawFd;
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    // (chunk, delimiters in chunk)
+    let mut chunks: VecDeque<(Vec<u8>, u64)> = VecDeque::new();
+    let mut total = 0u64;
+    let mut spare: Option<Vec<u8>> = None;
 
-    if n == 0 || file_size == 0 {
-        return Ok(true);
+    loop {
+        let mut buf = spare.take().unwrap_or_default();
+        buf.resize(STREAM_CHUNK, 0);
+        let len = read_retry(reader, &mut buf)?;
+        if len == 0 {
+            break;
+        }
+        buf.truncate(len);
+        let count = memchr_iter(delimiter, &buf).count() as u64;
+        total += count;
+        chunks.push_back((buf, count));
+        // The last N lines start after one of the final N+1 delimiters, so a
+        // leading chunk is no longer needed once the rest hold that many.
+        while let Some(&(_, front)) = chunks.front() {
+            if total - front > n {
+                total -= front;
+                spare = chunks.pop_front().map(|(b, _)| b);
+            } else {
+                break;
+            }
+        }
     }
 
-    let in_fd = file.as_raw_fd();
-
-    // Disable forward readahead — we scan backward from EOF
-    let _ = unsafe { libc::posix_fadvise(in_fd, 0, 0, libc::POSIX_FADV_RANDOM) };
-
-    let mut reader = file;
-    let start_byte = find_tail_start_byte(&mut reader, file_size, n, delimiter)?;
+    let data: Vec<u8> = chunks.into_iter().flat_map(|(b, _)| b).collect();
+    tail_lines(&data, n, delimiter, out).map_err(TailError::Write)
+}
 
-    // Enable forward readahead from the output start point
-    let remaining = file_size - start_byte;
-    let _ = unsafe {
-        libc::posix_fadvise(
-            in_fd,
-            start_byte as libc::off_t,
-            remaining as libc::off_t,
-            libc::POSIX_FADV_SEQUENTIAL,
-        )
-    };
+/// Last N bytes of a non-seekable input.
+fn stream_tail_bytes(
+    reader: &mut impl Read,
+    n: u64,
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    let mut chunks: VecDeque<Vec<u8>> = VecDeque::new();
+    let mut total = 0u64;
+    let mut spare: Option<Vec<u8>> = None;
 
-    // Zero-copy output via sendfile
-    let mut offset = start_byte as libc::off_t;
-    let mut left = remaining;
-    while left > 0 {
-        let chunk = left.min(0x7fff_f000) as usize;
-        let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
-        if ret > 0 {
-            left -= ret as u64;
-        } else if ret == 0 {
+    loop {
+        let mut buf = spare.take().unwrap_or_default();
+        buf.resize(STREAM_CHUNK, 0);
+        let len = read_retry(reader, &mut buf)?;
+        if len == 0 {
             break;
-        } else {
-            let err = io::Error::last_os_error();
-            if err.kind() == io::ErrorKind::Interrupted {
-                continue;
+        }
+        buf.truncate(len);
+        total += len as u64;
+        chunks.push_back(buf);
+        while let Some(front) = chunks.front() {
+            if total - front.len() as u64 >= n {
+                total -= front.len() as u64;
+                spare = chunks.pop_front();
+            } else {
+                break;
             }
-            return Err(err);
         }
     }
 
-    Ok(true)
+    let data: Vec<u8> = chunks.into_iter().flatten().collect();
+    tail_bytes(&data, n, out).map_err(TailError::Write)
 }
 
-/// Streaming tail -n N for regular files: read backward from EOF, then
-/// seek forward and copy. Caller opens the file. Used on non-Linux platforms.
-#[cfg(not(target_os = "linux"))]
-fn tail_lines_streaming_file(
-    mut file: std::fs::File,
-    file_size: u64,
+/// Output from line N onward (1-indexed), reading forward.
+fn stream_tail_lines_from(
+    reader: &mut impl Read,
     n: u64,
     delimiter: u8,
     out: &mut impl Write,
-) -> io::Result<bool> {
-    if n == 0 || file_size == 0 {
-        return Ok(true);
+) -> Result<(), TailError> {
+    if n > 1 {
+        let skip = n - 1;
+        let mut buf = vec![0u8; STREAM_CHUNK];
+        let mut count = 0u64;
+        loop {
+            let len = read_retry(reader, &mut buf)?;
+            if len == 0 {
+                return Ok(());
+            }
+            let chunk = &buf[..len];
+            let found = memchr_iter(delimiter, chunk).find(|_| {
+                count += 1;
+                count == skip
+            });
+            if let Some(pos) = found {
+                out.write_all(&chunk[pos + 1..]).map_err(TailError::Write)?;
+                break;
+            }
+        }
     }
-
-    let start_byte = find_tail_start_byte(&mut file, file_size, n, delimiter)?;
-    file.seek(io::SeekFrom::Start(start_byte))?;
-    io::copy(&mut file, out)?;
-
-    Ok(true)
+    copy_rest(reader, out)
 }
 
-/// Streaming tail -n +N for regular files: skip N-1 lines from start.
-/// Caller opens the file.
-///
-/// **Precondition**: On Linux, the `n <= 1` path uses `sendfile` which writes
-/// directly to stdout (bypassing `out`). The caller MUST `out.flush()` before
-/// calling this function to avoid interleaved output.
-fn tail_lines_from_streaming_file(
-    file: std::fs::File,
+/// Output from byte N onward (1-indexed), reading forward.
+fn stream_tail_bytes_from(
+    reader: &mut impl Read,
     n: u64,
-    delimiter: u8,
     out: &mut impl Write,
-) -> io::Result<bool> {
-    if n <= 1 {
-        // Output entire file via sendfile
-        #[cfg(target_os = "linux")]
-        {
-            use std::os::unix::io::AsRawFd;
-            let in_fd = file.as_raw_fd();
-            let stdout = io::stdout();
-            let out_fd = stdout.as_raw_fd();
-            let file_size = file.metadata()?.len();
-            return sendfile_to_stdout_raw(in_fd, file_size, out_fd);
-        }
-        #[cfg(not(target_os = "linux"))]
-        {
-            let mut reader = io::BufReader::with_capacity(1024 * 1024, file);
-            let mut buf = [0u8; 262144];
-            loop {
-                let n = match reader.read(&mut buf) {
-                    Ok(0) => break,
-                    Ok(n) => n,
-                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
-                    Err(e) => return Err(e),
-                };
-                out.write_all(&buf[..n])?;
-            }
-            return Ok(true);
+) -> Result<(), TailError> {
+    let skip = n.saturating_sub(1);
+    if skip > 0 {
+        let skipped = io::copy(&mut Read::take(&mut *reader, skip), &mut io::sink())
+            .map_err(TailError::Read)?;
+        if skipped < skip {
+            return Ok(());
         }
     }
+    copy_rest(reader, out)
+}
 
-    let skip = n - 1;
-    let mut reader = io::BufReader::with_capacity(1024 * 1024, file);
-    let mut buf = [0u8; 262144];
-    let mut count = 0u64;
-    let mut skipping = true;
-
-    loop {
-        let bytes_read = match reader.read(&mut buf) {
-            Ok(0) => break,
-            Ok(n) => n,
-            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
-            Err(e) => return Err(e),
-        };
-
-        let chunk = &buf[..bytes_read];
+/// Tail an input that cannot be seeked, reading it forward.
+fn tail_stream(
+    reader: &mut impl Read,
+    mode: &TailMode,
+    delimiter: u8,
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    match mode {
+        TailMode::Lines(0) | TailMode::Bytes(0) => Ok(()),
+        TailMode::Lines(n) => stream_tail_lines(reader, *n, delimiter, out),
+        TailMode::Bytes(n) => stream_tail_bytes(reader, *n, out),
+        TailMode::LinesFrom(n) => stream_tail_lines_from(reader, *n, delimiter, out),
+        TailMode::BytesFrom(n) => stream_tail_bytes_from(reader, *n, out),
+    }
+}
 
-        if skipping {
-            for pos in memchr_iter(delimiter, chunk) {
-                count += 1;
-                if count == skip {
-                    // Found the start — output rest of this chunk and stop skipping
-                    let start = pos + 1;
-                    if start < chunk.len() {
-                        out.write_all(&chunk[start..])?;
-                    }
-                    skipping = false;
-                    break;
-                }
+/// Tail an open input: seek from the end of a regular file, stream anything else.
+fn tail_open_file(
+    file: &mut std::fs::File,
+    mode: &TailMode,
+    delimiter: u8,
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    let Some((start, end)) = seekable_range(file) else {
+        return tail_stream(file, mode, delimiter, out);
+    };
+    match mode {
+        TailMode::Lines(0) | TailMode::Bytes(0) => Ok(()),
+        TailMode::Lines(n) => {
+            #[cfg(target_os = "linux")]
+            {
+                use std::os::unix::io::AsRawFd;
+                // Disable forward readahead while scanning backward from EOF
+                let _ =
+                    unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_RANDOM) };
             }
-        } else {
-            out.write_all(chunk)?;
+            let from =
+                find_tail_start_byte(file, start, end, *n, delimiter).map_err(TailError::Read)?;
+            copy_range(file, from, end, out)
+        }
+        TailMode::Bytes(n) => copy_range(file, end.saturating_sub(*n).max(start), end, out),
+        TailMode::LinesFrom(n) if *n <= 1 => copy_range(file, start, end, out),
+        TailMode::LinesFrom(n) => stream_tail_lines_from(file, *n, delimiter, out),
+        TailMode::BytesFrom(n) => {
+            copy_range(file, start.saturating_add(n.saturating_sub(1)), end, out)
         }
     }
+}
 
-    Ok(true)
+/// Tail standard input. On unix fd 0 is used as a file, so a redirected
+/// regular file is tailed by seeking from its end.
+#[cfg(unix)]
+fn tail_stdin(mode: &TailMode, delimiter: u8, out: &mut impl Write) -> Result<(), TailError> {
+    use std::os::unix::io::FromRawFd;
+    // Borrow fd 0; ManuallyDrop keeps it open for later operands.
+    let mut file = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(0) });
+    tail_open_file(&mut file, mode, delimiter, out)
 }
 
-/// Raw sendfile helper
-#[cfg(target_os = "linux")]
-fn sendfile_to_stdout_raw(in_fd: i32, file_size: u64, out_fd: i32) -> io::Result<bool> {
-    let mut offset: libc::off_t = 0;
-    let mut remaining = file_size;
-    while remaining > 0 {
-        let chunk = remaining.min(0x7fff_f000) as usize;
-        let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
-        if ret > 0 {
-            remaining -= ret as u64;
-        } else if ret == 0 {
-            break;
-        } else {
-            let err = io::Error::last_os_error();
-            if err.kind() == io::ErrorKind::Interrupted {
-                continue;
-            }
-            return Err(err);
-        }
-    }
-    Ok(true)
+#[cfg(not(unix))]
+fn tail_stdin(mode: &TailMode, delimiter: u8, out: &mut impl Write) -> Result<(), TailError> {
+    tail_stream(&mut io::stdin().lock(), mode, delimiter, out)
 }
 
 /// Process a single file/stdin for tail.
 ///
-/// On Linux, the sendfile fast paths bypass `out` and write directly to stdout
-/// (fd 1). Callers MUST ensure `out` wraps stdout when these paths are active.
-/// The `out.flush()` call drains any buffered data before sendfile takes over.
+/// On Linux, output from seekable files is sent with sendfile directly to
+/// stdout (fd 1), bypassing `out` after flushing it. Callers MUST ensure
+/// `out` wraps stdout.
 pub fn tail_file(
     filename: &str,
     config: &TailConfig,
@@ -431,189 +501,18 @@ pub fn tail_file(
 ) -> io::Result<bool> {
     let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
 
-    if filename != "-" {
-        let path = Path::new(filename);
-
-        match &config.mode {
-            TailMode::Lines(n) => {
-                // Open the file first so open errors get the right message
-                #[cfg(target_os = "linux")]
-                let file = match open_noatime(path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        eprintln!(
-                            "{}: cannot open '{}' for reading: {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                };
-                #[cfg(not(target_os = "linux"))]
-                let file = match std::fs::File::open(path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        eprintln!(
-                            "{}: cannot open '{}' for reading: {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                };
-                let file_size = match file.metadata() {
-                    Ok(m) => m.len(),
-                    Err(e) => {
-                        eprintln!(
-                            "{}: error reading '{}': {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                };
-                #[cfg(target_os = "linux")]
-                {
-                    use std::os::unix::io::AsRawFd;
-                    out.flush()?;
-                    let stdout = io::stdout();
-                    let out_fd = stdout.as_raw_fd();
-                    match sendfile_tail_lines(file, file_size, *n, delimiter, out_fd) {
-                        Ok(_) => return Ok(true),
-                        Err(e) => {
-                            eprintln!(
-                                "{}: error reading '{}': {}",
-                                tool_name,
-                                filename,
-                                crate::common::io_error_msg(&e)
-                            );
-                            return Ok(false);
-                        }
-                    }
-                }
-                #[cfg(not(target_os = "linux"))]
-                {
-                    match tail_lines_streaming_file(file, file_size, *n, delimiter, out) {
-                        Ok(_) => return Ok(true),
-                        Err(e) => {
-                            eprintln!(
-                                "{}: error reading '{}': {}",
-                                tool_name,
-                                filename,
-                                crate::common::io_error_msg(&e)
-                            );
-                            return Ok(false);
-                        }
-                    }
-                }
-            }
-            TailMode::LinesFrom(n) => {
-                out.flush()?;
-                #[cfg(target_os = "linux")]
-                let file = match open_noatime(path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        eprintln!(
-                            "{}: cannot open '{}' for reading: {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                };
-                #[cfg(not(target_os = "linux"))]
-                let file = match std::fs::File::open(path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        eprintln!(
-                            "{}: cannot open '{}' for reading: {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                };
-                match tail_lines_from_streaming_file(file, *n, delimiter, out) {
-                    Ok(_) => return Ok(true),
-                    Err(e) => {
-                        eprintln!(
-                            "{}: error reading '{}': {}",
-                            tool_name,
-                            filename,
-                            crate::common::io_error_msg(&e)
-                        );
-                        return Ok(false);
-                    }
-                }
-            }
-            TailMode::Bytes(_n) => {
-                #[cfg(target_os = "linux")]
-                {
-                    use std::os::unix::io::AsRawFd;
-                    out.flush()?;
-                    let stdout = io::stdout();
-                    let out_fd = stdout.as_raw_fd();
-                    match sendfile_tail_bytes(path, *_n, out_fd) {
-                        Ok(true) => return Ok(true),
-                        Ok(false) => {}
-                        Err(e) => {
-                            eprintln!(
-                                "{}: error reading '{}': {}",
-                                tool_name,
-                                filename,
-                                crate::common::io_error_msg(&e)
-                            );
-                            return Ok(false);
-                        }
-                    }
-                }
-            }
-            TailMode::BytesFrom(_n) => {
-                #[cfg(target_os = "linux")]
-                {
-                    use std::os::unix::io::AsRawFd;
-                    out.flush()?;
-                    let stdout = io::stdout();
-                    let out_fd = stdout.as_raw_fd();
-                    match sendfile_tail_bytes_from(path, *_n, out_fd) {
-                        Ok(true) => return Ok(true),
-                        Ok(false) => {}
-                        Err(e) => {
-                            eprintln!(
-                                "{}: error reading '{}': {}",
-                                tool_name,
-                                filename,
-                                crate::common::io_error_msg(&e)
-                            );
-                            return Ok(false);
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Slow path: read entire input (stdin or fallback)
-    let data: FileData = if filename == "-" {
-        match read_stdin() {
-            Ok(d) => FileData::Owned(d),
-            Err(e) => {
-                eprintln!(
-                    "{}: standard input: {}",
-                    tool_name,
-                    crate::common::io_error_msg(&e)
-                );
-                return Ok(false);
-            }
-        }
+    let (result, display_name) = if filename == "-" {
+        (tail_stdin(&config.mode, delimiter, out), "standard input")
     } else {
-        match read_file(Path::new(filename)) {
-            Ok(d) => d,
+        #[cfg(target_os = "linux")]
+        let opened = open_noatime(Path::new(filename));
+        #[cfg(not(target_os = "linux"))]
+        let opened = std::fs::File::open(filename);
+        match opened {
+            Ok(mut file) => (
+                tail_open_file(&mut file, &config.mode, delimiter, out),
+                filename,
+            ),
             Err(e) => {
                 eprintln!(
                     "{}: cannot open '{}' for reading: {}",
@@ -625,177 +524,243 @@ pub fn tail_file(
             }
         }
     };
-
-    match &config.mode {
-        TailMode::Lines(n) => tail_lines(&data, *n, delimiter, out)?,
-        TailMode::LinesFrom(n) => tail_lines_from(&data, *n, delimiter, out)?,
-        TailMode::Bytes(n) => tail_bytes(&data, *n, out)?,
-        TailMode::BytesFrom(n) => tail_bytes_from(&data, *n, out)?,
+    match result {
+        Ok(()) => Ok(true),
+        Err(TailError::Write(e)) => Err(e),
+        Err(TailError::Read(e)) => {
+            eprintln!(
+                "{}: error reading '{}': {}",
+                tool_name,
+                display_name,
+                crate::common::io_error_msg(&e)
+            );
+            Ok(false)
+        }
     }
-
-    Ok(true)
 }
 
-/// sendfile from byte N onward (1-indexed)
-#[cfg(target_os = "linux")]
-fn sendfile_tail_bytes_from(path: &Path, n: u64, out_fd: i32) -> io::Result<bool> {
-    let file = open_noatime(path)?;
-
-    let metadata = file.metadata()?;
-    let file_size = metadata.len();
+/// Per-file state while following.
+struct FollowState<'a> {
+    name: &'a str,
+    file: Option<std::fs::File>,
+    pos: u64,
+    /// Device and inode of the open file, to notice a replaced name.
+    #[cfg(unix)]
+    dev: u64,
+    #[cfg(unix)]
+    ino: u64,
+    mtime: Option<std::time::SystemTime>,
+    /// Consecutive polls that found the file unchanged.
+    unchanged_stats: u64,
+}
 
-    if file_size == 0 {
-        return Ok(true);
+impl<'a> FollowState<'a> {
+    fn new(name: &'a str) -> Self {
+        let mut state = Self {
+            name,
+            file: None,
+            pos: 0,
+            #[cfg(unix)]
+            dev: 0,
+            #[cfg(unix)]
+            ino: 0,
+            mtime: None,
+            unchanged_stats: 0,
+        };
+        if name != "-"
+            && let Ok(file) = std::fs::File::open(name)
+            && let Ok(meta) = file.metadata()
+        {
+            state.adopt(file, &meta, meta.len());
+        }
+        state
     }
 
-    let start = if n <= 1 { 0 } else { (n - 1).min(file_size) };
-
-    if start >= file_size {
-        return Ok(true);
+    fn adopt(&mut self, file: std::fs::File, meta: &std::fs::Metadata, pos: u64) {
+        self.file = Some(file);
+        self.pos = pos;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            self.dev = meta.dev();
+            self.ino = meta.ino();
+        }
+        self.mtime = meta.modified().ok();
+        self.unchanged_stats = 0;
     }
 
-    use std::os::unix::io::AsRawFd;
-    let in_fd = file.as_raw_fd();
-    let output_len = file_size - start;
-    let _ = unsafe {
-        libc::posix_fadvise(
-            in_fd,
-            start as libc::off_t,
-            output_len as libc::off_t,
-            libc::POSIX_FADV_SEQUENTIAL,
-        )
-    };
-    let mut offset: libc::off_t = start as libc::off_t;
-    let mut remaining = output_len;
-
-    while remaining > 0 {
-        let chunk = remaining.min(0x7fff_f000) as usize;
-        let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
-        if ret > 0 {
-            remaining -= ret as u64;
-        } else if ret == 0 {
-            break;
-        } else {
-            let err = io::Error::last_os_error();
-            if err.kind() == io::ErrorKind::Interrupted {
-                continue;
+    /// With `--follow=name`, reopen the file if it was removed, replaced or
+    /// has (re)appeared. Returns true when a different file is now followed.
+    fn check_name(&mut self, tool_name: &str) -> bool {
+        if self.name == "-" {
+            return false;
+        }
+        let meta = match std::fs::metadata(self.name) {
+            Ok(m) => m,
+            Err(e) => {
+                if self.file.take().is_some() {
+                    eprintln!(
+                        "{}: '{}' has become inaccessible: {}",
+                        tool_name,
+                        self.name,
+                        crate::common::io_error_msg(&e)
+                    );
+                }
+                return false;
             }
-            return Err(err);
+        };
+        if self.file.is_some() && self.is_same_file(&meta) {
+            return false;
         }
+        let Ok(file) = std::fs::File::open(self.name) else {
+            return false;
+        };
+        let Ok(meta) = file.metadata() else {
+            return false;
+        };
+        let what = if self.file.is_some() {
+            "has been replaced"
+        } else {
+            "has appeared"
+        };
+        eprintln!(
+            "{}: '{}' {};  following new file",
+            tool_name, self.name, what
+        );
+        self.adopt(file, &meta, 0);
+        true
     }
 
-    Ok(true)
+    /// Whether `meta` describes the file already open. Without inode numbers
+    /// a replaced file can't be told apart, so the open one is kept.
+    #[cfg(unix)]
+    fn is_same_file(&self, meta: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+        meta.dev() == self.dev && meta.ino() == self.ino
+    }
+
+    #[cfg(not(unix))]
+    fn is_same_file(&self, _meta: &std::fs::Metadata) -> bool {
+        true
+    }
 }
 
-/// Follow a file for new data (basic implementation)
-#[cfg(target_os = "linux")]
-pub fn follow_file(filename: &str, config: &TailConfig, out: &mut impl Write) -> io::Result<()> {
+/// Whether the --pid process is still running.
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+/// Without a way to probe other processes, --pid never ends following.
+#[cfg(not(unix))]
+fn pid_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Follow several files for new data, polling every `sleep_interval`.
+///
+/// Files are checked in command-line order on each pass. When `show_headers`
+/// is set, a `==> FILE <==` header is written whenever output switches to a
+/// different file than the one written last; `last_shown` is the index of the
+/// file whose contents were output most recently (normally the last operand).
+/// With `--follow=name`, a file that is removed and recreated is reopened from
+/// the start and always gets a fresh header. As in GNU's polling mode, an open
+/// file's name is only rechecked after `max_unchanged_stats` consecutive polls
+/// found it unchanged; a missing file's name is rechecked on every poll.
+pub fn follow_files(
+    filenames: &[String],
+    config: &TailConfig,
+    show_headers: bool,
+    mut last_shown: Option<usize>,
+    out: &mut impl Write,
+) -> io::Result<()> {
     use std::thread;
     use std::time::Duration;
 
+    let tool_name = "tail";
     let sleep_duration = Duration::from_secs_f64(config.sleep_interval);
-    let path = Path::new(filename);
-
-    let mut last_size = match std::fs::metadata(path) {
-        Ok(m) => m.len(),
-        Err(_) => 0,
-    };
+    let by_name = config.follow == FollowMode::Name;
+    let mut states: Vec<FollowState> = filenames.iter().map(|f| FollowState::new(f)).collect();
+    let mut buf = vec![0u8; 64 * 1024];
 
     loop {
         // Check PID if set
         if let Some(pid) = config.pid {
-            if unsafe { libc::kill(pid as i32, 0) } != 0 {
+            if !pid_alive(pid) {
                 break;
             }
         }
 
+        if !config.retry && states.iter().all(|s| s.file.is_none()) {
+            break;
+        }
+
         thread::sleep(sleep_duration);
 
-        let current_size = match std::fs::metadata(path) {
-            Ok(m) => m.len(),
-            Err(_) => {
-                if config.retry {
-                    continue;
+        for (idx, state) in states.iter_mut().enumerate() {
+            if by_name
+                && state.file.is_none()
+                && state.check_name(tool_name)
+                && last_shown == Some(idx)
+            {
+                last_shown = None;
+            }
+            let Some(file) = state.file.as_mut() else {
+                continue;
+            };
+            let Ok(meta) = file.metadata() else {
+                continue;
+            };
+            let mtime = meta.modified().ok();
+            if meta.len() == state.pos && mtime == state.mtime {
+                let recheck = state.unchanged_stats >= config.max_unchanged_stats;
+                state.unchanged_stats += 1;
+                if by_name && recheck {
+                    state.unchanged_stats = 0;
+                    if state.check_name(tool_name) && last_shown == Some(idx) {
+                        last_shown = None;
+                    }
                 }
-                break;
+                continue;
+            }
+            state.unchanged_stats = 0;
+            state.mtime = mtime;
+            let size = meta.len();
+            if size < state.pos {
+                eprintln!("{}: {}: file truncated", tool_name, state.name);
+                state.pos = 0;
+            }
+            if size == state.pos {
+                continue;
             }
-        };
 
-        if current_size > last_size {
-            // Read new data
-            let file = std::fs::File::open(path)?;
-            use std::os::unix::io::AsRawFd;
-            let in_fd = file.as_raw_fd();
-            let stdout = io::stdout();
-            let out_fd = stdout.as_raw_fd();
-            let mut offset = last_size as libc::off_t;
-            let mut remaining = current_size - last_size; // u64, safe on 32-bit
-
-            while remaining > 0 {
-                let chunk = remaining.min(0x7fff_f000) as usize;
-                let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, chunk) };
-                if ret > 0 {
-                    remaining -= ret as u64;
-                } else if ret == 0 {
-                    break;
-                } else {
-                    let err = io::Error::last_os_error();
-                    if err.kind() == io::ErrorKind::Interrupted {
-                        continue;
-                    }
-                    return Err(err);
+            file.seek(io::SeekFrom::Start(state.pos))?;
+            let mut wrote = false;
+            loop {
+                let n = match file.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                if show_headers && !wrote && last_shown != Some(idx) {
+                    writeln!(out, "\n==> {} <==", state.name)?;
                 }
+                wrote = true;
+                out.write_all(&buf[..n])?;
+                state.pos += n as u64;
+            }
+            if wrote {
+                last_shown = Some(idx);
+                out.flush()?;
             }
-            let _ = out.flush();
-            last_size = current_size;
-        } else if current_size < last_size {
-            // File was truncated
-            last_size = current_size;
         }
     }
 
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+/// Follow a single file for new data.
 pub fn follow_file(filename: &str, config: &TailConfig, out: &mut impl Write) -> io::Result<()> {
-    use std::io::{Read, Seek};
-    use std::thread;
-    use std::time::Duration;
-
-    let sleep_duration = Duration::from_secs_f64(config.sleep_interval);
-    let path = Path::new(filename);
-
-    let mut last_size = match std::fs::metadata(path) {
-        Ok(m) => m.len(),
-        Err(_) => 0,
-    };
-
-    loop {
-        thread::sleep(sleep_duration);
-
-        let current_size = match std::fs::metadata(path) {
-            Ok(m) => m.len(),
-            Err(_) => {
-                if config.retry {
-                    continue;
-                }
-                break;
-            }
-        };
-
-        if current_size > last_size {
-            let mut file = std::fs::File::open(path)?;
-            file.seek(io::SeekFrom::Start(last_size))?;
-            let mut buf = vec![0u8; (current_size - last_size) as usize];
-            file.read_exact(&mut buf)?;
-            out.write_all(&buf)?;
-            out.flush()?;
-            last_size = current_size;
-        } else if current_size < last_size {
-            last_size = current_size;
-        }
-    }
-
-    Ok(())
+    follow_files(&[filename.to_string()], config, false, Some(0), out)
 }
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fs;
 use std::os::unix::fs::MetadataExt;
 
@@ -7,58 +8,19 @@ use std::os::unix::fs::MetadataExt;
 /// and `Err(msg)` on syntax/parse errors.
 ///
 /// This implements a recursive descent parser for the POSIX test expression
-/// grammar with GNU extensions.
+/// grammar with GNU extensions. Expressions of up to four arguments are
+/// disambiguated by argument count as POSIX requires, so that e.g.
+/// `test = = =` and `test ! -a` are string tests rather than syntax errors.
 pub fn evaluate(args: &[String]) -> Result<bool, String> {
     if args.is_empty() {
         return Ok(false);
     }
 
-    // Special cases for 1, 2, 3, and 4 arguments for POSIX compliance.
-    // These bypass the parser to handle ambiguous cases correctly.
-    match args.len() {
-        1 => return Ok(!args[0].is_empty()),
-        2 => {
-            if args[0] == "!" {
-                return Ok(args[1].is_empty());
-            }
-            return eval_unary(&args[0], &args[1]);
-        }
-        3 => {
-            // Try binary operator first
-            if let Ok(result) = eval_binary(&args[0], &args[1], &args[2]) {
-                return Ok(result);
-            }
-            // Try ! unary
-            if args[0] == "!" {
-                return evaluate(&args[1..]).map(|v| !v);
-            }
-            // Try ( expr )
-            if args[0] == "(" && args[2] == ")" {
-                return evaluate(&args[1..2]);
-            }
-            // GNU compat: if $2 is -a or -o, fall through to general parser
-            if args[1] == "-a" || args[1] == "-o" {
-                // Fall through to general parser below
-            } else {
-                return Err(format!("test: {}: binary operator expected", args[1]));
-            }
-        }
-        4 => {
-            // ! expr expr expr (3-arg expression negated)
-            if args[0] == "!" {
-                return evaluate(&args[1..]).map(|v| !v);
-            }
-            // ( expr ) with binary
-            // Fall through to general parser
-        }
-        _ => {}
-    }
-
     let mut parser = Parser::new(args);
-    let result = parser.parse_expr()?;
+    let result = parser.posixtest(args.len())?;
     if parser.pos < parser.args.len() {
         return Err(format!(
-            "test: {}: unexpected argument",
+            "test: extra argument '{}'",
             parser.args[parser.pos]
         ));
     }
@@ -89,41 +51,74 @@ fn eval_unary(op: &str, arg: &str) -> Result<bool, String> {
         "-z" => Ok(arg.is_empty()),
         "-n" => Ok(!arg.is_empty()),
         "-t" => {
-            let fd: i32 = arg
-                .parse()
-                .map_err(|_| format!("test: {}: integer expression expected", arg))?;
-            Ok(is_terminal(fd))
+            let fd = find_int(arg)?;
+            Ok(fd.parse::<i32>().is_ok_and(is_terminal))
         }
-        _ => Err(format!("test: {}: unary operator expected", op)),
+        _ => Err(format!("test: '{}': unary operator expected", op)),
     }
 }
 
-/// Evaluate a binary operator expression.
+/// Evaluate a binary operator expression. `left` and `right` are integer
+/// strings already for the integer comparisons when `-l` was used.
 fn eval_binary(left: &str, op: &str, right: &str) -> Result<bool, String> {
     match op {
         "=" | "==" => Ok(left == right),
         "!=" => Ok(left != right),
-        "-eq" => int_cmp(left, right, |a, b| a == b),
-        "-ne" => int_cmp(left, right, |a, b| a != b),
-        "-lt" => int_cmp(left, right, |a, b| a < b),
-        "-le" => int_cmp(left, right, |a, b| a <= b),
-        "-gt" => int_cmp(left, right, |a, b| a > b),
-        "-ge" => int_cmp(left, right, |a, b| a >= b),
+        "-eq" => int_cmp(left, right, Ordering::is_eq),
+        "-ne" => int_cmp(left, right, Ordering::is_ne),
+        "-lt" => int_cmp(left, right, Ordering::is_lt),
+        "-le" => int_cmp(left, right, Ordering::is_le),
+        "-gt" => int_cmp(left, right, Ordering::is_gt),
+        "-ge" => int_cmp(left, right, Ordering::is_ge),
         "-nt" => Ok(file_newer_than(left, right)),
         "-ot" => Ok(file_older_than(left, right)),
         "-ef" => Ok(same_file(left, right)),
-        _ => Err(format!("test: {}: unknown binary operator", op)),
+        _ => Err(format!("test: '{}': unknown binary operator", op)),
     }
 }
 
-fn int_cmp(left: &str, right: &str, cmp: impl Fn(i64, i64) -> bool) -> Result<bool, String> {
-    let a: i64 = left
-        .parse()
-        .map_err(|_| format!("test: {}: integer expression expected", left))?;
-    let b: i64 = right
-        .parse()
-        .map_err(|_| format!("test: {}: integer expression expected", right))?;
-    Ok(cmp(a, b))
+fn int_cmp(left: &str, right: &str, cmp: impl Fn(Ordering) -> bool) -> Result<bool, String> {
+    let a = find_int(left)?;
+    let b = find_int(right)?;
+    Ok(cmp(strintcmp(a, b)))
+}
+
+/// Validate an integer operand and return it without surrounding blanks.
+///
+/// Like GNU, leading and trailing blanks and a leading `+` are allowed, and
+/// there is no size limit: operands are compared as digit strings.
+fn find_int(s: &str) -> Result<&str, String> {
+    let t = s.trim_matches(|c| c == ' ' || c == '\t');
+    let digits = t
+        .strip_prefix('-')
+        .or_else(|| t.strip_prefix('+'))
+        .unwrap_or(t);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("test: invalid integer '{}'", s));
+    }
+    Ok(t.strip_prefix('+').unwrap_or(t))
+}
+
+/// Compare two integer strings as returned by `find_int`, of any length.
+fn strintcmp(a: &str, b: &str) -> Ordering {
+    fn split(s: &str) -> (bool, &str) {
+        let (neg, digits) = match s.strip_prefix('-') {
+            Some(d) => (true, d),
+            None => (false, s),
+        };
+        let digits = digits.trim_start_matches('0');
+        // -0 is zero, not negative
+        (neg && !digits.is_empty(), digits)
+    }
+    let (a_neg, a) = split(a);
+    let (b_neg, b) = split(b);
+    let magnitude = a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+    match (a_neg, b_neg) {
+        (false, false) => magnitude,
+        (true, true) => magnitude.reverse(),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+    }
 }
 
 // ---- File test primitives ----
@@ -208,26 +203,33 @@ fn is_terminal(fd: i32) -> bool {
     unsafe { libc::isatty(fd) == 1 }
 }
 
+/// FILE1 -nt FILE2: FILE1 is newer (by mtime), or FILE1 exists and FILE2
+/// does not.
 fn file_newer_than(a: &str, b: &str) -> bool {
-    let ma = fs::metadata(a).and_then(|m| m.modified());
-    let mb = fs::metadata(b).and_then(|m| m.modified());
-    match (ma, mb) {
-        (Ok(ta), Ok(tb)) => ta > tb,
-        (Ok(_), Err(_)) => true,
+    match (mtime(a), mtime(b)) {
+        (Some(ta), Some(tb)) => ta > tb,
+        (Some(_), None) => true,
         _ => false,
     }
 }
 
+/// FILE1 -ot FILE2: FILE1 is older (by mtime), or FILE2 exists and FILE1
+/// does not.
 fn file_older_than(a: &str, b: &str) -> bool {
-    let ma = fs::metadata(a).and_then(|m| m.modified());
-    let mb = fs::metadata(b).and_then(|m| m.modified());
-    match (ma, mb) {
-        (Ok(ta), Ok(tb)) => ta < tb,
-        (Err(_), Ok(_)) => true,
+    match (mtime(a), mtime(b)) {
+        (Some(ta), Some(tb)) => ta < tb,
+        (None, Some(_)) => true,
         _ => false,
     }
 }
 
+/// Modification time with nanosecond precision, following symlinks.
+fn mtime(path: &str) -> Option<(i64, i64)> {
+    fs::metadata(path).ok().map(|m| (m.mtime(), m.mtime_nsec()))
+}
+
+/// FILE1 -ef FILE2: both names resolve (following symlinks) to the same
+/// device and inode, e.g. hard links or a symlink and its target.
 fn same_file(a: &str, b: &str) -> bool {
     let ma = fs::metadata(a);
     let mb = fs::metadata(b);
@@ -246,9 +248,12 @@ fn to_cstr(s: &str) -> std::ffi::CString {
 // Grammar (POSIX + GNU extensions):
 //   expr     := or_expr
 //   or_expr  := and_expr ( '-o' and_expr )*
-//   and_expr := not_expr ( '-a' not_expr )*
-//   not_expr := '!' not_expr | primary
-//   primary  := '(' expr ')' | unary_op OPERAND | OPERAND binary_op OPERAND | OPERAND
+//   and_expr := term ( '-a' term )*
+//   term     := '!'* ( '(' expr ')' | '-l' STRING binary_op OPERAND
+//             | OPERAND binary_op OPERAND | unary_op OPERAND | OPERAND )
+//
+// Top-level expressions (and parenthesized ones) of 1 to 4 arguments are
+// first resolved by the POSIX argument-count rules in `posixtest`.
 
 struct Parser<'a> {
     args: &'a [String],
@@ -260,92 +265,240 @@ impl<'a> Parser<'a> {
         Self { args, pos: 0 }
     }
 
-    fn peek(&self) -> Option<&str> {
-        self.args.get(self.pos).map(|s| s.as_str())
+    fn arg(&self, offset: usize) -> Option<&'a str> {
+        self.args.get(self.pos + offset).map(|s| s.as_str())
     }
 
-    fn advance(&mut self) -> Result<&str, String> {
-        if self.pos >= self.args.len() {
-            return Err("test: missing argument".to_string());
+    fn remaining(&self) -> usize {
+        self.args.len() - self.pos
+    }
+
+    /// Move past the current argument. If `need_more` is set, it is an error
+    /// for the arguments to run out.
+    fn advance(&mut self, need_more: bool) -> Result<(), String> {
+        self.pos += 1;
+        if need_more && self.pos >= self.args.len() {
+            return Err(self.beyond());
+        }
+        Ok(())
+    }
+
+    fn beyond(&self) -> String {
+        format!(
+            "test: missing argument after '{}'",
+            self.args.last().map(|s| s.as_str()).unwrap_or("")
+        )
+    }
+
+    /// Evaluate the next `nargs` arguments using the POSIX rules for short
+    /// expressions, falling back to the full grammar for longer ones.
+    fn posixtest(&mut self, nargs: usize) -> Result<bool, String> {
+        match nargs {
+            1 => Ok(self.one_argument()),
+            2 => self.two_arguments(),
+            3 => self.three_arguments(),
+            4 => self.four_arguments(),
+            _ => self.parse_expr(),
         }
-        let val = &self.args[self.pos];
+    }
+
+    /// STRING: true if non-empty.
+    fn one_argument(&mut self) -> bool {
+        let value = !self.args[self.pos].is_empty();
         self.pos += 1;
-        Ok(val.as_str())
+        value
     }
 
-    fn parse_expr(&mut self) -> Result<bool, String> {
-        self.parse_or()
+    /// `! STRING` or `UNARY_OP OPERAND`.
+    fn two_arguments(&mut self) -> Result<bool, String> {
+        let first = self.args[self.pos].as_str();
+        if first == "!" {
+            self.advance(false)?;
+            Ok(!self.one_argument())
+        } else if is_switch(first) {
+            if is_unary_op(first) {
+                self.unary_operator()
+            } else {
+                Err(format!("test: '{}': unary operator expected", first))
+            }
+        } else {
+            Err(self.beyond())
+        }
     }
 
-    fn parse_or(&mut self) -> Result<bool, String> {
-        let mut result = self.parse_and()?;
-        while self.peek() == Some("-o") {
-            self.pos += 1;
-            let right = self.parse_and()?;
-            result = result || right;
+    /// `OPERAND BINARY_OP OPERAND`, `! EXPR2`, `( STRING )`, or an `-a`/`-o`
+    /// conjunction of two strings.
+    fn three_arguments(&mut self) -> Result<bool, String> {
+        let (first, second, third) = (
+            self.args[self.pos].as_str(),
+            self.args[self.pos + 1].as_str(),
+            self.args[self.pos + 2].as_str(),
+        );
+        if is_binary_op(second) {
+            self.binary_operator(false)
+        } else if first == "!" {
+            self.advance(true)?;
+            Ok(!self.two_arguments()?)
+        } else if first == "(" && third == ")" {
+            self.advance(false)?;
+            let value = self.one_argument();
+            self.advance(false)?;
+            Ok(value)
+        } else if second == "-a" || second == "-o" {
+            self.parse_expr()
+        } else {
+            Err(format!("test: '{}': binary operator expected", second))
         }
-        Ok(result)
     }
 
-    fn parse_and(&mut self) -> Result<bool, String> {
-        let mut result = self.parse_not()?;
-        while self.peek() == Some("-a") {
-            self.pos += 1;
-            let right = self.parse_not()?;
-            result = result && right;
+    /// `! EXPR3`, `( EXPR2 )`, or the general grammar.
+    fn four_arguments(&mut self) -> Result<bool, String> {
+        if self.args[self.pos] == "!" {
+            self.advance(true)?;
+            Ok(!self.three_arguments()?)
+        } else if self.args[self.pos] == "(" && self.args[self.pos + 3] == ")" {
+            self.advance(false)?;
+            let value = self.two_arguments()?;
+            self.advance(false)?;
+            Ok(value)
+        } else {
+            self.parse_expr()
         }
-        Ok(result)
     }
 
-    fn parse_not(&mut self) -> Result<bool, String> {
-        if self.peek() == Some("!") {
-            self.pos += 1;
-            let val = self.parse_not()?;
-            return Ok(!val);
+    fn parse_expr(&mut self) -> Result<bool, String> {
+        if self.pos >= self.args.len() {
+            return Err(self.beyond());
         }
-        self.parse_primary()
+        self.parse_or()
     }
 
-    fn parse_primary(&mut self) -> Result<bool, String> {
-        let token = self
-            .peek()
-            .ok_or_else(|| "test: missing argument".to_string())?;
-
-        // Parenthesized expression
-        if token == "(" {
-            self.pos += 1;
-            let result = self.parse_expr()?;
-            if self.peek() != Some(")") {
-                return Err("test: missing ')'".to_string());
+    fn parse_or(&mut self) -> Result<bool, String> {
+        let mut result = false;
+        loop {
+            result |= self.parse_and()?;
+            if self.arg(0) != Some("-o") {
+                return Ok(result);
             }
-            self.pos += 1;
-            return Ok(result);
+            self.advance(false)?;
         }
+    }
 
-        // Check for unary operators
-        if is_unary_op(token) {
-            let op = self.advance()?.to_string();
-            let operand = self.advance()?;
-            return eval_unary(&op, operand);
+    fn parse_and(&mut self) -> Result<bool, String> {
+        let mut result = true;
+        loop {
+            result &= self.parse_term()?;
+            if self.arg(0) != Some("-a") {
+                return Ok(result);
+            }
+            self.advance(false)?;
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<bool, String> {
+        // Leading '!'s each negate the term
+        let mut negated = false;
+        while self.arg(0) == Some("!") {
+            self.advance(true)?;
+            negated = !negated;
         }
+        let token = self.arg(0).ok_or_else(|| self.beyond())?;
+
+        let value = if token == "(" {
+            self.advance(true)?;
+            // Count the arguments up to the matching ')' so that short
+            // parenthesized expressions get the POSIX argument-count rules.
+            let mut nargs = 1;
+            while self.pos + nargs < self.args.len() && self.args[self.pos + nargs] != ")" {
+                if nargs == 4 {
+                    nargs = self.remaining();
+                    break;
+                }
+                nargs += 1;
+            }
+            let value = self.posixtest(nargs)?;
+            match self.arg(0) {
+                None => return Err("test: ')' expected".to_string()),
+                Some(")") => {}
+                Some(found) => {
+                    return Err(format!("test: ')' expected, found '{}'", found));
+                }
+            }
+            self.advance(false)?;
+            value
+        } else if self.remaining() >= 4 && token == "-l" && is_binary_op(&self.args[self.pos + 2]) {
+            self.binary_operator(true)?
+        } else if self.remaining() >= 3 && is_binary_op(&self.args[self.pos + 1]) {
+            self.binary_operator(false)?
+        } else if is_switch(token) {
+            if is_unary_op(token) {
+                self.unary_operator()?
+            } else {
+                self.advance(false)?;
+                true
+            }
+        } else {
+            self.advance(false)?;
+            !token.is_empty()
+        };
+        Ok(value != negated)
+    }
 
-        // Otherwise it's an operand, which might be followed by a binary operator
-        let left = self.advance()?.to_string();
+    /// Evaluate `UNARY_OP OPERAND` at the current position.
+    fn unary_operator(&mut self) -> Result<bool, String> {
+        let op = self.args[self.pos].as_str();
+        self.advance(true)?;
+        let operand = self.args[self.pos].as_str();
+        self.advance(false)?;
+        eval_unary(op, operand)
+    }
 
-        // Check if next token is a binary operator
-        if let Some(next) = self.peek() {
-            if is_binary_op(next) {
-                let op = self.advance()?.to_string();
-                let right = self.advance()?;
-                return eval_binary(&left, &op, right);
+    /// Evaluate `[-l] OPERAND BINARY_OP [-l] OPERAND` at the current position.
+    /// `-l STRING` stands for the length of STRING in integer comparisons.
+    fn binary_operator(&mut self, l_is_l: bool) -> Result<bool, String> {
+        if l_is_l {
+            self.advance(false)?;
+        }
+        let op_idx = self.pos + 1;
+        let op = self.args[op_idx].as_str();
+        let r_is_l = self.args.len() - op_idx > 2 && self.args[op_idx + 1] == "-l";
+        let left_arg = self.args[op_idx - 1].as_str();
+        let right_arg = if r_is_l {
+            self.args[op_idx + 2].as_str()
+        } else {
+            self.args.get(op_idx + 1).map(|s| s.as_str()).unwrap_or("")
+        };
+        self.pos = op_idx + if r_is_l { 3 } else { 2 };
+
+        // Other operators take the operands literally, as GNU does: a left
+        // -l is skipped and a right -l is compared as the string "-l".
+        if !matches!(op, "-eq" | "-ne" | "-lt" | "-le" | "-gt" | "-ge") {
+            if (l_is_l || r_is_l) && matches!(op, "-nt" | "-ot" | "-ef") {
+                return Err(format!("test: {} does not accept -l", op));
             }
+            let right = self.args.get(op_idx + 1).map(|s| s.as_str()).unwrap_or("");
+            return eval_binary(left_arg, op, right);
         }
-
-        // Bare string: true if non-empty
-        Ok(!left.is_empty())
+        let left = if l_is_l {
+            left_arg.len().to_string()
+        } else {
+            left_arg.to_string()
+        };
+        let right = if r_is_l {
+            right_arg.len().to_string()
+        } else {
+            right_arg.to_string()
+        };
+        eval_binary(&left, op, &right)
     }
 }
 
+/// A two-character argument of the form `-X`, which may name an operator.
+fn is_switch(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 2 && b[0] == b'-'
+}
+
 fn is_unary_op(s: &str) -> bool {
     matches!(
         s,
@@ -370,6 +523,8 @@ fn is_unary_op(s: &str) -> bool {
             | "-z"
             | "-n"
             | "-t"
+            | "-a"
+            | "-o"
     )
 }
 
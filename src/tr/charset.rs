@@ -320,6 +320,233 @@ pub fn validate_set2_class_at_end(
     Ok(())
 }
 
+/// GNU tr: "when not truncating set1, string2 must be non-empty".
+/// Without -t, a non-empty SET1 can only be translated through a non-empty SET2.
+/// `set2_len` is the length of SET2 with any `[c*]` repeat filled in.
+pub fn validate_set2_nonempty(set1_len: usize, set2_len: usize) -> Result<(), String> {
+    if set1_len > 0 && set2_len == 0 {
+        return Err("when not truncating set1, string2 must be non-empty".to_string());
+    }
+    Ok(())
+}
+
+/// GNU tr: when SET1 contains a character class and is complemented, the order of
+/// the complemented bytes is unspecified, so SET2 must map all of them to one byte.
+/// `set1_len` is the length of the complemented SET1 and `set2` is SET2 as used for
+/// translation (extended to SET1's length, or as given with -t).
+pub fn validate_complement_classes(
+    set1_str: &str,
+    set1_len: usize,
+    set2: &[u8],
+) -> Result<(), String> {
+    if !has_char_class(set1_str) {
+        return Ok(());
+    }
+    let homogeneous = set2.windows(2).all(|w| w[0] == w[1]);
+    if set2.len() != set1_len || !homogeneous {
+        return Err("when translating with complemented character classes,\n\
+             string2 must map all characters in the domain to one"
+            .to_string());
+    }
+    Ok(())
+}
+
+/// Whether a SET string contains a character class such as [:alpha:].
+fn has_char_class(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 1 < bytes.len() {
+            i += parse_escape(bytes, i).1;
+            continue;
+        }
+        if bytes[i] == b'['
+            && bytes.get(i + 1) == Some(&b':')
+            && parse_char_class(bytes, i).is_some()
+        {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// The bracket constructs found in a SET operand, for GNU tr's checks on
+/// which of them may appear in SET1 and SET2.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SetConstructs {
+    /// `[c*]` or `[c*0]` repeats, which fill SET2 out to SET1's length.
+    pub fill_repeats: usize,
+    /// Whether an equivalence class `[=c=]` appears.
+    pub equiv_class: bool,
+    /// Whether a character class other than [:upper:] or [:lower:] appears.
+    pub restricted_class: bool,
+}
+
+/// Check the bracket constructs in a SET operand the way GNU tr parses
+/// them. A `[` that starts no complete construct is literal, but a
+/// terminated `[:name:]`, `[=c=]` or `[c*n]` must be well formed.
+pub fn check_set(s: &str) -> Result<SetConstructs, String> {
+    let bytes = s.as_bytes();
+    let mut found = SetConstructs::default();
+    let mut have_prev = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += parse_escape(bytes, i).1;
+            have_prev = true;
+            continue;
+        }
+        if bytes[i] == b'-' && have_prev && i + 1 < bytes.len() {
+            // The range end is never the start of a construct.
+            i += 1 + if bytes[i + 1] == b'\\' {
+                parse_escape(bytes, i + 1).1
+            } else {
+                1
+            };
+            continue;
+        }
+        have_prev = true;
+        if bytes[i] != b'[' || i + 2 >= bytes.len() {
+            i += 1;
+            continue;
+        }
+
+        let delim = bytes[i + 1];
+        if delim == b':' || delim == b'=' {
+            let close =
+                (i + 2..bytes.len() - 1).find(|&j| bytes[j] == delim && bytes[j + 1] == b']');
+            if let Some(close) = close {
+                let operand = &bytes[i + 2..close];
+                let operand_str = String::from_utf8_lossy(operand);
+                if operand.is_empty() {
+                    return Err(if delim == b':' {
+                        "missing character class name '[::]'".to_string()
+                    } else {
+                        "missing equivalence class character '[==]'".to_string()
+                    });
+                }
+                let valid = if delim == b':' {
+                    expand_class(operand).is_some()
+                } else if operand[0] == b'\\' {
+                    parse_escape(operand, 0).1 == operand.len()
+                } else {
+                    operand.len() == 1
+                };
+                if valid {
+                    if delim == b':' {
+                        found.restricted_class |= operand != b"upper" && operand != b"lower";
+                    } else {
+                        found.equiv_class = true;
+                    }
+                    i = close + 2;
+                    continue;
+                }
+                // "[:*3]" and "[=*3]" are repeats of ':' and '='.
+                if !star_digits_close_bracket(bytes, i + 2) {
+                    return Err(if delim == b':' {
+                        format!("invalid character class '{}'", operand_str)
+                    } else {
+                        format!(
+                            "{}: equivalence class operand must be a single character",
+                            operand_str
+                        )
+                    });
+                }
+            }
+        }
+
+        match check_repeat(bytes, i)? {
+            Some((count, end)) => {
+                if count == 0 {
+                    found.fill_repeats += 1;
+                }
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    Ok(found)
+}
+
+/// Whether `bytes[i..]` is `*`, optional digits and `]`.
+fn star_digits_close_bracket(bytes: &[u8], i: usize) -> bool {
+    if bytes.get(i) != Some(&b'*') {
+        return false;
+    }
+    let digits = bytes[i + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    bytes.get(i + 1 + digits) == Some(&b']')
+}
+
+/// Check a `[c*n]` construct starting at the '[' at `i`, returning its
+/// repeat count (0 for `[c*]`) and the position after the ']', or `None`
+/// when there is no such construct. A count that is not all octal (with a
+/// leading 0) or decimal digits is an error.
+fn check_repeat(bytes: &[u8], i: usize) -> Result<Option<(usize, usize)>, String> {
+    let char_len = if bytes[i + 1] == b'\\' {
+        parse_escape(bytes, i + 1).1
+    } else {
+        1
+    };
+    let star = i + 1 + char_len;
+    if bytes.get(star) != Some(&b'*') {
+        return Ok(None);
+    }
+    let Some(close) = bytes[star + 1..]
+        .iter()
+        .take_while(|&&b| b != b'\\')
+        .position(|&b| b == b']')
+        .map(|n| star + 1 + n)
+    else {
+        return Ok(None);
+    };
+    let digits = &bytes[star + 1..close];
+    if digits.is_empty() {
+        return Ok(Some((0, close + 1)));
+    }
+    repeat_count(digits)
+        .map(|count| Some((count, close + 1)))
+        .ok_or_else(|| {
+            format!(
+                "invalid repeat count '{}' in [c*n] construct",
+                String::from_utf8_lossy(digits)
+            )
+        })
+}
+
+/// Portability warnings GNU tr gives for the backslash escapes in a set
+/// operand: a three-digit octal escape above \\377, which is read as two
+/// octal digits followed by a literal digit, and an unescaped backslash at
+/// the end of the string.
+pub fn escape_warnings(s: &str) -> Vec<String> {
+    let bytes = s.as_bytes();
+    let mut warnings = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            i += 1;
+            continue;
+        }
+        if i + 1 == bytes.len() {
+            warnings.push("an unescaped backslash at end of string is not portable".to_string());
+            break;
+        }
+        if let [a @ b'0'..=b'7', b @ b'0'..=b'7', c @ b'0'..=b'7', ..] = bytes[i + 1..]
+            && (a - b'0') as u16 * 64 + (b - b'0') as u16 * 8 + (c - b'0') as u16 > 255
+        {
+            let (a, b, c) = (a as char, b as char, c as char);
+            warnings.push(format!(
+                "the ambiguous octal escape \\{a}{b}{c} is being\n\tinterpreted as the 2-byte sequence \\0{a}{b}, {c}"
+            ));
+        }
+        i += parse_escape(bytes, i).1;
+    }
+    warnings
+}
+
 /// Parse escape sequence starting at position `i` (which points to '\').
 /// Returns (byte_value, number_of_bytes_consumed).
 fn parse_escape(bytes: &[u8], i: usize) -> (u8, usize) {
@@ -470,16 +697,22 @@ fn parse_repeat(bytes: &[u8], i: usize) -> Option<(u8, usize, usize)> {
         return None;
     }
 
-    let num_str = std::str::from_utf8(&bytes[after_star..end]).ok()?;
-    let count = if num_str.starts_with('0') && num_str.len() > 1 {
-        usize::from_str_radix(num_str, 8).ok()?
-    } else {
-        num_str.parse::<usize>().ok()?
-    };
-
+    let count = repeat_count(&bytes[after_star..end])?;
     Some((ch, count, end + 1))
 }
 
+/// Parse the count of a `[c*n]` repeat like strtoumax: octal when it starts
+/// with '0', else decimal, after optional leading whitespace.
+fn repeat_count(digits: &[u8]) -> Option<usize> {
+    let radix = if digits.first() == Some(&b'0') { 8 } else { 10 };
+    let digits = std::str::from_utf8(digits).ok()?;
+    usize::from_str_radix(
+        digits.trim_start_matches(|c: char| c.is_ascii_whitespace()),
+        radix,
+    )
+    .ok()
+}
+
 /// Expand SET2 to match SET1 length for translation.
 /// If SET2 has [c*] repeats, fill them. Otherwise repeat last char.
 pub fn expand_set2(set2_str: &str, set1_len: usize) -> Vec<u8> {
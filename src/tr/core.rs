@@ -1143,32 +1143,56 @@ fn detect_range_offset(table: &[u8; 256]) -> Option<(u8, u8, i8)> {
 /// Detect if the translate table maps a contiguous range [lo..=hi] to a single constant byte,
 /// and all other bytes are identity. This covers cases like `tr '\000-\037' 'X'` where
 /// a range maps to one replacement character.
+/// The range may wrap past 255 back to 0 (`lo > hi`), which is what a complemented
+/// range produces, e.g. `tr -c 'a-z' '\n'`. The replacement byte itself may sit inside
+/// the range, since mapping it to itself is still identity.
 /// Returns Some((lo, hi, replacement)) if the pattern matches.
 #[inline]
 fn detect_range_to_constant(table: &[u8; 256]) -> Option<(u8, u8, u8)> {
-    let mut lo: Option<u8> = None;
-    let mut hi = 0u8;
-    let mut replacement = 0u8;
-
+    let mut member = [false; 256];
+    let mut replacement: Option<u8> = None;
     for i in 0..256 {
         if table[i] != i as u8 {
-            match lo {
-                None => {
-                    lo = Some(i as u8);
-                    hi = i as u8;
-                    replacement = table[i];
-                }
-                Some(_) => {
-                    if table[i] != replacement || i as u8 != hi.wrapping_add(1) {
-                        return None;
-                    }
-                    hi = i as u8;
-                }
+            match replacement {
+                None => replacement = Some(table[i]),
+                Some(r) if r != table[i] => return None,
+                Some(_) => {}
             }
+            member[i] = true;
         }
     }
+    let replacement = replacement?;
+
+    if let Some((lo, hi)) = cyclic_run(&member) {
+        return Some((lo, hi, replacement));
+    }
+    member[replacement as usize] = true;
+    cyclic_run(&member).map(|(lo, hi)| (lo, hi, replacement))
+}
 
-    lo.map(|l| (l, hi, replacement))
+/// If the marked bytes form one run (possibly wrapping from 255 to 0),
+/// return its first and last byte.
+fn cyclic_run(member: &[bool; 256]) -> Option<(u8, u8)> {
+    let count = member.iter().filter(|&&m| m).count();
+    if count == 256 {
+        return Some((0, 255));
+    }
+    let mut start = None;
+    for i in 0..256 {
+        if member[i] && !member[(i + 255) % 256] {
+            if start.is_some() {
+                return None;
+            }
+            start = Some(i as u8);
+        }
+    }
+    start.map(|lo| (lo, lo.wrapping_add((count - 1) as u8)))
+}
+
+/// Whether `b` lies in [lo..=hi], where the range wraps past 255 when `lo > hi`.
+#[inline(always)]
+fn in_const_range(b: u8, lo: u8, hi: u8) -> bool {
+    b.wrapping_sub(lo) <= hi.wrapping_sub(lo)
 }
 
 /// SIMD-accelerated range-to-constant translation.
@@ -1195,7 +1219,7 @@ unsafe fn translate_range_to_constant_avx2_inplace(
     use std::arch::x86_64::*;
 
     unsafe {
-        let range = hi - lo;
+        let range = hi.wrapping_sub(lo);
         let bias_v = _mm256_set1_epi8(0x80u8.wrapping_sub(lo) as i8);
         let threshold_v = _mm256_set1_epi8(0x80u8.wrapping_add(range) as i8);
         let repl_v = _mm256_set1_epi8(replacement as i8);
@@ -1250,7 +1274,11 @@ unsafe fn translate_range_to_constant_avx2_inplace(
 
         while i < len {
             let b = *ptr.add(i);
-            *ptr.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *ptr.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -1267,7 +1295,7 @@ unsafe fn translate_range_to_constant_sse2_inplace(
     use std::arch::x86_64::*;
 
     unsafe {
-        let range = hi - lo;
+        let range = hi.wrapping_sub(lo);
         let bias_v = _mm_set1_epi8(0x80u8.wrapping_sub(lo) as i8);
         let threshold_v = _mm_set1_epi8(0x80u8.wrapping_add(range) as i8);
         let repl_v = _mm_set1_epi8(replacement as i8);
@@ -1294,7 +1322,11 @@ unsafe fn translate_range_to_constant_sse2_inplace(
 
         while i < len {
             let b = *ptr.add(i);
-            *ptr.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *ptr.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -1319,19 +1351,15 @@ unsafe fn translate_range_to_constant_neon_inplace(
         let len = data.len();
         let ptr = data.as_mut_ptr();
         let lo_v = vdupq_n_u8(lo);
-        let hi_v = vdupq_n_u8(hi);
+        let range_v = vdupq_n_u8(hi.wrapping_sub(lo));
         let repl_v = vdupq_n_u8(replacement);
         let mut i = 0;
 
         while i + 32 <= len {
             let in0 = vld1q_u8(ptr.add(i));
             let in1 = vld1q_u8(ptr.add(i + 16));
-            let ge0 = vcgeq_u8(in0, lo_v);
-            let le0 = vcleq_u8(in0, hi_v);
-            let mask0 = vandq_u8(ge0, le0);
-            let ge1 = vcgeq_u8(in1, lo_v);
-            let le1 = vcleq_u8(in1, hi_v);
-            let mask1 = vandq_u8(ge1, le1);
+            let mask0 = vcleq_u8(vsubq_u8(in0, lo_v), range_v);
+            let mask1 = vcleq_u8(vsubq_u8(in1, lo_v), range_v);
             // bsl: select repl where mask, keep input where not
             vst1q_u8(ptr.add(i), vbslq_u8(mask0, repl_v, in0));
             vst1q_u8(ptr.add(i + 16), vbslq_u8(mask1, repl_v, in1));
@@ -1340,16 +1368,18 @@ unsafe fn translate_range_to_constant_neon_inplace(
 
         if i + 16 <= len {
             let input = vld1q_u8(ptr.add(i));
-            let ge = vcgeq_u8(input, lo_v);
-            let le = vcleq_u8(input, hi_v);
-            let mask = vandq_u8(ge, le);
+            let mask = vcleq_u8(vsubq_u8(input, lo_v), range_v);
             vst1q_u8(ptr.add(i), vbslq_u8(mask, repl_v, input));
             i += 16;
         }
 
         while i < len {
             let b = *ptr.add(i);
-            *ptr.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *ptr.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -1358,7 +1388,7 @@ unsafe fn translate_range_to_constant_neon_inplace(
 #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
 fn translate_range_to_constant_simd_inplace(data: &mut [u8], lo: u8, hi: u8, replacement: u8) {
     for b in data.iter_mut() {
-        if *b >= lo && *b <= hi {
+        if in_const_range(*b, lo, hi) {
             *b = replacement;
         }
     }
@@ -1396,15 +1426,15 @@ unsafe fn translate_range_to_constant_neon(
         let sp = src.as_ptr();
         let dp = dst.as_mut_ptr();
         let lo_v = vdupq_n_u8(lo);
-        let hi_v = vdupq_n_u8(hi);
+        let range_v = vdupq_n_u8(hi.wrapping_sub(lo));
         let repl_v = vdupq_n_u8(replacement);
         let mut i = 0;
 
         while i + 32 <= len {
             let in0 = vld1q_u8(sp.add(i));
             let in1 = vld1q_u8(sp.add(i + 16));
-            let mask0 = vandq_u8(vcgeq_u8(in0, lo_v), vcleq_u8(in0, hi_v));
-            let mask1 = vandq_u8(vcgeq_u8(in1, lo_v), vcleq_u8(in1, hi_v));
+            let mask0 = vcleq_u8(vsubq_u8(in0, lo_v), range_v);
+            let mask1 = vcleq_u8(vsubq_u8(in1, lo_v), range_v);
             vst1q_u8(dp.add(i), vbslq_u8(mask0, repl_v, in0));
             vst1q_u8(dp.add(i + 16), vbslq_u8(mask1, repl_v, in1));
             i += 32;
@@ -1412,14 +1442,18 @@ unsafe fn translate_range_to_constant_neon(
 
         if i + 16 <= len {
             let input = vld1q_u8(sp.add(i));
-            let mask = vandq_u8(vcgeq_u8(input, lo_v), vcleq_u8(input, hi_v));
+            let mask = vcleq_u8(vsubq_u8(input, lo_v), range_v);
             vst1q_u8(dp.add(i), vbslq_u8(mask, repl_v, input));
             i += 16;
         }
 
         while i < len {
             let b = *sp.add(i);
-            *dp.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *dp.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -1429,7 +1463,11 @@ unsafe fn translate_range_to_constant_neon(
 fn translate_range_to_constant_simd(src: &[u8], dst: &mut [u8], lo: u8, hi: u8, replacement: u8) {
     for (i, &b) in src.iter().enumerate() {
         unsafe {
-            *dst.get_unchecked_mut(i) = if b >= lo && b <= hi { replacement } else { b };
+            *dst.get_unchecked_mut(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
         }
     }
 }
@@ -1445,7 +1483,7 @@ unsafe fn translate_range_to_constant_avx2(
 ) {
     use std::arch::x86_64::*;
     unsafe {
-        let range = hi - lo;
+        let range = hi.wrapping_sub(lo);
         let bias_v = _mm256_set1_epi8(0x80u8.wrapping_sub(lo) as i8);
         let threshold_v = _mm256_set1_epi8(0x80u8.wrapping_add(range) as i8);
         let repl_v = _mm256_set1_epi8(replacement as i8);
@@ -1480,7 +1518,11 @@ unsafe fn translate_range_to_constant_avx2(
         }
         while i < len {
             let b = *sp.add(i);
-            *dp.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *dp.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -1497,7 +1539,7 @@ unsafe fn translate_range_to_constant_sse2(
 ) {
     use std::arch::x86_64::*;
     unsafe {
-        let range = hi - lo;
+        let range = hi.wrapping_sub(lo);
         let bias_v = _mm_set1_epi8(0x80u8.wrapping_sub(lo) as i8);
         let threshold_v = _mm_set1_epi8(0x80u8.wrapping_add(range) as i8);
         let repl_v = _mm_set1_epi8(replacement as i8);
@@ -1520,7 +1562,11 @@ unsafe fn translate_range_to_constant_sse2(
         }
         while i < len {
             let b = *sp.add(i);
-            *dp.add(i) = if b >= lo && b <= hi { replacement } else { b };
+            *dp.add(i) = if in_const_range(b, lo, hi) {
+                replacement
+            } else {
+                b
+            };
             i += 1;
         }
     }
@@ -3069,68 +3115,84 @@ fn delete_bitset_scalar(src: &[u8], dst: &mut [u8], member: &[u8; 32]) -> usize
     wp
 }
 
+/// Precomputed vectors for testing 32 bytes at a time against a 256-bit
+/// membership set with pshufb.
+#[cfg(target_arch = "x86_64")]
+#[derive(Clone, Copy)]
+struct BitsetLookup {
+    /// member[0..16] broadcast to both lanes.
+    member_lo: std::arch::x86_64::__m256i,
+    /// member[16..32] broadcast to both lanes.
+    member_hi: std::arch::x86_64::__m256i,
+    /// `1 << i` for i in 0..8, per lane, for the bit within a member byte.
+    bit_table: std::arch::x86_64::__m256i,
+}
+
+#[cfg(target_arch = "x86_64")]
+impl BitsetLookup {
+    #[target_feature(enable = "avx2")]
+    unsafe fn new(member: &[u8; 32]) -> Self {
+        use std::arch::x86_64::*;
+        unsafe {
+            let member_v = _mm256_loadu_si256(member.as_ptr() as *const _);
+            Self {
+                member_lo: _mm256_broadcastsi128_si256(_mm256_castsi256_si128(member_v)),
+                member_hi: _mm256_broadcastsi128_si256(_mm256_extracti128_si256(member_v, 1)),
+                bit_table: _mm256_setr_epi8(
+                    1, 2, 4, 8, 16, 32, 64, -128i8, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 4, 8, 16, 32, 64,
+                    -128i8, 0, 0, 0, 0, 0, 0, 0, 0,
+                ),
+            }
+        }
+    }
+
+    /// Keep mask for the 32 bytes at `p`: bit i is set when byte i is NOT a
+    /// member of the set (i.e. it survives a delete).
+    ///
+    /// For each byte B the set holds bit `B & 7` of `member[B >> 3]`. pshufb
+    /// only indexes within a 128-bit lane, so the member byte is looked up in
+    /// both halves of the set by `(B >> 3) & 15` and blended on bit 4 of
+    /// `B >> 3`; the bit mask comes from a second pshufb on `B & 7`.
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn keep_mask(&self, p: *const u8) -> u32 {
+        use std::arch::x86_64::*;
+        unsafe {
+            let input = _mm256_loadu_si256(p as *const _);
+            let byte_idx = _mm256_and_si256(_mm256_srli_epi16(input, 3), _mm256_set1_epi8(0x1F));
+            let bit_pos = _mm256_and_si256(input, _mm256_set1_epi8(7));
+            let bit_mask = _mm256_shuffle_epi8(self.bit_table, bit_pos);
+
+            let idx_lo = _mm256_and_si256(byte_idx, _mm256_set1_epi8(0x0F));
+            let shuffled_lo = _mm256_shuffle_epi8(self.member_lo, idx_lo);
+            let shuffled_hi = _mm256_shuffle_epi8(self.member_hi, idx_lo);
+            // select hi when byte_idx >= 16 (shift bit 4 to bit 7 for blendv)
+            let use_hi = _mm256_slli_epi16(byte_idx, 3);
+            let member_byte = _mm256_blendv_epi8(shuffled_lo, shuffled_hi, use_hi);
+
+            let test = _mm256_and_si256(member_byte, bit_mask);
+            let is_zero = _mm256_cmpeq_epi8(test, _mm256_setzero_si256());
+            _mm256_movemask_epi8(is_zero) as u32
+        }
+    }
+}
+
 /// AVX2 bitset delete for streaming: uses SIMD to check 32 bytes against the
 /// membership bitset at once, then compact_8bytes_simd to pack kept bytes.
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
 unsafe fn delete_bitset_avx2_stream(src: &[u8], dst: &mut [u8], member: &[u8; 32]) -> usize {
-    use std::arch::x86_64::*;
-
     unsafe {
         let n = src.len();
         let sp = src.as_ptr();
         let dp = dst.as_mut_ptr();
         let mut ri = 0;
         let mut wp = 0;
-
-        // Load the 256-bit membership bitset into an AVX2 register.
-        // Byte i of member_v has bits set for characters in [i*8..i*8+7].
-        let member_v = _mm256_loadu_si256(member.as_ptr() as *const _);
-
-        // For each input byte B, we check: member[B >> 3] & (1 << (B & 7))
-        // Using SIMD: extract byte index (B >> 3) and bit position (B & 7).
-        let mask7 = _mm256_set1_epi8(7);
-        let mask_0x1f = _mm256_set1_epi8(0x1F_u8 as i8);
-
-        // Lookup table for (1 << (x & 7)) — pshufb gives per-byte shift
-        // that _mm256_sllv_epi32 can't do (it works on 32-bit lanes).
-        let bit_table = _mm256_setr_epi8(
-            1, 2, 4, 8, 16, 32, 64, -128i8, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 4, 8, 16, 32, 64, -128i8,
-            0, 0, 0, 0, 0, 0, 0, 0,
-        );
+        let lookup = BitsetLookup::new(member);
 
         while ri + 32 <= n {
-            let input = _mm256_loadu_si256(sp.add(ri) as *const _);
-
-            // byte_idx = input >> 3 (which byte of the 32-byte member set)
-            let byte_idx = _mm256_and_si256(_mm256_srli_epi16(input, 3), mask_0x1f);
-            // bit_pos = input & 7 (which bit within that byte)
-            let bit_pos = _mm256_and_si256(input, mask7);
-            // bit_mask = 1 << bit_pos (per-byte via shuffle lookup)
-            let bit_mask = _mm256_shuffle_epi8(bit_table, bit_pos);
-
-            // member_byte = shuffle member_v by byte_idx (pshufb)
-            // But pshufb only works within 128-bit lanes. We need cross-lane.
-            // Since member is 32 bytes and byte_idx can be 0-31, we need
-            // a different approach. Use two pshufb + blend:
-            // lo_half = pshufb(member[0..15], byte_idx)
-            // hi_half = pshufb(member[16..31], byte_idx - 16)
-            // select = byte_idx >= 16
-            let member_lo = _mm256_broadcastsi128_si256(_mm256_castsi256_si128(member_v));
-            let member_hi = _mm256_broadcastsi128_si256(_mm256_extracti128_si256(member_v, 1));
-            let lo_mask = _mm256_set1_epi8(0x0F);
-            let idx_lo = _mm256_and_si256(byte_idx, lo_mask);
-            let shuffled_lo = _mm256_shuffle_epi8(member_lo, idx_lo);
-            let shuffled_hi = _mm256_shuffle_epi8(member_hi, idx_lo);
-            // select hi when byte_idx >= 16 (bit 4 set)
-            let use_hi = _mm256_slli_epi16(byte_idx, 3); // shift bit 4 to bit 7
-            let member_byte = _mm256_blendv_epi8(shuffled_lo, shuffled_hi, use_hi);
-
-            // Check: (member_byte & bit_mask) != 0 → byte is in delete set
-            let test = _mm256_and_si256(member_byte, bit_mask);
-            let is_zero = _mm256_cmpeq_epi8(test, _mm256_setzero_si256());
             // keep_mask: bit set = byte should be KEPT (not in delete set)
-            let keep_mask = _mm256_movemask_epi8(is_zero) as u32;
+            let keep_mask = lookup.keep_mask(sp.add(ri));
 
             if keep_mask == 0xFFFFFFFF {
                 // All 32 bytes kept — bulk copy
@@ -3706,28 +3768,28 @@ unsafe fn squeeze_single_avx2_inplace(
                 let m3 = (keep_mask >> 24) as u8;
 
                 if m0 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri), ptr.add(wp), 8);
+                    std::ptr::copy(ptr.add(ri), ptr.add(wp), 8);
                 } else if m0 != 0 {
                     compact_8bytes_simd(ptr.add(ri), ptr.add(wp), m0);
                 }
                 let c0 = m0.count_ones() as usize;
 
                 if m1 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri + 8), ptr.add(wp + c0), 8);
+                    std::ptr::copy(ptr.add(ri + 8), ptr.add(wp + c0), 8);
                 } else if m1 != 0 {
                     compact_8bytes_simd(ptr.add(ri + 8), ptr.add(wp + c0), m1);
                 }
                 let c1 = m1.count_ones() as usize;
 
                 if m2 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri + 16), ptr.add(wp + c0 + c1), 8);
+                    std::ptr::copy(ptr.add(ri + 16), ptr.add(wp + c0 + c1), 8);
                 } else if m2 != 0 {
                     compact_8bytes_simd(ptr.add(ri + 16), ptr.add(wp + c0 + c1), m2);
                 }
                 let c2 = m2.count_ones() as usize;
 
                 if m3 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri + 24), ptr.add(wp + c0 + c1 + c2), 8);
+                    std::ptr::copy(ptr.add(ri + 24), ptr.add(wp + c0 + c1 + c2), 8);
                 } else if m3 != 0 {
                     compact_8bytes_simd(ptr.add(ri + 24), ptr.add(wp + c0 + c1 + c2), m3);
                 }
@@ -3759,13 +3821,13 @@ unsafe fn squeeze_single_avx2_inplace(
                 let m0 = keep_mask as u8;
                 let m1 = (keep_mask >> 8) as u8;
                 if m0 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri), ptr.add(wp), 8);
+                    std::ptr::copy(ptr.add(ri), ptr.add(wp), 8);
                 } else if m0 != 0 {
                     compact_8bytes_simd(ptr.add(ri), ptr.add(wp), m0);
                 }
                 let c0 = m0.count_ones() as usize;
                 if m1 == 0xFF {
-                    std::ptr::copy_nonoverlapping(ptr.add(ri + 8), ptr.add(wp + c0), 8);
+                    std::ptr::copy(ptr.add(ri + 8), ptr.add(wp + c0), 8);
                 } else if m1 != 0 {
                     compact_8bytes_simd(ptr.add(ri + 8), ptr.add(wp + c0), m1);
                 }
@@ -4286,11 +4348,9 @@ pub fn delete_mmap(delete_chars: &[u8], data: &[u8], writer: &mut impl Write) ->
         .iter()
         .filter(|&&b| is_member(&member, b))
         .count();
-    let estimated_deletes = if sample_size > 0 {
-        data.len() * sample_deletes / sample_size
-    } else {
-        data.len()
-    };
+    let estimated_deletes = (data.len() * sample_deletes)
+        .checked_div(sample_size)
+        .unwrap_or(data.len());
 
     if estimated_deletes < MAX_IOV / 2 {
         return delete_bitset_zerocopy(data, &member, writer);
@@ -4305,7 +4365,7 @@ pub fn delete_mmap(delete_chars: &[u8], data: &[u8], writer: &mut impl Write) ->
         let chunk_lens: Vec<usize> = data
             .par_chunks(chunk_size)
             .zip(outbuf.par_chunks_mut(chunk_size))
-            .map(|(src_chunk, dst_chunk)| delete_chunk_bitset_into(src_chunk, &member, dst_chunk))
+            .map(|(src_chunk, dst_chunk)| delete_bitset_dispatch(src_chunk, dst_chunk, &member))
             .collect();
 
         // Use writev to write each chunk at its original position, avoiding
@@ -4326,7 +4386,7 @@ pub fn delete_mmap(delete_chars: &[u8], data: &[u8], writer: &mut impl Write) ->
     let mut outbuf = alloc_uninit_vec(COMPACT_BUF);
 
     for chunk in data.chunks(COMPACT_BUF) {
-        let out_pos = delete_chunk_bitset_into(chunk, &member, &mut outbuf);
+        let out_pos = delete_bitset_dispatch(chunk, &mut outbuf, &member);
         if out_pos > 0 {
             writer.write_all(&outbuf[..out_pos])?;
         }
@@ -4350,11 +4410,9 @@ fn delete_range_mmap(data: &[u8], writer: &mut impl Write, lo: u8, hi: u8) -> io
     // if estimated_deletes > MAX_IOV/2, the writev overhead from multiple syscalls
     // exceeds the compact approach cost. Only use zero-copy when all gaps fit in
     // a single writev call.
-    let estimated_deletes = if sample_size > 0 {
-        data.len() * sample_deletes / sample_size
-    } else {
-        data.len()
-    };
+    let estimated_deletes = (data.len() * sample_deletes)
+        .checked_div(sample_size)
+        .unwrap_or(data.len());
     if estimated_deletes < MAX_IOV / 2 {
         return delete_range_mmap_zerocopy(data, writer, lo, hi);
     }
@@ -4822,57 +4880,6 @@ unsafe fn delete_range_zerocopy_neon(
     }
 }
 
-/// Delete bytes from chunk using bitset, writing into pre-allocated buffer.
-/// Returns number of bytes written.
-#[inline]
-fn delete_chunk_bitset_into(chunk: &[u8], member: &[u8; 32], outbuf: &mut [u8]) -> usize {
-    let len = chunk.len();
-    let mut out_pos = 0;
-    let mut i = 0;
-
-    while i + 8 <= len {
-        unsafe {
-            let b0 = *chunk.get_unchecked(i);
-            let b1 = *chunk.get_unchecked(i + 1);
-            let b2 = *chunk.get_unchecked(i + 2);
-            let b3 = *chunk.get_unchecked(i + 3);
-            let b4 = *chunk.get_unchecked(i + 4);
-            let b5 = *chunk.get_unchecked(i + 5);
-            let b6 = *chunk.get_unchecked(i + 6);
-            let b7 = *chunk.get_unchecked(i + 7);
-
-            *outbuf.get_unchecked_mut(out_pos) = b0;
-            out_pos += !is_member(member, b0) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b1;
-            out_pos += !is_member(member, b1) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b2;
-            out_pos += !is_member(member, b2) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b3;
-            out_pos += !is_member(member, b3) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b4;
-            out_pos += !is_member(member, b4) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b5;
-            out_pos += !is_member(member, b5) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b6;
-            out_pos += !is_member(member, b6) as usize;
-            *outbuf.get_unchecked_mut(out_pos) = b7;
-            out_pos += !is_member(member, b7) as usize;
-        }
-        i += 8;
-    }
-
-    while i < len {
-        unsafe {
-            let b = *chunk.get_unchecked(i);
-            *outbuf.get_unchecked_mut(out_pos) = b;
-            out_pos += !is_member(member, b) as usize;
-        }
-        i += 1;
-    }
-
-    out_pos
-}
-
 /// Zero-copy delete for general bitset: scan for runs of kept bytes,
 /// build IoSlice entries pointing directly into the source data.
 /// No allocation for output data — just ~16 bytes per IoSlice entry.
@@ -4882,6 +4889,13 @@ fn delete_bitset_zerocopy(
     member: &[u8; 32],
     writer: &mut impl Write,
 ) -> io::Result<()> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if get_simd_level() >= 3 {
+            return unsafe { delete_bitset_zerocopy_avx2(data, member, writer) };
+        }
+    }
+
     let mut iov: Vec<std::io::IoSlice> = Vec::with_capacity(MAX_IOV);
     let len = data.len();
     let mut i = 0;
@@ -4917,6 +4931,77 @@ fn delete_bitset_zerocopy(
     Ok(())
 }
 
+/// AVX2 zero-copy bitset delete: classifies 32 bytes per step with
+/// `BitsetLookup::keep_mask` and walks the mask bits only where kept and
+/// deleted bytes meet, so long runs of either cost one compare per block.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn delete_bitset_zerocopy_avx2(
+    data: &[u8],
+    member: &[u8; 32],
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let mut iov: Vec<std::io::IoSlice> = Vec::with_capacity(MAX_IOV);
+    let len = data.len();
+    let lookup = unsafe { BitsetLookup::new(member) };
+    let mut run_start: Option<usize> = None;
+    let mut i = 0;
+
+    while i + 32 <= len {
+        let keep = unsafe { lookup.keep_mask(data.as_ptr().add(i)) };
+        match (keep, run_start) {
+            (u32::MAX, Some(_)) | (0, None) => {}
+            _ => {
+                let mut bit = 0u32;
+                while bit < 32 {
+                    if let Some(rs) = run_start {
+                        // Extend the kept run up to the next deleted byte
+                        let gap = (!keep >> bit).trailing_zeros();
+                        if bit + gap >= 32 {
+                            break;
+                        }
+                        bit += gap;
+                        iov.push(std::io::IoSlice::new(&data[rs..i + bit as usize]));
+                        run_start = None;
+                        if iov.len() >= MAX_IOV {
+                            write_ioslices(writer, &iov)?;
+                            iov.clear();
+                        }
+                    } else {
+                        // Skip deleted bytes up to the next kept one
+                        let gap = (keep >> bit).trailing_zeros();
+                        if bit + gap >= 32 {
+                            break;
+                        }
+                        bit += gap;
+                        run_start = Some(i + bit as usize);
+                    }
+                }
+            }
+        }
+        i += 32;
+    }
+
+    // Scalar tail
+    while i < len {
+        if is_member(member, data[i]) {
+            if let Some(rs) = run_start.take() {
+                iov.push(std::io::IoSlice::new(&data[rs..i]));
+            }
+        } else if run_start.is_none() {
+            run_start = Some(i);
+        }
+        i += 1;
+    }
+    if let Some(rs) = run_start {
+        iov.push(std::io::IoSlice::new(&data[rs..]));
+    }
+    if !iov.is_empty() {
+        write_ioslices(writer, &iov)?;
+    }
+    Ok(())
+}
+
 fn delete_single_char_mmap(ch: u8, data: &[u8], writer: &mut impl Write) -> io::Result<()> {
     // Streaming zero-copy delete using writev: build IoSlice batches of MAX_IOV
     // pointing to gaps between deleted characters, write each batch immediately.
@@ -2,8 +2,9 @@ mod charset;
 mod core;
 
 pub use self::charset::{
-    CaseClass, CaseClassInfo, complement, expand_set2, expand_set2_with_classes, parse_set,
-    parse_set_with_classes, validate_case_classes, validate_set2_class_at_end,
+    CaseClass, CaseClassInfo, SetConstructs, check_set, complement, escape_warnings, expand_set2,
+    expand_set2_with_classes, parse_set, parse_set_with_classes, validate_case_classes,
+    validate_complement_classes, validate_set2_class_at_end, validate_set2_nonempty,
 };
 pub use self::core::{delete, delete_squeeze, squeeze, translate, translate_squeeze};
 pub use self::core::{
@@ -16,6 +16,62 @@ pub struct WcCounts {
     pub max_line_length: u64,
 }
 
+/// When to print the line of total counts (`--total=WHEN`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotalMode {
+    /// Only when more than one file operand is given.
+    Auto,
+    Always,
+    /// Print the total alone, without the per-file lines.
+    Only,
+    Never,
+}
+
+const TOTAL_ARGS: &[(&str, TotalMode)] = &[
+    ("auto", TotalMode::Auto),
+    ("always", TotalMode::Always),
+    ("only", TotalMode::Only),
+    ("never", TotalMode::Never),
+];
+
+impl TotalMode {
+    /// Parse a `--total` argument.  Like GNU argmatch, unambiguous
+    /// abbreviations are accepted.
+    pub fn parse(s: &str) -> Result<TotalMode, String> {
+        if let Some(&(_, mode)) = TOTAL_ARGS.iter().find(|(name, _)| *name == s) {
+            return Ok(mode);
+        }
+        let mut matches = TOTAL_ARGS.iter().filter(|(name, _)| name.starts_with(s));
+        match (matches.next(), matches.next()) {
+            (Some(&(_, mode)), None) => Ok(mode),
+            (first, _) => Err(format!(
+                "{} argument '{}' for '--total'\n\
+                 Valid arguments are:\n  \
+                 - 'auto'\n  \
+                 - 'always'\n  \
+                 - 'only'\n  \
+                 - 'never'",
+                if first.is_some() {
+                    "ambiguous"
+                } else {
+                    "invalid"
+                },
+                s
+            )),
+        }
+    }
+
+    /// Whether the total line is printed for `nfiles` file operands, counting
+    /// those that could not be read.
+    pub fn shows_total(self, nfiles: usize) -> bool {
+        match self {
+            TotalMode::Auto => nfiles > 1,
+            TotalMode::Always | TotalMode::Only => true,
+            TotalMode::Never => false,
+        }
+    }
+}
+
 // ──────────────────────────────────────────────────
 // Byte classification for word counting
 // ──────────────────────────────────────────────────
@@ -137,6 +193,17 @@ pub fn count_lines(data: &[u8]) -> u64 {
     memchr_iter(b'\n', data).count() as u64
 }
 
+/// Count NUL-terminated records (`wc -z`).
+/// Unlike newline counting, a final record without a trailing NUL still counts.
+#[inline]
+pub fn count_lines_zero(data: &[u8]) -> u64 {
+    let nuls = memchr_iter(b'\0', data).count() as u64;
+    match data.last() {
+        Some(&b) if b != b'\0' => nuls + 1,
+        _ => nuls,
+    }
+}
+
 /// Count bytes. Trivial but included for API consistency.
 #[inline]
 pub fn count_bytes(data: &[u8]) -> u64 {
@@ -1336,6 +1403,15 @@ pub fn max_line_length(data: &[u8], utf8: bool) -> u64 {
     }
 }
 
+/// Compute maximum display width of NUL-terminated records (`wc -z`).
+/// Each record is measured up to its terminating NUL.
+pub fn max_line_length_zero(data: &[u8], utf8: bool) -> u64 {
+    data.split(|&b| b == b'\0')
+        .map(|record| max_line_length(record, utf8))
+        .max()
+        .unwrap_or(0)
+}
+
 /// Count all metrics using optimized individual passes.
 ///
 /// Each metric uses its own optimized algorithm: